@@ -0,0 +1,7 @@
+#!/bin/sh
+# Compiles the Java helper classes that duchess defines in the JVM at runtime (see `src/proxy.rs` and `src/io.rs`).
+# The class files are checked in so that building duchess doesn't need a JDK: rerun this after editing the sources.
+set -e
+cd "$(dirname "$0")"
+rm -rf classes
+"${JAVA_HOME:+$JAVA_HOME/bin/}javac" --release 9 -d classes -sourcepath . duchess/*.java
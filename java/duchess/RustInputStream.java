@@ -0,0 +1,81 @@
+package duchess;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.lang.ref.Cleaner;
+import java.lang.ref.Reference;
+import java.util.Objects;
+
+/**
+ * An input stream reading from a boxed Rust `std::io::Read`.
+ *
+ * This class is defined at runtime by duchess (see `src/io.rs`) and is not
+ * intended to be used directly. The reader is dropped when the stream is
+ * closed, and released once the stream becomes unreachable.
+ */
+final class RustInputStream extends InputStream {
+    private static final Cleaner CLEANER = Cleaner.create();
+
+    // The native methods only get the reader, so they are called with fences that keep the cleaner from releasing
+    // it meanwhile
+    private final long reader;
+
+    private RustInputStream(long reader) {
+        this.reader = reader;
+        CLEANER.register(this, new Release(reader));
+    }
+
+    /** Creates a stream reading from `reader`. Takes ownership of `reader`. */
+    static InputStream newStream(long reader) {
+        return new RustInputStream(reader);
+    }
+
+    @Override
+    public int read() throws IOException {
+        byte[] b = new byte[1];
+        int n = read(b, 0, 1);
+        return n <= 0 ? -1 : b[0] & 0xff;
+    }
+
+    @Override
+    public int read(byte[] b, int off, int len) throws IOException {
+        Objects.checkFromIndexSize(off, len, b.length);
+        if (len == 0) {
+            return 0;
+        }
+        try {
+            return read(reader, b, off, len);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    @Override
+    public void close() throws IOException {
+        try {
+            close(reader);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    /** Returns the number of bytes read, or -1 at the end of the stream. */
+    private static native int read(long reader, byte[] b, int off, int len) throws IOException;
+
+    private static native void close(long reader) throws IOException;
+
+    private static native void release(long reader);
+
+    private static final class Release implements Runnable {
+        private final long reader;
+
+        Release(long reader) {
+            this.reader = reader;
+        }
+
+        @Override
+        public void run() {
+            release(reader);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+package duchess;
+
+import java.lang.ref.Cleaner;
+import java.lang.ref.Reference;
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.Method;
+import java.lang.reflect.Proxy;
+
+/**
+ * Forwards calls on a proxy object to a boxed Rust closure.
+ *
+ * This class is defined at runtime by duchess (see `src/proxy.rs`) and is not
+ * intended to be used directly. The closure is released once the handler
+ * becomes unreachable.
+ */
+final class RustInvocationHandler implements InvocationHandler {
+    private static final Cleaner CLEANER = Cleaner.create();
+
+    private final Class<?> iface;
+    private final long closure;
+
+    private RustInvocationHandler(Class<?> iface, long closure) {
+        this.iface = iface;
+        this.closure = closure;
+        CLEANER.register(this, new Release(closure));
+    }
+
+    /** Creates a proxy implementing `iface`. Takes ownership of `closure`. */
+    static Object newProxy(Class<?> iface, long closure) {
+        RustInvocationHandler handler = new RustInvocationHandler(iface, closure);
+        ClassLoader loader = iface.getClassLoader();
+        if (loader == null) {
+            loader = RustInvocationHandler.class.getClassLoader();
+        }
+        return Proxy.newProxyInstance(loader, new Class<?>[] { iface }, handler);
+    }
+
+    @Override
+    public Object invoke(Object proxy, Method method, Object[] args) throws Throwable {
+        if (method.getDeclaringClass() == Object.class) {
+            switch (method.getName()) {
+                case "equals":
+                    return proxy == args[0];
+                case "hashCode":
+                    return System.identityHashCode(proxy);
+                case "toString":
+                    return iface.getName() + "$RustProxy@" + Integer.toHexString(System.identityHashCode(proxy));
+            }
+        }
+        try {
+            return invokeClosure(closure, method, args);
+        } finally {
+            // The native method only gets the closure, so this keeps the cleaner from releasing it meanwhile
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    private static native Object invokeClosure(long closure, Method method, Object[] args);
+
+    private static native void releaseClosure(long closure);
+
+    private static final class Release implements Runnable {
+        private final long closure;
+
+        Release(long closure) {
+            this.closure = closure;
+        }
+
+        @Override
+        public void run() {
+            releaseClosure(closure);
+        }
+    }
+}
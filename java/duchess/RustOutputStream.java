@@ -0,0 +1,89 @@
+package duchess;
+
+import java.io.IOException;
+import java.io.OutputStream;
+import java.lang.ref.Cleaner;
+import java.lang.ref.Reference;
+import java.util.Objects;
+
+/**
+ * An output stream writing to a boxed Rust `std::io::Write`.
+ *
+ * This class is defined at runtime by duchess (see `src/io.rs`) and is not
+ * intended to be used directly. The writer is flushed and dropped when the
+ * stream is closed, and released once the stream becomes unreachable.
+ */
+final class RustOutputStream extends OutputStream {
+    private static final Cleaner CLEANER = Cleaner.create();
+
+    // The native methods only get the writer, so they are called with fences that keep the cleaner from releasing
+    // it meanwhile
+    private final long writer;
+
+    private RustOutputStream(long writer) {
+        this.writer = writer;
+        CLEANER.register(this, new Release(writer));
+    }
+
+    /** Creates a stream writing to `writer`. Takes ownership of `writer`. */
+    static OutputStream newStream(long writer) {
+        return new RustOutputStream(writer);
+    }
+
+    @Override
+    public void write(int b) throws IOException {
+        write(new byte[] { (byte) b }, 0, 1);
+    }
+
+    @Override
+    public void write(byte[] b, int off, int len) throws IOException {
+        Objects.checkFromIndexSize(off, len, b.length);
+        if (len == 0) {
+            return;
+        }
+        try {
+            write(writer, b, off, len);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    @Override
+    public void flush() throws IOException {
+        try {
+            flush(writer);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    @Override
+    public void close() throws IOException {
+        try {
+            close(writer);
+        } finally {
+            Reference.reachabilityFence(this);
+        }
+    }
+
+    private static native void write(long writer, byte[] b, int off, int len) throws IOException;
+
+    private static native void flush(long writer) throws IOException;
+
+    private static native void close(long writer) throws IOException;
+
+    private static native void release(long writer);
+
+    private static final class Release implements Runnable {
+        private final long writer;
+
+        Release(long writer) {
+            this.writer = writer;
+        }
+
+        @Override
+        public void run() {
+            release(writer);
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Reads arbitrary bytes as a class file, which must fail without panicking if they are malformed.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The class file reader only depends on `std`, so it is compiled into the target directly (the macro crate can't
+// export it)
+#[path = "../../src/class_info/class_file.rs"]
+#[allow(dead_code)]
+mod class_file;
+
+fuzz_target!(|bytes: &[u8]| {
+    let javap = class_file::to_javap(bytes);
+    let descriptors = class_file::method_descriptors(bytes);
+    // Both only fail for malformed class files, though rendering also checks the signatures of the methods
+    if javap.is_ok() {
+        assert!(descriptors.is_ok());
+    }
+});
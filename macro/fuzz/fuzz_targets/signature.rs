@@ -0,0 +1,13 @@
+//! Parses arbitrary text as generic signatures and descriptors, which must fail without panicking if it is malformed.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../src/class_info/class_file.rs"]
+#[allow(dead_code)]
+mod class_file;
+
+fuzz_target!(|text: &str| {
+    let _ = class_file::method_signature_to_javap(text);
+    let _ = class_file::field_signature_to_javap(text);
+});
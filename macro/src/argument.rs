@@ -1,4 +1,7 @@
-use proc_macro2::Span;
+use std::path::PathBuf;
+
+use litrs::StringLit;
+use proc_macro2::{Span, TokenTree};
 
 use crate::{
     class_info::{ClassDecl, ClassInfo, DotId, Id},
@@ -6,13 +9,24 @@ use crate::{
 };
 
 pub struct DuchessDeclaration {
+    pub jars: Vec<FromJar>,
     pub packages: Vec<JavaPackage>,
 }
 
 impl Parse for DuchessDeclaration {
     fn parse(p: &mut Parser) -> syn::Result<Option<Self>> {
-        let packages = JavaPackage::parse_many(p)?;
-        Ok(Some(DuchessDeclaration { packages }))
+        let mut jars = vec![];
+        let mut packages = vec![];
+        loop {
+            if let Some(jar) = FromJar::parse(p)? {
+                jars.push(jar);
+            } else if let Some(package) = JavaPackage::parse(p)? {
+                packages.push(package);
+            } else {
+                break;
+            }
+        }
+        Ok(Some(DuchessDeclaration { jars, packages }))
     }
 
     fn description() -> String {
@@ -126,9 +140,209 @@ impl Parse for MethodSelector {
     }
 }
 
+/// User wrote `from_jar "path/to/lib.jar";`, to reflect on the classes in that jar file without running `javap`.
+/// Relative paths are relative to the directory of the crate's `Cargo.toml`.
+pub struct FromJar {
+    pub path: PathBuf,
+    pub span: Span,
+}
+
+impl Parse for FromJar {
+    fn parse(p: &mut Parser) -> syn::Result<Option<Self>> {
+        // Not a Java keyword, so not in `KEYWORDS`
+        let Some(()) = p.eat_map(|t| match t {
+            TokenTree::Ident(i) if i == "from_jar" => Some(()),
+            _ => None,
+        }) else {
+            return Ok(None);
+        };
+
+        let Some((path, span)) = p.eat_map(|t| match t {
+            TokenTree::Literal(l) => Some((StringLit::try_from(l.clone()).ok()?, l.span())),
+            _ => None,
+        }) else {
+            return Err(syn::Error::new(
+                p.last_span().unwrap(),
+                "expected the path of a jar file as a string literal",
+            ));
+        };
+
+        let Some(_) = p.eat_punct(';') else {
+            return Err(syn::Error::new(
+                span,
+                "expected `;` after the path of the jar file",
+            ));
+        };
+
+        let mut full_path = PathBuf::new();
+        if let Ok(dir) = std::env::var("CARGO_MANIFEST_DIR") {
+            full_path.push(dir);
+        }
+        full_path.push(path.value());
+        Ok(Some(FromJar {
+            path: full_path,
+            span,
+        }))
+    }
+
+    fn description() -> String {
+        format!("jar file to reflect on (e.g., `from_jar \"libs/foo.jar\";`)")
+    }
+}
+
 pub struct JavaPackage {
     pub package_name: JavaPath,
     pub classes: Vec<ClassDecl>,
+    pub filters: Vec<Filter>,
+}
+
+/// A wildcard filter on the classes or methods of a package, like `exclude java.util.concurrent.*;`.
+pub enum Filter {
+    /// User wrote `generate pattern;`, to generate all the public classes whose names match
+    Generate(Pattern),
+
+    /// User wrote `exclude pattern;`, so that classes whose names match aren't generated
+    ExcludeClasses(Pattern),
+
+    /// User wrote `include class_pattern::method_pattern;`, so that only matching methods are
+    /// generated for matching classes
+    IncludeMethods(Pattern, Pattern),
+
+    /// User wrote `exclude class_pattern::method_pattern;`
+    ExcludeMethods(Pattern, Pattern),
+}
+
+impl Parse for Filter {
+    fn parse(p: &mut Parser) -> syn::Result<Option<Self>> {
+        let Some(keyword) = p.eat_map(|t| match t {
+            TokenTree::Ident(i) if i == "generate" || i == "include" || i == "exclude" => {
+                Some(i.clone())
+            }
+            _ => None,
+        }) else {
+            return Ok(None);
+        };
+
+        let class = Pattern::parse_until(p, keyword.span())?;
+        let method = if let Some(_) = p.eat_punct(':') {
+            let Some(span) = p.eat_punct(':') else {
+                return Err(syn::Error::new(p.last_span().unwrap(), "expected `::`"));
+            };
+            Some(Pattern::parse_until(p, span)?)
+        } else {
+            None
+        };
+
+        let Some(_) = p.eat_punct(';') else {
+            return Err(syn::Error::new(
+                p.last_span().unwrap(),
+                format!("expected `;` after `{keyword}` filter"),
+            ));
+        };
+
+        match (&keyword.to_string()[..], method) {
+            ("generate", None) => Ok(Some(Filter::Generate(class))),
+            ("exclude", None) => Ok(Some(Filter::ExcludeClasses(class))),
+            ("include", Some(method)) => Ok(Some(Filter::IncludeMethods(class, method))),
+            ("exclude", Some(method)) => Ok(Some(Filter::ExcludeMethods(class, method))),
+            ("generate", Some(_)) => Err(syn::Error::new(
+                keyword.span(),
+                "`generate` selects classes; use `include` to select their methods",
+            )),
+            _ => Err(syn::Error::new(
+                keyword.span(),
+                "`include` selects methods (e.g., `include foo.Bar::baz*;`); use `generate` to select classes",
+            )),
+        }
+    }
+
+    fn description() -> String {
+        format!("class or method filter (e.g., `generate *;` or `exclude foo.Bar::baz;`)")
+    }
+}
+
+/// A pattern matching Java names, where `*` matches any sequence of characters (including `.`
+/// and `$`, so `foo.*` matches the classes of subpackages and nested classes too).
+pub struct Pattern {
+    pub text: String,
+    pub span: Span,
+}
+
+impl Pattern {
+    /// Parses a pattern up to a `;` or `::`; `span` is that of the preceding token, for errors.
+    fn parse_until(p: &mut Parser, span: Span) -> syn::Result<Pattern> {
+        let mut text = String::new();
+        let mut span = span;
+        let mut first = true;
+        while let Some(t) = p.eat_token_if(|t| match t {
+            TokenTree::Ident(_) => true,
+            TokenTree::Punct(p) => matches!(p.as_char(), '.' | '*' | '$'),
+            _ => false,
+        }) {
+            text.push_str(&t.to_string());
+            span = if first {
+                t.span()
+            } else {
+                span.join(t.span()).unwrap_or(span)
+            };
+            first = false;
+        }
+        if text.is_empty() {
+            return Err(syn::Error::new(
+                span,
+                "expected a pattern (e.g., `*` or `foo.bar.*`)",
+            ));
+        }
+        Ok(Pattern { text, span })
+    }
+
+    /// Whether `name` matches this pattern.
+    pub fn matches(&self, name: &str) -> bool {
+        glob_matches(&self.text, name)
+    }
+
+    /// The pattern as an absolute class name pattern, prefixed with `package` unless it already
+    /// contains a `.`.
+    pub fn in_package(&self, package: &JavaPath) -> Pattern {
+        let text = if self.text.contains('.') {
+            self.text.clone()
+        } else {
+            format!("{package}.{}", self.text)
+        };
+        Pattern {
+            text,
+            span: self.span,
+        }
+    }
+
+    /// The longest package that contains every class the pattern can match (e.g., `foo.bar` for
+    /// `foo.bar.Baz*`).
+    pub fn package_prefix(&self) -> Vec<&str> {
+        let literal = self.text.split('*').next().unwrap();
+        let mut ids: Vec<&str> = literal.split('.').collect();
+        ids.pop();
+        ids
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| glob_matches(rest, &name[i..]))
+        }
+    }
 }
 
 impl Parse for JavaPackage {
@@ -151,11 +365,22 @@ impl Parse for JavaPackage {
             ));
         };
 
-        let classes = ClassDecl::parse_many(p)?;
+        let mut classes = vec![];
+        let mut filters = vec![];
+        loop {
+            if let Some(filter) = Filter::parse(p)? {
+                filters.push(filter);
+            } else if let Some(class) = ClassDecl::parse(p)? {
+                classes.push(class);
+            } else {
+                break;
+            }
+        }
 
         Ok(Some(JavaPackage {
             package_name,
             classes,
+            filters,
         }))
     }
 
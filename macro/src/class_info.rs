@@ -145,7 +145,14 @@ pub struct ClassInfo {
 
 impl ClassInfo {
     pub fn parse(text: &str, span: Span) -> syn::Result<ClassInfo> {
-        javap::parse_class_info(span, &text)
+        // The values of constants (printed by `javap -constants`) aren't part of the grammar,
+        // so they are split off first
+        let (text, constants) = javap::split_constants(text);
+        let mut info = javap::parse_class_info(span, &text)?;
+        for field in &mut info.fields {
+            field.constant = constants.get(&field.name[..]).cloned();
+        }
+        Ok(info)
     }
 
     pub fn this_ref(&self) -> ClassRef {
@@ -159,6 +166,67 @@ impl ClassInfo {
         }
     }
 
+    /// Whether this is a Java enum (which extends `java.lang.Enum`).
+    pub fn is_enum(&self) -> bool {
+        self.extends
+            .iter()
+            .any(|c| c.name == DotId::parse("java.lang.Enum"))
+    }
+
+    /// The constants of a Java enum, in declaration order, or `None` if the class isn't an enum.
+    /// Other fields of the enum's type, like `static final Color DEFAULT = RED;`, aren't constants.
+    pub fn enum_constants(&self) -> Option<Vec<&Field>> {
+        if !self.is_enum() {
+            return None;
+        }
+        Some(
+            self.fields
+                .iter()
+                .filter(|f| self.should_mirror_in_rust(f.flags.privacy))
+                .filter(|f| f.flags.is_enum)
+                .collect(),
+        )
+    }
+
+    /// If this is a (non-generic) record, returns its canonical constructor and the accessors of
+    /// its components, in order. The components aren't in the class signature, so they are taken to
+    /// be the first declared methods without arguments whose return types match the arguments of a
+    /// declared constructor, in order.
+    pub fn record_components(&self) -> Option<(&Constructor, Vec<&Method>)> {
+        if !self.generics.is_empty()
+            || !self
+                .extends
+                .iter()
+                .any(|c| c.name == DotId::parse("java.lang.Record"))
+        {
+            return None;
+        }
+        let candidates: Vec<&Method> = self
+            .methods
+            .iter()
+            .filter(|m| self.should_mirror_in_rust(m.flags.privacy))
+            .filter(|m| !m.flags.is_static && m.generics.is_empty())
+            .filter(|m| m.argument_tys.is_empty() && m.return_ty.is_some())
+            .filter(|m| !["toString", "hashCode"].contains(&&m.name[..]))
+            .collect();
+        self.constructors
+            .iter()
+            .filter(|c| self.should_mirror_in_rust(c.flags.privacy) && c.generics.is_empty())
+            .find_map(|c| {
+                let mut candidates = candidates.iter();
+                let accessors = c
+                    .argument_tys
+                    .iter()
+                    .map(|t| {
+                        candidates
+                            .find(|m| m.return_ty.as_ref() == Some(t))
+                            .copied()
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some((c, accessors))
+            })
+    }
+
     /// Indicates whether a member with the given privacy level should be reflected in Rust.
     /// We always mirror things declared as public.
     /// In classes, the default privacy indicates "package level" visibility and we do not mirror.
@@ -184,6 +252,33 @@ impl Generic {
     pub fn to_ident(&self, span: Span) -> Ident {
         self.id.to_ident(span)
     }
+
+    /// The generic parameters visible in a member that declares `member_generics`, in a class that declares
+    /// `class_generics`. The member's come first, since they shadow the class's.
+    pub fn in_scope<'g>(
+        member_generics: &'g [Generic],
+        class_generics: &'g [Generic],
+    ) -> Vec<&'g Generic> {
+        member_generics.iter().chain(class_generics).collect()
+    }
+
+    /// The JNI descriptor of the erasure of this parameter, i.e. of its first bound, or of `java.lang.Object` if it
+    /// has none. `generics` are the parameters in scope, since the bound can be another parameter.
+    fn erased_descriptor(&self, generics: &[&Generic]) -> String {
+        match self.extends.first() {
+            None => format!("Ljava/lang/Object;"),
+            Some(bound) => {
+                let parameter = match &bound.name[..] {
+                    [id] => generics.iter().find(|g| g.id == *id && g.id != self.id),
+                    _ => None,
+                };
+                match parameter {
+                    Some(parameter) => parameter.erased_descriptor(generics),
+                    None => format!("L{};", bound.name.to_jni_name()),
+                }
+            }
+        }
+    }
 }
 
 impl std::fmt::Display for Generic {
@@ -216,6 +311,9 @@ pub struct Flags {
     pub is_default: bool,
     pub is_transient: bool,
     pub is_volatile: bool,
+    /// Whether a field is a constant of its enum (`ACC_ENUM`), which isn't part of the declaration but taken from
+    /// the class file.
+    pub is_enum: bool,
 }
 
 impl Flags {
@@ -230,6 +328,7 @@ impl Flags {
             is_default: false,
             is_transient: false,
             is_volatile: false,
+            is_enum: false,
         }
     }
 }
@@ -280,12 +379,14 @@ impl Constructor {
         }
     }
 
-    pub fn descriptor(&self) -> String {
+    /// The JNI descriptor of the constructor of a class whose generic parameters are `class_generics`.
+    pub fn descriptor(&self, class_generics: &[Generic]) -> String {
+        let generics = Generic::in_scope(&self.generics, class_generics);
         format!(
             "({})V",
             self.argument_tys
                 .iter()
-                .map(|a| a.descriptor())
+                .map(|a| a.descriptor(&generics))
                 .collect::<String>()
         )
     }
@@ -296,6 +397,71 @@ pub struct Field {
     pub flags: Flags,
     pub name: Id,
     pub ty: Type,
+    /// The value of a constant field, as printed by `javap -constants` (e.g. `'\uffff'`)
+    pub constant: Option<String>,
+}
+
+/// The value of a constant field (a `static final` field of primitive or `String` type
+/// initialized with a constant expression), see [`Field::constant_value`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstantValue {
+    Boolean(bool),
+    Byte(i8),
+    Char(u16),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+}
+
+impl Field {
+    /// Whether the field can be a constant, whose value is then found by reflection.
+    pub fn may_be_constant(&self) -> bool {
+        self.flags.is_static
+            && self.flags.is_final
+            && match &self.ty {
+                Type::Scalar(_) => true,
+                Type::Ref(RefType::Class(c)) => c.name == DotId::parse("java.lang.String"),
+                _ => false,
+            }
+    }
+
+    /// The value of the field, if it is a constant. Strings with unpaired surrogates have no
+    /// Rust equivalent, so they aren't considered constants.
+    pub fn constant_value(&self) -> Option<ConstantValue> {
+        if !self.may_be_constant() {
+            return None;
+        }
+        let literal = self.constant.as_deref()?;
+        Some(match &self.ty {
+            Type::Scalar(ScalarType::Boolean) => ConstantValue::Boolean(literal.parse().ok()?),
+            Type::Scalar(ScalarType::Byte) => ConstantValue::Byte(literal.parse().ok()?),
+            Type::Scalar(ScalarType::Short) => ConstantValue::Short(literal.parse().ok()?),
+            Type::Scalar(ScalarType::Int) => ConstantValue::Int(literal.parse().ok()?),
+            Type::Scalar(ScalarType::Long) => {
+                ConstantValue::Long(literal.strip_suffix('l')?.parse().ok()?)
+            }
+            Type::Scalar(ScalarType::F32) => {
+                ConstantValue::Float(literal.strip_suffix('f')?.parse().ok()?)
+            }
+            Type::Scalar(ScalarType::F64) => {
+                ConstantValue::Double(literal.strip_suffix('d')?.parse().ok()?)
+            }
+            Type::Scalar(ScalarType::Char) => {
+                let units = javap::unescape(literal.strip_prefix('\'')?.strip_suffix('\'')?)?;
+                match units[..] {
+                    [unit] => ConstantValue::Char(unit),
+                    _ => return None,
+                }
+            }
+            Type::Ref(_) | Type::Repeat(_) => {
+                let units = javap::unescape(literal.strip_prefix('"')?.strip_suffix('"')?)?;
+                ConstantValue::String(String::from_utf16(&units).ok()?)
+            }
+        })
+    }
 }
 
 #[derive(Eq, Ord, PartialEq, PartialOrd, Clone, Debug)]
@@ -317,16 +483,19 @@ impl Method {
         }
     }
 
-    pub fn descriptor(&self) -> String {
+    /// The JNI descriptor of the method, which is declared in a class whose generic parameters are
+    /// `class_generics`.
+    pub fn descriptor(&self, class_generics: &[Generic]) -> String {
+        let generics = Generic::in_scope(&self.generics, class_generics);
         format!(
             "({}){}",
             self.argument_tys
                 .iter()
-                .map(|a| a.descriptor())
+                .map(|a| a.descriptor(&generics))
                 .collect::<String>(),
             self.return_ty
                 .as_ref()
-                .map(|r| r.descriptor())
+                .map(|r| r.descriptor(&generics))
                 .unwrap_or_else(|| format!("V")),
         )
     }
@@ -424,8 +593,10 @@ impl Type {
         }
     }
 
-    pub fn descriptor(&self) -> String {
-        self.to_non_repeating().descriptor()
+    /// The JNI descriptor of the type, where `generics` are the generic parameters in scope (see
+    /// [`Generic::in_scope`]), which are erased to their bounds.
+    pub fn descriptor(&self, generics: &[&Generic]) -> String {
+        self.to_non_repeating().descriptor(generics)
     }
 }
 
@@ -437,18 +608,21 @@ pub enum NonRepeatingType {
 }
 
 impl NonRepeatingType {
-    pub fn descriptor(&self) -> String {
+    pub fn descriptor(&self, generics: &[&Generic]) -> String {
         match self {
             NonRepeatingType::Ref(r) => match r {
                 RefType::Class(c) => format!("L{};", c.name.to_jni_name()),
-                RefType::Array(r) => format!("[{}", r.descriptor()),
-
-                // FIXME(#42): The descriptor actually depends on how the type
-                // parameter was declared!
-                RefType::TypeParameter(_)
-                | RefType::Extends(_)
-                | RefType::Super(_)
-                | RefType::Wildcard => format!("Ljava/lang/Object;"),
+                RefType::Array(r) => format!("[{}", r.descriptor(generics)),
+
+                RefType::TypeParameter(t) => match generics.iter().find(|g| g.id == *t) {
+                    Some(g) => g.erased_descriptor(generics),
+                    None => format!("Ljava/lang/Object;"),
+                },
+
+                // Wildcards only appear as type arguments, which are erased
+                RefType::Extends(_) | RefType::Super(_) | RefType::Wildcard => {
+                    format!("Ljava/lang/Object;")
+                }
             },
             NonRepeatingType::Scalar(s) => match s {
                 ScalarType::Int => format!("I"),
@@ -699,4 +873,5 @@ impl std::fmt::Display for DotId {
     }
 }
 
+pub mod class_file;
 mod javap;
@@ -0,0 +1,816 @@
+//! Reads class files, rendering them as `javap -p -constants` would, so that classes found on the classpath can be
+//! reflected without a JDK (and are then parsed like the output of `javap` itself).
+//!
+//! See chapter 4 of the JVM specification for the format of class files and the grammar of generic signatures.
+//!
+//! Class files may come from any jar on the classpath, so malformed ones are reported as errors rather than panicking
+//! the macro; the `fuzz` directory of this crate has cargo-fuzz targets for the functions of this module.
+
+use std::fmt::Write;
+
+const ACC_PUBLIC: u16 = 0x0001;
+const ACC_PRIVATE: u16 = 0x0002;
+const ACC_PROTECTED: u16 = 0x0004;
+const ACC_STATIC: u16 = 0x0008;
+const ACC_FINAL: u16 = 0x0010;
+const ACC_SYNCHRONIZED: u16 = 0x0020;
+const ACC_VOLATILE: u16 = 0x0040;
+const ACC_TRANSIENT: u16 = 0x0080;
+const ACC_VARARGS: u16 = 0x0080;
+const ACC_NATIVE: u16 = 0x0100;
+const ACC_INTERFACE: u16 = 0x0200;
+const ACC_ABSTRACT: u16 = 0x0400;
+const ACC_STRICT: u16 = 0x0800;
+const ACC_ENUM: u16 = 0x4000;
+
+/// The modifiers javap prints, in the order it prints them.
+const CLASS_MODIFIERS: &[(u16, &str)] = &[
+    (ACC_PUBLIC, "public"),
+    (ACC_FINAL, "final"),
+    (ACC_ABSTRACT, "abstract"),
+];
+const FIELD_MODIFIERS: &[(u16, &str)] = &[
+    (ACC_PUBLIC, "public"),
+    (ACC_PRIVATE, "private"),
+    (ACC_PROTECTED, "protected"),
+    (ACC_STATIC, "static"),
+    (ACC_FINAL, "final"),
+    (ACC_VOLATILE, "volatile"),
+    (ACC_TRANSIENT, "transient"),
+];
+const METHOD_MODIFIERS: &[(u16, &str)] = &[
+    (ACC_PUBLIC, "public"),
+    (ACC_PRIVATE, "private"),
+    (ACC_PROTECTED, "protected"),
+    (ACC_STATIC, "static"),
+    (ACC_FINAL, "final"),
+    (ACC_SYNCHRONIZED, "synchronized"),
+    (ACC_NATIVE, "native"),
+    (ACC_ABSTRACT, "abstract"),
+    (ACC_STRICT, "strictfp"),
+];
+
+/// The most nested types that are parsed, which bounds the recursion of the signature parser. This is also the most
+/// dimensions that an array type may have.
+const MAX_TYPE_DEPTH: usize = 255;
+
+/// Renders the class file `bytes` as `javap -p -constants` would (without the `Compiled from` header).
+pub fn to_javap(bytes: &[u8]) -> Result<String, String> {
+    let class = ClassFile::read(bytes).ok_or("malformed class file")?;
+    class.to_javap()
+}
+
+/// A method (or constructor, named `<init>`) as declared in a class file.
+pub struct MethodDescriptor {
+    pub name: String,
+    pub descriptor: String,
+    pub argument_count: usize,
+}
+
+/// The methods of the class file `bytes`, to check the descriptors that are derived from the output of [`to_javap`]
+/// against.
+pub fn method_descriptors(bytes: &[u8]) -> Result<Vec<MethodDescriptor>, String> {
+    let class = ClassFile::read(bytes).ok_or("malformed class file")?;
+    class
+        .methods
+        .iter()
+        .map(|method| {
+            let descriptor = class.utf8(method.descriptor)?;
+            Ok(MethodDescriptor {
+                name: class.utf8(method.name)?,
+                argument_count: SignatureParser::new(&descriptor)
+                    .method_signature()?
+                    .arguments
+                    .len(),
+                descriptor,
+            })
+        })
+        .collect()
+}
+
+/// The names of the fields of the class file `bytes` that are enum constants (`ACC_ENUM`), in declaration order.
+/// javap only prints the flag with `-v`, so other fields of the enum's type can't be told apart from them otherwise.
+pub fn enum_constants(bytes: &[u8]) -> Result<Vec<String>, String> {
+    let class = ClassFile::read(bytes).ok_or("malformed class file")?;
+    class
+        .fields
+        .iter()
+        .filter(|field| field.flags & ACC_ENUM != 0)
+        .map(|field| class.utf8(field.name))
+        .collect()
+}
+
+/// Renders the generic method signature or method descriptor `signature` as javap would, e.g.
+/// `<T> (T, int) -> java.util.List<T>`. Only used by the `signature` fuzz target.
+#[allow(dead_code)]
+pub fn method_signature_to_javap(signature: &str) -> Result<String, String> {
+    let signature = SignatureParser::new(signature).method_signature()?;
+    let generics = if signature.generics.is_empty() {
+        String::new()
+    } else {
+        format!("<{}> ", signature.generics.join(", "))
+    };
+    Ok(format!(
+        "{generics}({}) -> {}",
+        signature.arguments.join(", "),
+        signature.return_ty
+    ))
+}
+
+/// Renders the generic field signature or field descriptor `signature` as javap would, e.g. `java.util.List<T>`. Only used by
+/// the `signature` fuzz target.
+#[allow(dead_code)]
+pub fn field_signature_to_javap(signature: &str) -> Result<String, String> {
+    SignatureParser::new(signature).field_type()
+}
+
+enum Constant {
+    Utf8(Vec<u16>),
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    Class(u16),
+    String(u16),
+    /// Constants javap doesn't print in declarations, and the unusable slot after longs and doubles.
+    Other,
+}
+
+struct Member {
+    flags: u16,
+    name: u16,
+    descriptor: u16,
+    attributes: Vec<Attribute>,
+}
+
+struct Attribute {
+    name: u16,
+    info: Vec<u8>,
+}
+
+struct ClassFile {
+    constants: Vec<Constant>,
+    flags: u16,
+    this_class: u16,
+    super_class: u16,
+    interfaces: Vec<u16>,
+    fields: Vec<Member>,
+    methods: Vec<Member>,
+    attributes: Vec<Attribute>,
+}
+
+/// A cursor over big-endian class file data.
+struct Bytes<'b> {
+    data: &'b [u8],
+}
+
+impl<'b> Bytes<'b> {
+    fn take(&mut self, n: usize) -> Option<&'b [u8]> {
+        if self.data.len() < n {
+            return None;
+        }
+        let (taken, rest) = self.data.split_at(n);
+        self.data = rest;
+        Some(taken)
+    }
+
+    fn u1(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u2(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u4(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u8(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn attributes(&mut self) -> Option<Vec<Attribute>> {
+        (0..self.u2()?)
+            .map(|_| {
+                let name = self.u2()?;
+                let len = self.u4()? as usize;
+                let info = self.take(len)?.to_vec();
+                Some(Attribute { name, info })
+            })
+            .collect()
+    }
+
+    fn members(&mut self) -> Option<Vec<Member>> {
+        (0..self.u2()?)
+            .map(|_| {
+                Some(Member {
+                    flags: self.u2()?,
+                    name: self.u2()?,
+                    descriptor: self.u2()?,
+                    attributes: self.attributes()?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl ClassFile {
+    fn read(data: &[u8]) -> Option<Self> {
+        let mut b = Bytes { data };
+        if b.u4()? != 0xCAFEBABE {
+            return None;
+        }
+        let _minor_version = b.u2()?;
+        let _major_version = b.u2()?;
+
+        // Index 0 of the constant pool is unused
+        let count = b.u2()?;
+        let mut constants = vec![Constant::Other];
+        while constants.len() < count as usize {
+            let constant = match b.u1()? {
+                1 => {
+                    let len = b.u2()? as usize;
+                    Constant::Utf8(decode_modified_utf8(b.take(len)?)?)
+                }
+                3 => Constant::Integer(b.u4()? as i32),
+                4 => Constant::Float(f32::from_bits(b.u4()?)),
+                5 => Constant::Long(b.u8()? as i64),
+                6 => Constant::Double(f64::from_bits(b.u8()?)),
+                7 => Constant::Class(b.u2()?),
+                8 => Constant::String(b.u2()?),
+                // MethodType, Module, Package
+                16 | 19 | 20 => {
+                    b.take(2)?;
+                    Constant::Other
+                }
+                // MethodHandle
+                15 => {
+                    b.take(3)?;
+                    Constant::Other
+                }
+                // Fieldref, Methodref, InterfaceMethodref, NameAndType, Dynamic, InvokeDynamic
+                9 | 10 | 11 | 12 | 17 | 18 => {
+                    b.take(4)?;
+                    Constant::Other
+                }
+                _ => return None,
+            };
+            // Longs and doubles take up two entries
+            let wide = matches!(constant, Constant::Long(_) | Constant::Double(_));
+            constants.push(constant);
+            if wide {
+                constants.push(Constant::Other);
+            }
+        }
+
+        let flags = b.u2()?;
+        let this_class = b.u2()?;
+        let super_class = b.u2()?;
+        let interfaces = (0..b.u2()?).map(|_| b.u2()).collect::<Option<_>>()?;
+        let fields = b.members()?;
+        let methods = b.members()?;
+        let attributes = b.attributes()?;
+        Some(ClassFile {
+            constants,
+            flags,
+            this_class,
+            super_class,
+            interfaces,
+            fields,
+            methods,
+            attributes,
+        })
+    }
+
+    fn utf8(&self, index: u16) -> Result<String, String> {
+        match self.constants.get(index as usize) {
+            Some(Constant::Utf8(units)) => Ok(String::from_utf16_lossy(units)),
+            _ => Err(format!("constant #{index} isn't a string")),
+        }
+    }
+
+    /// The Java name (e.g. `java.util.Map$Entry`) of the class constant at `index`.
+    fn class_name(&self, index: u16) -> Result<String, String> {
+        match self.constants.get(index as usize) {
+            Some(Constant::Class(name)) => Ok(self.utf8(*name)?.replace('/', ".")),
+            _ => Err(format!("constant #{index} isn't a class")),
+        }
+    }
+
+    fn attribute<'a>(&self, attributes: &'a [Attribute], name: &str) -> Option<&'a [u8]> {
+        attributes
+            .iter()
+            .find(|a| self.utf8(a.name).is_ok_and(|n| n == name))
+            .map(|a| &a.info[..])
+    }
+
+    /// The generic signature recorded in `attributes`, if any.
+    fn signature(&self, attributes: &[Attribute]) -> Result<Option<String>, String> {
+        let Some(info) = self.attribute(attributes, "Signature") else {
+            return Ok(None);
+        };
+        let index = Bytes { data: info }.u2().ok_or("malformed signature")?;
+        Ok(Some(self.utf8(index)?))
+    }
+
+    fn is_interface(&self) -> bool {
+        self.flags & ACC_INTERFACE != 0
+    }
+
+    fn to_javap(&self) -> Result<String, String> {
+        let mut out = String::new();
+        let name = self.class_name(self.this_class)?;
+
+        // Interfaces are implicitly abstract
+        let class_flags = if self.is_interface() {
+            self.flags & !ACC_ABSTRACT
+        } else {
+            self.flags
+        };
+        write_modifiers(&mut out, class_flags, CLASS_MODIFIERS);
+        let kind = if self.is_interface() {
+            "interface"
+        } else {
+            "class"
+        };
+        write!(out, "{kind} {name}").unwrap();
+
+        match self.signature(&self.attributes)? {
+            Some(signature) => {
+                let signature = SignatureParser::new(&signature).class_signature()?;
+                out.push_str(&signature.to_javap(self.is_interface()));
+            }
+            None => {
+                if !self.is_interface() && self.super_class != 0 {
+                    let super_class = self.class_name(self.super_class)?;
+                    if super_class != "java.lang.Object" {
+                        write!(out, " extends {super_class}").unwrap();
+                    }
+                }
+                for (i, &interface) in self.interfaces.iter().enumerate() {
+                    let separator = match (i, self.is_interface()) {
+                        (0, false) => " implements ",
+                        (0, true) => " extends ",
+                        _ => ",",
+                    };
+                    write!(out, "{separator}{}", self.class_name(interface)?).unwrap();
+                }
+            }
+        }
+        out.push_str(" {\n");
+
+        for field in &self.fields {
+            self.write_field(&mut out, field)?;
+        }
+        for method in &self.methods {
+            self.write_method(&mut out, &name, method)?;
+        }
+        out.push_str("}\n");
+        Ok(out)
+    }
+
+    fn write_field(&self, out: &mut String, field: &Member) -> Result<(), String> {
+        out.push_str("  ");
+        write_modifiers(out, field.flags, FIELD_MODIFIERS);
+        let descriptor = self.utf8(field.descriptor)?;
+        let ty = match self.signature(&field.attributes)? {
+            Some(signature) => SignatureParser::new(&signature).field_type()?,
+            None => SignatureParser::new(&descriptor).field_type()?,
+        };
+        write!(out, "{ty} {}", self.utf8(field.name)?).unwrap();
+
+        if let Some(info) = self.attribute(&field.attributes, "ConstantValue") {
+            let index = Bytes { data: info }
+                .u2()
+                .ok_or("malformed constant value")?;
+            write!(out, " = {}", self.constant_value(&descriptor, index)?).unwrap();
+        }
+        out.push_str(";\n");
+        Ok(())
+    }
+
+    fn constant_value(&self, descriptor: &str, index: u16) -> Result<String, String> {
+        Ok(match self.constants.get(index as usize) {
+            Some(Constant::Integer(value)) => match descriptor {
+                "C" => format!("'{}'", escape(&[*value as u16], '\'')),
+                "Z" => format!("{}", *value == 1),
+                _ => format!("{value}"),
+            },
+            Some(Constant::Long(value)) => format!("{value}l"),
+            Some(Constant::Float(value)) => format!("{}f", java_float_string(*value as f64, true)),
+            Some(Constant::Double(value)) => format!("{}d", java_float_string(*value, false)),
+            Some(Constant::String(string)) => match self.constants.get(*string as usize) {
+                Some(Constant::Utf8(units)) => format!("\"{}\"", escape(units, '"')),
+                _ => return Err(format!("constant #{string} isn't a string")),
+            },
+            _ => return Err(format!("constant #{index} isn't a constant value")),
+        })
+    }
+
+    fn write_method(
+        &self,
+        out: &mut String,
+        class_name: &str,
+        method: &Member,
+    ) -> Result<(), String> {
+        let name = self.utf8(method.name)?;
+        let signature = match self.signature(&method.attributes)? {
+            Some(signature) => SignatureParser::new(&signature).method_signature()?,
+            None => SignatureParser::new(&self.utf8(method.descriptor)?).method_signature()?,
+        };
+
+        out.push_str("  ");
+        write_modifiers(out, method.flags, METHOD_MODIFIERS);
+        // Non-abstract instance methods of interfaces are default methods
+        if self.is_interface()
+            && method.flags & (ACC_ABSTRACT | ACC_STATIC | ACC_PRIVATE) == 0
+            && name != "<clinit>"
+        {
+            out.push_str("default ");
+        }
+        if !signature.generics.is_empty() {
+            write!(out, "<{}> ", signature.generics.join(", ")).unwrap();
+        }
+
+        let mut arguments = format!("({})", signature.arguments.join(", "));
+        if method.flags & ACC_VARARGS != 0 {
+            if let Some(i) = arguments.rfind("[]") {
+                arguments.replace_range(i..i + 2, "...");
+            }
+        }
+        match &name[..] {
+            "<init>" => write!(out, "{class_name}{arguments}").unwrap(),
+            "<clinit>" => out.push_str("{}"),
+            _ => write!(out, "{} {name}{arguments}", signature.return_ty).unwrap(),
+        }
+
+        // The generic signature only lists what's thrown if that is generic, so the attribute is what tells if
+        // anything is
+        if let Some(info) = self.attribute(&method.attributes, "Exceptions") {
+            let throws = if !signature.throws.is_empty() {
+                signature.throws
+            } else {
+                let mut b = Bytes { data: info };
+                let count = b.u2().ok_or("malformed exceptions")?;
+                (0..count)
+                    .map(|_| self.class_name(b.u2().ok_or("malformed exceptions")?))
+                    .collect::<Result<_, _>>()?
+            };
+            write!(out, " throws {}", throws.join(", ")).unwrap();
+        }
+        out.push_str(";\n");
+        Ok(())
+    }
+}
+
+fn write_modifiers(out: &mut String, flags: u16, modifiers: &[(u16, &str)]) {
+    for &(flag, modifier) in modifiers {
+        if flags & flag != 0 {
+            out.push_str(modifier);
+            out.push(' ');
+        }
+    }
+}
+
+/// Decodes the "modified UTF-8" of class files (in which supplementary characters are encoded as surrogate pairs of
+/// three bytes each) into UTF-16 code units.
+fn decode_modified_utf8(bytes: &[u8]) -> Option<Vec<u16>> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b0 = bytes[i] as u16;
+        let continuation = |j: usize| -> Option<u16> {
+            let b = *bytes.get(i + j)? as u16;
+            (b & 0xC0 == 0x80).then_some(b & 0x3F)
+        };
+        let (unit, len) = if b0 & 0x80 == 0 {
+            (b0, 1)
+        } else if b0 & 0xE0 == 0xC0 {
+            ((b0 & 0x1F) << 6 | continuation(1)?, 2)
+        } else if b0 & 0xF0 == 0xE0 {
+            (
+                (b0 & 0x0F) << 12 | continuation(1)? << 6 | continuation(2)?,
+                3,
+            )
+        } else {
+            return None;
+        };
+        units.push(unit);
+        i += len;
+    }
+    Some(units)
+}
+
+/// Escapes the contents of a character or string literal (delimited by `quote`) like javap does, which is with
+/// `\uXXXX` for all non-ASCII characters.
+fn escape(units: &[u16], quote: char) -> String {
+    let mut out = String::new();
+    for &unit in units {
+        match unit {
+            0x08 => out.push_str("\\b"),
+            0x09 => out.push_str("\\t"),
+            0x0a => out.push_str("\\n"),
+            0x0c => out.push_str("\\f"),
+            0x0d => out.push_str("\\r"),
+            0x5c => out.push_str("\\\\"),
+            _ if unit == quote as u16 => {
+                out.push('\\');
+                out.push(quote);
+            }
+            32..=126 => out.push(unit as u8 as char),
+            _ => write!(out, "\\u{unit:04x}").unwrap(),
+        }
+    }
+    out
+}
+
+/// Formats `value` like Java's `Double.toString` (or `Float.toString`, if `single` is set, in which case `value` must
+/// be exactly representable as an `f32`).
+fn java_float_string(value: f64, single: bool) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    let sign = if value.is_sign_negative() { "-" } else { "" };
+    if value.is_infinite() {
+        return format!("{sign}Infinity");
+    }
+    if value == 0.0 {
+        return format!("{sign}0.0");
+    }
+
+    // The shortest digits that round-trip, and the exponent of the first of them. Java uses at least two digits
+    // (the closest ones to `value`), e.g. `4.9E-324` rather than `5.0E-324`.
+    let mut scientific = if single {
+        format!("{:e}", (value as f32).abs())
+    } else {
+        format!("{:e}", value.abs())
+    };
+    if !scientific.contains('.') {
+        scientific = if single {
+            format!("{:.1e}", (value as f32).abs())
+        } else {
+            format!("{:.1e}", value.abs())
+        };
+    }
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let digits = match digits.trim_end_matches('0') {
+        "" => "0",
+        digits => digits,
+    };
+    let exponent: i32 = exponent.parse().unwrap();
+
+    if (-3..7).contains(&exponent) {
+        let (integer, fraction) = if exponent >= 0 {
+            let point = exponent as usize + 1;
+            let padded = format!("{digits:0<point$}");
+            let (integer, fraction) = padded.split_at(point);
+            (integer.to_string(), fraction.to_string())
+        } else {
+            let zeros = "0".repeat((-exponent - 1) as usize);
+            ("0".to_string(), format!("{zeros}{digits}"))
+        };
+        let fraction = if fraction.is_empty() { "0" } else { &fraction };
+        format!("{sign}{integer}.{fraction}")
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() { "0" } else { rest };
+        format!("{sign}{first}.{rest}E{exponent}")
+    }
+}
+
+/// The parts of a method signature (or descriptor), rendered as javap does.
+struct MethodSignature {
+    generics: Vec<String>,
+    arguments: Vec<String>,
+    return_ty: String,
+    throws: Vec<String>,
+}
+
+/// The parts of a class signature, rendered as javap does.
+struct ClassSignature {
+    generics: Vec<String>,
+    super_class: String,
+    interfaces: Vec<String>,
+}
+
+impl ClassSignature {
+    /// Renders the generics and supertypes that follow the name of the class.
+    fn to_javap(&self, is_interface: bool) -> String {
+        let mut out = String::new();
+        if !self.generics.is_empty() {
+            write!(out, "<{}>", self.generics.join(", ")).unwrap();
+        }
+        if is_interface {
+            if !self.interfaces.is_empty() {
+                write!(out, " extends {}", self.interfaces.join(", ")).unwrap();
+            }
+        } else {
+            if self.super_class != "java.lang.Object" {
+                write!(out, " extends {}", self.super_class).unwrap();
+            }
+            if !self.interfaces.is_empty() {
+                write!(out, " implements {}", self.interfaces.join(", ")).unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Parses generic signatures and descriptors (which are a subset of them), rendering the types in them as javap does.
+struct SignatureParser<'s> {
+    text: &'s str,
+    /// How many types the type being parsed is nested in.
+    depth: usize,
+}
+
+impl<'s> SignatureParser<'s> {
+    fn new(text: &'s str) -> Self {
+        SignatureParser { text, depth: 0 }
+    }
+
+    fn error(&self) -> String {
+        format!("malformed signature at `{}`", self.text)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text.chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        match self.text.strip_prefix(c) {
+            Some(rest) => {
+                self.text = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Takes the text up to (not including) the first of `terminators`.
+    fn identifier(&mut self, terminators: &[char]) -> Result<&'s str, String> {
+        let end = self.text.find(terminators).ok_or_else(|| self.error())?;
+        let (identifier, rest) = self.text.split_at(end);
+        self.text = rest;
+        Ok(identifier)
+    }
+
+    fn end(&self) -> Result<(), String> {
+        if self.text.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn class_signature(mut self) -> Result<ClassSignature, String> {
+        let generics = self.type_parameters()?;
+        let super_class = self.reference_type()?;
+        let mut interfaces = vec![];
+        while !self.text.is_empty() {
+            interfaces.push(self.reference_type()?);
+        }
+        Ok(ClassSignature {
+            generics,
+            super_class,
+            interfaces,
+        })
+    }
+
+    fn method_signature(mut self) -> Result<MethodSignature, String> {
+        let generics = self.type_parameters()?;
+        self.expect('(')?;
+        let mut arguments = vec![];
+        while !self.eat(')') {
+            arguments.push(self.java_type()?);
+        }
+        let return_ty = if self.eat('V') {
+            "void".to_string()
+        } else {
+            self.java_type()?
+        };
+        let mut throws = vec![];
+        while self.eat('^') {
+            throws.push(self.reference_type()?);
+        }
+        self.end()?;
+        Ok(MethodSignature {
+            generics,
+            arguments,
+            return_ty,
+            throws,
+        })
+    }
+
+    fn field_type(mut self) -> Result<String, String> {
+        let ty = self.java_type()?;
+        self.end()?;
+        Ok(ty)
+    }
+
+    /// Parses `<T:Ljava/lang/Object;U::Ljava/lang/Comparable<TU;>;>` into `["T", "U extends java.lang.Comparable<U>"]`;
+    /// bounds of `java.lang.Object` are left out.
+    fn type_parameters(&mut self) -> Result<Vec<String>, String> {
+        let mut generics = vec![];
+        if !self.eat('<') {
+            return Ok(generics);
+        }
+        while !self.eat('>') {
+            let mut generic = self.identifier(&[':'])?.to_string();
+            let mut separator = " extends ";
+            self.expect(':')?;
+            // The class bound may be empty, when there are only interface bounds
+            if !matches!(self.peek(), Some(':')) {
+                let bound = self.reference_type()?;
+                if bound != "java.lang.Object" {
+                    write!(generic, "{separator}{bound}").unwrap();
+                    separator = " & ";
+                }
+            }
+            while self.eat(':') {
+                let bound = self.reference_type()?;
+                write!(generic, "{separator}{bound}").unwrap();
+                separator = " & ";
+            }
+            generics.push(generic);
+        }
+        Ok(generics)
+    }
+
+    fn java_type(&mut self) -> Result<String, String> {
+        let scalar = match self.peek() {
+            Some('B') => "byte",
+            Some('C') => "char",
+            Some('D') => "double",
+            Some('F') => "float",
+            Some('I') => "int",
+            Some('J') => "long",
+            Some('S') => "short",
+            Some('Z') => "boolean",
+            _ => return self.reference_type(),
+        };
+        self.text = &self.text[1..];
+        Ok(scalar.to_string())
+    }
+
+    fn reference_type(&mut self) -> Result<String, String> {
+        if self.depth == MAX_TYPE_DEPTH {
+            return Err(format!("types nested too deeply at `{}`", self.text));
+        }
+        self.depth += 1;
+        let ty = self.unnested_reference_type();
+        self.depth -= 1;
+        ty
+    }
+
+    fn unnested_reference_type(&mut self) -> Result<String, String> {
+        if self.eat('[') {
+            Ok(format!("{}[]", self.java_type()?))
+        } else if self.eat('T') {
+            let name = self.identifier(&[';'])?;
+            self.expect(';')?;
+            Ok(name.to_string())
+        } else if self.eat('L') {
+            // Classes nested in generic classes are written `Lpkg/Outer<TT;>.Inner;`
+            let mut ty = String::new();
+            loop {
+                ty.push_str(&self.identifier(&['<', '.', ';'])?.replace('/', "."));
+                if self.eat('<') {
+                    let mut arguments = vec![];
+                    while !self.eat('>') {
+                        arguments.push(self.type_argument()?);
+                    }
+                    write!(ty, "<{}>", arguments.join(", ")).unwrap();
+                }
+                if self.eat(';') {
+                    return Ok(ty);
+                }
+                self.expect('.')?;
+                ty.push('.');
+            }
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn type_argument(&mut self) -> Result<String, String> {
+        if self.eat('*') {
+            Ok("?".to_string())
+        } else if self.eat('+') {
+            Ok(format!("? extends {}", self.reference_type()?))
+        } else if self.eat('-') {
+            Ok(format!("? super {}", self.reference_type()?))
+        } else {
+            self.reference_type()
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display};
 
 use lalrpop_util::{lalrpop_mod, lexer::Token};
 use proc_macro2::Span;
@@ -21,6 +21,65 @@ pub(super) fn parse_class_info(span: Span, input: &str) -> syn::Result<ClassInfo
     }
 }
 
+/// Removes the values of constant fields from `javap -constants` output, like the `= 2147483647`
+/// in `public static final int MAX_VALUE = 2147483647;`, returning the remaining output and the
+/// values by field name.
+pub(super) fn split_constants(input: &str) -> (String, BTreeMap<String, String>) {
+    let mut output = String::with_capacity(input.len());
+    let mut constants = BTreeMap::new();
+    for line in input.lines() {
+        // Only field declarations have an initializer, and their type and name can't contain `=`
+        if let Some((declaration, value)) = line.split_once(" = ") {
+            if let (Some(value), Some(name)) = (
+                value.trim_end().strip_suffix(';'),
+                declaration.split_whitespace().last(),
+            ) {
+                constants.insert(name.to_owned(), value.to_owned());
+                output.push_str(declaration);
+                output.push_str(";\n");
+                continue;
+            }
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    (output, constants)
+}
+
+/// Decodes the escape sequences of the contents of a Java character or string literal (which
+/// javap uses for all non-ASCII characters) into UTF-16 code units.
+pub(crate) fn unescape(literal: &str) -> Option<Vec<u16>> {
+    let mut units = vec![];
+    let mut chars = literal.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 2];
+            units.extend_from_slice(c.encode_utf16(&mut buf));
+            continue;
+        }
+        let unit = match chars.next()? {
+            'b' => 0x08,
+            't' => 0x09,
+            'n' => 0x0a,
+            'f' => 0x0c,
+            'r' => 0x0d,
+            '"' => 0x22,
+            '\'' => 0x27,
+            '\\' => 0x5c,
+            'u' => {
+                let hex: String = chars.by_ref().take(4).collect();
+                if hex.len() != 4 {
+                    return None;
+                }
+                u16::from_str_radix(&hex, 16).ok()?
+            }
+            _ => return None,
+        };
+        units.push(unit);
+    }
+    Some(units)
+}
+
 fn format_lalrpop_error(
     input: &str,
     error: lalrpop_util::ParseError<usize, Token<'_>, impl Display>,
@@ -0,0 +1,272 @@
+//! Finding class files on a classpath of directories and jar files, so that they can be reflected without running
+//! `javap` (see [`crate::class_info::class_file`]).
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::class_info::DotId;
+
+/// The directories and jar files to look for class files in, in order.
+#[derive(Default)]
+pub struct Classpath {
+    entries: Vec<Entry>,
+}
+
+enum Entry {
+    Directory(PathBuf),
+    Jar(Jar),
+}
+
+impl Classpath {
+    /// Adds the jar at `path`, reading its table of contents.
+    pub fn add_jar(&mut self, path: &Path) -> Result<(), String> {
+        let jar =
+            Jar::open(path).map_err(|err| format!("failed to read `{}`: {err}", path.display()))?;
+        self.entries.push(Entry::Jar(jar));
+        Ok(())
+    }
+
+    /// Adds the entries of a classpath like that of the `CLASSPATH` variable. Entries that don't exist are skipped,
+    /// like the JVM does (as are wildcards like `lib/*`, which are left to `javap`).
+    pub fn add_classpath(&mut self, classpath: &str) -> Result<(), String> {
+        for entry in std::env::split_paths(classpath) {
+            if entry.is_dir() {
+                self.entries.push(Entry::Directory(entry));
+            } else if entry.is_file() {
+                self.add_jar(&entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The contents of the class file of `class_name` (a binary name, like `java.util.Map$Entry`), if on the
+    /// classpath.
+    pub fn find_class(&self, class_name: &DotId) -> Result<Option<Vec<u8>>, String> {
+        let path = format!("{}.class", class_name.to_string().replace('.', "/"));
+        for entry in &self.entries {
+            match entry {
+                Entry::Directory(dir) => {
+                    let file = dir.join(&path);
+                    if file.is_file() {
+                        return std::fs::read(&file)
+                            .map(Some)
+                            .map_err(|err| format!("failed to read `{}`: {err}", file.display()));
+                    }
+                }
+                Entry::Jar(jar) => {
+                    if let Some(bytes) = jar.read(&path)? {
+                        return Ok(Some(bytes));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// The binary names of the classes in `package` and its subpackages.
+    pub fn class_names_in(&self, package: &[&str]) -> Result<Vec<String>, String> {
+        let mut names = vec![];
+        for entry in &self.entries {
+            match entry {
+                Entry::Directory(dir) => {
+                    let mut package_dir = dir.clone();
+                    package_dir.extend(package);
+                    if package_dir.is_dir() {
+                        class_names_in_dir(&package_dir, &package.join("."), &mut names).map_err(
+                            |err| format!("failed to list `{}`: {err}", package_dir.display()),
+                        )?;
+                    }
+                }
+                Entry::Jar(jar) => {
+                    let prefix: String = package.iter().map(|id| format!("{id}/")).collect();
+                    names.extend(jar.entries.keys().filter_map(|name| {
+                        let class = name.strip_prefix(&prefix)?.strip_suffix(".class")?;
+                        Some(format!("{prefix}{class}").replace('/', "."))
+                    }));
+                }
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// Adds the binary names of the classes in `dir` (whose package is `package`) and its subdirectories to `names`.
+fn class_names_in_dir(dir: &Path, package: &str, names: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        let qualified = |name: &str| {
+            if package.is_empty() {
+                name.to_string()
+            } else {
+                format!("{package}.{name}")
+            }
+        };
+        if entry.file_type()?.is_dir() {
+            class_names_in_dir(&entry.path(), &qualified(&file_name), names)?;
+        } else if let Some(class) = file_name.strip_suffix(".class") {
+            names.push(qualified(class));
+        }
+    }
+    Ok(())
+}
+
+/// The binary names of the classes in the JDK's runtime image, which aren't stored as class files, as listed by
+/// `jimage`.
+pub fn runtime_image_class_names() -> Result<Vec<String>, String> {
+    let java_home = java_home().ok_or(
+        "can't find the JDK to list its classes (set `JAVA_HOME` or put `javap` on the `PATH`)",
+    )?;
+    let mut command = Command::new(java_home.join("bin").join("jimage"));
+    command
+        .arg("list")
+        .arg(java_home.join("lib").join("modules"));
+    let output = command
+        .output()
+        .map_err(|err| format!("failed to execute `{command:?}`: {err}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "unsuccessful execution of `{command:?}`: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    // The output lists the resources of each module, indented under a `Module: name` line
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("    ")?.strip_suffix(".class"))
+        .map(|path| path.replace('/', "."))
+        .collect())
+}
+
+/// The directory of the JDK: `JAVA_HOME`, or else the one that `javap` on the `PATH` belongs to.
+fn java_home() -> Option<PathBuf> {
+    if let Some(java_home) = std::env::var_os("JAVA_HOME") {
+        return Some(java_home.into());
+    }
+    let javap = std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join("javap"))
+        .find(|javap| javap.is_file())?;
+    // `javap` is often a symbolic link to the JDK's `bin` directory
+    let javap = javap.canonicalize().ok()?;
+    Some(javap.parent()?.parent()?.to_owned())
+}
+
+/// A jar (that is, zip) file whose central directory has been read.
+struct Jar {
+    path: PathBuf,
+    data: Vec<u8>,
+    /// The entries by file name.
+    entries: BTreeMap<String, ZipEntry>,
+}
+
+struct ZipEntry {
+    /// 0 if stored, 8 if deflated.
+    method: u16,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    local_header_offset: usize,
+}
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x02014b50;
+const LOCAL_FILE_HEADER: u32 = 0x04034b50;
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+impl Jar {
+    fn open(path: &Path) -> Result<Self, String> {
+        let data = std::fs::read(path).map_err(|err| err.to_string())?;
+        let entries = Self::read_central_directory(&data).ok_or("malformed zip file")?;
+        Ok(Jar {
+            path: path.to_owned(),
+            data,
+            entries,
+        })
+    }
+
+    fn read_central_directory(data: &[u8]) -> Option<BTreeMap<String, ZipEntry>> {
+        // The end of central directory record is the last thing in the file, followed only by a comment of up to 64k
+        let search_from = data.len().saturating_sub(22 + 0xFFFF);
+        let end = (search_from..=data.len().checked_sub(22)?)
+            .rev()
+            .find(|&offset| u32_at(data, offset) == Some(END_OF_CENTRAL_DIRECTORY))?;
+        let count = u16_at(data, end + 10)?;
+        let mut offset = u32_at(data, end + 16)? as usize;
+
+        let mut entries = BTreeMap::new();
+        for _ in 0..count {
+            if u32_at(data, offset)? != CENTRAL_DIRECTORY_HEADER {
+                return None;
+            }
+            let name_len = u16_at(data, offset + 28)? as usize;
+            let extra_len = u16_at(data, offset + 30)? as usize;
+            let comment_len = u16_at(data, offset + 32)? as usize;
+            let name = data.get(offset + 46..offset + 46 + name_len)?;
+            let entry = ZipEntry {
+                method: u16_at(data, offset + 10)?,
+                compressed_size: u32_at(data, offset + 20)? as usize,
+                uncompressed_size: u32_at(data, offset + 24)? as usize,
+                local_header_offset: u32_at(data, offset + 42)? as usize,
+            };
+            entries.insert(String::from_utf8_lossy(name).into_owned(), entry);
+            offset += 46 + name_len + extra_len + comment_len;
+        }
+        Some(entries)
+    }
+
+    /// The contents of the file `name` in the jar, if it has one.
+    fn read(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+        let malformed = || format!("malformed entry `{name}` in `{}`", self.path.display());
+
+        // The local header repeats the name, and may have different extra fields than the central directory
+        let header = entry.local_header_offset;
+        if u32_at(&self.data, header) != Some(LOCAL_FILE_HEADER) {
+            return Err(malformed());
+        }
+        let name_len = u16_at(&self.data, header + 26).ok_or_else(malformed)? as usize;
+        let extra_len = u16_at(&self.data, header + 28).ok_or_else(malformed)? as usize;
+        let start = header + 30 + name_len + extra_len;
+        let compressed = self
+            .data
+            .get(start..start + entry.compressed_size)
+            .ok_or_else(malformed)?;
+
+        let bytes = match entry.method {
+            0 => compressed.to_vec(),
+            // The size limit keeps malformed entries from inflating without bound
+            8 => miniz_oxide::inflate::decompress_to_vec_with_limit(
+                compressed,
+                entry.uncompressed_size,
+            )
+            .map_err(|_| malformed())?,
+            method => {
+                return Err(format!(
+                    "entry `{name}` in `{}` is compressed with unsupported method {method}",
+                    self.path.display()
+                ))
+            }
+        };
+        if bytes.len() != entry.uncompressed_size {
+            return Err(malformed());
+        }
+        Ok(Some(bytes))
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
     argument::DuchessDeclaration,
     class_info::{
-        ClassInfo, Constructor, DotId, Field, Id, Method, NonRepeatingType, RootMap,
-        SpannedPackageInfo, Type,
+        ClassInfo, ConstantValue, Constructor, DotId, Field, Generic, Id, Method, NonRepeatingType,
+        RefType, RootMap, SpannedPackageInfo, Type,
     },
     reflect::Reflector,
     signature::Signature,
@@ -15,6 +15,9 @@ use quote::quote_spanned;
 impl DuchessDeclaration {
     pub fn to_tokens(&self) -> syn::Result<TokenStream> {
         let reflector = &mut Reflector::default();
+        for jar in &self.jars {
+            reflector.add_jar(&jar.path, jar.span)?;
+        }
         let root_map = self.to_root_map(reflector)?;
         let () = root_map.check(reflector)?;
         root_map.to_tokens(reflector)
@@ -76,6 +79,7 @@ impl ClassInfo {
     pub fn to_tokens(&self, upcasts: &Upcasts) -> syn::Result<TokenStream> {
         let struct_name = self.struct_name();
         let cached_class = self.cached_class();
+        let cached_array_class = self.cached_array_class();
         let this_ty = self.this_type();
         let java_class_generics_with_defaults = self.class_generic_names_with_defaults();
         let java_class_generics = self.class_generic_names();
@@ -114,6 +118,29 @@ impl ClassInfo {
             .map(|m| self.obj_struct_method(m))
             .collect::<Result<_, _>>()?;
 
+        // Generate getters and setters for instance fields (final fields can only be read), which
+        // are available in the same forms as instance methods
+        let mut field_accessors = vec![];
+        for field in self
+            .fields
+            .iter()
+            .filter(|f: &&Field| self.should_mirror_in_rust(f.flags.privacy))
+            .filter(|f| !f.flags.is_static)
+        {
+            field_accessors.push(self.instance_field_accessor(field, false)?);
+            if !field.flags.is_final {
+                field_accessors.push(self.instance_field_accessor(field, true)?);
+            }
+        }
+        let mut op_methods = op_methods;
+        let mut obj_methods = obj_methods;
+        let mut inherent_field_accessors = vec![];
+        for (op_method, obj_method, inherent_accessor) in field_accessors {
+            op_methods.push(op_method);
+            obj_methods.push(obj_method);
+            inherent_field_accessors.push(inherent_accessor);
+        }
+
         let assoc_struct_declarations = self.assoc_structs(upcasts, op_methods, obj_methods)?;
 
         // Convert instance methods of the form `Foo::method`
@@ -134,8 +161,28 @@ impl ClassInfo {
             .map(|f| self.static_field_getter(f))
             .collect::<Result<_, _>>()?;
 
+        // Generate consts for constant fields, in addition to their getters
+        let static_field_constants: Vec<_> = self
+            .fields
+            .iter()
+            .filter(|f: &&Field| self.should_mirror_in_rust(f.flags.privacy))
+            .filter_map(|f| self.static_field_constant(f))
+            .collect();
+
+        // Generate static field setters (final fields can only be read)
+        let static_field_setters: Vec<_> = self
+            .fields
+            .iter()
+            .filter(|f: &&Field| self.should_mirror_in_rust(f.flags.privacy))
+            .filter(|f| f.flags.is_static && !f.flags.is_final)
+            .map(|f| self.static_field_setter(f))
+            .collect::<Result<_, _>>()?;
+
         let upcast_impls = self.upcast_impls(upcasts)?;
 
+        let (enum_declaration, enum_impls) = self.java_enum().unzip();
+        let (record_declaration, record_impls) = self.java_record()?.unzip();
+
         let output = quote_spanned! {
             self.span =>
 
@@ -144,6 +191,10 @@ impl ClassInfo {
                 _dummy: ::core::marker::PhantomData<(#(#java_class_generics,)*)>
             }
 
+            #enum_declaration
+
+            #record_declaration
+
             // Hide other generated items
             #[allow(unused_imports)]
             #[allow(nonstandard_style)]
@@ -157,6 +208,13 @@ impl ClassInfo {
                     #cached_class
                 }
 
+                unsafe impl<#(#java_class_generics,)*> duchess::JavaType for #struct_name<#(#java_class_generics,)*>
+                where
+                    #(#java_class_generics: duchess::JavaObject,)*
+                {
+                    #cached_array_class
+                }
+
                 impl<#(#java_class_generics,)*> ::core::convert::AsRef<#struct_name<#(#java_class_generics,)*>> for #struct_name<#(#java_class_generics,)*>
                 where
                     #(#java_class_generics: duchess::JavaObject,)*
@@ -206,6 +264,10 @@ impl ClassInfo {
                 // Other upcast impls
                 #upcast_impls
 
+                #enum_impls
+
+                #record_impls
+
                 impl< #(#java_class_generics,)* > #this_ty
                 where
                     #(#java_class_generics: duchess::JavaObject,)*
@@ -216,7 +278,13 @@ impl ClassInfo {
 
                     #(#static_field_getters)*
 
+                    #(#static_field_constants)*
+
+                    #(#static_field_setters)*
+
                     #(#inherent_object_methods)*
+
+                    #(#inherent_field_accessors)*
                 }
             };
         };
@@ -406,16 +474,35 @@ impl ClassInfo {
 
     fn cached_class(&self) -> TokenStream {
         let jni_class_name = self.jni_class_name();
+        let java_name = Literal::string(&self.name.to_string());
 
         quote_spanned! {
             self.span =>
             fn class<'jvm>(jvm: &mut duchess::Jvm<'jvm>) -> duchess::Result<'jvm, duchess::Local<'jvm, java::lang::Class>> {
-                static CLASS: duchess::plumbing::once_cell::sync::OnceCell<duchess::Global<java::lang::Class>> = duchess::plumbing::once_cell::sync::OnceCell::new();
-                let global = CLASS.get_or_try_init::<_, duchess::Error<duchess::Local<java::lang::Throwable>>>(|| {
+                static CLASS: duchess::plumbing::CachedMember<()> = duchess::plumbing::CachedMember::new();
+                let (class, ()) = CLASS.get_or_try_init(jvm, |jvm| {
                     let class = duchess::plumbing::find_class(jvm, #jni_class_name)?;
-                    Ok(jvm.global(&class))
+                    duchess::classes::register::<Self>(#java_name);
+                    Ok((class, ()))
+                })?;
+                Ok(jvm.local(class))
+            }
+        }
+    }
+
+    /// Like [`Self::cached_class`], for the class of arrays of this type.
+    fn cached_array_class(&self) -> TokenStream {
+        let jni_array_class_name = jni_c_str(format!("[L{};", self.name.to_jni_name()), self.span);
+
+        quote_spanned! {
+            self.span =>
+            fn array_class<'jvm>(jvm: &mut duchess::Jvm<'jvm>) -> duchess::Result<'jvm, duchess::Local<'jvm, java::lang::Class>> {
+                static CLASS: duchess::plumbing::CachedMember<()> = duchess::plumbing::CachedMember::new();
+                let (class, ()) = CLASS.get_or_try_init(jvm, |jvm| {
+                    let class = duchess::plumbing::find_class(jvm, #jni_array_class_name)?;
+                    Ok((class, ()))
                 })?;
-                Ok(jvm.local(global))
+                Ok(jvm.local(class))
             }
         }
     }
@@ -438,14 +525,14 @@ impl ClassInfo {
 
         let java_class_generics = self.class_generic_names();
 
-        let jni_descriptor = jni_c_str(constructor.descriptor(), self.span);
+        let jni_descriptor = jni_c_str(constructor.descriptor(&self.generics), self.span);
 
         // Code to convert each input appropriately
         let prepare_inputs = self.prepare_inputs(&input_names, &constructor.argument_tys);
 
         // for debugging JVM invocation failures
         let name = Literal::string(&self.name.to_string());
-        let descriptor = Literal::string(&constructor.descriptor());
+        let descriptor = Literal::string(&constructor.descriptor(&self.generics));
 
         let output = quote_spanned!(self.span =>
             pub fn new(
@@ -509,26 +596,22 @@ impl ClassInfo {
                     ) -> duchess::Result<'jvm, Self::Output<'jvm>> {
                         #(#prepare_inputs)*
 
-                        let class = <#ty as duchess::JavaObject>::class(jvm)?;
-
-                        // Cache the method id for the constructor -- note that we only have one cache
+                        // Cache the class and method id for the constructor -- note that we only have one cache
                         // no matter how many generic monomorphizations there are. This makes sense
                         // given Java's erased-based generics system.
-                        static CONSTRUCTOR: duchess::plumbing::once_cell::sync::OnceCell<duchess::plumbing::MethodPtr> = duchess::plumbing::once_cell::sync::OnceCell::new();
-                        let constructor = CONSTRUCTOR.get_or_try_init(|| {
-                            duchess::plumbing::find_constructor(jvm, &class, #jni_descriptor)
+                        static CONSTRUCTOR: duchess::plumbing::CachedMember<duchess::plumbing::MethodPtr> = duchess::plumbing::CachedMember::new();
+                        let (class, constructor) = CONSTRUCTOR.get_or_try_init(jvm, |jvm| {
+                            let class = <#ty as duchess::JavaObject>::class(jvm)?;
+                            let constructor = duchess::plumbing::find_constructor(jvm, &class, #jni_descriptor)?;
+                            Ok((class, constructor))
                         })?;
 
-                        let env = jvm.env();
+                        // A constructed object can't be null, so (unlike method calls) the result is a `Local`
+                        // rather than an `Option`, and no exception check is needed once the object exists
                         let obj: ::core::option::Option<duchess::Local<#ty>> = unsafe {
-                            env.invoke(|env| env.NewObjectA, |env, f| f(
-                                env,
-                                duchess::plumbing::JavaObjectExt::as_raw(&*class).as_ptr(),
-                                constructor.as_ptr(),
-                                [
-                                    #(duchess::plumbing::IntoJniValue::into_jni_value(#input_names),)*
-                                ].as_ptr(),
-                            ))
+                            duchess::plumbing::call::new_object(jvm, duchess::plumbing::JavaObjectExt::as_raw(class), constructor, &[
+                                #(duchess::plumbing::IntoJniValue::into_jni_value(#input_names),)*
+                            ])
                         }?;
                         obj.ok_or_else(|| {
                             // NewObjectA should only return a null pointer when an exception occurred in the
@@ -705,8 +788,8 @@ impl ClassInfo {
         // would be `JavaMethod<Foo>`.
         let output_trait = sig.method_trait(&method.return_ty)?;
 
-        // The appropriate JNI function to call this method.
-        let jni_call_fn = sig.jni_call_fn(&method.return_ty)?;
+        // The shared function that calls this method.
+        let call_shim = sig.call_shim(&method.return_ty)?;
 
         // If this method returns a java object, then this is the
         // Rust type representing the java class/interface that is returned
@@ -718,7 +801,10 @@ impl ClassInfo {
             None => None,
         };
 
-        let jni_descriptor = jni_c_str(&method.descriptor(), self.span);
+        // The local reference to the returned object, if any, counted in `JvmOp::LOCALS`
+        let output_locals = usize::from(java_ref_output_ty.is_some());
+
+        let jni_descriptor = jni_c_str(&method.descriptor(&self.generics), self.span);
 
         // Code to convert each input appropriately
         let prepare_inputs = self.prepare_inputs(&input_names, &method.argument_tys);
@@ -806,6 +892,12 @@ impl ClassInfo {
             {
                 type Output<'jvm> = #output_ty;
 
+                const LOCALS: usize = duchess::plumbing::sum_locals(&[
+                    <#this as duchess::prelude::IntoJava<#this_ty>>::LOCALS,
+                    #(<#input_names as #input_traits>::LOCALS,)*
+                    #output_locals,
+                ]);
+
                 fn execute_with<'jvm>(
                     self,
                     jvm: &mut duchess::Jvm<'jvm>,
@@ -819,21 +911,17 @@ impl ClassInfo {
                     // Cache the method id for this method -- note that we only have one cache
                     // no matter how many generic monomorphizations there are. This makes sense
                     // given Java's erased-based generics system.
-                    static METHOD: duchess::plumbing::once_cell::sync::OnceCell<duchess::plumbing::MethodPtr> = duchess::plumbing::once_cell::sync::OnceCell::new();
-                    let method = METHOD.get_or_try_init(|| {
+                    static METHOD: duchess::plumbing::CachedMember<duchess::plumbing::MethodPtr> = duchess::plumbing::CachedMember::new();
+                    let (_, method) = METHOD.get_or_try_init(jvm, |jvm| {
                         let class = <#this_ty as duchess::JavaObject>::class(jvm)?;
-                        duchess::plumbing::find_method(jvm, &class, #jni_method, #jni_descriptor, false)
+                        let method = duchess::plumbing::find_method(jvm, &class, #jni_method, #jni_descriptor, false)?;
+                        Ok((class, method))
                     })?;
 
                     unsafe {
-                        jvm.env().invoke(|env| env.#jni_call_fn, |env, f| f(
-                            env,
-                            this.as_ptr(),
-                            method.as_ptr(),
-                            [
-                                #(duchess::plumbing::IntoJniValue::into_jni_value(#input_names),)*
-                            ].as_ptr(),
-                        ))
+                        duchess::plumbing::call::#call_shim(jvm, this, method, &[
+                            #(duchess::plumbing::IntoJniValue::into_jni_value(#input_names),)*
+                        ])
                     }
                 }
             }
@@ -907,7 +995,7 @@ impl ClassInfo {
 
         let output_ty = sig.output_type(&method.return_ty)?;
         let output_trait = sig.method_trait(&method.return_ty)?;
-        let jni_call_fn = sig.jni_static_call_fn(&method.return_ty)?;
+        let call_shim = sig.static_call_shim(&method.return_ty)?;
 
         // If this method returns a java object, then this is the
         // Rust type representing the java class/interface that is returned
@@ -919,7 +1007,10 @@ impl ClassInfo {
             None => None,
         };
 
-        let jni_descriptor = jni_c_str(&method.descriptor(), self.span);
+        // The local reference to the returned object, if any, counted in `JvmOp::LOCALS`
+        let output_locals = usize::from(java_ref_output_ty.is_some());
+
+        let jni_descriptor = jni_c_str(&method.descriptor(&self.generics), self.span);
 
         // Code to convert each input appropriately
         let prepare_inputs = self.prepare_inputs(&input_names, &method.argument_tys);
@@ -996,31 +1087,31 @@ impl ClassInfo {
             {
                 type Output<'jvm> = #output_ty;
 
+                const LOCALS: usize = duchess::plumbing::sum_locals(&[
+                    #(<#input_names as #input_traits>::LOCALS,)*
+                    #output_locals,
+                ]);
+
                 fn execute_with<'jvm>(
                     self,
                     jvm: &mut duchess::Jvm<'jvm>,
                 ) -> duchess::Result<'jvm, Self::Output<'jvm>> {
                     #(#prepare_inputs)*
 
-                    // Cache the method id for this method -- note that we only have one cache
+                    // Cache the class and method id for this method -- note that we only have one cache
                     // no matter how many generic monomorphizations there are. This makes sense
                     // given Java's erased-based generics system.
-                    static METHOD: duchess::plumbing::once_cell::sync::OnceCell<duchess::plumbing::MethodPtr> = duchess::plumbing::once_cell::sync::OnceCell::new();
-                    let method = METHOD.get_or_try_init(|| {
+                    static METHOD: duchess::plumbing::CachedMember<duchess::plumbing::MethodPtr> = duchess::plumbing::CachedMember::new();
+                    let (class, method) = METHOD.get_or_try_init(jvm, |jvm| {
                         let class = <#this_ty as duchess::JavaObject>::class(jvm)?;
-                        duchess::plumbing::find_method(jvm, &class, #jni_method, #jni_descriptor, true)
+                        let method = duchess::plumbing::find_method(jvm, &class, #jni_method, #jni_descriptor, true)?;
+                        Ok((class, method))
                     })?;
 
-                    let class = <#this_ty as duchess::JavaObject>::class(jvm)?;
                     unsafe {
-                        jvm.env().invoke(|env| env.#jni_call_fn, |env, f| f(
-                            env,
-                            duchess::plumbing::JavaObjectExt::as_raw(&*class).as_ptr(),
-                            method.as_ptr(),
-                            [
-                                #(duchess::plumbing::IntoJniValue::into_jni_value(#input_names),)*
-                            ].as_ptr(),
-                        ))
+                        duchess::plumbing::call::#call_shim(jvm, duchess::plumbing::JavaObjectExt::as_raw(class), method, &[
+                            #(duchess::plumbing::IntoJniValue::into_jni_value(#input_names),)*
+                        ])
                     }
                 }
             }
@@ -1084,7 +1175,10 @@ impl ClassInfo {
         let jni_field_fn = sig.jni_static_field_get_fn(&field.ty)?;
 
         let jni_field = jni_c_str(&*field.name, self.span);
-        let jni_descriptor = jni_c_str(&field.ty.descriptor(), self.span);
+        let jni_descriptor = jni_c_str(
+            &field.ty.descriptor(&Generic::in_scope(&[], &self.generics)),
+            self.span,
+        );
 
         let rust_field_name =
             Id::from(format!("get_{}", field.name.to_snake_case())).to_ident(self.span);
@@ -1129,20 +1223,20 @@ impl ClassInfo {
                     jvm: &mut duchess::Jvm<'jvm>,
                 ) -> duchess::Result<'jvm, Self::Output<'jvm>> {
 
-                    // Cache the field id for this field -- note that we only have one cache
+                    // Cache the class and field id for this field -- note that we only have one cache
                     // no matter how many generic monomorphizations there are. This makes sense
                     // given Java's erased-based generics system.
-                    static FIELD: duchess::plumbing::once_cell::sync::OnceCell<duchess::plumbing::FieldPtr> = duchess::plumbing::once_cell::sync::OnceCell::new();
-                    let field = FIELD.get_or_try_init(|| {
+                    static FIELD: duchess::plumbing::CachedMember<duchess::plumbing::FieldPtr> = duchess::plumbing::CachedMember::new();
+                    let (class, field) = FIELD.get_or_try_init(jvm, |jvm| {
                         let class = <#this_ty as duchess::JavaObject>::class(jvm)?;
-                        duchess::plumbing::find_field(jvm, &class, #jni_field, #jni_descriptor, true)
+                        let field = duchess::plumbing::find_field(jvm, &class, #jni_field, #jni_descriptor, true)?;
+                        Ok((class, field))
                     })?;
 
-                    let class = <#this_ty as duchess::JavaObject>::class(jvm)?;
                     unsafe {
                         jvm.env().invoke(|env| env.#jni_field_fn, |env, f| f(
                             env,
-                            duchess::plumbing::JavaObjectExt::as_raw(&*class).as_ptr(),
+                            duchess::plumbing::JavaObjectExt::as_raw(class).as_ptr(),
                             field.as_ptr(),
                         ))
                     }
@@ -1188,6 +1282,577 @@ impl ClassInfo {
         Ok(inherent_method)
     }
 
+    /// For Java enums, a Rust enum `<Name>Enum` with a variant for each constant, and the
+    /// conversions between them (by the ordinals of the constants, and their static fields).
+    fn java_enum(&self) -> Option<(TokenStream, TokenStream)> {
+        let constants = self.enum_constants()?;
+        let span = self.span;
+        let struct_name = self.struct_name();
+        let enum_name = Ident::new(&format!("{struct_name}Enum"), span);
+
+        let variants: Vec<Ident> = constants
+            .iter()
+            .map(|f| Ident::new(&f.name.replace('$', "__").to_pascal_case(), span))
+            .collect();
+        let getters: Vec<Ident> = constants
+            .iter()
+            .map(|f| Id::from(format!("get_{}", f.name.to_snake_case())).to_ident(span))
+            .collect();
+        let num_variants = variants.len();
+
+        let doc = format!(
+            "The constants of the Java enum `{}`, converted from and to [`{struct_name}`] with \
+             `to_rust()` and `to_java()`.",
+            self.name
+        );
+        let declaration = quote_spanned!(span =>
+            #[doc = #doc]
+            #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            pub enum #enum_name {
+                #(#variants,)*
+            }
+        );
+
+        let ordinal_method = jni_c_str("ordinal", span);
+        let ordinal_descriptor = jni_c_str("()I", span);
+        let java_name = self.name.to_string();
+        let impls = quote_spanned!(span =>
+            impl duchess::IntoRust<#enum_name> for &#struct_name {
+                fn into_rust<'jvm>(self, jvm: &mut duchess::Jvm<'jvm>) -> duchess::Result<'jvm, #enum_name> {
+                    const VARIANTS: [#enum_name; #num_variants] = [#(#enum_name::#variants,)*];
+
+                    fn ordinal_of<'jvm>(
+                        jvm: &mut duchess::Jvm<'jvm>,
+                        value: &#struct_name,
+                    ) -> duchess::Result<'jvm, i32> {
+                        static METHOD: duchess::plumbing::once_cell::sync::OnceCell<duchess::plumbing::MethodPtr> = duchess::plumbing::once_cell::sync::OnceCell::new();
+                        let method = METHOD.get_or_try_init(|| {
+                            let class = <duchess::java::lang::Enum as duchess::JavaObject>::class(jvm)?;
+                            duchess::plumbing::find_method(jvm, &class, #ordinal_method, #ordinal_descriptor, false)
+                        })?;
+
+                        let value = duchess::plumbing::JavaObjectExt::as_raw(value);
+                        unsafe { duchess::plumbing::call::int_method(jvm, value, *method, &[]) }
+                    }
+
+                    // The ordinals of the variants' constants, which are looked up once since they can't change
+                    static ORDINALS: duchess::plumbing::once_cell::sync::OnceCell<[i32; #num_variants]> = duchess::plumbing::once_cell::sync::OnceCell::new();
+                    let ordinals = ORDINALS.get_or_try_init(|| -> duchess::Result<'jvm, _> {
+                        let mut ordinals = [0; #num_variants];
+                        for (slot, variant) in ordinals.iter_mut().zip(VARIANTS) {
+                            let constant = duchess::plumbing::ToJavaImpl::<#struct_name>::to_java_impl(&variant, jvm)?;
+                            let constant = constant.ok_or(duchess::Error::NullDeref)?;
+                            *slot = ordinal_of(jvm, &constant)?;
+                        }
+                        Ok(ordinals)
+                    })?;
+
+                    let ordinal = ordinal_of(jvm, self)?;
+                    match ordinals.iter().position(|&o| o == ordinal) {
+                        Some(index) => Ok(VARIANTS[index]),
+                        None => Err(duchess::Error::JvmInternal(format!(
+                            "constant of `{}` with ordinal {} isn't declared",
+                            #java_name,
+                            ordinal,
+                        ))),
+                    }
+                }
+            }
+
+            impl duchess::plumbing::ToJavaImpl<#struct_name> for #enum_name {
+                fn to_java_impl<'jvm>(
+                    rust: &Self,
+                    jvm: &mut duchess::Jvm<'jvm>,
+                ) -> duchess::Result<'jvm, Option<duchess::Local<'jvm, #struct_name>>> {
+                    match rust {
+                        #(#enum_name::#variants => duchess::prelude::JvmOp::execute_with(#struct_name::#getters(), jvm),)*
+                    }
+                }
+            }
+        );
+
+        Some((declaration, impls))
+    }
+
+    fn java_record(&self) -> syn::Result<Option<(TokenStream, TokenStream)>> {
+        let Some((constructor, accessors)) = self.record_components() else {
+            return Ok(None);
+        };
+        let span = self.span;
+        let struct_name = self.struct_name();
+        let record_name = Ident::new(&format!("{struct_name}Record"), span);
+        let mut sig = Signature::new(self.name.class_name(), span, &self.generics);
+
+        let field_names: Vec<Ident> = accessors
+            .iter()
+            .map(|m| m.name.to_snake_case().to_ident(span))
+            .collect();
+
+        // Scalars and strings are converted, other objects are kept as global references.
+        let mut only_values = true;
+        let mut field_tys = vec![];
+        let mut field_initializers = vec![];
+        let mut constructor_args = vec![];
+        for (ty, name) in constructor.argument_tys.iter().zip(&field_names) {
+            match ty {
+                Type::Scalar(scalar) => {
+                    field_tys.push(scalar.to_tokens(span));
+                    field_initializers
+                        .push(quote_spanned!(span => self.#name().execute_with(jvm)?));
+                    constructor_args.push(quote_spanned!(span => rust.#name));
+                }
+                Type::Ref(RefType::Class(c)) if c.name == DotId::parse("java.lang.String") => {
+                    field_tys.push(quote_spanned!(span => String));
+                    field_initializers.push(quote_spanned!(span =>
+                        self.#name().assert_not_null().to_rust().execute_with(jvm)?
+                    ));
+                    constructor_args.push(quote_spanned!(span => &rust.#name));
+                }
+                _ => {
+                    only_values = false;
+                    let java_ty = sig.forbid_capture(|sig| sig.java_ty(ty))?;
+                    field_tys.push(quote_spanned!(span => duchess::Global<#java_ty>));
+                    field_initializers.push(quote_spanned!(span =>
+                        self.#name().assert_not_null().global().execute_with(jvm)?
+                    ));
+                    constructor_args.push(quote_spanned!(span => &rust.#name));
+                }
+            }
+        }
+
+        let derives =
+            only_values.then(|| quote_spanned!(span => #[derive(Clone, Debug, PartialEq)]));
+        let doc = format!(
+            "The components of the Java record `{}`, converted from and to [`{struct_name}`] with \
+             `to_rust()` and `to_java()`.",
+            self.name
+        );
+        let declaration = quote_spanned!(span =>
+            #[doc = #doc]
+            #derives
+            pub struct #record_name {
+                #(pub #field_names: #field_tys,)*
+            }
+        );
+
+        let impls = quote_spanned!(span =>
+            impl duchess::IntoRust<#record_name> for &#struct_name {
+                fn into_rust<'jvm>(self, jvm: &mut duchess::Jvm<'jvm>) -> duchess::Result<'jvm, #record_name> {
+                    use duchess::prelude::*;
+                    Ok(#record_name {
+                        #(#field_names: #field_initializers,)*
+                    })
+                }
+            }
+
+            impl duchess::plumbing::ToJavaImpl<#struct_name> for #record_name {
+                fn to_java_impl<'jvm>(
+                    rust: &Self,
+                    jvm: &mut duchess::Jvm<'jvm>,
+                ) -> duchess::Result<'jvm, Option<duchess::Local<'jvm, #struct_name>>> {
+                    use duchess::prelude::*;
+                    let record = #struct_name::new(#(#constructor_args,)*).execute_with(jvm)?;
+                    Ok(Some(record))
+                }
+            }
+        );
+
+        Ok(Some((declaration, impls)))
+    }
+
+    /// A `const` with the value of a constant field (resolved from the class file), if it is one.
+    fn static_field_constant(&self, field: &Field) -> Option<TokenStream> {
+        let value = field.constant_value()?;
+        let span = self.span;
+        let name = field.name.to_ident(span);
+
+        // NaN and infinities have no literals, so they are named by their associated consts
+        macro_rules! float {
+            ($v:expr, $ty:ident) => {{
+                let v = $v;
+                let ty = quote_spanned!(span => $ty);
+                let value = if v.is_nan() {
+                    quote_spanned!(span => #ty::NAN)
+                } else if v.is_infinite() && v > 0.0 {
+                    quote_spanned!(span => #ty::INFINITY)
+                } else if v.is_infinite() {
+                    quote_spanned!(span => #ty::NEG_INFINITY)
+                } else {
+                    quote_spanned!(span => #v)
+                };
+                (ty, value)
+            }};
+        }
+
+        let (ty, value) = match value {
+            ConstantValue::Boolean(v) => (quote_spanned!(span => bool), quote_spanned!(span => #v)),
+            ConstantValue::Byte(v) => (quote_spanned!(span => i8), quote_spanned!(span => #v)),
+            ConstantValue::Char(v) => (quote_spanned!(span => u16), quote_spanned!(span => #v)),
+            ConstantValue::Short(v) => (quote_spanned!(span => i16), quote_spanned!(span => #v)),
+            ConstantValue::Int(v) => (quote_spanned!(span => i32), quote_spanned!(span => #v)),
+            ConstantValue::Long(v) => (quote_spanned!(span => i64), quote_spanned!(span => #v)),
+            ConstantValue::Float(v) => float!(v, f32),
+            ConstantValue::Double(v) => float!(v, f64),
+            ConstantValue::String(v) => (
+                quote_spanned!(span => &'static str),
+                quote_spanned!(span => #v),
+            ),
+        };
+
+        Some(quote_spanned!(span =>
+            pub const #name: #ty = #value;
+        ))
+    }
+
+    /// Generates a static field setter that should be part of the inherent methods
+    /// for the struct.
+    ///
+    /// NB. This function (particularly the JvmOp impl) has significant overlap with
+    /// `static_field_getter` and `static_method`, so if you make changes here, you may well
+    /// need changes there.
+    fn static_field_setter(&self, field: &Field) -> syn::Result<TokenStream> {
+        assert!(field.flags.is_static);
+
+        let mut sig = Signature::new(&field.name, self.span, &self.generics);
+
+        let input_trait = sig.input_trait(&field.ty)?;
+        let output_trait = sig.method_trait(&None)?;
+        let (jni_field_fn, jni_value_field) = sig.jni_static_field_set_fn(&field.ty)?;
+
+        let jni_field = jni_c_str(&*field.name, self.span);
+        let jni_descriptor = jni_c_str(
+            &field.ty.descriptor(&Generic::in_scope(&[], &self.generics)),
+            self.span,
+        );
+
+        let input_name = Ident::new("a0", self.span);
+        let prepare_input = self.prepare_inputs(&[input_name.clone()], &[field.ty.clone()]);
+
+        let rust_field_name =
+            Id::from(format!("set_{}", field.name.to_snake_case())).to_ident(self.span);
+        let rust_field_type_name =
+            Id::from(format!("{}Setter", field.name.to_camel_case())).to_ident(self.span);
+
+        // The generic parameters declared on the Java method.
+        let java_class_generics: Vec<_> = self.class_generic_names();
+
+        // The generic parameters we need on the *setter struct* (which will implement the `JvmOp`):
+        // the class generics plus a type parameter `a0` for the new value.
+        let field_struct_generics: Vec<_> = java_class_generics
+            .iter()
+            .chain(Some(&input_name))
+            .collect();
+
+        // For each field `f` in the Java type, we create a struct (named `<f>Setter`)
+        // that will implement the `JvmOp`.
+        let field_struct = quote_spanned!(self.span =>
+            pub struct #rust_field_type_name<
+                #(#field_struct_generics,)*
+            > {
+                #input_name: #input_name,
+                phantom: ::core::marker::PhantomData<(
+                    #(#field_struct_generics,)*
+                )>,
+            }
+        );
+
+        let sig_where_clauses = &sig.where_clauses;
+
+        // Implementation of `JvmOp` for `f` -- when executed, set the field
+        // via JNI, after converting the new value appropriately.
+        let this_ty = self.this_type();
+        let jvmop_impl = quote_spanned!(self.span =>
+            impl<#(#field_struct_generics),*> duchess::prelude::JvmOp
+            for #rust_field_type_name<#(#field_struct_generics),*>
+            where
+                #input_name: #input_trait,
+                #(#java_class_generics: duchess::JavaObject,)*
+                #(#sig_where_clauses,)*
+            {
+                type Output<'jvm> = ();
+
+                fn execute_with<'jvm>(
+                    self,
+                    jvm: &mut duchess::Jvm<'jvm>,
+                ) -> duchess::Result<'jvm, Self::Output<'jvm>> {
+                    #(#prepare_input)*
+
+                    // Cache the class and field id for this field, see `static_field_getter`.
+                    static FIELD: duchess::plumbing::CachedMember<duchess::plumbing::FieldPtr> = duchess::plumbing::CachedMember::new();
+                    let (class, field) = FIELD.get_or_try_init(jvm, |jvm| {
+                        let class = <#this_ty as duchess::JavaObject>::class(jvm)?;
+                        let field = duchess::plumbing::find_field(jvm, &class, #jni_field, #jni_descriptor, true)?;
+                        Ok((class, field))
+                    })?;
+
+                    unsafe {
+                        let value = duchess::plumbing::IntoJniValue::into_jni_value(#input_name).#jni_value_field;
+                        jvm.env().invoke(|env| env.#jni_field_fn, |env, f| f(
+                            env,
+                            duchess::plumbing::JavaObjectExt::as_raw(class).as_ptr(),
+                            field.as_ptr(),
+                            value,
+                        ))
+                    }
+                }
+            }
+
+            impl<#(#field_struct_generics),*> ::core::marker::Copy for #rust_field_type_name<#(#field_struct_generics),*>
+            where
+                #input_name: #input_trait,
+                #(#java_class_generics: duchess::JavaObject,)*
+                #(#sig_where_clauses,)*
+            {
+            }
+
+            impl<#(#field_struct_generics),*> ::core::clone::Clone for #rust_field_type_name<#(#field_struct_generics),*>
+            where
+                #input_name: #input_trait,
+                #(#java_class_generics: duchess::JavaObject,)*
+                #(#sig_where_clauses,)*
+            {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+        );
+
+        let inherent_method = quote_spanned!(self.span =>
+            pub fn #rust_field_name(#input_name: impl #input_trait) -> impl #output_trait
+            where
+                #(#sig_where_clauses,)*
+            {
+                #field_struct
+
+                #jvmop_impl
+
+                #rust_field_type_name {
+                    #input_name: #input_name,
+                    phantom: ::core::default::Default::default(),
+                }
+            }
+        );
+
+        Ok(inherent_method)
+    }
+
+    /// Generates the getter (`get_field_x`) or setter (`set_field_x`) of the instance field `x`:
+    /// the method on the op struct, the method on the obj struct, and the inherent method
+    /// (e.g. `Point::get_field_x(this)`) that the other two call.
+    ///
+    /// NB. This function (particularly the JvmOp impl) has significant overlap with
+    /// `inherent_object_method` and `static_field_getter`, so if you make changes here, you may
+    /// well need changes there.
+    fn instance_field_accessor(
+        &self,
+        field: &Field,
+        setter: bool,
+    ) -> syn::Result<(TokenStream, TokenStream, TokenStream)> {
+        assert!(!field.flags.is_static);
+
+        let mut sig = Signature::new(&field.name, self.span, &self.generics);
+
+        let jni_field = jni_c_str(&*field.name, self.span);
+        let jni_descriptor = jni_c_str(
+            &field.ty.descriptor(&Generic::in_scope(&[], &self.generics)),
+            self.span,
+        );
+
+        // The new value, for setters
+        let input_names: Vec<_> = if setter {
+            vec![Ident::new("a0", self.span)]
+        } else {
+            vec![]
+        };
+        let input_traits: Vec<_> = if setter {
+            vec![sig.input_trait(&field.ty)?]
+        } else {
+            vec![]
+        };
+        let prepare_inputs = self.prepare_inputs(&input_names, &[field.ty.clone()]);
+
+        let (output_ty, output_trait, access_field) = if setter {
+            let (jni_field_fn, jni_value_field) = sig.jni_field_set_fn(&field.ty)?;
+            let access_field = quote_spanned!(self.span =>
+                let value = duchess::plumbing::IntoJniValue::into_jni_value(a0).#jni_value_field;
+                jvm.env().invoke(|env| env.#jni_field_fn, |env, f| f(
+                    env,
+                    this.as_ptr(),
+                    field.as_ptr(),
+                    value,
+                ))
+            );
+            (
+                quote_spanned!(self.span => ()),
+                sig.method_trait(&None)?,
+                access_field,
+            )
+        } else {
+            let jni_field_fn = sig.jni_field_get_fn(&field.ty)?;
+            let access_field = quote_spanned!(self.span =>
+                jvm.env().invoke(|env| env.#jni_field_fn, |env, f| f(
+                    env,
+                    this.as_ptr(),
+                    field.as_ptr(),
+                ))
+            );
+            (
+                sig.non_void_output_type(&field.ty)?,
+                sig.field_trait(&field.ty)?,
+                access_field,
+            )
+        };
+
+        let prefix = if setter { "set" } else { "get" };
+        let rust_field_name =
+            Id::from(format!("{prefix}_field_{}", field.name.to_snake_case())).to_ident(self.span);
+        let rust_field_type_name = Id::from(format!(
+            "{}Field{}",
+            if setter { "Set" } else { "Get" },
+            field.name.to_camel_case()
+        ))
+        .to_ident(self.span);
+
+        // The generic parameters declared on the Java class.
+        let java_class_generics: Vec<_> = self.class_generic_names();
+
+        // The generic parameters we need on the *accessor struct* (which will implement the `JvmOp`):
+        // the class generics, plus `this` and the new value (for setters).
+        let this = Ident::new("this", self.span);
+        let field_struct_generics: Vec<_> = java_class_generics
+            .iter()
+            .chain(Some(&this))
+            .chain(&input_names)
+            .collect();
+
+        let field_struct = quote_spanned!(self.span =>
+            pub struct #rust_field_type_name<
+                #(#field_struct_generics,)*
+            > {
+                #this: #this,
+                #(#input_names : #input_names,)*
+                phantom: ::core::marker::PhantomData<(
+                    #(#field_struct_generics,)*
+                )>,
+            }
+        );
+
+        let sig_where_clauses = &sig.where_clauses;
+
+        // The Rust type of the class declaring this field.
+        let this_ty = self.this_type();
+
+        let jvmop_impl = quote_spanned!(self.span =>
+            impl<#(#field_struct_generics),*> ::core::marker::Copy
+            for #rust_field_type_name<#(#field_struct_generics),*>
+            where
+                #this: duchess::prelude::IntoJava<#this_ty>,
+                #(#input_names: #input_traits,)*
+                #(#java_class_generics: duchess::JavaObject,)*
+                #(#sig_where_clauses,)*
+            {}
+
+            impl<#(#field_struct_generics),*> ::core::clone::Clone
+            for #rust_field_type_name<#(#field_struct_generics),*>
+            where
+                #this: duchess::prelude::IntoJava<#this_ty>,
+                #(#input_names: #input_traits,)*
+                #(#java_class_generics: duchess::JavaObject,)*
+                #(#sig_where_clauses,)*
+            {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<#(#field_struct_generics),*> duchess::prelude::JvmOp
+            for #rust_field_type_name<#(#field_struct_generics),*>
+            where
+                #this: duchess::prelude::IntoJava<#this_ty>,
+                #(#input_names: #input_traits,)*
+                #(#java_class_generics: duchess::JavaObject,)*
+                #(#sig_where_clauses,)*
+            {
+                type Output<'jvm> = #output_ty;
+
+                fn execute_with<'jvm>(
+                    self,
+                    jvm: &mut duchess::Jvm<'jvm>,
+                ) -> duchess::Result<'jvm, Self::Output<'jvm>> {
+                    let this = self.#this.into_java(jvm)?;
+                    let this: & #this_ty = duchess::prelude::AsJRef::as_jref(&this)?;
+                    let this = duchess::plumbing::JavaObjectExt::as_raw(this);
+
+                    #(#prepare_inputs)*
+
+                    // Cache the field id for this field, see `static_field_getter`.
+                    static FIELD: duchess::plumbing::CachedMember<duchess::plumbing::FieldPtr> = duchess::plumbing::CachedMember::new();
+                    let (_, field) = FIELD.get_or_try_init(jvm, |jvm| {
+                        let class = <#this_ty as duchess::JavaObject>::class(jvm)?;
+                        let field = duchess::plumbing::find_field(jvm, &class, #jni_field, #jni_descriptor, false)?;
+                        Ok((class, field))
+                    })?;
+
+                    unsafe {
+                        #access_field
+                    }
+                }
+            }
+        );
+
+        let inherent_accessor = quote_spanned!(self.span =>
+            pub fn #rust_field_name(
+                #this: impl duchess::prelude::IntoJava<#this_ty>,
+                #(#input_names: impl #input_traits),*
+            ) -> impl #output_trait
+            where
+                #(#sig_where_clauses,)*
+            {
+                #field_struct
+
+                #jvmop_impl
+
+                #rust_field_type_name {
+                    #this: #this,
+                    #(#input_names: #input_names,)*
+                    phantom: ::core::default::Default::default(),
+                }
+            }
+        );
+
+        let op_accessor = quote_spanned!(self.span =>
+            pub fn #rust_field_name(
+                &self,
+                #(#input_names: impl #input_traits),*
+            ) -> impl #output_trait
+            where
+                #(#sig_where_clauses,)*
+            {
+                <#this_ty>::#rust_field_name(
+                    self.this,
+                    #(#input_names,)*
+                )
+            }
+        );
+
+        let obj_accessor = quote_spanned!(self.span =>
+            pub fn #rust_field_name<'a>(
+                &'a self,
+                #(#input_names: impl #input_traits + 'a),*
+            ) -> impl #output_trait + 'a
+            where
+                #(#sig_where_clauses,)*
+            {
+                <#this_ty>::#rust_field_name(
+                    &self.this,
+                    #(#input_names,)*
+                )
+            }
+        );
+
+        Ok((op_accessor, obj_accessor, inherent_accessor))
+    }
+
     fn struct_name(&self) -> Ident {
         self.name.class_name().to_ident(self.span)
     }
@@ -1227,7 +1892,7 @@ impl ClassInfo {
             .zip(input_types)
             .map(|(input_name, input_ty)| match input_ty.to_non_repeating() {
                 NonRepeatingType::Scalar(_) => quote_spanned!(self.span =>
-                    let #input_name = self.#input_name.execute_with(jvm)?;
+                    let #input_name = duchess::IntoScalar::into_scalar(self.#input_name, jvm)?;
                 ),
                 NonRepeatingType::Ref(_) => quote_spanned!(self.span =>
                     let #input_name = self.#input_name.into_java(jvm)?;
@@ -10,9 +10,9 @@ use synstructure::VariantInfo;
 
 use crate::{
     argument::{JavaPath, MethodSelector},
-    class_info::{ClassInfo, ClassRef, Type},
+    class_info::{ClassInfo, ClassRef, DotId, Id, RefType, Type},
     parse::{Parse, Parser},
-    reflect::Reflector,
+    reflect::{ReflectedMethod, Reflector},
     signature::Signature,
     upcasts::Upcasts,
 };
@@ -123,6 +123,7 @@ impl Driver<'_> {
         let root_to_rust = self.variant_to_rust(
             quote_spanned!(root.variant.ast().ident.span() => self),
             root.variant,
+            &root.class,
         )?;
 
         let child_class_names = children
@@ -135,6 +136,7 @@ impl Driver<'_> {
                 self.variant_to_rust(
                     quote_spanned!(c.variant.ast().ident.span() => variant),
                     c.variant,
+                    &c.class,
                 )
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -162,9 +164,13 @@ impl Driver<'_> {
     fn try_derive_to_java_struct(&mut self) -> Result<proc_macro2::TokenStream, syn::Error> {
         let variant = &self.input.variants()[0];
         let method = self.find_method_selector(variant.ast().ident.span(), variant.ast().attrs)?;
-        let class = self
-            .reflector
-            .reflect(&method.class_name(), method.class_span())?;
+        // A builder creates an instance of whatever its `build` method returns
+        let class = match self.find_builder(variant, &method)? {
+            Some(builder) => self.reflector.reflect(&builder.target, method.span())?,
+            None => self
+                .reflector
+                .reflect(&method.class_name(), method.class_span())?,
+        };
         self.try_derive_to_java_variants(&class, [variant])
     }
 
@@ -264,6 +270,7 @@ impl Driver<'_> {
         &self,
         obj: TokenStream,
         variant: &VariantInfo,
+        class: &ClassInfo,
     ) -> Result<proc_macro2::TokenStream, syn::Error> {
         // For each field, construct an expression we will use to initialize its value.
         let mut initializers = VecDeque::new();
@@ -273,17 +280,47 @@ impl Driver<'_> {
                     // Special case for fields named this
                     initializers
                         .push_back(quote_spanned!(name.span() => #obj.global().execute_with(jvm)?));
+                    continue;
+                }
+
+                // Prefer a method named like the field (e.g. a record accessor), then a bean getter.
+                // Getters inherited from superclasses aren't reflected, so fall back to the field name.
+                let field_name = name.to_string();
+                let candidates = [
+                    field_name.clone(),
+                    format!("get_{field_name}"),
+                    format!("is_{field_name}"),
+                ];
+                let getter = candidates.iter().find_map(|candidate| {
+                    class.methods.iter().find(|m| {
+                        !m.flags.is_static
+                            && m.argument_tys.is_empty()
+                            && m.return_ty.is_some()
+                            && m.name.to_snake_case().to_string() == *candidate
+                    })
+                });
+                let getter_name = match getter {
+                    Some(getter) => getter.name.to_snake_case().to_ident(name.span()),
+                    None => name.clone(),
+                };
+
+                if let Some(Some(Type::Scalar(_))) = getter.map(|g| &g.return_ty) {
+                    initializers.push_back(quote_spanned!(name.span() =>
+                    #obj
+                        .#getter_name()
+                        .execute_with(jvm)?
+                    ));
                 } else if self.is_option(&field.ty) {
                     initializers.push_back(quote_spanned!(name.span() =>
                     #obj
-                        .#name()
+                        .#getter_name()
                         .to_rust()
                         .execute_with(jvm)?
                     ));
                 } else {
                     initializers.push_back(quote_spanned!(name.span() =>
                     #obj
-                        .#name()
+                        .#getter_name()
                         .assert_not_null()
                         .to_rust()
                         .execute_with(jvm)?
@@ -347,6 +384,17 @@ impl Driver<'_> {
             ));
         }
 
+        // A method without arguments for a variant with fields creates a builder, which is given
+        // each field in turn.
+        if let Some(builder) = self.find_builder(variant, &method_selector)? {
+            return self.variant_to_java_with_builder(
+                variant,
+                &method_selector,
+                &reflected_method,
+                builder,
+            );
+        }
+
         // We are going to pass each field as an argument to the method,
         // so there have to be the same number.
         //
@@ -416,6 +464,152 @@ impl Driver<'_> {
         ))
     }
 
+    /// Generates the code to create this variant with a builder, e.g. `Server.builder().host(..).port(..).build()`.
+    /// Each field is passed to the builder method named like it, or like it with a `set` or `with` prefix.
+    fn variant_to_java_with_builder(
+        &self,
+        variant: &VariantInfo,
+        method_selector: &MethodSelector,
+        reflected_method: &ReflectedMethod,
+        builder: Builder,
+    ) -> Result<proc_macro2::TokenStream, syn::Error> {
+        let span = method_selector.span();
+        let builder_class_name = builder.class.name.to_module_name(span);
+        let mut signature = Signature::new(&Id::from("build"), span, &builder.class.generics);
+
+        let setters = signature.forbid_capture(|signature| {
+            variant
+                .bindings()
+                .iter()
+                .map(|binding| {
+                    let field = binding.ast().ident.as_ref().ok_or_else(|| {
+                        syn::Error::new(binding.ast().span(), "tuple structs not yet supported")
+                    })?;
+                    let field = field.to_string();
+                    let candidates = [field.clone(), format!("set_{field}"), format!("with_{field}")];
+                    let setter = builder
+                        .class
+                        .methods
+                        .iter()
+                        .filter(|m| !m.flags.is_static && m.argument_tys.len() == 1)
+                        .filter(|m| m.generics.is_empty())
+                        .find(|m| candidates.contains(&m.name.to_snake_case().to_string()))
+                        .ok_or_else(|| {
+                            syn::Error::new(
+                                binding.ast().span(),
+                                format!(
+                                    "builder `{}` has no method for field `{field}` (one of `{}`)",
+                                    builder.class.name,
+                                    candidates.join("`, `")
+                                ),
+                            )
+                        })?;
+
+                    let arg = match &setter.argument_tys[0] {
+                        Type::Scalar(_) => quote_spanned!(binding.span()=> *#binding),
+                        t @ (Type::Ref(_) | Type::Repeat(_)) => {
+                            let java_ty = signature.java_ty(t)?;
+                            quote_spanned!(binding.span()=> duchess::ToJava::to_java::<#java_ty>(#binding))
+                        }
+                    };
+                    let setter_name = setter.name.to_snake_case().to_ident(span);
+                    // Fluent setters may return a new builder, so keep using what they return
+                    let returns_builder = matches!(
+                        &setter.return_ty,
+                        Some(Type::Ref(RefType::Class(c))) if c.name == builder.class.name
+                    );
+                    Ok::<_, syn::Error>(if returns_builder {
+                        quote_spanned!(binding.span()=>
+                            let builder = builder.#setter_name(#arg).assert_not_null().execute_with(jvm)?;
+                        )
+                    } else {
+                        quote_spanned!(binding.span()=>
+                            builder.#setter_name(#arg).execute_with(jvm)?;
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let class_name = reflected_method
+            .class()
+            .name
+            .to_module_name(method_selector.class_span());
+        let method_name = reflected_method.name().to_snake_case().to_ident(span);
+        let create = match reflected_method {
+            ReflectedMethod::Constructor(..) => {
+                quote_spanned!(span=> #class_name :: #method_name ())
+            }
+            ReflectedMethod::Method(..) => {
+                quote_spanned!(span=> #class_name :: #method_name () .assert_not_null())
+            }
+        };
+
+        let pattern = variant.pat();
+        Ok(quote_spanned!(self.span() =>
+            #pattern => {
+                let builder: duchess::Local<'_, #builder_class_name> = #create .execute_with(jvm)?;
+                #(#setters)*
+                builder.build().upcast().execute_with(jvm)
+            }
+        ))
+    }
+
+    /// If the selected method creates a builder for `variant`, i.e. takes no arguments though the variant has fields,
+    /// returns the builder class (which has to have a `build` method).
+    fn find_builder(
+        &self,
+        variant: &VariantInfo,
+        method_selector: &MethodSelector,
+    ) -> Result<Option<Builder>, syn::Error> {
+        // Variants with a `this` field are already Java objects
+        let has_this = variant
+            .bindings()
+            .iter()
+            .any(|b| b.ast().ident.as_ref().map(|i| i == "this").unwrap_or(false));
+        if has_this || variant.ast().fields.is_empty() {
+            return Ok(None);
+        }
+        let method = self.reflector.reflect_method(method_selector)?;
+        if !method.argument_tys().is_empty() {
+            return Ok(None);
+        }
+        let span = method_selector.span();
+        let class = match &method {
+            ReflectedMethod::Constructor(class, _) => class.clone(),
+            ReflectedMethod::Method(class, index) => match &class.methods[*index].return_ty {
+                Some(Type::Ref(RefType::Class(c))) => self.reflector.reflect(&c.name, span)?,
+                _ => {
+                    return Err(syn::Error::new(
+                        span,
+                        format!(
+                            "selected method has no arguments, but there are {} fields, and it doesn't return a builder",
+                            variant.ast().fields.len()
+                        ),
+                    ))
+                }
+            },
+        };
+        let build = class
+            .methods
+            .iter()
+            .find(|m| &m.name[..] == "build" && !m.flags.is_static && m.argument_tys.is_empty());
+        match build.and_then(|m| m.return_ty.as_ref()) {
+            Some(Type::Ref(RefType::Class(target))) => Ok(Some(Builder {
+                target: target.name.clone(),
+                class,
+            })),
+            _ => Err(syn::Error::new(
+                span,
+                format!(
+                    "selected method has no arguments, but there are {} fields, and `{}` has no `build` method",
+                    variant.ast().fields.len(),
+                    class.name
+                ),
+            )),
+        }
+    }
+
     fn find_method_selector(
         &self,
         span: Span,
@@ -448,12 +642,19 @@ impl Driver<'_> {
 
     fn is_option(&self, ty: &syn::Type) -> bool {
         match ty {
-            syn::Type::Path(p) => p.path.is_ident("Option"),
+            syn::Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Option"),
             _ => false,
         }
     }
 }
 
+/// The builder that a `ToJava` derive calls to create a variant, see [`Driver::find_builder`].
+struct Builder {
+    class: Arc<ClassInfo>,
+    /// The class that `build` returns
+    target: DotId,
+}
+
 fn check_all_extend_root<'a>(
     root: &ClassInfo,
     variants: impl IntoIterator<Item = &'a MethodSelector>,
@@ -86,15 +86,15 @@ pub fn java_function(selector: MethodSelector, input: syn::ItemFn) -> syn::Resul
 
     let rust_this_ty = driver.convert_ty(&class_info.this_ref().into())?;
     let method_name_literal = Literal::string(&selector.method_name());
-    let signature_literal = Literal::string(&driver.method_info.descriptor());
+    let signature_literal = Literal::string(&driver.method_info.descriptor(&class_info.generics));
 
     let tokens = quote_spanned!(span =>
-        // Declare a function with no-mangle linkage as expected by Java.
+        // Declare a function with no-mangle linkage and the JNI calling convention as expected by Java.
         // The function is declared inside a `const _` block so that it is not nameable from Rust code.
-        #[allow(unused_variables, nonstandard_style)]
+        #[allow(unused_variables, nonstandard_style, improper_ctypes_definitions)]
         const _: () = {
             #[no_mangle]
-            fn #java_fn_name(
+            extern "system" fn #java_fn_name(
                 #env_name: #env_ty,
                 #this_name: #this_ty,
                 #(#user_argument_names: #user_argument_tys,)*
@@ -6,6 +6,7 @@ use rust_format::Formatter;
 mod argument;
 mod check;
 mod class_info;
+mod classpath;
 mod codegen;
 mod derive;
 mod java_function;
@@ -154,6 +154,17 @@ impl<'p> TextAccum<'p> {
     }
 
     fn accum_token(&mut self, token: &TokenTree) {
+        // `$` is part of the binary names of nested classes (e.g. `java.util.Map$Entry`), but Rust tokenizes it on
+        // its own, so glue it back to the identifiers around it
+        if let TokenTree::Punct(p) = token {
+            if p.as_char() == '$' {
+                self.text.truncate(self.text.trim_end().len());
+                self.text.push('$');
+                self.span = self.span.join(token.span()).unwrap_or(self.span);
+                return;
+            }
+        }
+
         self.text.push_str(&token.to_string());
 
         // insert whitespace if this is a token that needs to be separated from following tokens
@@ -187,17 +198,6 @@ pub trait Parse: Sized {
     /// Ok(Some(e)) -- successful parse of `Self`
     fn parse(p: &mut Parser) -> syn::Result<Option<Self>>;
 
-    /// parse any number of instances of self.
-    fn parse_many(p: &mut Parser) -> syn::Result<Vec<Self>> {
-        let mut result = vec![];
-
-        while let Some(e) = Self::parse(p)? {
-            result.push(e);
-        }
-
-        Ok(result)
-    }
-
     /// Describes the thing we are parsing, for use in error messages.
     /// e.g. "java path".
     fn description() -> String;
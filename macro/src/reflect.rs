@@ -1,12 +1,22 @@
-use std::{cell::RefCell, collections::BTreeMap, env, path::PathBuf, process::Command, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
 
+use once_cell::unsync::OnceCell;
 use proc_macro2::Span;
 
 use crate::{
-    argument::{DuchessDeclaration, Ident, JavaPackage, MethodSelector},
+    argument::{DuchessDeclaration, Filter, Ident, JavaPackage, MethodSelector, Pattern},
     class_info::{
-        ClassDecl, ClassInfo, DotId, Generic, Id, Method, RootMap, SpannedPackageInfo, Type,
+        class_file, ClassDecl, ClassInfo, ClassRef, DotId, Generic, Id, Method, Privacy, RootMap,
+        SpannedPackageInfo, Type,
     },
+    classpath::{self, Classpath},
     upcasts::Upcasts,
 };
 
@@ -23,6 +33,11 @@ impl DuchessDeclaration {
             )?;
         }
 
+        // Classes selected with `generate` come after the declared ones, which take precedence
+        for package in &self.packages {
+            package.generate_classes(reflector, &mut subpackages, &mut classes)?;
+        }
+
         let upcasts: Upcasts = Upcasts::from_iter(classes.values().map(|v| &**v));
 
         Ok(RootMap {
@@ -74,17 +89,28 @@ impl JavaPackage {
                 ClassDecl::Reflected(c) => {
                     let dot_id = self.make_absolute_dot_id(c.span, &c.name)?;
                     let info = reflector.reflect(&dot_id, c.span)?;
-                    (dot_id, info.clone())
+                    (dot_id, self.filter_methods(info))
                 }
                 ClassDecl::Specified(c) => {
                     let dot_id = self.make_absolute_dot_id(c.span, &c.name)?;
-                    (
-                        dot_id.clone(),
-                        Arc::new(ClassInfo {
-                            name: dot_id,
-                            ..c.clone()
-                        }),
-                    )
+                    let mut info = ClassInfo {
+                        name: dot_id.clone(),
+                        ..c.clone()
+                    };
+
+                    // The values of constants, and which fields are enum constants, are taken from the class file
+                    if info.is_enum() || info.fields.iter().any(|f| f.may_be_constant()) {
+                        let reflected = reflector.reflect(&dot_id, c.span)?;
+                        for field in &mut info.fields {
+                            let reflected = reflected.fields.iter().find(|r| r.name == field.name);
+                            if field.may_be_constant() {
+                                field.constant = reflected.and_then(|r| r.constant.clone());
+                            }
+                            field.flags.is_enum = reflected.is_some_and(|r| r.flags.is_enum);
+                        }
+                    }
+
+                    (dot_id, Arc::new(info))
                 }
             };
 
@@ -94,6 +120,153 @@ impl JavaPackage {
         Ok(())
     }
 
+    /// Reflects on the public classes selected by `generate` filters (and not excluded), inserting
+    /// them into the packages they belong to, which may be subpackages of this one.
+    fn generate_classes(
+        &self,
+        reflector: &mut Reflector,
+        map: &mut BTreeMap<Id, SpannedPackageInfo>,
+        classes: &mut BTreeMap<DotId, Arc<ClassInfo>>,
+    ) -> syn::Result<()> {
+        let mut generated: BTreeMap<DotId, (Arc<ClassInfo>, Span)> = BTreeMap::new();
+        for filter in &self.filters {
+            let Filter::Generate(pattern) = filter else {
+                continue;
+            };
+            let pattern = self.absolute_pattern(pattern)?;
+
+            let mut found = false;
+            for name in reflector.class_names_in(&pattern.package_prefix(), pattern.span)? {
+                if !pattern.matches(&name)
+                    || name.ends_with("package-info")
+                    || name.ends_with("module-info")
+                    || self.excludes_class(&name)?
+                {
+                    continue;
+                }
+                let dot_id = DotId::parse(&name);
+                if classes.contains_key(&dot_id) || generated.contains_key(&dot_id) {
+                    found = true;
+                    continue;
+                }
+                let info = reflector.reflect(&dot_id, pattern.span)?;
+                if info.flags.privacy == Privacy::Public {
+                    generated.insert(dot_id, (info, pattern.span));
+                    found = true;
+                }
+            }
+            if !found {
+                return Err(syn::Error::new(
+                    pattern.span,
+                    format!("no public classes matching `{pattern}` found"),
+                ));
+            }
+        }
+
+        // Supertypes that aren't generated (e.g., because they aren't public) are left out, like
+        // they would be if the classes were declared by hand. Classes are only required for those
+        // packages that have classes oxidized.
+        let oxidized = |name: &DotId| classes.contains_key(name) || generated.contains_key(name);
+        let package_oxidized = |name: &DotId| {
+            let package = name.split().0;
+            classes
+                .keys()
+                .chain(generated.keys())
+                .any(|c| c.split().0 == package)
+        };
+        let generated: Vec<_> = generated
+            .iter()
+            .map(|(dot_id, (info, span))| {
+                let mut info = self.filter_methods(info.clone());
+                let keep = |c: &ClassRef| oxidized(&c.name) || !package_oxidized(&c.name);
+                if !info.extends.iter().all(keep) || !info.implements.iter().all(keep) {
+                    let info = Arc::make_mut(&mut info);
+                    info.extends.retain(keep);
+                    info.implements.retain(keep);
+                }
+                (dot_id.clone(), info, *span)
+            })
+            .collect();
+
+        for (dot_id, info, span) in generated {
+            let (package_ids, _) = dot_id.split();
+            let mut package: Option<&mut SpannedPackageInfo> = None;
+            for id in package_ids {
+                let subpackages = match package {
+                    Some(p) => &mut p.subpackages,
+                    None => &mut *map,
+                };
+                package =
+                    Some(
+                        subpackages
+                            .entry(id.clone())
+                            .or_insert_with(|| SpannedPackageInfo {
+                                name: id.clone(),
+                                span,
+                                subpackages: Default::default(),
+                                classes: Default::default(),
+                            }),
+                    );
+            }
+            package.unwrap().classes.push(dot_id.clone());
+            classes.insert(dot_id, info);
+        }
+        Ok(())
+    }
+
+    /// Makes a pattern from a filter absolute, checking that it's within this package.
+    fn absolute_pattern(&self, pattern: &Pattern) -> syn::Result<Pattern> {
+        let pattern = pattern.in_package(&self.package_name);
+        if !pattern.text.starts_with(&format!("{}.", self.package_name)) {
+            return Err(syn::Error::new(
+                pattern.span,
+                format!("expected a pattern within package `{}`", self.package_name),
+            ));
+        }
+        Ok(pattern)
+    }
+
+    fn excludes_class(&self, name: &str) -> syn::Result<bool> {
+        for filter in &self.filters {
+            if let Filter::ExcludeClasses(pattern) = filter {
+                if self.absolute_pattern(pattern)?.matches(name) {
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Applies the `include` and `exclude` filters on methods to a reflected class: if any
+    /// `include` filter matches the class, only the methods it (or another) includes are kept.
+    fn filter_methods(&self, info: Arc<ClassInfo>) -> Arc<ClassInfo> {
+        let class_name = info.name.to_string();
+        let mut includes = vec![];
+        let mut excludes = vec![];
+        for filter in &self.filters {
+            match filter {
+                Filter::IncludeMethods(class, method) => includes.push((class, method)),
+                Filter::ExcludeMethods(class, method) => excludes.push((class, method)),
+                Filter::Generate(_) | Filter::ExcludeClasses(_) => continue,
+            }
+        }
+        let applies = |(class, _): &&(&Pattern, &Pattern)| {
+            class.in_package(&self.package_name).matches(&class_name)
+        };
+        let includes: Vec<_> = includes.iter().filter(applies).collect();
+        let excludes: Vec<_> = excludes.iter().filter(applies).collect();
+        if includes.is_empty() && excludes.is_empty() {
+            return info;
+        }
+
+        let mut info = (*info).clone();
+        info.methods.retain(|m| {
+            (includes.is_empty() || includes.iter().any(|(_, method)| method.matches(&m.name)))
+                && !excludes.iter().any(|(_, method)| method.matches(&m.name))
+        });
+        Arc::new(info)
+    }
+
     /// The users give classnames that may not include java package information.
     fn make_absolute_dot_id(&self, span: Span, class_dot_id: &DotId) -> syn::Result<DotId> {
         let package_ids: Vec<Id> = self.package_name.ids.iter().map(|n| n.to_id()).collect();
@@ -117,14 +290,93 @@ impl JavaPackage {
     }
 }
 
+/// Checks that the descriptors derived from the declarations of `class` are those of its class file, to catch bugs in
+/// the derivation. Enabled by setting `DUCHESS_CHECK_DESCRIPTORS`, as duchess's own tests do.
+///
+/// Methods are only compared to the methods of the class file with the same name and number of arguments: generic
+/// signatures leave out the synthetic arguments of some constructors, like the outer instance of inner classes.
+fn check_descriptors(
+    class: &ClassInfo,
+    descriptors: &[class_file::MethodDescriptor],
+    span: Span,
+) -> syn::Result<()> {
+    let declared = class
+        .constructors
+        .iter()
+        .map(|c| {
+            (
+                "<init>",
+                c.argument_tys.len(),
+                c.descriptor(&class.generics),
+            )
+        })
+        .chain(class.methods.iter().map(|m| {
+            (
+                &m.name[..],
+                m.argument_tys.len(),
+                m.descriptor(&class.generics),
+            )
+        }));
+    for (name, argument_count, derived) in declared {
+        let mut candidates = descriptors
+            .iter()
+            .filter(|d| d.name == name && d.argument_count == argument_count)
+            .peekable();
+        if candidates.peek().is_some() && !candidates.any(|d| d.descriptor == derived) {
+            return Err(syn::Error::new(
+                span,
+                format!(
+                    "derived the descriptor `{derived}` for `{}.{name}`, which isn't in its class file",
+                    class.name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The names of the fields that `javap -v` flags as enum constants (`ACC_ENUM`), in declaration order.
+fn verbose_javap_enum_constants(text: &str) -> Vec<String> {
+    let mut constants = vec![];
+    let mut field = None;
+    for line in text.lines() {
+        if let Some(flags) = line.strip_prefix("    flags: ") {
+            if let Some(name) = field.take().filter(|_| flags.contains("ACC_ENUM")) {
+                constants.push(name);
+            }
+        } else if let Some(member) = line.strip_prefix("  ").filter(|m| !m.starts_with(' ')) {
+            // The declarations of members are indented once, their details (like the flags) twice
+            field = member
+                .strip_suffix(';')
+                .filter(|m| !m.contains('('))
+                .and_then(|m| m.split(' ').next_back())
+                .map(String::from);
+        }
+    }
+    constants
+}
+
 /// Reflection cache. Given fully qualified java class names,
 /// look up info about their interfaces.
+///
+/// Class files are read directly from the jars given with `from_jar` and from the `CLASSPATH`; only classes found in
+/// neither (like those of the JDK itself, which aren't stored as class files) are reflected with `javap`.
 #[derive(Default)]
 pub struct Reflector {
     classes: RefCell<BTreeMap<DotId, Arc<ClassInfo>>>,
+    jars: Classpath,
+    env_classpath: OnceCell<Result<Classpath, String>>,
+    runtime_image_classes: OnceCell<Result<Vec<String>, String>>,
 }
 
 impl Reflector {
+    /// Reflects on the classes in the jar file at `path` (before those on the `CLASSPATH`).
+    pub fn add_jar(&mut self, path: &Path, span: Span) -> syn::Result<()> {
+        self.jars
+            .add_jar(path)
+            .map_err(|err| syn::Error::new(span, err))
+    }
+
     /// Returns the (potentially cached) info about `class_name`;
     pub fn reflect(&self, class_name: &DotId, span: Span) -> syn::Result<Arc<ClassInfo>> {
         // yields an error if we cannot reflect on that class.
@@ -132,6 +384,106 @@ impl Reflector {
             return Ok(class);
         }
 
+        let class_file = self.find_class_file(class_name, span)?;
+        let read_error = |err| {
+            syn::Error::new(
+                span,
+                format!("failed to read the class file of `{class_name}`: {err}"),
+            )
+        };
+        let s = match &class_file {
+            Some(bytes) => class_file::to_javap(bytes).map_err(read_error)?,
+            None => self.javap(class_name, false, span)?,
+        };
+
+        let mut ci = ClassInfo::parse(&s, span)?;
+        if ci.is_enum() {
+            let constants = match &class_file {
+                Some(bytes) => class_file::enum_constants(bytes).map_err(read_error)?,
+                None => verbose_javap_enum_constants(&self.javap(class_name, true, span)?),
+            };
+            for field in &mut ci.fields {
+                field.flags.is_enum = constants.iter().any(|c| *c == field.name[..]);
+            }
+        }
+        if let Some(bytes) = &class_file {
+            if env::var_os("DUCHESS_CHECK_DESCRIPTORS").is_some() {
+                let descriptors = class_file::method_descriptors(bytes).map_err(read_error)?;
+                check_descriptors(&ci, &descriptors, span)?;
+            }
+        }
+
+        // reset the span for the cached data to the call site so that when others look it up,
+        // they get the same span.
+        ci.span = Span::call_site();
+        Ok(self
+            .classes
+            .borrow_mut()
+            .entry(class_name.clone())
+            .or_insert(Arc::new(ci))
+            .clone())
+    }
+
+    /// The binary names of the classes in `package` and its subpackages: those in the jars given
+    /// with `from_jar` and on the `CLASSPATH`, or if there are none, those of the JDK.
+    pub fn class_names_in(&self, package: &[&str], span: Span) -> syn::Result<Vec<String>> {
+        let mut names = vec![];
+        for classpath in [&self.jars, self.env_classpath(span)?] {
+            names.extend(
+                classpath
+                    .class_names_in(package)
+                    .map_err(|err| syn::Error::new(span, err))?,
+            );
+        }
+
+        if names.is_empty() {
+            let runtime_image_classes = self
+                .runtime_image_classes
+                .get_or_init(classpath::runtime_image_class_names)
+                .as_ref()
+                .map_err(|err| syn::Error::new(span, err))?;
+            let prefix: String = package.iter().map(|id| format!("{id}.")).collect();
+            names.extend(
+                runtime_image_classes
+                    .iter()
+                    .filter(|name| name.starts_with(&prefix))
+                    .cloned(),
+            );
+        }
+
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    fn env_classpath(&self, span: Span) -> syn::Result<&Classpath> {
+        let env_classpath = self.env_classpath.get_or_init(|| {
+            let mut classpath = Classpath::default();
+            if let Ok(entries) = env::var("CLASSPATH") {
+                classpath.add_classpath(&entries)?;
+            }
+            Ok(classpath)
+        });
+        env_classpath
+            .as_ref()
+            .map_err(|err| syn::Error::new(span, err))
+    }
+
+    fn find_class_file(&self, class_name: &DotId, span: Span) -> syn::Result<Option<Vec<u8>>> {
+        for classpath in [&self.jars, self.env_classpath(span)?] {
+            if let Some(bytes) = classpath
+                .find_class(class_name)
+                .map_err(|err| syn::Error::new(span, err))?
+            {
+                return Ok(Some(bytes));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Runs `javap` on `class_name`, returning its output, which with `verbose` also shows the flags and attributes
+    /// of the class file (`-v`).
+    fn javap(&self, class_name: &DotId, verbose: bool, span: Span) -> syn::Result<String> {
         let mut javap_path = PathBuf::new();
         if let Ok(java_home) = env::var("JAVA_HOME") {
             javap_path.extend([java_home.as_str(), "bin"]);
@@ -145,7 +497,11 @@ impl Reflector {
             command.arg("-cp").arg(classpath);
         }
 
-        command.arg("-p").arg(format!("{}", class_name));
+        command.arg("-p").arg("-constants");
+        if verbose {
+            command.arg("-v");
+        }
+        command.arg(format!("{}", class_name));
 
         let output_or_err = command.output();
 
@@ -169,27 +525,13 @@ impl Reflector {
             ));
         }
 
-        let s = match String::from_utf8(output.stdout) {
-            Ok(o) => o,
-            Err(err) => {
-                return Err(syn::Error::new(
-                    span,
-                    format!("failed to parse output of `{command:?}` as utf-8: {err}"),
-                ));
-            }
-        };
-
-        let mut ci = ClassInfo::parse(&s, span)?;
-
-        // reset the span for the cached data to the call site so that when others look it up,
-        // they get the same span.
-        ci.span = Span::call_site();
-        Ok(self
-            .classes
-            .borrow_mut()
-            .entry(class_name.clone())
-            .or_insert(Arc::new(ci))
-            .clone())
+        match String::from_utf8(output.stdout) {
+            Ok(o) => Ok(o),
+            Err(err) => Err(syn::Error::new(
+                span,
+                format!("failed to parse output of `{command:?}` as utf-8: {err}"),
+            )),
+        }
     }
 
     ///
@@ -32,10 +32,21 @@ pub struct Signature {
     /// Where clauses to include on the generated Rust method.
     pub where_clauses: Vec<TokenStream>,
 
-    /// If true, permit `?` and translate to fresh generics.
-    /// If false, report an error if `?` appears, because it is a context where
-    /// we don't support capture.
-    capture_generics: bool,
+    /// How Java wildcards are translated in the current context.
+    wildcards: Wildcards,
+}
+
+/// How [`Signature`] translates Java wildcards (`?`, `? extends T`, and `? super T`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Wildcards {
+    /// Into fresh generic parameters of the method, e.g. for arguments, since the caller picks their type.
+    Capture,
+    /// Into a type that is known to hold for every value, i.e. the upper bound for `? extends T` and
+    /// `java.lang.Object` otherwise, e.g. for return types, whose type the caller doesn't pick.
+    UpperBound,
+    /// Into the narrowest type that satisfies them, i.e. `T` for both `? extends T` and `? super T`, for the bounds
+    /// of generic parameters. For example, `<T extends Comparable<? super T>>` becomes `T: AsJRef<Comparable<T>>`.
+    Narrowest,
 }
 
 impl Signature {
@@ -55,7 +66,7 @@ impl Signature {
             in_scope_generics: external_generics.iter().map(|g| g.id.clone()).collect(),
             rust_generics: vec![],
             where_clauses: vec![],
-            capture_generics: true,
+            wildcards: Wildcards::Capture,
         }
     }
 
@@ -70,9 +81,9 @@ impl Signature {
         s.in_scope_generics
             .extend(internal_generics.iter().map(|g| g.id.clone()));
 
-        // Forbid capture we don't have to worry about things like `X extends ArrayList<?>`.
-        // Actually, we could probably support capture here, but I don't know want to right now.
-        s.forbid_capture(|s| {
+        // Capturing wildcards in bounds would introduce generic parameters that callers have no way to infer, so
+        // `X extends Comparable<? super X>` is narrowed to `X: AsJRef<Comparable<X>>` instead.
+        s.with_wildcards(Wildcards::Narrowest, |s| {
             for g in internal_generics {
                 let ident = g.id.to_ident(s.span);
                 s.rust_generics.push(ident.clone());
@@ -90,12 +101,18 @@ impl Signature {
         Ok(s)
     }
 
-    /// Set the `capture_generics` field to false while `op` executes,
-    /// then restore its value.
+    /// Translates wildcards into their upper bound (rather than fresh generics) while `op` executes,
+    /// for types that the caller doesn't pick, like return types.
     pub fn forbid_capture<R>(&mut self, op: impl FnOnce(&mut Self) -> R) -> R {
-        let v = std::mem::replace(&mut self.capture_generics, false);
+        self.with_wildcards(Wildcards::UpperBound, op)
+    }
+
+    /// Set the `wildcards` field to `wildcards` while `op` executes,
+    /// then restore its value.
+    fn with_wildcards<R>(&mut self, wildcards: Wildcards, op: impl FnOnce(&mut Self) -> R) -> R {
+        let v = std::mem::replace(&mut self.wildcards, wildcards);
         let r = op(self);
-        self.capture_generics = v;
+        self.wildcards = v;
         r
     }
 
@@ -105,22 +122,17 @@ impl Signature {
     /// translated to a Rust type like `ArrayList<Pi>` for some fresh `Pi`.
     ///
     /// See also `Self::push_where_bound`.
-    fn fresh_generic(&mut self) -> syn::Result<Ident> {
-        if !self.capture_generics {
-            let msg = format!("unsupported wildcards in `{}`", self.item_name);
-            Err(syn::Error::new(self.span, msg))
-        } else {
-            let mut i = self.rust_generics.len();
-            loop {
-                let ident = Ident::new(&format!("Capture{}", i), self.span);
-                if !self.rust_generics.contains(&ident) {
-                    self.rust_generics.push(ident.clone());
-                    self.where_clauses
-                        .push(quote_spanned!(self.span => #ident : duchess::JavaObject));
-                    return Ok(ident);
-                }
-                i += 1;
+    fn fresh_generic(&mut self) -> Ident {
+        let mut i = self.rust_generics.len();
+        loop {
+            let ident = Ident::new(&format!("Capture{}", i), self.span);
+            if !self.rust_generics.contains(&ident) {
+                self.rust_generics.push(ident.clone());
+                self.where_clauses
+                    .push(quote_spanned!(self.span => #ident : duchess::JavaObject));
+                return ident;
             }
+            i += 1;
         }
     }
 
@@ -174,9 +186,10 @@ impl Signature {
         })
     }
 
-    pub fn jni_call_fn(&mut self, ty: &Option<Type>) -> syn::Result<Ident> {
+    /// The function of `duchess::plumbing::call` that calls an instance method returning `ty`.
+    pub fn call_shim(&mut self, ty: &Option<Type>) -> syn::Result<Ident> {
         let f = match ty {
-            Some(Type::Ref(_)) => "CallObjectMethodA",
+            Some(Type::Ref(_)) => "object_method",
             Some(Type::Repeat(_)) => {
                 return Err(syn::Error::new(
                     self.span,
@@ -187,23 +200,24 @@ impl Signature {
                 ))
             }
             Some(Type::Scalar(scalar)) => match scalar {
-                ScalarType::Int => "CallIntMethodA",
-                ScalarType::Long => "CallLongMethodA",
-                ScalarType::Short => "CallShortMethodA",
-                ScalarType::Byte => "CallByteMethodA",
-                ScalarType::F64 => "CallDoubleMethodA",
-                ScalarType::F32 => "CallFloatMethodA",
-                ScalarType::Boolean => "CallBooleanMethodA",
-                ScalarType::Char => "CallCharMethodA",
+                ScalarType::Int => "int_method",
+                ScalarType::Long => "long_method",
+                ScalarType::Short => "short_method",
+                ScalarType::Byte => "byte_method",
+                ScalarType::F64 => "double_method",
+                ScalarType::F32 => "float_method",
+                ScalarType::Boolean => "boolean_method",
+                ScalarType::Char => "char_method",
             },
-            None => "CallVoidMethodA",
+            None => "void_method",
         };
         Ok(Ident::new(f, self.span))
     }
 
-    pub fn jni_static_call_fn(&mut self, ty: &Option<Type>) -> syn::Result<Ident> {
+    /// The function of `duchess::plumbing::call` that calls a static method returning `ty`.
+    pub fn static_call_shim(&mut self, ty: &Option<Type>) -> syn::Result<Ident> {
         let f = match ty {
-            Some(Type::Ref(_)) => "CallStaticObjectMethodA",
+            Some(Type::Ref(_)) => "static_object_method",
             Some(Type::Repeat(_)) => {
                 let msg = format!(
                     "unsupported repeating return type in static method `{}`",
@@ -212,16 +226,16 @@ impl Signature {
                 return Err(syn::Error::new(self.span, msg));
             }
             Some(Type::Scalar(scalar)) => match scalar {
-                ScalarType::Int => "CallStaticIntMethodA",
-                ScalarType::Long => "CallStaticLongMethodA",
-                ScalarType::Short => "CallStaticShortMethodA",
-                ScalarType::Byte => "CallStaticByteMethodA",
-                ScalarType::F64 => "CallStaticDoubleMethodA",
-                ScalarType::F32 => "CallStaticFloatMethodA",
-                ScalarType::Boolean => "CallStaticBooleanMethodA",
-                ScalarType::Char => "CallStaticCharMethodA",
+                ScalarType::Int => "static_int_method",
+                ScalarType::Long => "static_long_method",
+                ScalarType::Short => "static_short_method",
+                ScalarType::Byte => "static_byte_method",
+                ScalarType::F64 => "static_double_method",
+                ScalarType::F32 => "static_float_method",
+                ScalarType::Boolean => "static_boolean_method",
+                ScalarType::Char => "static_char_method",
             },
-            None => "CallStaticVoidMethodA",
+            None => "static_void_method",
         };
         Ok(Ident::new(f, self.span))
     }
@@ -250,6 +264,82 @@ impl Signature {
         Ok(Ident::new(f, self.span))
     }
 
+    pub fn jni_field_get_fn(&mut self, ty: &Type) -> syn::Result<Ident> {
+        let f = match ty {
+            Type::Ref(_) => "GetObjectField",
+            Type::Repeat(_) => {
+                let msg = format!(
+                    "unsupported repeating type in getter of field `{}`",
+                    self.item_name
+                );
+                return Err(syn::Error::new(self.span, msg));
+            }
+            Type::Scalar(scalar) => match scalar {
+                ScalarType::Int => "GetIntField",
+                ScalarType::Long => "GetLongField",
+                ScalarType::Short => "GetShortField",
+                ScalarType::Byte => "GetByteField",
+                ScalarType::F64 => "GetDoubleField",
+                ScalarType::F32 => "GetFloatField",
+                ScalarType::Boolean => "GetBooleanField",
+                ScalarType::Char => "GetCharField",
+            },
+        };
+        Ok(Ident::new(f, self.span))
+    }
+
+    /// Returns the JNI function that sets a field of type `ty`, along with the
+    /// field of `jvalue` that holds a value of that type.
+    pub fn jni_field_set_fn(&mut self, ty: &Type) -> syn::Result<(Ident, Ident)> {
+        let (f, v) = match ty {
+            Type::Ref(_) => ("SetObjectField", "l"),
+            Type::Repeat(_) => {
+                let msg = format!(
+                    "unsupported repeating type in setter of field `{}`",
+                    self.item_name
+                );
+                return Err(syn::Error::new(self.span, msg));
+            }
+            Type::Scalar(scalar) => match scalar {
+                ScalarType::Int => ("SetIntField", "i"),
+                ScalarType::Long => ("SetLongField", "j"),
+                ScalarType::Short => ("SetShortField", "s"),
+                ScalarType::Byte => ("SetByteField", "b"),
+                ScalarType::F64 => ("SetDoubleField", "d"),
+                ScalarType::F32 => ("SetFloatField", "f"),
+                ScalarType::Boolean => ("SetBooleanField", "z"),
+                ScalarType::Char => ("SetCharField", "c"),
+            },
+        };
+        Ok((Ident::new(f, self.span), Ident::new(v, self.span)))
+    }
+
+    /// Returns the JNI function that sets a static field of type `ty`, along with the
+    /// field of `jvalue` that holds a value of that type.
+    pub fn jni_static_field_set_fn(&mut self, ty: &Type) -> syn::Result<(Ident, Ident)> {
+        let (f, v) = match ty {
+            Type::Ref(_) => ("SetStaticObjectField", "l"),
+            Type::Repeat(_) => {
+                let msg = format!(
+                    "unsupported repeating type in setter of static field `{}`",
+                    self.item_name
+                );
+                return Err(syn::Error::new(self.span, msg));
+            }
+            Type::Scalar(scalar) => match scalar {
+                ScalarType::Int => ("SetStaticIntField", "i"),
+                ScalarType::Long => ("SetStaticLongField", "j"),
+                ScalarType::Short => ("SetStaticShortField", "s"),
+                ScalarType::Byte => ("SetStaticByteField", "b"),
+                ScalarType::F64 => ("SetStaticDoubleField", "d"),
+                ScalarType::F32 => ("SetStaticFloatField", "f"),
+                ScalarType::Boolean => ("SetStaticBooleanField", "z"),
+                ScalarType::Char => ("SetStaticCharField", "c"),
+            },
+        };
+        Ok((Ident::new(f, self.span), Ident::new(v, self.span)))
+    }
+
     /// Returns an appropriate trait for a method that
     /// returns `ty`. Assumes objects are nullable.
     pub fn method_trait(&mut self, ty: &Option<Type>) -> syn::Result<TokenStream> {
@@ -318,21 +408,34 @@ impl Signature {
                     Err(syn::Error::new(self.span, msg))
                 }
             }
-            RefType::Extends(ty) => {
-                let g = self.fresh_generic()?;
-                let e = self.java_ref_ty(ty)?;
-                self.push_where_bound(quote_spanned!(self.span => #g : duchess::AsJRef<#e>));
-                Ok(quote_spanned!(self.span => #g))
-            }
-            RefType::Super(_) => {
-                let g = self.fresh_generic()?;
-                // FIXME: missing where bound, really
-                Ok(quote_spanned!(self.span => #g))
-            }
-            RefType::Wildcard => {
-                let g = self.fresh_generic()?;
-                Ok(quote_spanned!(self.span => #g))
-            }
+            RefType::Extends(ty) => match self.wildcards {
+                Wildcards::Capture => {
+                    let g = self.fresh_generic();
+                    let e = self.java_ref_ty(ty)?;
+                    self.push_where_bound(quote_spanned!(self.span => #g : duchess::AsJRef<#e>));
+                    Ok(quote_spanned!(self.span => #g))
+                }
+                Wildcards::UpperBound | Wildcards::Narrowest => self.java_ref_ty(ty),
+            },
+            RefType::Super(ty) => match self.wildcards {
+                Wildcards::Capture => {
+                    let g = self.fresh_generic();
+                    let s = self.java_ref_ty(ty)?;
+                    self.push_where_bound(quote_spanned!(self.span => #s : duchess::AsJRef<#g>));
+                    Ok(quote_spanned!(self.span => #g))
+                }
+                Wildcards::UpperBound => Ok(quote_spanned!(self.span => java::lang::Object)),
+                Wildcards::Narrowest => self.java_ref_ty(ty),
+            },
+            RefType::Wildcard => match self.wildcards {
+                Wildcards::Capture => {
+                    let g = self.fresh_generic();
+                    Ok(quote_spanned!(self.span => #g))
+                }
+                Wildcards::UpperBound | Wildcards::Narrowest => {
+                    Ok(quote_spanned!(self.span => java::lang::Object))
+                }
+            },
         }
     }
 
@@ -0,0 +1,339 @@
+//! Reading Java annotations from classes, methods, and fields, e.g. to turn annotated Java models into Rust
+//! configuration.
+//!
+//! Annotations declared with [`java_package!`](crate::java_package) (as interfaces extending
+//! `java.lang.annotation.Annotation`) are read with [`JvmOp::get_annotation`], and their attributes with their
+//! generated methods:
+//!
+//! ```ignore
+//! let table: Option<String> = class
+//!     .get_annotation::<com::example::Table>()
+//!     .assert_not_null()
+//!     .name()
+//!     .to_rust()
+//!     .execute()?;
+//! ```
+//!
+//! Annotations can also be converted as a whole into [`AnnotationValues`] (with `to_rust()`), without declaring them
+//! first, e.g. the ones returned by `getAnnotations()`.
+
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use jni_sys::jvalue;
+
+use crate::{
+    cast::Upcast,
+    find::find_method,
+    java::{
+        self,
+        lang::{
+            annotation::Annotation,
+            reflect::{AnnotatedElement, Method},
+            Class, Object,
+        },
+    },
+    jvm::JavaObjectExt,
+    prelude::{JavaArrayExt, JavaObjectArrayExt},
+    raw::MethodPtr,
+    AsJRef, ConversionError, Error, IntoRust, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// [`JvmOp`][] that reads the annotation of class `A` from a class, method, or field, see
+/// [`JvmOp::get_annotation`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct GetAnnotation<J: JvmOp, A> {
+    op: J,
+    _marker: PhantomData<A>,
+}
+
+impl<J, A> GetAnnotation<J, A>
+where
+    J: JvmOp,
+    for<'jvm> J::Output<'jvm>: AsJRef<AnnotatedElement>,
+    A: Upcast<Annotation>,
+{
+    pub(crate) fn new(op: J) -> Self {
+        Self {
+            op,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<J, A> JvmOp for GetAnnotation<J, A>
+where
+    J: JvmOp,
+    for<'jvm> J::Output<'jvm>: AsJRef<AnnotatedElement>,
+    A: Upcast<Annotation>,
+{
+    type Output<'jvm> = Option<Local<'jvm, A>>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let element = self.op.execute_with(jvm)?;
+        let element = element.as_jref()?;
+        let class = A::class(jvm)?;
+
+        // `getAnnotation` is looked up by hand since it is generic over the `Class` it takes
+        let element_class = AnnotatedElement::class(jvm)?;
+        let method = find_method(
+            jvm,
+            &element_class,
+            c"getAnnotation",
+            c"(Ljava/lang/Class;)Ljava/lang/annotation/Annotation;",
+            false,
+        )?;
+        let args = [jvalue {
+            l: class.as_raw().as_ptr(),
+        }];
+        let env = jvm.env();
+        // SAFETY: `method` is `AnnotatedElement.getAnnotation(Class)`, and `element` is an `AnnotatedElement`
+        let annotation: Option<Local<Annotation>> = unsafe {
+            env.invoke(
+                |env| env.CallObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        element.as_raw().as_ptr(),
+                        method.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }?;
+
+        let Some(annotation) = annotation else {
+            return Ok(None);
+        };
+        match (&*annotation).try_downcast::<A>().execute_with(jvm)? {
+            Ok(annotation) => Ok(Some(annotation)),
+            Err(_) => Err(Error::JvmInternal(
+                "getAnnotation returned an annotation of another class".into(),
+            )),
+        }
+    }
+}
+
+/// An annotation converted into Rust, with the values of all its attributes (including defaulted ones).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnnotationValues {
+    /// The fully qualified name of the annotation interface, e.g. `java.lang.Deprecated`.
+    pub annotation_type: String,
+    pub values: BTreeMap<String, AnnotationValue>,
+}
+
+/// The value of an annotation attribute, see [`AnnotationValues`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnnotationValue {
+    Boolean(bool),
+    Byte(i8),
+    Char(char),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    /// A class, by its fully qualified name.
+    Class(String),
+    /// An enum constant, by the fully qualified name of its enum and the name of the constant.
+    Enum {
+        class_name: String,
+        name: String,
+    },
+    Annotation(AnnotationValues),
+    Array(Vec<AnnotationValue>),
+}
+
+impl AnnotationValues {
+    /// The value of the attribute `name`, if the annotation has one.
+    pub fn get(&self, name: &str) -> Option<&AnnotationValue> {
+        self.values.get(name)
+    }
+}
+
+impl IntoRust<AnnotationValues> for &Annotation {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, AnnotationValues> {
+        let annotation = self;
+        let annotation_type = annotation
+            .annotation_type()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        // The attributes of an annotation are the methods of its interface
+        let mut values = BTreeMap::new();
+        let methods = annotation_type
+            .get_declared_methods()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        for index in 0..methods.length().execute_with(jvm)? {
+            let method = methods.get(index).assert_not_null().execute_with(jvm)?;
+            let name: String = method
+                .get_name()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            values.insert(name, attribute_value(jvm, annotation, &method)?);
+        }
+
+        Ok(AnnotationValues {
+            annotation_type: class_name(jvm, &annotation_type)?,
+            values,
+        })
+    }
+}
+
+/// Calls the method of `annotation` for an attribute. The method is called through JNI rather than with
+/// `Method.invoke`, which requires a Java caller (and access to the annotation interface).
+fn attribute_value<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    annotation: &Annotation,
+    method: &Method,
+) -> crate::Result<'jvm, AnnotationValue> {
+    let return_type = method
+        .get_return_type()
+        .assert_not_null()
+        .execute_with(jvm)?;
+    let return_type = if return_type.is_primitive().execute_with(jvm)? {
+        Some(class_name(jvm, &return_type)?)
+    } else {
+        None
+    };
+
+    let env = jvm.env();
+    let method_raw = method.as_raw().as_ptr();
+    // SAFETY: `method` is a live reference to a `java.lang.reflect.Method`
+    let method_id =
+        unsafe { env.invoke_unchecked(|env| env.FromReflectedMethod, |env, f| f(env, method_raw)) };
+    let Some(method_id) = MethodPtr::new(method_id) else {
+        return Err(Error::JvmInternal(
+            "FromReflectedMethod returned null".into(),
+        ));
+    };
+
+    macro_rules! call {
+        ($call_fn:ident) => {
+            // SAFETY: `method_id` is a method of `annotation`'s interface that takes no arguments, and returns the
+            // type that `$call_fn` expects
+            unsafe {
+                env.invoke(
+                    |env| env.$call_fn,
+                    |env, f| {
+                        f(
+                            env,
+                            annotation.as_raw().as_ptr(),
+                            method_id.as_ptr(),
+                            std::ptr::null(),
+                        )
+                    },
+                )
+            }?
+        };
+    }
+
+    Ok(match return_type.as_deref() {
+        Some("boolean") => AnnotationValue::Boolean(call!(CallBooleanMethodA)),
+        Some("byte") => AnnotationValue::Byte(call!(CallByteMethodA)),
+        Some("char") => AnnotationValue::Char(to_char(call!(CallCharMethodA))?),
+        Some("short") => AnnotationValue::Short(call!(CallShortMethodA)),
+        Some("int") => AnnotationValue::Int(call!(CallIntMethodA)),
+        Some("long") => AnnotationValue::Long(call!(CallLongMethodA)),
+        Some("float") => AnnotationValue::Float(call!(CallFloatMethodA)),
+        Some("double") => AnnotationValue::Double(call!(CallDoubleMethodA)),
+        Some(other) => {
+            return Err(Error::JvmInternal(format!(
+                "unexpected type `{other}` of annotation attribute"
+            )))
+        }
+        None => {
+            let value: Option<Local<Object>> = call!(CallObjectMethodA);
+            let value = value.ok_or(Error::NullDeref)?;
+            AnnotationValue::from_java(jvm, &value)?
+        }
+    })
+}
+
+impl AnnotationValue {
+    /// Converts the value of an attribute whose type isn't primitive (or an element of an array of them).
+    fn from_java<'jvm>(jvm: &mut Jvm<'jvm>, value: &Object) -> crate::Result<'jvm, Self> {
+        macro_rules! try_convert {
+            ($java:ty => |$v:ident| $convert:expr) => {
+                if let Ok(local) = value.try_downcast::<$java>().execute_with(jvm)? {
+                    let $v: &$java = &local;
+                    return Ok($convert);
+                }
+            };
+        }
+
+        try_convert!(java::lang::String => |v| AnnotationValue::String(v.to_rust().execute_with(jvm)?));
+        try_convert!(Class => |v| AnnotationValue::Class(class_name(jvm, v)?));
+        try_convert!(java::lang::Enum => |v| {
+            let class = v.get_declaring_class().assert_not_null().execute_with(jvm)?;
+            AnnotationValue::Enum {
+                class_name: class_name(jvm, &class)?,
+                name: v.name().assert_not_null().to_rust().execute_with(jvm)?,
+            }
+        });
+        try_convert!(Annotation => |v| AnnotationValue::Annotation(v.to_rust().execute_with(jvm)?));
+
+        // Arrays of primitives
+        macro_rules! try_convert_array {
+            ($($rust:ty => $variant:ident,)*) => {
+                $(
+                    try_convert!(java::Array<$rust> => |v| {
+                        let elements: Vec<$rust> = v.to_rust().execute_with(jvm)?;
+                        AnnotationValue::Array(elements.into_iter().map(AnnotationValue::$variant).collect())
+                    });
+                )*
+            };
+        }
+        try_convert_array! {
+            bool => Boolean,
+            i8 => Byte,
+            i16 => Short,
+            i32 => Int,
+            i64 => Long,
+            f32 => Float,
+            f64 => Double,
+        }
+        try_convert!(java::Array<u16> => |v| {
+            let elements: Vec<u16> = v.to_rust().execute_with(jvm)?;
+            let elements = elements
+                .into_iter()
+                .map(|c| Ok(AnnotationValue::Char(to_char(c)?)))
+                .collect::<Result<_, ConversionError>>()?;
+            AnnotationValue::Array(elements)
+        });
+
+        // Arrays of everything else
+        try_convert!(java::Array<Object> => |v| {
+            let mut elements = vec![];
+            for index in 0..v.length().execute_with(jvm)? {
+                let element = v.get(index).assert_not_null().execute_with(jvm)?;
+                elements.push(AnnotationValue::from_java(jvm, &element)?);
+            }
+            AnnotationValue::Array(elements)
+        });
+
+        let class = value.get_class().assert_not_null().execute_with(jvm)?;
+        Err(Error::JvmInternal(format!(
+            "unexpected annotation value of class `{}`",
+            class_name(jvm, &class)?
+        )))
+    }
+}
+
+fn class_name<'jvm>(jvm: &mut Jvm<'jvm>, class: &Class) -> crate::Result<'jvm, String> {
+    class
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)
+}
+
+/// Java `char`s are UTF-16 code units, so surrogates can't be converted on their own.
+fn to_char(c: u16) -> Result<char, ConversionError> {
+    char::from_u32(u32::from(c)).ok_or(ConversionError::OutOfRange {
+        value: c.to_string(),
+        target: "char",
+    })
+}
@@ -1,13 +1,17 @@
-use std::marker::PhantomData;
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+};
 
 use crate::{
     cast::Upcast,
+    into_rust::convert_element,
     java::{self, lang::Class},
     jvm::JavaView,
     plumbing::{FromRef, JavaObjectExt},
     to_java::ToJavaImpl,
-    AsJRef, Error, IntoRust, JDeref, JavaObject, JavaType, Jvm, JvmOp, Local, Nullable,
-    ScalarMethod, TryJDeref,
+    AsJRef, Error, GlobalResult, IntoJava, IntoRust, IntoScalar, JDeref, JavaObject, JavaType, Jvm,
+    JvmOp, Local, Nullable, ScalarMethod, TryJDeref,
 };
 
 pub struct JavaArray<T> {
@@ -46,16 +50,28 @@ unsafe impl<T: JavaType> JavaObject for JavaArray<T> {
     }
 }
 
+unsafe impl<T: JavaType> JavaType for JavaArray<T> {
+    fn array_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Class>> {
+        // Not cached, since a static can't be specific to `T`
+        Self::class(jvm)?
+            .array_type()
+            .assert_not_null()
+            .execute_with(jvm)
+    }
+}
+
 impl<T> JavaView for JavaArray<T> {
     type OfOp<J> = JavaArrayOp<T, J, <java::lang::Object as JavaView>::OfOpWith<J, ()>>;
 
-    type OfOpWith<J, N> = JavaArrayOp<T, J, N>
+    type OfOpWith<J, N>
+        = JavaArrayOp<T, J, N>
     where
         N: FromRef<J>;
 
     type OfObj<J> = JavaArrayObj<T, J, <java::lang::Object as JavaView>::OfObjWith<J, ()>>;
 
-    type OfObjWith<J, N> = JavaArrayObj<T, J, N>
+    type OfObjWith<J, N>
+        = JavaArrayObj<T, J, N>
     where
         N: FromRef<J>;
 }
@@ -115,6 +131,8 @@ where
 {
     type Output<'jvm> = jni_sys::jsize;
 
+    const LOCALS: usize = This::LOCALS;
+
     fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
         let this = self.this.execute_with(jvm)?;
         let this = this.as_jref()?.as_raw();
@@ -127,6 +145,216 @@ where
     }
 }
 
+impl<T: JavaObject> JavaArray<T> {
+    /// Creates a new `T[]` of length `len` whose elements are all null.
+    pub fn new_with_len<L>(len: L) -> NewObjectArray<T, L>
+    where
+        L: IntoScalar<i32>,
+    {
+        NewObjectArray {
+            len,
+            element: PhantomData,
+        }
+    }
+
+    /// Creates a new `T[]` holding the (non-null) `elements`, in order.
+    pub fn from_elements<'jvm, E>(
+        jvm: &mut Jvm<'jvm>,
+        elements: impl IntoIterator<Item = E>,
+    ) -> crate::Result<'jvm, Local<'jvm, Self>>
+    where
+        E: IntoJava<T>,
+    {
+        let elements: Vec<E> = elements.into_iter().collect();
+        let Ok(len) = elements.len().try_into() else {
+            return Err(Error::SliceTooLong(elements.len()));
+        };
+        let array = Self::new_with_len(len).execute_with(jvm)?;
+        for (index, element) in (0..len).zip(elements) {
+            (&array).set(index, element).execute_with(jvm)?;
+        }
+        Ok(array)
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct NewObjectArray<T, L: IntoScalar<i32>> {
+    len: L,
+    element: PhantomData<T>,
+}
+
+impl<T, L> JvmOp for NewObjectArray<T, L>
+where
+    T: JavaObject,
+    L: IntoScalar<i32>,
+{
+    type Output<'jvm> = Local<'jvm, JavaArray<T>>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let len = self.len.into_scalar(jvm)?;
+        let class = T::class(jvm)?;
+
+        let env = jvm.env();
+        // SAFETY: class is a live reference to the class of `T`. A negative length throws `NegativeArraySizeException`.
+        let array: Option<Local<JavaArray<T>>> = unsafe {
+            env.invoke(
+                |env| env.NewObjectArray,
+                |env, f| f(env, len, class.as_raw().as_ptr(), std::ptr::null_mut()),
+            )
+        }?;
+        array.ok_or_else(|| {
+            Error::JvmInternal(format!(
+                "failed to allocate `{}[{}]`",
+                std::any::type_name::<T>(),
+                len
+            ))
+        })
+    }
+}
+
+/// Element access for arrays of Java objects. Out-of-range indices throw `ArrayIndexOutOfBoundsException`.
+pub trait JavaObjectArrayExt<T: JavaObject>: JvmOp {
+    /// Returns the element at `index`, which may be null.
+    fn get<I>(self, index: I) -> GetElement<Self, T, I>
+    where
+        I: IntoScalar<i32>;
+
+    /// Stores `value` at `index`.
+    fn set<I, V>(self, index: I, value: V) -> SetElement<Self, T, I, V>
+    where
+        I: IntoScalar<i32>,
+        V: IntoJava<T>;
+}
+
+impl<This, T> JavaObjectArrayExt<T> for This
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: JavaObject,
+{
+    fn get<I>(self, index: I) -> GetElement<Self, T, I>
+    where
+        I: IntoScalar<i32>,
+    {
+        GetElement {
+            this: self,
+            index,
+            element: PhantomData,
+        }
+    }
+
+    fn set<I, V>(self, index: I, value: V) -> SetElement<Self, T, I, V>
+    where
+        I: IntoScalar<i32>,
+        V: IntoJava<T>,
+    {
+        SetElement {
+            this: self,
+            index,
+            value,
+            element: PhantomData,
+        }
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct GetElement<This: JvmOp, T, I: IntoScalar<i32>> {
+    this: This,
+    index: I,
+    element: PhantomData<T>,
+}
+
+impl<This, T, I> JvmOp for GetElement<This, T, I>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: JavaObject,
+    I: IntoScalar<i32>,
+{
+    type Output<'jvm> = Option<Local<'jvm, T>>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let this = this.as_jref()?.as_raw();
+        let index = self.index.into_scalar(jvm)?;
+
+        // SAFETY: this is a live reference to a `T[]`, so any element is an instance of `T` (or null)
+        unsafe {
+            jvm.env().invoke(
+                |env| env.GetObjectArrayElement,
+                |env, f| f(env, this.as_ptr(), index),
+            )
+        }
+    }
+}
+
+impl<This, T, I> std::ops::Deref for GetElement<This, T, I>
+where
+    This: JvmOp,
+    T: JavaObject,
+    I: IntoScalar<i32>,
+{
+    type Target = <T as JavaView>::OfOp<Self>;
+
+    fn deref(&self) -> &Self::Target {
+        <Self::Target as FromRef<_>>::from_ref(self)
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct SetElement<This: JvmOp, T: JavaObject, I: IntoScalar<i32>, V: IntoJava<T>> {
+    this: This,
+    index: I,
+    value: V,
+    element: PhantomData<T>,
+}
+
+impl<This, T, I, V> JvmOp for SetElement<This, T, I, V>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<JavaArray<T>>,
+    T: JavaObject,
+    I: IntoScalar<i32>,
+    V: IntoJava<T>,
+{
+    type Output<'jvm> = ();
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let this = this.as_jref()?.as_raw();
+        let index = self.index.into_scalar(jvm)?;
+        let value = self.value.into_java(jvm)?;
+        let value = value.as_jref()?.as_raw();
+
+        // SAFETY: this is a live reference to a `T[]` and value is an instance of `T`
+        unsafe {
+            jvm.env().invoke(
+                |env| env.SetObjectArrayElement,
+                |env, f| f(env, this.as_ptr(), index, value.as_ptr()),
+            )
+        }
+    }
+}
+
+/// Converts each (non-null) element of the array.
+impl<R, T> IntoRust<Vec<R>> for &JavaArray<T>
+where
+    T: JavaObject,
+    for<'a> &'a T: IntoRust<R>,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<R>> {
+        let len = self.length().execute_with(jvm)?;
+        let mut vec = Vec::with_capacity(len.max(0) as usize);
+        for index in 0..len {
+            vec.push(convert_element(jvm, |jvm| {
+                let element = self.get(index).execute_with(jvm)?.ok_or(Error::NullDeref)?;
+                <&T as IntoRust<R>>::into_rust(&element, jvm)
+            })?);
+        }
+        Ok(vec)
+    }
+}
+
 macro_rules! primivite_array {
     ($([$rust:ty]: $java_name:literal $java_ty:ident $new_fn:ident $get_fn:ident $set_fn:ident,)*) => {
         $(
@@ -168,6 +396,22 @@ macro_rules! primivite_array {
                 }
             }
 
+            impl JvmOp for &Vec<$rust> {
+                type Output<'jvm> = Local<'jvm, JavaArray<$rust>>;
+
+                fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+                    <&[$rust] as JvmOp>::execute_with(self, jvm)
+                }
+            }
+
+            impl<const N: usize> JvmOp for &[$rust; N] {
+                type Output<'jvm> = Local<'jvm, JavaArray<$rust>>;
+
+                fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+                    <&[$rust] as JvmOp>::execute_with(self, jvm)
+                }
+            }
+
             impl ToJavaImpl<java::Array<$rust>> for [$rust] {
                 fn to_java_impl<'jvm>(
                     rust: &Self,
@@ -221,3 +465,218 @@ primivite_array! {
     [f32]: "float" jfloat NewFloatArray GetFloatArrayRegion SetFloatArrayRegion,
     [f64]: "double" jdouble NewDoubleArray GetDoubleArrayRegion SetDoubleArrayRegion,
 }
+
+// Whether the current thread is accessing the elements of an array with `with_elements`, during which it must not make
+// any JNI calls. `Jvm::with` checks it, so that the closure can't use the JVM through it (e.g., by calling `execute`),
+// and the Java references dropped meanwhile are deleted once the section ends.
+thread_local! {
+    static IN_CRITICAL_SECTION: Cell<bool> = const { Cell::new(false) };
+    static DEFERRED_DELETES: RefCell<Vec<Box<dyn FnOnce()>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Fails with [`Error::InCriticalSection`] if the current thread is in a `with_elements` closure.
+pub(crate) fn check_not_in_critical_section() -> GlobalResult<()> {
+    if IN_CRITICAL_SECTION.get() {
+        return Err(Error::InCriticalSection);
+    }
+    Ok(())
+}
+
+/// Runs `delete`, which deletes a dropped Java reference, right away, or once the critical section ends if the current
+/// thread is in one (since deleting a reference is a JNI call).
+pub(crate) fn delete_outside_critical_section(delete: impl FnOnce() + 'static) {
+    // `try_with` because references may be dropped from other thread-local destructors during thread exit
+    if IN_CRITICAL_SECTION.try_with(Cell::get).unwrap_or(false) {
+        DEFERRED_DELETES.with_borrow_mut(|deletes| deletes.push(Box::new(delete)));
+    } else {
+        delete();
+    }
+}
+
+/// Marks the current thread as being in a critical section until dropped, including when the closure in it panics.
+/// It must be dropped after the elements are released, since it then deletes the references dropped in the section.
+struct CriticalSection;
+
+impl CriticalSection {
+    fn enter() -> Self {
+        IN_CRITICAL_SECTION.set(true);
+        CriticalSection
+    }
+}
+
+impl Drop for CriticalSection {
+    fn drop(&mut self) {
+        IN_CRITICAL_SECTION.set(false);
+        for delete in DEFERRED_DELETES.take() {
+            delete();
+        }
+    }
+}
+
+/// Releases the elements of a primitive array that were pinned or copied for Rust, including when the closure using
+/// them panics.
+struct ReleaseElements<F: FnMut()> {
+    release: F,
+}
+
+impl<F: FnMut()> Drop for ReleaseElements<F> {
+    fn drop(&mut self) {
+        (self.release)();
+    }
+}
+
+macro_rules! primitive_array_elements {
+    ($([$rust:ty]: $get_fn:ident $release_fn:ident,)*) => {
+        $(
+            impl JavaArray<$rust> {
+                /// Calls `f` with the elements of the array, accessed with `GetPrimitiveArrayCritical`, which usually
+                /// avoids copying them. Changes that `f` makes are written back to the array.
+                ///
+                /// While `f` runs, the JVM may pause garbage collection or block other threads, so `f` should be
+                /// quick, and it can't use the JVM (see [`JavaArray::with_elements_non_critical`] for that): `Jvm::with`
+                /// (and so `execute`) fails with [`Error::InCriticalSection`] until `f` returns. The Java references
+                /// that `f` drops are deleted once the elements are released.
+                pub fn with_elements<'jvm, R>(
+                    &self,
+                    jvm: &mut Jvm<'jvm>,
+                    f: impl FnOnce(&mut [$rust]) -> R,
+                ) -> crate::Result<'jvm, R> {
+                    let len = self.length().execute_with(jvm)? as usize;
+                    let env = jvm.env();
+                    let array = self.as_raw().as_ptr();
+                    // SAFETY: `array` is a live reference to an array
+                    let elements = unsafe {
+                        env.invoke_unchecked(
+                            |env| env.GetPrimitiveArrayCritical,
+                            |env, f| f(env, array, std::ptr::null_mut()),
+                        )
+                    };
+                    if elements.is_null() {
+                        env.check_exception()?;
+                        return Err(Error::JvmInternal(
+                            "failed to access the elements of an array".into(),
+                        ));
+                    }
+                    // `f` has no `Jvm`, and the critical section keeps it from getting one with `Jvm::with`. It is
+                    // declared before `_release` so that it ends after the elements are released.
+                    let _critical_section = CriticalSection::enter();
+                    let _release = ReleaseElements {
+                        // SAFETY: `elements` were returned by `GetPrimitiveArrayCritical` for `array`
+                        release: || unsafe {
+                            env.invoke_unchecked(
+                                |env| env.ReleasePrimitiveArrayCritical,
+                                |env, f| f(env, array, elements, 0),
+                            )
+                        },
+                    };
+                    // SAFETY: the array has `len` elements of type `$rust`, which are pinned until released
+                    let elements = unsafe { std::slice::from_raw_parts_mut(elements.cast::<$rust>(), len) };
+                    Ok(f(elements))
+                }
+
+                /// Like [`JavaArray::with_elements`], but with `Get<Type>ArrayElements`, which may copy the elements
+                /// and doesn't restrict what `f` does with the JVM meanwhile. Changes that `f` makes are written back
+                /// to the array, whether or not it succeeds.
+                pub fn with_elements_non_critical<'jvm, R>(
+                    &self,
+                    jvm: &mut Jvm<'jvm>,
+                    f: impl FnOnce(&mut Jvm<'jvm>, &mut [$rust]) -> crate::Result<'jvm, R>,
+                ) -> crate::Result<'jvm, R> {
+                    let len = self.length().execute_with(jvm)? as usize;
+                    let env = jvm.env();
+                    let array = self.as_raw().as_ptr();
+                    // SAFETY: `array` is a live reference to an array of `$rust`
+                    let elements = unsafe {
+                        env.invoke_unchecked(
+                            |env| env.$get_fn,
+                            |env, f| f(env, array, std::ptr::null_mut()),
+                        )
+                    };
+                    if elements.is_null() {
+                        env.check_exception()?;
+                        return Err(Error::JvmInternal(
+                            "failed to access the elements of an array".into(),
+                        ));
+                    }
+                    let _release = ReleaseElements {
+                        // SAFETY: `elements` were returned by `$get_fn` for `array`
+                        release: || unsafe {
+                            env.invoke_unchecked(
+                                |env| env.$release_fn,
+                                |env, f| f(env, array, elements, 0),
+                            )
+                        },
+                    };
+                    // SAFETY: `elements` holds the `len` elements of the array until released
+                    let elements = unsafe { std::slice::from_raw_parts_mut(elements.cast::<$rust>(), len) };
+                    f(jvm, elements)
+                }
+            }
+        )*
+    };
+}
+
+primitive_array_elements! {
+    [bool]: GetBooleanArrayElements ReleaseBooleanArrayElements,
+    [i8]: GetByteArrayElements ReleaseByteArrayElements,
+    [u16]: GetCharArrayElements ReleaseCharArrayElements,
+    [i16]: GetShortArrayElements ReleaseShortArrayElements,
+    [i32]: GetIntArrayElements ReleaseIntArrayElements,
+    [i64]: GetLongArrayElements ReleaseLongArrayElements,
+    [f32]: GetFloatArrayElements ReleaseFloatArrayElements,
+    [f64]: GetDoubleArrayElements ReleaseDoubleArrayElements,
+}
+
+// Rust byte buffers are usually `u8`s, so allow them wherever a Java `byte[]` is expected. The bits are reinterpreted
+// as-is, e.g. `0xFF_u8` is `-1` in Java.
+macro_rules! unsigned_byte_array {
+    ($($self_ty:ty,)*) => {
+        $(
+            impl JvmOp for $self_ty {
+                type Output<'jvm> = Local<'jvm, JavaArray<i8>>;
+
+                fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+                    let bytes: &[u8] = self.as_ref();
+                    // SAFETY: u8 and i8 have the same size and alignment
+                    let bytes: &[i8] = unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len()) };
+                    bytes.execute_with(jvm)
+                }
+            }
+        )*
+    };
+}
+
+unsigned_byte_array! {
+    &[u8],
+    &Vec<u8>,
+}
+
+impl<const N: usize> JvmOp for &[u8; N] {
+    type Output<'jvm> = Local<'jvm, JavaArray<i8>>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        self.as_slice().execute_with(jvm)
+    }
+}
+
+impl ToJavaImpl<java::Array<i8>> for [u8] {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::Array<i8>>>> {
+        Ok(Some(rust.execute_with(jvm)?))
+    }
+}
+
+impl IntoRust<Vec<u8>> for &JavaArray<i8> {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<u8>> {
+        let bytes: Vec<i8> = self.into_rust(jvm)?;
+        let mut bytes = std::mem::ManuallyDrop::new(bytes);
+        // SAFETY: u8 and i8 have the same size and alignment, so the allocation can be reused as is
+        Ok(
+            unsafe {
+                Vec::from_raw_parts(bytes.as_mut_ptr().cast(), bytes.len(), bytes.capacity())
+            },
+        )
+    }
+}
@@ -0,0 +1,447 @@
+//! Reading and writing the properties of JavaBeans by name (see [`JvmOp::get_property`] and
+//! [`JvmOp::set_property`]), for configuration and templating layers that work with arbitrary objects.
+//!
+//! A property `name` is read with the public instance method `getName()` (or `isName()`, if it returns a `boolean`)
+//! and written with the public instance method `setName(value)`, which returns `void`. If a class has several such
+//! setters, the first one that reflection reports is used. The accessors are looked up with reflection once per class
+//! and property, and called with JNI from then on.
+//!
+//! Values are converted to and from [`BeanValue`]s: primitives and strings into their Rust equivalents, and other
+//! objects into global references. Setting a primitive property takes a value of exactly its type, since primitives
+//! aren't boxed or widened.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    sync::RwLock,
+};
+
+use crate::{
+    array::{JavaArrayExt, JavaObjectArrayExt},
+    call,
+    descriptor::Kind,
+    java::{self, lang::Class},
+    jvm::JavaObjectExt,
+    raw::{IntoJniValue, MethodPtr, ObjectPtr},
+    AsJRef, ConversionError, Error, Global, Jvm, JvmOp, Local, ToJava,
+};
+
+/// The value of a property, see the [module docs](self).
+#[derive(PartialEq)]
+pub enum BeanValue {
+    Null,
+    Boolean(bool),
+    Byte(i8),
+    Char(char),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    /// Any other object, including boxed primitives.
+    Object(Global<java::lang::Object>),
+}
+
+impl Debug for BeanValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BeanValue::Null => write!(f, "Null"),
+            BeanValue::Boolean(v) => f.debug_tuple("Boolean").field(v).finish(),
+            BeanValue::Byte(v) => f.debug_tuple("Byte").field(v).finish(),
+            BeanValue::Char(v) => f.debug_tuple("Char").field(v).finish(),
+            BeanValue::Short(v) => f.debug_tuple("Short").field(v).finish(),
+            BeanValue::Int(v) => f.debug_tuple("Int").field(v).finish(),
+            BeanValue::Long(v) => f.debug_tuple("Long").field(v).finish(),
+            BeanValue::Float(v) => f.debug_tuple("Float").field(v).finish(),
+            BeanValue::Double(v) => f.debug_tuple("Double").field(v).finish(),
+            BeanValue::String(v) => f.debug_tuple("String").field(v).finish(),
+            BeanValue::Object(_) => write!(f, "Object(..)"),
+        }
+    }
+}
+
+macro_rules! bean_value_from {
+    ($($rust:ty => $variant:ident,)*) => {
+        $(
+            impl From<$rust> for BeanValue {
+                fn from(value: $rust) -> Self {
+                    BeanValue::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+bean_value_from! {
+    bool => Boolean,
+    i8 => Byte,
+    char => Char,
+    i16 => Short,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    String => String,
+    Global<java::lang::Object> => Object,
+}
+
+impl From<&str> for BeanValue {
+    fn from(value: &str) -> Self {
+        BeanValue::String(value.to_string())
+    }
+}
+
+/// [`JvmOp`][] that reads a property of an object, see [`JvmOp::get_property`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct GetProperty<'a, This: JvmOp> {
+    this: This,
+    name: &'a str,
+}
+
+impl<'a, This> GetProperty<'a, This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<java::lang::Object>,
+{
+    pub(crate) fn new(this: This, name: &'a str) -> Self {
+        GetProperty { this, name }
+    }
+}
+
+impl<This> JvmOp for GetProperty<'_, This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<java::lang::Object>,
+{
+    type Output<'jvm> = BeanValue;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, BeanValue> {
+        let object = self.this.execute_with(jvm)?;
+        let object = object.as_jref()?;
+        let (class, property) = property(jvm, object, self.name)?;
+        let Some(getter) = property.getter else {
+            return no_accessor(jvm, &class, "readable", self.name);
+        };
+        let this = object.as_raw();
+        let method = getter.method;
+        // SAFETY: the getter is a public instance method of the class of `object`, without parameters, returning
+        // `getter.kind`
+        unsafe {
+            Ok(match getter.kind {
+                Kind::Boolean => BeanValue::Boolean(call::boolean_method(jvm, this, method, &[])?),
+                Kind::Byte => BeanValue::Byte(call::byte_method(jvm, this, method, &[])?),
+                Kind::Char => {
+                    let c = call::char_method(jvm, this, method, &[])?;
+                    BeanValue::Char(char::from_u32(c.into()).ok_or_else(|| {
+                        ConversionError::OutOfRange {
+                            value: format!("{c:#x}"),
+                            target: "char",
+                        }
+                    })?)
+                }
+                Kind::Short => BeanValue::Short(call::short_method(jvm, this, method, &[])?),
+                Kind::Int => BeanValue::Int(call::int_method(jvm, this, method, &[])?),
+                Kind::Long => BeanValue::Long(call::long_method(jvm, this, method, &[])?),
+                Kind::Float => BeanValue::Float(call::float_method(jvm, this, method, &[])?),
+                Kind::Double => BeanValue::Double(call::double_method(jvm, this, method, &[])?),
+                Kind::Object => match call::any_object_method(jvm, this, method, &[])? {
+                    None => BeanValue::Null,
+                    Some(value) => match value
+                        .try_downcast::<java::lang::String>()
+                        .execute_with(jvm)?
+                    {
+                        Ok(string) => BeanValue::String((&*string).to_rust().execute_with(jvm)?),
+                        Err(value) => BeanValue::Object(jvm.global(&*value)),
+                    },
+                },
+            })
+        }
+    }
+}
+
+/// [`JvmOp`][] that writes a property of an object, see [`JvmOp::set_property`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct SetProperty<'a, This: JvmOp> {
+    this: This,
+    name: &'a str,
+    value: &'a BeanValue,
+}
+
+impl<'a, This> SetProperty<'a, This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<java::lang::Object>,
+{
+    pub(crate) fn new(this: This, name: &'a str, value: &'a BeanValue) -> Self {
+        SetProperty { this, name, value }
+    }
+}
+
+impl<This> JvmOp for SetProperty<'_, This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<java::lang::Object>,
+{
+    type Output<'jvm> = ();
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        let object = self.this.execute_with(jvm)?;
+        let object = object.as_jref()?;
+        let (class, property) = property(jvm, object, self.name)?;
+        let Some(setter) = property.setter else {
+            return no_accessor(jvm, &class, "writable", self.name);
+        };
+
+        // Keeps the Java string of a `BeanValue::String` alive until the call
+        let mut string: Option<Local<'jvm, java::lang::String>> = None;
+        let arg = match (setter.kind, self.value) {
+            (Kind::Boolean, BeanValue::Boolean(v)) => v.into_jni_value(),
+            (Kind::Byte, BeanValue::Byte(v)) => v.into_jni_value(),
+            (Kind::Char, BeanValue::Char(v)) => {
+                let Ok(c) = u16::try_from(u32::from(*v)) else {
+                    return Err(ConversionError::OutOfRange {
+                        value: format!("{v:?}"),
+                        target: "char",
+                    }
+                    .into());
+                };
+                c.into_jni_value()
+            }
+            (Kind::Short, BeanValue::Short(v)) => v.into_jni_value(),
+            (Kind::Int, BeanValue::Int(v)) => v.into_jni_value(),
+            (Kind::Long, BeanValue::Long(v)) => v.into_jni_value(),
+            (Kind::Float, BeanValue::Float(v)) => v.into_jni_value(),
+            (Kind::Double, BeanValue::Double(v)) => v.into_jni_value(),
+            (Kind::Object, BeanValue::Null) => None::<&java::lang::Object>.into_jni_value(),
+            (Kind::Object, BeanValue::String(v)) => {
+                let s = string.insert(
+                    v.to_java::<java::lang::String>()
+                        .assert_not_null()
+                        .execute_with(jvm)?,
+                );
+                (&**s).into_jni_value()
+            }
+            (Kind::Object, BeanValue::Object(v)) => (&**v).into_jni_value(),
+            (kind, value) => {
+                return Err(ConversionError::Malformed {
+                    value: format!("{value:?}"),
+                    target: kind_name(kind),
+                }
+                .into())
+            }
+        };
+        // SAFETY: the setter is a public instance method of the class of `object` with a single parameter of
+        // `setter.kind`, which `arg` matches; the JVM checks the class of object arguments
+        unsafe { call::void_method(jvm, object.as_raw(), setter.method, &[arg]) }
+    }
+}
+
+/// An accessor of a property, which takes or returns a value of `kind`.
+#[derive(Copy, Clone)]
+struct Accessor {
+    method: MethodPtr,
+    kind: Kind,
+}
+
+#[derive(Copy, Clone)]
+struct Property {
+    getter: Option<Accessor>,
+    setter: Option<Accessor>,
+}
+
+/// The accessors found so far, by property name and then class.
+static PROPERTIES: RwLock<Option<HashMap<String, Vec<(Global<Class>, Property)>>>> =
+    RwLock::new(None);
+
+/// Returns the class of `object` and the accessors of its property `name`, looking them up the first time.
+fn property<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    object: &java::lang::Object,
+    name: &str,
+) -> crate::Result<'jvm, (Local<'jvm, Class>, Property)> {
+    let env = jvm.env();
+    // SAFETY: `object` is a live reference; GetObjectClass never returns null for a non-null object.
+    let class: Local<Class> = unsafe {
+        let class = env.invoke_unchecked(
+            |env| env.GetObjectClass,
+            |env, f| f(env, object.as_raw().as_ptr()),
+        );
+        Local::from_raw(env, ObjectPtr::new(class).unwrap())
+    };
+
+    {
+        let properties = PROPERTIES.read().unwrap_or_else(|e| e.into_inner());
+        let classes = properties
+            .as_ref()
+            .and_then(|properties| properties.get(name));
+        for (cached, property) in classes.into_iter().flatten() {
+            if is_same_class(jvm, cached, &class) {
+                return Ok((class, *property));
+            }
+        }
+    }
+
+    // Not found: look the accessors up without holding the lock, since reflection may run Java code
+    let property = find_property(jvm, &class, name)?;
+    let mut properties = PROPERTIES.write().unwrap_or_else(|e| e.into_inner());
+    properties
+        .get_or_insert_with(HashMap::new)
+        .entry(name.to_string())
+        .or_default()
+        .push((jvm.global(&*class), property));
+    Ok((class, property))
+}
+
+fn is_same_class(jvm: &mut Jvm<'_>, a: &Class, b: &Class) -> bool {
+    // SAFETY: both are live references
+    unsafe {
+        jvm.env().invoke_unchecked(
+            |env| env.IsSameObject,
+            |env, f| f(env, a.as_raw().as_ptr(), b.as_raw().as_ptr()),
+        ) == jni_sys::JNI_TRUE
+    }
+}
+
+/// Looks up the accessors of the property `name` among the public methods of `class`.
+fn find_property<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    class: &Class,
+    name: &str,
+) -> crate::Result<'jvm, Property> {
+    let mut chars = name.chars();
+    let capitalized = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+        None => String::new(),
+    };
+    let (getter_name, is_getter_name, setter_name) = (
+        format!("get{capitalized}"),
+        format!("is{capitalized}"),
+        format!("set{capitalized}"),
+    );
+
+    let mut property = Property {
+        getter: None,
+        setter: None,
+    };
+    let methods = class.get_methods().assert_not_null().execute_with(jvm)?;
+    for index in 0..methods.length().execute_with(jvm)? {
+        let method = methods.get(index).assert_not_null().execute_with(jvm)?;
+        // `java.lang.reflect.Modifier.STATIC`
+        if method.get_modifiers().execute_with(jvm)? & 0x8 != 0 {
+            continue;
+        }
+        let method_name: String = method
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let parameters = method.get_parameter_count().execute_with(jvm)?;
+        let return_kind = {
+            let return_type = method
+                .get_return_type()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            type_kind(jvm, &return_type)?
+        };
+
+        if property.getter.is_none() && parameters == 0 {
+            let kind = match return_kind {
+                Some(kind) if method_name == getter_name => Some(kind),
+                Some(Kind::Boolean) if method_name == is_getter_name => Some(Kind::Boolean),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                property.getter = Some(Accessor {
+                    method: from_reflected(jvm, &method)?,
+                    kind,
+                });
+            }
+        } else if property.setter.is_none()
+            && parameters == 1
+            && return_kind.is_none()
+            && method_name == setter_name
+        {
+            let parameter = method
+                .get_parameter_types()
+                .assert_not_null()
+                .get(0)
+                .assert_not_null()
+                .execute_with(jvm)?;
+            if let Some(kind) = type_kind(jvm, &parameter)? {
+                property.setter = Some(Accessor {
+                    method: from_reflected(jvm, &method)?,
+                    kind,
+                });
+            }
+        }
+    }
+    Ok(property)
+}
+
+/// The kind of values of `class`, or `None` for `void`.
+fn type_kind<'jvm>(jvm: &mut Jvm<'jvm>, class: &Class) -> crate::Result<'jvm, Option<Kind>> {
+    if !class.is_primitive().execute_with(jvm)? {
+        return Ok(Some(Kind::Object));
+    }
+    let name: String = class
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    Ok(match &name[..] {
+        "boolean" => Some(Kind::Boolean),
+        "byte" => Some(Kind::Byte),
+        "char" => Some(Kind::Char),
+        "short" => Some(Kind::Short),
+        "int" => Some(Kind::Int),
+        "long" => Some(Kind::Long),
+        "float" => Some(Kind::Float),
+        "double" => Some(Kind::Double),
+        _ => None,
+    })
+}
+
+fn kind_name(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Object => "object",
+        Kind::Boolean => "boolean",
+        Kind::Byte => "byte",
+        Kind::Char => "char",
+        Kind::Short => "short",
+        Kind::Int => "int",
+        Kind::Long => "long",
+        Kind::Float => "float",
+        Kind::Double => "double",
+    }
+}
+
+fn from_reflected<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    method: &java::lang::reflect::Method,
+) -> crate::Result<'jvm, MethodPtr> {
+    // SAFETY: `method` is a live reference to a `Method`
+    let id = unsafe {
+        jvm.env().invoke_unchecked(
+            |env| env.FromReflectedMethod,
+            |env, f| f(env, method.as_raw().as_ptr()),
+        )
+    };
+    MethodPtr::new(id).ok_or_else(|| Error::JvmInternal("FromReflectedMethod returned null".into()))
+}
+
+fn no_accessor<'jvm, T>(
+    jvm: &mut Jvm<'jvm>,
+    class: &Class,
+    access: &str,
+    name: &str,
+) -> crate::Result<'jvm, T> {
+    let class_name: String = class
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    Err(Error::JvmInternal(format!(
+        "`{class_name}` has no {access} property `{name}`"
+    )))
+}
@@ -0,0 +1,164 @@
+//! Conversions of `i128` and `u128` to and from `java.math.BigInteger`, through the two's complement of the value, most
+//! significant byte first, as taken by `new BigInteger(byte[])` and returned by `BigInteger.toByteArray()`.
+//!
+//! With the `num-bigint` feature, `num_bigint::BigInt` converts to and from `BigInteger` through the same bytes, without
+//! a limit on its size. A `BigDecimal` converts to and from a [`money::Decimal`](crate::money::Decimal), whose unscaled
+//! value is an `i128`.
+
+use crate::{
+    error::ConversionError,
+    into_rust::{into_rust_or_conversion_error, TryIntoRust},
+    java,
+    to_java::ToJavaImpl,
+    IntoRust, Jvm, JvmOp, Local,
+};
+
+/// The two's complement of `value`, as taken by `new BigInteger(byte[])`.
+pub(crate) fn to_twos_complement(value: i128) -> [i8; 16] {
+    value.to_be_bytes().map(|b| b as i8)
+}
+
+/// The value of a two's complement as returned by `BigInteger.toByteArray()`, or `None` if it doesn't fit in an
+/// `i128`.
+pub(crate) fn from_twos_complement(bytes: &[i8]) -> Option<i128> {
+    if bytes.len() > 16 {
+        return None;
+    }
+    let sign_extension = if bytes.first().is_some_and(|&b| b < 0) {
+        0xFF
+    } else {
+        0
+    };
+    let mut be_bytes = [sign_extension; 16];
+    for (byte, &b) in be_bytes[16 - bytes.len()..].iter_mut().zip(bytes) {
+        *byte = b as u8;
+    }
+    Some(i128::from_be_bytes(be_bytes))
+}
+
+/// The two's complement of a `BigInteger`, or the result of converting it to a string if `convert` fails, for the
+/// `OutOfRange` error.
+fn convert<'jvm, R>(
+    jvm: &mut Jvm<'jvm>,
+    integer: &java::math::BigInteger,
+    target: &'static str,
+    convert: impl FnOnce(&[i8]) -> Option<R>,
+) -> crate::Result<'jvm, Result<R, ConversionError>> {
+    let bytes: Vec<i8> = integer
+        .to_byte_array()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    if let Some(value) = convert(&bytes) {
+        return Ok(Ok(value));
+    }
+    let value: String = integer
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    Ok(Err(ConversionError::OutOfRange { value, target }))
+}
+
+impl ToJavaImpl<java::math::BigInteger> for i128 {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::math::BigInteger>>> {
+        let bytes = to_twos_complement(*rust);
+        Ok(Some(
+            java::math::BigInteger::new(&bytes[..]).execute_with(jvm)?,
+        ))
+    }
+}
+
+impl ToJavaImpl<java::math::BigInteger> for u128 {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::math::BigInteger>>> {
+        // A leading zero byte keeps values from `2^127` on positive
+        let mut bytes = [0; 17];
+        for (byte, b) in bytes[1..].iter_mut().zip(rust.to_be_bytes()) {
+            *byte = b as i8;
+        }
+        Ok(Some(
+            java::math::BigInteger::new(&bytes[..]).execute_with(jvm)?,
+        ))
+    }
+}
+
+impl IntoRust<i128> for &java::math::BigInteger {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, i128> {
+        into_rust_or_conversion_error(self, jvm)
+    }
+}
+
+/// Fails if the value doesn't fit in an `i128`.
+impl TryIntoRust<i128> for &java::math::BigInteger {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<i128, ConversionError>> {
+        convert(jvm, self, "i128", from_twos_complement)
+    }
+}
+
+impl IntoRust<u128> for &java::math::BigInteger {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, u128> {
+        into_rust_or_conversion_error(self, jvm)
+    }
+}
+
+/// Fails if the value is negative or doesn't fit in a `u128`.
+impl TryIntoRust<u128> for &java::math::BigInteger {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<u128, ConversionError>> {
+        convert(jvm, self, "u128", |bytes| match bytes {
+            [first, ..] if *first < 0 => None,
+            // Values from `2^127` on take 17 bytes, the first being zero
+            [0, rest @ ..] if rest.len() == 16 => Some(
+                rest.iter()
+                    .fold(0, |value, &b| value << 8 | u128::from(b as u8)),
+            ),
+            _ => from_twos_complement(bytes).map(|value| value as u128),
+        })
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+mod num_bigint_conversions {
+    use num_bigint::BigInt;
+
+    use crate::{java, to_java::ToJavaImpl, IntoRust, Jvm, JvmOp, Local};
+
+    impl ToJavaImpl<java::math::BigInteger> for BigInt {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::math::BigInteger>>> {
+            let bytes: Vec<i8> = rust
+                .to_signed_bytes_be()
+                .into_iter()
+                .map(|b| b as i8)
+                .collect();
+            Ok(Some(
+                java::math::BigInteger::new(&bytes[..]).execute_with(jvm)?,
+            ))
+        }
+    }
+
+    impl IntoRust<BigInt> for &java::math::BigInteger {
+        fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, BigInt> {
+            let bytes: Vec<i8> = self
+                .to_byte_array()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            let bytes: Vec<u8> = bytes.into_iter().map(|b| b as u8).collect();
+            Ok(BigInt::from_signed_bytes_be(&bytes))
+        }
+    }
+}
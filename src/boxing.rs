@@ -0,0 +1,62 @@
+//! Conversions between Rust scalars and the Java classes that box them, e.g. `42.to_java::<java::lang::Integer>()`
+//! and `IntoRust<i32>` for `java.lang.Integer`, so that generic Java collections of boxed values (e.g. a
+//! `java.util.List<java.lang.Integer>`) convert to and from Rust collections of scalars.
+//!
+//! Each wrapper class converts to and from the scalar of its primitive type (`Character` to and from `u16`, like a
+//! Java `char`). A `java.lang.Number` converts to any numeric scalar, through its `intValue()`, `doubleValue()`, etc.
+//! methods, which round or truncate the number like a Java cast does.
+
+use crate::{java, to_java::ToJavaImpl, IntoRust, Jvm, JvmOp, Local};
+
+macro_rules! boxing {
+    ($($rust:ty => $java:ident, $value:ident;)*) => {
+        $(
+            impl ToJavaImpl<java::lang::$java> for $rust {
+                fn to_java_impl<'jvm>(
+                    rust: &Self,
+                    jvm: &mut Jvm<'jvm>,
+                ) -> crate::Result<'jvm, Option<Local<'jvm, java::lang::$java>>> {
+                    java::lang::$java::value_of(*rust).execute_with(jvm)
+                }
+            }
+
+            impl IntoRust<$rust> for &java::lang::$java {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $rust> {
+                    self.$value().execute_with(jvm)
+                }
+            }
+        )*
+    };
+}
+
+boxing! {
+    bool => Boolean, boolean_value;
+    u16 => Character, char_value;
+    i8 => Byte, byte_value;
+    i16 => Short, short_value;
+    i32 => Integer, int_value;
+    i64 => Long, long_value;
+    f32 => Float, float_value;
+    f64 => Double, double_value;
+}
+
+macro_rules! unbox_number {
+    ($($rust:ty => $value:ident;)*) => {
+        $(
+            impl IntoRust<$rust> for &java::lang::Number {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $rust> {
+                    self.$value().execute_with(jvm)
+                }
+            }
+        )*
+    };
+}
+
+unbox_number! {
+    i8 => byte_value;
+    i16 => short_value;
+    i32 => int_value;
+    i64 => long_value;
+    f32 => float_value;
+    f64 => double_value;
+}
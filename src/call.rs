@@ -0,0 +1,641 @@
+//! Calling Java methods for which no bindings were generated, through the same functions as generated code.
+//!
+//! [`call_method`] and [`call_static_method`] look the method up by name and JNI descriptor, and return its result
+//! as the [`CallResult`] they are asked for. In debug builds, they check the descriptor against the arguments and the
+//! result type. Exceptions are returned as [`Error::Thrown`](crate::Error::Thrown) and objects as [`Local`]s, as for
+//! generated methods.
+//!
+//! The functions they end up in take raw handles instead: one for each JNI calling shape (instance or static, and the
+//! kind of value returned), which generated code calls rather than carrying the code of a JNI invocation for each
+//! method of each binding. Those that return objects are generic, but only cast the result of a shared function. They
+//! skip the lookup, so they are the way to call a method repeatedly: find it once with [`find_method`], and convert
+//! the arguments with [`IntoJniValue`] and the target with [`JavaObjectExt::as_raw`].
+//!
+//! The [`call!`](crate::call!) macro is the safe way in: it builds the descriptor from the types of the Rust arguments
+//! and of the expected result, so the JVM picks the overload that they match, like `javac` would for arguments of
+//! exactly those types. It does so at runtime, looking the method up on every call, so it is meant for methods that
+//! are called now and then; generated bindings resolve their overloads once.
+//!
+//! # Safety
+//!
+//! All of them require `method` to be a method (or constructor) of the class of `this` (or of `class`) whose
+//! parameters match `args` and whose return type matches the function's, and `this` or `class` to be live references.
+//! Each argument is a [`jvalue`] with the field of its parameter's type set (e.g. `i` for `int`, and `l` for objects,
+//! which must be live references too or null).
+
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    ffi::{CStr, CString},
+    marker::PhantomData,
+    sync::Mutex,
+};
+
+use crate::{
+    java::lang::{Class, Object},
+    Error, JavaObject, Jvm, JvmOp, Local,
+};
+
+pub use crate::find::find_method;
+pub use crate::jvm::JavaObjectExt;
+pub use crate::raw::{IntoJniValue, MethodPtr, ObjectPtr};
+pub use jni_sys::jvalue;
+
+macro_rules! call_shims {
+    ($($method:ident, $static_method:ident: $ty:ty = $jni_method:ident, $jni_static_method:ident;)*) => {
+        $(
+            /// Calls the instance method `method` of `this`.
+            ///
+            /// # Safety
+            ///
+            /// See the [module docs](self).
+            pub unsafe fn $method<'jvm>(
+                jvm: &mut Jvm<'jvm>,
+                this: ObjectPtr,
+                method: MethodPtr,
+                args: &[jvalue],
+            ) -> crate::Result<'jvm, $ty> {
+                unsafe {
+                    jvm.env().invoke(
+                        |env| env.$jni_method,
+                        |env, f| f(env, this.as_ptr(), method.as_ptr(), args.as_ptr()),
+                    )
+                }
+            }
+
+            /// Calls the static method `method` of `class`.
+            ///
+            /// # Safety
+            ///
+            /// See the [module docs](self).
+            pub unsafe fn $static_method<'jvm>(
+                jvm: &mut Jvm<'jvm>,
+                class: ObjectPtr,
+                method: MethodPtr,
+                args: &[jvalue],
+            ) -> crate::Result<'jvm, $ty> {
+                unsafe {
+                    jvm.env().invoke(
+                        |env| env.$jni_static_method,
+                        |env, f| f(env, class.as_ptr(), method.as_ptr(), args.as_ptr()),
+                    )
+                }
+            }
+        )*
+    };
+}
+
+call_shims! {
+    any_object_method, static_any_object_method: Option<Local<'jvm, Object>> = CallObjectMethodA, CallStaticObjectMethodA;
+    boolean_method, static_boolean_method: bool = CallBooleanMethodA, CallStaticBooleanMethodA;
+    byte_method, static_byte_method: i8 = CallByteMethodA, CallStaticByteMethodA;
+    char_method, static_char_method: u16 = CallCharMethodA, CallStaticCharMethodA;
+    short_method, static_short_method: i16 = CallShortMethodA, CallStaticShortMethodA;
+    int_method, static_int_method: i32 = CallIntMethodA, CallStaticIntMethodA;
+    long_method, static_long_method: i64 = CallLongMethodA, CallStaticLongMethodA;
+    float_method, static_float_method: f32 = CallFloatMethodA, CallStaticFloatMethodA;
+    double_method, static_double_method: f64 = CallDoubleMethodA, CallStaticDoubleMethodA;
+    void_method, static_void_method: () = CallVoidMethodA, CallStaticVoidMethodA;
+}
+
+/// Calls the instance method `method` of `this`, which returns an instance of `R` (or null).
+///
+/// # Safety
+///
+/// See the [module docs](self).
+#[inline]
+pub unsafe fn object_method<'jvm, R: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    this: ObjectPtr,
+    method: MethodPtr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, Option<Local<'jvm, R>>> {
+    let object = unsafe { any_object_method(jvm, this, method, args) }?;
+    // SAFETY: the method returns an instance of `R`
+    Ok(object.map(|object| unsafe { Local::from_raw(jvm.env(), object.into_raw()) }))
+}
+
+/// Calls the static method `method` of `class`, which returns an instance of `R` (or null).
+///
+/// # Safety
+///
+/// See the [module docs](self).
+#[inline]
+pub unsafe fn static_object_method<'jvm, R: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    class: ObjectPtr,
+    method: MethodPtr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, Option<Local<'jvm, R>>> {
+    let object = unsafe { static_any_object_method(jvm, class, method, args) }?;
+    // SAFETY: the method returns an instance of `R`
+    Ok(object.map(|object| unsafe { Local::from_raw(jvm.env(), object.into_raw()) }))
+}
+
+unsafe fn new_any_object<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    class: ObjectPtr,
+    constructor: MethodPtr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+    // SAFETY: guaranteed by the caller of `new_object`
+    unsafe {
+        jvm.env().invoke_new_object(
+            |env| env.NewObjectA,
+            |env, f| f(env, class.as_ptr(), constructor.as_ptr(), args.as_ptr()),
+        )
+    }
+}
+
+/// Creates an object of `class`, an instance of `R`, with `constructor`. As for
+/// [`EnvPtr::invoke_new_object`](crate::raw::EnvPtr::invoke_new_object), the result is `None` only if `NewObjectA`
+/// returned null without throwing.
+///
+/// # Safety
+///
+/// See the [module docs](self).
+#[inline]
+pub unsafe fn new_object<'jvm, R: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    class: ObjectPtr,
+    constructor: MethodPtr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, Option<Local<'jvm, R>>> {
+    let object = unsafe { new_any_object(jvm, class, constructor, args) }?;
+    // SAFETY: `class` is the class of `R`, or a subclass of it
+    Ok(object.map(|object| unsafe { Local::from_raw(jvm.env(), object.into_raw()) }))
+}
+
+/// The result of a method called with [`call_method`] or [`call_static_method`]: `()` for `void`, the Rust type of a
+/// primitive (e.g. `i32` for `int` and `u16` for `char`), or `Option<Local<'jvm, T>>` for objects and arrays.
+pub trait CallResult<'jvm>: Sized {
+    /// The first character of the return type in descriptors.
+    #[doc(hidden)]
+    const DESCRIPTOR: u8;
+
+    /// Appends the return type to the descriptor of a method called with [`call!`](crate::call!).
+    #[doc(hidden)]
+    fn push_descriptor(jvm: &mut Jvm<'jvm>, descriptor: &mut String) -> crate::Result<'jvm, ()>;
+
+    #[doc(hidden)]
+    unsafe fn call(
+        jvm: &mut Jvm<'jvm>,
+        this: ObjectPtr,
+        method: MethodPtr,
+        args: &[jvalue],
+    ) -> crate::Result<'jvm, Self>;
+
+    #[doc(hidden)]
+    unsafe fn call_static(
+        jvm: &mut Jvm<'jvm>,
+        class: ObjectPtr,
+        method: MethodPtr,
+        args: &[jvalue],
+    ) -> crate::Result<'jvm, Self>;
+}
+
+macro_rules! call_results {
+    ($($ty:ty = $descriptor:literal, $method:ident, $static_method:ident;)*) => {
+        $(
+            impl<'jvm> CallResult<'jvm> for $ty {
+                const DESCRIPTOR: u8 = $descriptor;
+
+                fn push_descriptor(_jvm: &mut Jvm<'jvm>, descriptor: &mut String) -> crate::Result<'jvm, ()> {
+                    descriptor.push(char::from($descriptor));
+                    Ok(())
+                }
+
+                unsafe fn call(
+                    jvm: &mut Jvm<'jvm>,
+                    this: ObjectPtr,
+                    method: MethodPtr,
+                    args: &[jvalue],
+                ) -> crate::Result<'jvm, Self> {
+                    unsafe { $method(jvm, this, method, args) }
+                }
+
+                unsafe fn call_static(
+                    jvm: &mut Jvm<'jvm>,
+                    class: ObjectPtr,
+                    method: MethodPtr,
+                    args: &[jvalue],
+                ) -> crate::Result<'jvm, Self> {
+                    unsafe { $static_method(jvm, class, method, args) }
+                }
+            }
+        )*
+    };
+}
+
+call_results! {
+    () = b'V', void_method, static_void_method;
+    bool = b'Z', boolean_method, static_boolean_method;
+    i8 = b'B', byte_method, static_byte_method;
+    u16 = b'C', char_method, static_char_method;
+    i16 = b'S', short_method, static_short_method;
+    i32 = b'I', int_method, static_int_method;
+    i64 = b'J', long_method, static_long_method;
+    f32 = b'F', float_method, static_float_method;
+    f64 = b'D', double_method, static_double_method;
+}
+
+impl<'jvm, T: JavaObject> CallResult<'jvm> for Option<Local<'jvm, T>> {
+    const DESCRIPTOR: u8 = b'L';
+
+    fn push_descriptor(jvm: &mut Jvm<'jvm>, descriptor: &mut String) -> crate::Result<'jvm, ()> {
+        push_class_descriptor::<T>(jvm, descriptor)
+    }
+
+    unsafe fn call(
+        jvm: &mut Jvm<'jvm>,
+        this: ObjectPtr,
+        method: MethodPtr,
+        args: &[jvalue],
+    ) -> crate::Result<'jvm, Self> {
+        unsafe { object_method(jvm, this, method, args) }
+    }
+
+    unsafe fn call_static(
+        jvm: &mut Jvm<'jvm>,
+        class: ObjectPtr,
+        method: MethodPtr,
+        args: &[jvalue],
+    ) -> crate::Result<'jvm, Self> {
+        unsafe { static_object_method(jvm, class, method, args) }
+    }
+}
+
+/// Calls the instance method `name` with the JNI `descriptor` (e.g. `(ILjava/lang/String;)V`) of `this`, which is
+/// looked up in the class of `this` on every call. See the [module docs](self).
+///
+/// # Panics
+///
+/// In debug builds, unless `descriptor` is well-formed, takes as many parameters as there are `args`, and returns `R`.
+///
+/// # Safety
+///
+/// Each of `args` must match the type of its parameter, see the [module docs](self#safety).
+pub unsafe fn call_method<'jvm, R: CallResult<'jvm>>(
+    jvm: &mut Jvm<'jvm>,
+    this: &impl JavaObject,
+    name: &CStr,
+    descriptor: &CStr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, R> {
+    if cfg!(debug_assertions) {
+        check_descriptor::<R>(descriptor, args);
+    }
+    let this = this.as_raw();
+    let class = object_class(jvm, this);
+    let method = find_method(jvm, &class, name, descriptor, false)?;
+    // SAFETY: `method` was found in the class of `this` with a descriptor that returns `R`, and the caller guarantees
+    // that `args` match it
+    unsafe { R::call(jvm, this, method, args) }
+}
+
+/// Calls the static method `name` with the JNI `descriptor` of `class`, which is looked up on every call. See
+/// [`call_method`].
+///
+/// # Panics
+///
+/// As for [`call_method`].
+///
+/// # Safety
+///
+/// As for [`call_method`].
+pub unsafe fn call_static_method<'jvm, R: CallResult<'jvm>>(
+    jvm: &mut Jvm<'jvm>,
+    class: &Class,
+    name: &CStr,
+    descriptor: &CStr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, R> {
+    if cfg!(debug_assertions) {
+        check_descriptor::<R>(descriptor, args);
+    }
+    let method = find_method(jvm, class, name, descriptor, true)?;
+    // SAFETY: as for `call_method`
+    unsafe { R::call_static(jvm, class.as_raw(), method, args) }
+}
+
+/// The class of `this`, which must be a live reference.
+fn object_class<'jvm>(jvm: &mut Jvm<'jvm>, this: ObjectPtr) -> Local<'jvm, Class> {
+    let env = jvm.env();
+    // SAFETY: `this` is a live reference, and GetObjectClass never returns null for one
+    unsafe {
+        let class = env.invoke_unchecked(|env| env.GetObjectClass, |env, f| f(env, this.as_ptr()));
+        Local::from_raw(env, ObjectPtr::new(class).unwrap())
+    }
+}
+
+/// Panics unless `descriptor` is that of a method taking `args` and returning `R`.
+fn check_descriptor<'jvm, R: CallResult<'jvm>>(descriptor: &CStr, args: &[jvalue]) {
+    let descriptor = descriptor.to_string_lossy();
+    let Some((params, _)) = crate::descriptor::parse_descriptor(&descriptor) else {
+        panic!("invalid method descriptor `{descriptor}`");
+    };
+    assert_eq!(
+        params.len(),
+        args.len(),
+        "method with descriptor `{descriptor}` takes {} arguments, but {} were passed",
+        params.len(),
+        args.len()
+    );
+    let result = match descriptor.as_bytes()[descriptor.find(')').unwrap() + 1] {
+        b'[' => b'L',
+        result => result,
+    };
+    assert!(
+        result == R::DESCRIPTOR,
+        "method with descriptor `{descriptor}` doesn't return `{}`",
+        std::any::type_name::<R>()
+    );
+}
+
+/// The descriptors of the classes of the types passed to and returned by [`call!`](crate::call!), which are looked up
+/// once per type.
+static CLASS_DESCRIPTORS: Mutex<Option<HashMap<TypeId, &'static str>>> = Mutex::new(None);
+
+/// Appends the descriptor of the class of `T` (e.g. `Ljava/lang/String;`, or `[I` for an array), taken from the name
+/// of its class, so that generic types are erased like in Java.
+fn push_class_descriptor<'jvm, T: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    descriptor: &mut String,
+) -> crate::Result<'jvm, ()> {
+    let cached = CLASS_DESCRIPTORS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .and_then(|descriptors| descriptors.get(&TypeId::of::<T>()).copied());
+    if let Some(cached) = cached {
+        descriptor.push_str(cached);
+        return Ok(());
+    }
+
+    let class = T::class(jvm)?;
+    let name: String = class
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    // `Class.getName()` is already in descriptor form for arrays, other than its dots
+    let class_descriptor = if name.starts_with('[') {
+        name.replace('.', "/")
+    } else {
+        format!("L{};", name.replace('.', "/"))
+    };
+    descriptor.push_str(&class_descriptor);
+    // Leaked once per type, like the classes of the types themselves
+    CLASS_DESCRIPTORS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .entry(TypeId::of::<T>())
+        .or_insert_with(|| class_descriptor.leak());
+    Ok(())
+}
+
+/// An argument of a method called with [`call!`](crate::call!): a Java scalar (e.g. `i32` for `int` and `u16` for
+/// `char`), a reference to a Java object, or an `Option` of one for objects that may be null. Its type is what selects
+/// the overload of the method, so name it with a suffix for literals that aren't `int`s (e.g. `3_i64` for a `long`).
+///
+/// # Safety
+///
+/// The descriptor must be that of the parameters that take the value returned by
+/// [`IntoJniValue::into_jni_value`].
+pub unsafe trait CallArgument: IntoJniValue {
+    /// Appends the descriptor of the parameter that takes this argument (e.g. `I` for an `int`).
+    fn push_descriptor<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        descriptor: &mut String,
+    ) -> crate::Result<'jvm, ()>;
+}
+
+macro_rules! scalar_arguments {
+    ($($ty:ty = $descriptor:literal,)*) => {
+        $(
+            // SAFETY: the scalar is passed in the field of its Java type
+            unsafe impl CallArgument for $ty {
+                fn push_descriptor<'jvm>(
+                    _jvm: &mut Jvm<'jvm>,
+                    descriptor: &mut String,
+                ) -> crate::Result<'jvm, ()> {
+                    descriptor.push($descriptor);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+scalar_arguments! {
+    bool = 'Z',
+    i8 = 'B',
+    u16 = 'C',
+    i16 = 'S',
+    i32 = 'I',
+    i64 = 'J',
+    f32 = 'F',
+    f64 = 'D',
+}
+
+// SAFETY: the object is passed as a reference, and the class of `T` is that of the parameter
+unsafe impl<T: JavaObject> CallArgument for &T {
+    fn push_descriptor<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        descriptor: &mut String,
+    ) -> crate::Result<'jvm, ()> {
+        push_class_descriptor::<T>(jvm, descriptor)
+    }
+}
+
+// SAFETY: as for `&T`, with `None` passed as null
+unsafe impl<T: JavaObject> CallArgument for Option<&T> {
+    fn push_descriptor<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        descriptor: &mut String,
+    ) -> crate::Result<'jvm, ()> {
+        push_class_descriptor::<T>(jvm, descriptor)
+    }
+}
+
+/// An argument of [`call!`](crate::call!), converted for JNI along with the way to describe its type. It borrows the
+/// reference it was made from (for `'a`), which must stay alive while the value is passed to JNI:
+///
+/// ```compile_fail
+/// # use duchess::{call::Argument, java, prelude::*, Jvm, Local};
+/// Jvm::with(|jvm| {
+///     let hello = "hello".to_java::<java::lang::String>().assert_not_null().execute_with(jvm)?;
+///     let suffix = "!".to_java::<java::lang::String>().assert_not_null().execute_with(jvm)?;
+///     let argument = Argument::new(&*suffix);
+///     drop(suffix);
+///     let _: Option<Local<java::lang::String>> =
+///         duchess::call::invoke_method(jvm, &*hello, "concat", &[argument])?;
+///     Ok(())
+/// })?;
+/// # duchess::GlobalResult::Ok(())
+/// ```
+#[doc(hidden)]
+pub struct Argument<'a> {
+    value: jvalue,
+    push_descriptor: for<'jvm> fn(&mut Jvm<'jvm>, &mut String) -> crate::Result<'jvm, ()>,
+    _borrow: PhantomData<&'a ()>,
+}
+
+impl<'a> Argument<'a> {
+    pub fn new<A: CallArgument + 'a>(argument: A) -> Self {
+        Argument {
+            value: argument.into_jni_value(),
+            push_descriptor: A::push_descriptor,
+            _borrow: PhantomData,
+        }
+    }
+}
+
+/// The descriptor of a method taking `args`, whose result is appended by `push_result`, and the JNI values of `args`.
+fn describe<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    args: &[Argument<'_>],
+    push_result: impl FnOnce(&mut Jvm<'jvm>, &mut String) -> crate::Result<'jvm, ()>,
+) -> crate::Result<'jvm, (CString, Vec<jvalue>)> {
+    let mut descriptor = String::from("(");
+    for arg in args {
+        (arg.push_descriptor)(jvm, &mut descriptor)?;
+    }
+    descriptor.push(')');
+    push_result(jvm, &mut descriptor)?;
+    let descriptor = CString::new(descriptor).expect("descriptors have no nul characters");
+    Ok((descriptor, args.iter().map(|arg| arg.value).collect()))
+}
+
+fn method_name(name: &str) -> CString {
+    CString::new(name).expect("Java identifiers have no nul characters")
+}
+
+/// The instance method `name` of `this`, as called by [`call!`](crate::call!).
+#[doc(hidden)]
+pub fn invoke_method<'jvm, R: CallResult<'jvm>>(
+    jvm: &mut Jvm<'jvm>,
+    this: &impl JavaObject,
+    name: &str,
+    args: &[Argument<'_>],
+) -> crate::Result<'jvm, R> {
+    let (descriptor, args) = describe(jvm, args, R::push_descriptor)?;
+    let this = this.as_raw();
+    let class = object_class(jvm, this);
+    let method = find_method(jvm, &class, &method_name(name), &descriptor, false)?;
+    // SAFETY: `method` was found with the descriptor of `args` and `R`
+    unsafe { R::call(jvm, this, method, &args) }
+}
+
+/// The static method `name` of `class`, as called by [`call!`](crate::call!).
+#[doc(hidden)]
+pub fn invoke_static_method<'jvm, R: CallResult<'jvm>>(
+    jvm: &mut Jvm<'jvm>,
+    class: &Class,
+    name: &str,
+    args: &[Argument<'_>],
+) -> crate::Result<'jvm, R> {
+    let (descriptor, args) = describe(jvm, args, R::push_descriptor)?;
+    let method = find_method(jvm, class, &method_name(name), &descriptor, true)?;
+    // SAFETY: as for `invoke_method`
+    unsafe { R::call_static(jvm, class.as_raw(), method, &args) }
+}
+
+/// The constructor of `T`, as called by [`call!`](crate::call!).
+#[doc(hidden)]
+pub fn invoke_constructor<'jvm, T: JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    args: &[Argument<'_>],
+) -> crate::Result<'jvm, Local<'jvm, T>> {
+    let (descriptor, args) = describe(jvm, args, <()>::push_descriptor)?;
+    let class = T::class(jvm)?;
+    let constructor = find_method(jvm, &class, c"<init>", &descriptor, false)?;
+    // SAFETY: `constructor` was found in the class of `T` with the descriptor of `args`
+    let object = unsafe { new_object(jvm, class.as_raw(), constructor, &args) }?;
+    object.ok_or_else(|| {
+        Error::JvmInternal(format!(
+            "constructor `{}` returned null",
+            descriptor.to_string_lossy()
+        ))
+    })
+}
+
+/// Calls a Java method, selecting its overload from the types of the Rust arguments, e.g. `int` for an `i32` and
+/// `java.lang.String` for a `&java::lang::String` (see [`CallArgument`]), and from the result type, which is given
+/// by the context (see [`CallResult`]). This is a way to call overloads that bindings leave out, or methods that have
+/// no bindings at all, without writing their descriptors. Methods are taken by their Java names.
+///
+/// The overload is resolved at runtime rather than from the bindings: each call looks the method up with its
+/// descriptor (a `GetMethodID` call), and the class names of the object types involved are looked up the first time
+/// each type is used. Methods that are called often are better called through bindings, or with
+/// [`find_method`](crate::call::find_method) once and the shims of [`duchess::call`](crate::call) after that.
+///
+/// ```
+/// # use duchess::{java, prelude::*, Jvm, Local};
+/// Jvm::with(|jvm| {
+///     let hello = "hello".to_java::<java::lang::String>().assert_not_null().execute_with(jvm)?;
+///     let l = "l".to_java::<java::lang::String>().assert_not_null().execute_with(jvm)?;
+///
+///     // `indexOf(int)` and `indexOf(String)`
+///     let by_char: i32 = duchess::call!(jvm, hello.indexOf(i32::from(b'l')))?;
+///     let by_string: i32 = duchess::call!(jvm, hello.indexOf(&*l))?;
+///     assert_eq!((by_char, by_string), (2, 2));
+///
+///     // The static `String.valueOf(boolean)`
+///     let yes: Option<Local<java::lang::String>> = duchess::call!(jvm, <java::lang::String>::valueOf(true))?;
+///     assert_eq!((&*yes.unwrap()).to_rust().execute_with(jvm)?, "true");
+///     Ok(())
+/// })?;
+/// # duchess::GlobalResult::Ok(())
+/// ```
+///
+/// The forms are:
+///
+/// * `call!(jvm, object.method(args...))` for an instance method of `object` (a reference to a Java object, or a
+///   `Local` or `Global` of one; wrap expressions in parentheses);
+/// * `call!(jvm, <Type>::method(args...))` for a static method of the class bound to `Type`;
+/// * `call!(jvm, static class.method(args...))` for a static method of `class`, a `java::lang::Class` (e.g. of a
+///   class without bindings, found with [`find_class`](crate::plumbing::find_class));
+/// * `call!(jvm, <Type>::new(args...))` for a constructor, which returns a `Local<Type>`.
+///
+/// Arguments only match parameters of exactly their type, so objects passed to a parameter of one of their superclasses
+/// or interfaces are upcast first, e.g. with `AsRef::<java::lang::Object>::as_ref(&local)`. Like descriptors, the
+/// result type is erased: a method returning a `T` of a generic class returns an
+/// `Option<Local<java::lang::Object>>`. Exceptions are returned as [`Error::Thrown`](crate::Error::Thrown), and
+/// methods that don't exist with those types as a thrown `NoSuchMethodError`.
+#[macro_export]
+macro_rules! call {
+    ($jvm:expr, <$class:ty>::new($($arg:expr),* $(,)?)) => {
+        $crate::call::invoke_constructor::<$class>($jvm, &[$($crate::call::Argument::new($arg)),*])
+    };
+    ($jvm:expr, <$class:ty>::$method:ident($($arg:expr),* $(,)?)) => {
+        match &mut *$jvm {
+            jvm => match <$class as $crate::JavaObject>::class(jvm) {
+                Ok(class) => $crate::call::invoke_static_method(
+                    jvm,
+                    &class,
+                    stringify!($method),
+                    &[$($crate::call::Argument::new($arg)),*],
+                ),
+                Err(e) => Err(e),
+            },
+        }
+    };
+    ($jvm:expr, static $class:tt.$method:ident($($arg:expr),* $(,)?)) => {
+        $crate::call::invoke_static_method(
+            $jvm,
+            &*$class,
+            stringify!($method),
+            &[$($crate::call::Argument::new($arg)),*],
+        )
+    };
+    ($jvm:expr, $this:tt.$method:ident($($arg:expr),* $(,)?)) => {
+        $crate::call::invoke_method(
+            $jvm,
+            &*$this,
+            stringify!($method),
+            &[$($crate::call::Argument::new($arg)),*],
+        )
+    };
+}
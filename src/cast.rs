@@ -1,6 +1,6 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, panic::Location};
 
-use crate::Jvm;
+use crate::{classes, error::ConversionError, Jvm};
 use crate::{jvm::JavaObjectExt, refs::AsJRef, JavaObject, JvmOp, Local, TryJDeref};
 
 /// A trait to represent safe upcast operations for a [`JavaObject`].
@@ -66,6 +66,55 @@ where
     }
 }
 
+#[derive_where::derive_where(Copy, Clone)]
+pub struct Downcast<J: JvmOp, To> {
+    op: TryDowncast<J, To>,
+    location: &'static Location<'static>,
+}
+
+impl<J, To> Downcast<J, To>
+where
+    J: JvmOp,
+    for<'jvm> J::Output<'jvm>: TryJDeref,
+    To: for<'jvm> Upcast<<J::Output<'jvm> as TryJDeref>::Java>,
+{
+    pub(crate) fn new(op: J, location: &'static Location<'static>) -> Self {
+        Self {
+            op: TryDowncast::new(op),
+            location,
+        }
+    }
+}
+
+impl<J, To> JvmOp for Downcast<J, To>
+where
+    J: JvmOp,
+    for<'jvm> J::Output<'jvm>: TryJDeref,
+    To: for<'jvm> Upcast<<J::Output<'jvm> as TryJDeref>::Java>,
+{
+    type Output<'jvm> = Local<'jvm, To>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        match self.op.execute_with(jvm)? {
+            Ok(casted) => Ok(casted),
+            Err(instance) => {
+                let expected: String = To::class(jvm)?
+                    .get_name()
+                    .assert_not_null()
+                    .to_rust()
+                    .execute_with(jvm)?;
+                let actual = classes::runtime_class_name(jvm, instance.try_jderef()?)?;
+                Err(ConversionError::UnexpectedClass {
+                    expected: classes::describe(&expected),
+                    actual: classes::describe(&actual),
+                    location: self.location,
+                }
+                .into())
+            }
+        }
+    }
+}
+
 #[derive_where::derive_where(Copy, Clone)]
 pub struct AsUpcast<J: JvmOp, To> {
     op: J,
@@ -0,0 +1,95 @@
+//! The environments that duchess uses, for the `checked-env` feature (in debug builds). [`EnvUse`]s count the duchess
+//! frames using each environment, which lets [`EnvPtr`]s check cheaply that they are used on the thread of their
+//! environment, and name that thread when they aren't.
+
+use std::{cell::RefCell, collections::HashMap, sync::Mutex, thread::Thread};
+
+use crate::raw::EnvPtr;
+
+thread_local! {
+    /// The environments in use on the current thread (usually just one), with the number of their uses.
+    static IN_USE: RefCell<Vec<(*mut jni_sys::JNIEnv, usize)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The threads of the environments in use, keyed by their addresses. Only updated by the first and last use of an
+/// environment on its thread, and only read to report misuses.
+static OWNERS: Mutex<Option<HashMap<usize, Thread>>> = Mutex::new(None);
+
+/// A use of an environment by a duchess frame on its own thread (see [`crate::thread`]), which ends when dropped.
+/// Nested frames share the environment of their thread, so the uses are reference counted.
+pub(crate) struct EnvUse {
+    env: *mut jni_sys::JNIEnv,
+}
+
+impl EnvUse {
+    /// Starts a use of `env`, which must be the environment of the current thread.
+    pub(crate) fn new(env: EnvPtr<'_>) -> Self {
+        let env = env.as_ptr();
+        let first = IN_USE.with_borrow_mut(|in_use| {
+            match in_use.iter_mut().find(|(used, _)| *used == env) {
+                Some((_, uses)) => {
+                    *uses += 1;
+                    false
+                }
+                None => {
+                    in_use.push((env, 1));
+                    true
+                }
+            }
+        });
+        if first {
+            let mut owners = OWNERS.lock().unwrap_or_else(|e| e.into_inner());
+            owners
+                .get_or_insert_with(HashMap::new)
+                .insert(env as usize, std::thread::current());
+        }
+        EnvUse { env }
+    }
+}
+
+impl Drop for EnvUse {
+    fn drop(&mut self) {
+        // `try_with` because attach guards may be dropped from other thread-local destructors during thread exit
+        let last = IN_USE
+            .try_with(|in_use| {
+                let mut in_use = in_use.borrow_mut();
+                let index = in_use.iter().position(|(used, _)| *used == self.env)?;
+                in_use[index].1 -= 1;
+                if in_use[index].1 > 0 {
+                    return Some(false);
+                }
+                in_use.swap_remove(index);
+                Some(true)
+            })
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+        if last {
+            let mut owners = OWNERS.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(owners) = owners.as_mut() {
+                owners.remove(&(self.env as usize));
+            }
+        }
+    }
+}
+
+/// Whether `env` is in use by a duchess frame on the current thread, in which case it is the environment of the thread.
+pub(crate) fn in_use_here(env: EnvPtr<'_>) -> bool {
+    let env = env.as_ptr();
+    IN_USE
+        .try_with(|in_use| in_use.borrow().iter().any(|(used, _)| *used == env))
+        .unwrap_or(false)
+}
+
+/// The thread that uses `env` in a duchess frame, if any.
+pub(crate) fn owner(env: EnvPtr<'_>) -> Option<Thread> {
+    let owners = OWNERS.lock().unwrap_or_else(|e| e.into_inner());
+    owners.as_ref()?.get(&(env.as_ptr() as usize)).cloned()
+}
+
+/// The name of `thread` for messages, or its ID if it has none.
+pub(crate) fn describe(thread: &Thread) -> String {
+    thread
+        .name()
+        .map_or_else(|| format!("{:?}", thread.id()), str::to_string)
+}
@@ -0,0 +1,80 @@
+//! The Rust types that bind Java classes, looked up by the runtime name of the class (`getClass().getName()`, e.g.
+//! `java.util.ArrayList`), e.g. to report which class an object has in the terms of its Rust binding:
+//!
+//! ```
+//! # fn main() -> duchess::GlobalResult<()> {
+//! use duchess::{java, prelude::*};
+//!
+//! let list = java::util::ArrayList::<java::lang::String>::new().global().execute()?;
+//! let class = duchess::classes::lookup("java.util.ArrayList").unwrap();
+//! assert!(class.rust_type.ends_with("::java::util::ArrayList"));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The classes declared with [`java_package!`](crate::java_package) register themselves the first time their class is
+//! looked up (e.g. when an object is constructed or downcast to them), so only the classes that are used are in the
+//! registry. Classes bound by hand can be registered with [`register`].
+//!
+//! The registry is what lets [`JvmOp::downcast`](crate::JvmOp::downcast) say which class it got instead of the one it
+//! expected.
+
+use std::{collections::BTreeMap, sync::RwLock};
+
+use crate::{java::lang::Object, jvm::JavaObjectExt, prelude::*, JavaObject, Jvm};
+
+/// A Java class and the Rust type that binds it, see [`lookup`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RegisteredClass {
+    /// The binary name of the class, as returned by `Class.getName()` (e.g. `java.util.Map$Entry`).
+    pub java_name: &'static str,
+    /// The path of the Rust type where it is defined, without its generic arguments (e.g. `my_crate::java::util::Map__Entry`
+    /// for a class declared in `my_crate::java`). It may differ from the path it is re-exported at.
+    pub rust_type: &'static str,
+}
+
+static CLASSES: RwLock<BTreeMap<&'static str, RegisteredClass>> = RwLock::new(BTreeMap::new());
+
+/// Registers `T` as the binding of the class named `java_name` (as returned by `Class.getName()`). When several Rust
+/// types bind the same class, the first one registered is kept.
+pub fn register<T: JavaObject>(java_name: &'static str) {
+    let rust_type = std::any::type_name::<T>();
+    let rust_type = rust_type
+        .split_once('<')
+        .map_or(rust_type, |(path, _)| path);
+    let mut classes = CLASSES.write().unwrap_or_else(|e| e.into_inner());
+    classes.entry(java_name).or_insert(RegisteredClass {
+        java_name,
+        rust_type,
+    });
+}
+
+/// The class named `java_name` (as returned by `Class.getName()`), if it is registered.
+pub fn lookup(java_name: &str) -> Option<RegisteredClass> {
+    let classes = CLASSES.read().unwrap_or_else(|e| e.into_inner());
+    classes.get(java_name).copied()
+}
+
+/// The name of the runtime class of `object` (`object.getClass().getName()`).
+pub fn runtime_class_name<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    object: &impl JavaObject,
+) -> crate::Result<'jvm, String> {
+    // SAFETY: every Java object is an `Object`
+    let object = unsafe { Object::from_raw(object.as_raw()) };
+    object
+        .get_class()
+        .assert_not_null()
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)
+}
+
+/// Describes the class named `java_name` for error messages: its name, along with its Rust type if it is registered.
+pub(crate) fn describe(java_name: &str) -> String {
+    match lookup(java_name) {
+        Some(class) => format!("{} (`{}`)", class.java_name, class.rust_type),
+        None => java_name.to_string(),
+    }
+}
@@ -0,0 +1,73 @@
+//! Extending the classpath of a running JVM, whose own classpath is fixed when it is created, e.g. with the jars of
+//! plugins that are only discovered at runtime.
+
+use std::{path::Path, sync::Mutex};
+
+use crate::{
+    find::{class_loader, replace_class_loader},
+    java,
+    jvm::is_same_object,
+    prelude::*,
+    Error, Global, Jvm,
+};
+
+/// The class loader created by [`Jvm::add_classpath`], if any, which duchess loads classes with until another one is
+/// set.
+static CLASSPATH_LOADER: Mutex<Option<Global<java::net::URLClassLoader>>> = Mutex::new(None);
+
+impl<'jvm> Jvm<'jvm> {
+    /// Adds the jar file or directory at `path`, which must exist, to the classes that duchess can load, so that the
+    /// classes of `java_package!` bindings can be found there.
+    ///
+    /// The entries are added to a `URLClassLoader` that duchess then loads classes with, like one set with
+    /// [`hot_reload::set_class_loader`](crate::hot_reload::set_class_loader). Its parent is the class loader that
+    /// duchess used before (or the system class loader), so the classes found before keep being found the same way,
+    /// and classes that were already looked up aren't affected. If another class loader is set afterwards, the next
+    /// call starts a new `URLClassLoader` on top of it.
+    ///
+    /// Only duchess loads classes with it: Java code keeps loading the classes it refers to with the class loader of
+    /// its own class, and libraries that look classes up by name usually use the context class loader of the thread,
+    /// which can be set to the same loader with `Thread.setContextClassLoader`.
+    ///
+    /// Fails with [`Error::ClasspathNotFound`] if `path` doesn't exist, since `URLClassLoader` would silently skip it.
+    pub fn add_classpath(&mut self, path: impl AsRef<Path>) -> crate::Result<'jvm, ()> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(Error::ClasspathNotFound(path.to_path_buf()));
+        }
+
+        let url = path
+            .to_java::<java::io::File>()
+            .assert_not_null()
+            .to_uri()
+            .assert_not_null()
+            .to_url()
+            .assert_not_null()
+            .execute_with(self)?;
+
+        let mut classpath_loader = CLASSPATH_LOADER.lock().unwrap_or_else(|e| e.into_inner());
+        let current = class_loader(self);
+        let reusable = match (&*classpath_loader, &current) {
+            (Some(loader), Some(current)) => is_same_object(self, &**loader, &**current),
+            _ => false,
+        };
+        if !reusable {
+            let parent = match current {
+                Some(current) => Some(current),
+                None => java::lang::ClassLoader::get_system_class_loader().execute_with(self)?,
+            };
+            let urls = java::Array::<java::net::URL>::new_with_len(0).execute_with(self)?;
+            let loader: crate::Local<'jvm, java::net::URLClassLoader> = crate::call!(
+                self,
+                <java::net::URLClassLoader>::new(&*urls, parent.as_deref())
+            )?;
+            let loader = self.global(&*loader);
+            replace_class_loader(Some(self.global(&*loader).upcast()));
+            *classpath_loader = Some(loader);
+        }
+
+        let loader: &java::net::URLClassLoader = classpath_loader.as_ref().unwrap();
+        // `addURL` is protected, which JNI doesn't check
+        crate::call!(self, loader.addURL(&*url))
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::RwLock;
+
+use jni_sys::jvalue;
+use once_cell::sync::OnceCell;
+
+use crate::{
+    find::find_method,
+    java::lang::{Class, Object},
+    jvm::JavaObjectExt,
+    raw::MethodPtr,
+    Error, Global, JavaObject, Jvm, JvmOp, Local, TryJDeref,
+};
+
+/// Copy constructors registered with [`Jvm::register_copy_constructor`], keyed by their (exact) class.
+static COPY_CONSTRUCTORS: RwLock<Vec<(Global<Class>, MethodPtr)>> = RwLock::new(Vec::new());
+
+/// [`JvmOp`][] that creates an independent copy of a Java object, see [`JvmOp::java_clone`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct JavaClone<J: JvmOp> {
+    op: J,
+}
+
+impl<J> JavaClone<J>
+where
+    J: JvmOp,
+    for<'jvm> J::Output<'jvm>: TryJDeref,
+{
+    pub(crate) fn new(op: J) -> Self {
+        Self { op }
+    }
+}
+
+impl<J> JvmOp for JavaClone<J>
+where
+    J: JvmOp,
+    for<'jvm> J::Output<'jvm>: TryJDeref,
+{
+    type Output<'jvm> = Local<'jvm, <J::Output<'jvm> as TryJDeref>::Java>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let instance = self.op.execute_with(jvm)?;
+        let instance = instance.try_jderef()?;
+        let instance_raw = instance.as_raw();
+        let env = jvm.env();
+
+        let copy: Option<Local<Object>> = match copy_constructor_for(jvm, instance)? {
+            Some((class, constructor)) => {
+                let args = [jvalue {
+                    l: instance_raw.as_ptr(),
+                }];
+                // SAFETY: `constructor` is a `(LT;)V` constructor of `class`, which is the class of `instance`
+                unsafe {
+                    env.invoke(
+                        |env| env.NewObjectA,
+                        |env, f| {
+                            f(
+                                env,
+                                class.as_raw().as_ptr(),
+                                constructor.as_ptr(),
+                                args.as_ptr(),
+                            )
+                        },
+                    )
+                }?
+            }
+            None => {
+                let clone = object_clone_method(jvm)?;
+                // SAFETY: `clone` is `Object.clone()`, which every object has. JNI doesn't check that the (protected)
+                // method is accessible.
+                unsafe {
+                    env.invoke(
+                        |env| env.CallObjectMethodA,
+                        |env, f| f(env, instance_raw.as_ptr(), clone.as_ptr(), std::ptr::null()),
+                    )
+                }?
+            }
+        };
+        let copy = copy.ok_or_else(|| Error::JvmInternal("clone returned null".into()))?;
+
+        // `clone()` is only conventionally of the same class, so check the result before casting it.
+        let class = <J::Output<'jvm> as TryJDeref>::Java::class(jvm)?;
+        let is_inst = unsafe {
+            env.invoke_unchecked(
+                |env| env.IsInstanceOf,
+                |env, f| f(env, copy.as_raw().as_ptr(), class.as_raw().as_ptr()),
+            ) == jni_sys::JNI_TRUE
+        };
+        if !is_inst {
+            return Err(Error::JvmInternal(format!(
+                "clone of a `{}` returned an instance of another class",
+                std::any::type_name::<<J::Output<'jvm> as TryJDeref>::Java>()
+            )));
+        }
+        // SAFETY: just shown that `copy` is an instance of the target type, and we own the local ref
+        Ok(unsafe { Local::from_raw(env, copy.into_raw()) })
+    }
+}
+
+impl<'jvm> Jvm<'jvm> {
+    /// Registers the copy constructor of `T` (a public constructor that takes a `T`) to be used by
+    /// [`JvmOp::java_clone`] for instances whose class is exactly `T`, rather than `clone()`.
+    pub fn register_copy_constructor<T>(&mut self) -> crate::Result<'jvm, ()>
+    where
+        T: JavaObject,
+    {
+        let class = T::class(self)?;
+        let name: String = class
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(self)?;
+        let descriptor = format!("(L{};)V", name.replace('.', "/"));
+        let descriptor = std::ffi::CString::new(descriptor).unwrap();
+        let constructor = find_method(self, &class, c"<init>", &descriptor, false)?;
+
+        let class = self.global(&*class);
+        let mut registry = COPY_CONSTRUCTORS.write().unwrap();
+        registry.push((class, constructor));
+        Ok(())
+    }
+}
+
+/// Looks up the copy constructor registered for the class of `instance`, if any.
+fn copy_constructor_for<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    instance: &impl JavaObject,
+) -> crate::Result<'jvm, Option<(Local<'jvm, Class>, MethodPtr)>> {
+    let registry = COPY_CONSTRUCTORS.read().unwrap();
+    if registry.is_empty() {
+        return Ok(None);
+    }
+
+    let env = jvm.env();
+    // SAFETY: instance is a live reference; GetObjectClass never returns null for a non-null object.
+    let class: Local<Class> = unsafe {
+        let class = env.invoke_unchecked(
+            |env| env.GetObjectClass,
+            |env, f| f(env, instance.as_raw().as_ptr()),
+        );
+        Local::from_raw(env, crate::raw::ObjectPtr::new(class).unwrap())
+    };
+    for (registered, constructor) in registry.iter() {
+        let same = unsafe {
+            env.invoke_unchecked(
+                |env| env.IsSameObject,
+                |env, f| f(env, class.as_raw().as_ptr(), registered.as_raw().as_ptr()),
+            ) == jni_sys::JNI_TRUE
+        };
+        if same {
+            return Ok(Some((class, *constructor)));
+        }
+    }
+    Ok(None)
+}
+
+fn object_clone_method<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, MethodPtr> {
+    static METHOD: OnceCell<MethodPtr> = OnceCell::new();
+    METHOD
+        .get_or_try_init(|| {
+            let class = Object::class(jvm)?;
+            find_method(jvm, &class, c"clone", c"()Ljava/lang/Object;", false)
+        })
+        .copied()
+}
@@ -0,0 +1,123 @@
+//! Conversions of Rust `char`s, which are Unicode scalar values, to and from Java.
+//!
+//! A Java `char` is a UTF-16 code unit (a `u16` in Rust), so it can't hold the characters beyond the Basic
+//! Multilingual Plane, which Java represents as surrogate pairs. Java APIs that handle any character take its code
+//! point as an `int` instead (e.g. `String.indexOf(int)`), which is how a `char` is passed to Java: it can be given
+//! to any `int` parameter. Code points returned by Java convert back with `try_to_rust()`, which fails for integers
+//! that aren't Unicode scalar values (e.g. lone surrogates).
+//!
+//! A `char` also converts to and from a `java.lang.Character` (failing for characters that are surrogate pairs in
+//! Java). Slices of `char`s convert to Java `char[]` arrays, with surrogate pairs for the characters that need them,
+//! and back with `try_to_rust()`, which fails for unpaired surrogates.
+
+use crate::{
+    error::ConversionError,
+    into_rust::{into_rust_or_conversion_error, TryIntoRust},
+    java::{self, Array as JavaArray},
+    to_java::ToJavaImpl,
+    IntoRust, Jvm, JvmOp, Local,
+};
+
+/// Passed to Java as its code point, an `int`.
+impl JvmOp for char {
+    type Output<'jvm> = i32;
+
+    fn execute_with<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, i32> {
+        Ok(u32::from(self) as i32)
+    }
+}
+
+/// The character with the code point, failing if it isn't a Unicode scalar value.
+impl TryIntoRust<char> for i32 {
+    fn try_into_rust<'jvm>(
+        self,
+        _jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<char, ConversionError>> {
+        Ok(from_code_point(self))
+    }
+}
+
+fn from_code_point(code_point: i32) -> Result<char, ConversionError> {
+    u32::try_from(code_point)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or_else(|| ConversionError::OutOfRange {
+            value: format!("{code_point:#x}"),
+            target: "char",
+        })
+}
+
+/// The UTF-16 code unit of `c`, failing if it needs a surrogate pair.
+fn to_code_unit(c: char) -> Result<u16, ConversionError> {
+    u16::try_from(u32::from(c)).map_err(|_| ConversionError::OutOfRange {
+        value: format!("{c:?}"),
+        target: "java.lang.Character",
+    })
+}
+
+impl ToJavaImpl<java::lang::Character> for char {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::lang::Character>>> {
+        let unit = to_code_unit(*rust)?;
+        java::lang::Character::value_of(unit).execute_with(jvm)
+    }
+}
+
+impl IntoRust<char> for &java::lang::Character {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, char> {
+        into_rust_or_conversion_error(self, jvm)
+    }
+}
+
+/// Fails if the character is half of a surrogate pair.
+impl TryIntoRust<char> for &java::lang::Character {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<char, ConversionError>> {
+        self.char_value().execute_with(jvm)?.try_into_rust(jvm)
+    }
+}
+
+/// Encodes the characters in UTF-16, as Java does.
+impl ToJavaImpl<JavaArray<u16>> for [char] {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, JavaArray<u16>>>> {
+        let mut units = Vec::with_capacity(rust.len());
+        let mut buffer = [0; 2];
+        for c in rust {
+            units.extend_from_slice(c.encode_utf16(&mut buffer));
+        }
+        Ok(Some(units.as_slice().execute_with(jvm)?))
+    }
+}
+
+impl ToJavaImpl<JavaArray<u16>> for Vec<char> {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, JavaArray<u16>>>> {
+        <[char]>::to_java_impl(rust, jvm)
+    }
+}
+
+/// Decodes the surrogate pairs of the array, failing if there are unpaired surrogates. There is no `IntoRust`
+/// counterpart, since `to_rust()` converts the array to a `Vec<u16>`.
+impl TryIntoRust<Vec<char>> for &JavaArray<u16> {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<Vec<char>, ConversionError>> {
+        let units: Vec<u16> = self.into_rust(jvm)?;
+        Ok(char::decode_utf16(units)
+            .collect::<Result<_, _>>()
+            .map_err(|e| ConversionError::OutOfRange {
+                value: format!("{:#06x}", e.unpaired_surrogate()),
+                target: "char",
+            }))
+    }
+}
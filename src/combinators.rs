@@ -0,0 +1,282 @@
+//! General-purpose [`JvmOp`] combinators, to post-process and combine the outputs of operations without leaving the
+//! op pipeline (and so within a single [`execute`](JvmOp::execute)).
+
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    from_ref::FromRef, jvm::JavaObjectExt, jvm::JavaView, raw::ObjectPtr, Error, Global,
+    JavaObject, Jvm, JvmOp, Local,
+};
+
+/// [`JvmOp`][] that transforms the output of an operation with a Rust closure, see [`JvmOp::map`].
+#[derive(Copy, Clone)]
+pub struct Map<This, F> {
+    this: This,
+    f: F,
+}
+
+impl<This, F, R> Map<This, F>
+where
+    This: JvmOp,
+    F: Copy + for<'jvm> FnOnce(This::Output<'jvm>, &mut Jvm<'jvm>) -> R,
+{
+    pub(crate) fn new(this: This, f: F) -> Self {
+        Map { this, f }
+    }
+}
+
+impl<This, F, R> JvmOp for Map<This, F>
+where
+    This: JvmOp,
+    F: Copy + for<'jvm> FnOnce(This::Output<'jvm>, &mut Jvm<'jvm>) -> R,
+{
+    type Output<'jvm> = R;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, R> {
+        let output = self.this.execute_with(jvm)?;
+        Ok((self.f)(output, jvm))
+    }
+}
+
+/// [`JvmOp`][] that runs the operation a closure makes from the output of another one, see [`JvmOp::and_then`].
+#[derive_where::derive_where(Copy, Clone; This, F)]
+pub struct AndThen<This, F, Next> {
+    this: This,
+    f: F,
+    phantom: PhantomData<fn() -> Next>,
+}
+
+impl<This, F, Next> AndThen<This, F, Next>
+where
+    This: JvmOp,
+    F: Copy + for<'jvm> FnOnce(This::Output<'jvm>) -> Next,
+    Next: JvmOp,
+{
+    pub(crate) fn new(this: This, f: F) -> Self {
+        AndThen {
+            this,
+            f,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<This, F, Next> JvmOp for AndThen<This, F, Next>
+where
+    This: JvmOp,
+    F: Copy + for<'jvm> FnOnce(This::Output<'jvm>) -> Next,
+    Next: JvmOp,
+{
+    type Output<'jvm> = Next::Output<'jvm>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let output = self.this.execute_with(jvm)?;
+        (self.f)(output).execute_with(jvm)
+    }
+}
+
+/// [`JvmOp`][] that runs two operations one after the other and pairs their outputs, see [`JvmOp::zip`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct Zip<This: JvmOp, Other: JvmOp> {
+    this: This,
+    other: Other,
+}
+
+impl<This: JvmOp, Other: JvmOp> Zip<This, Other> {
+    pub(crate) fn new(this: This, other: Other) -> Self {
+        Zip { this, other }
+    }
+}
+
+impl<This: JvmOp, Other: JvmOp> JvmOp for Zip<This, Other> {
+    type Output<'jvm> = (This::Output<'jvm>, Other::Output<'jvm>);
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let this = self.this.execute_with(jvm)?;
+        let other = self.other.execute_with(jvm)?;
+        Ok((this, other))
+    }
+}
+
+/// [`JvmOp`][] that runs an operation on the output of another one unless it is null, like Kotlin's `?.`, see
+/// [`JvmOp::if_not_null`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct IfNotNull<This: JvmOp, Next: JvmOp> {
+    this: This,
+    next: Next,
+    /// The identity of the [`NotNullValue`] that `next` was made with.
+    id: u64,
+}
+
+impl<This, Next, T> IfNotNull<This, Next>
+where
+    This: for<'jvm> JvmOp<Output<'jvm> = Option<Local<'jvm, T>>>,
+    Next: JvmOp,
+    for<'jvm> Next::Output<'jvm>: IntoNullable,
+    T: JavaObject,
+{
+    pub(crate) fn new(this: This, f: impl FnOnce(NotNullValue<T>) -> Next) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let next = f(NotNullValue {
+            id,
+            phantom: PhantomData,
+        });
+        IfNotNull { this, next, id }
+    }
+}
+
+impl<This, Next, T> JvmOp for IfNotNull<This, Next>
+where
+    This: for<'jvm> JvmOp<Output<'jvm> = Option<Local<'jvm, T>>>,
+    Next: JvmOp,
+    for<'jvm> Next::Output<'jvm>: IntoNullable,
+    T: JavaObject,
+{
+    type Output<'jvm> = <Next::Output<'jvm> as IntoNullable>::Nullable;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let Some(value) = self.this.execute_with(jvm)? else {
+            return Ok(<Next::Output<'jvm> as IntoNullable>::null());
+        };
+        let output = {
+            let _value = NotNullScope::enter(self.id, value.as_raw());
+            self.next.execute_with(jvm)?
+        };
+        Ok(output.into_nullable())
+    }
+}
+
+// The values that `NotNullValue`s stand for while the `next` operation of their `IfNotNull` runs, innermost last.
+thread_local! {
+    static NOT_NULL_VALUES: RefCell<Vec<(u64, ObjectPtr)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes `value` available to the [`NotNullValue`] `id` until dropped.
+struct NotNullScope;
+
+impl NotNullScope {
+    fn enter(id: u64, value: ObjectPtr) -> Self {
+        NOT_NULL_VALUES.with(|values| values.borrow_mut().push((id, value)));
+        NotNullScope
+    }
+}
+
+impl Drop for NotNullScope {
+    fn drop(&mut self) {
+        NOT_NULL_VALUES.with(|values| values.borrow_mut().pop());
+    }
+}
+
+/// [`JvmOp`][] that outputs the (non-null) value that [`JvmOp::if_not_null`] passes on, as a new local reference.
+/// Only valid within the operation made by the closure it is given to: executing it elsewhere fails with
+/// [`Error::JvmInternal`].
+pub struct NotNullValue<T> {
+    id: u64,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for NotNullValue<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NotNullValue<T> {}
+
+impl<T: JavaObject> JvmOp for NotNullValue<T> {
+    type Output<'jvm> = Local<'jvm, T>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, T>> {
+        let value = NOT_NULL_VALUES.with(|values| {
+            let values = values.borrow();
+            values
+                .iter()
+                .rev()
+                .find(|(id, _)| *id == self.id)
+                .map(|(_, value)| *value)
+        });
+        let Some(value) = value else {
+            return Err(Error::JvmInternal(
+                "the value of `if_not_null` was used outside of its closure's operation".into(),
+            ));
+        };
+        let env = jvm.env();
+        // SAFETY: `value` is a live local ref to a `T` held by the `IfNotNull` operation that is running, and
+        // `NewLocalRef` makes a new one owned by the returned `Local`
+        unsafe {
+            let new_ref =
+                env.invoke_unchecked(|jni| jni.NewLocalRef, |jni, f| f(jni, value.as_ptr()));
+            Ok(Local::from_raw(env, ObjectPtr::new(new_ref).unwrap()))
+        }
+    }
+}
+
+impl<T: JavaObject> std::ops::Deref for NotNullValue<T> {
+    type Target = <T as JavaView>::OfOp<Self>;
+
+    fn deref(&self) -> &Self::Target {
+        <Self::Target as FromRef<_>>::from_ref(self)
+    }
+}
+
+/// Outputs of the operations given to [`JvmOp::if_not_null`], and how null is represented for them: nullable outputs
+/// (`Option`s) stay as they are, while others are wrapped in an `Option`.
+pub trait IntoNullable {
+    type Nullable;
+
+    fn into_nullable(self) -> Self::Nullable;
+
+    fn null() -> Self::Nullable;
+}
+
+impl<T> IntoNullable for Option<T> {
+    type Nullable = Option<T>;
+
+    fn into_nullable(self) -> Option<T> {
+        self
+    }
+
+    fn null() -> Option<T> {
+        None
+    }
+}
+
+macro_rules! non_null_outputs {
+    ($([$($param:tt)*] $t:ty,)*) => {
+        $(
+            impl<$($param)*> IntoNullable for $t {
+                type Nullable = Option<$t>;
+
+                fn into_nullable(self) -> Option<$t> {
+                    Some(self)
+                }
+
+                fn null() -> Option<$t> {
+                    None
+                }
+            }
+        )*
+    };
+}
+
+non_null_outputs! {
+    [] (),
+    [] bool,
+    [] i8,
+    [] u16,
+    [] i16,
+    [] i32,
+    [] i64,
+    [] f32,
+    [] f64,
+    [] String,
+    ['jvm, T: JavaObject] Local<'jvm, T>,
+    [T: JavaObject] Global<T>,
+    [T] Vec<T>,
+    [A, B] (A, B),
+}
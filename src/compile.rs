@@ -0,0 +1,345 @@
+//! Compiling Java source code at runtime with the JDK's compiler (`javax.tools.JavaCompiler`), e.g. for code generated
+//! from templates, and loading the compiled classes into the JVM:
+//!
+//! ```ignore
+//! let compiled = duchess::compile::compile(
+//!     jvm,
+//!     &[("gen.Upper", "package gen; public class Upper implements java.util.function.Function<String, String> { ... }")],
+//!     &[],
+//! )??;
+//! let classes = compiled.load(jvm)?;
+//! let upper = classes.new_instance::<java::util::function::Function<java::lang::String, java::lang::String>>(jvm, "gen.Upper")?;
+//! let result: String = upper.apply("hello").assert_not_null().to_rust().execute_with(jvm)?;
+//! ```
+//!
+//! Since the classes are loaded by their own class loader, code declared with [`java_package!`](crate::java_package)
+//! can't name them directly. Instead, they are used through interfaces or superclasses known to the rest of the
+//! program (see [`LoadedClasses::new_instance`]).
+
+use std::{
+    ffi::CString,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use thiserror::Error;
+
+use crate::{
+    cast::Upcast,
+    find::find_constructor,
+    java::{
+        self,
+        lang::{Class, ClassLoader, Object},
+    },
+    jvm::JavaObjectExt,
+    AsJRef, Error, Global, Jvm, JvmOp, Local,
+};
+
+mod auto {
+    // Make current crate available as `duchess` for use by the generated code.
+    #[cfg(not(doctest))]
+    use crate as duchess;
+
+    duchess_macro::java_package! {
+        package javax.tools;
+
+        public class javax.tools.ToolProvider {
+            public static javax.tools.JavaCompiler getSystemJavaCompiler();
+        }
+
+        public interface javax.tools.Tool {
+        }
+
+        public interface javax.tools.JavaCompiler extends javax.tools.Tool {
+        }
+    }
+}
+
+/// Why Java sources couldn't be compiled, see [`compile`].
+#[derive(Error, Debug)]
+pub enum CompileError {
+    #[error("no Java compiler is available (the JVM may be a JRE rather than a JDK)")]
+    NoCompiler,
+
+    /// The sources didn't compile; holds the compiler's diagnostics.
+    #[error("failed to compile Java sources:\n{0}")]
+    Failed(String),
+
+    #[error("failed to write Java sources or read compiled classes: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A class compiled by [`compile`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledClass {
+    /// The binary name of the class, e.g. `gen.Upper` or `gen.Upper$Inner`.
+    pub name: String,
+    /// The contents of the class file.
+    pub bytes: Vec<u8>,
+}
+
+/// The classes compiled by [`compile`], which includes nested and anonymous classes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompiledClasses {
+    pub classes: Vec<CompiledClass>,
+}
+
+/// Compiles Java `sources`, given as pairs of the fully qualified name of the top-level class they declare and their
+/// code, passing `options` (e.g., `["--release", "17"]`) to the compiler. The outer result holds errors of the JVM,
+/// the inner one errors of the compilation itself.
+pub fn compile<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    sources: &[(&str, &str)],
+    options: &[&str],
+) -> crate::Result<'jvm, Result<CompiledClasses, CompileError>> {
+    let compiler =
+        auto::javax::tools::ToolProvider::get_system_java_compiler().execute_with(jvm)?;
+    let Some(compiler) = compiler else {
+        return Ok(Err(CompileError::NoCompiler));
+    };
+
+    // javac reads and writes files, so the sources are staged in a temporary directory
+    let dir = match TempDir::new() {
+        Ok(dir) => dir,
+        Err(e) => return Ok(Err(e.into())),
+    };
+    let source_files = match dir.write_sources(sources) {
+        Ok(files) => files,
+        Err(e) => return Ok(Err(e.into())),
+    };
+
+    let classes_dir = dir.classes().display().to_string();
+    let mut args: Vec<String> = vec!["-d".into(), classes_dir];
+    args.extend(options.iter().map(|&option| option.to_owned()));
+    args.extend(source_files);
+    let args = java::Array::<java::lang::String>::from_elements(jvm, args.iter())?;
+
+    let input = java::io::ByteArrayInputStream::new(&[] as &[i8]).execute_with(jvm)?;
+    let output = java::io::ByteArrayOutputStream::new().execute_with(jvm)?;
+    let (stdin, stdout): (&java::io::InputStream, &java::io::OutputStream) =
+        (input.as_jref()?, output.as_jref()?);
+    // `Tool.run` is called by its descriptor, which the argument types spell out
+    let exit_code: i32 = crate::call!(jvm, compiler.run(stdin, stdout, stdout, &*args))?;
+    if exit_code != 0 {
+        let diagnostics: Vec<i8> = output
+            .to_byte_array()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let diagnostics: Vec<u8> = diagnostics.into_iter().map(|b| b as u8).collect();
+        return Ok(Err(CompileError::Failed(
+            String::from_utf8_lossy(&diagnostics).into_owned(),
+        )));
+    }
+
+    Ok(dir.read_classes().map_err(CompileError::from))
+}
+
+impl CompiledClasses {
+    /// Loads the classes into the JVM (with JNI's `DefineClass`) in a new class loader, whose parent is the system
+    /// class loader.
+    pub fn load<'jvm>(&self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, LoadedClasses> {
+        let urls = java::Array::<java::net::URL>::new_with_len(0);
+        let loader = java::net::URLClassLoader::new(urls).execute_with(jvm)?;
+
+        // A class can only be defined once its superclass and interfaces can be loaded, so the classes are defined in
+        // rounds until they all are (or none of the remaining ones can be)
+        let mut pending: Vec<&CompiledClass> = self.classes.iter().collect();
+        while !pending.is_empty() {
+            let mut failed = vec![];
+            let mut last_error = None;
+            for &class in &pending {
+                match define_class(jvm, &loader, class) {
+                    Ok(_) => {}
+                    Err(Error::Thrown(e)) if is_no_class_def_found(jvm, &e)? => {
+                        failed.push(class);
+                        last_error = Some(Error::Thrown(e));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            if failed.len() == pending.len() {
+                return Err(last_error.unwrap());
+            }
+            pending = failed;
+        }
+
+        Ok(LoadedClasses {
+            loader: jvm.global(&*loader).upcast(),
+        })
+    }
+}
+
+/// Classes loaded with [`CompiledClasses::load`].
+pub struct LoadedClasses {
+    loader: Global<ClassLoader>,
+}
+
+impl LoadedClasses {
+    /// The class loader of the classes, through which they (and the classes of the rest of the program) are found.
+    pub fn loader(&self) -> &Global<ClassLoader> {
+        &self.loader
+    }
+
+    /// The class with the binary name `name` (e.g., `gen.Upper`).
+    pub fn class<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        name: &str,
+    ) -> crate::Result<'jvm, Local<'jvm, Class>> {
+        self.loader
+            .load_class(name)
+            .assert_not_null()
+            .execute_with(jvm)
+    }
+
+    /// Creates an instance of the class `name` with its public constructor without parameters, as an `I`, which is
+    /// typically an interface that the class implements.
+    pub fn new_instance<'jvm, I>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        name: &str,
+    ) -> crate::Result<'jvm, Local<'jvm, I>>
+    where
+        I: Upcast<Object>,
+    {
+        let class = self.class(jvm, name)?;
+        let constructor = find_constructor(jvm, &class, c"()V")?;
+        let env = jvm.env();
+        // SAFETY: `constructor` is the constructor without parameters of `class`
+        let instance: Option<Local<Object>> = unsafe {
+            env.invoke_new_object(
+                |env| env.NewObjectA,
+                |env, f| {
+                    f(
+                        env,
+                        class.as_raw().as_ptr(),
+                        constructor.as_ptr(),
+                        std::ptr::null(),
+                    )
+                },
+            )
+        }?;
+        let instance = instance.ok_or(Error::NullDeref)?;
+        match (&*instance).try_downcast::<I>().execute_with(jvm)? {
+            Ok(instance) => Ok(instance),
+            Err(_) => Err(Error::JvmInternal(format!(
+                "an instance of `{name}` isn't an instance of the expected type"
+            ))),
+        }
+    }
+}
+
+fn define_class<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    loader: &java::net::URLClassLoader,
+    class: &CompiledClass,
+) -> crate::Result<'jvm, Local<'jvm, Class>> {
+    let Ok(len) = class.bytes.len().try_into() else {
+        return Err(Error::SliceTooLong(class.bytes.len()));
+    };
+    let name = CString::new(class.name.replace('.', "/")).unwrap();
+    let env = jvm.env();
+    // SAFETY: `name` is nul-terminated, and `bytes` holds `len` bytes
+    let defined: Option<Local<Class>> = unsafe {
+        env.invoke(
+            |env| env.DefineClass,
+            |env, f| {
+                f(
+                    env,
+                    name.as_ptr(),
+                    loader.as_raw().as_ptr(),
+                    class.bytes.as_ptr().cast(),
+                    len,
+                )
+            },
+        )
+    }?;
+    defined.ok_or_else(|| Error::JvmInternal(format!("failed to define class `{}`", class.name)))
+}
+
+fn is_no_class_def_found<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    exception: &java::lang::Throwable,
+) -> crate::Result<'jvm, bool> {
+    let class = exception.get_class().assert_not_null().execute_with(jvm)?;
+    let name: String = class
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    Ok(name == "java.lang.NoClassDefFoundError")
+}
+
+/// A directory holding the sources and classes of a compilation, which is deleted when dropped.
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new() -> std::io::Result<Self> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "duchess-compile-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(path.join("classes"))?;
+        Ok(TempDir { path })
+    }
+
+    fn classes(&self) -> PathBuf {
+        self.path.join("classes")
+    }
+
+    /// Writes each source to the file javac expects for its class, returning the paths of the files.
+    fn write_sources(&self, sources: &[(&str, &str)]) -> std::io::Result<Vec<String>> {
+        let mut files = vec![];
+        for (class_name, code) in sources {
+            let mut file = self.path.join("sources");
+            file.extend(class_name.split('.'));
+            file.set_extension("java");
+            std::fs::create_dir_all(file.parent().unwrap())?;
+            std::fs::write(&file, code)?;
+            files.push(file.display().to_string());
+        }
+        Ok(files)
+    }
+
+    fn read_classes(&self) -> std::io::Result<CompiledClasses> {
+        let mut classes = vec![];
+        read_classes_in(&self.classes(), "", &mut classes)?;
+        Ok(CompiledClasses { classes })
+    }
+}
+
+/// Reads the class files in `dir`, whose package is `package` (e.g., `gen.` or the empty string).
+fn read_classes_in(
+    dir: &Path,
+    package: &str,
+    classes: &mut Vec<CompiledClass>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if entry.file_type()?.is_dir() {
+            read_classes_in(&path, &format!("{package}{file_name}."), classes)?;
+        } else if let Some(class_name) = file_name.strip_suffix(".class") {
+            classes.push(CompiledClass {
+                name: format!("{package}{class_name}"),
+                bytes: std::fs::read(&path)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_dir_all(&self.path) {
+            tracing::warn!(?err, path = ?self.path, "couldn't delete temporary directory");
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! State shared between Java and Rust: Java's `AtomicInteger`, `AtomicLong`, `AtomicReference` and
+//! `ConcurrentHashMap`, with operations named like those of [`std::sync::atomic`].
+//!
+//! Each wrapper holds a global reference to its Java object, which can be handed to Java code with
+//! [`as_java`](AtomicI64::as_java), or wrapped with [`from_java`](AtomicI64::from_java) when it comes from Java:
+//!
+//! ```
+//! # fn main() -> duchess::GlobalResult<()> {
+//! use duchess::concurrent::AtomicI64;
+//!
+//! let requests = AtomicI64::new(0)?;
+//! assert_eq!(requests.fetch_add(5)?, 0);
+//! assert_eq!(requests.compare_exchange(5, 7)?, Ok(5));
+//! assert_eq!(requests.compare_exchange(5, 9)?, Err(7));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The Java operations are sequentially consistent, so unlike their Rust counterparts, they don't take orderings.
+//! Each operation is a JNI call, so use the Java objects directly (e.g. `as_java().get_and_add(1)`) to combine them
+//! with other [`JvmOp`]s.
+
+use crate::{
+    call::{call_method, IntoJniValue},
+    cast::Upcast,
+    java::{
+        lang::Object,
+        util::concurrent::{
+            atomic::{AtomicInteger, AtomicLong, AtomicReference},
+            ConcurrentHashMap,
+        },
+    },
+    jvm::is_same_object,
+    prelude::*,
+    AsJRef, Global, GlobalResult, Jvm, Local,
+};
+
+macro_rules! atomic_integer {
+    ($(#[$attr:meta])* $name:ident($ty:ty) = $class:ident) => {
+        $(#[$attr])*
+        pub struct $name {
+            java: Global<$class>,
+        }
+
+        impl $name {
+            /// Creates a Java atomic holding `value`.
+            pub fn new(value: $ty) -> GlobalResult<Self> {
+                let java = $class::new(value).global().execute()?;
+                Ok(Self { java })
+            }
+
+            /// Wraps an atomic created by Java.
+            pub fn from_java(java: Global<$class>) -> Self {
+                Self { java }
+            }
+
+            /// The Java atomic, e.g. to pass to Java code.
+            pub fn as_java(&self) -> &Global<$class> {
+                &self.java
+            }
+
+            /// Returns the value (`get`).
+            pub fn load(&self) -> GlobalResult<$ty> {
+                self.java.get().execute()
+            }
+
+            /// Sets the value (`set`).
+            pub fn store(&self, value: $ty) -> GlobalResult<()> {
+                self.java.set(value).execute()
+            }
+
+            /// Sets the value, returning the previous one (`getAndSet`).
+            pub fn swap(&self, value: $ty) -> GlobalResult<$ty> {
+                self.java.get_and_set(value).execute()
+            }
+
+            /// Adds to the value, wrapping around on overflow, and returns the previous one (`getAndAdd`).
+            pub fn fetch_add(&self, value: $ty) -> GlobalResult<$ty> {
+                self.java.get_and_add(value).execute()
+            }
+
+            /// Subtracts from the value, wrapping around on overflow, and returns the previous one.
+            pub fn fetch_sub(&self, value: $ty) -> GlobalResult<$ty> {
+                self.java.get_and_add(value.wrapping_neg()).execute()
+            }
+
+            /// Sets the value to `new` if it is `current` (`compareAndExchange`). Returns the previous value, as `Ok`
+            /// if it was `current` and as `Err` otherwise.
+            pub fn compare_exchange(&self, current: $ty, new: $ty) -> GlobalResult<Result<$ty, $ty>> {
+                let previous = self.java.compare_and_exchange(current, new).execute()?;
+                Ok(if previous == current {
+                    Ok(previous)
+                } else {
+                    Err(previous)
+                })
+            }
+
+            /// Sets the value to what `f` returns for it, retrying if another thread changed it in the meantime. Returns
+            /// the previous value, as `Ok` if it was updated and as `Err` if `f` returned `None`.
+            pub fn fetch_update(
+                &self,
+                mut f: impl FnMut($ty) -> Option<$ty>,
+            ) -> GlobalResult<Result<$ty, $ty>> {
+                let mut previous = self.load()?;
+                while let Some(new) = f(previous) {
+                    match self.compare_exchange(previous, new)? {
+                        Ok(previous) => return Ok(Ok(previous)),
+                        Err(changed) => previous = changed,
+                    }
+                }
+                Ok(Err(previous))
+            }
+        }
+    };
+}
+
+atomic_integer! {
+    /// A Java `AtomicInteger`, see the [module docs](self).
+    AtomicI32(i32) = AtomicInteger
+}
+
+atomic_integer! {
+    /// A Java `AtomicLong`, see the [module docs](self).
+    AtomicI64(i64) = AtomicLong
+}
+
+/// The (possibly null) value of an [`AtomicRef`].
+type Value<T> = Option<Global<T>>;
+
+/// A Java `AtomicReference`, which holds a (possibly null) `T`, see the [module docs](self).
+///
+/// Java compares references by identity, so [`compare_exchange`](Self::compare_exchange) only succeeds if the
+/// value is the same object as `current` (not just equal to it).
+pub struct AtomicRef<T: Upcast<Object> + Upcast<T>> {
+    java: Global<AtomicReference<T>>,
+}
+
+impl<T: Upcast<Object> + Upcast<T>> AtomicRef<T> {
+    /// Creates a Java atomic holding `value`.
+    pub fn new(value: Option<&T>) -> GlobalResult<Self> {
+        let java = AtomicReference::<T>::new().global().execute()?;
+        let this = Self { java };
+        if value.is_some() {
+            this.store(value)?;
+        }
+        Ok(this)
+    }
+
+    /// Wraps an atomic created by Java.
+    pub fn from_java(java: Global<AtomicReference<T>>) -> Self {
+        Self { java }
+    }
+
+    /// The Java atomic, e.g. to pass to Java code.
+    pub fn as_java(&self) -> &Global<AtomicReference<T>> {
+        &self.java
+    }
+
+    /// Returns the value (`get`).
+    pub fn load(&self) -> GlobalResult<Value<T>> {
+        self.java.get().global().execute()
+    }
+
+    // The methods taking values are called through `call_method`, since generated methods don't take nulls. `V` is
+    // erased to `Object` in their descriptors.
+
+    /// Sets the value (`set`).
+    pub fn store(&self, value: Option<&T>) -> GlobalResult<()> {
+        Jvm::with(|jvm| {
+            // SAFETY: `value` is an object
+            unsafe {
+                call_method(
+                    jvm,
+                    &*self.java,
+                    c"set",
+                    c"(Ljava/lang/Object;)V",
+                    &[value.into_jni_value()],
+                )
+            }
+        })
+    }
+
+    /// Sets the value, returning the previous one (`getAndSet`).
+    pub fn swap(&self, value: Option<&T>) -> GlobalResult<Value<T>> {
+        Jvm::with(|jvm| {
+            // SAFETY: `value` is an object
+            let previous: Option<Local<T>> = unsafe {
+                call_method(
+                    jvm,
+                    &*self.java,
+                    c"getAndSet",
+                    c"(Ljava/lang/Object;)Ljava/lang/Object;",
+                    &[value.into_jni_value()],
+                )
+            }?;
+            Ok(previous.map(|p| jvm.global(&*p)))
+        })
+    }
+
+    /// Sets the value to `new` if it is `current` (`compareAndExchange`). Returns the previous value, as `Ok` if it
+    /// was `current` and as `Err` otherwise.
+    pub fn compare_exchange(
+        &self,
+        current: Option<&T>,
+        new: Option<&T>,
+    ) -> GlobalResult<Result<Value<T>, Value<T>>> {
+        Jvm::with(|jvm| {
+            // SAFETY: both arguments are objects
+            let previous: Option<Local<T>> = unsafe {
+                call_method(
+                    jvm,
+                    &*self.java,
+                    c"compareAndExchange",
+                    c"(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+                    &[current.into_jni_value(), new.into_jni_value()],
+                )
+            }?;
+            let same = match (&previous, current) {
+                (Some(previous), Some(current)) => is_same_object(jvm, &**previous, current),
+                (None, None) => true,
+                _ => false,
+            };
+            let previous = previous.map(|p| jvm.global(&*p));
+            Ok(if same { Ok(previous) } else { Err(previous) })
+        })
+    }
+}
+
+/// A Java `ConcurrentHashMap`, see the [module docs](self).
+///
+/// Keys and values are compared with their `equals` methods.
+pub struct ConcurrentMap<K: Upcast<Object>, V: Upcast<Object>> {
+    java: Global<ConcurrentHashMap<K, V>>,
+}
+
+impl<K: Upcast<Object>, V: Upcast<Object>> ConcurrentMap<K, V> {
+    /// Creates an empty `ConcurrentHashMap`.
+    pub fn new() -> GlobalResult<Self> {
+        let java = ConcurrentHashMap::new().global().execute()?;
+        Ok(Self { java })
+    }
+
+    /// Wraps a map created by Java.
+    pub fn from_java(java: Global<ConcurrentHashMap<K, V>>) -> Self {
+        Self { java }
+    }
+
+    /// The Java map, e.g. to pass to Java code.
+    pub fn as_java(&self) -> &Global<ConcurrentHashMap<K, V>> {
+        &self.java
+    }
+
+    /// The number of entries (`mappingCount`).
+    pub fn len(&self) -> GlobalResult<usize> {
+        let len = self.java.mapping_count().execute()?;
+        Ok(usize::try_from(len).unwrap_or(usize::MAX))
+    }
+
+    pub fn is_empty(&self) -> GlobalResult<bool> {
+        self.java.is_empty().execute()
+    }
+
+    /// The value of `key`, if there is one (`get`).
+    pub fn get(&self, key: impl IntoJava<K>) -> GlobalResult<Option<Global<V>>> {
+        Jvm::with(|jvm| {
+            let key = key.into_java(jvm)?;
+            let key = jvm.local(key.as_jref()?);
+            self.java.get(&key).global().execute_with(jvm)
+        })
+    }
+
+    pub fn contains_key(&self, key: impl IntoJava<K>) -> GlobalResult<bool> {
+        Jvm::with(|jvm| {
+            let key = key.into_java(jvm)?;
+            let key = jvm.local(key.as_jref()?);
+            self.java.contains_key(&key).execute_with(jvm)
+        })
+    }
+
+    /// Sets the value of `key`, returning the previous one (`put`).
+    pub fn insert(
+        &self,
+        key: impl IntoJava<K>,
+        value: impl IntoJava<V>,
+    ) -> GlobalResult<Option<Global<V>>> {
+        self.java.put(key, value).global().execute()
+    }
+
+    /// Sets the value of `key` unless it has one, which is returned (`putIfAbsent`).
+    pub fn insert_if_absent(
+        &self,
+        key: impl IntoJava<K>,
+        value: impl IntoJava<V>,
+    ) -> GlobalResult<Option<Global<V>>> {
+        self.java.put_if_absent(key, value).global().execute()
+    }
+
+    /// Sets the value of `key` to `new` if it is (equal to) `current`, returning whether it did (`replace`).
+    pub fn replace_if_eq(
+        &self,
+        key: impl IntoJava<K>,
+        current: impl IntoJava<V>,
+        new: impl IntoJava<V>,
+    ) -> GlobalResult<bool> {
+        self.java.replace(key, current, new).execute()
+    }
+
+    /// Removes the value of `key`, returning it (`remove`).
+    pub fn remove(&self, key: impl IntoJava<K>) -> GlobalResult<Option<Global<V>>> {
+        Jvm::with(|jvm| {
+            let key = key.into_java(jvm)?;
+            let key = jvm.local(key.as_jref()?);
+            self.java.remove(&key).global().execute_with(jvm)
+        })
+    }
+
+    pub fn clear(&self) -> GlobalResult<()> {
+        self.java.clear().execute()
+    }
+}
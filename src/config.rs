@@ -0,0 +1,140 @@
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+use crate::{frame::DEFAULT_FRAME_CAPACITY, jvm::try_global_jvm, Error, GlobalResult};
+
+static CAPTURE_BACKTRACES: AtomicBool = AtomicBool::new(false);
+static METRICS: AtomicBool = AtomicBool::new(true);
+static STRING_FAST_PATH: AtomicBool = AtomicBool::new(true);
+static LOCAL_FRAME_CAPACITY: AtomicI32 = AtomicI32::new(DEFAULT_FRAME_CAPACITY);
+
+/// Settings of duchess that apply to the whole process, applied with [`Config::apply`]:
+///
+/// ```
+/// # fn main() -> duchess::GlobalResult<()> {
+/// duchess::Config::new()
+///     .capture_backtraces(true)
+///     .local_frame_capacity(64)
+///     .apply()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`capture_backtraces`](Self::capture_backtraces) and [`metrics`](Self::metrics) can be changed at any time, also
+/// with [`Config::set_capture_backtraces`] and [`Config::set_metrics`]. The other settings are fixed once the JVM is
+/// launched (or an existing one is used).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    capture_backtraces: bool,
+    metrics: bool,
+    string_fast_path: bool,
+    local_frame_capacity: i32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            capture_backtraces: false,
+            metrics: true,
+            string_fast_path: true,
+            local_frame_capacity: DEFAULT_FRAME_CAPACITY,
+        }
+    }
+}
+
+impl Config {
+    /// The default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The settings in effect.
+    pub fn current() -> Self {
+        Self {
+            capture_backtraces: capture_backtraces(),
+            metrics: metrics(),
+            string_fast_path: string_fast_path(),
+            local_frame_capacity: local_frame_capacity(),
+        }
+    }
+
+    /// Whether the exceptions returned by [`Jvm::with`](crate::Jvm::with) (and so by
+    /// [`JvmOp::execute`](crate::JvmOp::execute)) come with their stack traces, as if by
+    /// [`Error::with_backtrace`]. Off by default, since capturing a trace takes many JNI calls.
+    pub fn capture_backtraces(mut self, capture: bool) -> Self {
+        self.capture_backtraces = capture;
+        self
+    }
+
+    /// Whether operations are reported to the observer set with
+    /// [`set_execution_observer`](crate::observe::set_execution_observer), and counted by
+    /// `duchess::crossings` (with the `count-crossings` feature). On by default.
+    pub fn metrics(mut self, metrics: bool) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Whether short ASCII strings are converted to Java strings without encoding them first. On by default; turning
+    /// it off sends every string through the general conversion, e.g. to rule it out when debugging.
+    pub fn string_fast_path(mut self, fast_path: bool) -> Self {
+        self.string_fast_path = fast_path;
+        self
+    }
+
+    /// The number of local references that the JVM is asked to reserve for the local frames that duchess pushes, e.g.
+    /// for each [`JvmOp::execute`](crate::JvmOp::execute) (see `PushLocalFrame`). Frames grow as needed, so this only
+    /// matters for performance. Must be positive, and defaults to 32.
+    pub fn local_frame_capacity(mut self, capacity: i32) -> Self {
+        self.local_frame_capacity = capacity;
+        self
+    }
+
+    /// Applies the settings. Once the JVM is launched, this fails with [`Error::JvmAlreadyExists`] (applying none of
+    /// them) if the settings that are fixed by then differ from the current ones, and with [`Error::InvalidConfig`] if
+    /// a setting is out of range.
+    pub fn apply(self) -> GlobalResult<()> {
+        if self.local_frame_capacity <= 0 {
+            return Err(Error::InvalidConfig(format!(
+                "the local frame capacity must be positive, not {}",
+                self.local_frame_capacity
+            )));
+        }
+        let current = Self::current();
+        if try_global_jvm().is_some()
+            && (self.string_fast_path != current.string_fast_path
+                || self.local_frame_capacity != current.local_frame_capacity)
+        {
+            return Err(Error::JvmAlreadyExists);
+        }
+        STRING_FAST_PATH.store(self.string_fast_path, Ordering::Relaxed);
+        LOCAL_FRAME_CAPACITY.store(self.local_frame_capacity, Ordering::Relaxed);
+        Self::set_capture_backtraces(self.capture_backtraces);
+        Self::set_metrics(self.metrics);
+        Ok(())
+    }
+
+    /// Sets [`capture_backtraces`](Self::capture_backtraces), which takes effect immediately.
+    pub fn set_capture_backtraces(capture: bool) {
+        CAPTURE_BACKTRACES.store(capture, Ordering::Relaxed);
+    }
+
+    /// Sets [`metrics`](Self::metrics), which takes effect immediately.
+    pub fn set_metrics(metrics: bool) {
+        METRICS.store(metrics, Ordering::Relaxed);
+    }
+}
+
+pub(crate) fn capture_backtraces() -> bool {
+    CAPTURE_BACKTRACES.load(Ordering::Relaxed)
+}
+
+pub(crate) fn metrics() -> bool {
+    METRICS.load(Ordering::Relaxed)
+}
+
+pub(crate) fn string_fast_path() -> bool {
+    STRING_FAST_PATH.load(Ordering::Relaxed)
+}
+
+pub(crate) fn local_frame_capacity() -> i32 {
+    LOCAL_FRAME_CAPACITY.load(Ordering::Relaxed)
+}
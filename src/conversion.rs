@@ -0,0 +1,104 @@
+//! Conversions of Java objects into Rust types that are registered once, at startup, rather than implemented with
+//! [`IntoRust`], e.g. because neither the Java class nor the Rust type is defined by your crate (so the orphan rules
+//! forbid the impl):
+//!
+//! ```ignore
+//! duchess::conversion::register::<java::time::Instant, chrono::DateTime<chrono::Utc>>(|jvm, instant| {
+//!     let seconds = instant.get_epoch_second().execute_with(jvm)?;
+//!     let nanos = instant.get_nano().execute_with(jvm)?;
+//!     Ok(chrono::DateTime::from_timestamp(seconds, nanos as u32).unwrap())
+//! });
+//! ```
+//!
+//! The registered conversion is then used whenever a Java object of that class is converted into a [`Converted`] of
+//! that type, including as part of larger structures:
+//!
+//! ```ignore
+//! let times: Vec<Converted<chrono::DateTime<chrono::Utc>>> = events.times().assert_not_null().to_rust().execute()?;
+//! ```
+//!
+//! Classes opt into this by implementing [`Convertible`] (e.g. `impl Convertible for java::time::Instant {}` next to the
+//! registration), which leaves inference of `to_rust()` unaffected for all others.
+//!
+//! Conversions are looked up by the Rust type of the Java object (`java::time::Instant` above) as declared, so a
+//! subclass needs its own registration.
+
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    sync::RwLock,
+};
+
+use crate::{ConversionError, IntoRust, JavaObject, Jvm};
+
+/// A conversion registered with [`register`].
+pub type Conversion<J, R> = for<'jvm> fn(&mut Jvm<'jvm>, &J) -> crate::Result<'jvm, R>;
+
+/// The registered conversions, keyed by the `TypeId`s of the Java type and the Rust type. The values are
+/// [`Conversion`]s, which are boxed since their types differ.
+static CONVERSIONS: RwLock<BTreeMap<(TypeId, TypeId), Box<dyn Any + Send + Sync>>> =
+    RwLock::new(BTreeMap::new());
+
+/// Registers `convert` as the conversion of `J` objects into `R` values, replacing the one registered before (if any).
+pub fn register<J, R>(convert: Conversion<J, R>)
+where
+    J: JavaObject,
+    R: 'static,
+{
+    let mut conversions = CONVERSIONS.write().unwrap_or_else(|e| e.into_inner());
+    conversions.insert((TypeId::of::<J>(), TypeId::of::<R>()), Box::new(convert));
+}
+
+/// Returns the conversion of `J` objects into `R` values, if one has been registered.
+pub fn registered<J, R>() -> Option<Conversion<J, R>>
+where
+    J: JavaObject,
+    R: 'static,
+{
+    let conversions = CONVERSIONS.read().unwrap_or_else(|e| e.into_inner());
+    let convert = conversions.get(&(TypeId::of::<J>(), TypeId::of::<R>()))?;
+    // Copied out of the map, so that the lock isn't held while converting (which may convert nested objects)
+    Some(*convert.downcast_ref::<Conversion<J, R>>().unwrap())
+}
+
+/// A Rust value converted from a Java object by the conversion [registered](register) for its class. Converting an
+/// object for whose class none is registered fails with [`ConversionError::Unregistered`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Converted<R>(pub R);
+
+impl<R> Converted<R> {
+    pub fn into_inner(self) -> R {
+        self.0
+    }
+}
+
+impl<R> std::ops::Deref for Converted<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.0
+    }
+}
+
+/// Java classes whose objects can be converted into [`Converted`] values. This is an opt-in because a blanket impl
+/// of [`IntoRust<Converted<R>>`](IntoRust) for all Java objects would make the target type of `to_rust()` ambiguous
+/// for classes with a single conversion, like `java.lang.String`.
+pub trait Convertible: JavaObject {}
+
+impl<J, R> IntoRust<Converted<R>> for &J
+where
+    J: Convertible,
+    R: 'static,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Converted<R>> {
+        // Looked up once per deep conversion, rather than for each element
+        let Some(convert) = crate::into_rust::deep_cached(registered::<J, R>) else {
+            return Err(ConversionError::Unregistered {
+                java: std::any::type_name::<J>(),
+                rust: std::any::type_name::<R>(),
+            }
+            .into());
+        };
+        Ok(Converted(convert(jvm, self)?))
+    }
+}
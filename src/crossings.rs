@@ -0,0 +1,72 @@
+//! Counts of the crossings from Rust into the JVM made by the current thread, to check how many JNI calls an operation
+//! takes. Only available with the `count-crossings` feature, since counting has a (small) cost on every JNI call.
+//!
+//! ```
+//! # use duchess::{java, prelude::*};
+//! let (len, crossings) = duchess::crossings::count(|| {
+//!     "hello".to_java::<java::lang::String>().assert_not_null().length().execute()
+//! });
+//! assert_eq!(len.unwrap(), 5);
+//! assert_eq!(crossings.local_frames, 1);
+//! ```
+
+use std::cell::Cell;
+
+/// The crossings counted by [`count`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Crossings {
+    /// The number of JNI calls, including those that push and pop local frames or delete local refs.
+    pub jni_calls: u64,
+    /// The number of times the thread was attached to the JVM.
+    pub attaches: u64,
+    /// The number of local frames pushed by duchess.
+    pub local_frames: u64,
+}
+
+thread_local! {
+    static TOTAL: Cell<Crossings> = const {
+        Cell::new(Crossings {
+            jni_calls: 0,
+            attaches: 0,
+            local_frames: 0,
+        })
+    };
+}
+
+/// Runs `f`, returning its result along with the crossings the current thread made meanwhile. Those of other threads
+/// (e.g. of callbacks run from Java threads) aren't counted.
+pub fn count<R>(f: impl FnOnce() -> R) -> (R, Crossings) {
+    let before = TOTAL.with(Cell::get);
+    let r = f();
+    let after = TOTAL.with(Cell::get);
+    let crossings = Crossings {
+        jni_calls: after.jni_calls - before.jni_calls,
+        attaches: after.attaches - before.attaches,
+        local_frames: after.local_frames - before.local_frames,
+    };
+    (r, crossings)
+}
+
+fn add(f: impl FnOnce(&mut Crossings)) {
+    if !crate::config::metrics() {
+        return;
+    }
+    // `try_with` because JNI calls may be made from other thread-local destructors during thread exit
+    let _ = TOTAL.try_with(|total| {
+        let mut crossings = total.get();
+        f(&mut crossings);
+        total.set(crossings);
+    });
+}
+
+pub(crate) fn jni_call() {
+    add(|crossings| crossings.jni_calls += 1);
+}
+
+pub(crate) fn attach() {
+    add(|crossings| crossings.attaches += 1);
+}
+
+pub(crate) fn local_frame() {
+    add(|crossings| crossings.local_frames += 1);
+}
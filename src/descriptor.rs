@@ -0,0 +1,61 @@
+//! JNI method descriptors, like `(ILjava/lang/String;)V`.
+
+/// The type of a parameter or result, as far as calling conventions go.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Object,
+    Boolean,
+    Byte,
+    Char,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+}
+
+/// Splits a method descriptor like `(ILjava/lang/String;)V` into the kinds of its parameters and result (`None` for
+/// `void`).
+pub(crate) fn parse_descriptor(descriptor: &str) -> Option<(Vec<Kind>, Option<Kind>)> {
+    fn parse_type(chars: &mut std::str::Chars<'_>) -> Option<Kind> {
+        Some(match chars.next()? {
+            'Z' => Kind::Boolean,
+            'B' => Kind::Byte,
+            'C' => Kind::Char,
+            'S' => Kind::Short,
+            'I' => Kind::Int,
+            'J' => Kind::Long,
+            'F' => Kind::Float,
+            'D' => Kind::Double,
+            'L' => {
+                chars.find(|&c| c == ';')?;
+                Kind::Object
+            }
+            '[' => {
+                parse_type(chars)?;
+                Kind::Object
+            }
+            _ => return None,
+        })
+    }
+
+    let mut chars = descriptor.strip_prefix('(')?.chars();
+    let mut params = vec![];
+    loop {
+        if chars.as_str().starts_with(')') {
+            chars.next();
+            break;
+        }
+        params.push(parse_type(&mut chars)?);
+    }
+    let result = if chars.as_str() == "V" {
+        None
+    } else {
+        let result = parse_type(&mut chars)?;
+        if !chars.as_str().is_empty() {
+            return None;
+        }
+        Some(result)
+    };
+    Some((params, result))
+}
@@ -0,0 +1,329 @@
+//! Direct `java.nio.ByteBuffer`s over Rust memory, and Rust slices over the memory of direct buffers, so that bytes
+//! are shared between Rust and Java without copying them across JNI.
+//!
+//! [`DirectBuffer::new`] moves a `Vec<u8>` into a buffer created with JNI's `NewDirectByteBuffer`. The memory is freed
+//! once neither Rust (the `DirectBuffer`) nor Java (the buffer, or any view of it) uses it anymore: Java code may keep
+//! the buffer for as long as it likes. [`DirectBuffer::borrow`] instead lends a `&mut [u8]` to Java for the duration
+//! of a closure.
+//!
+//! In the other direction, [`direct_slice`] and [`direct_slice_mut`] view the memory of a direct buffer created by
+//! Java (e.g. with `ByteBuffer.allocateDirect`), via `GetDirectBufferAddress`.
+//!
+//! The memory moved into direct buffers is counted in [`memory::stats`](crate::memory::stats), and can be reported
+//! to the JVM's garbage collector, see [`memory`](crate::memory).
+
+use std::{ptr::NonNull, sync::Mutex};
+
+use jni_sys::{jlong, jvalue};
+use once_cell::sync::OnceCell;
+
+use crate::{
+    cast::Upcast,
+    find::{find_class, find_method},
+    java::{self, lang::Object, nio::ByteBuffer},
+    jvm::JavaObjectExt,
+    memory::{Pinned, PinnedKind},
+    Error, Global, Jvm, JvmOp, Local,
+};
+
+/// A direct `ByteBuffer` over memory owned by Rust, see the [module docs](self).
+pub struct DirectBuffer {
+    buffer: Global<ByteBuffer>,
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: the memory isn't tied to any thread, and the buffer is a global reference
+unsafe impl Send for DirectBuffer {}
+unsafe impl Sync for DirectBuffer {}
+
+/// The allocation of a `Vec<u8>`, taken apart so that it can be shared with Java without asserting unique access.
+struct Memory {
+    ptr: NonNull<u8>,
+    len: usize,
+    capacity: usize,
+    _pinned: Pinned,
+}
+
+// SAFETY: as for `Vec<u8>`
+unsafe impl Send for Memory {}
+unsafe impl Sync for Memory {}
+
+impl Memory {
+    fn new(bytes: Vec<u8>, pinned: Pinned) -> Self {
+        let mut bytes = std::mem::ManuallyDrop::new(bytes);
+        Memory {
+            // SAFETY: the pointer of a `Vec` is never null (but dangling if it has no capacity)
+            ptr: unsafe { NonNull::new_unchecked(bytes.as_mut_ptr()) },
+            len: bytes.len(),
+            capacity: bytes.capacity(),
+            _pinned: pinned,
+        }
+    }
+}
+
+impl Drop for Memory {
+    fn drop(&mut self) {
+        // SAFETY: these are the parts of a `Vec<u8>`, which is dropped only once
+        drop(unsafe { Vec::from_raw_parts(self.ptr.as_ptr(), self.len, self.capacity) });
+    }
+}
+
+impl DirectBuffer {
+    /// Moves `bytes` into a new direct buffer.
+    ///
+    /// The bytes are freed once both the `DirectBuffer` has been dropped and Java has garbage collected the buffer,
+    /// by a `java.lang.ref.Cleaner`. If [`memory::report_to_jvm`](crate::memory::report_to_jvm) is enabled and the
+    /// JVM's direct memory is exhausted even after a garbage collection, fails with an `OutOfMemoryError`.
+    pub fn new<'jvm>(jvm: &mut Jvm<'jvm>, bytes: Vec<u8>) -> crate::Result<'jvm, Self> {
+        let pinned = Pinned::new(jvm, PinnedKind::DirectBuffer, bytes.len())?;
+        let memory = Memory::new(bytes, pinned);
+        let (ptr, len) = (memory.ptr, memory.len);
+        // SAFETY: the memory is valid until it is freed by the cleaner registered below
+        let buffer = unsafe { new_direct_byte_buffer(jvm, ptr, len) }?;
+
+        let memory = Mutex::new(Some(memory));
+        let release = java::lang::Runnable::from_fn(jvm, move || {
+            drop(memory.lock().unwrap_or_else(|e| e.into_inner()).take());
+        })?;
+        register_cleanup(jvm, &buffer, &release)?;
+
+        Ok(DirectBuffer {
+            buffer: jvm.global(&*buffer),
+            ptr,
+            len,
+        })
+    }
+
+    /// Lends `bytes` to Java as a direct buffer, which is passed to `op`.
+    ///
+    /// # Safety
+    ///
+    /// Java code must not use the buffer once `op` returns, since `bytes` may then be freed or used by Rust. Neither
+    /// `op` nor the Java code that it calls may keep the buffer (or views of it, like those returned by
+    /// `ByteBuffer.slice()`) beyond that.
+    pub unsafe fn borrow<'jvm, R>(
+        jvm: &mut Jvm<'jvm>,
+        bytes: &mut [u8],
+        op: impl FnOnce(&mut Jvm<'jvm>, &ByteBuffer) -> crate::Result<'jvm, R>,
+    ) -> crate::Result<'jvm, R> {
+        let ptr = NonNull::new(bytes.as_mut_ptr()).unwrap();
+        // SAFETY: `bytes` stays borrowed while the caller guarantees that the buffer is used
+        let buffer = unsafe { new_direct_byte_buffer(jvm, ptr, bytes.len()) }?;
+        op(jvm, &buffer)
+    }
+
+    /// The Java buffer over the memory, to pass to Java code.
+    pub fn as_java(&self) -> &Global<ByteBuffer> {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The memory of the buffer.
+    ///
+    /// # Safety
+    ///
+    /// Java code must not change the memory while the slice is borrowed.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        // SAFETY: the buffer keeps `len` bytes alive at `ptr`, and the caller guarantees that they aren't changed
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// The memory of the buffer, for writing.
+    ///
+    /// # Safety
+    ///
+    /// Java code must not read or change the memory while the slice is borrowed.
+    pub unsafe fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: as for `as_slice`, and the caller guarantees that Java doesn't access the memory at all
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+/// The memory of the direct buffer `buffer`. Fails if `buffer` isn't a direct buffer.
+///
+/// # Safety
+///
+/// Java code must not change the memory while the slice is borrowed.
+pub unsafe fn direct_slice<'a, 'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    buffer: &'a ByteBuffer,
+) -> crate::Result<'jvm, &'a [u8]> {
+    let (ptr, len) = direct_memory(jvm, buffer)?;
+    // SAFETY: `buffer` keeps its memory alive while it is borrowed, and the caller guarantees that it isn't changed
+    Ok(unsafe { std::slice::from_raw_parts(ptr.as_ptr(), len) })
+}
+
+/// A reference that owns the `ByteBuffer` it points to, i.e. a [`Local`] or a [`Global`], which
+/// [`direct_slice_mut`] borrows uniquely for as long as the slice is used.
+pub trait BufferRef: std::ops::Deref<Target = ByteBuffer> + sealed::Sealed {}
+
+impl BufferRef for Local<'_, ByteBuffer> {}
+
+impl BufferRef for Global<ByteBuffer> {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::Local<'_, crate::java::nio::ByteBuffer> {}
+
+    impl Sealed for crate::Global<crate::java::nio::ByteBuffer> {}
+}
+
+/// The memory of the direct buffer `buffer`, for writing. Fails if `buffer` isn't a direct buffer, or is read-only.
+///
+/// The reference is borrowed mutably, so that it can't be used for another slice while this one lives.
+///
+/// # Safety
+///
+/// Java code must not read or change the memory while the slice is borrowed, and no other slice of it may exist
+/// (e.g. through another reference to the same buffer).
+pub unsafe fn direct_slice_mut<'a, 'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    buffer: &'a mut impl BufferRef,
+) -> crate::Result<'jvm, &'a mut [u8]> {
+    let buffer: &ByteBuffer = buffer;
+    if buffer.is_read_only().execute_with(jvm)? {
+        return Err(Error::JvmInternal(
+            "cannot write to a read-only buffer".into(),
+        ));
+    }
+    let (ptr, len) = direct_memory(jvm, buffer)?;
+    // SAFETY: as for `direct_slice`, and the caller guarantees that nothing else accesses the memory
+    Ok(unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), len) })
+}
+
+/// The address and capacity of the direct buffer `buffer`, with `GetDirectBufferAddress` and
+/// `GetDirectBufferCapacity`.
+pub(crate) fn direct_memory<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    buffer: &impl Upcast<java::nio::Buffer>,
+) -> crate::Result<'jvm, (NonNull<u8>, usize)> {
+    let env = jvm.env();
+    let buffer_raw = buffer.as_raw().as_ptr();
+    // SAFETY: `buffer` is a live reference to a buffer (which JNI checks to be direct)
+    let (address, capacity) = unsafe {
+        (
+            env.invoke_unchecked(
+                |env| env.GetDirectBufferAddress,
+                |env, f| f(env, buffer_raw),
+            ),
+            env.invoke_unchecked(
+                |env| env.GetDirectBufferCapacity,
+                |env, f| f(env, buffer_raw),
+            ),
+        )
+    };
+    match NonNull::new(address.cast::<u8>()) {
+        Some(ptr) => Ok((ptr, capacity as usize)),
+        // Empty buffers may have no address
+        None if capacity == 0 => Ok((NonNull::dangling(), 0)),
+        // The capacity is -1 for buffers that aren't direct
+        None if capacity < 0 => Err(Error::JvmInternal("buffer is not a direct buffer".into())),
+        None => Err(Error::JvmInternal(
+            "JVM does not support access to direct buffers".into(),
+        )),
+    }
+}
+
+/// Creates a direct buffer over the `len` bytes at `ptr` with `NewDirectByteBuffer`.
+///
+/// # Safety
+///
+/// The memory must stay valid for as long as the buffer is used.
+unsafe fn new_direct_byte_buffer<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    ptr: NonNull<u8>,
+    len: usize,
+) -> crate::Result<'jvm, Local<'jvm, ByteBuffer>> {
+    let Ok(capacity) = jlong::try_from(len) else {
+        return Err(Error::SliceTooLong(len));
+    };
+    let env = jvm.env();
+    // SAFETY: the caller guarantees that the memory stays valid
+    let buffer: Option<Local<ByteBuffer>> = unsafe {
+        env.invoke(
+            |env| env.NewDirectByteBuffer,
+            |env, f| f(env, ptr.as_ptr().cast(), capacity),
+        )
+    }?;
+    buffer.ok_or_else(|| Error::JvmInternal("JVM does not support creating direct buffers".into()))
+}
+
+/// Registers `release` to be run once `buffer` becomes unreachable, with a `java.lang.ref.Cleaner` shared by all
+/// direct buffers. Looked up by hand since `java.lang.ref` is a Rust keyword.
+fn register_cleanup<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    buffer: &ByteBuffer,
+    release: &java::lang::Runnable,
+) -> crate::Result<'jvm, ()> {
+    static CLEANER: OnceCell<Global<Object>> = OnceCell::new();
+
+    let cleaner_class = find_class(jvm, c"java/lang/ref/Cleaner")?;
+    let cleaner =
+        CLEANER.get_or_try_init::<_, crate::Error<Local<java::lang::Throwable>>>(|| {
+            let create = find_method(
+                jvm,
+                &cleaner_class,
+                c"create",
+                c"()Ljava/lang/ref/Cleaner;",
+                true,
+            )?;
+            let env = jvm.env();
+            // SAFETY: `create` is a static method of `cleaner_class` without parameters
+            let cleaner: Option<Local<Object>> = unsafe {
+                env.invoke(
+                    |env| env.CallStaticObjectMethodA,
+                    |env, f| {
+                        f(
+                            env,
+                            cleaner_class.as_raw().as_ptr(),
+                            create.as_ptr(),
+                            std::ptr::null(),
+                        )
+                    },
+                )
+            }?;
+            let cleaner = cleaner.ok_or(Error::NullDeref)?;
+            Ok(jvm.global(&*cleaner))
+        })?;
+
+    let register = find_method(
+        jvm,
+        &cleaner_class,
+        c"register",
+        c"(Ljava/lang/Object;Ljava/lang/Runnable;)Ljava/lang/ref/Cleaner$Cleanable;",
+        false,
+    )?;
+    let args = [
+        jvalue {
+            l: buffer.as_raw().as_ptr(),
+        },
+        jvalue {
+            l: release.as_raw().as_ptr(),
+        },
+    ];
+    let env = jvm.env();
+    // SAFETY: `register` is a method of `cleaner`'s class taking an `Object` and a `Runnable`
+    let _cleanable: Option<Local<Object>> = unsafe {
+        env.invoke(
+            |env| env.CallObjectMethodA,
+            |env, f| {
+                f(
+                    env,
+                    cleaner.as_raw().as_ptr(),
+                    register.as_ptr(),
+                    args.as_ptr(),
+                )
+            },
+        )
+    }?;
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use std::{
     fmt::{Debug, Display},
+    path::PathBuf,
     result,
 };
 
 use thiserror::Error;
 
-use crate::AsJRef;
+use crate::{cast::Upcast, stack_trace::JavaStackTrace, AsJRef};
 use crate::{java::lang::Throwable, Global, Jvm, JvmOp, Local};
 
 /// Result returned by most Java operations that may contain a local reference
@@ -16,34 +17,157 @@ pub type Result<'jvm, T> = result::Result<T, Error<Local<'jvm, Throwable>>>;
 /// exception as a global reference.
 pub type GlobalResult<T> = result::Result<T, Error<Global<Throwable>>>;
 
-#[derive(Error)]
 pub enum Error<T: AsJRef<Throwable>> {
-    /// A reference to an uncaught Java exception
-    #[error("Java invocation threw: {}", try_extract_message(.0))]
+    /// A reference to an uncaught Java exception. Use [`Error::with_backtrace`] to have its causes reported by
+    /// [`source`](std::error::Error::source) as well.
     Thrown(T),
 
-    #[error(
-        "slice was too long (`{0}`) to convert to a Java array, which are limited to `i32::MAX`"
-    )]
+    /// A reference to an uncaught Java exception, along with its details captured by
+    /// [`Error::with_backtrace`], which are displayed (including the stack trace) without using the JVM.
+    ///
+    /// The causes of the exception are reported by [`source`](std::error::Error::source), one level at a time.
+    ThrownWithBacktrace(T, Box<JavaStackTrace>),
+
     SliceTooLong(usize),
 
-    #[error("attempted to deref a null Java object pointer")]
     NullDeref,
 
-    #[error("attempted to nest `Jvm::with` calls")]
-    NestedUsage,
-
-    #[error("JVM already exists")]
     JvmAlreadyExists,
 
+    /// There are several JVMs in the process (as many as given), so duchess can't tell which one to use. Select one
+    /// with [`ExistingJvm::select`](crate::ExistingJvm::select).
+    MultipleJvms(usize),
+
+    /// The JVM is shutting down, so no new operations are accepted, see
+    /// [`install_shutdown_bridge`](crate::install_shutdown_bridge).
+    ShuttingDown,
+
+    /// The JVM was used (e.g., with [`Jvm::with`]) while the thread was accessing the elements of an array with
+    /// `with_elements`, during which it must not make any JNI calls.
+    InCriticalSection,
+
+    /// [`Config::apply`](crate::Config::apply) was given an invalid setting, which is described.
+    InvalidConfig(String),
+
+    /// [`Jvm::add_classpath`](crate::Jvm::add_classpath) was given a path that doesn't exist.
+    ClasspathNotFound(PathBuf),
+
     #[cfg(feature = "dylibjvm")]
-    #[error(transparent)]
-    UnableToLoadLibjvm(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    UnableToLoadLibjvm(Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// A value could not be converted between Java and Rust because of its contents.
+    Conversion(ConversionError),
 
-    #[error("{0}")]
     JvmInternal(String),
 }
 
+impl<T> Display for Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Thrown(t) => write!(f, "Java invocation threw: {}", try_extract_message(t)),
+            Error::ThrownWithBacktrace(_, trace) => {
+                write!(f, "Java invocation threw: ")?;
+                trace.fmt_exception(f)
+            }
+            Error::SliceTooLong(len) => write!(
+                f,
+                "slice was too long (`{len}`) to convert to a Java array, which are limited to `i32::MAX`"
+            ),
+            Error::NullDeref => write!(f, "attempted to deref a null Java object pointer"),
+            Error::JvmAlreadyExists => write!(f, "JVM already exists"),
+            Error::MultipleJvms(count) => write!(
+                f,
+                "found {count} JVMs in the process, select one with `ExistingJvm::select`"
+            ),
+            Error::ShuttingDown => write!(
+                f,
+                "the JVM is shutting down and doesn't accept new operations"
+            ),
+            Error::InCriticalSection => write!(
+                f,
+                "the JVM can't be used while the elements of an array are accessed with `with_elements`"
+            ),
+            Error::InvalidConfig(message) => write!(f, "invalid duchess config: {message}"),
+            Error::ClasspathNotFound(path) => {
+                write!(f, "classpath entry `{}` doesn't exist", path.display())
+            }
+            #[cfg(feature = "dylibjvm")]
+            Error::UnableToLoadLibjvm(e) => Display::fmt(e, f),
+            Error::Conversion(e) => Display::fmt(e, f),
+            Error::JvmInternal(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl<T> std::error::Error for Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ThrownWithBacktrace(_, trace) => trace.cause_as_error(),
+            #[cfg(feature = "dylibjvm")]
+            Error::UnableToLoadLibjvm(e) => e.source(),
+            Error::Conversion(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "dylibjvm")]
+impl<T> From<Box<dyn std::error::Error + Send + Sync + 'static>> for Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    fn from(e: Box<dyn std::error::Error + Send + Sync + 'static>) -> Self {
+        Error::UnableToLoadLibjvm(e)
+    }
+}
+
+impl<T> From<ConversionError> for Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    fn from(e: ConversionError) -> Self {
+        Error::Conversion(e)
+    }
+}
+
+/// A value could not be converted between Java and Rust because of its contents (e.g., a number that doesn't fit in
+/// the target type), as opposed to a failure of the JVM itself. Returned by the fallible conversions
+/// [`TryIntoRust`](crate::TryIntoRust) and [`TryToJava`](crate::TryToJava).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    #[error("value `{value}` is out of range for `{target}`")]
+    OutOfRange { value: String, target: &'static str },
+
+    #[error("Java string contained invalid modified UTF-8: {0}")]
+    InvalidString(String),
+
+    /// The value doesn't have the syntax or shape of the target type, e.g. a Java URI that isn't a valid URL.
+    #[error("value `{value}` is not a valid `{target}`")]
+    Malformed { value: String, target: &'static str },
+
+    /// An object isn't an instance of the class it was downcast to with [`JvmOp::downcast`]. The classes are described
+    /// with the Rust types that bind them, if they are [registered](crate::classes).
+    #[error("expected {expected}, got {actual} at {location}")]
+    UnexpectedClass {
+        expected: String,
+        actual: String,
+        location: &'static std::panic::Location<'static>,
+    },
+
+    /// No conversion is registered for the class, see [`conversion`](crate::conversion).
+    #[error("no conversion of `{java}` into `{rust}` is registered")]
+    Unregistered {
+        java: &'static str,
+        rust: &'static str,
+    },
+}
+
 fn try_extract_message(exception: &impl AsJRef<Throwable>) -> String {
     let message = Jvm::with(|jvm| {
         let exception = jvm.local(exception.as_jref()?);
@@ -65,16 +189,143 @@ where
     }
 }
 
+impl<T> Error<T>
+where
+    T: AsJRef<Throwable>,
+{
+    /// The thrown exception, if this is one.
+    pub fn thrown(&self) -> Option<&T> {
+        match self {
+            Error::Thrown(t) | Error::ThrownWithBacktrace(t, _) => Some(t),
+            _ => None,
+        }
+    }
+
+    /// The details of the thrown exception, if they were captured with `with_backtrace`.
+    pub fn backtrace(&self) -> Option<&JavaStackTrace> {
+        match self {
+            Error::ThrownWithBacktrace(_, trace) => Some(trace),
+            _ => None,
+        }
+    }
+
+    /// Converts the thrown exception (if any) with `f`, keeping all other errors as they are.
+    pub(crate) fn map_thrown<U>(self, f: impl FnOnce(T) -> U) -> Error<U>
+    where
+        U: AsJRef<Throwable>,
+    {
+        match self {
+            Error::Thrown(t) => Error::Thrown(f(t)),
+            Error::ThrownWithBacktrace(t, trace) => Error::ThrownWithBacktrace(f(t), trace),
+            Error::SliceTooLong(s) => Error::SliceTooLong(s),
+            Error::NullDeref => Error::NullDeref,
+            Error::JvmAlreadyExists => Error::JvmAlreadyExists,
+            Error::MultipleJvms(count) => Error::MultipleJvms(count),
+            Error::ShuttingDown => Error::ShuttingDown,
+            Error::InCriticalSection => Error::InCriticalSection,
+            Error::InvalidConfig(m) => Error::InvalidConfig(m),
+            Error::ClasspathNotFound(path) => Error::ClasspathNotFound(path),
+            #[cfg(feature = "dylibjvm")]
+            Error::UnableToLoadLibjvm(e) => Error::UnableToLoadLibjvm(e),
+            Error::Conversion(e) => Error::Conversion(e),
+            Error::JvmInternal(m) => Error::JvmInternal(m),
+        }
+    }
+}
+
+impl Error<Global<Throwable>> {
+    /// Like [`Error::with_backtrace`] for a local exception, attaching to the JVM as needed.
+    pub fn with_backtrace(self) -> Self {
+        match self {
+            Error::Thrown(t) => match Jvm::with(|jvm| JavaStackTrace::capture(jvm, &t)) {
+                Ok(trace) => Error::ThrownWithBacktrace(t, Box::new(trace)),
+                Err(_) => Error::Thrown(t),
+            },
+            e => e,
+        }
+    }
+
+    /// Whether this is a thrown exception of class `E` (or one of its subclasses).
+    ///
+    /// Like the other helpers for thrown exceptions, this attaches to the JVM with [`Jvm::with`], so it can't be used
+    /// from within one (and returns `false` there).
+    pub fn is_instance<E>(&self) -> bool
+    where
+        E: Upcast<Throwable>,
+    {
+        let Some(exception) = self.thrown() else {
+            return false;
+        };
+        Jvm::with(|jvm| Ok(exception.try_downcast::<E>().execute_with(jvm)?.is_ok()))
+            .unwrap_or(false)
+    }
+
+    /// Returns the thrown exception as an `E`, or this error unchanged if it isn't a thrown exception of class `E`.
+    pub fn downcast<E>(self) -> result::Result<Global<E>, Self>
+    where
+        E: Upcast<Throwable>,
+    {
+        let Some(exception) = self.thrown() else {
+            return Err(self);
+        };
+        let downcast = Jvm::with(
+            |jvm| match exception.try_downcast::<E>().execute_with(jvm)? {
+                Ok(exception) => Ok(Some(jvm.global(&*exception))),
+                Err(_) => Ok(None),
+            },
+        );
+        match downcast {
+            Ok(Some(exception)) => Ok(exception),
+            _ => Err(self),
+        }
+    }
+
+    /// The fully qualified name of the class of the thrown exception (e.g., `java.io.IOException`), if this is one.
+    pub fn thrown_class_name(&self) -> Option<String> {
+        let exception = self.thrown()?;
+        Jvm::with(|jvm| {
+            let class = exception.get_class().assert_not_null().execute_with(jvm)?;
+            class
+                .get_name()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)
+        })
+        .ok()
+    }
+}
+
 impl<'jvm> Error<Local<'jvm, Throwable>> {
+    /// Captures the message and stack trace (see [`JavaStackTrace`]) of the thrown exception, if this is one, so
+    /// that displaying the error shows them without using the JVM. If they can't be captured, the error is
+    /// returned as is.
+    pub fn with_backtrace(self, jvm: &mut Jvm<'jvm>) -> Self {
+        match self {
+            Error::Thrown(t) => match JavaStackTrace::capture(jvm, &t) {
+                Ok(trace) => Error::ThrownWithBacktrace(t, Box::new(trace)),
+                Err(_) => Error::Thrown(t),
+            },
+            e => e,
+        }
+    }
+
     pub fn into_global(self, jvm: &mut Jvm<'jvm>) -> Error<Global<Throwable>> {
         match self {
             Error::Thrown(t) => Error::Thrown(jvm.global(&t)),
+            Error::ThrownWithBacktrace(t, trace) => {
+                Error::ThrownWithBacktrace(jvm.global(&t), trace)
+            }
             Error::SliceTooLong(s) => Error::SliceTooLong(s),
             Error::NullDeref => Error::NullDeref,
-            Error::NestedUsage => Error::NestedUsage,
             Error::JvmAlreadyExists => Error::JvmAlreadyExists,
+            Error::MultipleJvms(count) => Error::MultipleJvms(count),
+            Error::ShuttingDown => Error::ShuttingDown,
+            Error::InCriticalSection => Error::InCriticalSection,
+            Error::InvalidConfig(m) => Error::InvalidConfig(m),
+            Error::ClasspathNotFound(path) => Error::ClasspathNotFound(path),
             #[cfg(feature = "dylibjvm")]
             Error::UnableToLoadLibjvm(e) => Error::UnableToLoadLibjvm(e),
+            Error::Conversion(e) => Error::Conversion(e),
             Error::JvmInternal(m) => Error::JvmInternal(m),
         }
     }
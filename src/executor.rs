@@ -0,0 +1,176 @@
+use std::{
+    marker::PhantomData,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    java::{
+        self,
+        util::concurrent::{ExecutorService, Executors, TimeUnit},
+    },
+    Global, GlobalResult, Jvm, JvmOp,
+};
+
+/// A task of a [`ScopedExecutor`], which has been taken out of its slot once it has run (or been discarded).
+type Slot = Arc<Mutex<Option<Box<dyn FnOnce() + Send>>>>;
+
+/// Creates a Java fixed thread pool (`Executors.newFixedThreadPool`) with `threads` threads, and runs `f` with a
+/// [`ScopedExecutor`] that submits Rust closures to it.
+///
+/// When `f` returns (or panics), the pool is shut down and this waits for its termination, so it never leaks Java
+/// threads: all the tasks that were submitted run to completion first. The tasks may therefore borrow from outside
+/// the scope, like the threads of [`std::thread::scope`]:
+///
+/// ```
+/// # fn main() -> duchess::GlobalResult<()> {
+/// let words = ["duchess", "java", "rust"];
+/// let lengths = duchess::scoped_executor(2, |executor| {
+///     let tasks = words
+///         .iter()
+///         .map(|word| executor.submit(move || word.len()))
+///         .collect::<Result<Vec<_>, _>>()?;
+///     Ok(tasks.into_iter().map(|task| task.join().unwrap()).collect::<Vec<_>>())
+/// })?;
+/// assert_eq!(lengths, [7, 4, 4]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The tasks run on Java threads, which can use the JVM without being attached.
+///
+/// # Panics
+///
+/// If a task panics and its [`ScopedTask`] was dropped without being joined, this panics once the pool has
+/// terminated. It also panics if `threads` is zero.
+pub fn scoped_executor<'env, R>(
+    threads: usize,
+    f: impl FnOnce(&ScopedExecutor<'env>) -> GlobalResult<R>,
+) -> GlobalResult<R> {
+    assert!(threads > 0, "a scoped executor needs at least one thread");
+    let threads = i32::try_from(threads).unwrap_or(i32::MAX);
+    let service = Executors::new_fixed_thread_pool(threads)
+        .assert_not_null()
+        .global()
+        .execute()?;
+    let executor = ScopedExecutor {
+        service,
+        tasks: Mutex::new(Vec::new()),
+        panicked: Arc::new(AtomicBool::new(false)),
+        env: PhantomData,
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&executor)));
+    let terminated = executor.terminate();
+    let result = match result {
+        Ok(result) => result,
+        Err(panic) => panic::resume_unwind(panic),
+    };
+    terminated?;
+    if executor.panicked.load(Ordering::Acquire) {
+        panic!("a task of a scoped executor panicked");
+    }
+    result
+}
+
+/// Submits closures to the Java thread pool of a [`scoped_executor`]. The closures may borrow anything that outlives
+/// `'env`.
+pub struct ScopedExecutor<'env> {
+    service: Global<ExecutorService>,
+    /// The closures of all the submitted tasks, whose lifetimes have been erased to `'static`
+    tasks: Mutex<Vec<Slot>>,
+    /// Set when a task panics while nobody can join it
+    panicked: Arc<AtomicBool>,
+    /// Invariant, so that `'env` can't be shortened to the body of the scope
+    env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'env> ScopedExecutor<'env> {
+    /// Runs `task` on one of the threads of the pool, returning a handle to join it. Fails if the pool rejects the
+    /// task, in which case `task` is dropped without running.
+    pub fn submit<T, F>(&self, task: F) -> GlobalResult<ScopedTask<T>>
+    where
+        F: FnOnce() -> T + Send + 'env,
+        T: Send + 'env,
+    {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let panicked = self.panicked.clone();
+        let job: Box<dyn FnOnce() + Send + 'env> = Box::new(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(task));
+            if let Err(mpsc::SendError(Err(_))) = sender.send(result) {
+                panicked.store(true, Ordering::Release);
+            }
+        });
+        // SAFETY: the scope takes the closure out of its slot before it returns, at which point `'env` is still
+        // alive. The task holds the lock of the slot while it runs, so that waits for it to finish.
+        let job = unsafe {
+            std::mem::transmute::<Box<dyn FnOnce() + Send + 'env>, Box<dyn FnOnce() + Send>>(job)
+        };
+        let slot: Slot = Arc::new(Mutex::new(Some(job)));
+        self.tasks.lock().unwrap().push(slot.clone());
+
+        let service = &self.service;
+        Jvm::with(|jvm| {
+            let runnable = java::lang::Runnable::from_fn(jvm, move || {
+                let mut slot = slot.lock().unwrap();
+                if let Some(job) = slot.take() {
+                    job();
+                }
+            })?;
+            // Not `service.execute(..)`, which would be `JvmOp::execute`
+            java::util::concurrent::Executor::execute(service, &runnable).execute_with(jvm)
+        })?;
+        Ok(ScopedTask {
+            result: receiver,
+            panicked: self.panicked.clone(),
+        })
+    }
+
+    /// Shuts the pool down and waits for it to terminate. Whatever happens, the closures that are still in their
+    /// slots afterwards (if waiting failed) are dropped without running.
+    fn terminate(&self) -> GlobalResult<()> {
+        let result = Jvm::with(|jvm| {
+            self.service.shutdown().execute_with(jvm)?;
+            let forever = TimeUnit::get_nanoseconds();
+            while !self
+                .service
+                .await_termination(i64::MAX, forever)
+                .execute_with(jvm)?
+            {}
+            Ok(())
+        });
+        for slot in self.tasks.lock().unwrap().drain(..) {
+            drop(slot.lock().unwrap().take());
+        }
+        result
+    }
+}
+
+/// The handle of a task submitted to a [`ScopedExecutor`].
+pub struct ScopedTask<T> {
+    result: Receiver<thread::Result<T>>,
+    panicked: Arc<AtomicBool>,
+}
+
+impl<T> ScopedTask<T> {
+    /// Waits for the task to finish, returning its output, or the payload it panicked with.
+    pub fn join(self) -> thread::Result<T> {
+        self.result
+            .recv()
+            .expect("the task was dropped without running")
+    }
+}
+
+impl<T> Drop for ScopedTask<T> {
+    fn drop(&mut self) {
+        // The task may have panicked before its handle was dropped, in which case its result was sent
+        if let Ok(Err(_)) = self.result.try_recv() {
+            self.panicked.store(true, Ordering::Release);
+        }
+    }
+}
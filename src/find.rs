@@ -1,15 +1,86 @@
-use std::ffi::CStr;
+use std::{
+    ffi::CStr,
+    ptr,
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, Ordering},
+        Mutex, RwLock,
+    },
+};
 
 use crate::{
+    call::call_static_method,
     java,
     jvm::JavaObjectExt,
-    raw::{FieldPtr, MethodPtr},
-    Jvm, Local, Result,
+    raw::{FieldPtr, IntoJniValue, MethodPtr},
+    Global, Jvm, JvmOp, Local, Result,
 };
 
+/// The class loader that classes are loaded with, if one was set with [`crate::hot_reload::set_class_loader`].
+static CLASS_LOADER: RwLock<Option<Global<java::lang::ClassLoader>>> = RwLock::new(None);
+
+/// Finds the class with the JNI name `jni_name` (e.g. `java/lang/String`), with JNI's `FindClass` or, if one was set
+/// with [`crate::hot_reload::set_class_loader`], with `Class.forName` and the class loader.
 pub fn find_class<'jvm>(
     jvm: &mut Jvm<'jvm>,
     jni_name: &CStr,
+) -> Result<'jvm, Local<'jvm, java::lang::Class>> {
+    let loader = class_loader(jvm);
+    match loader {
+        Some(loader) => load_class(jvm, &loader, jni_name),
+        None => find_class_with_jni(jvm, jni_name),
+    }
+}
+
+pub(crate) fn class_loader<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+) -> Option<Local<'jvm, java::lang::ClassLoader>> {
+    let loader = CLASS_LOADER.read().unwrap_or_else(|e| e.into_inner());
+    loader.as_ref().map(|loader| jvm.local(&**loader))
+}
+
+/// Replaces the class loader used by [`find_class`], returning the previous one.
+pub(crate) fn replace_class_loader(
+    loader: Option<Global<java::lang::ClassLoader>>,
+) -> Option<Global<java::lang::ClassLoader>> {
+    let mut current = CLASS_LOADER.write().unwrap_or_else(|e| e.into_inner());
+    std::mem::replace(&mut *current, loader)
+}
+
+/// Loads a class with `Class.forName`, which (unlike `ClassLoader.loadClass`) also takes names of array classes.
+fn load_class<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    loader: &java::lang::ClassLoader,
+    jni_name: &CStr,
+) -> Result<'jvm, Local<'jvm, java::lang::Class>> {
+    // Not looked up with `find_class`, which would end up here again
+    let class_class = find_class_with_jni(jvm, c"java/lang/Class")?;
+    let name = jni_name.to_string_lossy().replace('/', ".");
+    let name = name.as_str().execute_with(jvm)?;
+    // SAFETY: the arguments match the descriptor
+    let class: Option<Local<java::lang::Class>> = unsafe {
+        call_static_method(
+            jvm,
+            &class_class,
+            c"forName",
+            c"(Ljava/lang/String;ZLjava/lang/ClassLoader;)Ljava/lang/Class;",
+            &[
+                (&*name).into_jni_value(),
+                false.into_jni_value(),
+                loader.into_jni_value(),
+            ],
+        )
+    }?;
+    class.ok_or_else(|| {
+        crate::Error::JvmInternal(format!(
+            "failed to load class `{name}`",
+            name = jni_name.to_string_lossy()
+        ))
+    })
+}
+
+fn find_class_with_jni<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    jni_name: &CStr,
 ) -> Result<'jvm, Local<'jvm, java::lang::Class>> {
     let class: Option<Local<java::lang::Class>> = unsafe {
         // SAFETY: jni_name is a valid pointer to a nul-terminated byte string
@@ -119,3 +190,94 @@ pub fn find_constructor<'jvm>(
     const METHOD_NAME: &CStr = unsafe { CStr::from_bytes_with_nul_unchecked(b"<init>\0") };
     find_method(jvm, class, METHOD_NAME, jni_descriptor, false)
 }
+
+/// The class and ID of a member (or constructor), or just a class (with `P = ()`), cached by the code generated for
+/// each of them, so that calls neither look the member up nor create a local reference to its class.
+///
+/// IDs stay valid until their class is unloaded, which the global reference to it prevents, and JVMTI class
+/// redefinition keeps the IDs of the members that still exist, so the cache is only invalidated when classes are
+/// reloaded with a new class loader, see [`crate::hot_reload`].
+pub struct CachedMember<P: 'static> {
+    /// Leaked once set, since the class may still be borrowed after the entry is invalidated
+    entry: AtomicPtr<(Global<java::lang::Class>, P)>,
+    /// Whether this is in [`CACHES`]
+    registered: AtomicBool,
+}
+
+impl<P: Copy + Send + Sync> CachedMember<P> {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        CachedMember {
+            entry: AtomicPtr::new(ptr::null_mut()),
+            registered: AtomicBool::new(false),
+        }
+    }
+
+    /// The cached class and ID, which are looked up with `lookup` the first time (or the first time since the cache
+    /// was invalidated).
+    pub fn get_or_try_init<'jvm>(
+        &'static self,
+        jvm: &mut Jvm<'jvm>,
+        lookup: impl FnOnce(&mut Jvm<'jvm>) -> Result<'jvm, (Local<'jvm, java::lang::Class>, P)>,
+    ) -> Result<'jvm, (&'static java::lang::Class, P)> {
+        // SAFETY: entries are never freed
+        let entry = match unsafe { self.entry.load(Ordering::Acquire).as_ref() } {
+            Some(entry) => entry,
+            None => {
+                let (class, id) = lookup(jvm)?;
+                let new = Box::into_raw(Box::new((jvm.global(&*class), id)));
+                match self.entry.compare_exchange(
+                    ptr::null_mut(),
+                    new,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                ) {
+                    Ok(_) => {
+                        if !self.registered.swap(true, Ordering::AcqRel) {
+                            CACHES.lock().unwrap_or_else(|e| e.into_inner()).push(self);
+                        }
+                        // SAFETY: entries are never freed
+                        unsafe { &*new }
+                    }
+                    Err(current) => {
+                        // Another thread looked it up first, and nobody else has seen `new`
+                        // SAFETY: `new` came from `Box::into_raw` and isn't shared
+                        drop(unsafe { Box::from_raw(new) });
+                        // SAFETY: entries are never freed
+                        unsafe { &*current }
+                    }
+                }
+            }
+        };
+        Ok((&*entry.0, entry.1))
+    }
+}
+
+/// A [`CachedMember`] of any kind.
+pub(crate) trait Cache: Sync {
+    /// The cached class, if there is one.
+    fn class(&self) -> Option<&'static java::lang::Class>;
+
+    /// Forgets the cached class and ID, which will be looked up again on their next use.
+    fn invalidate(&self);
+}
+
+impl<P: Copy + Send + Sync> Cache for CachedMember<P> {
+    fn class(&self) -> Option<&'static java::lang::Class> {
+        // SAFETY: entries are never freed
+        unsafe { self.entry.load(Ordering::Acquire).as_ref() }.map(|entry| &*entry.0)
+    }
+
+    fn invalidate(&self) {
+        // The old entry is leaked, see `entry`
+        self.entry.swap(ptr::null_mut(), Ordering::AcqRel);
+    }
+}
+
+/// The caches that have been filled at some point.
+static CACHES: Mutex<Vec<&'static dyn Cache>> = Mutex::new(Vec::new());
+
+/// The caches that have been filled at some point, which may be invalidated.
+pub(crate) fn caches() -> Vec<&'static dyn Cache> {
+    CACHES.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
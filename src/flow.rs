@@ -0,0 +1,504 @@
+//! Bridging the reactive streams of `java.util.concurrent.Flow` with Rust [`Stream`]s, with backpressure.
+//!
+//! [`subscribe`] consumes a Java `Flow.Publisher` as a Rust stream, and [`publisher`] exposes a Rust stream as a
+//! `Flow.Publisher`. Publishers of other reactive libraries (e.g., Reactor's `Flux`) implement the equivalent
+//! `org.reactivestreams.Publisher`, which can be adapted with `org.reactivestreams.FlowAdapters`.
+//!
+//! The `Flow` interfaces are nested in `java.util.concurrent.Flow`, so publishers are passed around as plain
+//! `Object`s and checked at runtime.
+
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use futures_core::Stream;
+use jni_sys::jvalue;
+use once_cell::sync::OnceCell;
+
+use crate::{
+    cast::Upcast,
+    find::{find_class, find_method},
+    java::{
+        self,
+        lang::{Class, Object, Throwable},
+    },
+    jvm::JavaObjectExt,
+    raw::MethodPtr,
+    Error, Global, GlobalResult, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// Number of items a [`PublisherStream`] requests from its publisher at a time.
+const REQUEST_BATCH: i64 = 32;
+
+/// Subscribes to the Java `Flow.Publisher` `publisher`, whose items are instances of `T`, returning its items as a
+/// Rust [`Stream`]. Returns an error if `publisher` is not a `Flow.Publisher`.
+pub fn subscribe<'jvm, T>(
+    jvm: &mut Jvm<'jvm>,
+    publisher: &impl JavaObject,
+) -> crate::Result<'jvm, PublisherStream<T>>
+where
+    T: Upcast<Object>,
+{
+    let classes = flow_classes(jvm)?;
+    if !is_instance(jvm, publisher, &classes.publisher) {
+        return Err(Error::JvmInternal(
+            "object is not a `java.util.concurrent.Flow.Publisher`".into(),
+        ));
+    }
+
+    let shared = Arc::new(Mutex::new(SubscriberState {
+        subscription: None,
+        items: VecDeque::new(),
+        outstanding: 0,
+        end: None,
+        waker: None,
+    }));
+    let state = shared.clone();
+    let subscriber = jvm.proxy_of_class(&classes.subscriber, move |jvm, call| {
+        let name: String = call
+            .method()
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        match name.as_str() {
+            "onSubscribe" => {
+                let subscription = call.arg(jvm, 0)?.ok_or(Error::NullDeref)?;
+                let mut state = state.lock().unwrap();
+                if state.subscription.is_some() || state.end.is_some() {
+                    // Subscribed twice, or the stream was dropped in the meantime (rule 2.5)
+                    drop(state);
+                    // SAFETY: `subscription` is the argument of `onSubscribe`, so it is a `Flow.Subscription`
+                    unsafe { call_void(jvm, &*subscription, classes.cancel, &[]) }?;
+                } else {
+                    state.subscription = Some(Arc::new(jvm.global(&*subscription)));
+                    state.wake();
+                }
+            }
+            "onNext" => {
+                let item = call.arg_as::<T>(jvm, 0)?.ok_or(Error::NullDeref)?;
+                let item = jvm.global(&*item);
+                let mut state = state.lock().unwrap();
+                state.outstanding = (state.outstanding - 1).max(0);
+                state.items.push_back(item);
+                state.wake();
+            }
+            "onError" => {
+                let exception = call.arg_as::<Throwable>(jvm, 0)?.ok_or(Error::NullDeref)?;
+                let exception = jvm.global(&*exception);
+                let mut state = state.lock().unwrap();
+                state.end.get_or_insert(Err(Error::Thrown(exception)));
+                state.wake();
+            }
+            "onComplete" => {
+                let mut state = state.lock().unwrap();
+                state.end.get_or_insert(Ok(()));
+                state.wake();
+            }
+            _ => {}
+        }
+        Ok(None)
+    })?;
+
+    let args = [jvalue {
+        l: subscriber.as_raw().as_ptr(),
+    }];
+    // SAFETY: just checked that `publisher` is a `Flow.Publisher`, and `subscriber` is a `Flow.Subscriber`
+    unsafe { call_void(jvm, publisher, classes.subscribe, &args) }?;
+    Ok(PublisherStream { shared })
+}
+
+/// A Rust [`Stream`] of the items of a Java `Flow.Publisher`, see [`subscribe`].
+///
+/// Items are requested from the publisher in batches as the stream is polled, so a slow consumer slows down the
+/// publisher rather than buffering its items without bound. If the publisher fails, the exception is the last item of
+/// the stream. Dropping the stream cancels the subscription.
+///
+/// Requesting items attaches to the JVM with [`Jvm::with`], so the stream must not be polled from within one.
+pub struct PublisherStream<T: JavaObject> {
+    shared: Arc<Mutex<SubscriberState<T>>>,
+}
+
+struct SubscriberState<T: JavaObject> {
+    subscription: Option<Arc<Global<Object>>>,
+    items: VecDeque<Global<T>>,
+    /// Items that were requested but not yet received
+    outstanding: i64,
+    /// Set once the publisher completes or fails (or the stream is dropped)
+    end: Option<GlobalResult<()>>,
+    waker: Option<Waker>,
+}
+
+impl<T: JavaObject> SubscriberState<T> {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Stream for PublisherStream<T>
+where
+    T: Upcast<Object>,
+{
+    type Item = GlobalResult<Global<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.shared.lock().unwrap();
+        if let Some(item) = state.items.pop_front() {
+            return Poll::Ready(Some(Ok(item)));
+        }
+        match state.end.replace(Ok(())) {
+            Some(Ok(())) => return Poll::Ready(None),
+            Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+            None => state.end = None,
+        }
+
+        state.waker = Some(cx.waker().clone());
+        if state.outstanding > 0 {
+            return Poll::Pending;
+        }
+        // Until `onSubscribe` is called, which wakes us
+        let Some(subscription) = state.subscription.clone() else {
+            return Poll::Pending;
+        };
+        state.outstanding = REQUEST_BATCH;
+        // The publisher may call `onNext` from within `request`, which takes the lock
+        drop(state);
+
+        let result = Jvm::with(|jvm| {
+            let classes = flow_classes(jvm)?;
+            let args = [jvalue { j: REQUEST_BATCH }];
+            // SAFETY: `subscription` was passed to `onSubscribe`, so it is a `Flow.Subscription`
+            unsafe { call_void(jvm, &**subscription, classes.request, &args) }
+        });
+        if let Err(e) = result {
+            let mut state = self.shared.lock().unwrap();
+            state.end.get_or_insert(Err(e));
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
+impl<T: JavaObject> Drop for PublisherStream<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock().unwrap();
+        if state.end.is_some() {
+            return;
+        }
+        state.end = Some(Ok(()));
+        let Some(subscription) = state.subscription.take() else {
+            return;
+        };
+        drop(state);
+
+        // Errors can't be reported from `drop`; the publisher is meant to stop eventually either way
+        let _ = Jvm::with(|jvm| {
+            let classes = flow_classes(jvm)?;
+            // SAFETY: `subscription` was passed to `onSubscribe`, so it is a `Flow.Subscription`
+            unsafe { call_void(jvm, &**subscription, classes.cancel, &[]) }
+        });
+    }
+}
+
+/// Exposes `stream` as a Java `Flow.Publisher` (returned as an `Object`, see the [module docs](self)).
+///
+/// The stream is polled on the Java thread that requests items, which is blocked until they are ready. As a stream
+/// can only be consumed once, only the first subscriber receives its items; later ones fail with an
+/// `IllegalStateException`.
+pub fn publisher<'jvm, S, T>(
+    jvm: &mut Jvm<'jvm>,
+    stream: S,
+) -> crate::Result<'jvm, Local<'jvm, Object>>
+where
+    S: Stream<Item = Global<T>> + Unpin + Send + 'static,
+    T: Upcast<Object>,
+{
+    let classes = flow_classes(jvm)?;
+    let stream = Mutex::new(Some(stream));
+    jvm.proxy_of_class(&classes.publisher, move |jvm, call| {
+        // `subscribe` is the only method of `Flow.Publisher`
+        let subscriber = call.arg(jvm, 0)?.ok_or(Error::NullDeref)?;
+        let stream = stream.lock().unwrap().take();
+        let first = stream.is_some();
+        let subscription = Arc::new(StreamSubscription {
+            subscriber: jvm.global(&*subscriber),
+            stream: Mutex::new(stream),
+            demand: Mutex::new(Demand {
+                requested: 0,
+                draining: false,
+                cancelled: !first,
+            }),
+        });
+
+        let handle = subscription.clone();
+        let proxy = jvm.proxy_of_class(&classes.subscription, move |jvm, call| {
+            let name: String = call
+                .method()
+                .get_name()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            match name.as_str() {
+                "request" => {
+                    let n = call
+                        .arg_as::<java::lang::Long>(jvm, 0)?
+                        .ok_or(Error::NullDeref)?;
+                    let n = n.long_value().execute_with(jvm)?;
+                    handle.request(jvm, n)?;
+                }
+                "cancel" => handle.cancel(),
+                _ => {}
+            }
+            Ok(None)
+        })?;
+
+        let args = [jvalue {
+            l: proxy.as_raw().as_ptr(),
+        }];
+        // SAFETY: `subscriber` is the argument of `subscribe`, so it is a `Flow.Subscriber`, and `proxy` is a
+        // `Flow.Subscription`
+        unsafe { call_void(jvm, &*subscriber, classes.on_subscribe, &args) }?;
+        if !first {
+            let exception = java::lang::IllegalStateException::new(
+                "publisher of a Rust stream only supports a single subscriber",
+            )
+            .execute_with(jvm)?;
+            subscription.on_error(jvm, &*exception)?;
+        }
+        Ok(None)
+    })
+}
+
+struct StreamSubscription<S> {
+    subscriber: Global<Object>,
+    /// `None` once the stream has been used up or the subscription cancelled
+    stream: Mutex<Option<S>>,
+    demand: Mutex<Demand>,
+}
+
+struct Demand {
+    /// Items that were requested but not yet delivered
+    requested: i64,
+    /// Whether a call to `request` is delivering items
+    draining: bool,
+    cancelled: bool,
+}
+
+impl<S, T> StreamSubscription<S>
+where
+    S: Stream<Item = Global<T>> + Unpin,
+    T: Upcast<Object>,
+{
+    fn request<'jvm>(&self, jvm: &mut Jvm<'jvm>, n: i64) -> crate::Result<'jvm, ()> {
+        {
+            let mut demand = self.demand.lock().unwrap();
+            if demand.cancelled {
+                return Ok(());
+            }
+            if n <= 0 {
+                // Rule 3.9
+                drop(demand);
+                self.cancel();
+                let exception = java::lang::IllegalArgumentException::new(&format!(
+                    "non-positive number of items requested: {n}"
+                ))
+                .execute_with(jvm)?;
+                return self.on_error(jvm, &*exception);
+            }
+            demand.requested = demand.requested.saturating_add(n);
+            if demand.draining {
+                // `request` was called by `onNext` (or concurrently), so the running loop delivers the items
+                // instead, which bounds the recursion (rule 3.3)
+                return Ok(());
+            }
+            demand.draining = true;
+        }
+
+        let result = self.drain(jvm);
+        if result.is_err() {
+            self.cancel();
+        }
+        self.demand.lock().unwrap().draining = false;
+        result
+    }
+
+    /// Delivers items until the demand is met or the stream ends.
+    fn drain<'jvm>(&self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        let classes = flow_classes(jvm)?;
+        loop {
+            {
+                let mut demand = self.demand.lock().unwrap();
+                if demand.cancelled {
+                    drop(demand);
+                    self.stream.lock().unwrap().take();
+                    return Ok(());
+                }
+                if demand.requested == 0 {
+                    return Ok(());
+                }
+                demand.requested -= 1;
+            }
+
+            let item = {
+                let mut stream = self.stream.lock().unwrap();
+                let Some(stream) = stream.as_mut() else {
+                    return Ok(());
+                };
+                block_on(|cx| Pin::new(&mut *stream).poll_next(cx))
+            };
+            match item {
+                Some(item) => {
+                    let args = [jvalue {
+                        l: item.as_raw().as_ptr(),
+                    }];
+                    // SAFETY: `subscriber` was passed to `subscribe`, so it is a `Flow.Subscriber`
+                    unsafe { call_void(jvm, &*self.subscriber, classes.on_next, &args) }?;
+                }
+                None => {
+                    self.cancel();
+                    // SAFETY: `subscriber` was passed to `subscribe`, so it is a `Flow.Subscriber`
+                    return unsafe { call_void(jvm, &*self.subscriber, classes.on_complete, &[]) };
+                }
+            }
+        }
+    }
+}
+
+impl<S> StreamSubscription<S> {
+    fn cancel(&self) {
+        let mut demand = self.demand.lock().unwrap();
+        demand.cancelled = true;
+        let draining = demand.draining;
+        drop(demand);
+        // Otherwise the draining loop drops the stream, as it may be polling it
+        if !draining {
+            self.stream.lock().unwrap().take();
+        }
+    }
+
+    fn on_error<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        exception: &impl Upcast<Throwable>,
+    ) -> crate::Result<'jvm, ()> {
+        let classes = flow_classes(jvm)?;
+        let args = [jvalue {
+            l: exception.as_raw().as_ptr(),
+        }];
+        // SAFETY: `subscriber` was passed to `subscribe`, so it is a `Flow.Subscriber`
+        unsafe { call_void(jvm, &*self.subscriber, classes.on_error, &args) }
+    }
+}
+
+/// Polls `poll` to completion on the current thread, parking it while waiting to be woken.
+fn block_on<R>(mut poll: impl FnMut(&mut Context<'_>) -> Poll<R>) -> R {
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(r) = poll(&mut cx) {
+            return r;
+        }
+        std::thread::park();
+    }
+}
+
+/// The `Flow` interfaces and their methods.
+struct FlowClasses {
+    publisher: Global<Class>,
+    subscriber: Global<Class>,
+    subscription: Global<Class>,
+    subscribe: MethodPtr,
+    request: MethodPtr,
+    cancel: MethodPtr,
+    on_subscribe: MethodPtr,
+    on_next: MethodPtr,
+    on_error: MethodPtr,
+    on_complete: MethodPtr,
+}
+
+fn flow_classes<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, &'static FlowClasses> {
+    static CLASSES: OnceCell<FlowClasses> = OnceCell::new();
+    CLASSES.get_or_try_init(|| {
+        let publisher = find_class(jvm, c"java/util/concurrent/Flow$Publisher")?;
+        let subscriber = find_class(jvm, c"java/util/concurrent/Flow$Subscriber")?;
+        let subscription = find_class(jvm, c"java/util/concurrent/Flow$Subscription")?;
+        Ok(FlowClasses {
+            subscribe: find_method(
+                jvm,
+                &publisher,
+                c"subscribe",
+                c"(Ljava/util/concurrent/Flow$Subscriber;)V",
+                false,
+            )?,
+            request: find_method(jvm, &subscription, c"request", c"(J)V", false)?,
+            cancel: find_method(jvm, &subscription, c"cancel", c"()V", false)?,
+            on_subscribe: find_method(
+                jvm,
+                &subscriber,
+                c"onSubscribe",
+                c"(Ljava/util/concurrent/Flow$Subscription;)V",
+                false,
+            )?,
+            on_next: find_method(jvm, &subscriber, c"onNext", c"(Ljava/lang/Object;)V", false)?,
+            on_error: find_method(
+                jvm,
+                &subscriber,
+                c"onError",
+                c"(Ljava/lang/Throwable;)V",
+                false,
+            )?,
+            on_complete: find_method(jvm, &subscriber, c"onComplete", c"()V", false)?,
+            publisher: jvm.global(&*publisher),
+            subscriber: jvm.global(&*subscriber),
+            subscription: jvm.global(&*subscription),
+        })
+    })
+}
+
+fn is_instance(jvm: &mut Jvm<'_>, object: &impl JavaObject, class: &Class) -> bool {
+    // SAFETY: both are live references
+    unsafe {
+        jvm.env().invoke_unchecked(
+            |env| env.IsInstanceOf,
+            |env, f| f(env, object.as_raw().as_ptr(), class.as_raw().as_ptr()),
+        ) == jni_sys::JNI_TRUE
+    }
+}
+
+/// Calls the `void` method `method` of `object` with `args`.
+///
+/// # Safety
+///
+/// `object` must be an instance of the class declaring `method`, and `args` must match its parameters.
+unsafe fn call_void<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    object: &impl JavaObject,
+    method: MethodPtr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, ()> {
+    jvm.env().invoke(
+        |env| env.CallVoidMethodA,
+        |env, f| {
+            f(
+                env,
+                object.as_raw().as_ptr(),
+                method.as_ptr(),
+                args.as_ptr(),
+            )
+        },
+    )
+}
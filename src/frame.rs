@@ -0,0 +1,224 @@
+use crate::{
+    raw::{EnvPtr, ObjectPtr},
+    scope, Error, Global, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// Number of local refs the JVM is asked to reserve for frames created by duchess itself, unless configured otherwise
+/// (see [`Config::local_frame_capacity`](crate::Config::local_frame_capacity)). This is only a hint: locals are mostly
+/// deleted as they are dropped, so there are rarely many live at once.
+pub(crate) const DEFAULT_FRAME_CAPACITY: i32 = 32;
+
+impl<'jvm> Jvm<'jvm> {
+    /// Runs `op` in a new local reference frame with room for at least `capacity` local references (see
+    /// `PushLocalFrame`). Every local reference created inside the frame is released when `op` returns, including
+    /// ones that were borrowed or leaked, so this is useful to bound the number of live references in long loops.
+    ///
+    /// Local references can't be returned from the frame, but an exception thrown by `op` is moved to the enclosing
+    /// frame. To run a single operation in its own frame and keep its result, see [`JvmOp::in_frame`].
+    pub fn with_frame<R>(
+        &mut self,
+        capacity: i32,
+        op: impl for<'frame> FnOnce(&mut Jvm<'frame>) -> crate::Result<'frame, R>,
+    ) -> crate::Result<'jvm, R> {
+        self.run_in_frame(
+            capacity,
+            false,
+            |jvm| op(jvm),
+            |r, frame| {
+                drop(frame);
+                r
+            },
+        )
+    }
+
+    /// Runs the chained operations of [`JvmOp::execute`] in a local frame, in a fused delete scope (see
+    /// [`scope::enter_fused`]): the intermediate results are released all at once when the frame is popped.
+    ///
+    /// # Safety
+    ///
+    /// `R` must not hold local refs, and `op` must not drop local refs created in enclosing frames.
+    pub(crate) unsafe fn with_fused_frame<R>(
+        &mut self,
+        op: impl FnOnce(&mut Jvm<'jvm>) -> crate::Result<'jvm, R>,
+    ) -> crate::Result<'jvm, R> {
+        self.run_in_frame(
+            crate::config::local_frame_capacity(),
+            true,
+            op,
+            |r, frame| {
+                drop(frame);
+                r
+            },
+        )
+    }
+
+    /// Runs `op` in a new local frame, which is popped by `pop` (or when unwinding). If `fused`, the locals dropped in
+    /// it are left for the frame to release (see [`scope::enter_fused`]).
+    fn run_in_frame<R>(
+        &mut self,
+        capacity: i32,
+        fused: bool,
+        op: impl FnOnce(&mut Jvm<'jvm>) -> crate::Result<'jvm, R>,
+        pop: impl FnOnce(R, LocalFrame<'jvm>) -> R,
+    ) -> crate::Result<'jvm, R> {
+        let env = self.env();
+        // SAFETY: env is valid for `'jvm`
+        let code =
+            unsafe { env.invoke_unchecked(|jni| jni.PushLocalFrame, |jni, f| f(jni, capacity)) };
+        if code != jni_sys::JNI_OK {
+            // A failed push throws an `OutOfMemoryError`
+            env.check_exception()?;
+            return Err(Error::JvmInternal(format!(
+                "PushLocalFrame failed with code `{code}`"
+            )));
+        }
+        #[cfg(feature = "count-crossings")]
+        crate::crossings::local_frame();
+        let frame = LocalFrame { env };
+
+        let result = {
+            // SAFETY: the scope is dropped right before the frame is popped. Fused scopes are only entered by
+            // `with_fused_frame`, whose caller guarantees that `op` only drops locals of this frame.
+            let delete_scope = unsafe {
+                if fused {
+                    scope::enter_fused(env)
+                } else {
+                    scope::enter(env)
+                }
+            };
+            // Refs borrowed in the frame are kept until it is popped
+            op(&mut Jvm(env, delete_scope.depth()))
+        };
+
+        match result {
+            Ok(r) => Ok(pop(r, frame)),
+            // Dropping `frame` pops it, unless it is popped along with a thrown exception
+            Err(e) => Err(e.map_thrown(|thrown| frame.pop_with(thrown))),
+        }
+    }
+}
+
+/// A local reference frame pushed by duchess, which is popped when dropped.
+#[doc(hidden)]
+pub struct LocalFrame<'jvm> {
+    env: EnvPtr<'jvm>,
+}
+
+impl<'jvm> LocalFrame<'jvm> {
+    /// Pops the frame, returning a reference to `local` in the enclosing frame.
+    pub fn pop_with<T: JavaObject>(self, local: Local<'_, T>) -> Local<'jvm, T> {
+        let env = self.env;
+        std::mem::forget(self);
+        // SAFETY: `PopLocalFrame` returns a new local ref to the same object in the enclosing frame. The old ref is
+        // released along with the frame (or, if it came from an enclosing frame, when that frame is popped).
+        unsafe {
+            let obj = local.into_raw();
+            let new_ref =
+                env.invoke_unchecked(|jni| jni.PopLocalFrame, |jni, f| f(jni, obj.as_ptr()));
+            Local::from_raw(env, ObjectPtr::new(new_ref).unwrap())
+        }
+    }
+}
+
+impl Drop for LocalFrame<'_> {
+    fn drop(&mut self) {
+        // SAFETY: the frame was pushed by `run_in_frame` and none of its locals are used after this
+        unsafe {
+            self.env.invoke_unchecked(
+                |jni| jni.PopLocalFrame,
+                |jni, f| f(jni, std::ptr::null_mut()),
+            );
+        }
+    }
+}
+
+/// Output of an operation that can be run in its own local frame with [`JvmOp::in_frame`]: either a Rust value or a
+/// single local reference, which is moved to the enclosing frame.
+pub trait FrameOutput<'jvm> {
+    #[doc(hidden)]
+    fn pop_frame(self, frame: LocalFrame<'jvm>) -> Self;
+}
+
+macro_rules! rust_frame_output {
+    ($($t:ty,)*) => {
+        $(
+            impl<'jvm> FrameOutput<'jvm> for $t {
+                fn pop_frame(self, frame: LocalFrame<'jvm>) -> Self {
+                    drop(frame);
+                    self
+                }
+            }
+        )*
+    }
+}
+
+rust_frame_output! {
+    (),
+    bool,
+    u16, // java char
+    i8,
+    i16,
+    i32,
+    i64,
+    f32,
+    f64,
+    String,
+}
+
+impl<'jvm, T: JavaObject> FrameOutput<'jvm> for Global<T> {
+    fn pop_frame(self, frame: LocalFrame<'jvm>) -> Self {
+        drop(frame);
+        self
+    }
+}
+
+impl<'jvm, T: JavaObject> FrameOutput<'jvm> for Local<'jvm, T> {
+    fn pop_frame(self, frame: LocalFrame<'jvm>) -> Self {
+        frame.pop_with(self)
+    }
+}
+
+impl<'jvm, T: FrameOutput<'jvm>> FrameOutput<'jvm> for Option<T> {
+    fn pop_frame(self, frame: LocalFrame<'jvm>) -> Self {
+        match self {
+            Some(t) => Some(t.pop_frame(frame)),
+            None => {
+                drop(frame);
+                None
+            }
+        }
+    }
+}
+
+/// See [`JvmOp::in_frame`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct InFrame<This: JvmOp> {
+    this: This,
+}
+
+impl<This> InFrame<This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: FrameOutput<'jvm>,
+{
+    pub(crate) fn new(this: This) -> Self {
+        InFrame { this }
+    }
+}
+
+impl<This> JvmOp for InFrame<This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: FrameOutput<'jvm>,
+{
+    type Output<'jvm> = This::Output<'jvm>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        jvm.run_in_frame(
+            crate::config::local_frame_capacity(),
+            false,
+            |jvm| self.this.execute_with(jvm),
+            FrameOutput::pop_frame,
+        )
+    }
+}
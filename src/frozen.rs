@@ -0,0 +1,124 @@
+use std::{
+    fmt::{self, Debug, Display},
+    ops::Deref,
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::{
+    java::lang::Object, jvm::JavaObjectExt, prelude::*, Global, GlobalResult, IntoRust, JavaObject,
+    Jvm,
+};
+
+/// A [`Global`] to a Java object that isn't mutated anymore, whose `hashCode`, `toString` and conversion to a Rust `V`
+/// are computed once and then read from any thread without calling into the JVM:
+///
+/// ```
+/// # fn main() -> duchess::GlobalResult<()> {
+/// use duchess::{prelude::*, Frozen};
+/// use std::sync::Arc;
+///
+/// let string = "duchess".to_java().assert_not_null().global().execute()?;
+/// // Java strings are immutable
+/// let frozen: Arc<Frozen<_, String>> = Arc::new(Frozen::assume_immutable(string));
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| assert_eq!(frozen.value().unwrap(), "duchess"));
+///     }
+/// });
+/// assert_eq!(frozen.string()?, "duchess");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Each value is computed by the first thread that reads it (or several, if they race, keeping one of the results).
+/// Failures aren't cached, so reading a value that couldn't be computed tries again. `V` is typically a Rust struct
+/// with the fields extracted from the object, e.g. one converted with `#[derive(ToRust)]`; it defaults to `()`, for
+/// when only the hash code or string are needed.
+pub struct Frozen<T: JavaObject, V = ()> {
+    object: Global<T>,
+    hash_code: OnceCell<i32>,
+    string: OnceCell<String>,
+    value: OnceCell<V>,
+}
+
+impl<T: JavaObject, V> Frozen<T, V> {
+    /// Wraps `object`, without computing anything yet, assuming that it isn't mutated anymore, at least in ways that
+    /// change its `hashCode`, `toString` or conversion to `V`. Otherwise, the cached values go stale.
+    pub fn assume_immutable(object: Global<T>) -> Self {
+        Self {
+            object,
+            hash_code: OnceCell::new(),
+            string: OnceCell::new(),
+            value: OnceCell::new(),
+        }
+    }
+
+    pub fn into_inner(self) -> Global<T> {
+        self.object
+    }
+
+    /// The `hashCode()` of the object.
+    pub fn hash_code(&self) -> GlobalResult<i32> {
+        self.hash_code
+            .get_or_try_init(|| Jvm::with(|jvm| self.as_object().hash_code().execute_with(jvm)))
+            .copied()
+    }
+
+    /// The `toString()` of the object, failing with [`Error::NullDeref`](crate::Error::NullDeref) if it returns null.
+    pub fn string(&self) -> GlobalResult<&str> {
+        self.string
+            .get_or_try_init(|| {
+                Jvm::with(|jvm| {
+                    self.as_object()
+                        .to_string()
+                        .assert_not_null()
+                        .to_rust()
+                        .execute_with(jvm)
+                })
+            })
+            .map(String::as_str)
+    }
+
+    /// The object converted to a `V` with [`to_rust`](crate::prelude::JvmOp::to_rust).
+    pub fn value(&self) -> GlobalResult<&V>
+    where
+        for<'a> &'a T: IntoRust<V>,
+    {
+        self.value
+            .get_or_try_init(|| Jvm::with(|jvm| (&*self.object).into_rust(jvm)))
+    }
+
+    fn as_object(&self) -> &Object {
+        // SAFETY: every Java object is an `Object`
+        unsafe { Object::from_raw(self.object.as_raw()) }
+    }
+}
+
+impl<T: JavaObject, V> Deref for Frozen<T, V> {
+    type Target = Global<T>;
+
+    fn deref(&self) -> &Global<T> {
+        &self.object
+    }
+}
+
+/// Formats the cached `toString()`, or a placeholder if it can't be called.
+impl<T: JavaObject, V> Display for Frozen<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.string() {
+            Ok(string) => f.write_str(string),
+            Err(_) => f.write_str("<unable to call toString>"),
+        }
+    }
+}
+
+impl<T: JavaObject, V: Debug> Debug for Frozen<T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Frozen")
+            .field("object", &self.object)
+            .field("hash_code", &self.hash_code.get())
+            .field("value", &self.value.get())
+            .finish()
+    }
+}
@@ -0,0 +1,217 @@
+//! Bridging `java.util.concurrent.CompletableFuture` with Rust [`Future`]s.
+//!
+//! [`when_complete`] awaits a `CompletableFuture` from Rust, and [`completable`] hands a Rust future to Java as a
+//! `CompletableFuture`. Neither needs an async runtime: Java completes its futures on its own threads, and Rust futures
+//! are polled on the default executor of `CompletableFuture` (the common `ForkJoinPool`) whenever they are woken.
+
+use std::{
+    future::Future,
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::{
+    cast::Upcast,
+    java::{
+        self,
+        lang::{Object, Runnable, Throwable},
+        util::concurrent::CompletableFuture,
+    },
+    AsJRef, Error, Global, GlobalResult, Jvm, JvmOp, Local,
+};
+
+/// Awaits the completion of `future`, returning its value (which may be null) as the output of a Rust [`Future`].
+///
+/// If `future` fails, the output is the exception, unwrapped from the `CompletionException` that Java wraps the
+/// failures of dependent stages in (and a `CancellationException` if it was cancelled).
+pub fn when_complete<'jvm, T>(
+    jvm: &mut Jvm<'jvm>,
+    future: &CompletableFuture<T>,
+) -> crate::Result<'jvm, JavaFuture<T>>
+where
+    T: Upcast<Object> + AsJRef<T>,
+{
+    let shared = Arc::new(Mutex::new(Completion {
+        result: None,
+        waker: None,
+    }));
+    let state = shared.clone();
+    let action =
+        jvm.proxy::<java::util::function::BiConsumer<T, Throwable>>(move |jvm, call| {
+            let result = match call.arg_as::<Throwable>(jvm, 1)? {
+                Some(exception) => {
+                    let exception = unwrap_completion_exception(jvm, exception)?;
+                    Err(Error::Thrown(jvm.global(&*exception)))
+                }
+                None => Ok(call.arg_as::<T>(jvm, 0)?.map(|value| jvm.global(&*value))),
+            };
+            let mut state = state.lock().unwrap();
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+            Ok(None)
+        })?;
+    // Runs `action` right away if `future` is complete already
+    future.when_complete(&action).execute_with(jvm)?;
+    Ok(JavaFuture { shared })
+}
+
+/// A Rust [`Future`] of the completion of a Java `CompletableFuture`, see [`when_complete`].
+///
+/// Dropping it doesn't cancel the Java future.
+pub struct JavaFuture<T: Upcast<Object>> {
+    shared: Arc<Mutex<Completion<T>>>,
+}
+
+struct Completion<T: Upcast<Object>> {
+    /// Set once the Java future completes, and taken when the Rust future returns it
+    result: Option<GlobalResult<Option<Global<T>>>>,
+    waker: Option<Waker>,
+}
+
+impl<T: Upcast<Object>> Future for JavaFuture<T> {
+    type Output = GlobalResult<Option<Global<T>>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+fn unwrap_completion_exception<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    exception: Local<'jvm, Throwable>,
+) -> crate::Result<'jvm, Local<'jvm, Throwable>> {
+    let is_completion = (&*exception)
+        .try_downcast::<java::util::concurrent::CompletionException>()
+        .execute_with(jvm)?
+        .is_ok();
+    if !is_completion {
+        return Ok(exception);
+    }
+    Ok(exception
+        .get_cause()
+        .execute_with(jvm)?
+        .unwrap_or(exception))
+}
+
+/// Hands `future` to Java as a `CompletableFuture`, which completes with its output.
+///
+/// The future is first polled on the default executor of `CompletableFuture`, and again there whenever it is woken.
+/// Errors other than Java exceptions (including panics while polling) fail the `CompletableFuture` with a
+/// `RuntimeException`. If Java completes or cancels the `CompletableFuture` first, the Rust future is dropped the next
+/// time it is woken.
+pub fn completable<'jvm, F, T>(
+    jvm: &mut Jvm<'jvm>,
+    future: F,
+) -> crate::Result<'jvm, Local<'jvm, CompletableFuture<T>>>
+where
+    F: Future<Output = GlobalResult<Option<Global<T>>>> + Send + 'static,
+    T: Upcast<Object> + Upcast<T>,
+{
+    let completable = CompletableFuture::<T>::new().execute_with(jvm)?;
+    let task = Arc::new(Task {
+        future: Mutex::new(Some(Box::pin(future))),
+        completable: jvm.global(&*completable),
+        poll: Mutex::new(None),
+    });
+    let poll = {
+        let task = task.clone();
+        jvm.proxy::<Runnable>(move |jvm, _call| {
+            task.poll(jvm)?;
+            Ok(None)
+        })?
+    };
+    // The task and the `Runnable` refer to each other until the future is done
+    *task.poll.lock().unwrap() = Some(jvm.global(&*poll));
+    task.submit(jvm)?;
+    Ok(completable)
+}
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = GlobalResult<Option<Global<T>>>> + Send>>;
+
+/// A Rust future being polled for a `CompletableFuture`, see [`completable`].
+struct Task<T: Upcast<Object> + Upcast<T>> {
+    /// `None` once the future is done
+    future: Mutex<Option<BoxFuture<T>>>,
+    completable: Global<CompletableFuture<T>>,
+    /// The `Runnable` that polls the future, `None` once the future is done
+    poll: Mutex<Option<Global<Runnable>>>,
+}
+
+impl<T: Upcast<Object> + Upcast<T>> Task<T> {
+    /// Has the executor poll the future.
+    fn submit<'jvm>(&self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        let poll = self.poll.lock().unwrap();
+        let Some(poll) = &*poll else {
+            return Ok(());
+        };
+        let executor = self
+            .completable
+            .default_executor()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        // Not `executor.execute(..)`, which would be `JvmOp::execute`
+        java::util::concurrent::Executor::execute(&executor, poll).execute_with(jvm)
+    }
+
+    fn poll<'jvm>(self: &Arc<Self>, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        let mut future = self.future.lock().unwrap();
+        let Some(pending) = future.as_mut() else {
+            return Ok(());
+        };
+        if self.completable.is_done().execute_with(jvm)? {
+            *future = None;
+            self.poll.lock().unwrap().take();
+            return Ok(());
+        }
+
+        let waker = Waker::from(self.clone());
+        let mut cx = Context::from_waker(&waker);
+        let result =
+            match std::panic::catch_unwind(AssertUnwindSafe(|| pending.as_mut().poll(&mut cx))) {
+                Ok(Poll::Pending) => return Ok(()),
+                Ok(Poll::Ready(result)) => result,
+                Err(_) => Err(Error::JvmInternal("Rust future panicked".into())),
+            };
+        *future = None;
+        drop(future);
+        self.poll.lock().unwrap().take();
+
+        match result {
+            Ok(value) => {
+                self.completable.complete(&value).execute_with(jvm)?;
+            }
+            Err(Error::Thrown(exception) | Error::ThrownWithBacktrace(exception, _)) => {
+                self.completable
+                    .complete_exceptionally(&exception)
+                    .execute_with(jvm)?;
+            }
+            Err(error) => {
+                let exception =
+                    java::lang::RuntimeException::new(&error.to_string()).execute_with(jvm)?;
+                self.completable
+                    .complete_exceptionally(&exception)
+                    .execute_with(jvm)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Upcast<Object> + Upcast<T>> Wake for Task<T> {
+    fn wake(self: Arc<Self>) {
+        if let Err(error) = Jvm::with(|jvm| self.submit(jvm)) {
+            tracing::warn!(%error, "failed to poll Rust future of a `CompletableFuture`");
+        }
+    }
+}
@@ -23,6 +23,8 @@ where
 {
     type Output<'jvm> = GlobalVersionOf<'jvm, J::Output<'jvm>>;
 
+    const LOCALS: usize = J::LOCALS;
+
     fn execute_with<'jvm>(
         self,
         jvm: &mut crate::Jvm<'jvm>,
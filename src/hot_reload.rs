@@ -0,0 +1,74 @@
+//! Reloading Java classes during development: hot reload setups (e.g. Spring devtools-like restarts) load the new
+//! version of the application's classes with a new class loader, and drop the old one.
+//!
+//! Duchess caches the classes it looks up, and the IDs of their members, for the lifetime of the process. To use the
+//! new classes, set the new class loader with [`swap_class_loader`], which makes duchess load classes with it and
+//! forget the classes (and member IDs) of the old one, so they are looked up again on their next use:
+//!
+//! ```ignore
+//! let loader = restart_application()?; // a `Global<java::lang::ClassLoader>`
+//! // SAFETY: the objects of the previous version of the application are no longer used
+//! unsafe { duchess::hot_reload::swap_class_loader(Some(loader)) }?;
+//! ```
+//!
+//! Only the caches of [`java_package!`](crate::java_package) bindings are invalidated; those of the JDK classes that
+//! duchess uses internally never are, since the JDK can't be reloaded.
+
+use crate::{
+    find::{caches, replace_class_loader},
+    java::lang::ClassLoader,
+    jvm::is_same_object,
+    prelude::*,
+    Global, GlobalResult, Jvm,
+};
+
+/// Makes duchess load the classes it looks up from now on with `loader` (and its parents), or with JNI's `FindClass`
+/// for `None`, which is the default. Classes that were looked up already aren't affected, see [`swap_class_loader`].
+///
+/// `FindClass` uses the class loader of the class of the native method that is running, if any, and otherwise the
+/// system class loader.
+pub fn set_class_loader(loader: Option<Global<ClassLoader>>) {
+    replace_class_loader(loader);
+}
+
+/// Like [`set_class_loader`], but also forgets the classes defined by the previous class loader (set with
+/// [`set_class_loader`] or this), as [`invalidate_class_loader`] does.
+///
+/// # Safety
+///
+/// See [`invalidate_class_loader`].
+pub unsafe fn swap_class_loader(loader: Option<Global<ClassLoader>>) -> GlobalResult<usize> {
+    match replace_class_loader(loader) {
+        Some(previous) => unsafe { invalidate_class_loader(&previous) },
+        None => Ok(0),
+    }
+}
+
+/// Forgets the cached classes that were defined by `loader`, along with the cached IDs of their members, so that
+/// they are looked up again (e.g. with the class loader set with [`set_class_loader`]) on their next use. Returns how
+/// many caches were invalidated.
+///
+/// The references to the classes themselves are leaked rather than deleted, so the class loader isn't garbage
+/// collected. Hot reloading a few times during development is fine, but not doing it in a loop.
+///
+/// # Safety
+///
+/// Objects of the classes defined by `loader` must not be used through duchess afterwards, since the IDs of their
+/// members would then be looked up in the new classes, which they aren't instances of.
+pub unsafe fn invalidate_class_loader(loader: &ClassLoader) -> GlobalResult<usize> {
+    Jvm::with(|jvm| {
+        let mut invalidated = 0;
+        // `caches` returns a snapshot, since looking up the class loaders may fill other caches
+        for cache in caches() {
+            let Some(class) = cache.class() else {
+                continue;
+            };
+            let class_loader = class.get_class_loader().execute_with(jvm)?;
+            if class_loader.is_some_and(|l| is_same_object(jvm, &*l, loader)) {
+                cache.invalidate();
+                invalidated += 1;
+            }
+        }
+        Ok(invalidated)
+    })
+}
@@ -1,12 +1,31 @@
-use std::marker::PhantomData;
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    hash::{BuildHasher, Hash},
+    marker::PhantomData,
+};
 
-use crate::{Global, JavaObject, Jvm, JvmOp, Local};
+use crate::{
+    call,
+    cast::Upcast,
+    error::ConversionError,
+    find::{find_class, find_method, CachedMember},
+    java,
+    jvm::JavaObjectExt,
+    raw::MethodPtr,
+    AsJRef, Error, Global, JavaObject, Jvm, JvmOp, Local,
+};
 
 /// Types that are able to be converted back into a Rust `T`, either because they will produce a Rust primitive `T` or
 /// or because we can convert into them via a JNI call.
 ///
 /// This is intended to be used to explicitly bring a value back to Rust at the end of a JVM session or operation.
 pub trait IntoRust<R> {
+    /// An upper bound on the number of local references that the conversion creates, see [`JvmOp::LOCALS`].
+    #[doc(hidden)]
+    const LOCALS: usize = usize::MAX;
+
     fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, R>;
 }
 
@@ -14,6 +33,8 @@ macro_rules! identity_rust_op {
     ($($t:ty,)*) => {
         $(
             impl IntoRust<$t> for $t {
+                const LOCALS: usize = 0;
+
                 fn into_rust<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $t> {
                     Ok(self)
                 }
@@ -37,6 +58,8 @@ where
     JO: IntoRust<O>,
     JE: IntoRust<E>,
 {
+    const LOCALS: usize = crate::ops::sum_locals(&[JO::LOCALS, JE::LOCALS]);
+
     fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Result<O, E>> {
         match self {
             Ok(jo) => Ok(Ok(jo.into_rust(jvm)?)),
@@ -49,6 +72,8 @@ impl<O, JO> IntoRust<Option<O>> for Option<JO>
 where
     JO: IntoRust<O>,
 {
+    const LOCALS: usize = JO::LOCALS;
+
     fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Option<O>> {
         match self {
             Some(jo) => Ok(Some(jo.into_rust(jvm)?)),
@@ -62,6 +87,8 @@ where
     J: JavaObject,
     for<'a> &'a J: IntoRust<R>,
 {
+    const LOCALS: usize = <&J as IntoRust<R>>::LOCALS;
+
     fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, R> {
         <&J as IntoRust<R>>::into_rust(&self, jvm)
     }
@@ -72,11 +99,165 @@ where
     J: JavaObject,
     for<'a> &'a J: IntoRust<R>,
 {
+    const LOCALS: usize = <&J as IntoRust<R>>::LOCALS;
+
     fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, R> {
         <&J as IntoRust<R>>::into_rust(&self, jvm)
     }
 }
 
+/// Converts each (non-null) element of the list. The list is walked with its iterator, so this is efficient for
+/// linked as well as array-backed lists.
+impl<R, J> IntoRust<Vec<R>> for &java::util::List<J>
+where
+    J: JavaObject,
+    for<'a> &'a J: IntoRust<R>,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<R>> {
+        let len = self.size().execute_with(jvm)?;
+        let mut vec = Vec::with_capacity(len.max(0) as usize);
+        let iter = self.iterator().assert_not_null().execute_with(jvm)?;
+        while iter.has_next().execute_with(jvm)? {
+            vec.push(convert_element(jvm, |jvm| {
+                let element = iter.next().execute_with(jvm)?.ok_or(Error::NullDeref)?;
+                <&J as IntoRust<R>>::into_rust(&element, jvm)
+            })?);
+        }
+        Ok(vec)
+    }
+}
+
+impl<R, J> IntoRust<Vec<R>> for &java::util::ArrayList<J>
+where
+    J: JavaObject,
+    for<'a> &'a J: IntoRust<R>,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<R>> {
+        let list: &java::util::List<J> = self.as_jref()?;
+        list.into_rust(jvm)
+    }
+}
+
+/// Converts each entry of the map. A null key or value fails the conversion with [`Error::NullDeref`], so maps with
+/// null values can't be converted.
+impl<K, V, JK, JV, S> IntoRust<HashMap<K, V, S>> for &java::util::Map<JK, JV>
+where
+    JK: Upcast<java::lang::Object>,
+    JV: JavaObject,
+    for<'a> &'a JK: IntoRust<K>,
+    for<'a> &'a JV: IntoRust<V>,
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, HashMap<K, V, S>> {
+        let len = self.size().execute_with(jvm)?;
+        let mut map = HashMap::with_capacity_and_hasher(len.max(0) as usize, S::default());
+        map_entries_into_rust(self, jvm, |k, v| {
+            map.insert(k, v);
+        })?;
+        Ok(map)
+    }
+}
+
+/// Converts each entry of the map. A null key or value fails the conversion with [`Error::NullDeref`], so maps with
+/// null values can't be converted.
+impl<K, V, JK, JV> IntoRust<BTreeMap<K, V>> for &java::util::Map<JK, JV>
+where
+    JK: Upcast<java::lang::Object>,
+    JV: JavaObject,
+    for<'a> &'a JK: IntoRust<K>,
+    for<'a> &'a JV: IntoRust<V>,
+    K: Ord,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, BTreeMap<K, V>> {
+        let mut map = BTreeMap::new();
+        map_entries_into_rust(self, jvm, |k, v| {
+            map.insert(k, v);
+        })?;
+        Ok(map)
+    }
+}
+
+// Not generic over the Rust type, which would overlap with the conversion into `Converted`
+macro_rules! hash_map_into_rust {
+    ($($map:ty => [$($generics:tt)*],)*) => {
+        $(
+            impl<$($generics)*> IntoRust<$map> for &java::util::HashMap<JK, JV>
+            where
+                JK: Upcast<java::lang::Object>,
+                JV: JavaObject,
+                for<'a> &'a java::util::Map<JK, JV>: IntoRust<$map>,
+            {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $map> {
+                    let map: &java::util::Map<JK, JV> = self.as_jref()?;
+                    map.into_rust(jvm)
+                }
+            }
+        )*
+    };
+}
+
+hash_map_into_rust! {
+    HashMap<K, V, S> => [K, V, S, JK, JV],
+    BTreeMap<K, V> => [K, V, JK, JV],
+}
+
+/// Walks the entries of `map`, converting the key and value of each. Null keys and values fail the conversion with
+/// [`Error::NullDeref`].
+fn map_entries_into_rust<'jvm, K, V, JK, JV>(
+    map: &java::util::Map<JK, JV>,
+    jvm: &mut Jvm<'jvm>,
+    mut insert: impl FnMut(K, V),
+) -> crate::Result<'jvm, ()>
+where
+    JK: Upcast<java::lang::Object>,
+    JV: JavaObject,
+    for<'a> &'a JK: IntoRust<K>,
+    for<'a> &'a JV: IntoRust<V>,
+{
+    // `entrySet` and `Map$Entry` are looked up by hand since the bindings don't support nested classes
+    static ENTRY_SET: CachedMember<MethodPtr> = CachedMember::new();
+    static ENTRY: CachedMember<(MethodPtr, MethodPtr)> = CachedMember::new();
+
+    let (_, entry_set) = ENTRY_SET.get_or_try_init(jvm, |jvm| {
+        let class = <java::util::Map<JK, JV> as JavaObject>::class(jvm)?;
+        let entry_set = find_method(jvm, &class, c"entrySet", c"()Ljava/util/Set;", false)?;
+        Ok((class, entry_set))
+    })?;
+    let (_, (get_key, get_value)) = ENTRY.get_or_try_init(jvm, |jvm| {
+        let class = find_class(jvm, c"java/util/Map$Entry")?;
+        let get_key = find_method(jvm, &class, c"getKey", c"()Ljava/lang/Object;", false)?;
+        let get_value = find_method(jvm, &class, c"getValue", c"()Ljava/lang/Object;", false)?;
+        Ok((class, (get_key, get_value)))
+    })?;
+
+    // SAFETY: `entrySet` is a method of `Map` returning a `Set` (of `Map$Entry`s)
+    let entries: Option<Local<java::util::Set<java::lang::Object>>> =
+        unsafe { call::object_method(jvm, map.as_raw(), entry_set, &[]) }?;
+    let iter = entries
+        .ok_or(Error::NullDeref)?
+        .iterator()
+        .assert_not_null()
+        .execute_with(jvm)?;
+    while iter.has_next().execute_with(jvm)? {
+        let (key, value) = convert_element(jvm, |jvm| {
+            let entry = iter.next().execute_with(jvm)?.ok_or(Error::NullDeref)?;
+            // SAFETY: the entries of a `Map<JK, JV>` map `JK`s to `JV`s
+            let key: Option<Local<JK>> =
+                unsafe { call::object_method(jvm, entry.as_raw(), get_key, &[]) }?;
+            let value: Option<Local<JV>> =
+                unsafe { call::object_method(jvm, entry.as_raw(), get_value, &[]) }?;
+            let key = key.ok_or(Error::NullDeref)?;
+            let value = value.ok_or(Error::NullDeref)?;
+            let key = <&JK as IntoRust<K>>::into_rust(&key, jvm)?;
+            let value = <&JV as IntoRust<V>>::into_rust(&value, jvm)?;
+            Ok((key, value))
+        })?;
+        insert(key, value);
+    }
+    Ok(())
+}
+
 #[derive_where::derive_where(Copy, Clone)]
 pub struct ToRustOp<This, R>
 where
@@ -106,9 +287,263 @@ where
 {
     type Output<'jvm> = R;
 
+    const LOCALS: usize =
+        crate::ops::sum_locals(&[This::LOCALS, <This::Output<'static> as IntoRust<R>>::LOCALS]);
+
     fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
         let java = self.this.execute_with(jvm)?;
         let rust = IntoRust::into_rust(java, jvm)?;
         Ok(rust)
     }
 }
+
+/// Like [`ToRustOp`], but for large object graphs. See [`JvmOp::to_rust_deep`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct DeepToRustOp<This, R>
+where
+    This: JvmOp,
+{
+    this: This,
+    phantom: PhantomData<R>,
+}
+
+impl<This, R> DeepToRustOp<This, R>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: IntoRust<R>,
+{
+    pub(crate) fn new(this: This) -> Self {
+        DeepToRustOp {
+            this,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<This, R> JvmOp for DeepToRustOp<This, R>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: IntoRust<R>,
+{
+    type Output<'jvm> = R;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let _deep = DeepConversion::enter();
+        jvm.with_frame(crate::config::local_frame_capacity(), |jvm| {
+            let java = self.this.execute_with(jvm)?;
+            IntoRust::into_rust(java, jvm)
+        })
+    }
+}
+
+// The lookups cached by the deep conversion running on the current thread (see [`deep_cached`]), if there is one
+thread_local! {
+    static DEEP: RefCell<Option<HashMap<TypeId, Box<dyn Any>>>> = const { RefCell::new(None) };
+}
+
+/// Guard of the outermost [`DeepToRustOp`] running on the current thread, which ends the deep conversion when dropped.
+struct DeepConversion {
+    outermost: bool,
+}
+
+impl DeepConversion {
+    fn enter() -> Self {
+        let outermost = DEEP.with_borrow_mut(|deep| {
+            let outermost = deep.is_none();
+            deep.get_or_insert_with(HashMap::new);
+            outermost
+        });
+        DeepConversion { outermost }
+    }
+}
+
+impl Drop for DeepConversion {
+    fn drop(&mut self) {
+        if self.outermost {
+            // Dropped outside of the borrow, since the cached values may be anything
+            drop(DEEP.with_borrow_mut(Option::take));
+        }
+    }
+}
+
+/// Converts an element of a collection with `op`. During deep conversions, the element is converted in a local frame of
+/// its own, so that the local references borrowed along the way are released element by element.
+pub(crate) fn convert_element<'jvm, R>(
+    jvm: &mut Jvm<'jvm>,
+    op: impl for<'frame> FnOnce(&mut Jvm<'frame>) -> crate::Result<'frame, R>,
+) -> crate::Result<'jvm, R> {
+    if DEEP.with_borrow(Option::is_some) {
+        jvm.with_frame(crate::config::local_frame_capacity(), op)
+    } else {
+        op(jvm)
+    }
+}
+
+/// The value of type `T` returned by `lookup`, which is only called once per deep conversion: the value is cached
+/// (by its type) until the conversion ends, so that the elements of large collections share it. Outside of deep
+/// conversions, this just calls `lookup`.
+pub(crate) fn deep_cached<T>(lookup: impl FnOnce() -> T) -> T
+where
+    T: Copy + 'static,
+{
+    let key = TypeId::of::<T>();
+    let cached = DEEP.with_borrow(|deep| {
+        let deep = deep.as_ref()?;
+        Some(
+            deep.get(&key)
+                .map(|value| *value.downcast_ref::<T>().unwrap()),
+        )
+    });
+    match cached {
+        None => lookup(),
+        Some(Some(value)) => value,
+        Some(None) => {
+            // Looked up outside of the borrow, since `lookup` may convert other values
+            let value = lookup();
+            DEEP.with_borrow_mut(|deep| {
+                if let Some(deep) = deep {
+                    deep.insert(key, Box::new(value));
+                }
+            });
+            value
+        }
+    }
+}
+
+/// Fallible counterpart to [`IntoRust`], for conversions that can fail because of the value being converted (e.g., an
+/// `int` that is negative when converting to `u32`).
+///
+/// JVM failures are reported through the outer [`crate::Result`], while problems with the data itself are reported
+/// as a [`ConversionError`] in the inner result, so they can be handled separately.
+pub trait TryIntoRust<R> {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<R, ConversionError>>;
+}
+
+/// Converts `java` with [`TryIntoRust`], reporting a [`ConversionError`] as [`Error::Conversion`]. This is how the
+/// [`IntoRust`] impls of fallible conversions are written.
+pub(crate) fn into_rust_or_conversion_error<'jvm, R>(
+    java: impl TryIntoRust<R>,
+    jvm: &mut Jvm<'jvm>,
+) -> crate::Result<'jvm, R> {
+    java.try_into_rust(jvm)?.map_err(Error::Conversion)
+}
+
+macro_rules! try_from_scalar {
+    ($($java:ty => [$($rust:ty),*],)*) => {
+        $($(
+            impl TryIntoRust<$rust> for $java {
+                fn try_into_rust<'jvm>(
+                    self,
+                    _jvm: &mut Jvm<'jvm>,
+                ) -> crate::Result<'jvm, Result<$rust, ConversionError>> {
+                    Ok(<$rust>::try_from(self).map_err(|_| ConversionError::OutOfRange {
+                        value: self.to_string(),
+                        target: stringify!($rust),
+                    }))
+                }
+            }
+        )*)*
+    };
+}
+
+try_from_scalar! {
+    i8 => [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize],
+    i16 => [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize],
+    i32 => [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize],
+    i64 => [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize],
+    u16 => [i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize],
+}
+
+/// A Java `char` is a UTF-16 code unit, so it fails to convert if it is (half of) a surrogate pair.
+impl TryIntoRust<char> for u16 {
+    fn try_into_rust<'jvm>(
+        self,
+        _jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<char, ConversionError>> {
+        Ok(
+            char::from_u32(u32::from(self)).ok_or(ConversionError::OutOfRange {
+                value: format!("{self:#06x}"),
+                target: "char",
+            }),
+        )
+    }
+}
+
+impl<O, JO> TryIntoRust<Option<O>> for Option<JO>
+where
+    JO: TryIntoRust<O>,
+{
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<Option<O>, ConversionError>> {
+        match self {
+            Some(jo) => Ok(jo.try_into_rust(jvm)?.map(Some)),
+            None => Ok(Ok(None)),
+        }
+    }
+}
+
+impl<R, J> TryIntoRust<R> for Local<'_, J>
+where
+    J: JavaObject,
+    for<'a> &'a J: TryIntoRust<R>,
+{
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<R, ConversionError>> {
+        <&J as TryIntoRust<R>>::try_into_rust(&self, jvm)
+    }
+}
+
+impl<R, J> TryIntoRust<R> for Global<J>
+where
+    J: JavaObject,
+    for<'a> &'a J: TryIntoRust<R>,
+{
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<R, ConversionError>> {
+        <&J as TryIntoRust<R>>::try_into_rust(&self, jvm)
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct TryToRustOp<This, R>
+where
+    This: JvmOp,
+{
+    this: This,
+    phantom: PhantomData<R>,
+}
+
+impl<This, R> TryToRustOp<This, R>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: TryIntoRust<R>,
+{
+    pub(crate) fn new(this: This) -> Self {
+        TryToRustOp {
+            this,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<This, R> JvmOp for TryToRustOp<This, R>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: TryIntoRust<R>,
+{
+    type Output<'jvm> = Result<R, ConversionError>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let java = self.this.execute_with(jvm)?;
+        TryIntoRust::try_into_rust(java, jvm)
+    }
+}
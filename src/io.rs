@@ -0,0 +1,580 @@
+//! `std::io` adapters for Java streams: [`JavaInputStream`] and [`JavaOutputStream`] implement [`Read`] and
+//! [`Write`] over a `java.io.InputStream` and `java.io.OutputStream`, and [`InputStream::from_read`] and
+//! [`OutputStream::from_write`] go the other way, creating Java streams over a Rust reader or writer.
+//!
+//! The adapters copy bytes through a `byte[]` that they allocate once and reuse, so each call reads or writes at most
+//! that many bytes (8 KiB unless chosen otherwise). They attach to the JVM with [`Jvm::with`] on each call, which
+//! reuses the attachment of an enclosing `Jvm::with`.
+//!
+//! The Java streams are instances of `duchess.RustInputStream` and `duchess.RustOutputStream` (see `java/duchess`),
+//! helper classes that duchess defines in the JVM on first use. The Rust reader or writer is dropped when the stream
+//! is closed (a writer is flushed first), or once the stream is garbage collected.
+
+use std::{
+    ffi::{c_char, c_void, CStr},
+    io::{self, Read, Write},
+    panic::AssertUnwindSafe,
+    sync::{Mutex, MutexGuard},
+};
+
+use jni_sys::{jint, jlong, jvalue};
+use once_cell::sync::OnceCell;
+
+use crate::{
+    find::find_method,
+    java::{
+        self,
+        io::{InputStream, OutputStream},
+        lang::{Class, Throwable},
+    },
+    jvm::{native_scalar_function_with_jvm, JavaObjectExt},
+    proxy::define_class,
+    raw::EnvPtr,
+    to_java::ToJava,
+    Error, Global, Jvm, JvmOp, Local,
+};
+
+const DEFAULT_CAPACITY: usize = 8192;
+
+// Compiled by `java/compile.sh`, see `src/proxy.rs`.
+const INPUT_CLASS_NAME: &CStr = c"duchess/RustInputStream";
+const INPUT_CLASS: &[u8] = include_bytes!("../java/classes/duchess/RustInputStream.class");
+
+const INPUT_RELEASE_CLASS_NAME: &CStr = c"duchess/RustInputStream$Release";
+const INPUT_RELEASE_CLASS: &[u8] =
+    include_bytes!("../java/classes/duchess/RustInputStream$Release.class");
+
+const OUTPUT_CLASS_NAME: &CStr = c"duchess/RustOutputStream";
+const OUTPUT_CLASS: &[u8] = include_bytes!("../java/classes/duchess/RustOutputStream.class");
+
+const OUTPUT_RELEASE_CLASS_NAME: &CStr = c"duchess/RustOutputStream$Release";
+const OUTPUT_RELEASE_CLASS: &[u8] =
+    include_bytes!("../java/classes/duchess/RustOutputStream$Release.class");
+
+/// A [`Read`] over a Java `InputStream`, see the [module docs](self).
+pub struct JavaInputStream {
+    stream: Global<InputStream>,
+    buffer: Global<java::Array<i8>>,
+    capacity: usize,
+}
+
+impl JavaInputStream {
+    /// Reads from `stream`, at most 8 KiB at a time.
+    pub fn new<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        stream: Global<InputStream>,
+    ) -> crate::Result<'jvm, Self> {
+        Self::with_capacity(jvm, stream, DEFAULT_CAPACITY)
+    }
+
+    /// Reads from `stream`, at most `capacity` bytes at a time. Panics if `capacity` is zero.
+    pub fn with_capacity<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        stream: Global<InputStream>,
+        capacity: usize,
+    ) -> crate::Result<'jvm, Self> {
+        let buffer = new_buffer(jvm, capacity)?;
+        Ok(JavaInputStream {
+            stream,
+            buffer,
+            capacity,
+        })
+    }
+
+    pub fn get_ref(&self) -> &Global<InputStream> {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> Global<InputStream> {
+        self.stream
+    }
+}
+
+impl Read for JavaInputStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = buf.len().min(self.capacity);
+        Jvm::with(|jvm| {
+            let n = self
+                .stream
+                .read(&self.buffer, 0, len as jint)
+                .execute_with(jvm)?;
+            // `read` returns -1 at the end of the stream
+            let Ok(n) = usize::try_from(n) else {
+                return Ok(0);
+            };
+            get_region(jvm, &self.buffer, 0, &mut buf[..n])?;
+            Ok(n)
+        })
+        .map_err(io_error)
+    }
+}
+
+/// A [`Write`] over a Java `OutputStream`, see the [module docs](self).
+///
+/// Like Java's streams, it isn't closed when dropped: call `close` on the stream for that.
+pub struct JavaOutputStream {
+    stream: Global<OutputStream>,
+    buffer: Global<java::Array<i8>>,
+    capacity: usize,
+}
+
+impl JavaOutputStream {
+    /// Writes to `stream`, at most 8 KiB at a time.
+    pub fn new<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        stream: Global<OutputStream>,
+    ) -> crate::Result<'jvm, Self> {
+        Self::with_capacity(jvm, stream, DEFAULT_CAPACITY)
+    }
+
+    /// Writes to `stream`, at most `capacity` bytes at a time. Panics if `capacity` is zero.
+    pub fn with_capacity<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        stream: Global<OutputStream>,
+        capacity: usize,
+    ) -> crate::Result<'jvm, Self> {
+        let buffer = new_buffer(jvm, capacity)?;
+        Ok(JavaOutputStream {
+            stream,
+            buffer,
+            capacity,
+        })
+    }
+
+    pub fn get_ref(&self) -> &Global<OutputStream> {
+        &self.stream
+    }
+
+    pub fn into_inner(self) -> Global<OutputStream> {
+        self.stream
+    }
+}
+
+impl Write for JavaOutputStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = buf.len().min(self.capacity);
+        Jvm::with(|jvm| {
+            set_region(jvm, &self.buffer, 0, &buf[..len])?;
+            self.stream
+                .write(&self.buffer, 0, len as jint)
+                .execute_with(jvm)?;
+            Ok(len)
+        })
+        .map_err(io_error)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Jvm::with(|jvm| self.stream.flush().execute_with(jvm)).map_err(io_error)
+    }
+}
+
+impl InputStream {
+    /// Creates an `InputStream` that reads from `reader`.
+    ///
+    /// Java threads take turns reading from `reader`. Its errors are thrown as `IOException`s (except for
+    /// [`io::ErrorKind::Interrupted`], on which the read is retried), and its panics as `RuntimeException`s. `reader`
+    /// is dropped when the stream is closed, or once the stream is garbage collected.
+    pub fn from_read<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        reader: impl Read + Send + 'static,
+    ) -> crate::Result<'jvm, Local<'jvm, Self>> {
+        let class = input_class(jvm)?;
+        let stream: Box<Handle<dyn Read + Send>> = Box::new(Mutex::new(Stream {
+            inner: Some(Box::new(reader)),
+            buffer: Vec::new(),
+        }));
+        // SAFETY: `RustInputStream` streams are `InputStream`s
+        unsafe {
+            new_stream(
+                jvm,
+                &class,
+                c"(J)Ljava/io/InputStream;",
+                Box::into_raw(stream) as jlong,
+            )
+        }
+    }
+}
+
+impl OutputStream {
+    /// Creates an `OutputStream` that writes to `writer`.
+    ///
+    /// Java threads take turns writing to `writer`. Its errors are thrown as `IOException`s, and its panics as
+    /// `RuntimeException`s. `writer` is flushed and dropped when the stream is closed, or just dropped once the
+    /// stream is garbage collected.
+    pub fn from_write<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        writer: impl Write + Send + 'static,
+    ) -> crate::Result<'jvm, Local<'jvm, Self>> {
+        let class = output_class(jvm)?;
+        let stream: Box<Handle<dyn Write + Send>> = Box::new(Mutex::new(Stream {
+            inner: Some(Box::new(writer)),
+            buffer: Vec::new(),
+        }));
+        // SAFETY: `RustOutputStream` streams are `OutputStream`s
+        unsafe {
+            new_stream(
+                jvm,
+                &class,
+                c"(J)Ljava/io/OutputStream;",
+                Box::into_raw(stream) as jlong,
+            )
+        }
+    }
+}
+
+/// The state behind a `RustInputStream` or `RustOutputStream`: the reader or writer (until the stream is closed),
+/// and the buffer that bytes are copied through.
+struct Stream<T: ?Sized> {
+    inner: Option<Box<T>>,
+    buffer: Vec<u8>,
+}
+
+type Handle<T> = Mutex<Stream<T>>;
+
+fn lock<T: ?Sized>(handle: &Handle<T>) -> MutexGuard<'_, Stream<T>> {
+    handle.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Calls the `newStream` method (with the given descriptor) of the helper class `class`, which takes ownership of
+/// `handle`.
+///
+/// # Safety
+///
+/// `class` must create instances of `J`, and `handle` must be the state that its native methods expect.
+unsafe fn new_stream<'jvm, J: crate::JavaObject>(
+    jvm: &mut Jvm<'jvm>,
+    class: &Class,
+    descriptor: &CStr,
+    handle: jlong,
+) -> crate::Result<'jvm, Local<'jvm, J>> {
+    let new_stream = find_method(jvm, class, c"newStream", descriptor, true)?;
+    let args = [jvalue { j: handle }];
+    // SAFETY: the arguments match the descriptor of `newStream`, which takes ownership of `handle` (the stream is
+    // created before anything in there can throw)
+    let stream: Option<Local<'jvm, J>> = unsafe {
+        jvm.env().invoke(
+            |env| env.CallStaticObjectMethodA,
+            |env, f| {
+                f(
+                    env,
+                    class.as_raw().as_ptr(),
+                    new_stream.as_ptr(),
+                    args.as_ptr(),
+                )
+            },
+        )
+    }?;
+    stream.ok_or_else(|| Error::JvmInternal("`newStream` returned null".into()))
+}
+
+/// Allocates the `byte[]` that an adapter copies bytes through.
+fn new_buffer<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    capacity: usize,
+) -> crate::Result<'jvm, Global<java::Array<i8>>> {
+    assert!(
+        capacity > 0,
+        "the buffer of a stream adapter cannot be empty"
+    );
+    vec![0_u8; capacity]
+        .to_java::<java::Array<i8>>()
+        .assert_not_null()
+        .global()
+        .execute_with(jvm)
+}
+
+/// Copies the bytes of `array` from `offset` into `bytes`, with `GetByteArrayRegion`.
+fn get_region<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    array: &java::Array<i8>,
+    offset: jint,
+    bytes: &mut [u8],
+) -> crate::Result<'jvm, ()> {
+    // SAFETY: `bytes` has room for `bytes.len()` bytes, and JNI checks that the array has as many after `offset`
+    unsafe {
+        jvm.env().invoke(
+            |env| env.GetByteArrayRegion,
+            |env, f| {
+                f(
+                    env,
+                    array.as_raw().as_ptr(),
+                    offset,
+                    bytes.len() as jni_sys::jsize,
+                    bytes.as_mut_ptr().cast(),
+                )
+            },
+        )
+    }
+}
+
+/// Copies `bytes` into `array` from `offset`, with `SetByteArrayRegion`.
+fn set_region<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    array: &java::Array<i8>,
+    offset: jint,
+    bytes: &[u8],
+) -> crate::Result<'jvm, ()> {
+    // SAFETY: as for `get_region`
+    unsafe {
+        jvm.env().invoke(
+            |env| env.SetByteArrayRegion,
+            |env, f| {
+                f(
+                    env,
+                    array.as_raw().as_ptr(),
+                    offset,
+                    bytes.len() as jni_sys::jsize,
+                    bytes.as_ptr().cast(),
+                )
+            },
+        )
+    }
+}
+
+fn io_error(error: crate::Error<Global<Throwable>>) -> io::Error {
+    io::Error::other(error)
+}
+
+/// A new `IOException` with the message of `error`, to throw from a native method.
+fn io_exception<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    error: &io::Error,
+) -> crate::Error<Local<'jvm, Throwable>> {
+    match java::io::IOException::new(&error.to_string()).execute_with(jvm) {
+        Ok(exception) => Error::Thrown(exception.upcast()),
+        Err(e) => e,
+    }
+}
+
+fn closed_error() -> io::Error {
+    io::Error::other("stream closed")
+}
+
+/// Retries `op` as long as it fails with [`io::ErrorKind::Interrupted`].
+fn retry<R>(mut op: impl FnMut() -> io::Result<R>) -> io::Result<R> {
+    loop {
+        match op() {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            result => return result,
+        }
+    }
+}
+
+fn input_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Class>> {
+    static CLASS: OnceCell<Global<Class>> = OnceCell::new();
+    let natives = [
+        jni_sys::JNINativeMethod {
+            name: c"read".as_ptr() as *mut c_char,
+            signature: c"(J[BII)I".as_ptr() as *mut c_char,
+            fnPtr: read as *mut c_void,
+        },
+        jni_sys::JNINativeMethod {
+            name: c"close".as_ptr() as *mut c_char,
+            signature: c"(J)V".as_ptr() as *mut c_char,
+            fnPtr: close_reader as *mut c_void,
+        },
+        jni_sys::JNINativeMethod {
+            name: c"release".as_ptr() as *mut c_char,
+            signature: c"(J)V".as_ptr() as *mut c_char,
+            fnPtr: release_reader as *mut c_void,
+        },
+    ];
+    // SAFETY: the native methods match the declarations in `RustInputStream`
+    unsafe {
+        helper_class(
+            jvm,
+            &CLASS,
+            (INPUT_CLASS_NAME, INPUT_CLASS),
+            (INPUT_RELEASE_CLASS_NAME, INPUT_RELEASE_CLASS),
+            &natives,
+        )
+    }
+}
+
+fn output_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Class>> {
+    static CLASS: OnceCell<Global<Class>> = OnceCell::new();
+    let natives = [
+        jni_sys::JNINativeMethod {
+            name: c"write".as_ptr() as *mut c_char,
+            signature: c"(J[BII)V".as_ptr() as *mut c_char,
+            fnPtr: write as *mut c_void,
+        },
+        jni_sys::JNINativeMethod {
+            name: c"flush".as_ptr() as *mut c_char,
+            signature: c"(J)V".as_ptr() as *mut c_char,
+            fnPtr: flush as *mut c_void,
+        },
+        jni_sys::JNINativeMethod {
+            name: c"close".as_ptr() as *mut c_char,
+            signature: c"(J)V".as_ptr() as *mut c_char,
+            fnPtr: close_writer as *mut c_void,
+        },
+        jni_sys::JNINativeMethod {
+            name: c"release".as_ptr() as *mut c_char,
+            signature: c"(J)V".as_ptr() as *mut c_char,
+            fnPtr: release_writer as *mut c_void,
+        },
+    ];
+    // SAFETY: the native methods match the declarations in `RustOutputStream`
+    unsafe {
+        helper_class(
+            jvm,
+            &CLASS,
+            (OUTPUT_CLASS_NAME, OUTPUT_CLASS),
+            (OUTPUT_RELEASE_CLASS_NAME, OUTPUT_RELEASE_CLASS),
+            &natives,
+        )
+    }
+}
+
+/// Returns the helper class cached in `cell`, defining it (and its `Release` class, and registering its native
+/// methods) on first use.
+///
+/// # Safety
+///
+/// `natives` must match the native methods declared by the class.
+unsafe fn helper_class<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    cell: &OnceCell<Global<Class>>,
+    (name, bytes): (&CStr, &[u8]),
+    (release_name, release_bytes): (&CStr, &[u8]),
+    natives: &[jni_sys::JNINativeMethod],
+) -> crate::Result<'jvm, Local<'jvm, Class>> {
+    let global = cell.get_or_try_init::<_, crate::Error<Local<Throwable>>>(|| {
+        let loader = java::lang::ClassLoader::get_system_class_loader().execute_with(jvm)?;
+        let release = define_class(jvm, loader.as_deref(), release_name, release_bytes)?;
+        let class = define_class(jvm, loader.as_deref(), name, bytes)?;
+        drop(release);
+        // SAFETY: the caller guarantees that the native methods match
+        unsafe { jvm.env().register_native_methods(class.as_raw(), natives)? };
+        Ok(jvm.global(&class))
+    })?;
+    Ok(jvm.local(global))
+}
+
+/// Implementation of `RustInputStream.read`.
+unsafe extern "system" fn read(
+    env: EnvPtr<'_>,
+    _class: jni_sys::jclass,
+    reader: jlong,
+    b: &java::Array<i8>,
+    off: jint,
+    len: jint,
+) -> jint {
+    // SAFETY: `reader` was created by `InputStream::from_read` and is live until `release_reader` is called
+    let reader = unsafe { &*(reader as *const Handle<dyn Read + Send>) };
+    unsafe {
+        native_scalar_function_with_jvm(env, |jvm| {
+            let mut stream = lock(reader);
+            let Stream { inner, buffer } = &mut *stream;
+            buffer.resize((len as usize).min(DEFAULT_CAPACITY), 0);
+            let n = match inner {
+                Some(reader) => retry(|| reader.read(buffer)),
+                None => Err(closed_error()),
+            };
+            let n = n.map_err(|e| io_exception(jvm, &e))?;
+            if n == 0 {
+                return Ok(-1);
+            }
+            set_region(jvm, b, off, &buffer[..n])?;
+            Ok(n as jint)
+        })
+    }
+}
+
+/// Implementation of `RustInputStream.close`.
+unsafe extern "system" fn close_reader(env: EnvPtr<'_>, _class: jni_sys::jclass, reader: jlong) {
+    // SAFETY: as for `read`
+    let reader = unsafe { &*(reader as *const Handle<dyn Read + Send>) };
+    unsafe {
+        native_scalar_function_with_jvm(env, |_jvm| {
+            drop(lock(reader).inner.take());
+            Ok(())
+        })
+    }
+}
+
+/// Implementation of `RustInputStream.release`.
+unsafe extern "system" fn release_reader(_env: EnvPtr<'_>, _class: jni_sys::jclass, reader: jlong) {
+    // SAFETY: `reader` was created by `InputStream::from_read` and is released exactly once, by the stream's cleaner
+    let reader = unsafe { Box::from_raw(reader as *mut Handle<dyn Read + Send>) };
+    if std::panic::catch_unwind(AssertUnwindSafe(|| drop(reader))).is_err() {
+        tracing::warn!("panic while dropping reader of a Java input stream");
+    }
+}
+
+/// Implementation of `RustOutputStream.write`.
+unsafe extern "system" fn write(
+    env: EnvPtr<'_>,
+    _class: jni_sys::jclass,
+    writer: jlong,
+    b: &java::Array<i8>,
+    off: jint,
+    len: jint,
+) {
+    // SAFETY: `writer` was created by `OutputStream::from_write` and is live until `release_writer` is called
+    let writer = unsafe { &*(writer as *const Handle<dyn Write + Send>) };
+    unsafe {
+        native_scalar_function_with_jvm(env, |jvm| {
+            let mut stream = lock(writer);
+            let Stream { inner, buffer } = &mut *stream;
+            let Some(writer) = inner else {
+                return Err(io_exception(jvm, &closed_error()));
+            };
+            let (mut off, mut len) = (off, len as usize);
+            while len > 0 {
+                let chunk = len.min(DEFAULT_CAPACITY);
+                buffer.resize(chunk, 0);
+                get_region(jvm, b, off, buffer)?;
+                writer
+                    .write_all(buffer)
+                    .map_err(|e| io_exception(jvm, &e))?;
+                off += chunk as jint;
+                len -= chunk;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Implementation of `RustOutputStream.flush`.
+unsafe extern "system" fn flush(env: EnvPtr<'_>, _class: jni_sys::jclass, writer: jlong) {
+    // SAFETY: as for `write`
+    let writer = unsafe { &*(writer as *const Handle<dyn Write + Send>) };
+    unsafe {
+        native_scalar_function_with_jvm(env, |jvm| match &mut lock(writer).inner {
+            Some(writer) => retry(|| writer.flush()).map_err(|e| io_exception(jvm, &e)),
+            None => Err(io_exception(jvm, &closed_error())),
+        })
+    }
+}
+
+/// Implementation of `RustOutputStream.close`.
+unsafe extern "system" fn close_writer(env: EnvPtr<'_>, _class: jni_sys::jclass, writer: jlong) {
+    // SAFETY: as for `write`
+    let writer = unsafe { &*(writer as *const Handle<dyn Write + Send>) };
+    unsafe {
+        native_scalar_function_with_jvm(env, |jvm| {
+            let Some(mut writer) = lock(writer).inner.take() else {
+                return Ok(());
+            };
+            retry(|| writer.flush()).map_err(|e| io_exception(jvm, &e))
+        })
+    }
+}
+
+/// Implementation of `RustOutputStream.release`.
+unsafe extern "system" fn release_writer(_env: EnvPtr<'_>, _class: jni_sys::jclass, writer: jlong) {
+    // SAFETY: `writer` was created by `OutputStream::from_write` and is released exactly once, by the stream's
+    // cleaner
+    let writer = unsafe { Box::from_raw(writer as *mut Handle<dyn Write + Send>) };
+    if std::panic::catch_unwind(AssertUnwindSafe(|| drop(writer))).is_err() {
+        tracing::warn!("panic while dropping writer of a Java output stream");
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    cast::Upcast,
+    java::{self, lang::Object},
+    AsJRef, Error, Global, GlobalResult, IntoJava, Jvm, JvmOp,
+};
+
+/// A Rust [`Iterator`] over the elements of a Java `java.util.Iterator`, so Java collections can be used with `for`
+/// loops and iterator adapters.
+///
+/// Each call to [`next`](Iterator::next) attaches to the JVM with [`Jvm::with`], so the iterator must not be used from
+/// within one. Null elements are reported as [`Error::NullDeref`], and exceptions thrown by the Java iterator are
+/// returned as errors without ending the iteration.
+pub struct JavaIterator<T: Upcast<Object>> {
+    iter: Global<java::util::Iterator<T>>,
+}
+
+impl<T: Upcast<Object>> JavaIterator<T> {
+    pub fn new(iter: Global<java::util::Iterator<T>>) -> Self {
+        JavaIterator { iter }
+    }
+
+    /// Iterates over `iterable` (e.g., a `java.util.List`).
+    pub fn from_iterable<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        iterable: impl IntoJava<java::lang::Iterable<T>>,
+    ) -> crate::Result<'jvm, Self> {
+        let iterable = iterable.into_java(jvm)?;
+        let iterable: &java::lang::Iterable<T> = iterable.as_jref()?;
+        let iter = iterable.iterator().assert_not_null().execute_with(jvm)?;
+        Ok(Self::new(jvm.global(&*iter)))
+    }
+
+    /// The underlying Java iterator.
+    pub fn as_java(&self) -> &Global<java::util::Iterator<T>> {
+        &self.iter
+    }
+}
+
+impl<T: Upcast<Object>> Iterator for JavaIterator<T> {
+    type Item = GlobalResult<Global<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = Jvm::with(|jvm| {
+            if !self.iter.has_next().execute_with(jvm)? {
+                return Ok(None);
+            }
+            let element = self.iter.next().execute_with(jvm)?;
+            let element = element.ok_or(Error::NullDeref)?;
+            Ok(Some(jvm.global(&*element)))
+        });
+        next.transpose()
+    }
+}
+
+impl<T: Upcast<Object>> IntoIterator for Global<java::util::Iterator<T>> {
+    type Item = GlobalResult<Global<T>>;
+    type IntoIter = JavaIterator<T>;
+
+    fn into_iter(self) -> JavaIterator<T> {
+        JavaIterator::new(self)
+    }
+}
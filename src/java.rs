@@ -5,10 +5,96 @@ mod auto {
     use crate as duchess;
 
     duchess_macro::java_package! {
+        package java.io;
+
+        public interface java.io.Serializable {
+        }
+
+        public class java.io.IOException extends java.lang.Exception {
+            public java.io.IOException(java.lang.String);
+        }
+
+        public abstract class java.io.OutputStream {
+            // public abstract void write(int) throws java.io.IOException;
+            // public void write(byte[]) throws java.io.IOException;
+            public void write(byte[], int, int) throws java.io.IOException;
+            public void flush() throws java.io.IOException;
+            public void close() throws java.io.IOException;
+        }
+
+        public abstract class java.io.InputStream {
+            // public abstract int read() throws java.io.IOException;
+            // public int read(byte[]) throws java.io.IOException;
+            public int read(byte[], int, int) throws java.io.IOException;
+            public byte[] readAllBytes() throws java.io.IOException;
+            public int available() throws java.io.IOException;
+            public long transferTo(java.io.OutputStream) throws java.io.IOException;
+            public void close() throws java.io.IOException;
+        }
+
+        public class java.io.ByteArrayOutputStream extends java.io.OutputStream {
+            public java.io.ByteArrayOutputStream();
+            public synchronized byte[] toByteArray();
+            public synchronized int size();
+        }
+
+        public class java.io.ByteArrayInputStream extends java.io.InputStream {
+            public java.io.ByteArrayInputStream(byte[]);
+        }
+
+        public class java.io.ObjectOutputStream extends java.io.OutputStream {
+            public java.io.ObjectOutputStream(java.io.OutputStream) throws java.io.IOException;
+            public final void writeObject(java.lang.Object) throws java.io.IOException;
+            public void flush() throws java.io.IOException;
+            public void close() throws java.io.IOException;
+        }
+
+        public class java.io.File implements java.lang.Comparable<java.io.File> { // implements java.io.Serializable {
+            public java.io.File(java.lang.String);
+            // public java.io.File(java.lang.String, java.lang.String);
+            // public java.io.File(java.io.File, java.lang.String);
+            // public java.io.File(java.net.URI);
+            public java.lang.String getName();
+            public java.lang.String getParent();
+            public java.io.File getParentFile();
+            public java.lang.String getPath();
+            public boolean isAbsolute();
+            public java.lang.String getAbsolutePath();
+            public java.io.File getAbsoluteFile();
+            public java.net.URI toURI();
+            public boolean exists();
+            public boolean isDirectory();
+            public boolean isFile();
+            public long length();
+            public boolean delete();
+            public java.lang.String[] list();
+            public boolean mkdirs();
+            public java.nio.file.Path toPath();
+            public java.lang.String toString();
+        }
+
+        public class java.io.RandomAccessFile {
+            public java.io.RandomAccessFile(java.lang.String, java.lang.String) throws java.io.FileNotFoundException;
+            public final java.nio.channels.FileChannel getChannel();
+            public native long length() throws java.io.IOException;
+            public void close() throws java.io.IOException;
+        }
+
+        public class java.io.ObjectInputStream extends java.io.InputStream {
+            public java.io.ObjectInputStream(java.io.InputStream) throws java.io.IOException;
+            public final java.lang.Object readObject() throws java.io.IOException, java.lang.ClassNotFoundException;
+            public final void setObjectInputFilter(java.io.ObjectInputFilter);
+            public void close() throws java.io.IOException;
+        }
+
+        public interface java.io.ObjectInputFilter {
+        }
+
         package java.lang;
 
         public class java.lang.Object {
             public java.lang.Object();
+            public final native java.lang.Class getClass();
             public native int hashCode();
             public boolean equals(java.lang.Object);
             public java.lang.String toString();
@@ -51,7 +137,16 @@ mod auto {
         }
 
         public class java.lang.RuntimeException extends java.lang.Exception {
-            public java.lang.RuntimeException();
+            // public java.lang.RuntimeException();
+            public java.lang.RuntimeException(java.lang.String);
+        }
+
+        public class java.lang.IllegalArgumentException extends java.lang.RuntimeException {
+            public java.lang.IllegalArgumentException(java.lang.String);
+        }
+
+        public class java.lang.IllegalStateException extends java.lang.RuntimeException {
+            public java.lang.IllegalStateException(java.lang.String);
         }
 
         // NB: In Java, this is `Class<T>`, but we model it as the erased version
@@ -64,7 +159,7 @@ mod auto {
         // FIXME(#41): It's not clear that this is the best solution, and we may revisit it in the future,
         // perhaps by not modeling `arrayType()` and friends, or perhaps by finding some way to
         // model `?` in return types in a satisfactory way.
-        public final class java.lang.Class {
+        public final class java.lang.Class implements java.lang.reflect.AnnotatedElement {
             public java.lang.String toString();
             public java.lang.String toGenericString();
             public native boolean isInstance(java.lang.Object);
@@ -73,6 +168,7 @@ mod auto {
             public native boolean isPrimitive();
             public boolean isAnnotation();
             public boolean isSynthetic();
+            public boolean desiredAssertionStatus();
             public java.lang.String getName();
             public native java.lang.Class getSuperclass();
             // public native boolean isAssignableFrom(java.lang.Class<?>);
@@ -80,12 +176,148 @@ mod auto {
             public java.lang.Class[] getInterfaces();
             public java.lang.Class getComponentType();
             public java.lang.Class arrayType();
+            public java.lang.ClassLoader getClassLoader();
+            public java.lang.reflect.Field[] getDeclaredFields() throws java.lang.SecurityException;
+            public java.lang.reflect.Method[] getDeclaredMethods() throws java.lang.SecurityException;
+            public java.lang.reflect.Method[] getMethods() throws java.lang.SecurityException;
+            public java.lang.reflect.Field getDeclaredField(java.lang.String) throws java.lang.NoSuchFieldException, java.lang.SecurityException;
         }
 
         public final class java.lang.String {
             public java.lang.String(byte[]);
             public int length();
             public boolean isEmpty();
+            public int codePointAt(int);
+            public int indexOf(int);
+            public java.lang.String toUpperCase(java.util.Locale);
+            public java.lang.String toLowerCase(java.util.Locale);
+            public char[] toCharArray();
+        }
+
+        public interface java.lang.Iterable<T> {
+            public abstract java.util.Iterator<T> iterator();
+        }
+
+        public interface java.lang.Runnable {
+            public abstract void run();
+        }
+
+        public class java.lang.Thread implements java.lang.Runnable {
+            public static final int MIN_PRIORITY;
+            public static final int NORM_PRIORITY;
+            public static final int MAX_PRIORITY;
+            public java.lang.Thread(java.lang.Runnable, java.lang.String);
+            public static native java.lang.Thread currentThread();
+            public void interrupt();
+            public static boolean interrupted();
+            public boolean isInterrupted();
+            public long getId();
+            public final void setPriority(int);
+            public final int getPriority();
+            public final synchronized void setName(java.lang.String);
+            public final java.lang.String getName();
+            public final boolean isDaemon();
+        }
+
+        public interface java.lang.Comparable<T> {
+            public abstract int compareTo(T);
+        }
+
+        public abstract class java.lang.Number {
+            public abstract int intValue();
+            public abstract long longValue();
+            public abstract float floatValue();
+            public abstract double doubleValue();
+            public byte byteValue();
+            public short shortValue();
+        }
+
+        public final class java.lang.Boolean implements java.lang.Comparable<java.lang.Boolean> {
+            public boolean booleanValue();
+            public static java.lang.Boolean valueOf(boolean);
+        }
+
+        public final class java.lang.Character implements java.lang.Comparable<java.lang.Character> {
+            public static final char MIN_VALUE;
+            public static final char MAX_VALUE;
+            public static java.lang.Character valueOf(char);
+            public char charValue();
+            public static java.lang.String toString(int);
+            public static boolean isLetter(int);
+        }
+
+        public final class java.lang.Byte extends java.lang.Number implements java.lang.Comparable<java.lang.Byte> {
+            public static final byte MIN_VALUE;
+            public static final byte MAX_VALUE;
+            public static java.lang.Byte valueOf(byte);
+            public byte byteValue();
+        }
+
+        public final class java.lang.Short extends java.lang.Number implements java.lang.Comparable<java.lang.Short> {
+            public static final short MIN_VALUE;
+            public static final short MAX_VALUE;
+            public static java.lang.Short valueOf(short);
+            public short shortValue();
+        }
+
+        public final class java.lang.Integer extends java.lang.Number implements java.lang.Comparable<java.lang.Integer> {
+            public static final int MIN_VALUE;
+            public static final int MAX_VALUE;
+            public static java.lang.Integer valueOf(int);
+            public int intValue();
+            public java.lang.String toString();
+        }
+
+        public final class java.lang.Long extends java.lang.Number implements java.lang.Comparable<java.lang.Long> {
+            public static final long MIN_VALUE;
+            public static final long MAX_VALUE;
+            public static java.lang.Long valueOf(long);
+            public long longValue();
+            public java.lang.String toString();
+        }
+
+        public final class java.lang.Float extends java.lang.Number implements java.lang.Comparable<java.lang.Float> {
+            public static final float MAX_VALUE;
+            public static final float MIN_VALUE;
+            public static java.lang.Float valueOf(float);
+            public float floatValue();
+        }
+
+        public final class java.lang.Double extends java.lang.Number implements java.lang.Comparable<java.lang.Double> {
+            public static final double MAX_VALUE;
+            public static final double MIN_VALUE;
+            public static java.lang.Double valueOf(double);
+            public double doubleValue();
+        }
+
+        public abstract class java.lang.Enum<E extends java.lang.Enum<E>> {
+            public final java.lang.String name();
+            public final int ordinal();
+            public final java.lang.Class getDeclaringClass();
+            public java.lang.String toString();
+        }
+
+        public abstract class java.lang.ClassLoader {
+            public static java.lang.ClassLoader getSystemClassLoader();
+            public java.lang.String getName();
+            public java.lang.Class loadClass(java.lang.String) throws java.lang.ClassNotFoundException;
+        }
+
+        public final class java.lang.System {
+            public static java.lang.String getProperty(java.lang.String);
+            public static java.lang.String setProperty(java.lang.String, java.lang.String);
+            public static java.lang.String clearProperty(java.lang.String);
+            public static java.lang.String getenv(java.lang.String);
+            public static void exit(int);
+            public static void gc();
+        }
+
+        public class java.lang.Runtime {
+            public static java.lang.Runtime getRuntime();
+            public void exit(int);
+            public void addShutdownHook(java.lang.Thread);
+            public boolean removeShutdownHook(java.lang.Thread);
+            public void halt(int);
         }
 
         public abstract class java.lang.Record {
@@ -95,12 +327,228 @@ mod auto {
         }
 
 
+        package java.lang.annotation;
+
+        public interface java.lang.annotation.Annotation {
+            public abstract java.lang.Class annotationType();
+            public abstract java.lang.String toString();
+        }
+
+        package java.lang.reflect;
+
+        public interface java.lang.reflect.AnnotatedElement {
+            public abstract java.lang.annotation.Annotation[] getAnnotations();
+        }
+
+        public class java.lang.reflect.AccessibleObject implements java.lang.reflect.AnnotatedElement {
+        }
+
+        public abstract class java.lang.reflect.Executable extends java.lang.reflect.AccessibleObject {
+        }
+
+        public final class java.lang.reflect.Method extends java.lang.reflect.Executable {
+            public java.lang.Class getDeclaringClass();
+            public java.lang.String getName();
+            public int getParameterCount();
+            public java.lang.String toString();
+            public java.lang.Class getReturnType();
+            public java.lang.Class[] getParameterTypes();
+            public int getModifiers();
+        }
+
+        public final class java.lang.reflect.Field extends java.lang.reflect.AccessibleObject {
+            public java.lang.Class getDeclaringClass();
+            public java.lang.String getName();
+            public java.lang.Class getType();
+            public java.lang.String toString();
+        }
+
+        package java.math;
+
+        public class java.math.BigInteger extends java.lang.Number implements java.lang.Comparable<java.math.BigInteger> {
+            public java.math.BigInteger(byte[]);
+            // public java.math.BigInteger(java.lang.String);
+            public static java.math.BigInteger valueOf(long);
+            public int signum();
+            public int bitLength();
+            public byte[] toByteArray();
+            public int intValue();
+            public long longValue();
+            public double doubleValue();
+            public java.lang.String toString();
+        }
+
+        public class java.math.BigDecimal extends java.lang.Number implements java.lang.Comparable<java.math.BigDecimal> {
+            public java.math.BigDecimal(java.math.BigInteger, int);
+            // public java.math.BigDecimal(java.lang.String);
+            // public java.math.BigDecimal(long);
+            public static java.math.BigDecimal valueOf(long, int);
+            public int scale();
+            public int precision();
+            public java.math.BigInteger unscaledValue();
+            public int signum();
+            public java.math.BigDecimal stripTrailingZeros();
+            public int intValue();
+            public long longValue();
+            public double doubleValue();
+            public java.lang.String toPlainString();
+            public java.lang.String toString();
+        }
+
+        package java.net;
+
+        public final class java.net.URI implements java.lang.Comparable<java.net.URI> {
+            //   public java.net.URI(java.lang.String) throws java.net.URISyntaxException;
+            public static java.net.URI create(java.lang.String);
+            public java.net.URI normalize();
+            public java.net.URI resolve(java.net.URI);
+            // public java.net.URI resolve(java.lang.String);
+            public java.net.URI relativize(java.net.URI);
+            public java.net.URL toURL() throws java.net.MalformedURLException;
+            public java.lang.String getScheme();
+            public boolean isAbsolute();
+            public boolean isOpaque();
+            public java.lang.String getAuthority();
+            public java.lang.String getUserInfo();
+            public java.lang.String getHost();
+            public int getPort();
+            public java.lang.String getPath();
+            public java.lang.String getQuery();
+            public java.lang.String getFragment();
+            public java.lang.String toString();
+            public java.lang.String toASCIIString();
+        }
+
+        public final class java.net.URL {
+            public java.lang.String toExternalForm();
+            public java.net.URI toURI() throws java.net.URISyntaxException;
+            public java.lang.String toString();
+        }
+
+        public class java.net.InetAddress {
+            public boolean isAnyLocalAddress();
+            public boolean isLoopbackAddress();
+            public java.lang.String getHostName();
+            public byte[] getAddress();
+            public java.lang.String getHostAddress();
+            public java.lang.String toString();
+            // public static java.net.InetAddress getByAddress(java.lang.String, byte[]) throws java.net.UnknownHostException;
+            public static java.net.InetAddress getByName(java.lang.String) throws java.net.UnknownHostException;
+            public static java.net.InetAddress getLoopbackAddress();
+            public static java.net.InetAddress getByAddress(byte[]) throws java.net.UnknownHostException;
+        }
+
+        public class java.net.URLClassLoader extends java.security.SecureClassLoader {
+            public java.net.URLClassLoader(java.net.URL[]);
+            public void close() throws java.io.IOException;
+        }
+
+        package java.nio;
+
+        public abstract class java.nio.Buffer {
+            public final int capacity();
+            public abstract boolean isReadOnly();
+            public abstract boolean isDirect();
+        }
+
+        public abstract class java.nio.ByteBuffer extends java.nio.Buffer {
+            public static java.nio.ByteBuffer allocateDirect(int);
+            public static java.nio.ByteBuffer allocate(int);
+            public abstract byte get(int);
+            public abstract java.nio.ByteBuffer put(int, byte);
+        }
+
+        public abstract class java.nio.MappedByteBuffer extends java.nio.ByteBuffer {
+            public final boolean isLoaded();
+            public final java.nio.MappedByteBuffer load();
+            public final java.nio.MappedByteBuffer force();
+        }
+
+        package java.nio.channels;
+
+        public abstract class java.nio.channels.FileChannel {
+            public abstract long size() throws java.io.IOException;
+            public abstract void force(boolean) throws java.io.IOException;
+        }
+
+        package java.nio.file;
+
+        public interface java.nio.file.Path extends java.lang.Comparable<java.nio.file.Path> {
+            public abstract boolean isAbsolute();
+            public abstract java.nio.file.Path getRoot();
+            public abstract java.nio.file.Path getFileName();
+            public abstract java.nio.file.Path getParent();
+            public abstract int getNameCount();
+            public abstract java.nio.file.Path resolve(java.nio.file.Path);
+            public abstract java.nio.file.Path normalize();
+            public abstract java.nio.file.Path toAbsolutePath();
+            public abstract java.net.URI toUri();
+            public default java.io.File toFile();
+            public abstract java.lang.String toString();
+        }
+
+        package java.security;
+
+        public class java.security.SecureClassLoader extends java.lang.ClassLoader {
+        }
+
         package java.util;
 
-        public interface java.util.List<E> {
+        public final class java.util.Locale {
+            public static final java.util.Locale ROOT;
+            public static java.util.Locale getDefault();
+            public java.lang.String toLanguageTag();
+            public final java.lang.String toString();
+        }
+
+        public final class java.util.Currency { // implements java.io.Serializable {
+            public static java.util.Currency getInstance(java.lang.String);
+            // public static java.util.Currency getInstance(java.util.Locale);
+            public static java.util.Set<java.util.Currency> getAvailableCurrencies();
+            public java.lang.String getCurrencyCode();
+            public java.lang.String getSymbol();
+            public int getDefaultFractionDigits();
+            public int getNumericCode();
+            public java.lang.String getDisplayName();
+            public java.lang.String toString();
+        }
+
+        public final class java.util.UUID implements java.lang.Comparable<java.util.UUID> {
+            public java.util.UUID(long, long);
+            public static java.util.UUID randomUUID();
+            public static java.util.UUID fromString(java.lang.String);
+            public long getLeastSignificantBits();
+            public long getMostSignificantBits();
+            public int version();
+            public java.lang.String toString();
+            public int hashCode();
+            public boolean equals(java.lang.Object);
+        }
+
+        public interface java.util.Comparator<T> {
+            public abstract int compare(T, T);
+        }
+
+        public interface java.util.Iterator<E> {
+            public abstract boolean hasNext();
+            public abstract E next();
+        }
+
+        public interface java.util.Collection<E> extends java.lang.Iterable<E> {
             public abstract int size();
             public abstract boolean isEmpty();
             public abstract boolean contains(java.lang.Object);
+            public abstract java.util.Iterator<E> iterator();
+            public abstract boolean add(E);
+            public abstract boolean remove(java.lang.Object);
+            public abstract void clear();
+        }
+
+        public interface java.util.List<E> extends java.util.Collection<E> {
+            public abstract int size();
+            public abstract java.util.Iterator<E> iterator();
+            public abstract boolean isEmpty();
+            public abstract boolean contains(java.lang.Object);
             public abstract <T> T[] toArray(T[]);
             public abstract boolean add(E);
             public abstract boolean remove(java.lang.Object);
@@ -141,6 +589,16 @@ mod auto {
             public java.util.List<E> subList(int, int);
         }
 
+        public interface java.util.Set<E> extends java.util.Collection<E> {
+            public abstract int size();
+            public abstract boolean isEmpty();
+            public abstract boolean contains(java.lang.Object);
+            public abstract java.util.Iterator<E> iterator();
+            public abstract boolean add(E);
+            public abstract boolean remove(java.lang.Object);
+            public abstract void clear();
+        }
+
         public interface java.util.Map<K, V> {
             public abstract int size();
             public abstract boolean isEmpty();
@@ -151,7 +609,7 @@ mod auto {
             public abstract V remove(java.lang.Object);
             public abstract void putAll(java.util.Map<? extends K, ? extends V>);
             public abstract void clear();
-            // public abstract java.util.Set<K> keySet();
+            public abstract java.util.Set<K> keySet();
             // public abstract java.util.Collection<V> values();
             // public abstract java.util.Set<java.util.Map$Entry<K, V>> entrySet();
             public abstract boolean equals(java.lang.Object);
@@ -200,7 +658,7 @@ mod auto {
             public V remove(java.lang.Object);
             public void clear();
             public boolean containsValue(java.lang.Object);
-            // public java.util.Set<K> keySet();
+            public java.util.Set<K> keySet();
             // public java.util.Collection<V> values();
             // public java.util.Set<java.util.Map$Entry<K, V>> entrySet();
             public V getOrDefault(java.lang.Object, V);
@@ -258,6 +716,156 @@ mod auto {
             //   static {};
         }
 
+        public abstract class java.util.Calendar { // implements java.io.Serializable, java.lang.Cloneable, java.lang.Comparable<java.util.Calendar> {
+            public static java.util.Calendar getInstance();
+            // public static java.util.Calendar getInstance(java.util.TimeZone);
+            // public static java.util.Calendar getInstance(java.util.Locale);
+            // public static java.util.Calendar getInstance(java.util.TimeZone, java.util.Locale);
+            public final java.util.Date getTime();
+            public final void setTime(java.util.Date);
+            public long getTimeInMillis();
+            public void setTimeInMillis(long);
+            public int get(int);
+            // public void set(int, int);
+            // public final void set(int, int, int);
+            public java.lang.String toString();
+        }
+
+        package java.sql;
+
+        public class java.sql.Timestamp extends java.util.Date {
+            //   public java.sql.Timestamp(int, int, int, int, int, int, int);
+            public java.sql.Timestamp(long);
+            // public void setTime(long);
+            // public long getTime();
+            public static java.sql.Timestamp valueOf(java.lang.String);
+            public java.lang.String toString();
+            public int getNanos();
+            public void setNanos(int);
+            // public boolean equals(java.sql.Timestamp);
+            // public boolean before(java.sql.Timestamp);
+            // public boolean after(java.sql.Timestamp);
+            // public int compareTo(java.sql.Timestamp);
+            // public static java.sql.Timestamp valueOf(java.time.LocalDateTime);
+            // public java.time.LocalDateTime toLocalDateTime();
+            // public static java.sql.Timestamp from(java.time.Instant);
+            // public java.time.Instant toInstant();
+        }
+
+        package java.util.concurrent;
+
+        public interface java.util.concurrent.Executor {
+            public abstract void execute(java.lang.Runnable);
+        }
+
+        public interface java.util.concurrent.ExecutorService extends java.util.concurrent.Executor {
+            public abstract void shutdown();
+            public abstract boolean isTerminated();
+            public abstract boolean awaitTermination(long, java.util.concurrent.TimeUnit) throws java.lang.InterruptedException;
+        }
+
+        public class java.util.concurrent.Executors {
+            public static java.util.concurrent.ExecutorService newFixedThreadPool(int);
+        }
+
+        public final class java.util.concurrent.TimeUnit extends java.lang.Enum<java.util.concurrent.TimeUnit> {
+            public static final java.util.concurrent.TimeUnit NANOSECONDS;
+        }
+
+        public interface java.util.concurrent.ConcurrentMap<K, V> extends java.util.Map<K, V> {
+        }
+
+        public class java.util.concurrent.ConcurrentHashMap<K, V>
+            // extends java.util.AbstractMap<K, V>
+            implements java.util.concurrent.ConcurrentMap<K, V> // , java.io.Serializable
+        {
+            public java.util.concurrent.ConcurrentHashMap();
+            public int size();
+            public boolean isEmpty();
+            public V get(java.lang.Object);
+            public boolean containsKey(java.lang.Object);
+            public V put(K, V);
+            public V remove(java.lang.Object);
+            public void clear();
+            public V putIfAbsent(K, V);
+            public boolean replace(K, V, V);
+            public long mappingCount();
+        }
+
+        public class java.util.concurrent.CompletionException extends java.lang.RuntimeException {
+        }
+
+        public class java.util.concurrent.CancellationException extends java.lang.IllegalStateException {
+            public java.util.concurrent.CancellationException(java.lang.String);
+        }
+
+        public class java.util.concurrent.CompletableFuture<T> { // implements java.util.concurrent.Future<T>, java.util.concurrent.CompletionStage<T> {
+            public java.util.concurrent.CompletableFuture();
+            // public static <U> java.util.concurrent.CompletableFuture<U> completedFuture(U);
+            // public static <U> java.util.concurrent.CompletableFuture<U> failedFuture(java.lang.Throwable);
+            public boolean isDone();
+            public T get() throws java.lang.InterruptedException, java.util.concurrent.ExecutionException;
+            // public T get(long, java.util.concurrent.TimeUnit) throws java.lang.InterruptedException, java.util.concurrent.ExecutionException, java.util.concurrent.TimeoutException;
+            public T join();
+            public T getNow(T);
+            public boolean complete(T);
+            public boolean completeExceptionally(java.lang.Throwable);
+            public <U> java.util.concurrent.CompletableFuture<U> thenApply(java.util.function.Function<? super T, ? extends U>);
+            public java.util.concurrent.CompletableFuture<T> whenComplete(java.util.function.BiConsumer<? super T, ? super java.lang.Throwable>);
+            public boolean cancel(boolean);
+            public boolean isCancelled();
+            public boolean isCompletedExceptionally();
+            public java.lang.String toString();
+            public java.util.concurrent.Executor defaultExecutor();
+        }
+
+        package java.util.concurrent.atomic;
+
+        public class java.util.concurrent.atomic.AtomicInteger extends java.lang.Number { // implements java.io.Serializable {
+            public java.util.concurrent.atomic.AtomicInteger(int);
+            public final int get();
+            public final void set(int);
+            public final int getAndSet(int);
+            public final int getAndAdd(int);
+            public final int compareAndExchange(int, int);
+            public java.lang.String toString();
+        }
+
+        public class java.util.concurrent.atomic.AtomicLong extends java.lang.Number { // implements java.io.Serializable {
+            public java.util.concurrent.atomic.AtomicLong(long);
+            public final long get();
+            public final void set(long);
+            public final long getAndSet(long);
+            public final long getAndAdd(long);
+            public final long compareAndExchange(long, long);
+            public java.lang.String toString();
+        }
+
+        public class java.util.concurrent.atomic.AtomicReference<V> { // implements java.io.Serializable {
+            public java.util.concurrent.atomic.AtomicReference();
+            public final V get();
+            public final void set(V);
+            public final V getAndSet(V);
+            public final V compareAndExchange(V, V);
+            public java.lang.String toString();
+        }
+
+        package java.util.stream;
+
+        public interface java.util.stream.Stream<T> {
+            public default java.util.List<T> toList();
+        }
+
+        package java.util.function;
+
+        public interface java.util.function.Function<T, R> {
+            public abstract R apply(T);
+        }
+
+        public interface java.util.function.BiConsumer<T, U> {
+            public abstract void accept(T, U);
+        }
+
         package java.time;
 
         public final class java.time.Instant {
@@ -314,6 +922,58 @@ mod auto {
             // public int compareTo(java.lang.Object);
         }
 
+        public final class java.time.Duration {
+            public static final java.time.Duration ZERO;
+            // public static java.time.Duration ofSeconds(long);
+            public static java.time.Duration ofSeconds(long, long);
+            public static java.time.Duration ofMillis(long);
+            public static java.time.Duration ofNanos(long);
+            public long getSeconds();
+            public int getNano();
+            public boolean isNegative();
+            public boolean isZero();
+            public java.time.Duration negated();
+            public java.time.Duration plus(java.time.Duration);
+            public long toMillis();
+            public long toNanos();
+            public int compareTo(java.time.Duration);
+            public boolean equals(java.lang.Object);
+            public int hashCode();
+            public java.lang.String toString();
+        }
+
+        public final class java.time.LocalDateTime {
+            public static final java.time.LocalDateTime MIN;
+            public static final java.time.LocalDateTime MAX;
+            public static java.time.LocalDateTime of(int, int, int, int, int, int, int);
+            public static java.time.LocalDateTime ofEpochSecond(long, int, java.time.ZoneOffset);
+            public int getYear();
+            public int getMonthValue();
+            public int getDayOfMonth();
+            public int getHour();
+            public int getMinute();
+            public int getSecond();
+            public int getNano();
+            public java.time.OffsetDateTime atOffset(java.time.ZoneOffset);
+            public boolean equals(java.lang.Object);
+            public int hashCode();
+            public java.lang.String toString();
+        }
+
+        public final class java.time.OffsetDateTime {
+            public java.time.Instant toInstant();
+            public long toEpochSecond();
+            public int getNano();
+            public java.lang.String toString();
+        }
+
+        public final class java.time.ZoneOffset {
+            public static final java.time.ZoneOffset UTC;
+            public static java.time.ZoneOffset ofTotalSeconds(int);
+            public int getTotalSeconds();
+            public java.lang.String toString();
+        }
+
     }
 }
 
@@ -323,3 +983,4 @@ pub use auto::java::*;
 // Should it go somewhere outside of the JDK core classes?
 pub use crate::array::JavaArray as Array;
 pub use crate::array::JavaArrayExt as ArrayExt;
+pub use crate::array::JavaObjectArrayExt as ObjectArrayExt;
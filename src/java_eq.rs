@@ -0,0 +1,92 @@
+use std::{
+    fmt::{self, Debug, Display},
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+use crate::{java::lang::Object, jvm::JavaObjectExt, prelude::*, Global, JavaObject, Jvm};
+
+/// A [`Global`] compared with Java's `equals` and hashed with its `hashCode`, e.g. to use Java objects as the keys of
+/// a `HashMap` or `HashSet`:
+///
+/// ```
+/// # fn main() -> duchess::GlobalResult<()> {
+/// use duchess::{prelude::*, JavaEq};
+/// use std::collections::HashSet;
+///
+/// let a = "duchess".to_java().assert_not_null().global().execute()?;
+/// let b = "duchess".to_java().assert_not_null().global().execute()?;
+/// let set: HashSet<_> = [JavaEq::new(a), JavaEq::new(b)].into_iter().collect();
+/// assert_eq!(set.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// `Global` itself compares the references, which are distinct for the same object (see
+/// [`Global::ptr_eq`] to compare objects by identity).
+///
+/// `Eq` relies on the class respecting the contract of `equals` and `hashCode`, like Java's `HashMap` does.
+///
+/// # Panics
+///
+/// Comparing or hashing panics if `equals` or `hashCode` throws, or if the current thread can't be attached to the JVM.
+pub struct JavaEq<T: JavaObject>(Global<T>);
+
+impl<T: JavaObject> JavaEq<T> {
+    pub fn new(global: Global<T>) -> Self {
+        Self(global)
+    }
+
+    pub fn into_inner(self) -> Global<T> {
+        self.0
+    }
+
+    /// The object as an `Object`, whose `equals` and `hashCode` are called.
+    fn object(&self) -> &Object {
+        // SAFETY: every Java object is an `Object`
+        unsafe { Object::from_raw(self.0.as_raw()) }
+    }
+}
+
+impl<T: JavaObject> From<Global<T>> for JavaEq<T> {
+    fn from(global: Global<T>) -> Self {
+        Self(global)
+    }
+}
+
+impl<T: JavaObject> Deref for JavaEq<T> {
+    type Target = Global<T>;
+
+    fn deref(&self) -> &Global<T> {
+        &self.0
+    }
+}
+
+impl<T: JavaObject> PartialEq for JavaEq<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Jvm::with(|jvm| self.object().equals(other.object()).execute_with(jvm))
+            .unwrap_or_else(|e| panic!("unable to call equals: {e}"))
+    }
+}
+
+impl<T: JavaObject> Eq for JavaEq<T> {}
+
+impl<T: JavaObject> Hash for JavaEq<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let hash = Jvm::with(|jvm| self.object().hash_code().execute_with(jvm))
+            .unwrap_or_else(|e| panic!("unable to call hashCode: {e}"));
+        state.write_i32(hash);
+    }
+}
+
+impl<T: JavaObject> Display for JavaEq<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0.display(), f)
+    }
+}
+
+impl<T: JavaObject> Debug for JavaEq<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
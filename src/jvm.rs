@@ -1,16 +1,24 @@
 use crate::{
-    cast::{AsUpcast, TryDowncast, Upcast},
+    annotation::GetAnnotation,
+    bean::{BeanValue, GetProperty, SetProperty},
+    cast::{AsUpcast, Downcast, TryDowncast, Upcast},
+    clone::JavaClone,
+    combinators::{AndThen, IfNotNull, IntoNullable, Map, NotNullValue, Zip},
     find::find_class,
+    frame::{FrameOutput, InFrame},
     global::{GlobalOp, IntoGlobal},
-    into_rust::ToRustOp,
-    java::lang::{Class, Throwable},
+    into_rust::{DeepToRustOp, ToRustOp, TryIntoRust, TryToRustOp},
+    java::lang::{annotation::Annotation, reflect::AnnotatedElement, Class, Object, Throwable},
     link::{IntoJavaFns, JavaFunction},
     not_null::NotNull,
     plumbing::{FromRef, ToJavaImpl},
+    protobuf::ToProtoBytes,
     raw::{self, EnvPtr, JvmPtr, ObjectPtr},
+    scope,
+    serialize::Serialize,
     thread,
-    try_catch::TryCatch,
-    AsJRef, Error, Global, GlobalResult, IntoRust, Local, ToJava, TryJDeref,
+    try_catch::{CatchEnum, CatchInto, Catching, Finally, TryCatch},
+    AsJRef, Error, Global, GlobalResult, IntoRust, Local, ToJava, TryJDeref, Weak,
 };
 
 use std::{
@@ -19,6 +27,7 @@ use std::{
     ffi::{c_char, c_void, CStr},
     fmt::Display,
     panic::AssertUnwindSafe,
+    sync::Mutex,
 };
 
 use once_cell::sync::OnceCell;
@@ -28,12 +37,22 @@ use once_cell::sync::OnceCell;
 /// represent constructor or method calls, and they can be chained
 /// together.
 ///
-/// *Eventual goal:* Each call to `execute` represents a single crossing
-/// over into the JVM, so the more you can chain together your jvm-ops,
-/// the better.
+/// Each call to `execute` is fused into a single crossing over into the
+/// JVM: the thread is attached (if need be) once, and then the chained
+/// operations make their JNI calls one after the other. Chains that create
+/// more than a couple of local references also run in a local frame, which
+/// releases the intermediate results all at once rather than one by one, so
+/// the more you can chain together your jvm-ops, the better. The
+/// `count-crossings` feature counts the JNI calls to check this, see
+/// `duchess::crossings`.
 pub trait JvmOp: Copy {
     type Output<'jvm>;
 
+    /// An upper bound on the number of local references that `execute_with` creates, including the one it returns
+    /// (if any), or `usize::MAX` if unknown. See [`sum_locals`](crate::plumbing::sum_locals).
+    #[doc(hidden)]
+    const LOCALS: usize = usize::MAX;
+
     fn assert_not_null<T>(self) -> NotNull<Self>
     where
         T: JavaObject,
@@ -60,6 +79,18 @@ pub trait JvmOp: Copy {
         TryDowncast::new(self)
     }
 
+    /// Downcasts the output of this operation to `To`, like [`try_downcast`](Self::try_downcast), but failing with
+    /// [`ConversionError::UnexpectedClass`](crate::ConversionError::UnexpectedClass) if it isn't an instance of `To`,
+    /// which names the class it has and where the downcast was written.
+    #[track_caller]
+    fn downcast<To>(self) -> Downcast<Self, To>
+    where
+        for<'jvm> Self::Output<'jvm>: TryJDeref,
+        To: for<'jvm> Upcast<<Self::Output<'jvm> as TryJDeref>::Java>,
+    {
+        Downcast::new(self, std::panic::Location::caller())
+    }
+
     /// Most duchess-wrapped Java objects will automatically be able to call all
     /// methods defined on any of its super classes or interfaces it implements,
     /// but this can be used to "force" the output of the operation to be typed
@@ -82,6 +113,79 @@ pub trait JvmOp: Copy {
         GlobalOp::new(self)
     }
 
+    /// Reads the annotation of class `A` (declared as an interface extending
+    /// `java.lang.annotation.Annotation`) from the output of this operation, which is a class,
+    /// method, or field. Returns `None` if it isn't annotated with `A`. See [`crate::annotation`].
+    fn get_annotation<A>(self) -> GetAnnotation<Self, A>
+    where
+        for<'jvm> Self::Output<'jvm>: AsJRef<AnnotatedElement>,
+        A: Upcast<Annotation>,
+    {
+        GetAnnotation::new(self)
+    }
+
+    /// Creates an independent copy of the (non-null) output of this operation, e.g. to snapshot
+    /// a mutable object before handing it to another thread. Uses the copy constructor
+    /// registered for its class with [`Jvm::register_copy_constructor`], if any, and otherwise
+    /// `clone()`, which throws `CloneNotSupportedException` unless the class implements
+    /// `java.lang.Cloneable`.
+    fn java_clone(self) -> JavaClone<Self>
+    where
+        for<'jvm> Self::Output<'jvm>: TryJDeref,
+    {
+        JavaClone::new(self)
+    }
+
+    /// Serializes the output of this operation to bytes with `java.io.ObjectOutputStream`, which
+    /// throws `NotSerializableException` unless its class implements `java.io.Serializable`.
+    /// The bytes can be read back, in this or another JVM, with [`Jvm::deserialize`].
+    fn serialize(self) -> Serialize<Self>
+    where
+        for<'jvm> Self::Output<'jvm>: AsJRef<Object>,
+    {
+        Serialize::new(self)
+    }
+
+    /// Reads the JavaBean property `name` of the output of this operation, with its `getName()`
+    /// (or `isName()`) method, and converts its value into a [`BeanValue`]. See [`crate::bean`].
+    fn get_property(self, name: &str) -> GetProperty<'_, Self>
+    where
+        for<'jvm> Self::Output<'jvm>: AsJRef<Object>,
+    {
+        GetProperty::new(self, name)
+    }
+
+    /// Writes the JavaBean property `name` of the output of this operation with its
+    /// `setName(value)` method. See [`crate::bean`].
+    fn set_property<'a>(self, name: &'a str, value: &'a BeanValue) -> SetProperty<'a, Self>
+    where
+        for<'jvm> Self::Output<'jvm>: AsJRef<Object>,
+    {
+        SetProperty::new(self, name, value)
+    }
+
+    /// Encodes the Java protobuf message produced by this operation with its `toByteArray()`
+    /// method, so it can be decoded by a Rust protobuf library. The other direction is
+    /// [`Jvm::parse_proto`].
+    fn to_proto_bytes(self) -> ToProtoBytes<Self>
+    where
+        for<'jvm> Self::Output<'jvm>: TryJDeref,
+    {
+        ToProtoBytes::new(self)
+    }
+
+    /// Decodes the Java protobuf message produced by this operation into the Rust message `M`,
+    /// through the bytes of [`to_proto_bytes`](Self::to_proto_bytes). The other direction is
+    /// [`Jvm::prost_to_java`].
+    #[cfg(feature = "prost")]
+    fn to_prost<M>(self) -> crate::protobuf::ToProst<Self, M>
+    where
+        for<'jvm> Self::Output<'jvm>: TryJDeref,
+        M: prost::Message + Default,
+    {
+        crate::protobuf::ToProst::new(self)
+    }
+
     fn catch<J>(self) -> TryCatch<Self, J>
     where
         J: Upcast<Throwable>,
@@ -89,6 +193,140 @@ pub trait JvmOp: Copy {
         TryCatch::new(self)
     }
 
+    /// Handles the exceptions thrown by this operation with catch arms, which are added with
+    /// [`Catching::catch`] and tried in order, like the `catch` blocks of a Java `try` statement:
+    ///
+    /// ```ignore
+    /// let value = map
+    ///     .get(key)
+    ///     .catching()
+    ///     .catch::<ClassCastException, _>(|_jvm, _e| Ok(None))
+    ///     .catch::<NullPointerException, _>(|_jvm, _e| Ok(None))
+    ///     .execute()?;
+    /// ```
+    fn catching(self) -> Catching<Self, ()> {
+        Catching::new(self)
+    }
+
+    /// Catches the exceptions thrown by this operation that are instances of the variants of `E`, an enum declared with
+    /// [`catch_enum!`](crate::catch_enum), which is the error of the output. The variants are tried in order, so the
+    /// variant that is returned tells which class matched first; other exceptions are rethrown:
+    ///
+    /// ```ignore
+    /// duchess::catch_enum! {
+    ///     enum ReadError {
+    ///         NotFound(java::io::FileNotFoundException),
+    ///         Io(java::io::IOException),
+    ///     }
+    /// }
+    ///
+    /// match file.read().catch_into::<ReadError>().execute()? {
+    ///     Ok(bytes) => ...,
+    ///     Err(ReadError::NotFound(_)) => ...,
+    ///     Err(ReadError::Io(e)) => ...,
+    /// }
+    /// ```
+    fn catch_into<E>(self) -> CatchInto<Self, E>
+    where
+        E: CatchEnum,
+    {
+        CatchInto::new(self)
+    }
+
+    /// Runs `op` once this operation completes, whether it succeeded or not, like a Java
+    /// `finally` block. The output of `op` is discarded, but if it fails, its error is returned
+    /// instead of the outcome of this operation.
+    fn finally<F>(self, op: F) -> Finally<Self, F>
+    where
+        F: JvmOp,
+    {
+        Finally::new(self, op)
+    }
+
+    /// Transforms the output of this operation with `f`, which is given the JVM too (e.g. to
+    /// look at the output through [`Jvm::local`]), and whose result can't be tied to the JVM.
+    /// Java calls that can fail are better made with [`and_then`][`Self::and_then`]:
+    ///
+    /// ```
+    /// # use duchess::{java, prelude::*};
+    /// let doubled = "four"
+    ///     .to_java::<java::lang::String>()
+    ///     .assert_not_null()
+    ///     .length()
+    ///     .map(|len, _jvm| len * 2)
+    ///     .execute()?;
+    /// assert_eq!(doubled, 8);
+    /// # duchess::GlobalResult::Ok(())
+    /// ```
+    fn map<F, R>(self, f: F) -> Map<Self, F>
+    where
+        F: Copy + for<'jvm> FnOnce(Self::Output<'jvm>, &mut Jvm<'jvm>) -> R,
+    {
+        Map::new(self, f)
+    }
+
+    /// Runs the operation that `f` makes from the output of this one, for Java calls that
+    /// depend on it. Since operations are `Copy`, the returned operation can only capture
+    /// `Copy` data from the output (e.g. a number), and references from outside `f`:
+    ///
+    /// ```
+    /// # use duchess::{java, prelude::*};
+    /// let list: Vec<String> = vec!["a".into(), "b".into(), "c".into()];
+    /// let list = list.to_java::<java::util::List<java::lang::String>>().assert_not_null().global().execute()?;
+    /// let last: String = list
+    ///     .size()
+    ///     .and_then(|size| list.get(size - 1))
+    ///     .assert_not_null()
+    ///     .to_rust()
+    ///     .execute()?;
+    /// assert_eq!(last, "c");
+    /// # duchess::GlobalResult::Ok(())
+    /// ```
+    fn and_then<F, Next>(self, f: F) -> AndThen<Self, F, Next>
+    where
+        F: Copy + for<'jvm> FnOnce(Self::Output<'jvm>) -> Next,
+        Next: JvmOp,
+    {
+        AndThen::new(self, f)
+    }
+
+    /// Runs the operation that `f` makes from the output of this one unless it is null, in
+    /// which case the output is `None`, like Kotlin's `?.`. The closure is called right away,
+    /// with an operation standing for the (non-null) output, so that its methods can be chained:
+    ///
+    /// ```
+    /// # use duchess::{java, prelude::*};
+    /// let thread = java::lang::Thread::current_thread();
+    /// let name: Option<String> = thread
+    ///     .if_not_null(|thread| thread.get_name())
+    ///     .to_rust()
+    ///     .execute()?;
+    /// assert!(name.is_some());
+    /// # duchess::GlobalResult::Ok(())
+    /// ```
+    ///
+    /// If the operation made by `f` has an `Option` as output, it is passed on as is, so calls
+    /// can be chained like `a?.b()?.c()`. Other outputs are wrapped in `Some`.
+    fn if_not_null<T, F, Next>(self, f: F) -> IfNotNull<Self, Next>
+    where
+        T: JavaObject,
+        for<'jvm> Self: JvmOp<Output<'jvm> = Option<Local<'jvm, T>>>,
+        F: FnOnce(NotNullValue<T>) -> Next,
+        Next: JvmOp,
+        for<'jvm> Next::Output<'jvm>: IntoNullable,
+    {
+        IfNotNull::new(self, f)
+    }
+
+    /// Runs this operation and then `other`, pairing their outputs, so that both run in one
+    /// [`execute`][`Self::execute`]. Stops at the first one that fails.
+    fn zip<Other>(self, other: Other) -> Zip<Self, Other>
+    where
+        Other: JvmOp,
+    {
+        Zip::new(self, other)
+    }
+
     /// Given a JVM op that returns some Java type, convert it to its Rust equivalent
     /// (e.g., from a Java String to a Rust string).
     fn to_rust<R>(self) -> ToRustOp<Self, R>
@@ -98,16 +336,77 @@ pub trait JvmOp: Copy {
         ToRustOp::new(self)
     }
 
+    /// Runs this operation in its own local reference frame (see [`Jvm::with_frame`]), so that
+    /// the local references it creates along the way are all released once it completes. The
+    /// result, which may be a local reference, is moved to the enclosing frame.
+    fn in_frame(self) -> InFrame<Self>
+    where
+        for<'jvm> Self::Output<'jvm>: FrameOutput<'jvm>,
+    {
+        InFrame::new(self)
+    }
+
+    /// Like [`to_rust()`][`Self::to_rust`], but intended for converting large object graphs
+    /// (e.g., lists of nested `#[derive(ToRust)]` structs) in one go. The conversion runs in its
+    /// own local reference frame, and each element of the lists, maps and arrays in the graph is
+    /// converted in a frame of its own, so the local references are reclaimed element by element.
+    /// Lookups shared by the elements, like those of [registered conversions](crate::conversion),
+    /// are made once for the whole conversion.
+    fn to_rust_deep<R>(self) -> DeepToRustOp<Self, R>
+    where
+        for<'jvm> Self::Output<'jvm>: IntoRust<R>,
+    {
+        DeepToRustOp::new(self)
+    }
+
+    /// Like [`to_rust()`][`Self::to_rust`], but for conversions that can fail because of
+    /// the value being converted, which are reported as an inner `Err(ConversionError)`
+    /// rather than a JVM error.
+    fn try_to_rust<R>(self) -> TryToRustOp<Self, R>
+    where
+        for<'jvm> Self::Output<'jvm>: TryIntoRust<R>,
+    {
+        TryToRustOp::new(self)
+    }
+
     /// Execute the jvm op, starting a JVM instance if necessary.
     /// To use this method, the result type cannot be tied to the JVM.
     /// Typically this is achieved by a call to [`to_rust()`][`Self::to_rust`],
     /// but if you wish to hold on to a reference to a JVM object,
     /// you can use [`global()`][`Self::global`] to create a global reference.
+    ///
+    /// The execution is reported to the observer set with
+    /// [`set_execution_observer`](crate::observe::set_execution_observer), if any.
     fn execute<R>(self) -> crate::GlobalResult<R>
     where
         for<'jvm> Self: JvmOp<Output<'jvm> = R>,
     {
-        Jvm::with(|jvm| self.execute_with(jvm))
+        crate::observe::observe::<Self, R>(|| {
+            // `R` holds no local refs, so all those of the op are intermediate results. Releasing them along with a
+            // frame saves a `DeleteLocalRef` each, which pays for pushing and popping the frame past two of them.
+            if Self::LOCALS <= 2 {
+                return Jvm::with(|jvm| self.execute_with(jvm));
+            }
+            // SAFETY: `R` doesn't depend on `'jvm`, so it holds no local refs, and the op (which is `Copy`) can't own
+            // locals of enclosing frames to drop
+            Jvm::with(|jvm| unsafe { jvm.with_fused_frame(|jvm| self.execute_with(jvm)) })
+        })
+    }
+
+    /// Execute the jvm op within an existing `jvm` scope, returning a reference to the
+    /// resulting object that stays valid until that scope ends (e.g., the end of the
+    /// enclosing [`Jvm::with`] call) instead of an owned [`Local`].
+    ///
+    /// This is intended for read-only inspection paths, e.g. walking a chain of getters,
+    /// where the intermediate objects never escape the scope. Since the references are only
+    /// released when the scope ends, avoid this in long-running loops.
+    fn execute_borrowed<'jvm, T>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, &'jvm T>
+    where
+        T: JavaObject,
+        for<'a> Self: JvmOp<Output<'a> = Local<'a, T>>,
+    {
+        let local = self.execute_with(jvm)?;
+        Ok(jvm.borrow_local(local))
     }
 
     fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>>;
@@ -120,6 +419,9 @@ impl IsVoid for () {}
 
 static GLOBAL_JVM: OnceCell<JvmPtr> = OnceCell::new();
 
+/// Held while creating or looking up JVMs, which must not race (see [`raw::existing_jvms`]).
+static JVM_LOOKUP: Mutex<()> = Mutex::new(());
+
 fn get_or_default_init_jvm() -> crate::GlobalResult<JvmPtr> {
     match GLOBAL_JVM.get() {
         Some(jvm) => Ok(*jvm),
@@ -150,20 +452,24 @@ where
 {
     init_jvm_from_native_function(env);
     let _callback_guard = thread::attach_from_jni_callback(env);
+    let delete_scope = scope::enter(env);
+    let mut jvm = Jvm(env, delete_scope.depth());
 
     let result = match std::panic::catch_unwind(AssertUnwindSafe(|| op())) {
         Ok(result) => {
-            let mut jvm = Jvm(env);
             let obj = result.to_java().execute_with(&mut jvm);
             match obj {
                 Ok(Some(p)) => p.into_raw().as_ptr(),
                 Ok(None) => std::ptr::null_mut(),
-                Err(e) => todo!("convert from exception `{e:?}` into java exception"),
+                Err(e) => {
+                    rust_error_to_java_exception(&mut jvm, e);
+                    std::ptr::null_mut()
+                }
             }
         }
 
         Err(e) => {
-            rust_panic_to_java_exception(e);
+            rust_panic_to_java_exception(&mut jvm, e);
             std::ptr::null_mut()
         }
     };
@@ -186,11 +492,12 @@ where
 {
     init_jvm_from_native_function(env);
     let _callback_guard = thread::attach_from_jni_callback(env);
+    let delete_scope = scope::enter(env);
 
     let result = match std::panic::catch_unwind(AssertUnwindSafe(|| op())) {
         Ok(result) => result,
         Err(e) => {
-            rust_panic_to_java_exception(e);
+            rust_panic_to_java_exception(&mut Jvm(env, delete_scope.depth()), e);
             R::default()
         }
     };
@@ -198,6 +505,65 @@ where
     result
 }
 
+/// Like [`native_function_returning_object`], but for native functions defined by duchess
+/// itself: `op` is given the [`Jvm`] and directly returns the object to hand back to the JVM.
+///
+/// # Safety condition
+///
+/// Must be invoked as the entire body of a JNI native function, with
+/// `env` being the `EnvPtr` argument provided.
+pub(crate) unsafe fn native_function_with_jvm<'env>(
+    env: EnvPtr<'env>,
+    op: impl FnOnce(&mut Jvm<'env>) -> crate::Result<'env, Option<Local<'env, Object>>>,
+) -> jni_sys::jobject {
+    init_jvm_from_native_function(env);
+    let _callback_guard = thread::attach_from_jni_callback(env);
+    let delete_scope = scope::enter(env);
+    let mut jvm = Jvm(env, delete_scope.depth());
+
+    match std::panic::catch_unwind(AssertUnwindSafe(|| op(&mut jvm))) {
+        Ok(Ok(Some(obj))) => obj.into_raw().as_ptr(),
+        Ok(Ok(None)) => std::ptr::null_mut(),
+        Ok(Err(e)) => {
+            rust_error_to_java_exception(&mut jvm, e);
+            std::ptr::null_mut()
+        }
+        Err(e) => {
+            rust_panic_to_java_exception(&mut jvm, e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Like [`native_function_with_jvm`], but for native functions returning a scalar (or nothing):
+/// `R::default()` is handed back to the JVM if `op` fails.
+///
+/// # Safety condition
+///
+/// Must be invoked as the entire body of a JNI native function, with
+/// `env` being the `EnvPtr` argument provided.
+pub(crate) unsafe fn native_scalar_function_with_jvm<'env, R: Default>(
+    env: EnvPtr<'env>,
+    op: impl FnOnce(&mut Jvm<'env>) -> crate::Result<'env, R>,
+) -> R {
+    init_jvm_from_native_function(env);
+    let _callback_guard = thread::attach_from_jni_callback(env);
+    let delete_scope = scope::enter(env);
+    let mut jvm = Jvm(env, delete_scope.depth());
+
+    match std::panic::catch_unwind(AssertUnwindSafe(|| op(&mut jvm))) {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            rust_error_to_java_exception(&mut jvm, e);
+            R::default()
+        }
+        Err(e) => {
+            rust_panic_to_java_exception(&mut jvm, e);
+            R::default()
+        }
+    }
+}
+
 /// Invoked from inside a JNI native function when it is called by the JVM.
 /// If `GLOBAL_JVM` is not yet set, initializes it to use the provided `jvm`.
 /// Otherwise, does nothing.
@@ -205,7 +571,7 @@ where
 /// # Safety condition
 ///
 /// Must be invoked as the first thing from inside a JNI native function.
-unsafe fn init_jvm_from_native_function(env: EnvPtr<'_>) -> Jvm<'_> {
+unsafe fn init_jvm_from_native_function(env: EnvPtr<'_>) {
     // If the JVM is the master process and it invokes Rust code,
     // the global JVM environment may not yet have been initialized.
     //
@@ -215,11 +581,83 @@ unsafe fn init_jvm_from_native_function(env: EnvPtr<'_>) -> Jvm<'_> {
     let jvm = env.jvm_ptr().unwrap();
     let global_jvm = GLOBAL_JVM.get_or_init(|| jvm);
     assert_eq!(jvm, *global_jvm, "multiple JVM pointers in active use");
-    Jvm(env)
 }
 
-fn rust_panic_to_java_exception(_panic: Box<dyn Any + Send + 'static>) {
-    todo!("rust_panic_to_java_exception")
+/// Converts a panic that unwound out of a native function into a pending
+/// `java.lang.RuntimeException`, so that the panic does not unwind into the JVM.
+fn rust_panic_to_java_exception(jvm: &mut Jvm<'_>, panic: Box<dyn Any + Send + 'static>) {
+    let message = if let Some(s) = panic.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = panic.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        "Box<dyn Any>"
+    };
+    throw_runtime_exception(jvm, &format!("Rust panic: {message}"));
+}
+
+/// Converts an error produced while returning from a native function into a pending
+/// Java exception. Exceptions thrown by Java code are rethrown as-is; other errors
+/// become a `java.lang.RuntimeException`.
+fn rust_error_to_java_exception<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    error: crate::Error<Local<'jvm, Throwable>>,
+) {
+    match error {
+        Error::Thrown(throwable) | Error::ThrownWithBacktrace(throwable, _) => unsafe {
+            // SAFETY: `throwable` is a live local ref to a Throwable.
+            let code = jvm.env().invoke_unchecked(
+                |env| env.Throw,
+                |env, f| f(env, throwable.as_raw().as_ptr()),
+            );
+            if code != jni_sys::JNI_OK {
+                tracing::warn!(code, "failed to rethrow Java exception");
+            }
+        },
+        error => throw_runtime_exception(jvm, &error.to_string()),
+    }
+}
+
+/// Makes a new `java.lang.RuntimeException` with the given message the pending exception.
+fn throw_runtime_exception(jvm: &mut Jvm<'_>, message: &str) {
+    // Any exception thrown while creating the exception (e.g., OutOfMemoryError) is left pending instead.
+    let class = match find_class(jvm, c"java/lang/RuntimeException") {
+        Ok(class) => class,
+        Err(err) => {
+            tracing::warn!(?err, "unable to find RuntimeException to report Rust error");
+            return;
+        }
+    };
+
+    // Java expects modified UTF-8, which never contains a literal nul byte.
+    let mut message = cesu8::to_java_cesu8(message).into_owned();
+    message.push(0);
+
+    // SAFETY: `class` is a live local ref to a Throwable subclass and `message` is nul-terminated.
+    let code = unsafe {
+        jvm.env().invoke_unchecked(
+            |env| env.ThrowNew,
+            |env, f| {
+                f(
+                    env,
+                    class.as_raw().as_ptr(),
+                    message.as_ptr() as *const c_char,
+                )
+            },
+        )
+    };
+    if code != jni_sys::JNI_OK {
+        tracing::warn!(code, "failed to throw RuntimeException for Rust error");
+    }
+}
+
+/// Sets `GLOBAL_JVM` to `jvm`, unless it is already set to some other JVM.
+fn init_from_jvm_ptr(jvm: JvmPtr) -> crate::GlobalResult<()> {
+    if *GLOBAL_JVM.get_or_init(|| jvm) == jvm {
+        Ok(())
+    } else {
+        Err(Error::JvmAlreadyExists)
+    }
 }
 
 /// Get the global [`JvmPtr`] assuming that the JVM has already been initialized. Expected to be used with values
@@ -232,27 +670,113 @@ pub(crate) fn unwrap_global_jvm() -> JvmPtr {
     *GLOBAL_JVM.get().expect("JVM can't be unset")
 }
 
-pub struct Jvm<'jvm>(EnvPtr<'jvm>);
+/// Get the global [`JvmPtr`], if the JVM has been initialized.
+pub(crate) fn try_global_jvm() -> Option<JvmPtr> {
+    GLOBAL_JVM.get().copied()
+}
+
+/// Whether `a` and `b` are the same Java object.
+pub(crate) fn is_same_object(jvm: &mut Jvm<'_>, a: &impl JavaObject, b: &impl JavaObject) -> bool {
+    // SAFETY: both are live references
+    unsafe {
+        jvm.env().invoke_unchecked(
+            |env| env.IsSameObject,
+            |env, f| f(env, a.as_raw().as_ptr(), b.as_raw().as_ptr()),
+        ) == jni_sys::JNI_TRUE
+    }
+}
+
+/// The JNI env of the current thread, along with the depth of the duchess scope (see [`scope`]) that it was
+/// created for, which is where [`Jvm::borrow_local`] keeps references.
+pub struct Jvm<'jvm>(pub(crate) EnvPtr<'jvm>, pub(crate) usize);
 
 impl<'jvm> Jvm<'jvm> {
     pub fn builder() -> JvmBuilder {
         JvmBuilder::new()
     }
 
+    /// Configures duchess to use a JVM that was created elsewhere, e.g., the JVM that
+    /// loaded this crate as a native library. Intended to be called from `JNI_OnLoad`;
+    /// subsequent calls to [`Jvm::with`] will attach to this JVM rather than launching one.
+    ///
+    /// Returns [`Error::JvmAlreadyExists`] if duchess is already using a different JVM.
+    /// Calling this with the JVM that duchess is already using is a no-op.
+    ///
+    /// # Safety
+    ///
+    /// `vm` must be a valid `JavaVM` pointer that remains live for as long as duchess is used.
+    pub unsafe fn init_from_raw(vm: *mut jni_sys::JavaVM) -> crate::GlobalResult<()> {
+        let jvm = JvmPtr::new(vm)
+            .ok_or_else(|| Error::JvmInternal("null `JavaVM` pointer".to_string()))?;
+        init_from_jvm_ptr(jvm)
+    }
+
+    /// Like [`Jvm::init_from_raw`], but takes the `JNIEnv` pointer of the current thread
+    /// and looks up the JVM that it belongs to.
+    ///
+    /// # Safety
+    ///
+    /// `env` must be a valid `JNIEnv` pointer for the current thread.
+    pub unsafe fn init_from_env(env: *mut jni_sys::JNIEnv) -> crate::GlobalResult<()> {
+        let env = EnvPtr::new(env)
+            .ok_or_else(|| Error::JvmInternal("null `JNIEnv` pointer".to_string()))?;
+        let jvm = env
+            .jvm_ptr()
+            .map_err(|()| Error::JvmInternal("GetJavaVM failed".to_string()))?;
+        init_from_jvm_ptr(jvm)
+    }
+
+    /// Lists the JVMs of the process, whether duchess launched them or not. Duchess uses a single JVM, so if there are
+    /// several (and duchess isn't using one yet), [`Jvm::with`] fails with [`Error::MultipleJvms`] until one of them
+    /// is [selected](ExistingJvm::select).
+    pub fn existing_jvms() -> crate::GlobalResult<Vec<ExistingJvm>> {
+        let _lookup = JVM_LOOKUP.lock().unwrap_or_else(|e| e.into_inner());
+        // SAFETY: we're behind the JVM_LOOKUP lock and we won't race with other threads creating or finding an
+        // existing JVM.
+        let jvms = unsafe { raw::existing_jvms() }?;
+        Ok(jvms.into_iter().map(ExistingJvm).collect())
+    }
+
+    /// Attaches the current thread to the JVM until it exits, or until [`crate::detach_thread`] is called, so that
+    /// using the JVM from it doesn't attach and detach it each time.
     pub fn attach_thread_permanently() -> crate::GlobalResult<()> {
-        thread::attach_permanently(get_or_default_init_jvm()?)?;
+        thread::attach_permanently(get_or_default_init_jvm()?, false)?;
         Ok(())
     }
 
+    /// Like [`Jvm::attach_thread_permanently`], but attaches the current thread as a daemon thread, which doesn't
+    /// keep the JVM from shutting down (e.g. once the `main` method returns, when Java is the main program). Meant for
+    /// long-lived Rust worker threads.
+    ///
+    /// Has no effect if the thread is attached already, whether as a daemon or not. In particular, the thread that
+    /// launches the JVM is attached as its main thread, which isn't a daemon.
+    pub fn attach_thread_permanently_as_daemon() -> crate::GlobalResult<()> {
+        thread::attach_permanently(get_or_default_init_jvm()?, true)?;
+        Ok(())
+    }
+
+    #[inline]
     pub fn with<R>(
         op: impl for<'a> FnOnce(&mut Jvm<'a>) -> crate::Result<'a, R>,
     ) -> crate::GlobalResult<R> {
-        let jvm = get_or_default_init_jvm()?;
-        // SAFTEY: we won't deinitialize the JVM while the guard is live
-        let mut guard = unsafe { thread::attach(jvm)? };
-
-        let mut jvm = Jvm(guard.env());
-        op(&mut jvm).map_err(|e| e.into_global(&mut jvm))
+        crate::array::check_not_in_critical_section()?;
+        crate::shutdown::check_accepting()?;
+        // SAFTEY: we won't deinitialize the JVM while the guard is live. The JVM is only looked up (and launched, if
+        // need be) when the thread isn't attached yet, so that calls on permanently attached threads stay cheap.
+        let mut guard = unsafe { thread::attach(get_or_default_init_jvm)? };
+        let env = guard.env();
+        // SAFETY: the scope is dropped before the guard
+        let delete_scope = unsafe { scope::enter(env) };
+
+        let mut jvm = Jvm(env, delete_scope.depth());
+        op(&mut jvm).map_err(|e| {
+            let e = if crate::config::capture_backtraces() {
+                e.with_backtrace(&mut jvm)
+            } else {
+                e
+            };
+            e.into_global(&mut jvm)
+        })
     }
 
     pub fn local<R>(&mut self, r: &R) -> Local<'jvm, R>
@@ -269,20 +793,53 @@ impl<'jvm> Jvm<'jvm> {
         Global::new(self.0, r)
     }
 
+    /// Creates a weak global reference to `r`, which does not keep it from being garbage collected.
+    pub fn weak<R>(&mut self, r: &R) -> Weak<R>
+    where
+        R: JavaObject,
+    {
+        Weak::new(self.0, r)
+    }
+
+    /// Converts `local` into a reference that stays valid until the end of this `Jvm` scope.
+    /// See [`JvmOp::execute_borrowed`].
+    pub fn borrow_local<R>(&mut self, local: Local<'jvm, R>) -> &'jvm R
+    where
+        R: JavaObject,
+    {
+        // SAFETY: ownership of the local ref is handed to the current scope, which keeps it (or a global ref replacing
+        // it) live until this `Jvm`'s scope ends, or leaked, which keeps it live until the JNI frame ends. Either way,
+        // it stays live throughout `'jvm`.
+        unsafe {
+            let obj = local.into_raw();
+            match scope::keep_until_scope_end(self.0, self.1, obj) {
+                Some(kept) => kept.as_ref(),
+                None => {
+                    tracing::warn!("no active duchess scope, leaking borrowed local ref");
+                    obj.as_ref()
+                }
+            }
+        }
+    }
+
     /// Plumbing method that should only be used by generated and internal code.
     #[doc(hidden)]
     pub fn env(&self) -> EnvPtr<'jvm> {
         self.0
     }
 
-    fn register_native_methods(
-        &mut self,
-        java_functions: &[JavaFunction],
-    ) -> crate::Result<'jvm, ()> {
+    /// Registers implementations of Java native methods with the JVM via `RegisterNatives`.
+    /// Accepts either a single function (`foo::java_fn()`) or a suite (`Vec<JavaFunction>`).
+    ///
+    /// When duchess launches the JVM this is done for you by [`JvmBuilder::link`]. When the
+    /// JVM is the main process, call this from `JNI_OnLoad` (see [`Jvm::init_from_raw`]) or
+    /// rely on the JVM finding the `#[no_mangle]` functions in your library by name.
+    pub fn register_natives(&mut self, fns: impl IntoJavaFns) -> crate::Result<'jvm, ()> {
+        let java_functions = fns.into_java_fns();
         let mut sorted_by_class: HashMap<Local<'_, Class>, Vec<jni_sys::JNINativeMethod>> =
             HashMap::default();
 
-        for java_function in java_functions {
+        for java_function in &java_functions {
             let class = (java_function.class_fn)(self)?;
             sorted_by_class
                 .entry(class)
@@ -305,11 +862,48 @@ impl<'jvm> Jvm<'jvm> {
     }
 }
 
+/// A JVM of the process, as listed by [`Jvm::existing_jvms`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ExistingJvm(JvmPtr);
+
+impl ExistingJvm {
+    /// The `JavaVM` pointer of this JVM, e.g. to tell the JVMs apart with JNI calls.
+    pub fn as_raw(self) -> *mut jni_sys::JavaVM {
+        self.0.as_ptr()
+    }
+
+    /// Whether duchess uses this JVM.
+    pub fn is_selected(self) -> bool {
+        try_global_jvm() == Some(self.0)
+    }
+
+    /// Makes duchess use this JVM, like [`Jvm::init_from_raw`].
+    ///
+    /// Returns [`Error::JvmAlreadyExists`] if duchess is already using a different JVM.
+    pub fn select(self) -> crate::GlobalResult<()> {
+        init_from_jvm_ptr(self.0)
+    }
+}
+
+/// The events that the JVM reports on its standard output, see [`JvmBuilder::verbose`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Verbose {
+    /// Each class that is loaded (`-verbose:class`).
+    Class,
+    /// Each garbage collection (`-verbose:gc`).
+    Gc,
+    /// Each use of native methods and other JNI activity (`-verbose:jni`).
+    Jni,
+    /// Each module that is resolved (`-verbose:module`).
+    Module,
+}
+
 pub struct JvmBuilder {
     options: Vec<String>,
     #[cfg(feature = "dylibjvm")]
     libjvm_path: Option<std::path::PathBuf>,
     java_functions: Vec<JavaFunction>,
+    report_native_memory: bool,
 }
 
 impl JvmBuilder {
@@ -319,6 +913,7 @@ impl JvmBuilder {
             #[cfg(feature = "dylibjvm")]
             libjvm_path: None,
             java_functions: vec![],
+            report_native_memory: false,
         };
 
         if cfg!(debug_assertions) {
@@ -340,6 +935,65 @@ impl JvmBuilder {
         self
     }
 
+    /// Enables assertions (`-ea`) in all classes except the system classes, which are enabled with
+    /// [`JvmBuilder::enable_system_assertions`].
+    pub fn enable_assertions(self) -> Self {
+        self.custom("-ea")
+    }
+
+    /// Enables assertions in the classes of `package` (e.g. `com.example`) and its subpackages
+    /// (`-ea:com.example...`). Options for packages and classes apply in the order they are added,
+    /// so a later [`JvmBuilder::disable_assertions_in_package`] can carve out a subpackage.
+    pub fn enable_assertions_in_package(self, package: impl Display) -> Self {
+        self.custom(format!("-ea:{package}..."))
+    }
+
+    /// Enables assertions in the class named `class` (e.g. `com.example.Foo`), with `-ea:com.example.Foo`.
+    pub fn enable_assertions_in_class(self, class: impl Display) -> Self {
+        self.custom(format!("-ea:{class}"))
+    }
+
+    /// Disables assertions in the classes of `package` and its subpackages (`-da:com.example...`),
+    /// see [`JvmBuilder::enable_assertions_in_package`].
+    pub fn disable_assertions_in_package(self, package: impl Display) -> Self {
+        self.custom(format!("-da:{package}..."))
+    }
+
+    /// Disables assertions in the class named `class`, with `-da:com.example.Foo`.
+    pub fn disable_assertions_in_class(self, class: impl Display) -> Self {
+        self.custom(format!("-da:{class}"))
+    }
+
+    /// Enables assertions in the system classes (`-esa`).
+    pub fn enable_system_assertions(self) -> Self {
+        self.custom("-esa")
+    }
+
+    /// Makes the JVM report the events of `kind` on its standard output (`-verbose:class`, `-verbose:gc`, ...).
+    /// Call it once for each kind to report.
+    pub fn verbose(self, kind: Verbose) -> Self {
+        let kind = match kind {
+            Verbose::Class => "class",
+            Verbose::Gc => "gc",
+            Verbose::Jni => "jni",
+            Verbose::Module => "module",
+        };
+        self.custom(format!("-verbose:{kind}"))
+    }
+
+    /// Sets the JVM's limit on direct memory (`-XX:MaxDirectMemorySize`), which includes the memory that duchess
+    /// reports with [`JvmBuilder::report_native_memory`].
+    pub fn max_direct_memory_size(self, bytes: u64) -> Self {
+        self.custom(format!("-XX:MaxDirectMemorySize={bytes}"))
+    }
+
+    /// Reports the native memory that duchess pins for Java objects to the JVM as direct memory, see
+    /// [`crate::memory`]. Takes effect when the JVM is launched (or found to exist already).
+    pub fn report_native_memory(mut self) -> Self {
+        self.report_native_memory = true;
+        self
+    }
+
     pub fn link(mut self, fns: impl IntoJavaFns) -> Self {
         self.java_functions.extend(fns.into_java_fns());
         self
@@ -353,6 +1007,10 @@ impl JvmBuilder {
 
     /// Launch a new JVM, returning [`Error::JvmAlreadyExists`] if one already exists.
     pub fn try_launch(self) -> GlobalResult<()> {
+        if self.report_native_memory {
+            crate::memory::report_to_jvm(true);
+        }
+
         #[cfg(feature = "dylibjvm")]
         if let Some(path) = self.libjvm_path {
             crate::libjvm::libjvm_or_load_at(&path)?;
@@ -360,7 +1018,8 @@ impl JvmBuilder {
 
         let mut already_exists = true;
         GLOBAL_JVM.get_or_try_init(|| {
-            // SAFETY: we're behind the GLOBAL_JVM lock and we won't race with other threads creating or finding an
+            let _lookup = JVM_LOOKUP.lock().unwrap_or_else(|e| e.into_inner());
+            // SAFETY: we're behind the JVM_LOOKUP lock and we won't race with other threads creating or finding an
             // existing JVM.
             let jvm = unsafe { raw::try_create_jvm(self.options.into_iter()) }?;
             already_exists = false;
@@ -371,7 +1030,7 @@ impl JvmBuilder {
             Err(Error::JvmAlreadyExists)
         } else {
             if !self.java_functions.is_empty() {
-                Jvm::with(|jvm| jvm.register_native_methods(&self.java_functions))?;
+                Jvm::with(|jvm| jvm.register_natives(self.java_functions))?;
             }
 
             Ok(())
@@ -385,8 +1044,9 @@ impl JvmBuilder {
                 // GLOBAL_JVM, or (2) the JVM was created by some non-duchess code and we'll now need to look it up with
                 // the existing_jvm() call.
                 GLOBAL_JVM.get_or_try_init(|| {
-                    // SAFETY: we're behind the GLOBAL_JVM lock and we won't race with other threads creating or finding
-                    // an existing JVM.
+                    let _lookup = JVM_LOOKUP.lock().unwrap_or_else(|e| e.into_inner());
+                    // SAFETY: we're behind the JVM_LOOKUP lock and we won't race with other threads creating or
+                    // finding an existing JVM.
                     GlobalResult::Ok(
                         unsafe { raw::existing_jvm() }?.expect("JVM should already exist"),
                     )
@@ -417,7 +1077,8 @@ impl JvmBuilder {
 /// pub struct BigDecimal {
 ///     _private: (), // prevent construction
 /// }
-/// unsafe impl JavaObject for BigDecimal {}
+/// unsafe impl JavaObject for BigDecimal { /* ... */ }
+/// unsafe impl JavaType for BigDecimal { /* ... */ }
 /// ```
 pub unsafe trait JavaObject: 'static + Sized + JavaType + JavaView {
     // XX: can't be put on extension trait nor define a default because we want to cache the resolved
@@ -494,15 +1155,6 @@ pub unsafe trait JavaType: 'static {
     fn array_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Class>>;
 }
 
-unsafe impl<T: JavaObject> JavaType for T {
-    fn array_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Class>> {
-        T::class(jvm)?
-            .array_type()
-            .assert_not_null()
-            .execute_with(jvm)
-    }
-}
-
 pub trait JavaScalar: JavaType + Default {}
 
 macro_rules! scalar {
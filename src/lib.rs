@@ -1,59 +1,173 @@
 //! Experiments with Java-Rust interop.
 
 mod array;
+mod big_integer;
+mod boxing;
 mod cast;
+#[cfg(all(feature = "checked-env", debug_assertions))]
+mod checked_env;
+mod classpath;
+mod clone;
+mod code_point;
+mod combinators;
+mod config;
+mod descriptor;
 mod error;
+mod executor;
 mod find;
+mod frame;
 mod from_ref;
+mod frozen;
 mod global;
 mod into_rust;
+mod iter;
+mod java_eq;
 mod jvm;
 mod libjvm;
 mod link;
+mod net;
 mod not_null;
 mod ops;
+mod path;
+mod poll_loop;
+mod protobuf;
+mod proxy;
 mod raw;
 mod ref_;
 mod refs;
+mod scope;
+mod serialize;
+mod shutdown;
+mod stack_trace;
 mod str;
+mod system;
 mod thread;
+mod time;
 mod to_java;
 mod try_catch;
+mod unsigned;
+#[cfg(feature = "uuid")]
+mod uuid;
 
 /// Contains reusable declarations for classes distributed by the JDK under the `java.*` packages.
 pub mod java;
 
+pub mod annotation;
+
+pub mod bean;
+
+pub mod call;
+
+pub mod classes;
+
+pub mod compile;
+
+pub mod concurrent;
+
+pub mod direct_buffer;
+
+pub mod conversion;
+
+#[cfg(feature = "count-crossings")]
+pub mod crossings;
+
+pub mod flow;
+
+pub mod future;
+
+pub mod hot_reload;
+
+pub mod io;
+
+pub mod memory;
+
+pub mod method_handle;
+
+pub mod mmap;
+
+pub mod money;
+
+pub mod observe;
+
+#[cfg(feature = "pool")]
+pub mod pool;
+
+pub mod script;
+
+pub mod text;
+
+pub mod trace_context;
+
+pub use combinators::{AndThen, IfNotNull, IntoNullable, Map, NotNullValue, Zip};
+pub use config::Config;
 pub use duchess_macro::{java_function, java_package, ToJava, ToRust};
-pub use error::{Error, GlobalResult, Result};
+pub use error::{ConversionError, Error, GlobalResult, Result};
+pub use executor::{scoped_executor, ScopedExecutor, ScopedTask};
+pub use frozen::Frozen;
 pub use into_rust::IntoRust;
+pub use iter::JavaIterator;
+pub use java_eq::JavaEq;
+pub use jvm::ExistingJvm;
 pub use jvm::JavaObject;
 pub use jvm::JavaType;
 pub use jvm::Jvm;
+pub use jvm::Verbose;
 pub use link::JavaFunction;
-pub use ref_::{Global, Local};
+pub use poll_loop::PollLoop;
+pub use proxy::ProxyCall;
+pub use ref_::{Global, JavaDisplay, Local, Weak};
 pub use refs::{AsJRef, JDeref, NullJRef, Nullable, TryJDeref};
-pub use try_catch::TryCatch;
+pub use shutdown::{install_shutdown_bridge, is_shutting_down, on_shutdown};
+pub use stack_trace::{JavaStackFrame, JavaStackTrace};
+pub use thread::{
+    current_java_thread, current_thread_is_attached, current_thread_is_interrupted, detach_thread,
+    JavaThreadOptions,
+};
+pub use try_catch::{CatchArm, CatchArms, CatchEnum, CatchInto, Catching, Finally, TryCatch};
 
-pub use prelude::*;
+pub use prelude::core::*;
+pub use prelude::ext::*;
 
 /// Contains traits with methods expected to be invoked by end-users.
+///
+/// The prelude is split in two halves, both of which are re-exported here
+/// so that `use duchess::prelude::*` continues to bring everything into scope:
+///
+/// * [`core`] contains the traits that describe JVM operations themselves;
+///   they are needed to name or bound types that interact with duchess.
+/// * [`ext`] contains extension traits whose only purpose is to add methods
+///   (e.g., `to_java`, `length`, `java_fn`) to other types. If those method
+///   names collide with extension traits of your own, import
+///   `duchess::prelude::core::*` and pick items from `ext` individually.
 pub mod prelude {
-    pub use crate::jvm::JvmOp;
-    pub use crate::link::JavaFn;
-    pub use crate::ops::{
-        IntoJava, IntoScalar, IntoVoid, JavaConstructor, JavaField, JavaMethod, ScalarField,
-        ScalarMethod, VoidMethod,
-    };
-    pub use crate::refs::{AsJRef, JDeref, TryJDeref};
-    pub use crate::to_java::ToJava;
+    pub use self::core::*;
+    pub use self::ext::*;
+
+    /// Traits describing JVM operations and references.
+    pub mod core {
+        pub use crate::jvm::JvmOp;
+        pub use crate::ops::{IntoJava, IntoScalar, IntoVoid, JavaConstructor};
+        pub use crate::refs::{AsJRef, JDeref, TryJDeref};
+    }
+
+    /// Extension traits that add convenience methods to other types.
+    pub mod ext {
+        pub use crate::array::{JavaArrayExt, JavaObjectArrayExt};
+        pub use crate::into_rust::{IntoRust, TryIntoRust};
+        pub use crate::link::JavaFn;
+        pub use crate::ops::{JavaField, JavaMethod, ScalarField, ScalarMethod, VoidMethod};
+        pub use crate::to_java::{ToJava, TryToJava};
+    }
 }
 
 /// Internal module containing non-semver protected
 /// names used by generated code.
 #[doc(hidden)]
 pub mod plumbing {
+    pub use crate::call;
     pub use crate::cast::Upcast;
-    pub use crate::find::{find_class, find_constructor, find_field, find_method};
+    pub use crate::find::{find_class, find_constructor, find_field, find_method, CachedMember};
+    pub use crate::frame::{FrameOutput, LocalFrame};
     pub use crate::from_ref::FromRef;
     pub use crate::global::GlobalOp;
     pub use crate::jvm::native_function_returning_object;
@@ -62,9 +176,10 @@ pub mod plumbing {
     pub use crate::jvm::JavaView;
     pub use crate::link::JavaFn;
     pub use crate::link::JavaFunction;
+    pub use crate::ops::sum_locals;
     pub use crate::raw::{EnvPtr, FieldPtr, FromJniValue, IntoJniValue, MethodPtr, ObjectPtr};
     pub use crate::refs::NullJRef;
-    pub use crate::to_java::ToJavaImpl;
+    pub use crate::to_java::{ToJavaImpl, TryToJavaImpl};
     pub use jni_sys;
     pub use once_cell;
 }
@@ -0,0 +1,216 @@
+//! Accounting for the native memory that duchess pins on behalf of Java, so that the garbage collector knows about it.
+//!
+//! The memory of a [`DirectBuffer`](crate::direct_buffer::DirectBuffer) and the closure of a proxy (see
+//! [`Jvm::proxy`]) are only freed once Java garbage collects the objects that use them. The JVM doesn't see that
+//! memory, so a Java heap with plenty of room left can keep gigabytes of Rust memory alive. [`stats`] reports how much
+//! of it is pinned at the moment.
+//!
+//! Once [`report_to_jvm`] is enabled (e.g. with [`JvmBuilder::report_native_memory`]), the pinned memory is counted by
+//! the JVM like that of the buffers of `ByteBuffer.allocateDirect`: pinning more than the limit set with
+//! [`JvmBuilder::max_direct_memory_size`] first runs the garbage collector, to release unreachable buffers and proxies,
+//! and then fails with an `OutOfMemoryError`. The memory is also included in the `direct` `BufferPoolMXBean`.
+//!
+//! The JVM is told with the `java.nio.Bits.reserveMemory` and `unreserveMemory` methods, which are internal to
+//! OpenJDK (and its derivatives). On JVMs without them, the memory is only counted in [`stats`].
+//!
+//! [`JvmBuilder::report_native_memory`]: crate::JvmBuilder::report_native_memory
+//! [`JvmBuilder::max_direct_memory_size`]: crate::JvmBuilder::max_direct_memory_size
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use jni_sys::{jlong, jvalue};
+use once_cell::sync::OnceCell;
+
+use crate::{
+    find::{find_class, find_method},
+    java::lang::Class,
+    jvm::JavaObjectExt,
+    raw::MethodPtr,
+    Error, Global, Jvm,
+};
+
+static REPORT_TO_JVM: AtomicBool = AtomicBool::new(false);
+static DIRECT_BUFFERS: Counter = Counter::new();
+static PROXIES: Counter = Counter::new();
+
+/// The native memory pinned by duchess at some point, see [`stats`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NativeMemoryStats {
+    /// The number of buffers created by [`DirectBuffer::new`](crate::direct_buffer::DirectBuffer::new) whose memory
+    /// hasn't been freed yet.
+    pub direct_buffers: usize,
+    /// The capacity of those buffers, in bytes.
+    pub direct_buffer_bytes: usize,
+    /// The number of proxies whose closures haven't been dropped yet.
+    pub proxies: usize,
+    /// The (shallow) size of those closures, in bytes.
+    pub proxy_bytes: usize,
+}
+
+impl NativeMemoryStats {
+    pub fn total_bytes(&self) -> usize {
+        self.direct_buffer_bytes + self.proxy_bytes
+    }
+}
+
+/// The native memory that duchess currently pins for Java objects.
+pub fn stats() -> NativeMemoryStats {
+    NativeMemoryStats {
+        direct_buffers: DIRECT_BUFFERS.count.load(Ordering::Relaxed),
+        direct_buffer_bytes: DIRECT_BUFFERS.bytes.load(Ordering::Relaxed),
+        proxies: PROXIES.count.load(Ordering::Relaxed),
+        proxy_bytes: PROXIES.bytes.load(Ordering::Relaxed),
+    }
+}
+
+/// Whether to count the memory pinned from now on as direct memory of the JVM, see the [module docs](self). Memory
+/// that is pinned already stays as it is.
+pub fn report_to_jvm(enabled: bool) {
+    REPORT_TO_JVM.store(enabled, Ordering::Relaxed);
+}
+
+struct Counter {
+    count: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+impl Counter {
+    const fn new() -> Self {
+        Counter {
+            count: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum PinnedKind {
+    DirectBuffer,
+    Proxy,
+}
+
+impl PinnedKind {
+    fn counter(self) -> &'static Counter {
+        match self {
+            PinnedKind::DirectBuffer => &DIRECT_BUFFERS,
+            PinnedKind::Proxy => &PROXIES,
+        }
+    }
+}
+
+/// Native memory that Java keeps alive, which is counted until this is dropped.
+pub(crate) struct Pinned {
+    kind: PinnedKind,
+    bytes: usize,
+    /// Whether the memory was reserved with `Bits.reserveMemory`.
+    reserved: bool,
+}
+
+impl Pinned {
+    /// Counts `bytes` of memory, reserving them with the JVM if [`report_to_jvm`] is enabled. Fails with an
+    /// `OutOfMemoryError` if the JVM can't make room for them.
+    pub(crate) fn new<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        kind: PinnedKind,
+        bytes: usize,
+    ) -> crate::Result<'jvm, Self> {
+        let reserved = if REPORT_TO_JVM.load(Ordering::Relaxed) && bytes > 0 {
+            match bits(jvm)? {
+                Some(bits) => {
+                    bits.call(jvm, bits.reserve, bytes)?;
+                    true
+                }
+                None => false,
+            }
+        } else {
+            false
+        };
+        let counter = kind.counter();
+        counter.count.fetch_add(1, Ordering::Relaxed);
+        counter.bytes.fetch_add(bytes, Ordering::Relaxed);
+        Ok(Pinned {
+            kind,
+            bytes,
+            reserved,
+        })
+    }
+}
+
+impl Drop for Pinned {
+    fn drop(&mut self) {
+        let counter = self.kind.counter();
+        counter.count.fetch_sub(1, Ordering::Relaxed);
+        counter.bytes.fetch_sub(self.bytes, Ordering::Relaxed);
+        if self.reserved {
+            let result = Jvm::with(|jvm| match bits(jvm)? {
+                Some(bits) => bits.call(jvm, bits.unreserve, self.bytes),
+                None => Ok(()),
+            });
+            if let Err(error) = result {
+                tracing::warn!(%error, kind = ?self.kind, "failed to unreserve pinned memory");
+            }
+        }
+    }
+}
+
+/// `java.nio.Bits`, if the JVM has it.
+struct Bits {
+    class: Global<Class>,
+    reserve: MethodPtr,
+    unreserve: MethodPtr,
+}
+
+impl Bits {
+    /// Calls `reserveMemory` or `unreserveMemory` for `bytes` bytes (of as much capacity).
+    fn call<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        method: MethodPtr,
+        bytes: usize,
+    ) -> crate::Result<'jvm, ()> {
+        let Ok(bytes) = jlong::try_from(bytes) else {
+            return Err(Error::SliceTooLong(bytes));
+        };
+        let args = [jvalue { j: bytes }, jvalue { j: bytes }];
+        // SAFETY: both methods are static methods of `Bits` taking two `long`s
+        unsafe {
+            jvm.env().invoke(
+                |env| env.CallStaticVoidMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        self.class.as_raw().as_ptr(),
+                        method.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }
+    }
+}
+
+/// Looks up `Bits` by hand, since it isn't public (JNI doesn't check access).
+fn bits<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Option<&'static Bits>> {
+    static BITS: OnceCell<Option<Bits>> = OnceCell::new();
+    BITS.get_or_try_init(|| {
+        let lookup = |jvm: &mut Jvm<'jvm>| -> crate::Result<'jvm, Bits> {
+            let class = find_class(jvm, c"java/nio/Bits")?;
+            Ok(Bits {
+                reserve: find_method(jvm, &class, c"reserveMemory", c"(JJ)V", true)?,
+                unreserve: find_method(jvm, &class, c"unreserveMemory", c"(JJ)V", true)?,
+                class: jvm.global(&*class),
+            })
+        };
+        match lookup(jvm) {
+            Ok(bits) => Ok(Some(bits)),
+            Err(Error::Thrown(_) | Error::ThrownWithBacktrace(..)) => {
+                tracing::warn!(
+                    "JVM has no `java.nio.Bits` methods, so pinned memory isn't reported to it"
+                );
+                Ok(None)
+            }
+            Err(error) => Err(error),
+        }
+    })
+    .map(Option::as_ref)
+}
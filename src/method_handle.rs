@@ -0,0 +1,570 @@
+//! Invoking methods chosen at runtime (e.g. by name, from configuration or a scripting layer), for which no bindings
+//! were generated.
+//!
+//! A [`DynamicMethod`] takes its arguments and returns its result as objects, boxing primitives like reflection does,
+//! and has two ways to call the method (see [`CallPath`]):
+//!
+//! * [`CallPath::Jni`] calls it with JNI's `Call*MethodA`, unboxing primitive arguments and boxing primitive results
+//!   with JNI calls as well, which is what generated bindings do.
+//! * [`CallPath::MethodHandle`] calls a `java.lang.invoke.MethodHandle` bound to the method with
+//!   `invokeWithArguments`, so that the arguments are spread, unboxed, and boxed again by Java (in a single JNI
+//!   call). `invokeExact` and `invoke` can't be used, because JNI can't call signature-polymorphic methods.
+//!
+//! Which one is faster depends on the method and the JVM: the JNI path makes a JNI call per primitive argument, while
+//! the method handle path makes an `Object[]` of the arguments and goes through the generic invocation machinery of
+//! `invokeWithArguments`, which the JIT can't specialize for megamorphic call sites. Since measure beats guess,
+//! [`DynamicMethod::calibrate`] times both paths with representative arguments, and uses the faster one from then on.
+//! On HotSpot, the JNI path is usually faster, even for methods with several primitive parameters (by about ten times
+//! for `Integer.sum(int, int)`), so calibrating is only worth it for methods called often enough to matter.
+
+use std::{
+    ffi::{CStr, CString},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use jni_sys::jvalue;
+
+use crate::{
+    descriptor::{parse_descriptor, Kind},
+    find::{find_class, find_method},
+    java::lang::{Class, Object},
+    jvm::JavaObjectExt,
+    raw::{with_jni_args, MethodPtr},
+    Error, Global, Jvm, Local,
+};
+
+/// How a [`DynamicMethod`] calls its method, see the [module docs](self).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CallPath {
+    Jni,
+    MethodHandle,
+}
+
+impl Kind {
+    /// The wrapper class of a primitive type, its method returning the primitive, and the descriptor of its
+    /// `valueOf` method.
+    fn boxing(self) -> Option<(&'static CStr, &'static CStr, &'static CStr, &'static CStr)> {
+        Some(match self {
+            Kind::Object => return None,
+            Kind::Boolean => (
+                c"java/lang/Boolean",
+                c"booleanValue",
+                c"()Z",
+                c"(Z)Ljava/lang/Boolean;",
+            ),
+            Kind::Byte => (
+                c"java/lang/Byte",
+                c"byteValue",
+                c"()B",
+                c"(B)Ljava/lang/Byte;",
+            ),
+            Kind::Char => (
+                c"java/lang/Character",
+                c"charValue",
+                c"()C",
+                c"(C)Ljava/lang/Character;",
+            ),
+            Kind::Short => (
+                c"java/lang/Short",
+                c"shortValue",
+                c"()S",
+                c"(S)Ljava/lang/Short;",
+            ),
+            Kind::Int => (
+                c"java/lang/Integer",
+                c"intValue",
+                c"()I",
+                c"(I)Ljava/lang/Integer;",
+            ),
+            Kind::Long => (
+                c"java/lang/Long",
+                c"longValue",
+                c"()J",
+                c"(J)Ljava/lang/Long;",
+            ),
+            Kind::Float => (
+                c"java/lang/Float",
+                c"floatValue",
+                c"()F",
+                c"(F)Ljava/lang/Float;",
+            ),
+            Kind::Double => (
+                c"java/lang/Double",
+                c"doubleValue",
+                c"()D",
+                c"(D)Ljava/lang/Double;",
+            ),
+        })
+    }
+}
+
+/// Converts between a primitive type and its wrapper class.
+struct Boxing {
+    class: Global<Class>,
+    unbox: MethodPtr,
+    value_of: MethodPtr,
+}
+
+impl Boxing {
+    fn new<'jvm>(jvm: &mut Jvm<'jvm>, kind: Kind) -> crate::Result<'jvm, Option<Self>> {
+        let Some((class_name, unbox_name, unbox_descriptor, value_of_descriptor)) = kind.boxing()
+        else {
+            return Ok(None);
+        };
+        let class = find_class(jvm, class_name)?;
+        let unbox = find_method(jvm, &class, unbox_name, unbox_descriptor, false)?;
+        let value_of = find_method(jvm, &class, c"valueOf", value_of_descriptor, true)?;
+        Ok(Some(Boxing {
+            class: jvm.global(&*class),
+            unbox,
+            value_of,
+        }))
+    }
+}
+
+/// A method, looked up at runtime, that can be called through JNI or a method handle, see the
+/// [module docs](self).
+pub struct DynamicMethod {
+    class: Global<Class>,
+    method: MethodPtr,
+    is_static: bool,
+    params: Vec<(Kind, Option<Boxing>)>,
+    result: Option<(Kind, Option<Boxing>)>,
+    handle: Global<Object>,
+    invoke_with_arguments: MethodPtr,
+    /// Whether [`CallPath::MethodHandle`] is used by [`DynamicMethod::invoke`].
+    use_handle: AtomicBool,
+}
+
+impl DynamicMethod {
+    /// Looks up the method `name` with the JNI `descriptor` (e.g., `(ILjava/lang/String;)V`) in `class`, which must be
+    /// public for the method handle path. The method is called through JNI until [`DynamicMethod::set_path`] or
+    /// [`DynamicMethod::calibrate`] selects another path.
+    pub fn new<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        class: &Class,
+        name: &str,
+        descriptor: &str,
+        is_static: bool,
+    ) -> crate::Result<'jvm, Self> {
+        let Some((param_kinds, result_kind)) = parse_descriptor(descriptor) else {
+            return Err(Error::JvmInternal(format!(
+                "invalid method descriptor `{descriptor}`"
+            )));
+        };
+        let (Ok(jni_name), Ok(jni_descriptor)) = (CString::new(name), CString::new(descriptor))
+        else {
+            return Err(Error::JvmInternal(format!(
+                "invalid method `{name}` with descriptor `{descriptor}`"
+            )));
+        };
+        let method = find_method(jvm, class, &jni_name, &jni_descriptor, is_static)?;
+
+        let mut params = Vec::with_capacity(param_kinds.len());
+        for kind in param_kinds {
+            params.push((kind, Boxing::new(jvm, kind)?));
+        }
+        let result = match result_kind {
+            Some(kind) => Some((kind, Boxing::new(jvm, kind)?)),
+            None => None,
+        };
+
+        // MethodHandles.publicLookup().unreflect(method), by hand since `Lookup` is a nested class
+        let env = jvm.env();
+        // SAFETY: `method` is a method of `class`, and static iff `is_static`
+        let reflected: Option<Local<Object>> = unsafe {
+            env.invoke(
+                |env| env.ToReflectedMethod,
+                |env, f| {
+                    f(
+                        env,
+                        class.as_raw().as_ptr(),
+                        method.as_ptr(),
+                        is_static as u8,
+                    )
+                },
+            )
+        }?;
+        let reflected = reflected.ok_or(Error::NullDeref)?;
+        let method_handles = find_class(jvm, c"java/lang/invoke/MethodHandles")?;
+        let public_lookup = find_method(
+            jvm,
+            &method_handles,
+            c"publicLookup",
+            c"()Ljava/lang/invoke/MethodHandles$Lookup;",
+            true,
+        )?;
+        let env = jvm.env();
+        // SAFETY: `publicLookup` is a static method of `method_handles` without parameters
+        let lookup: Option<Local<Object>> = unsafe {
+            env.invoke(
+                |env| env.CallStaticObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        method_handles.as_raw().as_ptr(),
+                        public_lookup.as_ptr(),
+                        std::ptr::null(),
+                    )
+                },
+            )
+        }?;
+        let lookup = lookup.ok_or(Error::NullDeref)?;
+        let lookup_class = find_class(jvm, c"java/lang/invoke/MethodHandles$Lookup")?;
+        let unreflect = find_method(
+            jvm,
+            &lookup_class,
+            c"unreflect",
+            c"(Ljava/lang/reflect/Method;)Ljava/lang/invoke/MethodHandle;",
+            false,
+        )?;
+        let args = [jvalue {
+            l: reflected.as_raw().as_ptr(),
+        }];
+        let env = jvm.env();
+        // SAFETY: `unreflect` is a method of `lookup`'s class taking a `Method`
+        let handle: Option<Local<Object>> = unsafe {
+            env.invoke(
+                |env| env.CallObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        lookup.as_raw().as_ptr(),
+                        unreflect.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }?;
+        let handle = handle.ok_or(Error::NullDeref)?;
+        let method_handle_class = find_class(jvm, c"java/lang/invoke/MethodHandle")?;
+        let invoke_with_arguments = find_method(
+            jvm,
+            &method_handle_class,
+            c"invokeWithArguments",
+            c"([Ljava/lang/Object;)Ljava/lang/Object;",
+            false,
+        )?;
+
+        Ok(DynamicMethod {
+            class: jvm.global(class),
+            method,
+            is_static,
+            params,
+            result,
+            handle: jvm.global(&*handle),
+            invoke_with_arguments,
+            use_handle: AtomicBool::new(false),
+        })
+    }
+
+    /// The number of parameters of the method.
+    pub fn arity(&self) -> usize {
+        self.params.len()
+    }
+
+    /// The path used by [`DynamicMethod::invoke`].
+    pub fn path(&self) -> CallPath {
+        if self.use_handle.load(Ordering::Relaxed) {
+            CallPath::MethodHandle
+        } else {
+            CallPath::Jni
+        }
+    }
+
+    pub fn set_path(&self, path: CallPath) {
+        self.use_handle
+            .store(path == CallPath::MethodHandle, Ordering::Relaxed);
+    }
+
+    /// Calls the method through the selected [`path`](DynamicMethod::path), on `this` (which is ignored for static
+    /// methods). Primitive arguments are passed as their wrapper objects (e.g., `java.lang.Integer` for `int`), and a
+    /// primitive result is returned as one. Returns `None` for `void` methods.
+    pub fn invoke<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        this: Option<&Object>,
+        args: &[Option<&Object>],
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        self.invoke_with(jvm, self.path(), this, args)
+    }
+
+    /// Like [`DynamicMethod::invoke`], through `path`.
+    pub fn invoke_with<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        path: CallPath,
+        this: Option<&Object>,
+        args: &[Option<&Object>],
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        if args.len() != self.params.len() {
+            return Err(Error::JvmInternal(format!(
+                "method takes {} arguments but {} were supplied",
+                self.params.len(),
+                args.len()
+            )));
+        }
+        let this = if self.is_static {
+            None
+        } else {
+            Some(this.ok_or(Error::NullDeref)?)
+        };
+        match path {
+            CallPath::Jni => self.invoke_jni(jvm, this, args),
+            CallPath::MethodHandle => self.invoke_handle(jvm, this, args),
+        }
+    }
+
+    /// Times `iterations` calls with `this` and `args` through each path, selects the faster one for
+    /// [`DynamicMethod::invoke`], and returns it along with the time each path took.
+    pub fn calibrate<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        this: Option<&Object>,
+        args: &[Option<&Object>],
+        iterations: u32,
+    ) -> crate::Result<'jvm, (CallPath, Duration, Duration)> {
+        let time = |jvm: &mut Jvm<'jvm>, path| -> crate::Result<'jvm, Duration> {
+            // Warm up both paths, then measure
+            self.invoke_with(jvm, path, this, args)?;
+            let start = Instant::now();
+            for _ in 0..iterations {
+                // Each result is a local reference, which is deleted right away so they don't pile up
+                drop(self.invoke_with(jvm, path, this, args)?);
+            }
+            Ok(start.elapsed())
+        };
+        let jni = time(jvm, CallPath::Jni)?;
+        let handle = time(jvm, CallPath::MethodHandle)?;
+        let path = if handle < jni {
+            CallPath::MethodHandle
+        } else {
+            CallPath::Jni
+        };
+        self.set_path(path);
+        Ok((path, jni, handle))
+    }
+
+    fn invoke_jni<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        this: Option<&Object>,
+        args: &[Option<&Object>],
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        with_jni_args(
+            jvm,
+            self.params.iter().zip(args),
+            |jvm, ((kind, boxing), arg)| match boxing {
+                None => Ok(jvalue {
+                    l: arg.map_or(std::ptr::null_mut(), |arg| arg.as_raw().as_ptr()),
+                }),
+                Some(boxing) => unbox(jvm, *kind, boxing, arg.ok_or(Error::NullDeref)?),
+            },
+            |jvm, values| self.call_jni(jvm, this, values),
+        )
+    }
+
+    fn call_jni<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        this: Option<&Object>,
+        values: &[jvalue],
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        let env = jvm.env();
+        let target = match this {
+            Some(this) => this.as_raw().as_ptr(),
+            None => self.class.as_raw().as_ptr(),
+        };
+        macro_rules! call {
+            ($call_fn:ident, $call_static_fn:ident) => {
+                // SAFETY: `method` is a method of `class` (and of `this`'s class, which is checked by the JVM), and
+                // `values` matches its parameters
+                unsafe {
+                    if self.is_static {
+                        env.invoke(
+                            |env| env.$call_static_fn,
+                            |env, f| f(env, target, self.method.as_ptr(), values.as_ptr()),
+                        )
+                    } else {
+                        env.invoke(
+                            |env| env.$call_fn,
+                            |env, f| f(env, target, self.method.as_ptr(), values.as_ptr()),
+                        )
+                    }
+                }?
+            };
+        }
+
+        let Some((kind, boxing)) = &self.result else {
+            let () = call!(CallVoidMethodA, CallStaticVoidMethodA);
+            return Ok(None);
+        };
+        let value = match kind {
+            Kind::Object => return Ok(call!(CallObjectMethodA, CallStaticObjectMethodA)),
+            Kind::Boolean => {
+                let value: bool = call!(CallBooleanMethodA, CallStaticBooleanMethodA);
+                jvalue { z: value as u8 }
+            }
+            Kind::Byte => jvalue {
+                b: call!(CallByteMethodA, CallStaticByteMethodA),
+            },
+            Kind::Char => jvalue {
+                c: call!(CallCharMethodA, CallStaticCharMethodA),
+            },
+            Kind::Short => jvalue {
+                s: call!(CallShortMethodA, CallStaticShortMethodA),
+            },
+            Kind::Int => jvalue {
+                i: call!(CallIntMethodA, CallStaticIntMethodA),
+            },
+            Kind::Long => jvalue {
+                j: call!(CallLongMethodA, CallStaticLongMethodA),
+            },
+            Kind::Float => jvalue {
+                f: call!(CallFloatMethodA, CallStaticFloatMethodA),
+            },
+            Kind::Double => jvalue {
+                d: call!(CallDoubleMethodA, CallStaticDoubleMethodA),
+            },
+        };
+        let boxing = boxing.as_ref().expect("primitive kinds have boxing");
+        let env = jvm.env();
+        let args = [value];
+        // SAFETY: `value_of` is a static method of the wrapper class taking its primitive
+        unsafe {
+            env.invoke(
+                |env| env.CallStaticObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        boxing.class.as_raw().as_ptr(),
+                        boxing.value_of.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }
+    }
+
+    fn invoke_handle<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        this: Option<&Object>,
+        args: &[Option<&Object>],
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        // The receiver is the first argument of handles of instance methods
+        let all_args = this.map(Some).into_iter().chain(args.iter().copied());
+        let object_class = find_class(jvm, c"java/lang/Object")?;
+        let env = jvm.env();
+        let len = (usize::from(this.is_some()) + args.len()) as i32;
+        // SAFETY: `object_class` is a live class reference
+        let array: Option<Local<Object>> = unsafe {
+            env.invoke(
+                |env| env.NewObjectArray,
+                |env, f| {
+                    f(
+                        env,
+                        len,
+                        object_class.as_raw().as_ptr(),
+                        std::ptr::null_mut(),
+                    )
+                },
+            )
+        }?;
+        let array = array.ok_or(Error::NullDeref)?;
+        for (index, arg) in all_args.enumerate() {
+            let arg = arg.map_or(std::ptr::null_mut(), |arg| arg.as_raw().as_ptr());
+            // SAFETY: `index` is within the bounds of `array`, whose elements are objects
+            let () = unsafe {
+                env.invoke(
+                    |env| env.SetObjectArrayElement,
+                    |env, f| f(env, array.as_raw().as_ptr(), index as i32, arg),
+                )
+            }?;
+        }
+
+        let args = [jvalue {
+            l: array.as_raw().as_ptr(),
+        }];
+        // SAFETY: `invoke_with_arguments` is `MethodHandle.invokeWithArguments(Object[])`
+        let result: Option<Local<Object>> = unsafe {
+            env.invoke(
+                |env| env.CallObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        self.handle.as_raw().as_ptr(),
+                        self.invoke_with_arguments.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }?;
+        Ok(result.filter(|_| self.result.is_some()))
+    }
+}
+
+/// Gets the primitive value of kind `kind` out of the wrapper object `arg`.
+fn unbox<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    kind: Kind,
+    boxing: &Boxing,
+    arg: &Object,
+) -> crate::Result<'jvm, jvalue> {
+    let env = jvm.env();
+    let arg_raw = arg.as_raw().as_ptr();
+    // `IsInstanceOf` avoids calling the method on an object of the wrong class, which JNI doesn't check
+    // SAFETY: both are live references
+    let is_instance = unsafe {
+        env.invoke_unchecked(
+            |env| env.IsInstanceOf,
+            |env, f| f(env, arg_raw, boxing.class.as_raw().as_ptr()),
+        )
+    };
+    if is_instance != jni_sys::JNI_TRUE {
+        return Err(Error::JvmInternal(format!(
+            "expected a boxed {kind:?} argument"
+        )));
+    }
+
+    macro_rules! call {
+        ($call_fn:ident) => {
+            // SAFETY: `unbox` is a method of `arg`'s class without parameters
+            unsafe {
+                env.invoke(
+                    |env| env.$call_fn,
+                    |env, f| f(env, arg_raw, boxing.unbox.as_ptr(), std::ptr::null()),
+                )
+            }?
+        };
+    }
+    Ok(match kind {
+        Kind::Object => unreachable!("objects aren't boxed"),
+        Kind::Boolean => {
+            let value: bool = call!(CallBooleanMethodA);
+            jvalue { z: value as u8 }
+        }
+        Kind::Byte => jvalue {
+            b: call!(CallByteMethodA),
+        },
+        Kind::Char => jvalue {
+            c: call!(CallCharMethodA),
+        },
+        Kind::Short => jvalue {
+            s: call!(CallShortMethodA),
+        },
+        Kind::Int => jvalue {
+            i: call!(CallIntMethodA),
+        },
+        Kind::Long => jvalue {
+            j: call!(CallLongMethodA),
+        },
+        Kind::Float => jvalue {
+            f: call!(CallFloatMethodA),
+        },
+        Kind::Double => jvalue {
+            d: call!(CallDoubleMethodA),
+        },
+    })
+}
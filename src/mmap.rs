@@ -0,0 +1,272 @@
+//! Sharing memory-mapped files between Rust and Java, for large datasets that shouldn't be copied across JNI at all.
+//!
+//! The file is mapped once, by Java, into a `java.nio.MappedByteBuffer` (see [`MappedFile::map`]). Since that buffer
+//! is a direct buffer, Rust accesses the same mapped memory through its address, while Java code keeps using the
+//! buffer itself ([`MappedFile::as_java`]). Changes made on either side are visible to the other immediately, and
+//! [`MappedFile::force`] writes them back to the file.
+//!
+//! A single `MappedByteBuffer` is limited to [`MapRegion::MAX_LEN`] bytes, so larger files are mapped in several
+//! regions, e.g. with [`MapRegion::chunks`].
+
+use std::{ffi::CStr, ptr::NonNull};
+
+use jni_sys::jvalue;
+
+use crate::{
+    direct_buffer::direct_memory,
+    find::{find_class, find_field, find_method},
+    java::{self, lang::Object, nio::MappedByteBuffer},
+    jvm::JavaObjectExt,
+    Error, Global, Jvm, JvmOp, Local,
+};
+
+/// A region of a file: `len` bytes starting at byte `offset`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MapRegion {
+    pub offset: u64,
+    pub len: u64,
+}
+
+impl MapRegion {
+    /// The largest region that a single `MappedByteBuffer` can map (`Integer.MAX_VALUE` bytes).
+    pub const MAX_LEN: u64 = i32::MAX as u64;
+
+    pub fn new(offset: u64, len: u64) -> Self {
+        MapRegion { offset, len }
+    }
+
+    /// The offset just past the end of the region.
+    pub fn end(&self) -> u64 {
+        self.offset + self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the byte at `offset` (in the file) is part of the region.
+    pub fn contains(&self, offset: u64) -> bool {
+        self.offset <= offset && offset < self.end()
+    }
+
+    /// The bytes that are part of both regions, if any.
+    pub fn intersection(&self, other: MapRegion) -> Option<MapRegion> {
+        let offset = self.offset.max(other.offset);
+        let end = self.end().min(other.end());
+        (offset < end).then(|| MapRegion::new(offset, end - offset))
+    }
+
+    /// The smallest region that covers this one and whose start and end are multiples of `alignment` (e.g., the page
+    /// size).
+    pub fn align_to(&self, alignment: u64) -> MapRegion {
+        assert!(alignment > 0, "alignment must not be zero");
+        let offset = self.offset - self.offset % alignment;
+        let end = self.end().div_ceil(alignment) * alignment;
+        MapRegion::new(offset, end - offset)
+    }
+
+    /// Splits the region into consecutive regions of at most `max_len` bytes (e.g., [`MapRegion::MAX_LEN`]).
+    pub fn chunks(&self, max_len: u64) -> impl Iterator<Item = MapRegion> {
+        assert!(max_len > 0, "chunks must not be empty");
+        let end = self.end();
+        (self.offset..end)
+            .step_by(usize::try_from(max_len).unwrap_or(usize::MAX))
+            .map(move |offset| MapRegion::new(offset, max_len.min(end - offset)))
+    }
+}
+
+/// How a file is mapped, see `java.nio.channels.FileChannel.MapMode`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MapMode {
+    ReadOnly,
+    /// Changes are written back to the file, and visible to other programs mapping it.
+    ReadWrite,
+    /// Copy-on-write: changes are only visible through this mapping, and never written back to the file.
+    Private,
+}
+
+impl MapMode {
+    fn field_name(self) -> &'static CStr {
+        match self {
+            MapMode::ReadOnly => c"READ_ONLY",
+            MapMode::ReadWrite => c"READ_WRITE",
+            MapMode::Private => c"PRIVATE",
+        }
+    }
+
+    fn file_mode(self) -> &'static str {
+        match self {
+            MapMode::ReadOnly => "r",
+            // Java requires a writable file for copy-on-write mappings too
+            MapMode::ReadWrite | MapMode::Private => "rw",
+        }
+    }
+}
+
+/// A region of a file mapped into memory by Java, and shared with Rust, see the [module docs](self).
+///
+/// The memory stays mapped as long as the `MappedFile` (or a Java reference to its buffer) exists.
+pub struct MappedFile {
+    buffer: Global<MappedByteBuffer>,
+    region: MapRegion,
+    mode: MapMode,
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: the mapped memory isn't tied to any thread, and the buffer is a global reference
+unsafe impl Send for MappedFile {}
+unsafe impl Sync for MappedFile {}
+
+impl MappedFile {
+    /// Maps `region` of the file at `path` with `FileChannel.map`. Mapping a region past the end of the file grows
+    /// the file in [`MapMode::ReadWrite`] mode (and fails otherwise).
+    ///
+    /// # Safety
+    ///
+    /// As for any memory-mapped file, the slices returned by [`MappedFile::as_slice`] and
+    /// [`MappedFile::as_mut_slice`] assume that nobody else changes the mapped memory while they are borrowed. The
+    /// caller must ensure that neither Java code using the buffer nor other programs using the file modify the
+    /// region (or, for [`MappedFile::as_mut_slice`], read it) during that time.
+    pub unsafe fn map<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        path: &str,
+        region: MapRegion,
+        mode: MapMode,
+    ) -> crate::Result<'jvm, Self> {
+        let file = java::io::RandomAccessFile::new(path, mode.file_mode()).execute_with(jvm)?;
+        // The mapping stays valid once the file is closed
+        let buffer = map_channel(jvm, &file, region, mode).and_then(|buffer| {
+            file.close().execute_with(jvm)?;
+            Ok(buffer)
+        });
+        let buffer = match buffer {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                let _ = file.close().execute_with(jvm);
+                return Err(e);
+            }
+        };
+
+        let (ptr, len) = direct_memory(jvm, &*buffer)?;
+
+        Ok(MappedFile {
+            buffer: jvm.global(&*buffer),
+            region,
+            mode,
+            ptr,
+            len,
+        })
+    }
+
+    /// The region of the file that is mapped.
+    pub fn region(&self) -> MapRegion {
+        self.region
+    }
+
+    pub fn mode(&self) -> MapMode {
+        self.mode
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The Java buffer over the mapped memory, to pass to Java code.
+    pub fn as_java(&self) -> &Global<MappedByteBuffer> {
+        &self.buffer
+    }
+
+    /// The mapped memory, where index 0 is the first byte of [`MappedFile::region`].
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: the buffer keeps `len` bytes mapped at `ptr`; `map` requires that they aren't changed meanwhile
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// The mapped memory, for writing. Panics for a [`MapMode::ReadOnly`] mapping.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        assert!(
+            self.mode != MapMode::ReadOnly,
+            "cannot write to a read-only mapping"
+        );
+        // SAFETY: as for `as_slice`, and the memory is writable
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Writes the changes to the mapped memory back to the file with `MappedByteBuffer.force()`, so that they survive
+    /// a crash. Does nothing for [`MapMode::Private`] mappings.
+    pub fn force<'jvm>(&self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        if self.mode == MapMode::ReadWrite {
+            self.buffer.force().execute_with(jvm)?;
+        }
+        Ok(())
+    }
+
+    /// Asks the OS to load the mapped memory into RAM with `MappedByteBuffer.load()`, e.g. before latency critical
+    /// reads.
+    pub fn load<'jvm>(&self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        self.buffer.load().execute_with(jvm)?;
+        Ok(())
+    }
+}
+
+/// Calls `FileChannel.map`, which is looked up by hand since its `MapMode` parameter is a nested class.
+fn map_channel<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    file: &java::io::RandomAccessFile,
+    region: MapRegion,
+    mode: MapMode,
+) -> crate::Result<'jvm, Local<'jvm, MappedByteBuffer>> {
+    let channel = file.get_channel().assert_not_null().execute_with(jvm)?;
+
+    let mode_class = find_class(jvm, c"java/nio/channels/FileChannel$MapMode")?;
+    let mode_field = find_field(
+        jvm,
+        &mode_class,
+        mode.field_name(),
+        c"Ljava/nio/channels/FileChannel$MapMode;",
+        true,
+    )?;
+    let env = jvm.env();
+    // SAFETY: `mode_field` is a static field of `mode_class`
+    let mode: Option<Local<Object>> = unsafe {
+        env.invoke(
+            |env| env.GetStaticObjectField,
+            |env, f| f(env, mode_class.as_raw().as_ptr(), mode_field.as_ptr()),
+        )
+    }?;
+    let mode = mode.ok_or(Error::NullDeref)?;
+
+    let channel_class = find_class(jvm, c"java/nio/channels/FileChannel")?;
+    let map = find_method(
+        jvm,
+        &channel_class,
+        c"map",
+        c"(Ljava/nio/channels/FileChannel$MapMode;JJ)Ljava/nio/MappedByteBuffer;",
+        false,
+    )?;
+    let args = [
+        jvalue {
+            l: mode.as_raw().as_ptr(),
+        },
+        jvalue {
+            j: region.offset as i64,
+        },
+        jvalue {
+            j: region.len as i64,
+        },
+    ];
+    let env = jvm.env();
+    // SAFETY: `map` is a method of `channel`'s class taking a `MapMode` and two `long`s
+    let buffer: Option<Local<MappedByteBuffer>> = unsafe {
+        env.invoke(
+            |env| env.CallObjectMethodA,
+            |env, f| f(env, channel.as_raw().as_ptr(), map.as_ptr(), args.as_ptr()),
+        )
+    }?;
+    buffer.ok_or_else(|| Error::JvmInternal("FileChannel.map returned null".into()))
+}
@@ -0,0 +1,294 @@
+//! Amounts of money: conversions of `java.util.Currency` into [`CurrencyCode`]s, of `java.math.BigDecimal` into
+//! [`Decimal`]s, and of the classes of money libraries (like Joda-Money or JSR 354's `javax.money`) into [`Money`].
+//!
+//! The money libraries aren't part of the JDK, so their classes are declared with
+//! [`java_package!`](crate::java_package) and bound by implementing [`MoneyClass`], e.g. for Joda-Money:
+//!
+//! ```ignore
+//! impl duchess::money::MoneyClass for org::joda::money::Money {
+//!     fn amount<'jvm>(jvm: &mut Jvm<'jvm>, money: &Self) -> duchess::Result<'jvm, Local<'jvm, java::math::BigDecimal>> {
+//!         money.get_amount().assert_not_null().execute_with(jvm)
+//!     }
+//!
+//!     fn currency_code<'jvm>(jvm: &mut Jvm<'jvm>, money: &Self) -> duchess::Result<'jvm, String> {
+//!         money.get_currency_unit().get_code().assert_not_null().to_rust().execute_with(jvm)
+//!     }
+//!
+//!     fn of<'jvm>(
+//!         jvm: &mut Jvm<'jvm>,
+//!         amount: &java::math::BigDecimal,
+//!         currency_code: &str,
+//!     ) -> duchess::Result<'jvm, Local<'jvm, Self>> {
+//!         let currency = org::joda::money::CurrencyUnit::of(currency_code);
+//!         org::joda::money::Money::of(currency, amount).assert_not_null().execute_with(jvm)
+//!     }
+//! }
+//! ```
+//!
+//! With `javax.money`, the amount is `money.getNumber().numberValue(BigDecimal.class)` and the currency code
+//! `money.getCurrency().getCurrencyCode()`.
+//!
+//! With the `rust_decimal` feature, `rust_decimal::Decimal` also converts to and from `BigDecimal`.
+
+use std::fmt::Display;
+
+use crate::{
+    big_integer::{from_twos_complement, to_twos_complement},
+    cast::Upcast,
+    error::ConversionError,
+    into_rust::{into_rust_or_conversion_error, TryIntoRust},
+    java,
+    to_java::{ToJava, ToJavaImpl},
+    IntoRust, JavaObject, Jvm, JvmOp, Local,
+};
+
+/// An ISO 4217 currency code, like `EUR`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CurrencyCode(pub String);
+
+impl CurrencyCode {
+    pub fn new(code: impl Into<String>) -> Self {
+        CurrencyCode(code.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for CurrencyCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A decimal number, `unscaled × 10^-scale`, as held by a `java.math.BigDecimal` whose unscaled value fits in an
+/// `i128`. Its `Display` is that of `BigDecimal.toPlainString`, e.g. `12.50` for an unscaled value of 1250 with a
+/// scale of 2.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    pub unscaled: i128,
+    pub scale: i32,
+}
+
+impl Decimal {
+    pub fn new(unscaled: i128, scale: i32) -> Self {
+        Decimal { unscaled, scale }
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.unscaled.unsigned_abs().to_string();
+        let sign = if self.unscaled < 0 { "-" } else { "" };
+        if self.scale <= 0 {
+            let zeros = if self.unscaled == 0 {
+                0
+            } else {
+                self.scale.unsigned_abs() as usize
+            };
+            return write!(f, "{sign}{digits}{}", "0".repeat(zeros));
+        }
+        let scale = self.scale as usize;
+        if digits.len() > scale {
+            let (whole, fraction) = digits.split_at(digits.len() - scale);
+            write!(f, "{sign}{whole}.{fraction}")
+        } else {
+            write!(f, "{sign}0.{}{digits}", "0".repeat(scale - digits.len()))
+        }
+    }
+}
+
+/// An amount of money in some currency.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: CurrencyCode,
+}
+
+/// A Java class of amounts of money, which then converts into and from [`Money`], see the [module docs](self).
+pub trait MoneyClass: JavaObject {
+    /// The amount of `money`, without its currency.
+    fn amount<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        money: &Self,
+    ) -> crate::Result<'jvm, Local<'jvm, java::math::BigDecimal>>;
+
+    /// The ISO 4217 code of the currency of `money`.
+    fn currency_code<'jvm>(jvm: &mut Jvm<'jvm>, money: &Self) -> crate::Result<'jvm, String>;
+
+    /// Creates the money `amount` in the currency `currency_code`.
+    fn of<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        amount: &java::math::BigDecimal,
+        currency_code: &str,
+    ) -> crate::Result<'jvm, Local<'jvm, Self>>;
+}
+
+impl IntoRust<CurrencyCode> for &java::util::Currency {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, CurrencyCode> {
+        let code: String = self
+            .get_currency_code()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        Ok(CurrencyCode(code))
+    }
+}
+
+/// Fails with the `IllegalArgumentException` of `Currency.getInstance` if the code isn't a currency known to the JVM.
+impl ToJavaImpl<java::util::Currency> for CurrencyCode {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Currency>>> {
+        java::util::Currency::get_instance(rust.as_str()).execute_with(jvm)
+    }
+}
+
+impl IntoRust<Decimal> for &java::math::BigDecimal {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Decimal> {
+        into_rust_or_conversion_error(self, jvm)
+    }
+}
+
+/// Fails if the unscaled value doesn't fit in an `i128`.
+impl TryIntoRust<Decimal> for &java::math::BigDecimal {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<Decimal, ConversionError>> {
+        let scale = self.scale().execute_with(jvm)?;
+        // The two's complement of the unscaled value, most significant byte first, in as few bytes as possible
+        let bytes: Vec<i8> = self
+            .unscaled_value()
+            .assert_not_null()
+            .to_byte_array()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let Some(unscaled) = from_twos_complement(&bytes) else {
+            let value: String = self
+                .to_plain_string()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            return Ok(Err(ConversionError::OutOfRange {
+                value,
+                target: "Decimal",
+            }));
+        };
+        Ok(Ok(Decimal { unscaled, scale }))
+    }
+}
+
+impl ToJavaImpl<java::math::BigDecimal> for Decimal {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::math::BigDecimal>>> {
+        let bytes = to_twos_complement(rust.unscaled);
+        let unscaled = java::math::BigInteger::new(&bytes[..]).execute_with(jvm)?;
+        let decimal = java::math::BigDecimal::new(&unscaled, rust.scale).execute_with(jvm)?;
+        Ok(Some(decimal))
+    }
+}
+
+impl<J> IntoRust<Money> for &J
+where
+    J: MoneyClass,
+{
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Money> {
+        let amount = J::amount(jvm, self)?;
+        let amount: Decimal = (&*amount).into_rust(jvm)?;
+        let currency = CurrencyCode(J::currency_code(jvm, self)?);
+        Ok(Money { amount, currency })
+    }
+}
+
+impl<J> ToJavaImpl<J> for Money
+where
+    J: MoneyClass + Upcast<java::lang::Object>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, J>>> {
+        let amount = rust
+            .amount
+            .to_java::<java::math::BigDecimal>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        Ok(Some(J::of(jvm, &amount, rust.currency.as_str())?))
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal_conversions {
+    use crate::{
+        error::ConversionError,
+        into_rust::{into_rust_or_conversion_error, TryIntoRust},
+        java,
+        to_java::ToJavaImpl,
+        IntoRust, Jvm, JvmOp, Local,
+    };
+
+    impl ToJavaImpl<java::math::BigDecimal> for rust_decimal::Decimal {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::math::BigDecimal>>> {
+            let decimal = super::Decimal::new(rust.mantissa(), rust.scale() as i32);
+            super::Decimal::to_java_impl(&decimal, jvm)
+        }
+    }
+
+    impl IntoRust<rust_decimal::Decimal> for &java::math::BigDecimal {
+        fn into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, rust_decimal::Decimal> {
+            into_rust_or_conversion_error(self, jvm)
+        }
+    }
+
+    /// Fails if the value doesn't fit in a `rust_decimal::Decimal`, whose unscaled value has 96 bits and whose scale
+    /// is at most 28. Trailing zeros are dropped to fit a larger scale, but no other digits.
+    impl TryIntoRust<rust_decimal::Decimal> for &java::math::BigDecimal {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<rust_decimal::Decimal, ConversionError>> {
+            let decimal: Result<super::Decimal, _> = self.try_into_rust(jvm)?;
+            if let Some(value) = decimal.ok().and_then(to_rust_decimal) {
+                return Ok(Ok(value));
+            }
+            let value: String = self
+                .to_plain_string()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            Ok(Err(ConversionError::OutOfRange {
+                value,
+                target: "rust_decimal::Decimal",
+            }))
+        }
+    }
+
+    fn to_rust_decimal(decimal: super::Decimal) -> Option<rust_decimal::Decimal> {
+        let super::Decimal {
+            mut unscaled,
+            mut scale,
+        } = decimal;
+        // A negative scale is a number of trailing zeros
+        if scale < 0 {
+            unscaled = unscaled.checked_mul(10_i128.checked_pow(scale.unsigned_abs())?)?;
+            scale = 0;
+        }
+        while scale > rust_decimal::Decimal::MAX_SCALE as i32 && unscaled % 10 == 0 {
+            unscaled /= 10;
+            scale -= 1;
+        }
+        rust_decimal::Decimal::try_from_i128_with_scale(unscaled, scale as u32).ok()
+    }
+}
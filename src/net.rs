@@ -0,0 +1,161 @@
+//! Conversions between `std::net` addresses and `java.net.InetAddress`, and (with the `url` feature) between
+//! `url::Url` and `java.net.URI`/`java.net.URL`.
+//!
+//! Java strings convert into URIs with `java::net::URI::create`, which throws an `IllegalArgumentException` for
+//! strings that aren't valid URIs.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::{
+    error::ConversionError,
+    into_rust::{into_rust_or_conversion_error, TryIntoRust},
+    java,
+    to_java::ToJavaImpl,
+    IntoRust, Jvm, JvmOp, Local,
+};
+
+impl IntoRust<IpAddr> for &java::net::InetAddress {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, IpAddr> {
+        into_rust_or_conversion_error(self, jvm)
+    }
+}
+
+/// Keeps only the address, not the host name (nor, for IPv6, the scope) of the `InetAddress`.
+impl TryIntoRust<IpAddr> for &java::net::InetAddress {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<IpAddr, ConversionError>> {
+        let bytes: Vec<u8> = self
+            .get_address()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        if let Ok(octets) = <[u8; 4]>::try_from(&bytes[..]) {
+            return Ok(Ok(IpAddr::V4(Ipv4Addr::from(octets))));
+        }
+        if let Ok(octets) = <[u8; 16]>::try_from(&bytes[..]) {
+            return Ok(Ok(IpAddr::V6(Ipv6Addr::from(octets))));
+        }
+        Ok(Err(ConversionError::Malformed {
+            value: format!("{bytes:?}"),
+            target: "IpAddr",
+        }))
+    }
+}
+
+/// An `Inet4Address` or `Inet6Address`. Like `InetAddress.getByAddress`, IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`)
+/// become `Inet4Address`es.
+impl ToJavaImpl<java::net::InetAddress> for IpAddr {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::net::InetAddress>>> {
+        let octets = match rust {
+            IpAddr::V4(ip) => ip.octets().to_vec(),
+            IpAddr::V6(ip) => ip.octets().to_vec(),
+        };
+        java::net::InetAddress::get_by_address(&octets).execute_with(jvm)
+    }
+}
+
+impl ToJavaImpl<java::net::InetAddress> for Ipv4Addr {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::net::InetAddress>>> {
+        IpAddr::to_java_impl(&IpAddr::V4(*rust), jvm)
+    }
+}
+
+impl ToJavaImpl<java::net::InetAddress> for Ipv6Addr {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::net::InetAddress>>> {
+        IpAddr::to_java_impl(&IpAddr::V6(*rust), jvm)
+    }
+}
+
+#[cfg(feature = "url")]
+mod url_conversions {
+    use url::Url;
+
+    use crate::{
+        error::ConversionError,
+        into_rust::{into_rust_or_conversion_error, TryIntoRust},
+        java,
+        to_java::ToJavaImpl,
+        IntoRust, Jvm, JvmOp, Local,
+    };
+
+    fn parse(value: String) -> Result<Url, ConversionError> {
+        Url::parse(&value).map_err(|_| ConversionError::Malformed {
+            value,
+            target: "url::Url",
+        })
+    }
+
+    impl IntoRust<Url> for &java::net::URI {
+        fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Url> {
+            into_rust_or_conversion_error(self, jvm)
+        }
+    }
+
+    /// Fails for URIs that aren't URLs, e.g. relative ones.
+    impl TryIntoRust<Url> for &java::net::URI {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<Url, ConversionError>> {
+            let value: String = self
+                .to_ascii_string()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            Ok(parse(value))
+        }
+    }
+
+    impl IntoRust<Url> for &java::net::URL {
+        fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Url> {
+            into_rust_or_conversion_error(self, jvm)
+        }
+    }
+
+    impl TryIntoRust<Url> for &java::net::URL {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<Url, ConversionError>> {
+            let value: String = self
+                .to_external_form()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            Ok(parse(value))
+        }
+    }
+
+    impl ToJavaImpl<java::net::URI> for Url {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::net::URI>>> {
+            java::net::URI::create(rust.as_str()).execute_with(jvm)
+        }
+    }
+
+    /// Throws a `MalformedURLException` if the JVM has no protocol handler for the URL's scheme.
+    impl ToJavaImpl<java::net::URL> for Url {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::net::URL>>> {
+            java::net::URI::create(rust.as_str())
+                .assert_not_null()
+                .to_url()
+                .execute_with(jvm)
+        }
+    }
+}
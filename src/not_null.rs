@@ -1,5 +1,10 @@
-use crate::{Error, JavaObject, JvmOp, Local};
+use crate::{from_ref::FromRef, jvm::JavaView, Error, JavaObject, JvmOp, Local};
 
+/// [`JvmOp`][] that fails with [`Error::NullDeref`] if the object produced by `J` is null, see
+/// [`JvmOp::assert_not_null`].
+///
+/// Its output is a [`Local`], which can't be null, so the methods of the object can be called on it (and conversions
+/// applied to it) without checking for null again at each step.
 #[derive_where::derive_where(Copy, Clone)]
 pub struct NotNull<J: JvmOp> {
     j: J,
@@ -22,6 +27,8 @@ where
 {
     type Output<'jvm> = Local<'jvm, T>;
 
+    const LOCALS: usize = J::LOCALS;
+
     fn execute_with<'jvm>(
         self,
         jvm: &mut crate::Jvm<'jvm>,
@@ -30,3 +37,15 @@ where
         j.ok_or(Error::NullDeref)
     }
 }
+
+impl<J, T> std::ops::Deref for NotNull<J>
+where
+    J: for<'jvm> JvmOp<Output<'jvm> = Option<Local<'jvm, T>>>,
+    T: JavaObject,
+{
+    type Target = <T as JavaView>::OfOp<Self>;
+
+    fn deref(&self) -> &Self::Target {
+        <Self::Target as FromRef<_>>::from_ref(self)
+    }
+}
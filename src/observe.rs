@@ -0,0 +1,133 @@
+//! Observing the operations run with [`JvmOp::execute`], e.g. to find which Java calls dominate latency in production
+//! without a full profiler.
+//!
+//! Once an [`ExecutionObserver`] is set with [`set_execution_observer`], it is called after each `execute` with the
+//! operation and how long it took, on the thread that ran it. Closures are observers, and [`SlowCallLogger`] logs the
+//! operations that take longer than a threshold:
+//!
+//! ```
+//! # fn main() -> duchess::GlobalResult<()> {
+//! use duchess::observe::{set_execution_observer, SlowCallLogger};
+//! use std::time::Duration;
+//!
+//! set_execution_observer(Some(SlowCallLogger::new(Duration::from_millis(50))));
+//! # set_execution_observer(None::<SlowCallLogger>);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Operations run with [`JvmOp::execute_with`] aren't observed on their own, since they are the steps of others (e.g.
+//! within [`Jvm::with`](crate::Jvm::with)). Without an observer, `execute` only checks that there is none.
+
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::JvmOp;
+
+static OBSERVED: AtomicBool = AtomicBool::new(false);
+static OBSERVER: RwLock<Option<Arc<dyn ExecutionObserver>>> = RwLock::new(None);
+
+thread_local! {
+    /// Set while the observer runs, so that the operations it executes itself aren't observed.
+    static OBSERVING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// An operation run with [`JvmOp::execute`], see [`ExecutionObserver`].
+#[derive(Copy, Clone, Debug)]
+pub struct Execution {
+    /// The type name of the operation (e.g. `duchess::into_rust::ToRustOp<..>`), which names the Java methods it
+    /// calls.
+    pub op: &'static str,
+    /// The time the operation took, including attaching the thread to the JVM (or launching it) if need be.
+    pub duration: Duration,
+    /// Whether the operation succeeded, i.e., returned `Ok`.
+    pub succeeded: bool,
+}
+
+/// Called after each operation run with [`JvmOp::execute`], see [`set_execution_observer`].
+pub trait ExecutionObserver: Send + Sync + 'static {
+    fn executed(&self, execution: &Execution);
+}
+
+impl<F: Fn(&Execution) + Send + Sync + 'static> ExecutionObserver for F {
+    fn executed(&self, execution: &Execution) {
+        self(execution)
+    }
+}
+
+/// Sets the observer called after each operation run with [`JvmOp::execute`], replacing the previous one, or removes it
+/// for `None`.
+///
+/// The observer runs on the thread that executed the operation, so it should be quick. The operations that it
+/// executes itself aren't observed.
+///
+/// Nothing is reported while metrics are turned off with [`Config::metrics`](crate::Config::metrics).
+pub fn set_execution_observer(observer: Option<impl ExecutionObserver>) {
+    let observer = observer.map(|o| Arc::new(o) as Arc<dyn ExecutionObserver>);
+    let mut current = OBSERVER.write().unwrap();
+    OBSERVED.store(observer.is_some(), Ordering::Release);
+    *current = observer;
+}
+
+/// Logs the operations that take at least a threshold with a `tracing` warning.
+#[derive(Copy, Clone, Debug)]
+pub struct SlowCallLogger {
+    threshold: Duration,
+}
+
+impl SlowCallLogger {
+    pub fn new(threshold: Duration) -> Self {
+        Self { threshold }
+    }
+}
+
+impl ExecutionObserver for SlowCallLogger {
+    fn executed(&self, execution: &Execution) {
+        if execution.duration >= self.threshold {
+            tracing::warn!(
+                op = execution.op,
+                duration = ?execution.duration,
+                succeeded = execution.succeeded,
+                "slow JVM operation"
+            );
+        }
+    }
+}
+
+/// Runs `execute`, the body of [`JvmOp::execute`] for `O`, reporting it to the observer if there is one.
+pub(crate) fn observe<O: JvmOp, R>(
+    execute: impl FnOnce() -> crate::GlobalResult<R>,
+) -> crate::GlobalResult<R> {
+    if !OBSERVED.load(Ordering::Acquire) || !crate::config::metrics() || OBSERVING.with(Cell::get) {
+        return execute();
+    }
+
+    let start = Instant::now();
+    let result = execute();
+    let execution = Execution {
+        op: std::any::type_name::<O>(),
+        duration: start.elapsed(),
+        succeeded: result.is_ok(),
+    };
+    // Cloned so that the observer can replace itself
+    let observer = OBSERVER.read().unwrap().clone();
+    if let Some(observer) = observer {
+        OBSERVING.with(|o| o.set(true));
+        // Reset even if the observer panics
+        struct Reset;
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                OBSERVING.with(|o| o.set(false));
+            }
+        }
+        let _reset = Reset;
+        observer.executed(&execution);
+    }
+    result
+}
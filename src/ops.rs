@@ -12,6 +12,8 @@ macro_rules! identity_jvm_op {
             impl<$($param)*> JvmOp for $t {
                 type Output<'jvm> = Self;
 
+                const LOCALS: usize = 0;
+
                 fn execute_with<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
                     Ok(self)
                 }
@@ -27,7 +29,6 @@ identity_jvm_op! {
     [] i32,  // int
     [] i64,  // long
 
-    [] char, // long (Java char is 2 bytes / UTF-16)
     [] u16,  // char
 
     [] (),  // void
@@ -42,6 +43,18 @@ identity_jvm_op! {
     [R: JavaObject] &Option<Global<R>>,
 }
 
+/// Adds up the [`JvmOp::LOCALS`] of the parts of an operation, saturating at `usize::MAX` (i.e., unknown).
+#[doc(hidden)]
+pub const fn sum_locals(counts: &[usize]) -> usize {
+    let mut sum: usize = 0;
+    let mut i = 0;
+    while i < counts.len() {
+        sum = sum.saturating_add(counts[i]);
+        i += 1;
+    }
+    sum
+}
+
 /// Types that are able to be used as a Java `T`, either because they will produce a Java `T` (e.g. [`JvmOp`]s that
 /// produce a `T`) or because we can convert into them via a JNI call.
 ///
@@ -60,6 +73,10 @@ identity_jvm_op! {
 pub trait IntoJava<T: JavaObject>: Copy {
     type Output<'jvm>: AsJRef<T>;
 
+    /// See [`JvmOp::LOCALS`].
+    #[doc(hidden)]
+    const LOCALS: usize;
+
     fn into_java<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>>;
 }
 
@@ -71,6 +88,8 @@ where
 {
     type Output<'jvm> = <J as JvmOp>::Output<'jvm>;
 
+    const LOCALS: usize = <J as JvmOp>::LOCALS;
+
     fn into_java<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
         self.execute_with(jvm)
     }
@@ -94,14 +113,26 @@ where
 {
 }
 
-/// A [`JvmOp`] that produces a scalar value, like `i8` or `i32`.
-pub trait IntoScalar<T: JavaScalar>: for<'jvm> JvmOp<Output<'jvm> = T> {}
+/// Types that are able to be used as a Java scalar `T`, like `i8` or `i32`: [`JvmOp`]s that produce a `T`, and Rust
+/// unsigned integers, which are widened or checked to fit (or wrapped around, in a [`std::num::Wrapping`]).
+pub trait IntoScalar<T: JavaScalar>: Copy {
+    /// See [`JvmOp::LOCALS`].
+    #[doc(hidden)]
+    const LOCALS: usize;
+
+    fn into_scalar<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, T>;
+}
 
 impl<J, T> IntoScalar<T> for J
 where
     T: JavaScalar,
     J: for<'jvm> JvmOp<Output<'jvm> = T>,
 {
+    const LOCALS: usize = <J as JvmOp>::LOCALS;
+
+    fn into_scalar<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, T> {
+        self.execute_with(jvm)
+    }
 }
 
 /// A [`JvmOp`] that produces a void (`()`)
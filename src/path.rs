@@ -0,0 +1,180 @@
+//! Conversions between [`Path`]/[`PathBuf`] and `java.io.File`/`java.nio.file.Path`, through the string form of the
+//! path.
+//!
+//! Both sides use the separators of the platform, so paths keep their meaning unchanged; Java's `File` normalizes
+//! its path though (e.g. dropping trailing separators, or turning `/` into `\` on Windows).
+//!
+//! Paths that aren't valid UTF-8 are converted the way the JVM names files on the platform:
+//!
+//! * On Windows, file names are UTF-16, like Java strings, so the unpaired surrogates that a Rust string can't hold
+//!   round-trip unchanged.
+//! * On Unix, file names are bytes, which the JVM decodes with its `sun.jnu.encoding` (the encoding of the locale it
+//!   was started in). Under an ISO-8859-1 locale every byte is a character, so every path converts. Under UTF-8 (the
+//!   usual case) or ASCII, the JVM can't name a file whose name isn't valid in that encoding, and neither can the
+//!   conversion: it fails with a [`ConversionError::Malformed`](crate::ConversionError::Malformed), as it does for the other encodings.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    java::{self, lang::String as JavaString},
+    to_java::ToJavaImpl,
+    IntoRust, Jvm, JvmOp, Local,
+};
+
+#[cfg(not(windows))]
+fn malformed(path: &Path, target: &'static str) -> crate::ConversionError {
+    crate::ConversionError::Malformed {
+        value: path.display().to_string(),
+        target,
+    }
+}
+
+/// Whether the JVM decodes file names as ISO-8859-1, in which case every byte is a character.
+#[cfg(unix)]
+fn file_names_are_latin1<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, bool> {
+    // The encoding is fixed when the JVM starts
+    static LATIN1: once_cell::sync::OnceCell<bool> = once_cell::sync::OnceCell::new();
+    if let Some(&latin1) = LATIN1.get() {
+        return Ok(latin1);
+    }
+    let encoding = jvm.get_property("sun.jnu.encoding")?;
+    let latin1 = matches!(
+        encoding.as_deref(),
+        Some("ISO-8859-1" | "ISO8859_1" | "8859_1")
+    );
+    Ok(*LATIN1.get_or_init(|| latin1))
+}
+
+/// The Java string that names `path`, see the [module docs](self).
+fn path_string<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    path: &Path,
+    target: &'static str,
+) -> crate::Result<'jvm, Local<'jvm, JavaString>> {
+    if let Some(path) = path.to_str() {
+        return path.execute_with(jvm);
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        let _ = target;
+        let units: Vec<u16> = path.as_os_str().encode_wide().collect();
+        crate::str::new_string_utf16(jvm, &units)
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+
+        if !file_names_are_latin1(jvm)? {
+            return Err(malformed(path, target).into());
+        }
+        let decoded: String = path
+            .as_os_str()
+            .as_bytes()
+            .iter()
+            .map(|&b| char::from(b))
+            .collect();
+        decoded.execute_with(jvm)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = jvm;
+        Err(malformed(path, target).into())
+    }
+}
+
+/// The Rust path named by the Java string `string`, see the [module docs](self).
+fn path_buf<'jvm>(jvm: &mut Jvm<'jvm>, string: &JavaString) -> crate::Result<'jvm, PathBuf> {
+    #[cfg(windows)]
+    {
+        use std::{ffi::OsString, os::windows::ffi::OsStringExt};
+
+        let units: Vec<u16> = string
+            .to_char_array()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        Ok(OsString::from_wide(&units).into())
+    }
+
+    #[cfg(not(windows))]
+    {
+        let path: String = string.to_rust().execute_with(jvm)?;
+
+        #[cfg(unix)]
+        if !path.is_ascii() && file_names_are_latin1(jvm)? {
+            use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+            // The JVM can't name a file with characters outside of ISO-8859-1 either
+            let Ok(bytes) = path
+                .chars()
+                .map(u8::try_from)
+                .collect::<Result<Vec<u8>, _>>()
+            else {
+                return Err(malformed(Path::new(&path), "PathBuf").into());
+            };
+            return Ok(OsString::from_vec(bytes).into());
+        }
+
+        Ok(PathBuf::from(path))
+    }
+}
+
+impl IntoRust<PathBuf> for &java::io::File {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, PathBuf> {
+        let path = self.get_path().assert_not_null().execute_with(jvm)?;
+        path_buf(jvm, &path)
+    }
+}
+
+impl IntoRust<PathBuf> for &java::nio::file::Path {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, PathBuf> {
+        let path = self.to_string().assert_not_null().execute_with(jvm)?;
+        path_buf(jvm, &path)
+    }
+}
+
+impl ToJavaImpl<java::io::File> for Path {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::io::File>>> {
+        let path = path_string(jvm, rust, "java.io.File")?;
+        let file = java::io::File::new(&path).execute_with(jvm)?;
+        Ok(Some(file))
+    }
+}
+
+impl ToJavaImpl<java::io::File> for PathBuf {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::io::File>>> {
+        Path::to_java_impl(rust, jvm)
+    }
+}
+
+/// A path of the default file system, with `File.toPath`. Throws an `InvalidPathException` if the path isn't valid
+/// there, e.g. if it contains a NUL character.
+impl ToJavaImpl<java::nio::file::Path> for Path {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::nio::file::Path>>> {
+        let path = path_string(jvm, rust, "java.nio.file.Path")?;
+        java::io::File::new(&path).to_path().execute_with(jvm)
+    }
+}
+
+impl ToJavaImpl<java::nio::file::Path> for PathBuf {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::nio::file::Path>>> {
+        Path::to_java_impl(rust, jvm)
+    }
+}
@@ -0,0 +1,128 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::{GlobalResult, Jvm};
+
+/// Runs a polling operation (e.g., `KafkaConsumer.poll` or `MessageConsumer.receive`) in a loop on its own thread,
+/// delivering the records it returns through a channel, see [`PollLoop::spawn`].
+///
+/// The loop stops cooperatively: after [`PollLoop::stop`] (or once the `PollLoop` is dropped), the current poll is
+/// finished and no further polls are started. Polling ops should therefore use a timeout so that they return
+/// regularly, even if there are no records.
+pub struct PollLoop<R> {
+    records: Option<Receiver<GlobalResult<R>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<R: Send + 'static> PollLoop<R> {
+    /// Spawns a thread, attached to the JVM as a daemon (so that it doesn't keep the JVM from exiting), that calls
+    /// `poll` until the loop is stopped and delivers the records it returns. Each poll runs in its own local frame, so
+    /// `poll` only needs to convert the records to Rust (or [`Global`](crate::Global)) values.
+    ///
+    /// Up to `capacity` records are buffered; once the buffer is full, polling waits for records to be received. If
+    /// `poll` fails (e.g., by throwing), the error is delivered as the last record and the loop ends.
+    pub fn spawn(
+        capacity: usize,
+        mut poll: impl for<'jvm> FnMut(&mut Jvm<'jvm>) -> crate::Result<'jvm, Vec<R>> + Send + 'static,
+    ) -> std::io::Result<Self> {
+        let (sender, records) = mpsc::sync_channel(capacity);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let stopped = stop.clone();
+        let thread = std::thread::Builder::new()
+            .name("duchess-poll-loop".into())
+            .spawn(move || {
+                // e.g., failing to start the JVM
+                if let Err(e) = Jvm::attach_thread_permanently_as_daemon() {
+                    let _ = sender.send(Err(e));
+                    return;
+                }
+                let result = Jvm::with(|jvm| {
+                    while !stopped.load(Ordering::Acquire) {
+                        let batch =
+                            jvm.with_frame(crate::config::local_frame_capacity(), |jvm| poll(jvm));
+                        let batch = match batch {
+                            Ok(batch) => batch,
+                            Err(e) => {
+                                let _ = sender.send(Err(e.into_global(jvm)));
+                                return Ok(());
+                            }
+                        };
+                        for record in batch {
+                            if sender.send(Ok(record)).is_err() {
+                                // Nobody is receiving anymore
+                                return Ok(());
+                            }
+                        }
+                    }
+                    Ok(())
+                });
+                if let Err(e) = result {
+                    let _ = sender.send(Err(e));
+                }
+            })?;
+
+        Ok(PollLoop {
+            records: Some(records),
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl<R> PollLoop<R> {
+    /// Waits for the next record, returning `None` once the loop has ended and all records were received.
+    pub fn recv(&self) -> Option<GlobalResult<R>> {
+        self.receiver().recv().ok()
+    }
+
+    /// Like [`PollLoop::recv`], but waits at most `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<GlobalResult<R>, RecvTimeoutError> {
+        self.receiver().recv_timeout(timeout)
+    }
+
+    /// Iterates over the records as they arrive, until the loop ends.
+    pub fn iter(&self) -> impl Iterator<Item = GlobalResult<R>> + '_ {
+        self.receiver().iter()
+    }
+
+    /// Asks the loop to stop after the current poll, without waiting for it.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Release);
+    }
+
+    /// Whether the polling thread has ended.
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().map_or(true, |t| t.is_finished())
+    }
+
+    /// Stops the loop, dropping any records that were not received, and waits for the current poll to finish.
+    /// Returns an error if `poll` panicked.
+    pub fn shutdown(mut self) -> std::thread::Result<()> {
+        self.stop();
+        // Unblocks the thread if it is waiting for room in the buffer
+        self.records.take();
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(()),
+        }
+    }
+
+    fn receiver(&self) -> &Receiver<GlobalResult<R>> {
+        self.records.as_ref().unwrap()
+    }
+}
+
+impl<R> Drop for PollLoop<R> {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
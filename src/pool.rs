@@ -0,0 +1,189 @@
+//! Runs [`JvmOp`]s from async code on a pool of threads that are permanently attached to the JVM.
+//!
+//! Calling [`JvmOp::execute`] in an async task blocks the thread that polls it, and attaches (and later detaches)
+//! that thread for every call unless it is permanently attached. [`execute_on_pool`] instead hands the operation to
+//! a [`JvmPool`] and returns a future that completes once a pool thread has run it:
+//!
+//! ```rust,no_run
+//! # use duchess::{java, prelude::*};
+//! # async fn f() -> duchess::GlobalResult<()> {
+//! let upper: String = duchess::pool::execute_on_pool(duchess::text::to_upper_case("hello")).await?;
+//! let string = duchess::pool::execute_on_pool("hello".to_java::<java::lang::String>().global()).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! As with [`JvmOp::execute`], the output of the operation can't be tied to the JVM: use
+//! [`global()`][JvmOp::global] or [`to_rust()`][JvmOp::to_rust] to get values that can be sent back to the task.
+//!
+//! The futures are woken through their `Waker`s, so they don't depend on an async runtime and work with any executor
+//! (e.g., tokio).
+
+use std::{
+    future::Future,
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread::JoinHandle,
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::{GlobalResult, Jvm, JvmOp};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A pool of threads that are permanently attached to the JVM, see the [module docs](self). The threads are
+/// attached as daemon threads, so that they don't keep the JVM from shutting down.
+///
+/// Dropping the pool waits for the operations that were already submitted to run.
+pub struct JvmPool {
+    jobs: Mutex<Option<mpsc::Sender<Job>>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl JvmPool {
+    /// Starts `threads` threads and attaches each of them to the JVM (launching it if need be).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero.
+    pub fn new(threads: usize) -> GlobalResult<Self> {
+        assert!(threads > 0, "a JVM pool needs at least one thread");
+        // Launched here rather than by a pool thread, which would then become the (non-daemon) main thread
+        Jvm::builder().launch_or_use_existing()?;
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let (attached_sender, attached) = mpsc::channel();
+        let threads = (0..threads)
+            .map(|i| {
+                let receiver = receiver.clone();
+                let attached_sender = attached_sender.clone();
+                std::thread::Builder::new()
+                    .name(format!("duchess-pool-{i}"))
+                    .spawn(move || {
+                        let result = Jvm::attach_thread_permanently_as_daemon();
+                        let failed = result.is_err();
+                        let _ = attached_sender.send(result);
+                        drop(attached_sender);
+                        if failed {
+                            return;
+                        }
+                        loop {
+                            // The lock is only held while waiting, not while the job runs
+                            let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                            match job {
+                                Ok(job) => job(),
+                                Err(mpsc::RecvError) => return,
+                            }
+                        }
+                    })
+                    .expect("failed to spawn JVM pool thread")
+            })
+            .collect();
+        drop(attached_sender);
+
+        let pool = JvmPool {
+            jobs: Mutex::new(Some(sender)),
+            threads,
+        };
+        for result in attached {
+            result?;
+        }
+        Ok(pool)
+    }
+
+    /// Runs `op` on one of the threads of the pool, see [`execute_on_pool`].
+    pub fn execute_on_pool<O, R>(&self, op: O) -> PoolFuture<R>
+    where
+        O: Send + 'static,
+        for<'jvm> O: JvmOp<Output<'jvm> = R>,
+        R: Send + 'static,
+    {
+        let slot = Arc::new(Mutex::new(Slot {
+            outcome: None,
+            waker: None,
+        }));
+        let job_slot = slot.clone();
+        let job: Job = Box::new(move || {
+            let outcome = std::panic::catch_unwind(AssertUnwindSafe(|| op.execute()));
+            let mut slot = job_slot.lock().unwrap_or_else(|e| e.into_inner());
+            slot.outcome = Some(outcome);
+            if let Some(waker) = slot.waker.take() {
+                waker.wake();
+            }
+        });
+
+        let jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.as_ref()
+            .expect("JVM pool is shutting down")
+            .send(job)
+            .expect("JVM pool threads have exited");
+        PoolFuture { slot }
+    }
+}
+
+impl Drop for JvmPool {
+    fn drop(&mut self) {
+        // Closing the channel makes the threads exit once they have run the remaining jobs
+        drop(self.jobs.lock().unwrap_or_else(|e| e.into_inner()).take());
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Runs `op` on a pool shared by the whole process, with one thread per available CPU, returning a future of its
+/// outcome. The pool is started by the first call.
+///
+/// The operation runs even if the future is dropped before it completes. If the operation panics, so does polling
+/// the future.
+pub fn execute_on_pool<O, R>(op: O) -> PoolFuture<R>
+where
+    O: Send + 'static,
+    for<'jvm> O: JvmOp<Output<'jvm> = R>,
+    R: Send + 'static,
+{
+    static POOL: OnceCell<JvmPool> = OnceCell::new();
+
+    match POOL.get_or_try_init(|| {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        JvmPool::new(threads)
+    }) {
+        Ok(pool) => pool.execute_on_pool(op),
+        Err(e) => PoolFuture {
+            slot: Arc::new(Mutex::new(Slot {
+                outcome: Some(Ok(Err(e))),
+                waker: None,
+            })),
+        },
+    }
+}
+
+struct Slot<R> {
+    outcome: Option<std::thread::Result<GlobalResult<R>>>,
+    waker: Option<Waker>,
+}
+
+/// The outcome of an operation run by a [`JvmPool`], see [`execute_on_pool`].
+pub struct PoolFuture<R> {
+    slot: Arc<Mutex<Slot<R>>>,
+}
+
+impl<R> Future for PoolFuture<R> {
+    type Output = GlobalResult<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        match slot.outcome.take() {
+            Some(Ok(outcome)) => Poll::Ready(outcome),
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
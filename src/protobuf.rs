@@ -0,0 +1,182 @@
+//! Exchanging protobuf messages with Java's generated message classes through their serialized bytes.
+//!
+//! Java protobuf messages are looked up by their conventional `toByteArray()` and `static parseFrom(byte[])` methods,
+//! so no protobuf runtime is needed on the Rust side: the bytes can be encoded and decoded with any Rust protobuf
+//! implementation. With the `prost` feature, [`prost::Message`]s are converted directly, see [`JvmOp::to_prost`] and
+//! [`Jvm::prost_to_java`].
+
+use std::ffi::CString;
+
+use jni_sys::jvalue;
+
+use crate::{
+    find::find_method, java, jvm::JavaObjectExt, raw::ObjectPtr, Error, JavaObject, Jvm, JvmOp,
+    Local, TryJDeref,
+};
+
+/// [`JvmOp`][] that encodes a Java protobuf message, see [`JvmOp::to_proto_bytes`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct ToProtoBytes<This: JvmOp> {
+    this: This,
+}
+
+impl<This> ToProtoBytes<This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: TryJDeref,
+{
+    pub(crate) fn new(this: This) -> Self {
+        Self { this }
+    }
+}
+
+impl<This> JvmOp for ToProtoBytes<This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: TryJDeref,
+{
+    type Output<'jvm> = Vec<u8>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let message = self.this.execute_with(jvm)?;
+        let message = message.try_jderef()?.as_raw();
+
+        let env = jvm.env();
+        // SAFETY: message is a live reference; GetObjectClass never returns null for a non-null object.
+        let class: Local<java::lang::Class> = unsafe {
+            let class =
+                env.invoke_unchecked(|env| env.GetObjectClass, |env, f| f(env, message.as_ptr()));
+            Local::from_raw(env, ObjectPtr::new(class).unwrap())
+        };
+        // Looked up on the runtime class, since the static type may be an interface like `MessageLite`
+        let to_byte_array = find_method(jvm, &class, c"toByteArray", c"()[B", false)?;
+
+        // SAFETY: `to_byte_array` is a `()[B` method of the class of `message`
+        let bytes: Option<Local<java::Array<i8>>> = unsafe {
+            env.invoke(
+                |env| env.CallObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        message.as_ptr(),
+                        to_byte_array.as_ptr(),
+                        std::ptr::null(),
+                    )
+                },
+            )
+        }?;
+        let bytes = bytes.ok_or_else(|| Error::JvmInternal("toByteArray returned null".into()))?;
+        (&*bytes).to_rust().execute_with(jvm)
+    }
+}
+
+impl<'jvm> Jvm<'jvm> {
+    /// Decodes `bytes` (e.g., encoded by a Rust protobuf library) into the Java protobuf message class `T` with its
+    /// generated `static T parseFrom(byte[])` method, which throws `InvalidProtocolBufferException` if `bytes` is
+    /// not a valid message. See [`JvmOp::to_proto_bytes`] for the other direction.
+    pub fn parse_proto<T>(&mut self, bytes: &[u8]) -> crate::Result<'jvm, Local<'jvm, T>>
+    where
+        T: JavaObject,
+    {
+        let class = T::class(self)?;
+        let name: String = class
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(self)?;
+        let descriptor = format!("([B)L{};", name.replace('.', "/"));
+        let descriptor = CString::new(descriptor).unwrap();
+        let parse_from = find_method(self, &class, c"parseFrom", &descriptor, true)?;
+
+        let bytes = bytes.execute_with(self)?;
+        let args = [jvalue {
+            l: bytes.as_raw().as_ptr(),
+        }];
+        let env = self.env();
+        // SAFETY: `parse_from` is a static method of `T` taking a `byte[]` and returning a `T`
+        let message: Option<Local<T>> = unsafe {
+            env.invoke(
+                |env| env.CallStaticObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        class.as_raw().as_ptr(),
+                        parse_from.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }?;
+        message.ok_or_else(|| Error::JvmInternal("parseFrom returned null".into()))
+    }
+}
+
+#[cfg(feature = "prost")]
+mod prost_messages {
+    use std::marker::PhantomData;
+
+    use crate::{error::ConversionError, JavaObject, Jvm, JvmOp, Local, TryJDeref};
+
+    use super::ToProtoBytes;
+
+    /// [`JvmOp`][] that decodes a Java protobuf message into a [`prost::Message`], see [`JvmOp::to_prost`].
+    #[derive_where::derive_where(Copy, Clone)]
+    pub struct ToProst<This: JvmOp, M> {
+        this: This,
+        phantom: PhantomData<fn() -> M>,
+    }
+
+    impl<This, M> ToProst<This, M>
+    where
+        This: JvmOp,
+        for<'jvm> This::Output<'jvm>: TryJDeref,
+        M: prost::Message + Default,
+    {
+        pub(crate) fn new(this: This) -> Self {
+            Self {
+                this,
+                phantom: PhantomData,
+            }
+        }
+    }
+
+    impl<This, M> JvmOp for ToProst<This, M>
+    where
+        This: JvmOp,
+        for<'jvm> This::Output<'jvm>: TryJDeref,
+        M: prost::Message + Default,
+    {
+        type Output<'jvm> = M;
+
+        fn execute_with<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Self::Output<'jvm>> {
+            let bytes = ToProtoBytes::new(self.this).execute_with(jvm)?;
+            M::decode(&bytes[..]).map_err(|e| {
+                ConversionError::Malformed {
+                    value: e.to_string(),
+                    target: std::any::type_name::<M>(),
+                }
+                .into()
+            })
+        }
+    }
+
+    impl<'jvm> Jvm<'jvm> {
+        /// Encodes the Rust `message` and decodes it into the Java protobuf message class `T`, see
+        /// [`Jvm::parse_proto`]. The other direction is [`JvmOp::to_prost`].
+        pub fn prost_to_java<T>(
+            &mut self,
+            message: &impl prost::Message,
+        ) -> crate::Result<'jvm, Local<'jvm, T>>
+        where
+            T: JavaObject,
+        {
+            self.parse_proto(&message.encode_to_vec())
+        }
+    }
+}
+
+#[cfg(feature = "prost")]
+pub use prost_messages::ToProst;
@@ -0,0 +1,335 @@
+//! Implementing Java interfaces with Rust closures, via `java.lang.reflect.Proxy`.
+//!
+//! Calls on the proxy are dispatched by `duchess.RustInvocationHandler` (see `java/duchess`), a small helper class
+//! that duchess defines in the JVM on first use. The handler owns the boxed closure and releases it through a
+//! `java.lang.ref.Cleaner` once the proxy becomes unreachable.
+
+use std::{
+    ffi::{c_char, c_void, CStr},
+    panic::AssertUnwindSafe,
+};
+
+use jni_sys::{jlong, jvalue};
+use once_cell::sync::OnceCell;
+
+use crate::{
+    cast::Upcast,
+    find::find_method,
+    java::{
+        self,
+        lang::{reflect::Method, Class, Object},
+    },
+    jvm::{native_function_with_jvm, JavaObjectExt},
+    memory::{Pinned, PinnedKind},
+    raw::EnvPtr,
+    thread, Error, Global, JavaObject, Jvm, JvmOp, Local,
+};
+
+type Closure = dyn for<'jvm> Fn(&mut Jvm<'jvm>, ProxyCall<'_>) -> crate::Result<'jvm, Option<Local<'jvm, Object>>>
+    + Send
+    + Sync;
+
+// The helper classes are compiled from `java/duchess` by `java/compile.sh`, and checked in so that no JDK is needed
+// to build duchess.
+const HANDLER_CLASS_NAME: &CStr = c"duchess/RustInvocationHandler";
+const HANDLER_CLASS: &[u8] = include_bytes!("../java/classes/duchess/RustInvocationHandler.class");
+
+const RELEASE_CLASS_NAME: &CStr = c"duchess/RustInvocationHandler$Release";
+const RELEASE_CLASS: &[u8] =
+    include_bytes!("../java/classes/duchess/RustInvocationHandler$Release.class");
+
+/// A call to a method of a closure-backed Java interface, see [`Jvm::proxy`].
+#[derive(Copy, Clone)]
+pub struct ProxyCall<'a> {
+    method: &'a Method,
+    args: Option<&'a java::Array<Object>>,
+}
+
+impl<'a> ProxyCall<'a> {
+    /// The interface method that was invoked.
+    pub fn method(&self) -> &'a Method {
+        self.method
+    }
+
+    /// The number of arguments the method was invoked with.
+    pub fn arg_count<'jvm>(&self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, usize> {
+        match self.args {
+            Some(args) => {
+                let len = java::ArrayExt::length(args).execute_with(jvm)?;
+                Ok(len as usize)
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// The argument at `index`, boxed if it is a primitive. Throws `ArrayIndexOutOfBoundsException` if there is no
+    /// such argument.
+    pub fn arg<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        index: usize,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        let Some(args) = self.args else {
+            return Err(Error::JvmInternal(format!(
+                "method was invoked without arguments, cannot get argument {index}"
+            )));
+        };
+        // SAFETY: `args` is a live reference to an `Object[]`
+        unsafe {
+            jvm.env().invoke(
+                |env| env.GetObjectArrayElement,
+                |env, f| f(env, args.as_raw().as_ptr(), index as jni_sys::jsize),
+            )
+        }
+    }
+
+    /// Like [`ProxyCall::arg`], but casts the argument to `T`, reporting an error if it is of some other type.
+    pub fn arg_as<'jvm, T>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        index: usize,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, T>>>
+    where
+        T: Upcast<Object>,
+    {
+        let Some(arg) = self.arg(jvm, index)? else {
+            return Ok(None);
+        };
+        match (&arg).try_downcast::<T>().execute_with(jvm)? {
+            Ok(arg) => Ok(Some(arg)),
+            Err(_) => Err(Error::JvmInternal(format!(
+                "argument {index} is not an instance of `{}`",
+                std::any::type_name::<T>()
+            ))),
+        }
+    }
+}
+
+impl<'jvm> Jvm<'jvm> {
+    /// Creates an object implementing the Java interface `I` whose methods all invoke `f`.
+    ///
+    /// `f` is called on whichever Java thread invokes the proxy, with a [`ProxyCall`] describing the method and its
+    /// arguments. It returns the method's result, which must be boxed for methods returning a primitive and is
+    /// ignored for `void` methods. Errors and panics are rethrown in Java (the latter as `RuntimeException`s). The
+    /// `equals`, `hashCode`, and `toString` methods inherited from `Object` are implemented by identity.
+    ///
+    /// `f` is dropped once the proxy is garbage collected. If `I` is not an interface, an `IllegalArgumentException`
+    /// is thrown.
+    pub fn proxy<I>(
+        &mut self,
+        f: impl for<'a> Fn(&mut Jvm<'a>, ProxyCall<'_>) -> crate::Result<'a, Option<Local<'a, Object>>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> crate::Result<'jvm, Local<'jvm, I>>
+    where
+        I: JavaObject,
+    {
+        let iface = I::class(self)?;
+        let proxy = self.proxy_of_class(&iface, f)?;
+
+        // SAFETY: `Proxy.newProxyInstance` returns an instance of each of the given interfaces
+        let env = self.env();
+        Ok(unsafe { Local::from_raw(env, proxy.into_raw()) })
+    }
+
+    /// Like [`Jvm::proxy`], but for an interface that is only known by its class object (e.g., a nested interface,
+    /// which can't be declared with [`crate::java_package`]).
+    pub(crate) fn proxy_of_class(
+        &mut self,
+        iface: &Class,
+        f: impl for<'a> Fn(&mut Jvm<'a>, ProxyCall<'_>) -> crate::Result<'a, Option<Local<'a, Object>>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> crate::Result<'jvm, Local<'jvm, Object>> {
+        let handler = handler_class(self)?;
+        let new_proxy = find_method(
+            self,
+            &handler,
+            c"newProxy",
+            c"(Ljava/lang/Class;J)Ljava/lang/Object;",
+            true,
+        )?;
+
+        // The closure is counted as pinned memory until it is dropped
+        let size = std::mem::size_of_val(&f) + std::mem::size_of::<Box<Closure>>();
+        let pinned = Pinned::new(self, PinnedKind::Proxy, size)?;
+        let closure: Box<Box<Closure>> = Box::new(Box::new(move |jvm, call| {
+            let _pinned = &pinned;
+            f(jvm, call)
+        }));
+        let closure = Box::into_raw(closure);
+        let args = [
+            jvalue {
+                l: iface.as_raw().as_ptr(),
+            },
+            jvalue {
+                j: closure as jlong,
+            },
+        ];
+
+        // SAFETY: the arguments match the descriptor of `newProxy`, which takes ownership of `closure` (the handler
+        // is created before anything in there can throw).
+        let proxy: Option<Local<'jvm, Object>> = unsafe {
+            self.env().invoke(
+                |env| env.CallStaticObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        handler.as_raw().as_ptr(),
+                        new_proxy.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }?;
+        proxy.ok_or_else(|| Error::JvmInternal("`newProxy` returned null".into()))
+    }
+}
+
+impl java::lang::Runnable {
+    /// Creates a `Runnable` that invokes `f` when run.
+    pub fn from_fn<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        f: impl Fn() + Send + Sync + 'static,
+    ) -> crate::Result<'jvm, Local<'jvm, Self>> {
+        jvm.proxy(move |_jvm, _call| {
+            f();
+            Ok(None)
+        })
+    }
+}
+
+impl<T> java::util::Comparator<T>
+where
+    T: Upcast<Object>,
+{
+    /// Creates a `Comparator` that compares its (non-null) arguments with `f`.
+    pub fn from_fn<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        f: impl for<'a> Fn(&mut Jvm<'a>, &T, &T) -> crate::Result<'a, i32> + Send + Sync + 'static,
+    ) -> crate::Result<'jvm, Local<'jvm, Self>> {
+        jvm.proxy(move |jvm, call| {
+            let a = call.arg_as::<T>(jvm, 0)?.ok_or(Error::NullDeref)?;
+            let b = call.arg_as::<T>(jvm, 1)?.ok_or(Error::NullDeref)?;
+            let ordering = f(jvm, &a, &b)?;
+            let boxed = java::lang::Integer::value_of(ordering).execute_with(jvm)?;
+            Ok(boxed.map(|b| b.upcast()))
+        })
+    }
+}
+
+impl<T, R> java::util::function::Function<T, R>
+where
+    T: Upcast<Object>,
+    R: Upcast<Object>,
+{
+    /// Creates a `Function` that applies `f` to its (possibly null) argument.
+    pub fn from_fn<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        f: impl for<'a> Fn(&mut Jvm<'a>, Option<&T>) -> crate::Result<'a, Option<Local<'a, R>>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> crate::Result<'jvm, Local<'jvm, Self>> {
+        jvm.proxy(move |jvm, call| {
+            let t = call.arg_as::<T>(jvm, 0)?;
+            let r = f(jvm, t.as_deref())?;
+            Ok(r.map(|r| r.upcast()))
+        })
+    }
+}
+
+/// Returns the `RustInvocationHandler` class, defining it (and registering its native methods) on first use.
+fn handler_class<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Local<'jvm, Class>> {
+    static CLASS: OnceCell<Global<Class>> = OnceCell::new();
+    let global = CLASS.get_or_try_init::<_, crate::Error<Local<java::lang::Throwable>>>(|| {
+        // Define the classes in the system class loader so that proxies for application interfaces can see them.
+        let loader = java::lang::ClassLoader::get_system_class_loader().execute_with(jvm)?;
+        let release = define_class(jvm, loader.as_deref(), RELEASE_CLASS_NAME, RELEASE_CLASS)?;
+        let handler = define_class(jvm, loader.as_deref(), HANDLER_CLASS_NAME, HANDLER_CLASS)?;
+        drop(release);
+
+        let natives = [
+            jni_sys::JNINativeMethod {
+                name: c"invokeClosure".as_ptr() as *mut c_char,
+                signature: c"(JLjava/lang/reflect/Method;[Ljava/lang/Object;)Ljava/lang/Object;"
+                    .as_ptr() as *mut c_char,
+                fnPtr: invoke_closure as *mut c_void,
+            },
+            jni_sys::JNINativeMethod {
+                name: c"releaseClosure".as_ptr() as *mut c_char,
+                signature: c"(J)V".as_ptr() as *mut c_char,
+                fnPtr: release_closure as *mut c_void,
+            },
+        ];
+        // SAFETY: the native methods match the declarations in `RustInvocationHandler`
+        unsafe {
+            jvm.env()
+                .register_native_methods(handler.as_raw(), &natives)?
+        };
+
+        Ok(jvm.global(&handler))
+    })?;
+    Ok(jvm.local(global))
+}
+
+/// Defines the class `name` from the class file `bytes` in `loader` (the bootstrap class loader if `None`).
+pub(crate) fn define_class<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    loader: Option<&java::lang::ClassLoader>,
+    name: &CStr,
+    bytes: &[u8],
+) -> crate::Result<'jvm, Local<'jvm, Class>> {
+    let loader = loader.map_or(std::ptr::null_mut(), |l| l.as_raw().as_ptr());
+    // SAFETY: `bytes` is a valid class file for the class `name`
+    let class: Option<Local<Class>> = unsafe {
+        jvm.env().invoke(
+            |env| env.DefineClass,
+            |env, f| {
+                f(
+                    env,
+                    name.as_ptr(),
+                    loader,
+                    bytes.as_ptr() as *const jni_sys::jbyte,
+                    bytes.len() as jni_sys::jsize,
+                )
+            },
+        )
+    }?;
+    class.ok_or_else(|| {
+        Error::JvmInternal(format!(
+            "failed to define class `{}`",
+            name.to_string_lossy()
+        ))
+    })
+}
+
+/// Implementation of `RustInvocationHandler.invokeClosure`.
+unsafe extern "system" fn invoke_closure(
+    env: EnvPtr<'_>,
+    _class: jni_sys::jclass,
+    closure: jlong,
+    method: &Method,
+    args: Option<&java::Array<Object>>,
+) -> jni_sys::jobject {
+    // SAFETY: `closure` was created by `Jvm::proxy` and is live until `release_closure` is called.
+    let closure = unsafe { &*(closure as *const Box<Closure>) };
+    unsafe { native_function_with_jvm(env, |jvm| closure(jvm, ProxyCall { method, args })) }
+}
+
+/// Implementation of `RustInvocationHandler.releaseClosure`.
+unsafe extern "system" fn release_closure(
+    env: EnvPtr<'_>,
+    _class: jni_sys::jclass,
+    closure: jlong,
+) {
+    // The closure may use the JVM while it is dropped
+    let _callback_guard = unsafe { thread::attach_from_jni_callback(env) };
+    // SAFETY: `closure` was created by `Jvm::proxy` and is released exactly once, by the handler's cleaner.
+    let closure = unsafe { Box::from_raw(closure as *mut Box<Closure>) };
+    if std::panic::catch_unwind(AssertUnwindSafe(|| drop(closure))).is_err() {
+        tracing::warn!("panic while dropping closure of a Java proxy");
+    }
+}
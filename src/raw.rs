@@ -5,6 +5,7 @@
 //! The `'static` pointers generally rely on duchess not deinitializing a JVM after it's already initialized.
 
 use std::{
+    cell::Cell,
     ffi::{self},
     marker::PhantomData,
     mem::MaybeUninit,
@@ -13,11 +14,11 @@ use std::{
 
 use jni_sys::jvalue;
 
-use crate::{jvm::JavaObjectExt, Error, GlobalResult, JavaObject, Local};
+use crate::{jvm::JavaObjectExt, Error, GlobalResult, JavaObject, Jvm, Local};
 
 const VERSION: jni_sys::jint = jni_sys::JNI_VERSION_1_8;
 
-/// Get a [`JvmPtr`] to an already initialized JVM (if one exists).
+/// Get a [`JvmPtr`] to an already initialized JVM (if one exists), or an [`Error::MultipleJvms`] if there are several.
 ///
 /// If the `dynlibjvm` feature is enabled and `libjvm` isn't already loaded, it will first force it to be loaded.
 ///
@@ -25,33 +26,52 @@ const VERSION: jni_sys::jint = jni_sys::JNI_VERSION_1_8;
 ///
 /// Caller must ensure that no two threads race to call this fn or [`try_create_jvm()`].
 pub(crate) unsafe fn existing_jvm() -> GlobalResult<Option<JvmPtr>> {
-    let libjvm = crate::libjvm::libjvm_or_load()?;
+    let jvms = unsafe { existing_jvms() }?;
+    match jvms[..] {
+        [] => Ok(None),
+        [jvm] => Ok(Some(jvm)),
+        _ => Err(Error::MultipleJvms(jvms.len())),
+    }
+}
 
-    let mut jvms = [std::ptr::null_mut::<jni_sys::JavaVM>()];
-    let mut num_jvms: jni_sys::jsize = 0;
+/// Get [`JvmPtr`]s to all the initialized JVMs of the process.
+///
+/// If the `dynlibjvm` feature is enabled and `libjvm` isn't already loaded, it will first force it to be loaded.
+///
+/// # Safety
+///
+/// Caller must ensure that no two threads race to call this fn or [`try_create_jvm()`].
+pub(crate) unsafe fn existing_jvms() -> GlobalResult<Vec<JvmPtr>> {
+    let libjvm = crate::libjvm::libjvm_or_load()?;
 
-    let code = unsafe {
-        (libjvm.JNI_GetCreatedJavaVMs)(
-            jvms.as_mut_ptr(),
-            jvms.len().try_into().unwrap(),
-            &mut num_jvms as *mut _,
-        )
+    let get_created_jvms = |jvms: &mut [*mut jni_sys::JavaVM]| {
+        let mut num_jvms: jni_sys::jsize = 0;
+        let code = unsafe {
+            (libjvm.JNI_GetCreatedJavaVMs)(
+                jvms.as_mut_ptr(),
+                jvms.len().try_into().unwrap(),
+                &mut num_jvms as *mut _,
+            )
+        };
+        if code != jni_sys::JNI_OK {
+            return Err(Error::JvmInternal(format!(
+                "GetCreatedJavaVMs failed with code `{code}`"
+            )));
+        }
+        Ok(usize::try_from(num_jvms).unwrap_or(0))
     };
-    if code != jni_sys::JNI_OK {
-        return Err(Error::JvmInternal(format!(
-            "GetCreatedJavaVMs failed with code `{code}`"
-        )));
-    }
 
-    match num_jvms {
-        0 => Ok(None),
-        1 => JvmPtr::new(jvms[0])
-            .ok_or_else(|| Error::JvmInternal("GetCreatedJavaVMs returned null pointer".into()))
-            .map(Some),
-        _ => Err(Error::JvmInternal(format!(
-            "GetCreatedJavaVMs returned more JVMs than expected: `{num_jvms}`"
-        ))),
-    }
+    // The first call only counts the JVMs
+    let num_jvms = get_created_jvms(&mut [])?;
+    let mut jvms = vec![std::ptr::null_mut::<jni_sys::JavaVM>(); num_jvms];
+    let num_jvms = get_created_jvms(&mut jvms)?.min(num_jvms);
+    jvms[..num_jvms]
+        .iter()
+        .map(|&jvm| {
+            JvmPtr::new(jvm)
+                .ok_or_else(|| Error::JvmInternal("GetCreatedJavaVMs returned null pointer".into()))
+        })
+        .collect()
 }
 
 /// Try to initialize a new JVM with the provided `options`, returning a [`JvmPtr`] on success or an
@@ -127,6 +147,10 @@ impl JvmPtr {
         NonNull::new(ptr).map(Self)
     }
 
+    pub(crate) fn as_ptr(self) -> *mut jni_sys::JavaVM {
+        self.0.as_ptr()
+    }
+
     /// Returns an [`EnvPtr`] which can be used to invoke JNI methods on the current thread. Will return
     /// `None` if the current thread isn't attached to the JVM.
     ///
@@ -150,17 +174,24 @@ impl JvmPtr {
     }
 
     /// Attaches the current thread to the JVM and returns an [`EnvPtr`] that can be used to invoke JNI methods.
-    /// Multiple calls on the same thread are idempotent.
+    /// Daemon threads, attached with `AttachCurrentThreadAsDaemon`, don't keep the JVM from shutting down. Multiple
+    /// calls on the same thread are idempotent: a thread that is attached already stays (or doesn't stay) a daemon.
     ///
     /// # Safety
     ///
     /// The caller must ensure that the `'jvm` lifetime will not live past when the current thread is detached from the
     /// JVM.
-    pub(crate) unsafe fn attach_thread<'jvm>(self) -> GlobalResult<EnvPtr<'jvm>> {
+    pub(crate) unsafe fn attach_thread<'jvm>(self, daemon: bool) -> GlobalResult<EnvPtr<'jvm>> {
         let mut env_ptr = std::ptr::null_mut::<ffi::c_void>();
         match fn_table_call(
             self.0,
-            |jvm| jvm.AttachCurrentThread,
+            |jvm| {
+                if daemon {
+                    jvm.AttachCurrentThreadAsDaemon
+                } else {
+                    jvm.AttachCurrentThread
+                }
+            },
             |jvm, f| {
                 f(
                     jvm,
@@ -169,7 +200,14 @@ impl JvmPtr {
                 )
             },
         ) {
-            jni_sys::JNI_OK => Ok(EnvPtr::new(env_ptr.cast()).unwrap()),
+            jni_sys::JNI_OK => {
+                #[cfg(feature = "count-crossings")]
+                crate::crossings::attach();
+                Ok(EnvPtr::new(env_ptr.cast()).unwrap())
+            }
+            code if daemon => Err(Error::JvmInternal(format!(
+                "AttachCurrentThreadAsDaemon failed with code `{code}`"
+            ))),
             code => Err(Error::JvmInternal(format!(
                 "AttachCurrentThread failed with code `{code}`"
             ))),
@@ -229,6 +267,11 @@ impl<'jvm> EnvPtr<'jvm> {
         })
     }
 
+    /// Returns the raw `JNIEnv` pointer, e.g. for passing to other JNI libraries.
+    pub fn as_ptr(self) -> *mut jni_sys::JNIEnv {
+        self.ptr.as_ptr()
+    }
+
     /// Invoke a JNI method dispatched through a virtual table lookup. Used by codegen to make most JNI calls and so
     /// must be public.
     ///
@@ -239,6 +282,7 @@ impl<'jvm> EnvPtr<'jvm> {
     ///
     /// The caller must ensure that the [`jni_sys::JNIEnv`] raw pointer is only used for this invocation.
     #[doc(hidden)]
+    #[cfg_attr(feature = "checked-env", track_caller)]
     pub unsafe fn invoke<F, T: FromJniValue<'jvm>>(
         self,
         fn_field: impl FnOnce(&jni_sys::JNINativeInterface_) -> Option<F>,
@@ -253,6 +297,33 @@ impl<'jvm> EnvPtr<'jvm> {
         Ok(value)
     }
 
+    /// Invoke a JNI function that creates an object, like `NewObjectA`, which only returns null when it fails (with an
+    /// exception pending). Used to call constructors, see [`crate::call::new_object`].
+    ///
+    /// Unlike [`Self::invoke()`], a non-null result is returned without checking for an exception, since it proves
+    /// there is none. The result is `Ok(None)` only if the function returned null without an exception.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the [`jni_sys::JNIEnv`] raw pointer is only used for this invocation, and that the
+    /// JNI function returns a new local reference to an object of type `T`.
+    #[doc(hidden)]
+    #[cfg_attr(feature = "checked-env", track_caller)]
+    pub unsafe fn invoke_new_object<F, T: JavaObject>(
+        self,
+        fn_field: impl FnOnce(&jni_sys::JNINativeInterface_) -> Option<F>,
+        call: impl FnOnce(*mut jni_sys::JNIEnv, F) -> jni_sys::jobject,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, T>>> {
+        match ObjectPtr::new(self.invoke_unchecked(fn_field, call)) {
+            // SAFETY: the object is a new local ref of type `T`
+            Some(obj) => Ok(Some(unsafe { Local::from_raw(self, obj) })),
+            None => {
+                self.check_exception()?;
+                Ok(None)
+            }
+        }
+    }
+
     /// Invoke a JNI method dispatched through a virtual table lookup. Does *not* check for an exception and should
     /// only be used when other mechanisms can prove the absence of an exception (e.g. a non-null return value or a
     /// separate call to [`Self::check_exception()`]).
@@ -260,14 +331,63 @@ impl<'jvm> EnvPtr<'jvm> {
     /// # Safety
     ///
     /// The caller must ensure that the [`jni_sys::JNIEnv`] raw pointer is only used for this invocation.
+    #[cfg_attr(feature = "checked-env", track_caller)]
     pub(crate) unsafe fn invoke_unchecked<F, T>(
         self,
         fn_field: impl FnOnce(&jni_sys::JNINativeInterface_) -> Option<F>,
         call: impl FnOnce(*mut jni_sys::JNIEnv, F) -> T,
     ) -> T {
+        #[cfg(all(feature = "checked-env", debug_assertions))]
+        self.assert_current_thread();
+        #[cfg(feature = "count-crossings")]
+        crate::crossings::jni_call();
         fn_table_call(self.ptr, fn_field, call)
     }
 
+    /// Panics unless this is the environment of the current thread, which JNI requires of every call. Since
+    /// environments are only valid on their own thread, other uses would (at best) crash the JVM.
+    ///
+    /// Environments in use by the duchess frames of the current thread pass without asking the JVM, see
+    /// [`crate::checked_env`].
+    #[cfg(all(feature = "checked-env", debug_assertions))]
+    #[track_caller]
+    fn assert_current_thread(self) {
+        use crate::checked_env;
+
+        if checked_env::in_use_here(self) {
+            return;
+        }
+        let Some(jvm) = crate::jvm::try_global_jvm() else {
+            return;
+        };
+        // The threads are only described once the check has failed
+        let thread = || checked_env::describe(&std::thread::current());
+        let owner = || match checked_env::owner(self) {
+            Some(owner) => format!(" (in use by thread `{}`)", checked_env::describe(&owner)),
+            None => String::new(),
+        };
+        // SAFETY: the environment of the current thread is only compared
+        match unsafe { jvm.env() } {
+            Ok(Some(current)) if current.ptr == self.ptr => {}
+            Ok(Some(current)) => panic!(
+                "JNI environment {:?}{} used on thread `{}`, whose environment is {:?}: environments must not be \
+                 used from other threads",
+                self.ptr,
+                owner(),
+                thread(),
+                current.ptr,
+            ),
+            Ok(None) => panic!(
+                "JNI environment {:?}{} used on thread `{}`, which isn't attached to the JVM: environments must not \
+                 be used from other threads, or after their thread is detached",
+                self.ptr,
+                owner(),
+                thread(),
+            ),
+            Err(_) => {}
+        }
+    }
+
     /// Loads the JVM pointer from this environment.
     /// Returns Err if there is some sort of error.
     ///
@@ -314,15 +434,32 @@ impl<'jvm> EnvPtr<'jvm> {
         }
     }
 
+    /// Returns (and clears) the pending exception, if any. Called after every JNI call that may throw, so it only
+    /// checks with `ExceptionCheck`, which doesn't create a local reference, and takes the exception when there is one.
+    #[inline]
+    #[cfg_attr(feature = "checked-env", track_caller)]
     pub fn check_exception(self) -> crate::Result<'jvm, ()> {
+        // SAFETY: we don't hold on to the return env ptr
+        let pending = unsafe { self.invoke_unchecked(|env| env.ExceptionCheck, |env, f| f(env)) };
+        if pending == jni_sys::JNI_FALSE {
+            Ok(())
+        } else {
+            self.take_exception()
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn take_exception(self) -> crate::Result<'jvm, ()> {
         // SAFETY: we don't hold on to the return env ptr
         let thrown = unsafe { self.invoke_unchecked(|env| env.ExceptionOccurred, |env, f| f(env)) };
-        if let Some(thrown) = ObjectPtr::new(thrown) {
-            unsafe { self.invoke_unchecked(|env| env.ExceptionClear, |env, f| f(env)) };
+        unsafe { self.invoke_unchecked(|env| env.ExceptionClear, |env, f| f(env)) };
+        match ObjectPtr::new(thrown) {
             // SAFETY: the ptr returned by ExceptionOccurred is already a local ref and must be an instance of Throwable
-            Err(Error::Thrown(unsafe { Local::from_raw(self, thrown) }))
-        } else {
-            Ok(())
+            Some(thrown) => Err(Error::Thrown(unsafe { Local::from_raw(self, thrown) })),
+            None => Err(Error::JvmInternal(
+                "an exception was pending, but `ExceptionOccurred` returned null".into(),
+            )),
         }
     }
 }
@@ -429,6 +566,47 @@ impl<T: JavaObject> IntoJniValue for Option<&T> {
     }
 }
 
+/// The number of arguments of a JNI call that [`with_jni_args`] keeps on the stack.
+const INLINE_JNI_ARGS: usize = 8;
+
+thread_local! {
+    /// Reused for the arguments of calls with more than [`INLINE_JNI_ARGS`] arguments.
+    static SPILLED_JNI_ARGS: Cell<Vec<jvalue>> = const { Cell::new(Vec::new()) };
+}
+
+/// Converts the arguments of a JNI call whose number is only known at runtime with `to_jni`, and passes them to `op`,
+/// without allocating (generated code passes arrays instead, since it knows the number of arguments). Up to
+/// [`INLINE_JNI_ARGS`] arguments are kept on the stack, and more in a per-thread buffer that is reused across calls.
+///
+/// The buffer is taken out of its thread-local while `op` runs, so calls made by `op` (e.g. by Java code calling back
+/// into Rust) use another one rather than overwriting it.
+pub(crate) fn with_jni_args<'jvm, T, R>(
+    jvm: &mut Jvm<'jvm>,
+    args: impl IntoIterator<Item = T>,
+    mut to_jni: impl FnMut(&mut Jvm<'jvm>, T) -> crate::Result<'jvm, jvalue>,
+    op: impl FnOnce(&mut Jvm<'jvm>, &[jvalue]) -> crate::Result<'jvm, R>,
+) -> crate::Result<'jvm, R> {
+    let mut args = args.into_iter();
+    let mut inline = [jvalue { j: 0 }; INLINE_JNI_ARGS];
+    let mut len = 0;
+    while let Some(arg) = args.next() {
+        if len == INLINE_JNI_ARGS {
+            let mut spilled = SPILLED_JNI_ARGS.take();
+            spilled.clear();
+            spilled.extend_from_slice(&inline);
+            let result = std::iter::once(arg)
+                .chain(args)
+                .try_for_each(|arg| Ok(spilled.push(to_jni(jvm, arg)?)))
+                .and_then(|()| op(jvm, &spilled));
+            SPILLED_JNI_ARGS.set(spilled);
+            return result;
+        }
+        inline[len] = to_jni(jvm, arg)?;
+        len += 1;
+    }
+    op(jvm, &inline[..len])
+}
+
 /// Trait used by codegen to extract the return value of a JNI call.
 #[doc(hidden)]
 pub trait FromJniValue<'jvm> {
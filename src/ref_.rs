@@ -1,12 +1,23 @@
-use std::{marker::PhantomData, ops::Deref, ptr::NonNull};
+use std::{
+    fmt::{self, Debug, Display},
+    marker::PhantomData,
+    ops::Deref,
+    ptr::NonNull,
+};
 
-use crate::jvm::JavaObjectExt;
+use crate::jvm::{is_same_object, try_global_jvm, JavaObjectExt};
+use crate::scope::UNSCOPED;
 use crate::thread;
-use crate::{cast::Upcast, jvm::CloneIn, plumbing::ObjectPtr, raw::EnvPtr, JavaObject, Jvm};
+use crate::{
+    cast::Upcast, java::lang::Object, jvm::CloneIn, plumbing::ObjectPtr, prelude::*, raw::EnvPtr,
+    JavaObject, Jvm,
+};
 
 /// An owned local reference to a non-null Java object of type `T`. The reference will be freed when
 /// dropped. Cannot be shared across threads or [`Jvm::with`] invocations.
-#[derive_where::derive_where(PartialEq, Eq, Hash, Debug)]
+///
+/// Its `Debug` format and [`Local::display`] call the object's `toString()` method.
+#[derive_where::derive_where(PartialEq, Eq, Hash)]
 pub struct Local<'jvm, T: JavaObject> {
     env: EnvPtr<'jvm>,
     obj: ObjectPtr,
@@ -54,15 +65,57 @@ impl<'jvm, T: JavaObject> Local<'jvm, T> {
         std::mem::forget(self);
         p
     }
+
+    /// Whether `this` and `other` refer to the same Java object (JNI's `IsSameObject`), i.e., Java's `==`. Unlike
+    /// `==` on `Local`s, which compares the references themselves, this is true for two references to one object.
+    ///
+    /// Panics in the closure of [`JavaArray::with_elements`](crate::java::Array::with_elements), which can't make JNI
+    /// calls.
+    pub fn ptr_eq(this: &Self, other: &impl JavaObject) -> bool {
+        crate::array::check_not_in_critical_section()
+            .expect("unable to compare references while accessing array elements");
+        is_same_object(&mut Jvm(this.env, UNSCOPED), &**this, other)
+    }
+
+    /// Checks that `self.env` is the JNI env of the current thread, i.e., that it is still sound to use it.
+    fn env_is_current(&self) -> bool {
+        let Some(jvm) = crate::jvm::try_global_jvm() else {
+            return false;
+        };
+        // SAFETY: the returned env is only compared, never used.
+        match unsafe { jvm.env() } {
+            Ok(Some(env)) => env == self.env,
+            Ok(None) | Err(_) => false,
+        }
+    }
 }
 
 impl<T: JavaObject> Drop for Local<'_, T> {
     fn drop(&mut self) {
-        // SAFETY: Local owns the local ref and it's no longer possible to dereference the object pointer.
-        unsafe {
-            self.env
-                .invoke_unchecked(|jni| jni.DeleteLocalRef, |jni, f| f(jni, self.obj.as_ptr()));
+        if crate::scope::leave_to_frame(self.env) {
+            return;
         }
+
+        if !self.env_is_current() {
+            // Deleting a local ref through an env that does not belong to this thread is UB, so
+            // leaking the reference is the only safe option. This indicates a bug in unsafe code,
+            // so we fail loudly in debug builds (unless that would turn into a double panic).
+            if cfg!(debug_assertions) && !std::thread::panicking() {
+                panic!("`Local` dropped outside of the JNI env that created it");
+            }
+            tracing::warn!("leaking `Local` dropped outside of the JNI env that created it");
+            return;
+        }
+
+        let (env, obj) = (self.env.as_ptr(), self.obj);
+        crate::array::delete_outside_critical_section(move || {
+            // SAFETY: Local owns the local ref and it's no longer possible to dereference the object pointer. `env`
+            // is the env of the current thread, on which deferred deletes run.
+            unsafe {
+                let env = EnvPtr::new(env).unwrap();
+                env.invoke_unchecked(|jni| jni.DeleteLocalRef, |jni, f| f(jni, obj.as_ptr()));
+            }
+        });
     }
 }
 
@@ -76,6 +129,9 @@ impl<T: JavaObject> Deref for Local<'_, T> {
 }
 
 /// An owned global reference to a non-null Java object of type `T`. The reference will be freed when dropped.
+///
+/// Its `Debug` format and [`Global::display`] call the object's `toString()` method, attaching the current thread to
+/// the JVM if need be.
 #[derive_where::derive_where(PartialEq, Eq, Hash)]
 pub struct Global<T: JavaObject> {
     obj: ObjectPtr,
@@ -106,36 +162,52 @@ impl<T: JavaObject> Global<T> {
             Self::from_raw(NonNull::new(new_ref).unwrap().into())
         }
     }
+
+    /// Whether `this` and `other` refer to the same Java object (JNI's `IsSameObject`), i.e., Java's `==`. Unlike
+    /// `==` on `Global`s, which compares the references themselves, this is true for two references to one object.
+    /// To compare objects with their `equals` methods, see [`JavaEq`](crate::JavaEq).
+    ///
+    /// # Panics
+    ///
+    /// If the current thread can't be attached to the JVM.
+    pub fn ptr_eq(this: &Self, other: &impl JavaObject) -> bool {
+        Jvm::with(|jvm| Ok(is_same_object(jvm, &**this, other)))
+            .expect("unable to attach to the JVM to compare references")
+    }
 }
 
 impl<T: JavaObject> Drop for Global<T> {
     fn drop(&mut self) {
-        let jvm = crate::jvm::unwrap_global_jvm();
-
-        // SAFETY: Global owns the global ref and it's no longer possible to dereference the object pointer.
-        let delete = |env: EnvPtr<'_>| unsafe {
-            env.invoke_unchecked(
-                |jni| jni.DeleteGlobalRef,
-                |jni, f| f(jni, self.obj.as_ptr()),
-            )
-        };
+        let obj = self.obj;
+        crate::array::delete_outside_critical_section(move || {
+            // SAFETY: Global owns the global ref and it's no longer possible to dereference the object pointer.
+            with_any_env("delete global ref", |env| unsafe {
+                env.invoke_unchecked(|jni| jni.DeleteGlobalRef, |jni, f| f(jni, obj.as_ptr()))
+            });
+        });
+    }
+}
 
-        match unsafe { jvm.env() } {
-            Ok(Some(env)) => delete(env),
-            Ok(None) => {
-                // SAFETY: jvm is a valid pointer since duchess will not deinitialize a JVM once created
-                match unsafe { thread::attach(jvm) } {
-                    Ok(mut attached) => delete(attached.env()),
-                    Err(err) => {
-                        tracing::warn!(?err, "unable to attach current thread to delete global ref")
-                    }
+/// Runs `f` with the env of the current thread, attaching it to the JVM for the duration of the call if necessary.
+/// Used to release global (and weak global) refs, which may be dropped on any thread.
+fn with_any_env(what: &str, f: impl FnOnce(EnvPtr<'_>)) {
+    let jvm = crate::jvm::unwrap_global_jvm();
+
+    match unsafe { jvm.env() } {
+        Ok(Some(env)) => f(env),
+        Ok(None) => {
+            // SAFETY: jvm is a valid pointer since duchess will not deinitialize a JVM once created
+            match unsafe { thread::attach(|| Ok(jvm)) } {
+                Ok(mut attached) => f(attached.env()),
+                Err(err) => {
+                    tracing::warn!(?err, "unable to attach current thread to {what}")
                 }
             }
-            Err(err) => tracing::warn!(
-                ?err,
-                "unable to get JNI interface for local thread to delete global ref"
-            ),
         }
+        Err(err) => tracing::warn!(
+            ?err,
+            "unable to get JNI interface for local thread to {what}"
+        ),
     }
 }
 
@@ -152,6 +224,141 @@ impl<T: JavaObject> Deref for Global<T> {
     }
 }
 
+impl<'jvm, T: JavaObject> Local<'jvm, T> {
+    /// Formats the object with its `toString()`, e.g. for logging: `tracing::info!(list = %list.display())`.
+    ///
+    /// This is an adapter rather than a `Display` impl so that `to_string()` keeps calling the Java method.
+    pub fn display(&self) -> JavaDisplay<'_, T> {
+        JavaDisplay {
+            obj: self.env_is_current().then_some(&**self),
+        }
+    }
+}
+
+impl<T: JavaObject> Debug for Local<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_debug(
+            f,
+            "Local",
+            self.obj,
+            self.env_is_current().then_some(&**self),
+        )
+    }
+}
+
+impl<T: JavaObject> Global<T> {
+    /// Formats the object with its `toString()`, like [`Local::display`].
+    pub fn display(&self) -> JavaDisplay<'_, T> {
+        JavaDisplay { obj: Some(&**self) }
+    }
+}
+
+impl<T: JavaObject> Debug for Global<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_debug(f, "Global", self.obj, Some(&**self))
+    }
+}
+
+/// Formats a Java object with its `toString()`, or a placeholder if it can't be called (e.g. because it throws), see
+/// [`Local::display`] and [`Global::display`].
+pub struct JavaDisplay<'a, T: JavaObject> {
+    obj: Option<&'a T>,
+}
+
+impl<T: JavaObject> Display for JavaDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.obj.and_then(describe) {
+            Some((_, string)) => f.write_str(&string),
+            None => f.write_str("<unable to call toString>"),
+        }
+    }
+}
+
+/// Formats `obj` as e.g. `Global<java.util.ArrayList>("[1, 2]")`, or with its pointer if its class and `toString()`
+/// can't be looked up.
+fn fmt_debug<T: JavaObject>(
+    f: &mut fmt::Formatter<'_>,
+    reference: &str,
+    ptr: ObjectPtr,
+    obj: Option<&T>,
+) -> fmt::Result {
+    match obj.and_then(describe) {
+        Some((class, string)) => write!(f, "{reference}<{class}>({string:?})"),
+        None => write!(f, "{reference}({:p})", ptr.as_ptr()),
+    }
+}
+
+/// The name of the runtime class of `obj` and its `toString()` (`"null"` if that returns null), or `None` if they can't
+/// be looked up. Formatting never launches a JVM, so this is `None` until one exists.
+fn describe<T: JavaObject>(obj: &T) -> Option<(String, String)> {
+    try_global_jvm()?;
+    Jvm::with(|jvm| {
+        // SAFETY: every Java object is an `Object`
+        let obj = unsafe { Object::from_raw(obj.as_raw()) };
+        let class: String = obj
+            .get_class()
+            .assert_not_null()
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let string: Option<String> = obj.to_string().to_rust().execute_with(jvm)?;
+        Ok((class, string.unwrap_or_else(|| "null".into())))
+    })
+    .ok()
+}
+
+/// An owned weak global reference to a Java object of type `T`, which does not prevent the object from being garbage
+/// collected. Use [`Weak::upgrade`] to get a (strong) [`Local`] reference to the object if it is still alive. The
+/// reference will be freed when dropped.
+#[derive_where::derive_where(PartialEq, Eq, Hash)]
+pub struct Weak<T: JavaObject> {
+    obj: ObjectPtr,
+    _marker: PhantomData<T>,
+}
+
+impl<T: JavaObject> Weak<T> {
+    /// Creates a new weak global reference to `obj` via a `NewWeakGlobalRef` JNI call.
+    pub(crate) fn new(env: EnvPtr<'_>, obj: &T) -> Self {
+        // SAFETY: The JavaObject trait contract ensures that &T points to a Java object that is an instance of T.
+        unsafe {
+            let new_ref =
+                env.invoke_unchecked(|e| e.NewWeakGlobalRef, |e, f| f(e, obj.as_raw().as_ptr()));
+            Self {
+                obj: NonNull::new(new_ref).unwrap().into(),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// Returns a new local reference to the object, or `None` if it has already been garbage collected.
+    pub fn upgrade<'jvm>(&self, jvm: &mut Jvm<'jvm>) -> Option<Local<'jvm, T>> {
+        let env = jvm.env();
+        // SAFETY: self.obj is a live weak global ref to an instance of T. `NewLocalRef` returns null once the object
+        // has been collected, and otherwise a local ref that keeps it alive.
+        unsafe {
+            let new_ref = env.invoke_unchecked(|e| e.NewLocalRef, |e, f| f(e, self.obj.as_ptr()));
+            NonNull::new(new_ref).map(|obj| Local::from_raw(env, obj.into()))
+        }
+    }
+}
+
+impl<T: JavaObject> Drop for Weak<T> {
+    fn drop(&mut self) {
+        let obj = self.obj;
+        crate::array::delete_outside_critical_section(move || {
+            // SAFETY: Weak owns the weak global ref and it's no longer possible to use the object pointer.
+            with_any_env("delete weak global ref", |env| unsafe {
+                env.invoke_unchecked(|jni| jni.DeleteWeakGlobalRef, |jni, f| f(jni, obj.as_ptr()))
+            });
+        });
+    }
+}
+
+// SAFETY: The JNI promises weak global refs are shareable across threads, like global refs
+unsafe impl<T: JavaObject> Send for Weak<T> {}
+unsafe impl<T: JavaObject> Sync for Weak<T> {}
+
 impl<'a, R, S> AsRef<S> for Local<'a, R>
 where
     R: Upcast<S>,
@@ -169,9 +376,10 @@ impl<'a, R: JavaObject> Local<'a, R> {
         R: Upcast<S>,
         S: JavaObject + 'a,
     {
-        // SAFETY: From the Upcast trait contract, we know R is also an instance of S
-        let upcast = unsafe { Local::<S>::from_raw(self.env, self.obj) };
-        upcast
+        // SAFETY: From the Upcast trait contract, we know R is also an instance of S; ownership of the local ref moves
+        // to the result.
+        let env = self.env;
+        unsafe { Local::<S>::from_raw(env, self.into_raw()) }
     }
 }
 
@@ -192,9 +400,11 @@ impl<R: JavaObject> Global<R> {
         R: Upcast<S>,
         S: JavaObject + 'static,
     {
-        // SAFETY: From the Upcast trait contract, we know R is also an instance of S
-        let upcast = unsafe { Global::<S>::from_raw(self.obj) };
-        upcast
+        // SAFETY: From the Upcast trait contract, we know R is also an instance of S; ownership of the global ref moves
+        // to the result.
+        let obj = self.obj;
+        std::mem::forget(self);
+        unsafe { Global::<S>::from_raw(obj) }
     }
 }
 
@@ -0,0 +1,235 @@
+//! The duchess scopes of the current thread (`Jvm::with`, JNI callbacks and local frames): their depth, which tells
+//! how long the refs of a [`Jvm`](crate::Jvm) may be borrowed, the refs borrowed until they end, and whether dropped
+//! locals can be left for the frame of the scope to release.
+
+use std::cell::{Cell, RefCell};
+
+use crate::raw::{EnvPtr, ObjectPtr};
+
+/// The depth of the [`Jvm`](crate::Jvm)s that don't belong to a scope, whose refs can't be borrowed.
+pub(crate) const UNSCOPED: usize = 0;
+
+// The innermost duchess scope (i.e., `Jvm::with`, a JNI callback or a local frame) of the current thread, and the
+// borrowed refs (see `keep_until_scope_end`) that are deleted when their scope ends. The scope is tracked in a `Cell`,
+// and the borrowed refs are only looked at by scopes that borrowed some, so that entering and leaving a scope is cheap.
+thread_local! {
+    static SCOPE: Cell<Scope> = const { Cell::new(Scope::NONE) };
+    static BORROWED: RefCell<Vec<Borrowed>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Clone, Copy)]
+struct Scope {
+    /// The env of the scope (null outside of scopes), only used to check that locals belong to it.
+    env: *mut jni_sys::JNIEnv,
+    /// The number of scopes this one is nested in, plus one (so [`UNSCOPED`] outside of scopes).
+    depth: usize,
+    /// The index in [`BORROWED`] of the first ref borrowed in the scope, if it borrowed any.
+    borrowed_from: Option<usize>,
+    /// Whether the scope is the outermost one of a local frame that is popped when it ends, see [`enter_fused`].
+    fused: bool,
+    /// The number of dropped local refs that were left for the frame to release, for fused scopes.
+    left_to_frame: usize,
+}
+
+impl Scope {
+    const NONE: Self = Scope {
+        env: std::ptr::null_mut(),
+        depth: UNSCOPED,
+        borrowed_from: None,
+        fused: false,
+        left_to_frame: 0,
+    };
+}
+
+/// A ref borrowed with [`keep_until_scope_end`].
+#[derive(Clone, Copy)]
+struct Borrowed {
+    obj: ObjectPtr,
+    /// The depth of the scope until whose end the ref is kept, which may enclose the scope it was borrowed in.
+    depth: usize,
+    /// Whether `obj` is a global ref, rather than a local one.
+    global: bool,
+}
+
+/// Starts a scope for the local refs created from `env`, which ends (restoring the enclosing scope, if any) when the
+/// returned guard is dropped. Refs borrowed in the scope are deleted then.
+///
+/// # Safety
+///
+/// `env` must be the env of the current thread and remain valid until the guard is dropped. The guard must be dropped
+/// before any local refs borrowed in the scope are invalidated by the JVM (e.g., before a JNI callback returns).
+#[inline]
+pub(crate) unsafe fn enter(env: EnvPtr<'_>) -> DeleteScope<'_> {
+    enter_scope(env, false)
+}
+
+/// Like [`enter`], for a scope that runs in a local frame of its own: the refs dropped in it aren't deleted one by one,
+/// but left for `PopLocalFrame` to release all at once when the scope ends (unless there are more of them than the
+/// frame has room for). This saves the `DeleteLocalRef` calls of the intermediate results of chained operations, see
+/// [`JvmOp::execute`].
+///
+/// # Safety
+///
+/// As for [`enter`], and the frame must be popped right after the guard is dropped. The local refs dropped in the scope
+/// must have been created in that frame, so no refs from enclosing frames may be moved into it.
+///
+/// [`JvmOp::execute`]: crate::JvmOp::execute
+#[inline]
+pub(crate) unsafe fn enter_fused(env: EnvPtr<'_>) -> DeleteScope<'_> {
+    enter_scope(env, true)
+}
+
+#[inline]
+fn enter_scope(env: EnvPtr<'_>, fused: bool) -> DeleteScope<'_> {
+    let outer = SCOPE.get();
+    SCOPE.set(Scope {
+        env: env.as_ptr(),
+        depth: outer.depth + 1,
+        borrowed_from: None,
+        fused,
+        left_to_frame: 0,
+    });
+    DeleteScope { env, outer }
+}
+
+/// Whether the local ref of `env` being dropped can be left for the frame of the active scope to release, instead of
+/// being deleted right away. The caller must own the local ref and never use it again once this returns `true`.
+pub(crate) fn leave_to_frame(env: EnvPtr<'_>) -> bool {
+    // `try_with` because locals may be dropped from other thread-local destructors during thread exit.
+    SCOPE
+        .try_with(|cell| {
+            let scope = cell.get();
+            if !scope.fused
+                || scope.env != env.as_ptr()
+                || scope.left_to_frame >= fused_flush_threshold()
+            {
+                return false;
+            }
+            cell.set(Scope {
+                left_to_frame: scope.left_to_frame + 1,
+                ..scope
+            });
+            true
+        })
+        .unwrap_or(false)
+}
+
+/// Number of local refs dropped in a fused scope (see [`enter_fused`]) that are left for `PopLocalFrame` to release,
+/// which is the capacity of its frame. Past that, they are deleted as they are dropped, so that the frame doesn't grow
+/// far beyond its capacity.
+fn fused_flush_threshold() -> usize {
+    crate::config::local_frame_capacity() as usize
+}
+
+/// Keeps the local ref `obj` alive until the scope of depth `depth` (see [`DeleteScope::depth`]) for `env` ends, then
+/// deletes it, returning the ref to use meanwhile. Returns `None` (and does nothing) if there is no such scope. The
+/// caller must own the local ref and never use or delete it itself once this returns a ref.
+///
+/// If the scope isn't the innermost one (e.g., for a `Jvm` of an enclosing `Jvm::with`, used in a nested one), `obj`
+/// may live in a local frame pushed by a nested scope, which would release it before the scope ends. So it is
+/// replaced by a global ref then.
+pub(crate) fn keep_until_scope_end(
+    env: EnvPtr<'_>,
+    depth: usize,
+    obj: ObjectPtr,
+) -> Option<ObjectPtr> {
+    let scope = SCOPE.try_with(Cell::get).ok()?;
+    if scope.env != env.as_ptr() || depth == UNSCOPED || depth > scope.depth {
+        return None;
+    }
+    let global = depth < scope.depth;
+    let obj = if global {
+        // SAFETY: `obj` is a live local ref of `env`, which the caller owns
+        unsafe {
+            let global =
+                env.invoke_unchecked(|jni| jni.NewGlobalRef, |jni, f| f(jni, obj.as_ptr()));
+            let global = ObjectPtr::new(global)?;
+            env.invoke_unchecked(|jni| jni.DeleteLocalRef, |jni, f| f(jni, obj.as_ptr()));
+            global
+        }
+    } else {
+        obj
+    };
+    let Ok(Some(index)) = BORROWED.try_with(|borrowed| {
+        let mut borrowed = borrowed.try_borrow_mut().ok()?;
+        borrowed.push(Borrowed { obj, depth, global });
+        Some(borrowed.len() - 1)
+    }) else {
+        // The global ref is leaked, like the local ref would be
+        return None;
+    };
+    if scope.borrowed_from.is_none() {
+        SCOPE.set(Scope {
+            borrowed_from: Some(index),
+            ..scope
+        });
+    }
+    Some(obj)
+}
+
+/// Guard returned by [`enter`].
+pub(crate) struct DeleteScope<'jvm> {
+    /// The env of the scope, which is still valid when the guard is dropped.
+    env: EnvPtr<'jvm>,
+    /// The enclosing scope, restored when this one ends.
+    outer: Scope,
+}
+
+impl Drop for DeleteScope<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        let scope = SCOPE.replace(self.outer);
+        if let Some(from) = scope.borrowed_from {
+            self.delete_borrowed(from, scope);
+        }
+    }
+}
+
+impl DeleteScope<'_> {
+    /// The depth of the scope, which identifies it among the scopes of the thread while it is active.
+    pub(crate) fn depth(&self) -> usize {
+        self.outer.depth + 1
+    }
+
+    /// Deletes the refs borrowed in `scope` from index `from` on, except for those kept for enclosing scopes, which are
+    /// handed to the enclosing scope instead.
+    #[cold]
+    fn delete_borrowed(&self, from: usize, scope: Scope) {
+        BORROWED.with(|borrowed| {
+            let mut borrowed = borrowed.borrow_mut();
+            let mut kept = from;
+            for index in from..borrowed.len() {
+                let Borrowed { obj, depth, global } = borrowed[index];
+                if depth < scope.depth {
+                    borrowed[kept] = borrowed[index];
+                    kept += 1;
+                } else if global {
+                    // SAFETY: `obj` is a live global ref that is only used during the scope
+                    unsafe {
+                        self.env.invoke_unchecked(
+                            |jni| jni.DeleteGlobalRef,
+                            |jni, f| f(jni, obj.as_ptr()),
+                        );
+                    }
+                } else if !scope.fused {
+                    // SAFETY: `obj` was a live local ref borrowed in this scope, whose env is still valid. In fused
+                    // scopes, popping the frame deletes them all at once instead.
+                    unsafe {
+                        self.env.invoke_unchecked(
+                            |jni| jni.DeleteLocalRef,
+                            |jni, f| f(jni, obj.as_ptr()),
+                        );
+                    }
+                }
+            }
+            borrowed.truncate(kept);
+            // The refs of the enclosing scope (if any) come before `from`
+            if kept > from && self.outer.borrowed_from.is_none() {
+                SCOPE.set(Scope {
+                    borrowed_from: Some(from),
+                    ..self.outer
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,348 @@
+//! Embedding scripting through the JVM, for applications that let their users extend them with scripts.
+//!
+//! Two kinds of script engines are supported:
+//!
+//! * [`JShell`] evaluates Java snippets, such as `"int x = 40 + 2;"` or `"x * 2"`, with the JDK's `jdk.jshell`
+//!   module. Snippets evaluated by the same shell share their state, as in the interactive `jshell` tool.
+//!   [`eval`] is a shortcut that evaluates snippets in a shell shared by the whole program.
+//! * [`ScriptEngine`] uses a `javax.script` engine for other languages (e.g., `graal.js` or `groovy`), whose
+//!   implementation must be on the class path. Rust values can be bound to variables of the script with
+//!   [`ScriptEngine::put`].
+
+use std::sync::Mutex;
+
+use jni_sys::jvalue;
+use once_cell::sync::OnceCell;
+
+use crate::{
+    find::{find_class, find_method},
+    java::{self, lang::Object},
+    jvm::JavaObjectExt,
+    raw::MethodPtr,
+    Error, Global, GlobalResult, IntoJava, JavaObject, Jvm, JvmOp, Local, ToJava,
+};
+
+mod auto {
+    // Make current crate available as `duchess` for use by the generated code.
+    #[cfg(not(doctest))]
+    use crate as duchess;
+
+    duchess_macro::java_package! {
+        package javax.script;
+
+        public class javax.script.ScriptEngineManager {
+            public javax.script.ScriptEngineManager();
+            public javax.script.ScriptEngine getEngineByName(java.lang.String);
+            public javax.script.ScriptEngine getEngineByExtension(java.lang.String);
+        }
+
+        public interface javax.script.ScriptEngine {
+            public abstract java.lang.Object eval(java.lang.String) throws javax.script.ScriptException;
+            public abstract void put(java.lang.String, java.lang.Object);
+            public abstract java.lang.Object get(java.lang.String);
+        }
+
+        public class javax.script.ScriptException extends java.lang.Exception {
+            public javax.script.ScriptException(java.lang.String);
+        }
+
+        package jdk.jshell;
+
+        public class jdk.jshell.JShell {
+            public java.util.List<jdk.jshell.SnippetEvent> eval(java.lang.String) throws java.lang.IllegalStateException;
+            public java.util.stream.Stream<jdk.jshell.Diag> diagnostics(jdk.jshell.Snippet);
+            public void close();
+        }
+
+        public class jdk.jshell.SnippetEvent {
+            public jdk.jshell.Snippet snippet();
+            public jdk.jshell.JShellException exception();
+            public java.lang.String value();
+        }
+
+        public abstract class jdk.jshell.Snippet {
+            public java.lang.String source();
+        }
+
+        public abstract class jdk.jshell.Diag {
+            public abstract java.lang.String getMessage(java.util.Locale);
+        }
+
+        public class jdk.jshell.JShellException extends java.lang.Exception {
+        }
+    }
+}
+
+pub use auto::{javax, jdk};
+
+/// A `jdk.jshell.JShell` that evaluates Java snippets in the current JVM.
+///
+/// The shell isn't safe to use from several threads at the same time, so it should be guarded (e.g., by a `Mutex`)
+/// when it is shared.
+pub struct JShell {
+    shell: Global<jdk::jshell::JShell>,
+}
+
+impl JShell {
+    /// Creates a shell that runs snippets in the current JVM (rather than in a separate process, which is what
+    /// `JShell.create()` does).
+    pub fn new<'jvm>(jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self> {
+        // Built by hand since `JShell.Builder` is a nested class
+        let shell_class = jdk::jshell::JShell::class(jvm)?;
+        let builder = find_method(
+            jvm,
+            &shell_class,
+            c"builder",
+            c"()Ljdk/jshell/JShell$Builder;",
+            true,
+        )?;
+        // SAFETY: `builder` is a static method without parameters that returns an object
+        let builder: Option<Local<Object>> = unsafe {
+            jvm.env().invoke(
+                |env| env.CallStaticObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        shell_class.as_raw().as_ptr(),
+                        builder.as_ptr(),
+                        std::ptr::null(),
+                    )
+                },
+            )
+        }?;
+        let builder = builder.ok_or(Error::NullDeref)?;
+
+        let builder_class = find_class(jvm, c"jdk/jshell/JShell$Builder")?;
+        let execution_engine = find_method(
+            jvm,
+            &builder_class,
+            c"executionEngine",
+            c"(Ljava/lang/String;)Ljdk/jshell/JShell$Builder;",
+            false,
+        )?;
+        let local = "local"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        // SAFETY: `executionEngine` is a method of the builder taking a string and returning an object
+        let _: Option<Local<Object>> = unsafe {
+            call_object_method(
+                jvm,
+                &*builder,
+                execution_engine,
+                &[jvalue {
+                    l: local.as_raw().as_ptr(),
+                }],
+            )
+        }?;
+
+        let build = find_method(
+            jvm,
+            &builder_class,
+            c"build",
+            c"()Ljdk/jshell/JShell;",
+            false,
+        )?;
+        // SAFETY: `build` is a method of the builder without parameters that returns a `JShell`
+        let shell: Option<Local<jdk::jshell::JShell>> =
+            unsafe { call_object_method(jvm, &*builder, build, &[]) }?;
+        let shell = shell.ok_or(Error::NullDeref)?;
+        Ok(JShell {
+            shell: jvm.global(&*shell),
+        })
+    }
+
+    /// Evaluates a single Java snippet (a declaration, statement, or expression), returning the value of the snippet
+    /// as JShell displays it (e.g., `"84"` or `"\"a string\""`), if it has one.
+    ///
+    /// An exception thrown by the snippet is returned as a `jdk.jshell.EvalException` (whose message is that of the
+    /// original exception), and a snippet that doesn't compile as a `javax.script.ScriptException` with the
+    /// compiler's diagnostics.
+    pub fn eval<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        snippet: &str,
+    ) -> crate::Result<'jvm, Option<String>> {
+        let events = self
+            .shell
+            .eval(snippet)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        // The first event is for the snippet itself, the others for snippets it updated
+        if events.is_empty().execute_with(jvm)? {
+            return Ok(None);
+        }
+        let event = events.get(0).assert_not_null().execute_with(jvm)?;
+
+        if let Some(exception) = event.exception().execute_with(jvm)? {
+            return Err(Error::Thrown(exception.upcast()));
+        }
+        if snippet_status(jvm, &event)? == "REJECTED" {
+            let message = self.diagnostics(jvm, &event)?;
+            let exception =
+                javax::script::ScriptException::new(message.as_str()).execute_with(jvm)?;
+            return Err(Error::Thrown(exception.upcast()));
+        }
+        event.value().to_rust().execute_with(jvm)
+    }
+
+    /// Describes why the snippet of `event` was rejected.
+    fn diagnostics<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        event: &jdk::jshell::SnippetEvent,
+    ) -> crate::Result<'jvm, String> {
+        let snippet = event.snippet().assert_not_null().execute_with(jvm)?;
+        let source: String = snippet
+            .source()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let diagnostics = self
+            .shell
+            .diagnostics(&snippet)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let diagnostics = diagnostics.to_list().assert_not_null().execute_with(jvm)?;
+        let locale = java::util::Locale::get_default().execute_with(jvm)?;
+
+        let mut messages = vec![];
+        for index in 0..diagnostics.size().execute_with(jvm)? {
+            let diagnostic = diagnostics.get(index).assert_not_null().execute_with(jvm)?;
+            let message: Option<String> = diagnostic
+                .get_message(&locale)
+                .to_rust()
+                .execute_with(jvm)?;
+            messages.extend(message);
+        }
+        Ok(format!(
+            "snippet `{source}` was rejected: {}",
+            messages.join("; ")
+        ))
+    }
+
+    /// Closes the shell, releasing its resources (otherwise, that's left to the garbage collector).
+    pub fn close<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ()> {
+        self.shell.close().execute_with(jvm)
+    }
+
+    pub fn as_java(&self) -> &Global<jdk::jshell::JShell> {
+        &self.shell
+    }
+}
+
+/// Evaluates a Java snippet with [`JShell::eval`], in a shell that is shared by the whole program (so that
+/// declarations made by one snippet are visible to the following ones).
+pub fn eval(snippet: &str) -> GlobalResult<Option<String>> {
+    static SHELL: OnceCell<Mutex<JShell>> = OnceCell::new();
+
+    let shell = SHELL.get_or_try_init(|| Jvm::with(|jvm| Ok(Mutex::new(JShell::new(jvm)?))))?;
+    let shell = shell.lock().unwrap_or_else(|e| e.into_inner());
+    Jvm::with(|jvm| shell.eval(jvm, snippet))
+}
+
+/// A `javax.script.ScriptEngine`, see the [module docs](self).
+pub struct ScriptEngine {
+    engine: Global<javax::script::ScriptEngine>,
+}
+
+impl ScriptEngine {
+    /// The engine registered under `name` (e.g., `"groovy"`), if any.
+    pub fn by_name<'jvm>(jvm: &mut Jvm<'jvm>, name: &str) -> crate::Result<'jvm, Option<Self>> {
+        let manager = javax::script::ScriptEngineManager::new().execute_with(jvm)?;
+        let engine = manager.get_engine_by_name(name).execute_with(jvm)?;
+        Ok(engine.map(|engine| Self::new(jvm, &engine)))
+    }
+
+    /// The engine for files with the extension `extension` (e.g., `"js"`), if any.
+    pub fn by_extension<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        extension: &str,
+    ) -> crate::Result<'jvm, Option<Self>> {
+        let manager = javax::script::ScriptEngineManager::new().execute_with(jvm)?;
+        let engine = manager
+            .get_engine_by_extension(extension)
+            .execute_with(jvm)?;
+        Ok(engine.map(|engine| Self::new(jvm, &engine)))
+    }
+
+    pub fn new(jvm: &mut Jvm<'_>, engine: &javax::script::ScriptEngine) -> Self {
+        ScriptEngine {
+            engine: jvm.global(engine),
+        }
+    }
+
+    /// Binds the variable `name` of the scripts to `value`, e.g. a Rust string or a Java object.
+    pub fn put<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        name: &str,
+        value: impl IntoJava<Object>,
+    ) -> crate::Result<'jvm, ()> {
+        self.engine.put(name, value).execute_with(jvm)
+    }
+
+    /// The value of the variable `name` of the scripts.
+    pub fn get<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        name: &str,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        self.engine.get(name).execute_with(jvm)
+    }
+
+    /// Evaluates `script`, returning its value. Scripts that fail throw a `javax.script.ScriptException`.
+    pub fn eval<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        script: &str,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, Object>>> {
+        self.engine.eval(script).execute_with(jvm)
+    }
+
+    pub fn as_java(&self) -> &Global<javax::script::ScriptEngine> {
+        &self.engine
+    }
+}
+
+/// The name of the `Snippet.Status` of `event` (e.g., `VALID` or `REJECTED`), which is looked up by hand since it is
+/// a nested class.
+fn snippet_status<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    event: &jdk::jshell::SnippetEvent,
+) -> crate::Result<'jvm, String> {
+    let event_class = jdk::jshell::SnippetEvent::class(jvm)?;
+    let status = find_method(
+        jvm,
+        &event_class,
+        c"status",
+        c"()Ljdk/jshell/Snippet$Status;",
+        false,
+    )?;
+    // SAFETY: `status` is a method of the event without parameters that returns an object
+    let status: Option<Local<Object>> = unsafe { call_object_method(jvm, event, status, &[]) }?;
+    let status = status.ok_or(Error::NullDeref)?;
+    status
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)
+}
+
+/// # Safety
+///
+/// `method` must be a method of `this` that takes `args` and returns an object of type `R`.
+unsafe fn call_object_method<'jvm, R>(
+    jvm: &mut Jvm<'jvm>,
+    this: &impl JavaObject,
+    method: MethodPtr,
+    args: &[jvalue],
+) -> crate::Result<'jvm, Option<Local<'jvm, R>>>
+where
+    R: JavaObject,
+{
+    jvm.env().invoke(
+        |env| env.CallObjectMethodA,
+        |env, f| f(env, this.as_raw().as_ptr(), method.as_ptr(), args.as_ptr()),
+    )
+}
@@ -0,0 +1,137 @@
+use jni_sys::jvalue;
+
+use crate::{
+    cast::Upcast,
+    find::{find_class, find_method},
+    java,
+    jvm::JavaObjectExt,
+    AsJRef, Error, Jvm, JvmOp, Local,
+};
+
+/// [`JvmOp`][] that writes a Java object with `ObjectOutputStream`, see [`JvmOp::serialize`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct Serialize<This: JvmOp> {
+    this: This,
+}
+
+impl<This> Serialize<This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<java::lang::Object>,
+{
+    pub(crate) fn new(this: This) -> Self {
+        Self { this }
+    }
+}
+
+impl<This> JvmOp for Serialize<This>
+where
+    This: JvmOp,
+    for<'jvm> This::Output<'jvm>: AsJRef<java::lang::Object>,
+{
+    type Output<'jvm> = Vec<u8>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let instance = self.this.execute_with(jvm)?;
+        let instance = instance.as_jref()?;
+
+        let bytes = java::io::ByteArrayOutputStream::new().execute_with(jvm)?;
+        let out = java::io::ObjectOutputStream::new(&bytes).execute_with(jvm)?;
+        // Closed even if writing fails, but the first exception wins
+        let written = out.write_object(instance).execute_with(jvm);
+        let closed = out.close().execute_with(jvm);
+        written?;
+        closed?;
+        bytes
+            .to_byte_array()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)
+    }
+}
+
+impl<'jvm> Jvm<'jvm> {
+    /// Reads back an object written by [`JvmOp::serialize`] (or by any `ObjectOutputStream`), which
+    /// may have been in another process. Returns `None` if a null reference was serialized.
+    ///
+    /// **Deserializing untrusted bytes is dangerous**: `readObject` instantiates whatever classes the
+    /// bytes name, and the deserialization code of classes on the class path (so-called gadgets) can
+    /// be abused to run arbitrary code. So the classes that may be deserialized are restricted by
+    /// `filter`, which is consulted for every object in the stream (see `ObjectInputFilter`); create
+    /// one from a pattern like `"java.util.*;java.lang.*;!*"` with [`Jvm::object_input_filter`]. The
+    /// JVM-wide filter (set with the `jdk.serialFilter` property), if any, is replaced by it.
+    ///
+    /// The classes of the serialized objects are resolved by `ObjectInputStream`, so they must be
+    /// on the class path. If the object is not a `T`, a [`Error::JvmInternal`] is returned.
+    pub fn deserialize<T>(
+        &mut self,
+        bytes: &[u8],
+        filter: &java::io::ObjectInputFilter,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, T>>>
+    where
+        T: Upcast<java::lang::Object>,
+    {
+        let input = java::io::ByteArrayInputStream::new(bytes).execute_with(self)?;
+        let input = java::io::ObjectInputStream::new(&input).execute_with(self)?;
+        // Closed even if reading fails, but the first exception wins
+        let object = match input.set_object_input_filter(filter).execute_with(self) {
+            Ok(()) => input.read_object().execute_with(self),
+            Err(e) => Err(e),
+        };
+        let closed = input.close().execute_with(self);
+        let object = object?;
+        closed?;
+        let Some(object) = object else {
+            return Ok(None);
+        };
+
+        match object.try_downcast::<T>().execute_with(self)? {
+            Ok(object) => Ok(Some(object)),
+            Err(_) => Err(Error::JvmInternal(format!(
+                "deserialized object is not an instance of `{}`",
+                std::any::type_name::<T>()
+            ))),
+        }
+    }
+
+    /// Creates a filter for [`Jvm::deserialize`] from `pattern`, with the syntax of
+    /// `ObjectInputFilter.Config.createFilter`: `;`-separated class names or patterns (e.g.
+    /// `java.util.*`), which are rejected if prefixed by `!`, and limits like `maxdepth=10`.
+    /// Classes that no pattern matches are left undecided, so end the pattern with `!*` to reject
+    /// them. Fails with an `IllegalArgumentException` if the pattern is malformed.
+    pub fn object_input_filter(
+        &mut self,
+        pattern: &str,
+    ) -> crate::Result<'jvm, Local<'jvm, java::io::ObjectInputFilter>> {
+        // Looked up by hand since `Config` is a nested class
+        let config_class = find_class(self, c"java/io/ObjectInputFilter$Config")?;
+        let create_filter = find_method(
+            self,
+            &config_class,
+            c"createFilter",
+            c"(Ljava/lang/String;)Ljava/io/ObjectInputFilter;",
+            true,
+        )?;
+        let pattern = pattern.execute_with(self)?;
+        let args = [jvalue {
+            l: pattern.as_raw().as_ptr(),
+        }];
+        let env = self.env();
+        // SAFETY: `create_filter` is a static method of `config_class` taking a `String`
+        let filter: Option<Local<java::io::ObjectInputFilter>> = unsafe {
+            env.invoke(
+                |env| env.CallStaticObjectMethodA,
+                |env, f| {
+                    f(
+                        env,
+                        config_class.as_raw().as_ptr(),
+                        create_filter.as_ptr(),
+                        args.as_ptr(),
+                    )
+                },
+            )
+        }?;
+        // `createFilter` returns null for an empty pattern
+        filter.ok_or_else(|| Error::JvmInternal("the filter pattern is empty".into()))
+    }
+}
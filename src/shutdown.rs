@@ -0,0 +1,94 @@
+use std::{
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::{
+    java::lang::{Runnable, Runtime, Thread},
+    prelude::*,
+    thread, Error, GlobalResult, Jvm,
+};
+
+static INSTALLED: OnceCell<()> = OnceCell::new();
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static CALLBACKS: Mutex<Vec<Box<dyn FnOnce() + Send>>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// Set on the thread of the shutdown hook, whose callbacks may still use the JVM.
+    static RUNNING_CALLBACKS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Makes the JVM shut down in order when the process is terminated, e.g. with Ctrl-C. Calling it again does nothing.
+///
+/// The JVM handles `SIGINT`, `SIGTERM` and `SIGHUP` itself (unless it was started with `-Xrs`), by running its
+/// shutdown hooks and then exiting the process, as it does for `System.exit`. Without the bridge, the Rust threads that
+/// are using the JVM meanwhile carry on until the process is gone. The bridge installs a shutdown hook that:
+///
+/// * makes new operations (i.e., new calls to [`Jvm::with`], including through [`JvmOp::execute`]) fail with
+///   [`Error::ShuttingDown`], except on the threads that are inside one already, so that they can finish;
+/// * then runs the callbacks registered with [`on_shutdown`], e.g. to stop worker pools, in the order they were
+///   registered.
+///
+/// The callbacks run on the thread of the hook, where the JVM can still be used. The other shutdown hooks (including
+/// those of Java code) run concurrently.
+pub fn install_shutdown_bridge() -> GlobalResult<()> {
+    INSTALLED.get_or_try_init(|| {
+        Jvm::with(|jvm| {
+            let hook = Runnable::from_fn(jvm, shut_down)?;
+            let thread = Thread::new(&hook, "duchess shutdown bridge").execute_with(jvm)?;
+            Runtime::get_runtime()
+                .assert_not_null()
+                .add_shutdown_hook(&thread)
+                .execute_with(jvm)
+        })
+    })?;
+    Ok(())
+}
+
+/// Registers `callback` to run when the JVM shuts down, see [`install_shutdown_bridge`]. Callbacks registered once the
+/// JVM is shutting down never run.
+pub fn on_shutdown(callback: impl FnOnce() + Send + 'static) {
+    CALLBACKS.lock().unwrap().push(Box::new(callback));
+}
+
+/// Whether the JVM is shutting down, once [`install_shutdown_bridge`] was called.
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::Acquire)
+}
+
+/// The body of the shutdown hook.
+fn shut_down() {
+    SHUTTING_DOWN.store(true, Ordering::Release);
+    RUNNING_CALLBACKS.with(|running| running.set(true));
+    let callbacks = std::mem::take(&mut *CALLBACKS.lock().unwrap());
+    for callback in callbacks {
+        if panic::catch_unwind(AssertUnwindSafe(callback)).is_err() {
+            tracing::warn!("a shutdown callback panicked");
+        }
+    }
+}
+
+/// Fails with [`Error::ShuttingDown`] if a new operation can't start on the current thread.
+#[inline]
+pub(crate) fn check_accepting() -> GlobalResult<()> {
+    if is_shutting_down() {
+        check_accepting_during_shutdown()
+    } else {
+        Ok(())
+    }
+}
+
+#[cold]
+fn check_accepting_during_shutdown() -> GlobalResult<()> {
+    if thread::in_duchess_frame() || RUNNING_CALLBACKS.with(Cell::get) {
+        Ok(())
+    } else {
+        Err(Error::ShuttingDown)
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt::Display;
+
+use crate::{
+    java::lang::{StackTraceElement, Throwable},
+    AsJRef, IntoRust, Jvm, JvmOp,
+};
+
+/// The details of a Java exception, copied into Rust so that they can be displayed without a JVM, see
+/// [`Error::with_backtrace`](crate::Error::with_backtrace).
+///
+/// Displays like Java's `Throwable.printStackTrace()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JavaStackTrace {
+    /// The fully qualified name of the class of the exception, e.g. `java.io.IOException`.
+    pub class_name: String,
+    pub message: Option<String>,
+    /// The stack frames where the exception was created, innermost first.
+    pub frames: Vec<JavaStackFrame>,
+    /// The stack trace of the exception's cause, if any.
+    pub cause: Option<Box<JavaStackTrace>>,
+}
+
+/// A frame of a [`JavaStackTrace`], see `java.lang.StackTraceElement`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JavaStackFrame {
+    pub class_name: String,
+    pub method_name: String,
+    pub file_name: Option<String>,
+    /// `None` if unknown, e.g. for native methods.
+    pub line_number: Option<u32>,
+    pub is_native: bool,
+}
+
+/// Bounds the chain of causes that is captured, since Java allows it to be cyclic.
+const MAX_CAUSES: usize = 32;
+
+impl JavaStackTrace {
+    /// Copies the class, message, and stack trace of `exception` and its causes.
+    pub fn capture<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &impl AsJRef<Throwable>,
+    ) -> crate::Result<'jvm, Self> {
+        Self::capture_with_causes(jvm, exception.as_jref()?, MAX_CAUSES)
+    }
+
+    fn capture_with_causes<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &Throwable,
+        max_causes: usize,
+    ) -> crate::Result<'jvm, Self> {
+        let class = exception.get_class().assert_not_null().execute_with(jvm)?;
+        let class_name: String = class
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let message: Option<String> = exception.get_message().to_rust().execute_with(jvm)?;
+        let frames: Vec<JavaStackFrame> = exception
+            .get_stack_trace()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+
+        let cause = match exception.get_cause().execute_with(jvm)? {
+            Some(cause) if max_causes > 0 => Some(Box::new(Self::capture_with_causes(
+                jvm,
+                &cause,
+                max_causes - 1,
+            )?)),
+            _ => None,
+        };
+
+        Ok(JavaStackTrace {
+            class_name,
+            message,
+            frames,
+            cause,
+        })
+    }
+}
+
+impl IntoRust<JavaStackFrame> for &StackTraceElement {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, JavaStackFrame> {
+        let line_number = self.get_line_number().execute_with(jvm)?;
+        Ok(JavaStackFrame {
+            class_name: self
+                .get_class_name()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?,
+            method_name: self
+                .get_method_name()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?,
+            file_name: self.get_file_name().to_rust().execute_with(jvm)?,
+            // Negative for unknown lines (-1) and native methods (-2)
+            line_number: u32::try_from(line_number).ok(),
+            is_native: self.is_native_method().execute_with(jvm)?,
+        })
+    }
+}
+
+impl JavaStackTrace {
+    /// Formats the exception and its frames, but not its causes.
+    pub(crate) fn fmt_exception(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.class_name)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        for frame in &self.frames {
+            write!(f, "\n\tat {frame}")?;
+        }
+        Ok(())
+    }
+
+    /// The cause of the exception, as the [`source`](std::error::Error::source) of an error.
+    pub(crate) fn cause_as_error(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let cause = self.cause.as_deref()?;
+        Some(Cause::new(cause))
+    }
+}
+
+impl Display for JavaStackTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut trace = self;
+        loop {
+            trace.fmt_exception(f)?;
+            match &trace.cause {
+                Some(cause) => {
+                    write!(f, "\nCaused by: ")?;
+                    trace = cause;
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+}
+
+/// A level of the chain of causes of a [`JavaStackTrace`] as an error, which displays only that exception, so that
+/// error reports walking the [`source`](std::error::Error::source) chain show each exception once.
+#[derive(Debug)]
+#[repr(transparent)]
+struct Cause(JavaStackTrace);
+
+impl Cause {
+    fn new(trace: &JavaStackTrace) -> &Cause {
+        // SAFETY: `Cause` is a transparent wrapper around `JavaStackTrace`
+        unsafe { &*(trace as *const JavaStackTrace as *const Cause) }
+    }
+}
+
+impl Display for Cause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_exception(f)
+    }
+}
+
+impl std::error::Error for Cause {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.cause_as_error()
+    }
+}
+
+impl Display for JavaStackFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}(", self.class_name, self.method_name)?;
+        match (&self.file_name, self.line_number) {
+            _ if self.is_native => write!(f, "Native Method")?,
+            (Some(file), Some(line)) => write!(f, "{file}:{line}")?,
+            (Some(file), None) => write!(f, "{file}")?,
+            (None, _) => write!(f, "Unknown Source")?,
+        }
+        write!(f, ")")
+    }
+}
@@ -1,32 +1,76 @@
 use std::ffi::{c_char, CString};
 
 use crate::{
-    into_rust::IntoRust, java::lang::String as JavaString, jvm::JavaObjectExt, Error, Jvm, JvmOp,
-    Local,
+    error::ConversionError,
+    into_rust::{into_rust_or_conversion_error, IntoRust, TryIntoRust},
+    java::lang::String as JavaString,
+    jvm::JavaObjectExt,
+    Error, Jvm, JvmOp, Local,
 };
 
+/// Strings shorter than this (in bytes) that are ASCII without nul bytes are given to `NewStringUTF` from a buffer on
+/// the stack, since they are the same in Modified UTF-8 (see [`Config::string_fast_path`](crate::Config::string_fast_path)).
+const FAST_PATH_MAX_LEN: usize = 128;
+
 impl JvmOp for &str {
     type Output<'jvm> = Local<'jvm, JavaString>;
 
+    const LOCALS: usize = 1;
+
     fn execute_with<'jvm>(
         self,
         jvm: &mut Jvm<'jvm>,
     ) -> crate::Result<'jvm, Local<'jvm, JavaString>> {
+        if self.len() < FAST_PATH_MAX_LEN
+            && self.bytes().all(|b| b != 0 && b.is_ascii())
+            && crate::config::string_fast_path()
+        {
+            let mut buffer = [0u8; FAST_PATH_MAX_LEN];
+            buffer[..self.len()].copy_from_slice(self.as_bytes());
+            // SAFETY: the buffer holds the string in Modified UTF-8, followed by at least one nul byte
+            return unsafe { new_string_utf(jvm, buffer.as_ptr().cast()) };
+        }
+
         let encoded = cesu8::to_java_cesu8(self);
         // SAFETY: cesu8 encodes interior nul bytes as 0xC080
         let c_string = unsafe { CString::from_vec_unchecked(encoded.into_owned()) };
-
-        let env = jvm.env();
         // SAFETY: c_string is non-null pointer to cesu8-encoded encoded string ending in a trailing nul byte
-        let string: Option<Local<JavaString>> =
-            unsafe { env.invoke(|env| env.NewStringUTF, |env, f| f(env, c_string.as_ptr())) }?;
-        string.ok_or_else(|| Error::JvmInternal("JVM faild to create new String".into()))
+        unsafe { new_string_utf(jvm, c_string.as_ptr()) }
     }
 }
 
+/// Creates a Java string from `string`, which must be nul-terminated Modified UTF-8.
+unsafe fn new_string_utf<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    string: *const c_char,
+) -> crate::Result<'jvm, Local<'jvm, JavaString>> {
+    let env = jvm.env();
+    let string: Option<Local<JavaString>> =
+        unsafe { env.invoke(|env| env.NewStringUTF, |env, f| f(env, string)) }?;
+    string.ok_or_else(|| Error::JvmInternal("JVM faild to create new String".into()))
+}
+
+/// Creates a Java string from its UTF-16 code units, which may include unpaired surrogates (unlike a Rust string).
+#[cfg(windows)]
+pub(crate) fn new_string_utf16<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    units: &[u16],
+) -> crate::Result<'jvm, Local<'jvm, JavaString>> {
+    let Ok(len) = units.len().try_into() else {
+        return Err(Error::SliceTooLong(units.len()));
+    };
+    let env = jvm.env();
+    // SAFETY: units points to `len` UTF-16 code units
+    let string: Option<Local<JavaString>> =
+        unsafe { env.invoke(|env| env.NewString, |env, f| f(env, units.as_ptr(), len)) }?;
+    string.ok_or_else(|| Error::JvmInternal("JVM faild to create new String".into()))
+}
+
 impl JvmOp for &String {
     type Output<'jvm> = Local<'jvm, JavaString>;
 
+    const LOCALS: usize = 1;
+
     fn execute_with<'jvm>(
         self,
         jvm: &mut Jvm<'jvm>,
@@ -36,7 +80,18 @@ impl JvmOp for &String {
 }
 
 impl IntoRust<String> for &JavaString {
+    const LOCALS: usize = 0;
+
     fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, String> {
+        into_rust_or_conversion_error(self, jvm)
+    }
+}
+
+impl TryIntoRust<String> for &JavaString {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<String, ConversionError>> {
         let str_raw = self.as_raw();
 
         let env = jvm.env();
@@ -55,7 +110,7 @@ impl IntoRust<String> for &JavaString {
         // Shortcut for common case of empty strings. This also avoids us trying to write
         // to the null ptr of an empty Vec
         if cesu8_len == 0 {
-            return Ok(String::new());
+            return Ok(Ok(String::new()));
         }
         // java uses signed lengths
         assert!(cesu8_len > 0);
@@ -90,15 +145,10 @@ impl IntoRust<String> for &JavaString {
         // Rust String. Otherwise, we'll need to use the cesu8 crate to convert properly. Note that this is the same
         // first check done by cesu8, but because the interface takes an &[u8], it would force a copy.
         let decoded = match String::from_utf8(cesu_bytes) {
-            Ok(s) => s,
+            Ok(s) => Ok(s),
             Err(err) => cesu8::from_java_cesu8(err.as_bytes())
-                .map_err(|e| {
-                    Error::JvmInternal(format!(
-                        "Java String contained invalid Modified UTF-8: {}",
-                        e
-                    ))
-                })?
-                .into_owned(),
+                .map(|s| s.into_owned())
+                .map_err(|e| ConversionError::InvalidString(e.to_string())),
         };
 
         Ok(decoded)
@@ -0,0 +1,38 @@
+//! Java system properties and the environment of the process as the JVM sees it, e.g. to configure Java libraries
+//! from Rust before they are first used.
+
+use crate::{java, Jvm, JvmOp};
+
+impl<'jvm> Jvm<'jvm> {
+    /// The value of the system property `key` (e.g. `java.version`), with `System.getProperty`, or `None` if it isn't
+    /// set.
+    pub fn get_property(&mut self, key: &str) -> crate::Result<'jvm, Option<String>> {
+        java::lang::System::get_property(key)
+            .to_rust()
+            .execute_with(self)
+    }
+
+    /// Sets the system property `key` to `value`, with `System.setProperty`, returning its previous value. Libraries
+    /// often read their properties once, when their classes are initialized, so set them before using the library.
+    pub fn set_property(&mut self, key: &str, value: &str) -> crate::Result<'jvm, Option<String>> {
+        java::lang::System::set_property(key, value)
+            .to_rust()
+            .execute_with(self)
+    }
+
+    /// Removes the system property `key`, with `System.clearProperty`, returning its previous value.
+    pub fn clear_property(&mut self, key: &str) -> crate::Result<'jvm, Option<String>> {
+        java::lang::System::clear_property(key)
+            .to_rust()
+            .execute_with(self)
+    }
+
+    /// The value of the environment variable `name`, with `System.getenv`, or `None` if it isn't set. The JVM reads the
+    /// environment once, the first time it is asked for it, so this may not see the changes that Rust code makes with
+    /// [`std::env::set_var`].
+    pub fn get_env_var(&mut self, name: &str) -> crate::Result<'jvm, Option<String>> {
+        java::lang::System::getenv(name)
+            .to_rust()
+            .execute_with(self)
+    }
+}
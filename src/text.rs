@@ -0,0 +1,174 @@
+//! Case conversion and Unicode normalization done by Java, for code that must agree exactly with Java's Unicode
+//! tables (which may differ from those of Rust's standard library, depending on the versions of both).
+//!
+//! Each operation takes a Rust string and produces a Rust string, converting to and from a Java string around a
+//! single Java call:
+//!
+//! ```rust,no_run
+//! # use duchess::prelude::*;
+//! use duchess::text::{self, Form};
+//!
+//! # fn main() -> duchess::GlobalResult<()> {
+//! let upper = text::to_upper_case("straße").execute()?;
+//! assert_eq!(upper, "STRASSE");
+//! let composed = text::normalize("e\u{301}", Form::Nfc).execute()?;
+//! assert_eq!(composed, "\u{e9}");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Case conversion uses `Locale.ROOT`, so that the result doesn't depend on the default locale of the JVM (which
+//! e.g. for Turkish maps `i` to `İ`).
+
+use std::ffi::CStr;
+
+use jni_sys::jvalue;
+
+use crate::{
+    find::{find_class, find_field, find_method},
+    java::{self, lang::Object},
+    jvm::JavaObjectExt,
+    Error, Jvm, JvmOp, Local, ToJava,
+};
+
+/// The Unicode normalization forms of `java.text.Normalizer.Form`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Form {
+    /// Canonical decomposition, followed by canonical composition.
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility decomposition, followed by canonical composition.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+impl Form {
+    fn field_name(self) -> &'static CStr {
+        match self {
+            Form::Nfc => c"NFC",
+            Form::Nfd => c"NFD",
+            Form::Nfkc => c"NFKC",
+            Form::Nfkd => c"NFKD",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Transform {
+    UpperCase,
+    LowerCase,
+    Normalize(Form),
+}
+
+/// [`JvmOp`][] that transforms a Rust string with Java, see [`to_upper_case`], [`to_lower_case`] and [`normalize`].
+#[derive(Copy, Clone, Debug)]
+pub struct Text<'a> {
+    text: &'a str,
+    transform: Transform,
+}
+
+/// Upper cases `text` with `String.toUpperCase(Locale.ROOT)`.
+pub fn to_upper_case(text: &str) -> Text<'_> {
+    Text {
+        text,
+        transform: Transform::UpperCase,
+    }
+}
+
+/// Lower cases `text` with `String.toLowerCase(Locale.ROOT)`.
+pub fn to_lower_case(text: &str) -> Text<'_> {
+    Text {
+        text,
+        transform: Transform::LowerCase,
+    }
+}
+
+/// Normalizes `text` to `form` with `java.text.Normalizer.normalize`.
+pub fn normalize(text: &str, form: Form) -> Text<'_> {
+    Text {
+        text,
+        transform: Transform::Normalize(form),
+    }
+}
+
+impl JvmOp for Text<'_> {
+    type Output<'jvm> = String;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let string = self
+            .text
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let result = match self.transform {
+            Transform::UpperCase => string
+                .to_upper_case(java::util::Locale::get_root())
+                .execute_with(jvm)?,
+            Transform::LowerCase => string
+                .to_lower_case(java::util::Locale::get_root())
+                .execute_with(jvm)?,
+            Transform::Normalize(form) => normalize_string(jvm, &string, form)?,
+        };
+        let result = result.ok_or(Error::NullDeref)?;
+        (&*result).to_rust().execute_with(jvm)
+    }
+}
+
+/// Calls `Normalizer.normalize`, which is looked up by hand since its `Form` parameter is a nested class.
+fn normalize_string<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    string: &java::lang::String,
+    form: Form,
+) -> crate::Result<'jvm, Option<Local<'jvm, java::lang::String>>> {
+    let form_class = find_class(jvm, c"java/text/Normalizer$Form")?;
+    let form_field = find_field(
+        jvm,
+        &form_class,
+        form.field_name(),
+        c"Ljava/text/Normalizer$Form;",
+        true,
+    )?;
+    let env = jvm.env();
+    // SAFETY: `form_field` is a static field of `form_class`
+    let form: Option<Local<Object>> = unsafe {
+        env.invoke(
+            |env| env.GetStaticObjectField,
+            |env, f| f(env, form_class.as_raw().as_ptr(), form_field.as_ptr()),
+        )
+    }?;
+    let form = form.ok_or(Error::NullDeref)?;
+
+    let normalizer_class = find_class(jvm, c"java/text/Normalizer")?;
+    let normalize = find_method(
+        jvm,
+        &normalizer_class,
+        c"normalize",
+        c"(Ljava/lang/CharSequence;Ljava/text/Normalizer$Form;)Ljava/lang/String;",
+        true,
+    )?;
+    let args = [
+        jvalue {
+            l: string.as_raw().as_ptr(),
+        },
+        jvalue {
+            l: form.as_raw().as_ptr(),
+        },
+    ];
+    let env = jvm.env();
+    // SAFETY: `normalize` is a static method of `normalizer_class` taking a `CharSequence` and a `Form`
+    unsafe {
+        env.invoke(
+            |env| env.CallStaticObjectMethodA,
+            |env, f| {
+                f(
+                    env,
+                    normalizer_class.as_raw().as_ptr(),
+                    normalize.as_ptr(),
+                    args.as_ptr(),
+                )
+            },
+        )
+    }
+}
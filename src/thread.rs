@@ -1,47 +1,77 @@
 use std::cell::Cell;
 
 use crate::{
+    java::lang::{String as JavaString, Thread},
+    jvm::try_global_jvm,
+    prelude::*,
     raw::{EnvPtr, JvmPtr},
-    Error, GlobalResult,
+    Error, Global, GlobalResult, Jvm,
 };
 
-// XX: The current thread-local state will prevent duchess => java => duchess call stacks. We may want to relax this in
-// the future!
 thread_local! {
-    static STATE: Cell<State> = Cell::new(State::Detached);
+    static STATE: Cell<State> = const { Cell::new(State::Detached) };
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum State {
-    /// The JVM is attached to the current thread, but we're already inside a duchess frame.
-    InUse,
+    /// The JVM is attached to the current thread, which is inside `depth` (nested) duchess frames. The thread is
+    /// detached once the outermost frame ends, unless it is `permanent`ly attached.
+    InUse {
+        env: EnvPtr<'static>,
+        depth: usize,
+        permanent: bool,
+    },
     /// The JVM is permanently attached to the current thread, but we're not inside a duchess frame.
     AttachedPermanently(EnvPtr<'static>),
     /// Duchess thinks the JVM is detached, though JNI calls through other means could change this.
     Detached,
 }
 
-fn attached_or(
-    jvm: JvmPtr,
-    f: impl FnOnce() -> GlobalResult<AttachGuard>,
-) -> GlobalResult<AttachGuard> {
-    STATE.with(|state| match state.replace(State::InUse) {
-        State::AttachedPermanently(env) => Ok(AttachGuard {
-            jvm,
+/// Returns a guard for the current thread if it is attached already (permanently, or by an enclosing duchess frame,
+/// whose env is reused), and uses `f` to attach it otherwise. The first case is the common one, so it is kept to a
+/// single thread-local access.
+#[inline]
+fn attached_or(f: impl FnOnce() -> GlobalResult<AttachGuard>) -> GlobalResult<AttachGuard> {
+    STATE.with(|state| match state.get() {
+        State::AttachedPermanently(env) => {
+            state.set(State::InUse {
+                env,
+                depth: 1,
+                permanent: true,
+            });
+            Ok(AttachGuard::new(None, env))
+        }
+        State::InUse {
             env,
-            permanent: true,
-        }),
-        State::InUse => Err(Error::NestedUsage),
-        State::Detached => {
-            let result = f();
-            if result.is_err() {
-                state.set(State::Detached);
-            }
-            result
+            depth,
+            permanent,
+        } => {
+            state.set(State::InUse {
+                env,
+                depth: depth + 1,
+                permanent,
+            });
+            Ok(AttachGuard::new(None, env))
         }
+        State::Detached => attach_detached(state, f),
     })
 }
 
+#[cold]
+#[inline(never)]
+fn attach_detached(
+    state: &Cell<State>,
+    f: impl FnOnce() -> GlobalResult<AttachGuard>,
+) -> GlobalResult<AttachGuard> {
+    let guard = f()?;
+    state.set(State::InUse {
+        env: guard.env,
+        depth: 1,
+        permanent: guard.detach_from.is_none(),
+    });
+    Ok(guard)
+}
+
 /// Marks the current thread as attached until `detach_from_jni_callback` is called.
 /// Intended for use within JNI calls of native functions.
 /// Returns the previous thread state, which should be given to `detach_from_jni_callback`
@@ -62,7 +92,12 @@ pub unsafe fn attach_from_jni_callback(env: EnvPtr<'_>) -> JniCallbackGuard<'_>
         let env: EnvPtr<'static> = unsafe { std::mem::transmute(env) };
         state.replace(State::AttachedPermanently(env))
     });
-    JniCallbackGuard { env, old_state }
+    JniCallbackGuard {
+        env,
+        old_state,
+        #[cfg(all(feature = "checked-env", debug_assertions))]
+        _use: crate::checked_env::EnvUse::new(env),
+    }
 }
 
 /// A guard object whose destructor restores the thread attachment state
@@ -71,13 +106,14 @@ pub unsafe fn attach_from_jni_callback(env: EnvPtr<'_>) -> JniCallbackGuard<'_>
 pub struct JniCallbackGuard<'env> {
     env: EnvPtr<'env>,
     old_state: State,
+    #[cfg(all(feature = "checked-env", debug_assertions))]
+    _use: crate::checked_env::EnvUse,
 }
 
 impl Drop for JniCallbackGuard<'_> {
     fn drop(&mut self) {
         STATE.with(|state| {
-            let old_state = std::mem::replace(&mut self.old_state, State::InUse);
-            let jni_state = state.replace(old_state);
+            let jni_state = state.replace(self.old_state);
 
             // Unsafe condition: this pointer will not actually live past end of this block
             // so it remains inside its original lifetime.
@@ -90,52 +126,242 @@ impl Drop for JniCallbackGuard<'_> {
     }
 }
 
-pub fn attach_permanently(jvm: JvmPtr) -> GlobalResult<AttachGuard> {
-    attached_or(jvm, || {
-        Ok(AttachGuard {
-            jvm,
-            // no-op if already attached outside of duchess
-            env: unsafe { jvm.attach_thread()? },
-            permanent: true,
-        })
+/// Attaches the current thread to `jvm` for the rest of its life, as a daemon thread if `daemon` is set. Threads
+/// that are attached already are left as they are, except that a thread attached by the enclosing duchess frames
+/// stays attached once they end.
+pub fn attach_permanently(jvm: JvmPtr, daemon: bool) -> GlobalResult<AttachGuard> {
+    let guard = attached_or(|| {
+        // no-op if already attached outside of duchess
+        let env = unsafe { jvm.attach_thread(daemon)? };
+        Ok(AttachGuard::new(None, env))
+    })?;
+    STATE.with(|state| {
+        if let State::InUse { env, depth, .. } = state.get() {
+            state.set(State::InUse {
+                env,
+                depth,
+                permanent: true,
+            });
+        }
+    });
+    Ok(guard)
+}
+
+/// Attaches the current thread to the JVM returned by `jvm` until the guard is dropped, unless it is attached
+/// already. `jvm` is only called if the thread isn't attached.
+pub unsafe fn attach<'jvm>(
+    jvm: impl FnOnce() -> GlobalResult<JvmPtr>,
+) -> GlobalResult<AttachGuard> {
+    attached_or(|| {
+        let jvm = jvm()?;
+        // no-op if already attached outside of duchess
+        let env = unsafe { jvm.attach_thread(false)? };
+        Ok(AttachGuard::new(Some(jvm), env))
     })
 }
 
-pub unsafe fn attach<'jvm>(jvm: JvmPtr) -> GlobalResult<AttachGuard> {
-    attached_or(jvm, || {
-        Ok(AttachGuard {
-            jvm,
-            // no-op if already attached outside of duchess
-            env: unsafe { jvm.attach_thread()? },
-            permanent: false,
-        })
+/// Whether the current thread is inside a duchess frame, e.g. within [`Jvm::with`].
+pub(crate) fn in_duchess_frame() -> bool {
+    matches!(STATE.with(Cell::get), State::InUse { .. })
+}
+
+/// Whether the current thread is attached to the JVM, by duchess or otherwise (e.g. because it is a Java thread).
+/// Returns `false` if there is no JVM yet.
+pub fn current_thread_is_attached() -> bool {
+    match STATE.with(Cell::get) {
+        State::InUse { .. } | State::AttachedPermanently(_) => true,
+        State::Detached => match try_global_jvm() {
+            // SAFETY: the returned env isn't used
+            Some(jvm) => matches!(unsafe { jvm.env() }, Ok(Some(_))),
+            None => false,
+        },
+    }
+}
+
+/// Detaches the current thread from the JVM, e.g. before a thread that was attached with
+/// [`Jvm::attach_thread_permanently`](crate::Jvm::attach_thread_permanently) exits. Does nothing if the thread isn't
+/// attached. The thread is attached again by the next use of the JVM.
+///
+/// Fails if the thread is using the JVM, i.e. inside [`Jvm::with`](crate::Jvm::with) or an operation that it
+/// executes, and when called by a native method, since Java threads can't be detached.
+pub fn detach_thread() -> GlobalResult<()> {
+    STATE.with(|state| match state.get() {
+        State::InUse { .. } => Err(Error::JvmInternal(
+            "cannot detach the current thread while it is using the JVM".into(),
+        )),
+        State::AttachedPermanently(_) | State::Detached => {
+            let Some(jvm) = try_global_jvm() else {
+                return Ok(());
+            };
+            // SAFETY: outside of duchess frames there are no local refs, and JNI refuses to detach Java threads
+            // (including those in native methods)
+            unsafe { jvm.detach_thread() }?;
+            state.set(State::Detached);
+            Ok(())
+        }
     })
 }
 
-/// When dropped, will detach the current thread from the JVM unless it was permanently attached.
+/// The Java `Thread` object of the current thread, attaching the thread if need be.
+///
+/// A thread that isn't attached permanently (or inside [`Jvm::with`]) is attached only for this call, after which
+/// the returned `Thread` has terminated, so use [`JavaThreadOptions::attach_permanently`] first.
+pub fn current_java_thread() -> GlobalResult<Global<Thread>> {
+    Thread::current_thread()
+        .assert_not_null()
+        .global()
+        .execute()
+}
+
+/// Whether the Java thread of the current thread has been interrupted (e.g. by Java code calling `interrupt`, to
+/// cancel the work that the thread is doing). Unlike `Thread.interrupted`, this doesn't clear the interrupt status.
+pub fn current_thread_is_interrupted() -> GlobalResult<bool> {
+    Thread::current_thread()
+        .assert_not_null()
+        .is_interrupted()
+        .execute()
+}
+
+/// How the current Rust thread shows up in Java, e.g. in thread dumps and to code that inspects
+/// `Thread.currentThread()`. Applied by [`JavaThreadOptions::attach_permanently`]:
+///
+/// ```
+/// # fn main() -> duchess::GlobalResult<()> {
+/// # duchess::Jvm::with(|_| Ok(()))?;
+/// std::thread::spawn(|| -> duchess::GlobalResult<()> {
+///     let thread = duchess::JavaThreadOptions::new()
+///         .name("rust-worker")
+///         .priority(7)
+///         .daemon(true)
+///         .attach_permanently()?;
+///     // ... use the JVM ...
+///     # let _ = thread;
+///     Ok(())
+/// })
+/// .join()
+/// .unwrap()
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JavaThreadOptions {
+    name: Option<String>,
+    priority: Option<i32>,
+    daemon: bool,
+}
+
+impl JavaThreadOptions {
+    /// Options that leave the thread as the JVM attaches it: named `Thread-N`, with normal priority, and not a daemon.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the thread.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the priority of the thread, between `Thread.MIN_PRIORITY` (1) and `Thread.MAX_PRIORITY` (10).
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Whether to attach the thread as a daemon thread, see [`Jvm::attach_thread_permanently_as_daemon`].
+    pub fn daemon(mut self, daemon: bool) -> Self {
+        self.daemon = daemon;
+        self
+    }
+
+    /// Attaches the current thread permanently (see [`Jvm::attach_thread_permanently`]) and applies the options,
+    /// returning its Java `Thread`.
+    ///
+    /// Java can't turn threads that are running into daemons, so the daemon status of a thread that is attached
+    /// already stays as it is, but its name and priority are still set. Fails if the priority is out of range (with an
+    /// `IllegalArgumentException`).
+    pub fn attach_permanently(self) -> GlobalResult<Global<Thread>> {
+        if self.daemon {
+            Jvm::attach_thread_permanently_as_daemon()?;
+        } else {
+            Jvm::attach_thread_permanently()?;
+        }
+        Jvm::with(|jvm| {
+            let thread = Thread::current_thread()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            if let Some(name) = &self.name {
+                let name = name.to_java::<JavaString>().assert_not_null();
+                thread.set_name(name).execute_with(jvm)?;
+            }
+            if let Some(priority) = self.priority {
+                thread.set_priority(priority).execute_with(jvm)?;
+            }
+            Ok(jvm.global(&*thread))
+        })
+    }
+}
+
+/// When dropped, leaves the duchess frame, and detaches the current thread from the JVM if the frame is the outermost
+/// one and the thread isn't permanently attached.
 pub struct AttachGuard {
-    jvm: JvmPtr,
+    /// The JVM that the guard attached the current thread to, if it did so temporarily.
+    detach_from: Option<JvmPtr>,
     env: EnvPtr<'static>, // not send!
-    permanent: bool,
+    #[cfg(all(feature = "checked-env", debug_assertions))]
+    _use: crate::checked_env::EnvUse,
 }
 
 impl Drop for AttachGuard {
+    #[inline]
     fn drop(&mut self) {
-        if self.permanent {
-            STATE.with(|state| {
-                let old_state = state.replace(State::AttachedPermanently(self.env));
-                debug_assert!(matches!(old_state, State::InUse))
-            });
-        } else {
-            match unsafe { self.jvm.detach_thread() } {
-                Ok(()) => STATE.with(|state| state.set(State::Detached)),
-                Err(err) => tracing::warn!(?err, "couldn't detach thread from JVM"),
+        STATE.with(|state| match state.get() {
+            State::InUse {
+                env,
+                depth,
+                permanent,
+            } if depth > 1 => state.set(State::InUse {
+                env,
+                depth: depth - 1,
+                permanent,
+            }),
+            State::InUse {
+                env,
+                permanent: true,
+                ..
+            } => state.set(State::AttachedPermanently(env)),
+            State::InUse {
+                permanent: false, ..
+            } => {
+                // Only the outermost guard of a temporarily attached thread attached it
+                let jvm = self
+                    .detach_from
+                    .expect("temporarily attached thread without a JVM");
+                match unsafe { jvm.detach_thread() } {
+                    Ok(()) => state.set(State::Detached),
+                    Err(err) => {
+                        tracing::warn!(?err, "couldn't detach thread from JVM");
+                        state.set(State::AttachedPermanently(self.env));
+                    }
+                }
             }
-        }
+            old_state => debug_assert!(
+                false,
+                "invalid state `{old_state:?}` when leaving duchess frame"
+            ),
+        })
     }
 }
 
 impl AttachGuard {
+    fn new(detach_from: Option<JvmPtr>, env: EnvPtr<'static>) -> Self {
+        AttachGuard {
+            detach_from,
+            env,
+            #[cfg(all(feature = "checked-env", debug_assertions))]
+            _use: crate::checked_env::EnvUse::new(env),
+        }
+    }
+
     pub fn env(&mut self) -> EnvPtr<'_> {
         self.env
     }
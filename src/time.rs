@@ -0,0 +1,580 @@
+//! Conversions of [`SystemTime`] to and from `java.time.Instant`, `java.time.LocalDateTime` and the legacy date
+//! classes `java.util.Date`, `java.util.Calendar`, and `java.sql.Timestamp`, and of [`Duration`] to and from
+//! `java.time.Duration`.
+//!
+//! `Instant`s, `Duration`s, and `Timestamp`s are converted through their seconds and nanoseconds, so they keep their
+//! full precision. The other legacy classes represent a point in time as milliseconds since the Unix epoch:
+//! converting to a `Date` or `Calendar` truncates the time to milliseconds (towards the past, like Java does for times
+//! before the epoch). A `Calendar` is created with the default time zone and locale of the JVM.
+//!
+//! A `LocalDateTime` has no time zone, so it is taken to be in UTC, as timestamps stored without one usually are. The
+//! dates and times of other zones can be converted in Java, e.g. with `atZone(zone).toInstant()`.
+//!
+//! Values that don't fit in the target type (e.g. negative Java durations, or times beyond `Instant.MAX`) fail to
+//! convert with a [`ConversionError::OutOfRange`] rather than being truncated.
+//!
+//! With the `chrono` feature, `chrono::DateTime<Utc>`, `NaiveDateTime`, and `TimeDelta` also convert to and from
+//! `Instant`, `LocalDateTime`, and `Duration`, and with the `time` feature, so do `time::OffsetDateTime`,
+//! `PrimitiveDateTime`, and `Duration`. Their conversions from Java return times in UTC.
+
+use std::time::{Duration, SystemTime};
+
+use crate::{
+    error::ConversionError,
+    into_rust::{into_rust_or_conversion_error, TryIntoRust},
+    java,
+    to_java::ToJavaImpl,
+    IntoRust, Jvm, JvmOp, Local,
+};
+
+const NANOS_PER_SECOND: u32 = 1_000_000_000;
+const NANOS_PER_MILLI: u32 = 1_000_000;
+
+/// The epoch seconds of `Instant.MIN` and `Instant.MAX`.
+const INSTANT_MIN_SECONDS: i64 = -31_557_014_167_219_200;
+const INSTANT_MAX_SECONDS: i64 = 31_556_889_864_403_199;
+
+/// The epoch seconds of `LocalDateTime.MIN` and `LocalDateTime.MAX` in UTC.
+const LOCAL_DATE_TIME_MIN_SECONDS: i64 = -31_557_014_135_596_800;
+const LOCAL_DATE_TIME_MAX_SECONDS: i64 = 31_556_889_832_780_799;
+
+/// The seconds since the epoch (negative before it) and nanoseconds within that second of `time`, failing if the
+/// seconds don't fit in the `target` type.
+fn to_epoch_seconds(time: SystemTime, target: &'static str) -> Result<(i64, u32), ConversionError> {
+    let out_of_range = || ConversionError::OutOfRange {
+        value: format!("{time:?}"),
+        target,
+    };
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(since) => {
+            let seconds = i64::try_from(since.as_secs()).map_err(|_| out_of_range())?;
+            Ok((seconds, since.subsec_nanos()))
+        }
+        Err(err) => {
+            let before = err.duration();
+            let seconds = i64::try_from(before.as_secs()).map_err(|_| out_of_range())?;
+            if before.subsec_nanos() == 0 {
+                Ok((-seconds, 0))
+            } else {
+                Ok((-seconds - 1, NANOS_PER_SECOND - before.subsec_nanos()))
+            }
+        }
+    }
+}
+
+/// The time `seconds` (negative before the epoch) and `nanos` after the epoch.
+fn from_epoch_seconds(seconds: i64, nanos: u32) -> Result<SystemTime, ConversionError> {
+    let time = if seconds >= 0 {
+        SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(seconds as u64))
+    } else {
+        SystemTime::UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs()))
+    };
+    time.and_then(|time| time.checked_add(Duration::from_nanos(nanos.into())))
+        .ok_or_else(|| epoch_seconds_out_of_range(seconds, nanos, "SystemTime"))
+}
+
+fn epoch_seconds_out_of_range(seconds: i64, nanos: u32, target: &'static str) -> ConversionError {
+    ConversionError::OutOfRange {
+        value: format!("{seconds}s {nanos}ns since the epoch"),
+        target,
+    }
+}
+
+/// The seconds since the epoch and nanoseconds within that second of an `Instant`.
+fn instant_epoch_seconds<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    instant: &java::time::Instant,
+) -> crate::Result<'jvm, (i64, u32)> {
+    let seconds = instant.get_epoch_second().execute_with(jvm)?;
+    let nanos = instant.get_nano().execute_with(jvm)?;
+    Ok((seconds, nanos as u32))
+}
+
+/// The seconds since the epoch and nanoseconds within that second of a `LocalDateTime`, taken to be in UTC.
+fn local_date_time_epoch_seconds<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    date_time: &java::time::LocalDateTime,
+) -> crate::Result<'jvm, (i64, u32)> {
+    let utc = java::time::ZoneOffset::get_utc().assert_not_null();
+    let seconds = date_time
+        .at_offset(utc)
+        .assert_not_null()
+        .to_epoch_second()
+        .execute_with(jvm)?;
+    let nanos = date_time.get_nano().execute_with(jvm)?;
+    Ok((seconds, nanos as u32))
+}
+
+/// The `Instant` `seconds` and `nanos` after the epoch, failing if it is out of the range of `Instant`, with `value`
+/// (the Rust time) in the error.
+fn instant_of_epoch_seconds<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    seconds: i64,
+    nanos: u32,
+    value: &dyn std::fmt::Debug,
+) -> crate::Result<'jvm, Option<Local<'jvm, java::time::Instant>>> {
+    if !(INSTANT_MIN_SECONDS..=INSTANT_MAX_SECONDS).contains(&seconds) {
+        return Err(ConversionError::OutOfRange {
+            value: format!("{value:?}"),
+            target: "java.time.Instant",
+        }
+        .into());
+    }
+    java::time::Instant::of_epoch_second(seconds, i64::from(nanos)).execute_with(jvm)
+}
+
+/// The `LocalDateTime` in UTC `seconds` and `nanos` after the epoch, like [`instant_of_epoch_seconds`]. The
+/// nanoseconds may exceed a second (as they do for leap seconds in chrono), in which case they carry over.
+fn local_date_time_of_epoch_seconds<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    seconds: i64,
+    nanos: u32,
+    value: &dyn std::fmt::Debug,
+) -> crate::Result<'jvm, Option<Local<'jvm, java::time::LocalDateTime>>> {
+    let seconds = seconds.checked_add(i64::from(nanos / NANOS_PER_SECOND));
+    let nanos = nanos % NANOS_PER_SECOND;
+    let Some(seconds) = seconds.filter(|seconds| {
+        (LOCAL_DATE_TIME_MIN_SECONDS..=LOCAL_DATE_TIME_MAX_SECONDS).contains(seconds)
+    }) else {
+        return Err(ConversionError::OutOfRange {
+            value: format!("{value:?}"),
+            target: "java.time.LocalDateTime",
+        }
+        .into());
+    };
+    let utc = java::time::ZoneOffset::get_utc().assert_not_null();
+    java::time::LocalDateTime::of_epoch_second(seconds, nanos as i32, utc).execute_with(jvm)
+}
+
+/// The seconds (negative for negative durations) and nanoseconds (always positive) of a Java `Duration`.
+fn duration_parts<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    duration: &java::time::Duration,
+) -> crate::Result<'jvm, (i64, u32)> {
+    let seconds = duration.get_seconds().execute_with(jvm)?;
+    let nanos = duration.get_nano().execute_with(jvm)?;
+    Ok((seconds, nanos as u32))
+}
+
+fn duration_out_of_range<'jvm>(
+    jvm: &mut Jvm<'jvm>,
+    duration: &java::time::Duration,
+    target: &'static str,
+) -> crate::Result<'jvm, ConversionError> {
+    let value: String = duration
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute_with(jvm)?;
+    Ok(ConversionError::OutOfRange { value, target })
+}
+
+fn to_epoch_millis(time: SystemTime) -> Result<i64, ConversionError> {
+    let (seconds, nanos) = to_epoch_seconds(time, "java.util.Date")?;
+    seconds
+        .checked_mul(1000)
+        .and_then(|millis| millis.checked_add((nanos / NANOS_PER_MILLI).into()))
+        .ok_or_else(|| ConversionError::OutOfRange {
+            value: format!("{time:?}"),
+            target: "java.util.Date",
+        })
+}
+
+fn from_epoch_millis(millis: i64) -> Result<SystemTime, ConversionError> {
+    let nanos = millis.rem_euclid(1000) as u32 * NANOS_PER_MILLI;
+    from_epoch_seconds(millis.div_euclid(1000), nanos)
+}
+
+macro_rules! into_system_time {
+    ($($java:ty,)*) => {
+        $(
+            impl IntoRust<SystemTime> for &$java {
+                fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, SystemTime> {
+                    into_rust_or_conversion_error(self, jvm)
+                }
+            }
+        )*
+    };
+}
+
+into_system_time! {
+    java::time::Instant,
+    java::time::LocalDateTime,
+    java::util::Date,
+    java::util::Calendar,
+    java::sql::Timestamp,
+}
+
+impl TryIntoRust<SystemTime> for &java::time::Instant {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<SystemTime, ConversionError>> {
+        let (seconds, nanos) = instant_epoch_seconds(jvm, self)?;
+        Ok(from_epoch_seconds(seconds, nanos))
+    }
+}
+
+/// Takes the date and time to be in UTC.
+impl TryIntoRust<SystemTime> for &java::time::LocalDateTime {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<SystemTime, ConversionError>> {
+        let (seconds, nanos) = local_date_time_epoch_seconds(jvm, self)?;
+        Ok(from_epoch_seconds(seconds, nanos))
+    }
+}
+
+impl TryIntoRust<SystemTime> for &java::util::Date {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<SystemTime, ConversionError>> {
+        let millis = self.get_time().execute_with(jvm)?;
+        Ok(from_epoch_millis(millis))
+    }
+}
+
+impl TryIntoRust<SystemTime> for &java::util::Calendar {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<SystemTime, ConversionError>> {
+        let millis = self.get_time_in_millis().execute_with(jvm)?;
+        Ok(from_epoch_millis(millis))
+    }
+}
+
+/// Keeps the nanoseconds of the timestamp, rather than just its milliseconds.
+impl TryIntoRust<SystemTime> for &java::sql::Timestamp {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<SystemTime, ConversionError>> {
+        let millis = self.get_time().execute_with(jvm)?;
+        let nanos = self.get_nanos().execute_with(jvm)?;
+        Ok(from_epoch_seconds(millis.div_euclid(1000), nanos as u32))
+    }
+}
+
+impl ToJavaImpl<java::time::Instant> for SystemTime {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::Instant>>> {
+        let (seconds, nanos) = to_epoch_seconds(*rust, "java.time.Instant")?;
+        instant_of_epoch_seconds(jvm, seconds, nanos, rust)
+    }
+}
+
+/// The date and time in UTC.
+impl ToJavaImpl<java::time::LocalDateTime> for SystemTime {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::LocalDateTime>>> {
+        let (seconds, nanos) = to_epoch_seconds(*rust, "java.time.LocalDateTime")?;
+        local_date_time_of_epoch_seconds(jvm, seconds, nanos, rust)
+    }
+}
+
+impl ToJavaImpl<java::util::Date> for SystemTime {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Date>>> {
+        let millis = to_epoch_millis(*rust)?;
+        let date = java::util::Date::new().execute_with(jvm)?;
+        date.set_time(millis).execute_with(jvm)?;
+        Ok(Some(date))
+    }
+}
+
+impl ToJavaImpl<java::util::Calendar> for SystemTime {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Calendar>>> {
+        let millis = to_epoch_millis(*rust)?;
+        let calendar = java::util::Calendar::get_instance()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        calendar.set_time_in_millis(millis).execute_with(jvm)?;
+        Ok(Some(calendar))
+    }
+}
+
+/// Keeps the nanoseconds of the time, rather than just its milliseconds.
+impl ToJavaImpl<java::sql::Timestamp> for SystemTime {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::sql::Timestamp>>> {
+        let (seconds, nanos) = to_epoch_seconds(*rust, "java.sql.Timestamp")?;
+        let millis = seconds
+            .checked_mul(1000)
+            .ok_or_else(|| ConversionError::OutOfRange {
+                value: format!("{rust:?}"),
+                target: "java.sql.Timestamp",
+            })?;
+        let timestamp = java::sql::Timestamp::new(millis).execute_with(jvm)?;
+        timestamp.set_nanos(nanos as i32).execute_with(jvm)?;
+        Ok(Some(timestamp))
+    }
+}
+
+impl IntoRust<Duration> for &java::time::Duration {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Duration> {
+        into_rust_or_conversion_error(self, jvm)
+    }
+}
+
+/// Fails for negative durations.
+impl TryIntoRust<Duration> for &java::time::Duration {
+    fn try_into_rust<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<Duration, ConversionError>> {
+        let (seconds, nanos) = duration_parts(jvm, self)?;
+        let Ok(seconds) = u64::try_from(seconds) else {
+            return Ok(Err(duration_out_of_range(
+                jvm,
+                self,
+                "std::time::Duration",
+            )?));
+        };
+        Ok(Ok(Duration::new(seconds, nanos)))
+    }
+}
+
+impl ToJavaImpl<java::time::Duration> for Duration {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::Duration>>> {
+        let seconds = i64::try_from(rust.as_secs()).map_err(|_| ConversionError::OutOfRange {
+            value: format!("{rust:?}"),
+            target: "java.time.Duration",
+        })?;
+        java::time::Duration::of_seconds(seconds, i64::from(rust.subsec_nanos())).execute_with(jvm)
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_conversions {
+    use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+
+    use super::{
+        duration_out_of_range, duration_parts, epoch_seconds_out_of_range, instant_epoch_seconds,
+        instant_of_epoch_seconds, local_date_time_epoch_seconds, local_date_time_of_epoch_seconds,
+    };
+    use crate::{
+        error::ConversionError,
+        into_rust::{into_rust_or_conversion_error, TryIntoRust},
+        java,
+        to_java::ToJavaImpl,
+        IntoRust, Jvm, JvmOp, Local,
+    };
+
+    macro_rules! into_chrono {
+        ($($java:ty => $rust:ty,)*) => {
+            $(
+                impl IntoRust<$rust> for &$java {
+                    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $rust> {
+                        into_rust_or_conversion_error(self, jvm)
+                    }
+                }
+            )*
+        };
+    }
+
+    into_chrono! {
+        java::time::Instant => DateTime<Utc>,
+        java::time::LocalDateTime => NaiveDateTime,
+        java::time::Duration => TimeDelta,
+    }
+
+    /// Fails for instants beyond the years that chrono supports (about 262,000 years from year 0).
+    impl TryIntoRust<DateTime<Utc>> for &java::time::Instant {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<DateTime<Utc>, ConversionError>> {
+            let (seconds, nanos) = instant_epoch_seconds(jvm, self)?;
+            Ok(DateTime::from_timestamp(seconds, nanos)
+                .ok_or_else(|| epoch_seconds_out_of_range(seconds, nanos, "chrono::DateTime<Utc>")))
+        }
+    }
+
+    /// Like the conversion of `Instant`, with the date and time taken to be in UTC.
+    impl TryIntoRust<NaiveDateTime> for &java::time::LocalDateTime {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<NaiveDateTime, ConversionError>> {
+            let (seconds, nanos) = local_date_time_epoch_seconds(jvm, self)?;
+            Ok(DateTime::from_timestamp(seconds, nanos)
+                .map(|date_time| date_time.naive_utc())
+                .ok_or_else(|| epoch_seconds_out_of_range(seconds, nanos, "chrono::NaiveDateTime")))
+        }
+    }
+
+    /// Fails for durations beyond `i64::MAX` milliseconds either way, which `TimeDelta` can't hold.
+    impl TryIntoRust<TimeDelta> for &java::time::Duration {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<TimeDelta, ConversionError>> {
+            let (seconds, nanos) = duration_parts(jvm, self)?;
+            match TimeDelta::new(seconds, nanos) {
+                Some(delta) => Ok(Ok(delta)),
+                None => Ok(Err(duration_out_of_range(jvm, self, "chrono::TimeDelta")?)),
+            }
+        }
+    }
+
+    impl ToJavaImpl<java::time::Instant> for DateTime<Utc> {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::Instant>>> {
+            instant_of_epoch_seconds(jvm, rust.timestamp(), rust.timestamp_subsec_nanos(), rust)
+        }
+    }
+
+    /// The date and time taken to be in UTC.
+    impl ToJavaImpl<java::time::LocalDateTime> for NaiveDateTime {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::LocalDateTime>>> {
+            let utc = rust.and_utc();
+            local_date_time_of_epoch_seconds(
+                jvm,
+                utc.timestamp(),
+                utc.timestamp_subsec_nanos(),
+                rust,
+            )
+        }
+    }
+
+    impl ToJavaImpl<java::time::Duration> for TimeDelta {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::Duration>>> {
+            // The nanoseconds have the sign of the delta, which `ofSeconds` adjusts for
+            java::time::Duration::of_seconds(rust.num_seconds(), i64::from(rust.subsec_nanos()))
+                .execute_with(jvm)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_conversions {
+    use ::time::{OffsetDateTime, PrimitiveDateTime};
+
+    use super::{
+        duration_parts, epoch_seconds_out_of_range, instant_epoch_seconds,
+        instant_of_epoch_seconds, local_date_time_epoch_seconds, local_date_time_of_epoch_seconds,
+        NANOS_PER_SECOND,
+    };
+    use crate::{
+        error::ConversionError,
+        into_rust::{into_rust_or_conversion_error, TryIntoRust},
+        java,
+        to_java::ToJavaImpl,
+        IntoRust, Jvm, JvmOp, Local,
+    };
+
+    /// The UTC date and time `seconds` and `nanos` after the epoch, failing if it is beyond the years that the time
+    /// crate supports (±9999, unless its `large-dates` feature is enabled).
+    fn from_epoch_seconds(
+        seconds: i64,
+        nanos: u32,
+        target: &'static str,
+    ) -> Result<OffsetDateTime, ConversionError> {
+        let timestamp = i128::from(seconds) * i128::from(NANOS_PER_SECOND) + i128::from(nanos);
+        OffsetDateTime::from_unix_timestamp_nanos(timestamp)
+            .map_err(|_| epoch_seconds_out_of_range(seconds, nanos, target))
+    }
+
+    impl IntoRust<OffsetDateTime> for &java::time::Instant {
+        fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, OffsetDateTime> {
+            into_rust_or_conversion_error(self, jvm)
+        }
+    }
+
+    /// Returns the time in UTC.
+    impl TryIntoRust<OffsetDateTime> for &java::time::Instant {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<OffsetDateTime, ConversionError>> {
+            let (seconds, nanos) = instant_epoch_seconds(jvm, self)?;
+            Ok(from_epoch_seconds(seconds, nanos, "time::OffsetDateTime"))
+        }
+    }
+
+    impl IntoRust<PrimitiveDateTime> for &java::time::LocalDateTime {
+        fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, PrimitiveDateTime> {
+            into_rust_or_conversion_error(self, jvm)
+        }
+    }
+
+    impl TryIntoRust<PrimitiveDateTime> for &java::time::LocalDateTime {
+        fn try_into_rust<'jvm>(
+            self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Result<PrimitiveDateTime, ConversionError>> {
+            let (seconds, nanos) = local_date_time_epoch_seconds(jvm, self)?;
+            Ok(
+                from_epoch_seconds(seconds, nanos, "time::PrimitiveDateTime")
+                    .map(|utc| PrimitiveDateTime::new(utc.date(), utc.time())),
+            )
+        }
+    }
+
+    /// Always fits, since `time::Duration` holds as many seconds as a Java `Duration`.
+    impl IntoRust<::time::Duration> for &java::time::Duration {
+        fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, ::time::Duration> {
+            let (seconds, nanos) = duration_parts(jvm, self)?;
+            Ok(::time::Duration::new(seconds, nanos as i32))
+        }
+    }
+
+    impl ToJavaImpl<java::time::Instant> for OffsetDateTime {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::Instant>>> {
+            instant_of_epoch_seconds(jvm, rust.unix_timestamp(), rust.nanosecond(), rust)
+        }
+    }
+
+    /// The date and time taken to be in UTC.
+    impl ToJavaImpl<java::time::LocalDateTime> for PrimitiveDateTime {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::LocalDateTime>>> {
+            let utc = rust.assume_utc();
+            local_date_time_of_epoch_seconds(jvm, utc.unix_timestamp(), utc.nanosecond(), rust)
+        }
+    }
+
+    impl ToJavaImpl<java::time::Duration> for ::time::Duration {
+        fn to_java_impl<'jvm>(
+            rust: &Self,
+            jvm: &mut Jvm<'jvm>,
+        ) -> crate::Result<'jvm, Option<Local<'jvm, java::time::Duration>>> {
+            // The nanoseconds have the sign of the duration, which `ofSeconds` adjusts for
+            java::time::Duration::of_seconds(
+                rust.whole_seconds(),
+                i64::from(rust.subsec_nanoseconds()),
+            )
+            .execute_with(jvm)
+        }
+    }
+}
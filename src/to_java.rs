@@ -1,7 +1,11 @@
-use std::{collections::HashMap, marker::PhantomData};
+use std::{
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+};
 
 use crate::{
-    cast::Upcast, from_ref::FromRef, java, jvm::JavaView, Error, Global, Jvm, JvmOp, Local,
+    cast::Upcast, error::ConversionError, from_ref::FromRef, java, jvm::JavaView, Error, Global,
+    Jvm, JvmOp, Local,
 };
 
 pub trait ToJava {
@@ -29,7 +33,8 @@ where
 }
 
 impl<R: ?Sized> ToJava for R {
-    type JvmOp<'a, J> = ToJavaOp<'a, R, J>
+    type JvmOp<'a, J>
+        = ToJavaOp<'a, R, J>
     where
         Self: 'a,
         Self: ToJavaImpl<J>,
@@ -77,6 +82,75 @@ where
     }
 }
 
+/// Fallible counterpart to [`ToJava`]: problems with the value being converted (e.g., a number out of range for the
+/// Java type) are reported as an inner `Err(ConversionError)` rather than as a JVM error. A slice too long for a Java
+/// array is still reported as [`Error::SliceTooLong`].
+pub trait TryToJava {
+    fn try_to_java<J>(&self) -> TryToJavaOp<'_, Self, J>
+    where
+        Self: TryToJavaImpl<J>,
+        J: Upcast<java::lang::Object> + Upcast<J>;
+}
+
+pub trait TryToJavaImpl<J>
+where
+    J: Upcast<java::lang::Object>,
+{
+    fn try_to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<Option<Local<'jvm, J>>, ConversionError>>;
+}
+
+impl<R: ?Sized> TryToJava for R {
+    fn try_to_java<J>(&self) -> TryToJavaOp<'_, Self, J>
+    where
+        Self: TryToJavaImpl<J>,
+        J: Upcast<java::lang::Object> + Upcast<J>,
+    {
+        TryToJavaOp {
+            rust: self,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Every infallible conversion is also a fallible one, which separates out the errors caused by the value itself.
+impl<R, J> TryToJavaImpl<J> for R
+where
+    R: ToJavaImpl<J> + ?Sized,
+    J: Upcast<java::lang::Object>,
+{
+    fn try_to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Result<Option<Local<'jvm, J>>, ConversionError>> {
+        match R::to_java_impl(rust, jvm) {
+            Ok(java) => Ok(Ok(java)),
+            Err(Error::Conversion(e)) => Ok(Err(e)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive_where::derive_where(Copy, Clone)]
+pub struct TryToJavaOp<'a, R: ?Sized, J> {
+    rust: &'a R,
+    phantom: PhantomData<J>,
+}
+
+impl<R, J> JvmOp for TryToJavaOp<'_, R, J>
+where
+    R: TryToJavaImpl<J> + ?Sized,
+    J: Upcast<java::lang::Object> + Upcast<J>,
+{
+    type Output<'jvm> = Result<Option<Local<'jvm, J>>, ConversionError>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        R::try_to_java_impl(self.rust, jvm)
+    }
+}
+
 impl<K, V, JK, JV, S> ToJavaImpl<java::util::HashMap<JK, JV>> for HashMap<K, V, S>
 where
     K: ToJavaImpl<JK>,
@@ -117,7 +191,47 @@ where
     }
 }
 
-impl<E, JE> ToJavaImpl<java::util::ArrayList<JE>> for Vec<E>
+impl<K, V, JK, JV> ToJavaImpl<java::util::HashMap<JK, JV>> for BTreeMap<K, V>
+where
+    K: ToJavaImpl<JK>,
+    V: ToJavaImpl<JV>,
+    JK: Upcast<java::lang::Object> + Upcast<JK>,
+    JV: Upcast<java::lang::Object> + Upcast<JV>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::HashMap<JK, JV>>>> {
+        let jmap: Local<'jvm, java::util::HashMap<JK, JV>> =
+            java::util::HashMap::new().execute_with(jvm)?;
+        for (key, value) in rust {
+            jmap.put(key.to_java(), value.to_java()).execute_with(jvm)?;
+        }
+        Ok(Some(jmap))
+    }
+}
+
+impl<K, V, JK, JV> ToJavaImpl<java::util::Map<JK, JV>> for BTreeMap<K, V>
+where
+    K: ToJavaImpl<JK>,
+    V: ToJavaImpl<JV>,
+    JK: Upcast<java::lang::Object> + Upcast<JK>,
+    JV: Upcast<java::lang::Object> + Upcast<JV>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::Map<JK, JV>>>> {
+        Ok(Some(
+            rust.to_java::<java::util::HashMap<JK, JV>>()
+                .assert_not_null()
+                .upcast()
+                .execute_with(jvm)?,
+        ))
+    }
+}
+
+impl<E, JE> ToJavaImpl<java::util::ArrayList<JE>> for [E]
 where
     E: ToJavaImpl<JE>,
     JE: Upcast<java::lang::Object> + Upcast<JE>,
@@ -135,7 +249,7 @@ where
     }
 }
 
-impl<E, JE> ToJavaImpl<java::util::List<JE>> for Vec<E>
+impl<E, JE> ToJavaImpl<java::util::List<JE>> for [E]
 where
     E: ToJavaImpl<JE>,
     JE: Upcast<java::lang::Object> + Upcast<JE>,
@@ -153,6 +267,32 @@ where
     }
 }
 
+impl<E, JE> ToJavaImpl<java::util::ArrayList<JE>> for Vec<E>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::ArrayList<JE>>>> {
+        <[E]>::to_java_impl(rust, jvm)
+    }
+}
+
+impl<E, JE> ToJavaImpl<java::util::List<JE>> for Vec<E>
+where
+    E: ToJavaImpl<JE>,
+    JE: Upcast<java::lang::Object> + Upcast<JE>,
+{
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::List<JE>>>> {
+        <[E]>::to_java_impl(rust, jvm)
+    }
+}
+
 impl ToJavaImpl<java::lang::String> for String {
     fn to_java_impl<'jvm>(
         rust: &Self,
@@ -247,13 +387,21 @@ where
             Ok(r) => R::to_java_impl(r, jvm),
             Err(e) => match e {
                 Error::Thrown(t) => Err(Error::Thrown(jvm.local(t))),
+                Error::ThrownWithBacktrace(t, trace) => {
+                    Err(Error::ThrownWithBacktrace(jvm.local(t), trace.clone()))
+                }
                 Error::SliceTooLong(t) => Err(Error::SliceTooLong(*t)),
                 Error::NullDeref => Err(Error::NullDeref),
-                Error::NestedUsage => Err(Error::NestedUsage),
                 Error::JvmAlreadyExists => Err(Error::JvmAlreadyExists),
+                Error::MultipleJvms(count) => Err(Error::MultipleJvms(*count)),
+                Error::ShuttingDown => Err(Error::ShuttingDown),
+                Error::InCriticalSection => Err(Error::InCriticalSection),
+                Error::InvalidConfig(m) => Err(Error::InvalidConfig(m.clone())),
+                Error::ClasspathNotFound(path) => Err(Error::ClasspathNotFound(path.clone())),
                 Error::UnableToLoadLibjvm(t) => Err(Error::UnableToLoadLibjvm(
                     format!("UnableToLoadLibjvm({t:?})").as_str().into(), // FIXME: should to_java_impl be `self` ?
                 )),
+                Error::Conversion(t) => Err(Error::Conversion(t.clone())),
                 Error::JvmInternal(t) => Err(Error::JvmInternal(t.clone())),
             },
         }
@@ -273,13 +421,21 @@ where
             Ok(r) => R::to_java_impl(r, jvm),
             Err(e) => match e {
                 Error::Thrown(t) => Err(Error::Thrown(jvm.local(t))),
+                Error::ThrownWithBacktrace(t, trace) => {
+                    Err(Error::ThrownWithBacktrace(jvm.local(t), trace.clone()))
+                }
                 Error::SliceTooLong(t) => Err(Error::SliceTooLong(*t)),
                 Error::NullDeref => Err(Error::NullDeref),
-                Error::NestedUsage => Err(Error::NestedUsage),
                 Error::JvmAlreadyExists => Err(Error::JvmAlreadyExists),
+                Error::MultipleJvms(count) => Err(Error::MultipleJvms(*count)),
+                Error::ShuttingDown => Err(Error::ShuttingDown),
+                Error::InCriticalSection => Err(Error::InCriticalSection),
+                Error::InvalidConfig(m) => Err(Error::InvalidConfig(m.clone())),
+                Error::ClasspathNotFound(path) => Err(Error::ClasspathNotFound(path.clone())),
                 Error::UnableToLoadLibjvm(t) => Err(Error::UnableToLoadLibjvm(
                     format!("UnableToLoadLibjvm({t:?})").as_str().into(), // FIXME: should to_java_impl be `self` ?
                 )),
+                Error::Conversion(t) => Err(Error::Conversion(t.clone())),
                 Error::JvmInternal(t) => Err(Error::JvmInternal(t.clone())),
             },
         }
@@ -0,0 +1,254 @@
+//! Propagating [W3C Trace Context] between Rust and Java, so that distributed traces stay connected when a request
+//! crosses into the embedded JVM (or back out of it).
+//!
+//! The context is carried in a `java.util.Map<String, String>` under the standard `traceparent` and `tracestate`
+//! keys, the "carrier" format understood by OpenTelemetry's text map propagators on both sides. On the Java side,
+//! `W3CTraceContextPropagator.getInstance().extract(Context.current(), carrier, getter)` continues a trace injected
+//! here (and `inject` produces one that [`TraceContext::extract`] reads). duchess doesn't depend on the Java
+//! OpenTelemetry API, so making the extracted context current on the Java side is up to the Java code receiving the
+//! carrier.
+//!
+//! On the Rust side, the ids are the byte representations used by OpenTelemetry. With the `opentelemetry` feature,
+//! [`TraceContext::current`] reads the context of the current span, and [`TraceContext::to_span_context`] gives the
+//! remote parent of the Rust spans continuing a trace from Java (a `tracing` span's context is available from
+//! `tracing-opentelemetry`'s `OpenTelemetrySpanExt::context`):
+//!
+//! ```ignore
+//! let context = TraceContext::current().expect("in a span");
+//! let carrier = java::util::HashMap::new().execute_with(jvm)?;
+//! context.inject(jvm, &carrier)?;
+//! handler.handle(request, &carrier).execute_with(jvm)?;
+//! ```
+//!
+//! [W3C Trace Context]: https://www.w3.org/TR/trace-context/
+
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{
+    java::{self, lang::String as JavaString},
+    AsJRef, IntoJava, Jvm, JvmOp, ToJava,
+};
+
+type Carrier = java::util::Map<JavaString, JavaString>;
+
+/// The trace context of a span: the `traceparent` header (trace id, id of the parent span, and flags) and the vendor
+/// specific `tracestate` header, if any.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+    pub trace_id: [u8; 16],
+    pub parent_id: [u8; 8],
+    pub flags: u8,
+    pub trace_state: Option<String>,
+}
+
+/// A `traceparent` header that isn't valid according to the W3C Trace Context specification.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid traceparent header `{0}`")]
+pub struct InvalidTraceParent(pub String);
+
+impl TraceContext {
+    pub const TRACE_PARENT: &'static str = "traceparent";
+    pub const TRACE_STATE: &'static str = "tracestate";
+
+    /// The flag set when the caller may have recorded the trace.
+    pub const FLAG_SAMPLED: u8 = 0x01;
+
+    pub fn new(trace_id: [u8; 16], parent_id: [u8; 8], flags: u8) -> Self {
+        TraceContext {
+            trace_id,
+            parent_id,
+            flags,
+            trace_state: None,
+        }
+    }
+
+    pub fn with_trace_state(mut self, trace_state: impl Into<String>) -> Self {
+        self.trace_state = Some(trace_state.into());
+        self
+    }
+
+    /// The same trace, continued by the span `parent_id` (e.g., the Rust span calling into Java).
+    pub fn with_parent_id(mut self, parent_id: [u8; 8]) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.flags & Self::FLAG_SAMPLED != 0
+    }
+
+    /// The value of the `traceparent` header, e.g. `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01`.
+    pub fn trace_parent(&self) -> String {
+        self.to_string()
+    }
+
+    /// Parses a `traceparent` header (and the `tracestate` header that came with it, if any).
+    pub fn parse(
+        trace_parent: &str,
+        trace_state: Option<&str>,
+    ) -> Result<Self, InvalidTraceParent> {
+        let mut context: TraceContext = trace_parent.parse()?;
+        context.trace_state = trace_state.map(str::to_owned);
+        Ok(context)
+    }
+
+    /// Stores this context into the `carrier` map, replacing any previous context.
+    pub fn inject<'jvm>(
+        &self,
+        jvm: &mut Jvm<'jvm>,
+        carrier: impl IntoJava<Carrier>,
+    ) -> crate::Result<'jvm, ()> {
+        let carrier = carrier.into_java(jvm)?;
+        let carrier: &Carrier = carrier.as_jref()?;
+        carrier
+            .put(Self::TRACE_PARENT, &self.trace_parent())
+            .execute_with(jvm)?;
+        match &self.trace_state {
+            Some(trace_state) => {
+                carrier
+                    .put(Self::TRACE_STATE, trace_state)
+                    .execute_with(jvm)?;
+            }
+            None => {
+                carrier.remove(Self::TRACE_STATE).execute_with(jvm)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the context stored in the `carrier` map. As the specification requires, a missing or invalid
+    /// `traceparent` is treated as no context at all, in which case a new trace should be started.
+    pub fn extract<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        carrier: impl IntoJava<Carrier>,
+    ) -> crate::Result<'jvm, Option<Self>> {
+        let carrier = carrier.into_java(jvm)?;
+        let carrier: &Carrier = carrier.as_jref()?;
+        let get = |jvm: &mut Jvm<'jvm>, key: &str| -> crate::Result<'jvm, Option<String>> {
+            let key = key
+                .to_java::<JavaString>()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            carrier.get(&key).to_rust().execute_with(jvm)
+        };
+        let Some(trace_parent) = get(jvm, Self::TRACE_PARENT)? else {
+            return Ok(None);
+        };
+        let trace_state = get(jvm, Self::TRACE_STATE)?;
+        Ok(Self::parse(&trace_parent, trace_state.as_deref()).ok())
+    }
+}
+
+impl Display for TraceContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "00-")?;
+        for b in self.trace_id {
+            write!(f, "{b:02x}")?;
+        }
+        write!(f, "-")?;
+        for b in self.parent_id {
+            write!(f, "{b:02x}")?;
+        }
+        write!(f, "-{:02x}", self.flags)
+    }
+}
+
+/// Parses a `traceparent` header, without a `tracestate`.
+impl FromStr for TraceContext {
+    type Err = InvalidTraceParent;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidTraceParent(s.to_owned());
+        let mut parts = s.split('-');
+        let mut field = |len: usize| {
+            parts
+                .next()
+                .filter(|part| {
+                    part.len() == len
+                        && part.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+                })
+                .ok_or_else(invalid)
+        };
+        let version = field(2)?;
+        let trace_id = field(32)?;
+        let parent_id = field(16)?;
+        let flags = field(2)?;
+        // Later versions may append fields, but version 00 has exactly four
+        match version {
+            "ff" => return Err(invalid()),
+            "00" if parts.next().is_some() => return Err(invalid()),
+            _ => {}
+        }
+
+        let mut context = TraceContext::new([0; 16], [0; 8], 0);
+        decode_hex(trace_id, &mut context.trace_id);
+        decode_hex(parent_id, &mut context.parent_id);
+        decode_hex(flags, std::slice::from_mut(&mut context.flags));
+        // All-zero ids are reserved as invalid
+        if context.trace_id == [0; 16] || context.parent_id == [0; 8] {
+            return Err(invalid());
+        }
+        Ok(context)
+    }
+}
+
+/// Decodes lowercase hex digits, which were already validated, into `out`.
+fn decode_hex(hex: &str, out: &mut [u8]) {
+    for (byte, pair) in out.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let digit = |d: u8| match d {
+            b'0'..=b'9' => d - b'0',
+            _ => d - b'a' + 10,
+        };
+        *byte = (digit(pair[0]) << 4) | digit(pair[1]);
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+mod opentelemetry_spans {
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
+
+    use super::TraceContext;
+
+    impl TraceContext {
+        /// The context of the span that is current on this thread (see `opentelemetry::Context::current`), if any.
+        pub fn current() -> Option<Self> {
+            Self::from_span_context(opentelemetry::Context::current().span().span_context())
+        }
+
+        /// The context of `span_context`, if it is valid (i.e., its ids aren't all zeros).
+        pub fn from_span_context(span_context: &SpanContext) -> Option<Self> {
+            if !span_context.is_valid() {
+                return None;
+            }
+            let trace_state = span_context.trace_state().header();
+            Some(TraceContext {
+                trace_id: span_context.trace_id().to_bytes(),
+                parent_id: span_context.span_id().to_bytes(),
+                flags: span_context.trace_flags().to_u8(),
+                trace_state: (!trace_state.is_empty()).then_some(trace_state),
+            })
+        }
+
+        /// The span context, propagated from a remote parent, of this context (e.g., extracted from a call from Java).
+        /// Use it as the parent of the Rust spans that continue the trace, e.g. with
+        /// `opentelemetry::Context::current().with_remote_span_context(..)`. An invalid `tracestate` is dropped.
+        pub fn to_span_context(&self) -> SpanContext {
+            let trace_state = self
+                .trace_state
+                .as_deref()
+                .and_then(|trace_state| trace_state.parse().ok())
+                .unwrap_or(TraceState::NONE);
+            SpanContext::new(
+                TraceId::from_bytes(self.trace_id),
+                SpanId::from_bytes(self.parent_id),
+                TraceFlags::new(self.flags),
+                true,
+                trace_state,
+            )
+        }
+    }
+}
@@ -48,3 +48,309 @@ where
         }
     }
 }
+
+/// [`JvmOp`][] that handles the exceptions thrown by an operation with a list of catch arms, like a Java `try` block
+/// (see [`JvmOp::catching`]). Arms are added with [`Catching::catch`] and tried in order: the first arm whose exception
+/// class matches handles the exception, and exceptions that no arm matches are rethrown. Exceptions thrown by a
+/// handler itself are not caught by the other arms.
+#[derive_where::derive_where(Copy, Clone)]
+pub struct Catching<This, Arms>
+where
+    This: JvmOp,
+    Arms: CatchArms<This>,
+{
+    this: This,
+    arms: Arms,
+}
+
+impl<This> Catching<This, ()>
+where
+    This: JvmOp,
+{
+    pub(crate) fn new(this: This) -> Self {
+        Self { this, arms: () }
+    }
+}
+
+impl<This, Arms> Catching<This, Arms>
+where
+    This: JvmOp,
+    Arms: CatchArms<This>,
+{
+    /// Adds an arm that handles exceptions of class `J` (that weren't matched by a previous arm), producing the output
+    /// of the operation instead.
+    pub fn catch<J, F>(self, handler: F) -> Catching<This, CatchArm<J, F, Arms>>
+    where
+        J: Upcast<Throwable>,
+        F: Copy
+            + for<'jvm> FnOnce(
+                &mut Jvm<'jvm>,
+                Local<'jvm, J>,
+            ) -> crate::Result<'jvm, This::Output<'jvm>>,
+    {
+        Catching {
+            this: self.this,
+            arms: CatchArm {
+                previous: self.arms,
+                handler,
+                phantom: PhantomData,
+            },
+        }
+    }
+}
+
+impl<This, Arms> JvmOp for Catching<This, Arms>
+where
+    This: JvmOp,
+    Arms: CatchArms<This>,
+{
+    type Output<'jvm> = This::Output<'jvm>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        match self.this.execute_with(jvm) {
+            Err(crate::Error::Thrown(exception)) => match self.arms.handle(jvm, exception)? {
+                Ok(output) => Ok(output),
+                Err(exception) => Err(crate::Error::Thrown(exception)),
+            },
+            result => result,
+        }
+    }
+}
+
+/// The catch arms of a [`Catching`] operation: `()` if there are none, otherwise the last [`CatchArm`].
+pub trait CatchArms<This: JvmOp>: Copy {
+    /// Handles `exception` with the first matching arm, returning it back if none matches.
+    fn handle<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+        exception: Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Result<This::Output<'jvm>, Local<'jvm, Throwable>>>;
+}
+
+impl<This> CatchArms<This> for ()
+where
+    This: JvmOp,
+{
+    fn handle<'jvm>(
+        self,
+        _jvm: &mut Jvm<'jvm>,
+        exception: Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Result<This::Output<'jvm>, Local<'jvm, Throwable>>> {
+        Ok(Err(exception))
+    }
+}
+
+/// A catch arm of a [`Catching`] operation, see [`Catching::catch`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct CatchArm<J, F, Previous>
+where
+    J: Upcast<Throwable>,
+    F: Copy,
+    Previous: Copy,
+{
+    previous: Previous,
+    handler: F,
+    phantom: PhantomData<J>,
+}
+
+impl<This, J, F, Previous> CatchArms<This> for CatchArm<J, F, Previous>
+where
+    This: JvmOp,
+    J: Upcast<Throwable>,
+    F: Copy
+        + for<'jvm> FnOnce(&mut Jvm<'jvm>, Local<'jvm, J>) -> crate::Result<'jvm, This::Output<'jvm>>,
+    Previous: CatchArms<This>,
+{
+    fn handle<'jvm>(
+        self,
+        jvm: &mut Jvm<'jvm>,
+        exception: Local<'jvm, Throwable>,
+    ) -> crate::Result<'jvm, Result<This::Output<'jvm>, Local<'jvm, Throwable>>> {
+        // Earlier arms win
+        let exception = match self.previous.handle(jvm, exception)? {
+            Ok(output) => return Ok(Ok(output)),
+            Err(exception) => exception,
+        };
+        match exception.try_downcast::<J>().execute_with(jvm)? {
+            Ok(exception) => Ok(Ok((self.handler)(jvm, exception)?)),
+            Err(_) => Ok(Err(exception)),
+        }
+    }
+}
+
+/// An enum whose variants are the exceptions of the Java classes they hold, declared with
+/// [`catch_enum!`](crate::catch_enum), into which [`JvmOp::catch_into`] catches exceptions.
+pub trait CatchEnum: Sized {
+    /// The variant of the first class that `exception` is an instance of, if any.
+    fn from_exception<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        exception: &Throwable,
+    ) -> crate::Result<'jvm, Option<Self>>;
+}
+
+/// [`JvmOp`][] that catches the exceptions of an operation into a [`CatchEnum`], see [`JvmOp::catch_into`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct CatchInto<This, E>
+where
+    This: JvmOp,
+    E: CatchEnum,
+{
+    this: This,
+    phantom: PhantomData<fn() -> E>,
+}
+
+impl<This, E> CatchInto<This, E>
+where
+    This: JvmOp,
+    E: CatchEnum,
+{
+    pub(crate) fn new(this: This) -> Self {
+        Self {
+            this,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<This, E> JvmOp for CatchInto<This, E>
+where
+    This: JvmOp,
+    E: CatchEnum,
+{
+    type Output<'jvm> = Result<This::Output<'jvm>, E>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        match self.this.execute_with(jvm) {
+            Ok(output) => Ok(Ok(output)),
+            Err(crate::Error::Thrown(exception)) => match E::from_exception(jvm, &exception)? {
+                Some(caught) => Ok(Err(caught)),
+                None => Err(crate::Error::Thrown(exception)),
+            },
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Declares an enum whose variants hold [`Global`](crate::Global) references to exceptions, one variant per Java
+/// class, for [`JvmOp::catch_into`]:
+///
+/// ```ignore
+/// duchess::catch_enum! {
+///     #[derive(Debug)]
+///     pub enum ReadError {
+///         NotFound(java::io::FileNotFoundException),
+///         Io(java::io::IOException),
+///     }
+/// }
+/// ```
+///
+/// declares `ReadError::NotFound(Global<java::io::FileNotFoundException>)` and `ReadError::Io(..)`. An exception
+/// becomes the first variant whose class it is an instance of, so list the most derived classes first.
+#[macro_export]
+macro_rules! catch_enum {
+    (
+        $(#[$attr:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_attr:meta])* $variant:ident($class:ty)),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis enum $name {
+            $($(#[$variant_attr])* $variant($crate::Global<$class>),)*
+        }
+
+        impl $crate::CatchEnum for $name {
+            fn from_exception<'jvm>(
+                jvm: &mut $crate::Jvm<'jvm>,
+                exception: &$crate::java::lang::Throwable,
+            ) -> $crate::Result<'jvm, Option<Self>> {
+                $(
+                    if let Ok(e) = $crate::prelude::JvmOp::execute_with(
+                        $crate::prelude::JvmOp::try_downcast::<$class>(exception),
+                        jvm,
+                    )? {
+                        return Ok(Some($name::$variant(jvm.global(&*e))));
+                    }
+                )*
+                Ok(None)
+            }
+        }
+    };
+}
+
+/// [`JvmOp`][] that runs another operation after this one, whether it succeeded or not, see [`JvmOp::finally`].
+#[derive_where::derive_where(Copy, Clone)]
+pub struct Finally<This, F>
+where
+    This: JvmOp,
+    F: JvmOp,
+{
+    this: This,
+    finally: F,
+}
+
+impl<This, F> Finally<This, F>
+where
+    This: JvmOp,
+    F: JvmOp,
+{
+    pub(crate) fn new(this: This, finally: F) -> Self {
+        Self { this, finally }
+    }
+}
+
+impl<This, F> JvmOp for Finally<This, F>
+where
+    This: JvmOp,
+    F: JvmOp,
+{
+    type Output<'jvm> = This::Output<'jvm>;
+
+    fn execute_with<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Self::Output<'jvm>> {
+        let result = self.this.execute_with(jvm);
+        // As in Java, an exception thrown by the `finally` block replaces the outcome of the `try` block
+        self.finally.execute_with(jvm)?;
+        result
+    }
+}
+
+/// Dispatches an exception that was already caught (e.g. from [`Error::Thrown`](crate::Error::Thrown)) on its class,
+/// like the `catch` blocks of a Java `try` statement, instead of a chain of
+/// [`try_downcast`](crate::prelude::JvmOp::try_downcast)s:
+///
+/// ```ignore
+/// let message = duchess::match_throwable!(jvm, &exception, {
+///     java::io::FileNotFoundException => |e| format!("no such file: {e}"),
+///     java::io::IOException => |e| format!("unable to read: {e}"),
+///     _ => |e| format!("unexpected exception: {e}"),
+/// })?;
+/// ```
+///
+/// `jvm` is a `&mut Jvm` and `exception` a reference to a `Local` or `Global` whose type upcasts to `Throwable`. The
+/// arms are tried in order, so list the most derived classes first: the first arm whose class the exception is an
+/// instance of is called with it, as a `Local` of that class. The `_` arm, which is required, is called with
+/// `exception` as given if no other arm matches, e.g. to rethrow it. The macro evaluates to a
+/// [`duchess::Result`](crate::Result) of what the arm returns, which fails if the class of an arm can't be loaded.
+#[macro_export]
+macro_rules! match_throwable {
+    (@arms $jvm:ident, $exception:ident; _ => $fallback:expr $(,)?) => {
+        Ok(($fallback)($exception))
+    };
+
+    (@arms $jvm:ident, $exception:ident; $class:ty => $handler:expr, $($rest:tt)*) => {
+        match $crate::prelude::JvmOp::execute_with(
+            $crate::prelude::JvmOp::try_downcast::<$class>($exception),
+            $jvm,
+        ) {
+            Ok(Ok(e)) => Ok(($handler)(e)),
+            Ok(Err(_)) => $crate::match_throwable!(@arms $jvm, $exception; $($rest)*),
+            Err(error) => Err(error),
+        }
+    };
+
+    ($jvm:expr, $exception:expr, { $($arms:tt)* }) => {{
+        let jvm: &mut $crate::Jvm<'_> = &mut *$jvm;
+        let exception = $exception;
+        $crate::match_throwable!(@arms jvm, exception; $($arms)*)
+    }};
+}
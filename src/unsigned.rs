@@ -0,0 +1,75 @@
+//! Rust unsigned integers as arguments to Java methods, whose integers are all signed.
+//!
+//! A `u8`, `u32`, `u64` or `usize` can be passed to a Java `byte`, `short`, `int` or `long` parameter (an `i8`, `i16`,
+//! `i32` or `i64` in Rust). Where the Java type holds every value of the Rust one (e.g. a `u32` passed as a `long`) the
+//! value is widened; otherwise, it is checked to fit, failing with [`ConversionError::OutOfRange`]:
+//!
+//! ```ignore
+//! list.get(index) // `index: usize`, fails if it is more than `i32::MAX`
+//! ```
+//!
+//! Wrapping the value in a [`std::num::Wrapping`] opts into wrapping it around instead, as an `as` cast does, e.g. to
+//! pass a `u64` hash as a Java `long` with the same bits.
+
+use std::num::Wrapping;
+
+use crate::{error::ConversionError, IntoScalar, Jvm};
+
+macro_rules! unsigned {
+    ($($rust:ty => widened: [$($wide:ty),*], checked: [$($narrow:ty),*];)*) => {
+        $(
+            $(
+                impl IntoScalar<$wide> for $rust {
+                    const LOCALS: usize = 0;
+
+                    fn into_scalar<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $wide> {
+                        Ok(<$wide>::from(self))
+                    }
+                }
+            )*
+
+            $(
+                impl IntoScalar<$narrow> for $rust {
+                    const LOCALS: usize = 0;
+
+                    fn into_scalar<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $narrow> {
+                        <$narrow>::try_from(self).map_err(|_| {
+                            ConversionError::OutOfRange {
+                                value: self.to_string(),
+                                target: stringify!($narrow),
+                            }
+                            .into()
+                        })
+                    }
+                }
+            )*
+
+            $(
+                impl IntoScalar<$wide> for Wrapping<$rust> {
+                    const LOCALS: usize = 0;
+
+                    fn into_scalar<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $wide> {
+                        Ok(<$wide>::from(self.0))
+                    }
+                }
+            )*
+
+            $(
+                impl IntoScalar<$narrow> for Wrapping<$rust> {
+                    const LOCALS: usize = 0;
+
+                    fn into_scalar<'jvm>(self, _jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, $narrow> {
+                        Ok(self.0 as $narrow)
+                    }
+                }
+            )*
+        )*
+    };
+}
+
+unsigned! {
+    u8 => widened: [i16, i32, i64], checked: [i8];
+    u32 => widened: [i64], checked: [i8, i16, i32];
+    u64 => widened: [], checked: [i8, i16, i32, i64];
+    usize => widened: [], checked: [i8, i16, i32, i64];
+}
@@ -0,0 +1,26 @@
+//! Conversions of `java.util.UUID` to and from `uuid::Uuid` (with the `uuid` feature), through the two `long`s that
+//! hold its most and least significant bits, without formatting it as a string.
+
+use ::uuid::Uuid;
+
+use crate::{java, to_java::ToJavaImpl, IntoRust, Jvm, JvmOp, Local};
+
+impl ToJavaImpl<java::util::UUID> for Uuid {
+    fn to_java_impl<'jvm>(
+        rust: &Self,
+        jvm: &mut Jvm<'jvm>,
+    ) -> crate::Result<'jvm, Option<Local<'jvm, java::util::UUID>>> {
+        let (most, least) = rust.as_u64_pair();
+        Ok(Some(
+            java::util::UUID::new(most as i64, least as i64).execute_with(jvm)?,
+        ))
+    }
+}
+
+impl IntoRust<Uuid> for &java::util::UUID {
+    fn into_rust<'jvm>(self, jvm: &mut Jvm<'jvm>) -> crate::Result<'jvm, Uuid> {
+        let most = self.get_most_significant_bits().execute_with(jvm)? as u64;
+        let least = self.get_least_significant_bits().execute_with(jvm)? as u64;
+        Ok(Uuid::from_u64_pair(most, least))
+    }
+}
@@ -9,9 +9,15 @@ use walkdir::WalkDir;
 const SOURCE_PATH: &str = "java";
 const TARGET_PATH: &str = "../target";
 
+// Sources that are packaged into jar files instead, one per package, for testing `from_jar`. The classes are not put
+// in the target directory, so they are only found in the jars.
+const JAR_SOURCE_PATH: &str = "java-jar";
+const JAR_TARGET_PATH: &str = "../target/jars";
+
 fn main() -> std::io::Result<()> {
     // Rerun java build if any source file changes, but then we'll check each file individually below
     println!("cargo:rerun-if-changed={}", SOURCE_PATH);
+    println!("cargo:rerun-if-changed={}", JAR_SOURCE_PATH);
     println!("cargo:rustc-env=CLASSPATH=target/java");
 
     let target_dir = Path::new(TARGET_PATH);
@@ -37,6 +43,54 @@ fn main() -> std::io::Result<()> {
         }
     }
 
+    for entry_result in std::fs::read_dir(JAR_SOURCE_PATH)? {
+        build_jar(&entry_result?.path())?;
+    }
+
+    Ok(())
+}
+
+/// Compiles the sources of the package in `package_dir` and packages them into `<package>.jar`
+fn build_jar(package_dir: &Path) -> std::io::Result<()> {
+    let package = package_dir.file_name().unwrap().to_str().unwrap();
+    let classes_dir = Path::new(JAR_TARGET_PATH).join(package);
+    let sources: Vec<PathBuf> = WalkDir::new(package_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "java"))
+        .collect();
+
+    let output = Command::new("javac")
+        .arg("-d")
+        .arg(&classes_dir)
+        .args(&sources)
+        .output()?;
+    if !output.status.success() {
+        println!(
+            "cargo:warning=Failed to build {:?}: {}",
+            package_dir,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Ok(());
+    }
+
+    let output = Command::new("jar")
+        .arg("--create")
+        .arg("--file")
+        .arg(Path::new(JAR_TARGET_PATH).join(format!("{package}.jar")))
+        .arg("-C")
+        .arg(&classes_dir)
+        .arg(".")
+        .output()?;
+    if !output.status.success() {
+        println!(
+            "cargo:warning=Failed to package {:?}: {}",
+            package_dir,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,25 @@
+package jarred;
+
+import java.util.List;
+
+public class Greeter {
+    public static final String DEFAULT_NAME = "world";
+
+    private final String greeting;
+
+    public Greeter(String greeting) {
+        this.greeting = greeting;
+    }
+
+    public String greet(String name) {
+        return greeting + ", " + name + "!";
+    }
+
+    public String greetAll(List<String> names) {
+        StringBuilder result = new StringBuilder();
+        for (String name : names) {
+            result.append(greet(name)).append(' ');
+        }
+        return result.toString().trim();
+    }
+}
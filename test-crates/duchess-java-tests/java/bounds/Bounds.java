@@ -0,0 +1,41 @@
+package bounds;
+
+import java.util.ArrayList;
+import java.util.List;
+
+public class Bounds {
+    public static <T extends Number & Comparable<T>> T max(T a, T b) {
+        return a.compareTo(b) >= 0 ? a : b;
+    }
+
+    public static <U, T extends U> U widen(T t) {
+        return t;
+    }
+
+    public static double sum(List<? extends Number> numbers) {
+        double sum = 0;
+        for (Number n : numbers) {
+            sum += n.doubleValue();
+        }
+        return sum;
+    }
+
+    public static void fill(List<? super Integer> list, int count) {
+        for (int i = 0; i < count; i++) {
+            list.add(i);
+        }
+    }
+
+    public static List<? extends Number> numbers() {
+        List<Integer> list = new ArrayList<>();
+        list.add(1);
+        list.add(2);
+        return list;
+    }
+
+    public static List<?> anything() {
+        List<String> list = new ArrayList<>();
+        list.add("something");
+        return list;
+    }
+}
@@ -0,0 +1,17 @@
+package bounds;
+
+public class Holder<T extends Number> {
+    private T value;
+
+    public Holder(T value) {
+        this.value = value;
+    }
+
+    public T get() {
+        return value;
+    }
+
+    public void set(T value) {
+        this.value = value;
+    }
+}
@@ -0,0 +1,17 @@
+package builders;
+
+public class LocalServerBuilder {
+    private int port;
+
+    public LocalServerBuilder() {
+    }
+
+    public LocalServerBuilder port(int port) {
+        this.port = port;
+        return this;
+    }
+
+    public Server build() {
+        return Server.builder().host("localhost").setPort(port).build();
+    }
+}
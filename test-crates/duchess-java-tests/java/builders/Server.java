@@ -0,0 +1,48 @@
+package builders;
+
+public class Server {
+    private final String host;
+    private final int port;
+    private final boolean secure;
+
+    private Server(Builder builder) {
+        this.host = builder.host;
+        this.port = builder.port;
+        this.secure = builder.secure;
+    }
+
+    public static Builder builder() {
+        return new Builder();
+    }
+
+    public String describe() {
+        return (secure ? "https://" : "http://") + host + ":" + port;
+    }
+
+    public static class Builder {
+        private String host;
+        private int port;
+        private boolean secure;
+
+        public Builder() {
+        }
+
+        public Builder host(String host) {
+            this.host = host;
+            return this;
+        }
+
+        public Builder setPort(int port) {
+            this.port = port;
+            return this;
+        }
+
+        public void withSecure(boolean secure) {
+            this.secure = secure;
+        }
+
+        public Server build() {
+            return new Server(this);
+        }
+    }
+}
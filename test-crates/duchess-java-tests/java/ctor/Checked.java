@@ -0,0 +1,20 @@
+package ctor;
+
+public class Checked {
+    private final int value;
+
+    public Checked(int value) {
+        if (value < 0) {
+            throw new IllegalArgumentException("negative value");
+        }
+        this.value = value;
+    }
+
+    public Checked doubled() {
+        return new Checked(value * 2);
+    }
+
+    public int value() {
+        return value;
+    }
+}
@@ -0,0 +1,19 @@
+package deep;
+
+public final class Member {
+    private final String name;
+    private final String role;
+
+    public Member(String name, String role) {
+        this.name = name;
+        this.role = role;
+    }
+
+    public String name() {
+        return name;
+    }
+
+    public String role() {
+        return role;
+    }
+}
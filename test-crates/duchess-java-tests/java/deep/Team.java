@@ -0,0 +1,38 @@
+package deep;
+
+import java.util.ArrayList;
+import java.util.LinkedList;
+import java.util.List;
+
+public final class Team {
+    private final String name;
+    private final List<Member> members = new LinkedList<>();
+    private final List<Team> subteams = new ArrayList<>();
+
+    public Team(String name, int size) {
+        this.name = name;
+        for (int i = 0; i < size; i++) {
+            members.add(new Member(name + "-" + i, i == 0 ? "lead" : "member"));
+        }
+    }
+
+    public String name() {
+        return name;
+    }
+
+    public Member lead() {
+        return members.get(0);
+    }
+
+    public List<Member> members() {
+        return members;
+    }
+
+    public List<Team> subteams() {
+        return subteams;
+    }
+
+    public void addSubteam(Team team) {
+        subteams.add(team);
+    }
+}
@@ -0,0 +1,17 @@
+package enums;
+
+public enum Color {
+    RED,
+    GREEN,
+    BLUE;
+
+    public static final Color DEFAULT = GREEN;
+
+    public Color next() {
+        return values()[(ordinal() + 1) % values().length];
+    }
+
+    public static Color parse(String name) {
+        return valueOf(name);
+    }
+}
@@ -0,0 +1,17 @@
+package fields;
+
+public class Point {
+    public int x;
+    public int y;
+    public boolean visible = true;
+    public String label;
+    public final String id;
+
+    public Point(String id) {
+        this.id = id;
+    }
+
+    public int sum() {
+        return x + y;
+    }
+}
@@ -0,0 +1,31 @@
+package getters;
+
+public class Person {
+    private final String name;
+    private final int age;
+    private final boolean active;
+    private final String nickname;
+
+    public Person(String name, int age, boolean active, String nickname) {
+        this.name = name;
+        this.age = age;
+        this.active = active;
+        this.nickname = nickname;
+    }
+
+    public String getName() {
+        return name;
+    }
+
+    public int getAge() {
+        return age;
+    }
+
+    public boolean isActive() {
+        return active;
+    }
+
+    public String getNickname() {
+        return nickname;
+    }
+}
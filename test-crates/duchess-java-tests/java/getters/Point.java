@@ -0,0 +1,3 @@
+package getters;
+
+public record Point(int x, int y, String label) {}
@@ -0,0 +1,18 @@
+package money;
+
+// Shaped like Joda-Money's `CurrencyUnit`, which isn't available to the tests
+public final class CurrencyUnit {
+    private final String code;
+
+    private CurrencyUnit(String code) {
+        this.code = code;
+    }
+
+    public static CurrencyUnit of(String code) {
+        return new CurrencyUnit(code);
+    }
+
+    public String getCode() {
+        return code;
+    }
+}
@@ -0,0 +1,34 @@
+package money;
+
+import java.math.BigDecimal;
+
+// Shaped like Joda-Money's `Money`, which isn't available to the tests
+public final class Money {
+    private final CurrencyUnit currency;
+    private final BigDecimal amount;
+
+    private Money(CurrencyUnit currency, BigDecimal amount) {
+        this.currency = currency;
+        this.amount = amount;
+    }
+
+    public static Money of(CurrencyUnit currency, BigDecimal amount) {
+        return new Money(currency, amount);
+    }
+
+    public CurrencyUnit getCurrencyUnit() {
+        return currency;
+    }
+
+    public BigDecimal getAmount() {
+        return amount;
+    }
+
+    public Money plus(Money other) {
+        return new Money(currency, amount.add(other.amount));
+    }
+
+    public String toString() {
+        return currency.getCode() + " " + amount.toPlainString();
+    }
+}
@@ -0,0 +1,9 @@
+package native_panic;
+
+public class Native {
+    public String greet(String name) {
+        return explode(name);
+    }
+
+    native String explode(String name);
+}
@@ -0,0 +1,42 @@
+package nested;
+
+public class Outer {
+    private final String name;
+
+    public Outer(String name) {
+        this.name = name;
+    }
+
+    public String getName() {
+        return name;
+    }
+
+    public Inner inner(int count) {
+        return new Inner(count);
+    }
+
+    public static class Nested {
+        public Nested() {
+        }
+
+        public String greet() {
+            return "hello from nested";
+        }
+    }
+
+    public class Inner {
+        private final int count;
+
+        public Inner(int count) {
+            this.count = count;
+        }
+
+        public String describe() {
+            return name + " x " + count;
+        }
+
+        public Outer outer() {
+            return Outer.this;
+        }
+    }
+}
@@ -0,0 +1,76 @@
+package protobuf;
+
+import java.io.ByteArrayOutputStream;
+
+/// Stand-in for a generated protobuf message `message Point { int32 x = 1; int32 y = 2; }`, with just enough of the
+/// wire format (non-negative varints) for the test.
+public final class Point {
+    private final int x;
+    private final int y;
+
+    public Point(int x, int y) {
+        this.x = x;
+        this.y = y;
+    }
+
+    public int getX() {
+        return x;
+    }
+
+    public int getY() {
+        return y;
+    }
+
+    public byte[] toByteArray() {
+        ByteArrayOutputStream out = new ByteArrayOutputStream();
+        writeField(out, 1, x);
+        writeField(out, 2, y);
+        return out.toByteArray();
+    }
+
+    public static Point parseFrom(byte[] bytes) {
+        int x = 0;
+        int y = 0;
+        int[] pos = {0};
+        while (pos[0] < bytes.length) {
+            int tag = readVarint(bytes, pos);
+            int value = readVarint(bytes, pos);
+            switch (tag >>> 3) {
+                case 1: x = value; break;
+                case 2: y = value; break;
+                default: throw new IllegalArgumentException("unknown field " + (tag >>> 3));
+            }
+        }
+        return new Point(x, y);
+    }
+
+    private static void writeField(ByteArrayOutputStream out, int field, int value) {
+        if (value == 0) {
+            return;
+        }
+        writeVarint(out, field << 3);
+        writeVarint(out, value);
+    }
+
+    private static void writeVarint(ByteArrayOutputStream out, int value) {
+        while ((value & ~0x7F) != 0) {
+            out.write((value & 0x7F) | 0x80);
+            value >>>= 7;
+        }
+        out.write(value);
+    }
+
+    private static int readVarint(byte[] bytes, int[] pos) {
+        int value = 0;
+        for (int shift = 0; ; shift += 7) {
+            if (pos[0] >= bytes.length) {
+                throw new IllegalArgumentException("truncated varint");
+            }
+            byte b = bytes[pos[0]++];
+            value |= (b & 0x7F) << shift;
+            if ((b & 0x80) == 0) {
+                return value;
+            }
+        }
+    }
+}
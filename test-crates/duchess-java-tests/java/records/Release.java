@@ -0,0 +1,3 @@
+package records;
+
+public record Release(String name, Version version, long downloads) {}
@@ -0,0 +1,7 @@
+package records;
+
+public record Version(int major, int minor, String label) {
+    public boolean isStable() {
+        return major > 0;
+    }
+}
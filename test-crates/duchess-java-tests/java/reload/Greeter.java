@@ -0,0 +1,7 @@
+package reload;
+
+public class Greeter {
+    public String greet() {
+        return "compiled with the tests";
+    }
+}
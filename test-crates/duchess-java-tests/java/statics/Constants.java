@@ -0,0 +1,20 @@
+package statics;
+
+public class Constants {
+    public static final boolean ENABLED = true;
+    public static final byte SMALL = -3;
+    public static final char LETTER = 'x';
+    public static final char MAX_CHAR = '\uffff';
+    public static final short SHORT = 300;
+    public static final int INT = -5;
+    public static final long MIN_LONG = Long.MIN_VALUE;
+    public static final float HALF = 1.5f;
+    public static final float NAN = Float.NaN;
+    public static final double TINY = Double.MIN_VALUE;
+    public static final double NEG_INF = Double.NEGATIVE_INFINITY;
+    public static final String GREETING = "h\u00e9llo \"world\" = \ud83d\ude00;\n";
+
+    // Not constants: computed at runtime, or of other types
+    public static final int COMPUTED = Integer.parseInt("7");
+    public static final Integer BOXED = 8;
+}
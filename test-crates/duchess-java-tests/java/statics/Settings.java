@@ -0,0 +1,9 @@
+package statics;
+
+public class Settings {
+    public static final int MAX_RETRIES = 3;
+
+    public static int retries;
+    public static boolean verbose;
+    public static String name = "default";
+}
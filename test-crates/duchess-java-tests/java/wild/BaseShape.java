@@ -0,0 +1,8 @@
+package wild;
+
+// Not public, so not generated, and left out of the superclasses of `Circle`
+abstract class BaseShape {
+    public String kind() {
+        return getClass().getSimpleName();
+    }
+}
@@ -0,0 +1,26 @@
+package wild;
+
+public class Circle extends BaseShape implements Shape {
+    private final double radius;
+
+    public Circle(double radius) {
+        this.radius = radius;
+    }
+
+    public double area() {
+        return Math.PI * radius * radius;
+    }
+
+    public double getRadius() {
+        return radius;
+    }
+
+    // Overloaded, so these can't both be generated
+    public Circle scale(double factor) {
+        return new Circle(radius * factor);
+    }
+
+    public Circle scale(int factor) {
+        return new Circle(radius * factor);
+    }
+}
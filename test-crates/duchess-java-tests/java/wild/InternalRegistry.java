@@ -0,0 +1,7 @@
+package wild;
+
+public class InternalRegistry {
+    public static void register(Shape shape) {}
+
+    public static void register(Shape shape, String name) {}
+}
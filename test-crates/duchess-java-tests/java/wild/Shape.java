@@ -0,0 +1,5 @@
+package wild;
+
+public interface Shape {
+    double area();
+}
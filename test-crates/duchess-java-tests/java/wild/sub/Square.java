@@ -0,0 +1,23 @@
+package wild.sub;
+
+import wild.Shape;
+
+public class Square implements Shape {
+    private final double side;
+
+    public Square(double side) {
+        this.side = side;
+    }
+
+    public double area() {
+        return side * side;
+    }
+
+    public String label() {
+        return "square";
+    }
+
+    public String label(String prefix) {
+        return prefix + " square";
+    }
+}
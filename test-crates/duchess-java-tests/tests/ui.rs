@@ -3,6 +3,8 @@ use ui_test::*;
 
 fn main() -> color_eyre::eyre::Result<()> {
     std::env::set_var("CLASSPATH", "../target/java");
+    // Check the descriptors that the macros derive for the test classes against their class files
+    std::env::set_var("DUCHESS_CHECK_DESCRIPTORS", "1");
 
     // Tests can be blessed with `cargo test -- -- --bless`.
     let bless = std::env::args().any(|arg| arg == "--bless");
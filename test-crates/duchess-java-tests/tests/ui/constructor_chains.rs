@@ -0,0 +1,32 @@
+//@run
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package ctor;
+
+    public class ctor.Checked {
+        public ctor.Checked(int);
+        public ctor.Checked doubled();
+        public int value();
+    }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use ctor::Checked;
+
+    // Constructed objects can't be null, so methods on them need no `assert_not_null`
+    assert_eq!(Checked::new(21).value().execute()?, 21);
+    assert_eq!(Checked::new(21).doubled().value().execute()?, 42);
+
+    // Exceptions thrown by constructors are still reported
+    match Checked::new(-1).global().execute() {
+        Err(duchess::Error::Thrown(_)) => {}
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("constructor didn't throw"),
+    }
+
+    // ...and don't linger, so later calls succeed
+    assert_eq!(Checked::new(1).value().execute()?, 1);
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//@ run
+
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package deep;
+
+    public final class deep.Member {
+        public java.lang.String name();
+        public java.lang.String role();
+    }
+
+    public final class deep.Team {
+        public deep.Team(java.lang.String, int);
+        public java.lang.String name();
+        public java.util.List<deep.Member> members();
+        public java.util.List<deep.Team> subteams();
+        public void addSubteam(deep.Team);
+    }
+}
+
+#[derive(Debug, PartialEq, duchess::ToRust)]
+#[java(deep.Member)]
+struct Member {
+    name: String,
+    role: String,
+}
+
+#[derive(Debug, PartialEq, duchess::ToRust)]
+#[java(deep.Team)]
+struct Team {
+    name: String,
+    members: Vec<Member>,
+    subteams: Vec<Team>,
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    let team: Team = duchess::Jvm::with(|jvm| {
+        let root = deep::Team::new("root", 2).execute_with(jvm)?;
+        for i in 0..100 {
+            let subteam = deep::Team::new(&format!("sub{i}"), 50).execute_with(jvm)?;
+            root.add_subteam(&subteam).execute_with(jvm)?;
+        }
+        (&*root).to_rust_deep().execute_with(jvm)
+    })?;
+
+    assert_eq!(team.name, "root");
+    assert_eq!(
+        team.members,
+        vec![
+            Member {
+                name: "root-0".into(),
+                role: "lead".into()
+            },
+            Member {
+                name: "root-1".into(),
+                role: "member".into()
+            },
+        ]
+    );
+    assert_eq!(team.subteams.len(), 100);
+    assert_eq!(team.subteams[99].name, "sub99");
+    assert_eq!(team.subteams[99].members[49].name, "sub99-49");
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//@run
+use duchess::{java, prelude::*};
+
+duchess::java_package! {
+    package builders;
+
+    public class builders.Server { * }
+    public static class builders.Server$Builder { * }
+    public class builders.LocalServerBuilder { * }
+}
+
+/// Created with the static `builder` method
+#[derive(duchess::ToJava)]
+#[java(builders.Server::builder)]
+struct Server {
+    host: String,
+    port: i32,
+    secure: bool,
+}
+
+/// Created with the constructor of the builder
+#[derive(duchess::ToJava)]
+#[java(builders.LocalServerBuilder)]
+struct LocalServer {
+    port: i32,
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    let server = Server {
+        host: "example.com".into(),
+        port: 443,
+        secure: true,
+    };
+    let description: String = server
+        .to_java::<builders::Server>()
+        .assert_not_null()
+        .describe()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(description, "https://example.com:443");
+
+    let local = LocalServer { port: 8080 };
+    let description: String = local
+        .to_java::<builders::Server>()
+        .assert_not_null()
+        .describe()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(description, "http://localhost:8080");
+    Ok(())
+}
@@ -0,0 +1,65 @@
+//@run
+
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package getters;
+
+    public class getters.Person {
+        public getters.Person(java.lang.String, int, boolean, java.lang.String);
+        public java.lang.String getName();
+        public int getAge();
+        public boolean isActive();
+        public java.lang.String getNickname();
+    }
+
+    public final class getters.Point extends java.lang.Record {
+        public getters.Point(int, int, java.lang.String);
+        public int x();
+        public int y();
+        public java.lang.String label();
+    }
+}
+
+#[derive(Debug, PartialEq, duchess::ToRust)]
+#[java(getters.Person)]
+struct Person {
+    name: String,
+    age: i32,
+    active: bool,
+    nickname: Option<String>,
+}
+
+#[derive(Debug, PartialEq, duchess::ToRust)]
+#[java(getters.Point)]
+struct Point {
+    x: i32,
+    y: i32,
+    label: String,
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    let person: Person = getters::Person::new("Ada", 36, true, "Countess")
+        .to_rust()
+        .execute()?;
+    assert_eq!(
+        person,
+        Person {
+            name: "Ada".into(),
+            age: 36,
+            active: true,
+            nickname: Some("Countess".into()),
+        }
+    );
+
+    let point: Point = getters::Point::new(3, 4, "corner").to_rust().execute()?;
+    assert_eq!(
+        point,
+        Point {
+            x: 3,
+            y: 4,
+            label: "corner".into(),
+        }
+    );
+    Ok(())
+}
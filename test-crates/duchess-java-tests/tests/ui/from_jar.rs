@@ -0,0 +1,42 @@
+//@run
+use duchess::{java, prelude::*};
+
+duchess::java_package! {
+    // `jarred` classes are only in this jar, not on the `CLASSPATH`, so they are reflected by reading it
+    from_jar "../target/jars/jarred.jar";
+
+    package jarred;
+
+    public class jarred.Greeter { * }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    // The JVM needs the jar on its classpath as well
+    duchess::Jvm::builder()
+        .add_classpath("../target/jars/jarred.jar")
+        .try_launch()?;
+
+    use jarred::Greeter;
+
+    let greeter = Greeter::new("hello").global().execute()?;
+    let greeting: String = greeter
+        .greet(Greeter::DEFAULT_NAME)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(greeting, "hello, world!");
+
+    let names = vec!["duchess".to_string(), "jvm".to_string()];
+    let names = names
+        .to_java::<java::util::List<java::lang::String>>()
+        .global()
+        .execute()?;
+    let greetings: String = greeter
+        .greet_all(&names)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(greetings, "hello, duchess! hello, jvm!");
+
+    Ok(())
+}
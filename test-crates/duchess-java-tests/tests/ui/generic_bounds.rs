@@ -0,0 +1,49 @@
+//@run
+use duchess::{java, prelude::*};
+
+duchess::java_package! {
+    package bounds;
+
+    public class bounds.Bounds { * }
+    public class bounds.Holder { * }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use bounds::Bounds;
+
+    // `<T extends Number & Comparable<T>>` accepts `Integer`, and returns an `Integer`
+    let a = java::lang::Integer::value_of(3).global().execute()?;
+    let b = java::lang::Integer::value_of(7).global().execute()?;
+    let max: i32 = Bounds::max::<java::lang::Integer>(&a, &b).int_value().execute()?;
+    assert_eq!(max, 7);
+
+    // `<U, T extends U>` is bounded by another parameter
+    let widened: i32 = Bounds::widen::<java::lang::Number, java::lang::Integer>(&a).int_value().execute()?;
+    assert_eq!(widened, 3);
+
+    // `List<? extends Number>` accepts a list of `Integer`s
+    let integers = java::util::ArrayList::<java::lang::Integer>::new().global().execute()?;
+    integers.add(&a).execute()?;
+    integers.add(&b).execute()?;
+    assert_eq!(Bounds::sum(&integers).execute()?, 10.0);
+
+    // `List<? super Integer>` accepts a list of `Number`s
+    let numbers = java::util::ArrayList::<java::lang::Number>::new().global().execute()?;
+    Bounds::fill(&numbers, 3).execute()?;
+    assert_eq!(numbers.size().execute()?, 3);
+
+    // Returned wildcards are translated to their upper bound: `List<? extends Number>` is a `List<Number>`...
+    let first: i64 = Bounds::numbers().get(0).long_value().execute()?;
+    assert_eq!(first, 1);
+
+    // ...and `List<?>` is a `List<Object>`
+    let element: String = Bounds::anything().get(0).to_string().assert_not_null().to_rust().execute()?;
+    assert_eq!(element, "something");
+
+    // Uses of a class's bounded parameter erase to its bound, `Number`
+    let holder = bounds::Holder::<java::lang::Integer>::new(&a).global().execute()?;
+    holder.set(&b).execute()?;
+    assert_eq!(holder.get().int_value().execute()?, 7);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//@run
+
+use duchess::{hot_reload, java, prelude::*, Global, Jvm};
+
+duchess::java_package! {
+    package reload;
+
+    public class reload.Greeter {
+        public reload.Greeter();
+        public java.lang.String greet();
+    }
+}
+
+/// Compiles a version of `reload.Greeter` and loads it with a new class loader.
+fn load_greeter(greeting: &str) -> duchess::GlobalResult<Global<java::lang::ClassLoader>> {
+    let source = format!(
+        "package reload; public class Greeter {{ public String greet() {{ return \"{greeting}\"; }} }}"
+    );
+    Jvm::with(|jvm| {
+        let compiled = duchess::compile::compile(jvm, &[("reload.Greeter", &source)], &[])?
+            .expect("failed to compile");
+        let loaded = compiled.load(jvm)?;
+        Ok(jvm.global(&**loaded.loader()))
+    })
+}
+
+fn greet() -> duchess::GlobalResult<String> {
+    reload::Greeter::new()
+        .greet()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    // Without the test classes on the class path, so that only the loaded versions are found
+    Jvm::builder().custom("-Djava.class.path=").try_launch()?;
+
+    hot_reload::set_class_loader(Some(load_greeter("v1")?));
+    assert_eq!(greet()?, "v1");
+
+    // SAFETY: no `Greeter` of the old version is used afterwards
+    let invalidated = unsafe { hot_reload::swap_class_loader(Some(load_greeter("v2")?)) }?;
+    // The class and its constructor and method
+    assert_eq!(invalidated, 3);
+    assert_eq!(greet()?, "v2");
+    Ok(())
+}
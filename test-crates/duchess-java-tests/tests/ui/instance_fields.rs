@@ -0,0 +1,44 @@
+//@run
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package fields;
+
+    public class fields.Point {
+        public int x;
+        public int y;
+        public boolean visible;
+        public java.lang.String label;
+        public final java.lang.String id;
+        public fields.Point(java.lang.String);
+        public int sum();
+    }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use fields::Point;
+
+    let point = Point::new("p1").global().execute()?;
+    assert_eq!(point.get_field_x().execute()?, 0);
+
+    // Setters on objects, and on ops
+    point.set_field_x(3).execute()?;
+    Point::set_field_y(&point, 4).execute()?;
+    assert_eq!(point.sum().execute()?, 7);
+    assert_eq!(Point::new("p2").get_field_y().execute()?, 0);
+
+    assert!(point.get_field_visible().execute()?);
+    point.set_field_visible(false).execute()?;
+    assert!(!point.get_field_visible().execute()?);
+
+    let label: Option<String> = point.get_field_label().to_rust().execute()?;
+    assert_eq!(label, None);
+    point.set_field_label("origin").execute()?;
+    let label: Option<String> = point.get_field_label().to_rust().execute()?;
+    assert_eq!(label.as_deref(), Some("origin"));
+
+    let id: Option<String> = point.get_field_id().to_rust().execute()?;
+    assert_eq!(id.as_deref(), Some("p1"));
+
+    Ok(())
+}
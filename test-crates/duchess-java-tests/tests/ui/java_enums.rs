@@ -0,0 +1,53 @@
+//@run
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package enums;
+
+    public final class enums.Color { * }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use enums::{Color, ColorEnum};
+
+    // From Java to Rust, e.g. to `match` on the result of a method
+    let green: ColorEnum = Color::parse("GREEN")
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(green, ColorEnum::Green);
+    let next: ColorEnum = Color::parse("BLUE")
+        .next()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(next, ColorEnum::Red);
+
+    // From Rust to Java
+    let name: String = ColorEnum::Blue
+        .to_java::<Color>()
+        .name()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(name, "BLUE");
+    let next: ColorEnum = ColorEnum::Green
+        .to_java::<Color>()
+        .next()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(next, ColorEnum::Blue);
+
+    // Other fields of the enum's type aren't variants, so matching on the constants is exhaustive
+    let default: ColorEnum = Color::get_default()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    match default {
+        ColorEnum::Red | ColorEnum::Blue => unreachable!(),
+        ColorEnum::Green => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//@run
+use duchess::{
+    java,
+    money::{CurrencyCode, Decimal, Money, MoneyClass},
+    prelude::*,
+    Jvm, Local,
+};
+
+duchess::java_package! {
+    package money;
+
+    public final class money.CurrencyUnit { * }
+
+    public final class money.Money { * }
+}
+
+impl MoneyClass for money::Money {
+    fn amount<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        money: &Self,
+    ) -> duchess::Result<'jvm, Local<'jvm, java::math::BigDecimal>> {
+        money.get_amount().assert_not_null().execute_with(jvm)
+    }
+
+    fn currency_code<'jvm>(jvm: &mut Jvm<'jvm>, money: &Self) -> duchess::Result<'jvm, String> {
+        money
+            .get_currency_unit()
+            .assert_not_null()
+            .get_code()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)
+    }
+
+    fn of<'jvm>(
+        jvm: &mut Jvm<'jvm>,
+        amount: &java::math::BigDecimal,
+        currency_code: &str,
+    ) -> duchess::Result<'jvm, Local<'jvm, Self>> {
+        let currency = money::CurrencyUnit::of(currency_code);
+        money::Money::of(currency, amount)
+            .assert_not_null()
+            .execute_with(jvm)
+    }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    let price = Money {
+        amount: Decimal::new(1250, 2),
+        currency: CurrencyCode::new("EUR"),
+    };
+    let java_price = price
+        .to_java::<money::Money>()
+        .assert_not_null()
+        .global()
+        .execute()?;
+    let text: String = java_price
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(text, "EUR 12.50");
+
+    let total: Money = java_price
+        .plus(&java_price)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(total.amount, Decimal::new(2500, 2));
+    assert_eq!(total.amount.to_string(), "25.00");
+    assert_eq!(total.currency, CurrencyCode::new("EUR"));
+
+    assert_eq!(Decimal::new(-5, 3).to_string(), "-0.005");
+    assert_eq!(Decimal::new(12, -2).to_string(), "1200");
+
+    // Negative amounts, and ones that don't fit in an `i64`
+    let debt = Decimal::new(-123_456_789_012_345_678_901_234_567, 5);
+    let java_debt = debt
+        .to_java::<java::math::BigDecimal>()
+        .assert_not_null()
+        .global()
+        .execute()?;
+    let text: String = java_debt
+        .to_plain_string()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(text, debt.to_string());
+    let back: Decimal = (&*java_debt).to_rust().execute()?;
+    assert_eq!(back, debt);
+
+    let currency = CurrencyCode::new("JPY")
+        .to_java::<java::util::Currency>()
+        .assert_not_null()
+        .global()
+        .execute()?;
+    assert_eq!(currency.get_default_fraction_digits().execute()?, 0);
+    let code: CurrencyCode = (&*currency).to_rust().execute()?;
+    assert_eq!(code.as_str(), "JPY");
+
+    Ok(())
+}
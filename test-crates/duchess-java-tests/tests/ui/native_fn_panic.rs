@@ -0,0 +1,40 @@
+//@ run
+
+use duchess::{java, prelude::*};
+
+duchess::java_package! {
+    package native_panic;
+
+    public class native_panic.Native {
+        public native_panic.Native();
+        public java.lang.String greet(java.lang.String);
+        native java.lang.String explode(java.lang.String);
+    }
+}
+
+#[duchess::java_function(native_panic.Native::explode)]
+fn explode(_this: &native_panic::Native, _name: &java::lang::String) -> String {
+    panic!("boom")
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    // Keep the expected panic from printing to stderr.
+    std::panic::set_hook(Box::new(|_| {}));
+
+    duchess::Jvm::builder()
+        .link(explode::java_fn())
+        .try_launch()?;
+
+    // The panic is turned into a `RuntimeException` that propagates back out through Java.
+    let err = native_panic::Native::new()
+        .greet("Ferris")
+        .assert_not_null()
+        .to_rust::<String>()
+        .execute()
+        .unwrap_err();
+
+    assert!(matches!(err, duchess::Error::Thrown(_)));
+    assert_eq!(err.to_string(), "Java invocation threw: java.lang.RuntimeException: Rust panic: boom");
+
+    Ok(())
+}
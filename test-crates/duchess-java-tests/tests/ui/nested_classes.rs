@@ -0,0 +1,40 @@
+//@run
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package nested;
+
+    public class nested.Outer { * }
+    public static class nested.Outer$Nested { * }
+    public class nested.Outer$Inner { * }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use nested::{Outer, Outer__Inner, Outer__Nested};
+
+    // Static nested classes are constructed like any other class
+    let greeting: String = Outer__Nested::new()
+        .greet()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(greeting, "hello from nested");
+
+    // Inner classes are constructed with their outer instance as the first argument
+    let outer = Outer::new("outer").global().execute()?;
+    let inner = Outer__Inner::new(&outer, 3).global().execute()?;
+    let description: String = inner.describe().assert_not_null().to_rust().execute()?;
+    assert_eq!(description, "outer x 3");
+
+    // ...and can be returned from and used in the methods of other classes
+    let name: String = outer
+        .inner(5)
+        .outer()
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(name, "outer");
+
+    Ok(())
+}
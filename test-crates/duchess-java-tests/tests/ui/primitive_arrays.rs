@@ -0,0 +1,36 @@
+//@ run
+
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package primitive_arrays;
+
+    public final class primitive_arrays.Arrays {
+        public static int sum(int[]);
+        public static byte[] reverse(byte[]);
+        public static double[] scale(double[], double);
+    }
+}
+
+use primitive_arrays::Arrays;
+
+fn main() -> duchess::GlobalResult<()> {
+    let values = vec![1, 2, 3, 4];
+    assert_eq!(Arrays::sum(&values).execute()?, 10);
+    assert_eq!(Arrays::sum(&[5, 6]).execute()?, 11);
+    assert_eq!(Arrays::sum(&values[..2]).execute()?, 3);
+
+    let bytes: Vec<u8> = Arrays::reverse(b"abc\xFF")
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(bytes, b"\xFFcba");
+
+    let scaled: Vec<f64> = Arrays::scale(&vec![0.5, 1.5], 2.0)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(scaled, vec![1.0, 3.0]);
+
+    Ok(())
+}
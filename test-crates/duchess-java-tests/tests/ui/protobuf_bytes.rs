@@ -0,0 +1,39 @@
+//@ run
+
+use duchess::prelude::*;
+use duchess::Jvm;
+
+duchess::java_package! {
+    package protobuf;
+
+    public final class protobuf.Point {
+        public protobuf.Point(int, int);
+        public int getX();
+        public int getY();
+        public byte[] toByteArray();
+        public static protobuf.Point parseFrom(byte[]);
+    }
+}
+
+use protobuf::Point;
+
+fn main() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        // What e.g. `prost` would encode for `Point { x: 3, y: 300 }`
+        let encoded = [0x08, 3, 0x10, 0xAC, 0x02];
+
+        let point = jvm.parse_proto::<Point>(&encoded)?;
+        assert_eq!(point.get_x().execute_with(jvm)?, 3);
+        assert_eq!(point.get_y().execute_with(jvm)?, 300);
+
+        let bytes = point.to_proto_bytes().execute_with(jvm)?;
+        assert_eq!(bytes, encoded);
+
+        let bytes = Point::new(0, 1).to_proto_bytes().execute_with(jvm)?;
+        assert_eq!(bytes, [0x10, 1]);
+
+        // Invalid messages throw from `parseFrom`
+        assert!(jvm.parse_proto::<Point>(&[0x18, 1]).is_err());
+        Ok(())
+    })
+}
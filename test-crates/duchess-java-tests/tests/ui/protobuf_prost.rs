@@ -0,0 +1,36 @@
+//@ run
+
+use duchess::prelude::*;
+use duchess::Jvm;
+
+duchess::java_package! {
+    package protobuf;
+
+    public final class protobuf.Point {
+        public protobuf.Point(int, int);
+        public int getX();
+        public int getY();
+        public byte[] toByteArray();
+        public static protobuf.Point parseFrom(byte[]);
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct Point {
+    #[prost(int32, tag = "1")]
+    x: i32,
+    #[prost(int32, tag = "2")]
+    y: i32,
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        let point = jvm.prost_to_java::<protobuf::Point>(&Point { x: 3, y: 300 })?;
+        assert_eq!(point.get_x().execute_with(jvm)?, 3);
+        assert_eq!(point.get_y().execute_with(jvm)?, 300);
+
+        let point: Point = protobuf::Point::new(-1, 7).to_prost().execute_with(jvm)?;
+        assert_eq!(point, Point { x: -1, y: 7 });
+        Ok(())
+    })
+}
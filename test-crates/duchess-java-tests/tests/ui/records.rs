@@ -0,0 +1,57 @@
+//@run
+
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package records;
+
+    public final class records.Version extends java.lang.Record {
+        public records.Version(int, int, java.lang.String);
+        public boolean isStable();
+        public int major();
+        public int minor();
+        public java.lang.String label();
+        public java.lang.String toString();
+        public final int hashCode();
+    }
+
+    public final class records.Release extends java.lang.Record {
+        public records.Release(java.lang.String, records.Version, long);
+        public java.lang.String name();
+        public records.Version version();
+        public long downloads();
+    }
+}
+
+use records::{Release, ReleaseRecord, Version, VersionRecord};
+
+fn main() -> duchess::GlobalResult<()> {
+    let version = VersionRecord {
+        major: 1,
+        minor: 4,
+        label: "stable".into(),
+    };
+    let java_version = version.to_java::<Version>().assert_not_null().global().execute()?;
+    assert!(java_version.is_stable().execute()?);
+    let text: String = java_version.to_string().assert_not_null().to_rust().execute()?;
+    assert_eq!(text, "Version[major=1, minor=4, label=stable]");
+
+    let round_trip: VersionRecord = (&*java_version).to_rust().execute()?;
+    assert_eq!(round_trip, version);
+
+    let release = ReleaseRecord {
+        name: "duchess".into(),
+        version: java_version,
+        downloads: 1 << 40,
+    }
+    .to_java::<Release>()
+    .assert_not_null()
+    .global()
+    .execute()?;
+    let release: ReleaseRecord = (&*release).to_rust().execute()?;
+    assert_eq!(release.name, "duchess");
+    assert_eq!(release.downloads, 1 << 40);
+    let version: VersionRecord = (&*release.version).to_rust().execute()?;
+    assert_eq!(version.minor, 4);
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//@run
+
+use duchess::{java, prelude::*, Error};
+
+fn length() -> duchess::GlobalResult<i32> {
+    "duchess"
+        .to_java::<java::lang::String>()
+        .assert_not_null()
+        .length()
+        .execute()
+}
+
+fn main() -> duchess::GlobalResult<()> {
+    duchess::install_shutdown_bridge()?;
+    duchess::install_shutdown_bridge()?;
+    assert!(!duchess::is_shutting_down());
+
+    duchess::on_shutdown(|| {
+        assert!(duchess::is_shutting_down());
+        // Operations of other threads are rejected
+        let other = std::thread::spawn(length).join().unwrap();
+        assert!(matches!(other, Err(Error::ShuttingDown)), "{other:?}");
+        // Callbacks can still use the JVM
+        assert_eq!(length().unwrap(), 7);
+
+        // The test fails with the status given to `exit` unless the callback gets here
+        java::lang::Runtime::get_runtime()
+            .assert_not_null()
+            .halt(0)
+            .execute()
+            .unwrap();
+    });
+
+    assert_eq!(length()?, 7);
+    java::lang::System::exit(1).execute()?;
+    unreachable!()
+}
@@ -0,0 +1,55 @@
+//@run
+use duchess::{java, prelude::*};
+
+duchess::java_package! {
+    package statics;
+
+    public class statics.Constants {
+        public static final boolean ENABLED;
+        public static final byte SMALL;
+        public static final char LETTER;
+        public static final char MAX_CHAR;
+        public static final short SHORT;
+        public static final int INT;
+        public static final long MIN_LONG;
+        public static final float HALF;
+        public static final float NAN;
+        public static final double TINY;
+        public static final double NEG_INF;
+        public static final java.lang.String GREETING;
+        public static final int COMPUTED;
+    }
+}
+
+// Usable in const contexts, without a JVM
+const BUFFER: [u8; statics::Constants::SHORT as usize] = [0; statics::Constants::SHORT as usize];
+const INT_MAX: i32 = java::lang::Integer::MAX_VALUE;
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use statics::Constants;
+
+    assert!(Constants::ENABLED);
+    assert_eq!(Constants::SMALL, -3);
+    assert_eq!(Constants::LETTER, 'x' as u16);
+    assert_eq!(Constants::MAX_CHAR, u16::MAX);
+    assert_eq!(BUFFER.len(), 300);
+    assert_eq!(Constants::INT, -5);
+    assert_eq!(Constants::MIN_LONG, i64::MIN);
+    assert_eq!(Constants::HALF, 1.5);
+    assert!(Constants::NAN.is_nan());
+    assert_eq!(Constants::TINY, 4.9E-324);
+    assert_eq!(Constants::NEG_INF, f64::NEG_INFINITY);
+    assert_eq!(Constants::GREETING, "h\u{e9}llo \"world\" = \u{1f600};\n");
+    assert_eq!(INT_MAX, i32::MAX);
+    assert_eq!(java::lang::Long::MIN_VALUE, i64::MIN);
+
+    // The constants agree with the values read through JNI
+    assert_eq!(Constants::get_int().execute()?, Constants::INT);
+    let greeting: Option<String> = Constants::get_greeting().to_rust().execute()?;
+    assert_eq!(greeting.as_deref(), Some(Constants::GREETING));
+
+    // Fields that aren't constants are only available through their getters
+    assert_eq!(Constants::get_computed().execute()?, 7);
+
+    Ok(())
+}
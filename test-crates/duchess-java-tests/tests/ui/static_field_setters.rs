@@ -0,0 +1,33 @@
+//@run
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package statics;
+
+    public class statics.Settings {
+        public static final int MAX_RETRIES;
+        public static int retries;
+        public static boolean verbose;
+        public static java.lang.String name;
+    }
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use statics::Settings;
+
+    assert_eq!(Settings::get_max_retries().execute()?, 3);
+
+    Settings::set_retries(Settings::get_max_retries()).execute()?;
+    assert_eq!(Settings::get_retries().execute()?, 3);
+
+    Settings::set_verbose(true).execute()?;
+    assert!(Settings::get_verbose().execute()?);
+
+    let name: Option<String> = Settings::get_name().to_rust().execute()?;
+    assert_eq!(name.as_deref(), Some("default"));
+    Settings::set_name("custom").execute()?;
+    let name: Option<String> = Settings::get_name().to_rust().execute()?;
+    assert_eq!(name.as_deref(), Some("custom"));
+
+    Ok(())
+}
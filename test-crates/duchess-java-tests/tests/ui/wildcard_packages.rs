@@ -0,0 +1,43 @@
+//@run
+use duchess::prelude::*;
+
+duchess::java_package! {
+    package wild;
+
+    // All public classes of `wild` and its subpackages...
+    generate *;
+
+    // ...except these, which have overloaded methods
+    exclude wild.Internal*;
+
+    // Overloaded methods can be left out...
+    exclude Circle::scale;
+
+    // ...or the methods of a class selected
+    include wild.sub.Square::area;
+}
+
+duchess::java_package! {
+    package java.util.function;
+
+    // Classes of the JDK are found as well
+    generate java.util.function.*Supplier;
+}
+
+pub fn main() -> duchess::GlobalResult<()> {
+    use wild::{sub::Square, Circle, Shape};
+
+    let circle = Circle::new(2.0).global().execute()?;
+    assert_eq!(circle.get_radius().execute()?, 2.0);
+    let shape: &Shape = circle.as_ref();
+    assert!((shape.area().execute()? - 4.0 * std::f64::consts::PI).abs() < 1e-9);
+
+    let square = Square::new(3.0).global().execute()?;
+    let shape: &Shape = square.as_ref();
+    assert_eq!(shape.area().execute()?, 9.0);
+
+    let _: Option<&java::util::function::IntSupplier> = None;
+    let _: Option<&java::util::function::Supplier<duchess::java::lang::Object>> = None;
+
+    Ok(())
+}
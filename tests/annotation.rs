@@ -0,0 +1,189 @@
+use std::collections::BTreeMap;
+
+use duchess::{
+    annotation::{AnnotationValue, AnnotationValues},
+    compile::compile,
+    java,
+    prelude::*,
+    Jvm,
+};
+
+mod jdk {
+    duchess::java_package! {
+        package java.lang;
+
+        public interface java.lang.Deprecated extends java.lang.annotation.Annotation {
+            public abstract java.lang.String since();
+            public abstract boolean forRemoval();
+        }
+    }
+}
+
+const SOURCES: &[(&str, &str)] = &[
+    (
+        "model.Column",
+        "package model;
+        import java.lang.annotation.*;
+        @Retention(RetentionPolicy.RUNTIME)
+        @interface Column {
+            String name();
+            int width() default 10;
+            char separator() default ',';
+            double weight() default 1.5;
+            Class<?> type() default String.class;
+            ElementType target() default ElementType.FIELD;
+            long[] limits() default {1L, 2L};
+            String[] aliases() default {};
+            Deprecated note() default @Deprecated(since = \"2\");
+        }",
+    ),
+    (
+        "model.User",
+        "package model;
+        @Deprecated(since = \"1.5\", forRemoval = true)
+        public class User {
+            @Column(name = \"user_name\", aliases = {\"login\", \"handle\"})
+            public String name;
+            public int age;
+        }",
+    ),
+];
+
+#[test]
+fn annotations() {
+    Jvm::with(|jvm| {
+        let compiled = compile(jvm, SOURCES, &[])?.unwrap();
+        let classes = compiled.load(jvm)?;
+        let user = classes.class(jvm, "model.User")?;
+
+        // Typed annotations
+        let deprecated = (&*user)
+            .get_annotation::<jdk::java::lang::Deprecated>()
+            .execute_with(jvm)?
+            .expect("User is deprecated");
+        let since: String = deprecated
+            .since()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(since, "1.5");
+        assert!(deprecated.for_removal().execute_with(jvm)?);
+
+        // The same, chained onto the lookup of the annotation
+        let deprecated = (&*user)
+            .get_annotation::<jdk::java::lang::Deprecated>()
+            .assert_not_null();
+        let since: String = deprecated
+            .since()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(since, "1.5");
+        assert!(deprecated.for_removal().execute_with(jvm)?);
+
+        let name = user
+            .get_declared_field("name")
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let age = user
+            .get_declared_field("age")
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let not_deprecated = (&*name)
+            .get_annotation::<jdk::java::lang::Deprecated>()
+            .execute_with(jvm)?;
+        assert!(not_deprecated.is_none());
+
+        // Converted annotations
+        let annotations: Vec<AnnotationValues> = name
+            .get_annotations()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let expected = AnnotationValues {
+            annotation_type: "model.Column".into(),
+            values: BTreeMap::from([
+                ("name".into(), AnnotationValue::String("user_name".into())),
+                ("width".into(), AnnotationValue::Int(10)),
+                ("separator".into(), AnnotationValue::Char(',')),
+                ("weight".into(), AnnotationValue::Double(1.5)),
+                (
+                    "type".into(),
+                    AnnotationValue::Class("java.lang.String".into()),
+                ),
+                (
+                    "target".into(),
+                    AnnotationValue::Enum {
+                        class_name: "java.lang.annotation.ElementType".into(),
+                        name: "FIELD".into(),
+                    },
+                ),
+                (
+                    "limits".into(),
+                    AnnotationValue::Array(vec![
+                        AnnotationValue::Long(1),
+                        AnnotationValue::Long(2),
+                    ]),
+                ),
+                (
+                    "aliases".into(),
+                    AnnotationValue::Array(vec![
+                        AnnotationValue::String("login".into()),
+                        AnnotationValue::String("handle".into()),
+                    ]),
+                ),
+                (
+                    "note".into(),
+                    AnnotationValue::Annotation(AnnotationValues {
+                        annotation_type: "java.lang.Deprecated".into(),
+                        values: BTreeMap::from([
+                            ("since".into(), AnnotationValue::String("2".into())),
+                            ("forRemoval".into(), AnnotationValue::Boolean(false)),
+                        ]),
+                    }),
+                ),
+            ]),
+        };
+        assert_eq!(annotations, [expected]);
+
+        let annotations: Vec<AnnotationValues> = age
+            .get_annotations()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(annotations.is_empty());
+
+        let annotations: Vec<AnnotationValues> = user
+            .get_annotations()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(
+            annotations[0].get("forRemoval"),
+            Some(&AnnotationValue::Boolean(true))
+        );
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn annotations_of_jdk_classes() {
+    let annotations: Vec<AnnotationValues> = Jvm::with(|jvm| {
+        let runnable = <java::lang::Runnable as duchess::JavaObject>::class(jvm)?;
+        runnable
+            .get_annotations()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)
+    })
+    .unwrap();
+    assert_eq!(
+        annotations,
+        [AnnotationValues {
+            annotation_type: "java.lang.FunctionalInterface".into(),
+            values: BTreeMap::new(),
+        }]
+    );
+}
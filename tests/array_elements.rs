@@ -0,0 +1,105 @@
+use duchess::{java, prelude::*, Jvm, Local};
+
+#[test]
+fn critical_elements_are_written_back() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<i8>> = vec![1i8, 2, 3]
+            .to_java::<java::Array<i8>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let sum = array.with_elements(jvm, |bytes| {
+            bytes.reverse();
+            bytes.iter().map(|&b| i32::from(b)).sum::<i32>()
+        })?;
+        assert_eq!(sum, 6);
+
+        let bytes: Vec<i8> = (&*array).to_rust().execute_with(jvm)?;
+        assert_eq!(bytes, [3, 2, 1]);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn non_critical_elements_can_use_the_jvm() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<f64>> = vec![0.5_f64, 1.5]
+            .to_java::<java::Array<f64>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let other: Local<java::Array<f64>> = vec![2.0_f64]
+            .to_java::<java::Array<f64>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        array.with_elements_non_critical(jvm, |jvm, values| {
+            let len = other.length().execute_with(jvm)?;
+            values[0] += f64::from(len);
+            Ok(())
+        })?;
+
+        let values: Vec<f64> = (&*array).to_rust().execute_with(jvm)?;
+        assert_eq!(values, [1.5, 1.5]);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn empty_arrays() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<i64>> = Vec::<i64>::new()
+            .to_java::<java::Array<i64>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let len = array.with_elements(jvm, |values| values.len())?;
+        assert_eq!(len, 0);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn references_dropped_in_critical_sections() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<i32>> = vec![1, 2]
+            .to_java::<java::Array<i32>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let string = "kept".to_java::<java::lang::String>().assert_not_null();
+        let local = string.execute_with(jvm)?;
+        let global = jvm.global(&*local);
+        let weak = jvm.weak(&*local);
+        let kept = jvm.global(&*local);
+        // Deleting the references is deferred until the elements are released
+        array.with_elements(jvm, move |values| {
+            drop(global);
+            drop(weak);
+            drop(local);
+            values[0] = 3;
+        })?;
+
+        let values: Vec<i32> = (&*array).to_rust().execute_with(jvm)?;
+        assert_eq!(values, [3, 2]);
+        let kept: String = (&*kept).to_rust().execute_with(jvm)?;
+        assert_eq!(kept, "kept");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn jvm_cannot_be_reentered_in_critical_sections() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<i32>> = vec![1, 2]
+            .to_java::<java::Array<i32>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let reentered = array.with_elements(jvm, |_| Jvm::with(|_| Ok(())))?;
+        assert!(matches!(reentered, Err(duchess::Error::InCriticalSection)));
+        Ok(())
+    })
+    .unwrap();
+
+    // The section has ended, so the JVM can be used again
+    Jvm::with(|_| Ok(())).unwrap();
+}
@@ -0,0 +1,29 @@
+//! Enabling Java assertions per package and class when launching the JVM.
+
+use duchess::{java, prelude::*, JavaObject, Jvm, Verbose};
+
+fn launch() {
+    Jvm::builder()
+        .enable_assertions_in_package("java.util")
+        .disable_assertions_in_package("java.util.concurrent")
+        .enable_assertions_in_class("java.lang.Thread")
+        .verbose(Verbose::Gc)
+        .launch_or_use_existing()
+        .unwrap();
+}
+
+fn assertions_enabled<T: JavaObject>() -> duchess::GlobalResult<bool> {
+    Jvm::with(|jvm| T::class(jvm)?.desired_assertion_status().execute_with(jvm))
+}
+
+#[test]
+fn assertions_follow_the_options() -> duchess::GlobalResult<()> {
+    launch();
+    assert!(assertions_enabled::<
+        java::util::ArrayList<java::lang::Object>,
+    >()?);
+    assert!(!assertions_enabled::<java::util::concurrent::TimeUnit>()?);
+    assert!(assertions_enabled::<java::lang::Thread>()?);
+    assert!(!assertions_enabled::<java::lang::String>()?);
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::time::{Duration, SystemTime};
+
+use duchess::{bean::BeanValue, java, prelude::*, Global, Jvm};
+
+fn date(millis: u64) -> Global<java::util::Date> {
+    (SystemTime::UNIX_EPOCH + Duration::from_millis(millis))
+        .to_java::<java::util::Date>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap()
+}
+
+#[test]
+fn primitive_properties() {
+    let date = date(1234);
+    assert_eq!(
+        date.get_property("time").execute().unwrap(),
+        BeanValue::Long(1234)
+    );
+
+    date.set_property("time", &BeanValue::Long(5678))
+        .execute()
+        .unwrap();
+    assert_eq!(
+        date.get_property("time").execute().unwrap(),
+        BeanValue::Long(5678)
+    );
+}
+
+#[test]
+fn string_and_boolean_properties() {
+    std::thread::spawn(|| {
+        let thread = java::lang::Thread::current_thread()
+            .assert_not_null()
+            .global()
+            .execute()
+            .unwrap();
+        thread
+            .set_property("name", &"renamed by duchess".into())
+            .execute()
+            .unwrap();
+        assert_eq!(
+            thread.get_property("name").execute().unwrap(),
+            BeanValue::String("renamed by duchess".into())
+        );
+        // Read with `isDaemon`
+        assert!(matches!(
+            thread.get_property("daemon").execute().unwrap(),
+            BeanValue::Boolean(_)
+        ));
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn object_properties() {
+    let date = date(0);
+    let BeanValue::Object(class) = date.get_property("class").execute().unwrap() else {
+        panic!("`getClass` should return an object");
+    };
+    let name: String = Jvm::with(|jvm| {
+        let Ok(class) = class
+            .try_downcast::<java::lang::Class>()
+            .execute_with(jvm)?
+        else {
+            panic!("`getClass` should return a class");
+        };
+        class
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)
+    })
+    .unwrap();
+    assert_eq!(name, "java.util.Date");
+}
+
+#[test]
+fn missing_properties() {
+    let date = date(0);
+    let error = date.get_property("noSuchProperty").execute().unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("`java.util.Date` has no readable property `noSuchProperty`"),
+        "{error}"
+    );
+
+    // `getClass` has no setter
+    let error = date
+        .set_property("class", &BeanValue::Null)
+        .execute()
+        .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("has no writable property `class`"),
+        "{error}"
+    );
+}
+
+#[test]
+fn values_of_the_wrong_type() {
+    let date = date(0);
+    let error = date
+        .set_property("time", &BeanValue::Int(1))
+        .execute()
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("is not a valid `long`"),
+        "{error}"
+    );
+}
@@ -0,0 +1,138 @@
+//! `i128` and `u128` (and, with their features, `num_bigint::BigInt` and `rust_decimal::Decimal`) to and from
+//! `java.math.BigInteger` and `java.math.BigDecimal`.
+
+use duchess::{java, prelude::*};
+
+#[test]
+fn i128_round_trips() -> duchess::GlobalResult<()> {
+    for value in [
+        0,
+        1,
+        -1,
+        255,
+        -256,
+        i128::from(i64::MIN) - 1,
+        i128::MIN,
+        i128::MAX,
+    ] {
+        let back: i128 = value
+            .to_java::<java::math::BigInteger>()
+            .assert_not_null()
+            .to_rust()
+            .execute()?;
+        assert_eq!(back, value);
+    }
+    let string: String = i128::MIN
+        .to_java::<java::math::BigInteger>()
+        .assert_not_null()
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(string, i128::MIN.to_string());
+    Ok(())
+}
+
+#[test]
+fn u128_round_trips() -> duchess::GlobalResult<()> {
+    for value in [0, 1, 128, u128::from(u64::MAX) + 1, 1 << 127, u128::MAX] {
+        let back: u128 = value
+            .to_java::<java::math::BigInteger>()
+            .assert_not_null()
+            .to_rust()
+            .execute()?;
+        assert_eq!(back, value);
+    }
+    Ok(())
+}
+
+#[test]
+fn out_of_range() -> duchess::GlobalResult<()> {
+    let too_big = u128::MAX
+        .to_java::<java::math::BigInteger>()
+        .assert_not_null()
+        .try_to_rust::<i128>()
+        .execute()?;
+    assert!(too_big.is_err());
+
+    let negative = java::math::BigInteger::value_of(-5_i64)
+        .assert_not_null()
+        .try_to_rust::<u128>()
+        .execute()?;
+    assert!(negative.is_err());
+
+    let value: i128 = java::math::BigInteger::value_of(-5_i64)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(value, -5);
+    Ok(())
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn big_ints_round_trip() -> duchess::GlobalResult<()> {
+    use num_bigint::BigInt;
+
+    let huge = BigInt::from(u128::MAX) * BigInt::from(u128::MAX);
+    for value in [
+        BigInt::from(0),
+        BigInt::from(-1),
+        BigInt::from(i128::MIN),
+        -huge.clone(),
+        huge,
+    ] {
+        let java = value
+            .to_java::<java::math::BigInteger>()
+            .assert_not_null()
+            .global()
+            .execute()?;
+        let string: String = java.to_string().assert_not_null().to_rust().execute()?;
+        assert_eq!(string, value.to_string());
+        let back: BigInt = (&*java).to_rust().execute()?;
+        assert_eq!(back, value);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn rust_decimals_round_trip() -> duchess::GlobalResult<()> {
+    use rust_decimal::Decimal;
+
+    for value in [
+        Decimal::ZERO,
+        Decimal::new(1250, 2),
+        Decimal::new(-5, 28),
+        Decimal::MAX,
+        Decimal::MIN,
+    ] {
+        let java = value
+            .to_java::<java::math::BigDecimal>()
+            .assert_not_null()
+            .global()
+            .execute()?;
+        let string: String = java
+            .to_plain_string()
+            .assert_not_null()
+            .to_rust()
+            .execute()?;
+        assert_eq!(string, value.to_string());
+        let back: Decimal = (&*java).to_rust().execute()?;
+        assert_eq!(back, value);
+    }
+
+    // Negative scales and trailing zeros past the maximum scale fit, other digits don't
+    let convert = |unscaled: i128, scale: i32| {
+        duchess::money::Decimal::new(unscaled, scale)
+            .to_java::<java::math::BigDecimal>()
+            .assert_not_null()
+            .try_to_rust::<Decimal>()
+            .execute()
+    };
+    assert_eq!(convert(12, -2)?, Ok(Decimal::new(1200, 0)));
+    assert_eq!(convert(10_i128.pow(31), 32)?, Ok(Decimal::new(1, 1)));
+    assert!(convert(1, 29)?.is_err());
+    assert!(convert(1, -40)?.is_err());
+    Ok(())
+}
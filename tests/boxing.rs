@@ -0,0 +1,89 @@
+//! Conversions between Rust scalars and boxed Java values.
+
+use std::collections::HashMap;
+
+use duchess::{java, prelude::*};
+
+#[test]
+fn scalars_round_trip_through_wrappers() -> duchess::GlobalResult<()> {
+    let int: i32 = 42
+        .to_java::<java::lang::Integer>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(int, 42);
+    let long: i64 = i64::MIN
+        .to_java::<java::lang::Long>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(long, i64::MIN);
+    let double: f64 = 0.5
+        .to_java::<java::lang::Double>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(double, 0.5);
+    let boolean: bool = true
+        .to_java::<java::lang::Boolean>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert!(boolean);
+    let char: u16 = ('é' as u16)
+        .to_java::<java::lang::Character>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(char, 'é' as u16);
+
+    let string: String = 7_i8
+        .to_java::<java::lang::Byte>()
+        .assert_not_null()
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(string, "7");
+    Ok(())
+}
+
+#[test]
+fn numbers_convert_like_java_casts() -> duchess::GlobalResult<()> {
+    let number = 2.75
+        .to_java::<java::lang::Double>()
+        .assert_not_null()
+        .upcast::<java::lang::Number>();
+    let int: i32 = number.to_rust().execute()?;
+    assert_eq!(int, 2);
+    let float: f32 = number.to_rust().execute()?;
+    assert_eq!(float, 2.75);
+
+    let number = 300_i16
+        .to_java::<java::lang::Short>()
+        .assert_not_null()
+        .upcast::<java::lang::Number>();
+    let byte: i8 = number.to_rust().execute()?;
+    assert_eq!(byte, 300_i16 as i8);
+    Ok(())
+}
+
+#[test]
+fn collections_of_boxed_values() -> duchess::GlobalResult<()> {
+    let values = vec![1, -2, i32::MAX];
+    let back: Vec<i32> = values
+        .to_java::<java::util::List<java::lang::Integer>>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(back, values);
+
+    let map: HashMap<String, f64> = [("half".to_string(), 0.5), ("double".to_string(), 2.0)].into();
+    let back: HashMap<String, f64> = map
+        .to_java::<java::util::Map<java::lang::String, java::lang::Double>>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(back, map);
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use duchess::{
+    call::{self, call_method, call_static_method, find_method, IntoJniValue, JavaObjectExt},
+    java,
+    prelude::*,
+    Jvm, Local,
+};
+
+/// Looks up `java.lang.Math`, which has no bindings.
+fn math<'jvm>(jvm: &mut Jvm<'jvm>) -> duchess::Result<'jvm, Local<'jvm, java::lang::Class>> {
+    duchess::plumbing::find_class(jvm, c"java/lang/Math")
+}
+
+#[test]
+fn instance_methods() {
+    Jvm::with(|jvm| {
+        let hello = "hello"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let world = "world"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let concat: Option<Local<java::lang::String>> = unsafe {
+            call_method(
+                jvm,
+                &*hello,
+                c"concat",
+                c"(Ljava/lang/String;)Ljava/lang/String;",
+                &[(&*world).into_jni_value()],
+            )
+        }?;
+        let concat: String = (&*concat.unwrap()).to_rust().execute_with(jvm)?;
+        assert_eq!(concat, "helloworld");
+
+        let c: u16 =
+            unsafe { call_method(jvm, &*hello, c"charAt", c"(I)C", &[1.into_jni_value()]) }?;
+        assert_eq!(c, u16::from(b'e'));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn static_methods() {
+    Jvm::with(|jvm| {
+        let math = math(jvm)?;
+        let max: i32 = unsafe {
+            call_static_method(
+                jvm,
+                &math,
+                c"max",
+                c"(II)I",
+                &[3.into_jni_value(), 7.into_jni_value()],
+            )
+        }?;
+        assert_eq!(max, 7);
+
+        let property: Option<Local<java::lang::String>> = unsafe {
+            let system = duchess::plumbing::find_class(jvm, c"java/lang/System")?;
+            let key = "duchess.no.such.property"
+                .to_java::<java::lang::String>()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            call_static_method(
+                jvm,
+                &system,
+                c"getProperty",
+                c"(Ljava/lang/String;)Ljava/lang/String;",
+                &[(&*key).into_jni_value()],
+            )
+        }?;
+        assert!(property.is_none());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn exceptions_are_errors() {
+    let error = Jvm::with(|jvm| {
+        let integer = duchess::plumbing::find_class(jvm, c"java/lang/Integer")?;
+        let text = "not a number"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let _: i32 = unsafe {
+            call_static_method(
+                jvm,
+                &integer,
+                c"parseInt",
+                c"(Ljava/lang/String;)I",
+                &[(&*text).into_jni_value()],
+            )
+        }?;
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(
+        error.to_string().contains("NumberFormatException"),
+        "{error}"
+    );
+}
+
+#[test]
+fn missing_methods_are_errors() {
+    let error = Jvm::with(|jvm| {
+        let math = math(jvm)?;
+        let _: i32 = unsafe { call_static_method(jvm, &math, c"noSuchMethod", c"()I", &[]) }?;
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(error.to_string().contains("NoSuchMethodError"), "{error}");
+}
+
+#[test]
+fn shims_with_raw_handles() {
+    Jvm::with(|jvm| {
+        let math = math(jvm)?;
+        let abs = find_method(jvm, &math, c"abs", c"(J)J", true)?;
+        for i in -3i64..=3 {
+            let abs: i64 = unsafe {
+                call::static_long_method(jvm, math.as_raw(), abs, &[i.into_jni_value()])
+            }?;
+            assert_eq!(abs, i.abs());
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "takes 2 arguments, but 1 were passed")]
+fn wrong_number_of_arguments() {
+    let _ = Jvm::with(|jvm| {
+        let math = math(jvm)?;
+        unsafe { call_static_method::<i32>(jvm, &math, c"max", c"(II)I", &[1.into_jni_value()]) }
+    });
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "doesn't return `i64`")]
+fn wrong_result_type() {
+    let _ = Jvm::with(|jvm| {
+        let math = math(jvm)?;
+        let args = [1.into_jni_value(), 2.into_jni_value()];
+        unsafe { call_static_method::<i64>(jvm, &math, c"max", c"(II)I", &args) }
+    });
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "invalid method descriptor")]
+fn invalid_descriptor() {
+    let _ = Jvm::with(|jvm| {
+        let math = math(jvm)?;
+        unsafe { call_static_method::<i32>(jvm, &math, c"max", c"(II", &[]) }
+    });
+}
+
+#[test]
+fn call_macro_selects_overloads() {
+    Jvm::with(|jvm| {
+        let hello = "hello"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let lo = "lo"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let by_char: i32 = duchess::call!(jvm, hello.indexOf(i32::from(b'o')))?;
+        let by_string: i32 = duchess::call!(jvm, hello.indexOf(&*lo))?;
+        assert_eq!((by_char, by_string), (4, 3));
+        let from: i32 = duchess::call!(jvm, (&*hello).indexOf(i32::from(b'l'), 3))?;
+        assert_eq!(from, 3);
+
+        let mut values = vec![];
+        for value in [
+            duchess::call!(jvm, <java::lang::String>::valueOf(true))?,
+            duchess::call!(jvm, <java::lang::String>::valueOf(u16::from(b'c')))?,
+            duchess::call!(jvm, <java::lang::String>::valueOf(-7))?,
+            duchess::call!(jvm, <java::lang::String>::valueOf(i64::MAX))?,
+            duchess::call!(jvm, <java::lang::String>::valueOf(1.5))?,
+        ] {
+            let value: Option<Local<java::lang::String>> = value;
+            let value: String = (&*value.unwrap()).to_rust().execute_with(jvm)?;
+            values.push(value);
+        }
+        assert_eq!(values, ["true", "c", "-7", "9223372036854775807", "1.5"]);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn call_macro_static_methods_and_constructors() {
+    Jvm::with(|jvm| {
+        let math = math(jvm)?;
+        let int: i32 = duchess::call!(jvm, static math.max(3, 7))?;
+        let long: i64 = duchess::call!(jvm, static math.max(3_i64, i64::MAX))?;
+        let double: f64 = duchess::call!(jvm, static math.max(-0.5, 2.5))?;
+        assert_eq!((int, long, double), (7, i64::MAX, 2.5));
+
+        let list = duchess::call!(jvm, <java::util::ArrayList<java::lang::String>>::new(10))?;
+        let element = "element"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        // `add(E)` erases to `add(Object)`
+        let added: bool =
+            duchess::call!(jvm, list.add(AsRef::<java::lang::Object>::as_ref(&element)))?;
+        assert!(added);
+        let first: Option<Local<java::lang::Object>> = duchess::call!(jvm, list.get(0))?;
+        let first = first
+            .unwrap()
+            .try_downcast::<java::lang::String>()
+            .execute_with(jvm)?
+            .unwrap();
+        let first: String = (&*first).to_rust().execute_with(jvm)?;
+        assert_eq!(first, "element");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn call_macro_reports_missing_overloads() {
+    let error = Jvm::with(|jvm| {
+        let hello = "hello"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        // There is no `indexOf(long)`
+        let _: i32 = duchess::call!(jvm, hello.indexOf(1_i64))?;
+        Ok(())
+    })
+    .unwrap_err();
+    assert!(error.to_string().contains("NoSuchMethodError"), "{error}");
+}
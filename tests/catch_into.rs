@@ -0,0 +1,70 @@
+//! Catching exceptions into enums declared with `catch_enum!`.
+
+use duchess::{java, prelude::*, Error};
+
+duchess::catch_enum! {
+    #[derive(Debug)]
+    enum Failure {
+        IllegalArgument(java::lang::IllegalArgumentException),
+        Runtime(java::lang::RuntimeException),
+    }
+}
+
+duchess::catch_enum! {
+    enum OnlyIllegalState {
+        IllegalState(java::lang::IllegalStateException),
+    }
+}
+
+#[test]
+fn successes_pass_through() -> duchess::GlobalResult<()> {
+    let result = java::lang::Character::to_string(0x61)
+        .assert_not_null()
+        .to_rust()
+        .catch_into::<Failure>()
+        .execute()?;
+    let string: String = result.unwrap();
+    assert_eq!(string, "a");
+    Ok(())
+}
+
+#[test]
+fn first_matching_variant_wins() -> duchess::GlobalResult<()> {
+    // Not a code point
+    let result = java::lang::Character::to_string(-1)
+        .global()
+        .catch_into::<Failure>()
+        .execute()?;
+    assert!(
+        matches!(result, Err(Failure::IllegalArgument(_))),
+        "{result:?}"
+    );
+
+    let list = java::util::ArrayList::<java::lang::String>::new()
+        .global()
+        .execute()?;
+    let result = list.get(3).global().catch_into::<Failure>().execute()?;
+    let Err(Failure::Runtime(exception)) = result else {
+        panic!("expected a `RuntimeException`: {result:?}");
+    };
+    let class = exception
+        .get_class()
+        .assert_not_null()
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    let class: String = class;
+    assert_eq!(class, "java.lang.IndexOutOfBoundsException");
+    Ok(())
+}
+
+#[test]
+fn other_exceptions_are_rethrown() -> duchess::GlobalResult<()> {
+    let result = java::lang::Character::to_string(-1)
+        .global()
+        .catch_into::<OnlyIllegalState>()
+        .execute();
+    assert!(matches!(result, Err(Error::Thrown(_))));
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Tests of the `checked-env` feature, which only checks debug builds.
+#![cfg(all(feature = "checked-env", debug_assertions))]
+
+use duchess::{plumbing::EnvPtr, Jvm};
+
+/// An environment smuggled to another thread, as buggy unsafe code might do.
+struct Smuggled(EnvPtr<'static>);
+
+unsafe impl Send for Smuggled {}
+
+fn smuggle_env() -> Smuggled {
+    Jvm::with(|jvm| {
+        // SAFETY: not at all, which is what is being tested
+        Ok(Smuggled(unsafe {
+            std::mem::transmute::<EnvPtr<'_>, EnvPtr<'static>>(jvm.env())
+        }))
+    })
+    .unwrap()
+}
+
+/// Makes a JNI call with `env` on a new thread, returning the message it panics with.
+fn panic_on_other_thread(env: Smuggled, attach: bool) -> String {
+    let thread = std::thread::spawn(move || {
+        if attach {
+            Jvm::attach_thread_permanently().unwrap();
+        }
+        let env = env;
+        let _version: i32 = unsafe { env.0.invoke(|env| env.GetVersion, |env, f| f(env)) }.unwrap();
+    });
+    let panic = thread.join().unwrap_err();
+    panic.downcast_ref::<String>().unwrap().clone()
+}
+
+#[test]
+fn own_env_works() {
+    let version: i32 =
+        Jvm::with(|jvm| unsafe { jvm.env().invoke(|env| env.GetVersion, |env, f| f(env)) })
+            .unwrap();
+    assert!(version > 0);
+}
+
+#[test]
+fn env_of_other_thread_on_detached_thread() {
+    let message = panic_on_other_thread(smuggle_env(), false);
+    assert!(
+        message.contains("which isn't attached to the JVM"),
+        "{message}"
+    );
+}
+
+#[test]
+fn env_of_other_thread_on_attached_thread() {
+    let message = panic_on_other_thread(smuggle_env(), true);
+    assert!(
+        message.contains("environments must not be used from other threads"),
+        "{message}"
+    );
+}
+
+#[test]
+fn env_in_use_names_its_thread() {
+    let owner = std::thread::Builder::new()
+        .name("env-owner".into())
+        .spawn(|| {
+            Jvm::with(|jvm| {
+                // SAFETY: see `smuggle_env`
+                let env = Smuggled(unsafe {
+                    std::mem::transmute::<EnvPtr<'_>, EnvPtr<'static>>(jvm.env())
+                });
+                // Used while its thread is still in `Jvm::with`
+                Ok(panic_on_other_thread(env, true))
+            })
+            .unwrap()
+        })
+        .unwrap();
+    let message = owner.join().unwrap();
+    assert!(
+        message.contains("(in use by thread `env-owner`)"),
+        "{message}"
+    );
+}
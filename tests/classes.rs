@@ -0,0 +1,62 @@
+//! The registry of the Rust types that bind Java classes, and the downcasts that use it in their errors.
+
+use duchess::{java, prelude::*, ConversionError, Error, Jvm};
+
+#[test]
+fn classes_register_when_used() -> duchess::GlobalResult<()> {
+    let _map = java::util::HashMap::<java::lang::String, java::lang::String>::new()
+        .global()
+        .execute()?;
+    let class = duchess::classes::lookup("java.util.HashMap").unwrap();
+    assert_eq!(class.java_name, "java.util.HashMap");
+    // The path where the type is defined, which `duchess::java` re-exports
+    assert!(
+        class.rust_type.ends_with("::java::util::HashMap"),
+        "{class:?}"
+    );
+    assert!(duchess::classes::lookup("com.example.Unknown").is_none());
+    Ok(())
+}
+
+#[test]
+fn runtime_class_names() -> duchess::GlobalResult<()> {
+    let list = java::util::ArrayList::<java::lang::String>::new()
+        .global()
+        .execute()?;
+    let name = Jvm::with(|jvm| duchess::classes::runtime_class_name(jvm, &*list))?;
+    assert_eq!(name, "java.util.ArrayList");
+    Ok(())
+}
+
+#[test]
+fn downcasts_name_the_classes() -> duchess::GlobalResult<()> {
+    let object = java::util::ArrayList::<java::lang::String>::new()
+        .upcast::<java::lang::Object>()
+        .global()
+        .execute()?;
+    let list = object
+        .downcast::<java::util::ArrayList<java::lang::String>>()
+        .global()
+        .execute()?;
+    assert_eq!(list.size().execute()?, 0);
+
+    let line = line!() + 1;
+    let result = object.downcast::<java::lang::String>().global().execute();
+    let Err(Error::Conversion(error @ ConversionError::UnexpectedClass { .. })) = result else {
+        panic!("expected the downcast to fail: {result:?}");
+    };
+    let message = error.to_string();
+    assert!(
+        message.starts_with("expected java.lang.String (`duchess::java::"),
+        "{message}"
+    );
+    assert!(
+        message.contains("::java::lang::String`), got java.util.ArrayList (`duchess::java::"),
+        "{message}"
+    );
+    assert!(
+        message.contains(&format!("::java::util::ArrayList`) at {}:{line}:", file!())),
+        "{message}"
+    );
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Rust `char`s as Java code points, `Character`s and `char[]` arrays.
+
+use duchess::{java, prelude::*, ConversionError, Jvm};
+
+#[test]
+fn chars_are_passed_as_code_points() -> duchess::GlobalResult<()> {
+    let string = "a😀b".to_java::<java::lang::String>().assert_not_null();
+    assert_eq!(string.index_of('😀').execute()?, 1);
+    assert_eq!(string.index_of('b').execute()?, 3);
+    let c = string.code_point_at(1).try_to_rust::<char>().execute()?;
+    assert_eq!(c, Ok('😀'));
+
+    assert!(java::lang::Character::is_letter('é').execute()?);
+    let text: String = java::lang::Character::to_string('😀')
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(text, "😀");
+    Ok(())
+}
+
+#[test]
+fn invalid_code_points() -> duchess::GlobalResult<()> {
+    for code_point in [-1_i32, 0xD800, 0x110000] {
+        let c = code_point.try_to_rust::<char>().execute()?;
+        assert!(
+            matches!(c, Err(ConversionError::OutOfRange { .. })),
+            "{c:?}"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn characters() -> duchess::GlobalResult<()> {
+    let c: char = 'é'
+        .to_java::<java::lang::Character>()
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(c, 'é');
+
+    // Needs a surrogate pair
+    let boxed = Jvm::with(|jvm| {
+        let boxed = '😀'
+            .try_to_java::<java::lang::Character>()
+            .execute_with(jvm)?;
+        Ok(boxed.is_err())
+    })?;
+    assert!(boxed);
+
+    let surrogate = 0xD83D_u16
+        .to_java::<java::lang::Character>()
+        .assert_not_null()
+        .try_to_rust::<char>()
+        .execute()?;
+    assert!(surrogate.is_err());
+    Ok(())
+}
+
+#[test]
+fn char_arrays_use_surrogate_pairs() -> duchess::GlobalResult<()> {
+    let chars = vec!['a', '😀', 'é'];
+    let array = chars.to_java::<java::Array<u16>>().assert_not_null();
+    assert_eq!(array.length().execute()?, 4);
+    let back = array.try_to_rust::<Vec<char>>().execute()?;
+    assert_eq!(back, Ok(chars));
+
+    let from_string = "x😀"
+        .to_java::<java::lang::String>()
+        .assert_not_null()
+        .to_char_array()
+        .assert_not_null()
+        .try_to_rust::<Vec<char>>()
+        .execute()?;
+    assert_eq!(from_string, Ok(vec!['x', '😀']));
+
+    let unpaired = vec![0xD800_u16, 0x61]
+        .to_java::<java::Array<u16>>()
+        .assert_not_null()
+        .try_to_rust::<Vec<char>>()
+        .execute()?;
+    assert!(unpaired.is_err());
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use duchess::{java, prelude::*, Jvm};
+
+fn list(items: &[&str]) -> duchess::Global<java::util::List<java::lang::String>> {
+    let items: Vec<String> = items.iter().map(|s| s.to_string()).collect();
+    items
+        .to_java::<java::util::List<java::lang::String>>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap()
+}
+
+#[test]
+fn map_transforms_outputs() {
+    let list = list(&["a", "bb", "ccc"]);
+    let len = list
+        .size()
+        .map(|size, _jvm| size as usize)
+        .execute()
+        .unwrap();
+    assert_eq!(len, 3);
+
+    // The closure can look at Java objects through the JVM
+    let upper = list
+        .get(1)
+        .assert_not_null()
+        .map(|s, jvm| {
+            let s: String = (&*s).to_rust().execute_with(jvm).unwrap();
+            s.to_uppercase()
+        })
+        .execute()
+        .unwrap();
+    assert_eq!(upper, "BB");
+}
+
+#[test]
+fn and_then_runs_dependent_ops() {
+    let list = list(&["a", "bb", "ccc"]);
+    let last: String = list
+        .size()
+        .and_then(|size| list.get(size - 1))
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(last, "ccc");
+}
+
+#[test]
+fn and_then_propagates_errors() {
+    let list = list(&["a"]);
+    let error = list
+        .size()
+        .and_then(|size| list.get(size))
+        .map(|_, _| ())
+        .execute()
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("IndexOutOfBoundsException"),
+        "{error}"
+    );
+}
+
+#[test]
+fn zip_runs_both_ops() {
+    let list = list(&["a", "bb"]);
+    let (size, first): (i32, String) = list
+        .size()
+        .zip(list.get(0).assert_not_null().to_rust())
+        .execute()
+        .unwrap();
+    assert_eq!((size, first.as_str()), (2, "a"));
+}
+
+#[test]
+fn zip_stops_at_the_first_error() {
+    let list = list(&[]);
+    let error = list
+        .get(0)
+        .zip(
+            list.size()
+                .map(|_, _| -> () { unreachable!("ran after an error") }),
+        )
+        .map(|_, _| ())
+        .execute()
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("IndexOutOfBoundsException"),
+        "{error}"
+    );
+}
+
+#[test]
+fn combinators_chain_within_with() {
+    let list = list(&["x", "yy"]);
+    Jvm::with(|jvm| {
+        let (first, second) = list
+            .get(0)
+            .assert_not_null()
+            .zip(list.get(1).assert_not_null())
+            .execute_with(jvm)?;
+        let total = first
+            .length()
+            .zip(second.length())
+            .map(|(a, b), _| a + b)
+            .execute_with(jvm)?;
+        assert_eq!(total, 3);
+        Ok(())
+    })
+    .unwrap();
+}
+
+fn map(
+    entries: &[(&str, &str)],
+) -> duchess::Global<java::util::Map<java::lang::String, java::lang::String>> {
+    let entries: std::collections::HashMap<String, String> = entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    entries
+        .to_java::<java::util::Map<java::lang::String, java::lang::String>>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap()
+}
+
+#[test]
+fn if_not_null_runs_on_values() {
+    let map = map(&[("key", "value")]);
+    let len = map
+        .get("key".to_java::<java::lang::String>())
+        .if_not_null(|value| value.length())
+        .execute()
+        .unwrap();
+    assert_eq!(len, Some(5));
+}
+
+#[test]
+fn if_not_null_short_circuits_on_null() {
+    let map = map(&[]);
+    let len = map
+        .get("key".to_java::<java::lang::String>())
+        .if_not_null(|value| value.length())
+        .execute()
+        .unwrap();
+    assert_eq!(len, None);
+}
+
+#[test]
+fn if_not_null_chains() {
+    let map = map(&[("key", "value"), ("value", "end")]);
+    let lookup = |key: &'static str| {
+        map.get(key.to_java::<java::lang::String>())
+            .if_not_null(|value| map.get(value))
+            .if_not_null(|value| value.length())
+            .execute()
+            .unwrap()
+    };
+    assert_eq!(lookup("key"), Some(3));
+    assert_eq!(lookup("value"), None);
+    assert_eq!(lookup("end"), None);
+}
+
+#[test]
+fn if_not_null_values_nest() {
+    let list = list(&["outer", "inner"]);
+    let lengths = list
+        .get(0)
+        .if_not_null(|outer| {
+            list.get(1)
+                .if_not_null(move |inner| outer.length().zip(inner.length()))
+        })
+        .execute()
+        .unwrap();
+    // The inner `Option` is passed on as is
+    assert_eq!(lengths, Some((5, 5)));
+}
+
+#[test]
+fn if_not_null_values_are_only_valid_inside() {
+    let list = list(&["a"]);
+    let mut escaped = None;
+    list.get(0)
+        .if_not_null(|value| {
+            escaped = Some(value);
+            value.length()
+        })
+        .execute()
+        .unwrap();
+    let error = escaped.unwrap().length().execute().unwrap_err();
+    assert!(
+        error.to_string().contains("outside of its closure"),
+        "{error}"
+    );
+}
@@ -0,0 +1,75 @@
+use duchess::{
+    compile::{compile, CompileError},
+    java,
+    prelude::*,
+    Jvm,
+};
+
+type StringFunction = java::util::function::Function<java::lang::String, java::lang::String>;
+
+#[test]
+fn compile_load_and_call() {
+    let result: String = Jvm::with(|jvm| {
+        let compiled = compile(
+            jvm,
+            &[
+                // Defined before the class it extends, and using a nested class
+                (
+                    "gen.Shout",
+                    "package gen;
+                    public class Shout extends Base {
+                        static class Suffix { static final String VALUE = \"!\"; }
+                        public String apply(String s) { return prefix() + s.toUpperCase() + Suffix.VALUE; }
+                    }",
+                ),
+                (
+                    "gen.Base",
+                    "package gen;
+                    public abstract class Base implements java.util.function.Function<String, String> {
+                        protected String prefix() { return \">\"; }
+                    }",
+                ),
+            ],
+            &[],
+        )?
+        .unwrap();
+
+        let mut names: Vec<&str> = compiled.classes.iter().map(|c| c.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["gen.Base", "gen.Shout", "gen.Shout$Suffix"]);
+
+        let classes = compiled.load(jvm)?;
+        let shout = classes.new_instance::<StringFunction>(jvm, "gen.Shout")?;
+        shout.apply("hello").assert_not_null().to_rust().execute_with(jvm)
+    })
+    .unwrap();
+    assert_eq!(result, ">HELLO!");
+}
+
+#[test]
+fn compile_errors() {
+    let result =
+        Jvm::with(|jvm| compile(jvm, &[("Broken", "public class Broken { int x = ; }")], &[]))
+            .unwrap();
+    match result {
+        Err(CompileError::Failed(diagnostics)) => {
+            assert!(diagnostics.contains("Broken.java"), "{diagnostics}")
+        }
+        other => panic!("expected a compilation failure, got {other:?}"),
+    }
+}
+
+#[test]
+fn missing_class() {
+    let result = Jvm::with(|jvm| {
+        let compiled = compile(jvm, &[("Empty", "public class Empty {}")], &[])?.unwrap();
+        let classes = compiled.load(jvm)?;
+        classes.class(jvm, "Empty")?;
+        classes.class(jvm, "NotCompiled")?;
+        Ok(())
+    });
+    assert_eq!(
+        result.unwrap_err().thrown_class_name().as_deref(),
+        Some("java.lang.ClassNotFoundException")
+    );
+}
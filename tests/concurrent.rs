@@ -0,0 +1,129 @@
+use duchess::{
+    concurrent::{AtomicI32, AtomicI64, AtomicRef, ConcurrentMap},
+    java,
+    prelude::*,
+    Global,
+};
+
+fn java_string(s: &str) -> Global<java::lang::String> {
+    s.to_java::<java::lang::String>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap()
+}
+
+fn to_rust(s: &Option<Global<java::lang::String>>) -> Option<String> {
+    s.as_ref().map(|s| (&**s).to_rust().execute().unwrap())
+}
+
+#[test]
+fn atomic_integers() {
+    let counter = AtomicI32::new(40).unwrap();
+    assert_eq!(counter.fetch_add(2).unwrap(), 40);
+    assert_eq!(counter.fetch_sub(1).unwrap(), 42);
+    assert_eq!(counter.swap(i32::MAX).unwrap(), 41);
+    assert_eq!(counter.fetch_add(1).unwrap(), i32::MAX);
+    assert_eq!(counter.load().unwrap(), i32::MIN);
+
+    counter.store(7).unwrap();
+    assert_eq!(counter.compare_exchange(7, 8).unwrap(), Ok(7));
+    assert_eq!(counter.compare_exchange(7, 9).unwrap(), Err(8));
+    assert_eq!(counter.load().unwrap(), 8);
+}
+
+#[test]
+fn fetch_update_retries_until_done() {
+    let value = AtomicI64::new(3).unwrap();
+    assert_eq!(value.fetch_update(|v| Some(v * 2)).unwrap(), Ok(3));
+    assert_eq!(
+        value.fetch_update(|v| (v < 5).then_some(0)).unwrap(),
+        Err(6)
+    );
+    assert_eq!(value.load().unwrap(), 6);
+}
+
+#[test]
+fn atomics_are_shared_with_java() {
+    let value = AtomicI64::new(1).unwrap();
+    // Java code sees (and changes) the same atomic
+    value.as_java().get_and_add(10_i64).execute().unwrap();
+    assert_eq!(value.load().unwrap(), 11);
+    let description: String = value
+        .as_java()
+        .to_string()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(description, "11");
+}
+
+#[test]
+fn concurrent_increments() {
+    let counter = AtomicI64::new(0).unwrap();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..100 {
+                    counter.fetch_add(1).unwrap();
+                }
+            });
+        }
+    });
+    assert_eq!(counter.load().unwrap(), 400);
+}
+
+#[test]
+fn atomic_references_compare_identity() {
+    let first = java_string("first");
+    let value = AtomicRef::new(Some(&*first)).unwrap();
+    assert_eq!(to_rust(&value.load().unwrap()).as_deref(), Some("first"));
+
+    // An equal, but different, string doesn't match
+    let equal = java_string("first");
+    let second = java_string("second");
+    let previous = value
+        .compare_exchange(Some(&*equal), Some(&*second))
+        .unwrap();
+    assert_eq!(to_rust(&previous.err().unwrap()).as_deref(), Some("first"));
+
+    let previous = value
+        .compare_exchange(Some(&*first), Some(&*second))
+        .unwrap();
+    assert_eq!(to_rust(&previous.ok().unwrap()).as_deref(), Some("first"));
+
+    let previous = value.swap(None).unwrap();
+    assert_eq!(to_rust(&previous).as_deref(), Some("second"));
+    assert!(value.load().unwrap().is_none());
+    assert!(value.compare_exchange(None, Some(&*first)).unwrap().is_ok());
+}
+
+#[test]
+fn concurrent_maps() {
+    let map = ConcurrentMap::<java::lang::String, java::lang::String>::new().unwrap();
+    assert!(map.is_empty().unwrap());
+    assert!(map.insert("a", "one").unwrap().is_none());
+    assert_eq!(
+        to_rust(&map.insert("a", "uno").unwrap()).as_deref(),
+        Some("one")
+    );
+    assert_eq!(
+        to_rust(&map.insert_if_absent("a", "eins").unwrap()).as_deref(),
+        Some("uno")
+    );
+    assert!(map.insert_if_absent("b", "two").unwrap().is_none());
+    assert_eq!(map.len().unwrap(), 2);
+
+    assert_eq!(to_rust(&map.get("a").unwrap()).as_deref(), Some("uno"));
+    assert!(map.get("c").unwrap().is_none());
+    assert!(map.contains_key("b").unwrap());
+
+    // Values are compared with `equals`
+    assert!(!map.replace_if_eq("a", "one", "un").unwrap());
+    assert!(map.replace_if_eq("a", "uno", "un").unwrap());
+    assert_eq!(to_rust(&map.remove("a").unwrap()).as_deref(), Some("un"));
+
+    map.clear().unwrap();
+    assert_eq!(map.len().unwrap(), 0);
+}
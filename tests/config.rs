@@ -0,0 +1,85 @@
+//! Process-wide settings, which are all checked in one test since they are shared.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use duchess::{java, observe, prelude::*, Config, Error};
+
+fn length(s: &str) -> duchess::GlobalResult<i32> {
+    s.to_java::<java::lang::String>()
+        .assert_not_null()
+        .length()
+        .execute()
+}
+
+#[test]
+fn config() -> duchess::GlobalResult<()> {
+    assert_eq!(Config::current(), Config::new());
+
+    // Invalid settings are rejected, and none of the others applied
+    for capacity in [0, -1] {
+        let config = Config::new()
+            .local_frame_capacity(capacity)
+            .capture_backtraces(true);
+        assert!(matches!(config.apply(), Err(Error::InvalidConfig(_))));
+        assert_eq!(Config::current(), Config::new());
+    }
+
+    // Before the JVM is launched, everything can be set
+    let config = Config::new()
+        .local_frame_capacity(64)
+        .string_fast_path(false);
+    config.apply()?;
+    assert_eq!(Config::current(), config);
+    assert_eq!(length("slow path")?, 9);
+    for s in ["", "ascii", "nul\0byte", "é", "😀"] {
+        let back: String = s
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .to_rust()
+            .execute()?;
+        assert_eq!(back, s);
+    }
+
+    // Afterwards, only the runtime toggles can change
+    assert!(matches!(
+        Config::current().local_frame_capacity(16).apply(),
+        Err(Error::JvmAlreadyExists)
+    ));
+    let config = config.string_fast_path(true);
+    assert!(matches!(config.apply(), Err(Error::JvmAlreadyExists)));
+    Config::current().capture_backtraces(true).apply()?;
+
+    let error = java::util::ArrayList::<java::lang::String>::new()
+        .get(0)
+        .global()
+        .execute()
+        .unwrap_err();
+    assert!(matches!(error, Error::ThrownWithBacktrace(..)), "{error:?}");
+    assert!(error.to_string().contains("IndexOutOfBoundsException"));
+
+    Config::set_capture_backtraces(false);
+    let error = java::util::ArrayList::<java::lang::String>::new()
+        .get(0)
+        .global()
+        .execute()
+        .unwrap_err();
+    assert!(matches!(error, Error::Thrown(_)), "{error:?}");
+
+    // The observer isn't called while metrics are off
+    let observed = Arc::new(AtomicUsize::new(0));
+    let count = observed.clone();
+    observe::set_execution_observer(Some(move |_: &observe::Execution| {
+        count.fetch_add(1, Ordering::Relaxed);
+    }));
+    Config::set_metrics(false);
+    length("unobserved")?;
+    assert_eq!(observed.load(Ordering::Relaxed), 0);
+    Config::set_metrics(true);
+    length("observed")?;
+    assert_eq!(observed.load(Ordering::Relaxed), 1);
+    observe::set_execution_observer(None::<fn(&observe::Execution)>);
+    Ok(())
+}
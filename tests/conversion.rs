@@ -0,0 +1,61 @@
+use duchess::{
+    conversion::{self, Converted, Convertible},
+    prelude::*,
+    ConversionError, Error, Jvm,
+};
+
+duchess::java_package! {
+    package java.util.concurrent.atomic;
+
+    public class java.util.concurrent.atomic.AtomicLong {
+        public java.util.concurrent.atomic.AtomicLong(long);
+        public final long get();
+    }
+}
+
+use java::util::concurrent::atomic::AtomicLong;
+
+impl Convertible for AtomicLong {}
+
+/// A Rust type that neither crate defines a conversion into.
+#[derive(Debug, PartialEq)]
+struct Counter(u64);
+
+#[test]
+fn registered_conversions_apply_to_nested_objects() {
+    conversion::register::<AtomicLong, Counter>(|jvm, counter| {
+        let value = counter.get().execute_with(jvm)?;
+        Ok(Counter(value as u64))
+    });
+
+    Jvm::with(|jvm| {
+        let counters = duchess::java::util::ArrayList::<AtomicLong>::new().execute_with(jvm)?;
+        for value in [10_i64, 20] {
+            let counter = AtomicLong::new(value).execute_with(jvm)?;
+            counters.add(&counter).execute_with(jvm)?;
+        }
+
+        let counters: Vec<Converted<Counter>> = (&*counters).to_rust().execute_with(jvm)?;
+        let counters: Vec<Counter> = counters.into_iter().map(Converted::into_inner).collect();
+        assert_eq!(counters, [Counter(10), Counter(20)]);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn unregistered_conversions_fail() {
+    #[derive(Debug)]
+    struct Unregistered;
+
+    Jvm::with(|jvm| {
+        let result: duchess::Result<Converted<Unregistered>> =
+            AtomicLong::new(1_i64).to_rust().execute_with(jvm);
+        assert!(matches!(
+            result,
+            Err(Error::Conversion(ConversionError::Unregistered { .. }))
+        ));
+        Ok(())
+    })
+    .unwrap();
+}
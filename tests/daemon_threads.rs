@@ -0,0 +1,50 @@
+use duchess::{java, prelude::*, Jvm};
+
+fn current_thread_is_daemon() -> bool {
+    java::lang::Thread::current_thread()
+        .assert_not_null()
+        .is_daemon()
+        .execute()
+        .unwrap()
+}
+
+/// Launches the JVM on the test thread, since the thread that launches it is attached as its (non-daemon) main
+/// thread.
+fn launch() {
+    Jvm::with(|_| Ok(())).unwrap();
+}
+
+#[test]
+fn threads_can_be_attached_as_daemons() {
+    launch();
+    std::thread::spawn(|| {
+        Jvm::attach_thread_permanently_as_daemon().unwrap();
+        assert!(current_thread_is_daemon());
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn threads_are_not_daemons_by_default() {
+    launch();
+    std::thread::spawn(|| {
+        assert!(!current_thread_is_daemon());
+        Jvm::attach_thread_permanently().unwrap();
+        assert!(!current_thread_is_daemon());
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn attached_threads_stay_as_they_are() {
+    launch();
+    std::thread::spawn(|| {
+        Jvm::attach_thread_permanently().unwrap();
+        Jvm::attach_thread_permanently_as_daemon().unwrap();
+        assert!(!current_thread_is_daemon());
+    })
+    .join()
+    .unwrap();
+}
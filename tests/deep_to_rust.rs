@@ -0,0 +1,26 @@
+//! Tests that `to_rust_deep` converts the elements of collections in frames of their own, counted with the
+//! `count-crossings` feature.
+#![cfg(feature = "count-crossings")]
+
+use duchess::{crossings, java, prelude::*, Jvm};
+
+#[test]
+fn each_element_gets_a_frame() {
+    let strings: Vec<String> = (0..10).map(|i| format!("element {i}")).collect();
+    Jvm::with(|jvm| {
+        let list = strings
+            .to_java::<java::util::List<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        let (shallow, crossings) = crossings::count(|| (&*list).to_rust().execute_with(jvm));
+        assert_eq!(shallow?, strings);
+        assert_eq!(crossings.local_frames, 0);
+
+        let (deep, crossings) = crossings::count(|| (&*list).to_rust_deep().execute_with(jvm));
+        assert_eq!(deep?, strings);
+        assert_eq!(crossings.local_frames, 1 + strings.len() as u64);
+        Ok(())
+    })
+    .unwrap();
+}
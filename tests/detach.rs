@@ -0,0 +1,85 @@
+use std::sync::{Arc, Mutex};
+
+use duchess::{current_thread_is_attached, detach_thread, java, prelude::*, Jvm};
+
+/// Launches the JVM on the test thread, so that the spawned threads aren't the main thread.
+fn launch() {
+    Jvm::with(|_| Ok(())).unwrap();
+}
+
+fn use_jvm() {
+    let len = "four"
+        .to_java::<java::lang::String>()
+        .assert_not_null()
+        .length()
+        .execute()
+        .unwrap();
+    assert_eq!(len, 4);
+}
+
+#[test]
+fn permanently_attached_threads_can_be_detached() {
+    launch();
+    std::thread::spawn(|| {
+        assert!(!current_thread_is_attached());
+        Jvm::attach_thread_permanently().unwrap();
+        assert!(current_thread_is_attached());
+
+        detach_thread().unwrap();
+        assert!(!current_thread_is_attached());
+
+        // Using the JVM again attaches the thread only for as long as it is used
+        use_jvm();
+        assert!(!current_thread_is_attached());
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn detaching_a_detached_thread_does_nothing() {
+    launch();
+    std::thread::spawn(|| {
+        detach_thread().unwrap();
+        detach_thread().unwrap();
+        assert!(!current_thread_is_attached());
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn threads_using_the_jvm_cannot_be_detached() {
+    launch();
+    std::thread::spawn(|| {
+        Jvm::with(|_| {
+            assert!(current_thread_is_attached());
+            assert!(detach_thread().is_err());
+            Ok(())
+        })
+        .unwrap();
+        use_jvm();
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn native_methods_cannot_detach_their_thread() {
+    launch();
+    let result = Arc::new(Mutex::new(None));
+    Jvm::with(|jvm| {
+        let runnable = java::lang::Runnable::from_fn(jvm, {
+            let result = result.clone();
+            move || {
+                assert!(current_thread_is_attached());
+                *result.lock().unwrap() = Some(detach_thread());
+                use_jvm();
+            }
+        })?;
+        runnable.run().execute_with(jvm)
+    })
+    .unwrap();
+    assert!(result.lock().unwrap().take().unwrap().is_err());
+    use_jvm();
+}
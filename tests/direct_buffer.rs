@@ -0,0 +1,75 @@
+use duchess::{
+    direct_buffer::{direct_slice, direct_slice_mut, DirectBuffer},
+    java,
+    prelude::*,
+    Jvm,
+};
+
+#[test]
+fn rust_memory_is_shared_with_java() {
+    Jvm::with(|jvm| {
+        let mut buffer = DirectBuffer::new(jvm, b"hello".to_vec())?;
+        assert!(buffer.as_java().is_direct().execute_with(jvm)?);
+        assert_eq!(buffer.as_java().capacity().execute_with(jvm)?, 5);
+
+        // Rust writes, Java reads
+        unsafe { buffer.as_mut_slice()[0] = b'j' };
+        let first = buffer.as_java().get(0).execute_with(jvm)?;
+        assert_eq!(first, b'j' as i8);
+
+        // Java writes, Rust reads
+        buffer.as_java().put(4, b'y' as i8).execute_with(jvm)?;
+        assert_eq!(unsafe { buffer.as_slice() }, b"jelly");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn memory_outlives_rust_owner() {
+    Jvm::with(|jvm| {
+        let buffer = DirectBuffer::new(jvm, vec![7; 1024])?;
+        let java_buffer = jvm.global(&**buffer.as_java());
+        drop(buffer);
+
+        // Java still uses the buffer, so the memory must not have been freed
+        let last = java_buffer.get(1023).execute_with(jvm)?;
+        assert_eq!(last, 7);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn borrowed_memory() {
+    let mut bytes = [0u8; 3];
+    Jvm::with(|jvm| unsafe {
+        DirectBuffer::borrow(jvm, &mut bytes, |jvm, buffer| {
+            buffer.put(1, 42_i8).execute_with(jvm)?;
+            Ok(())
+        })
+    })
+    .unwrap();
+    assert_eq!(bytes, [0, 42, 0]);
+}
+
+#[test]
+fn slices_of_java_buffers() {
+    Jvm::with(|jvm| {
+        let mut buffer = java::nio::ByteBuffer::allocate_direct(4)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        buffer.put(2, 9_i8).execute_with(jvm)?;
+        assert_eq!(unsafe { direct_slice(jvm, &buffer)? }, [0, 0, 9, 0]);
+
+        unsafe { direct_slice_mut(jvm, &mut buffer)?[3] = 5 };
+        assert_eq!(buffer.get(3).execute_with(jvm)?, 5);
+
+        let heap = java::nio::ByteBuffer::allocate(4)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert!(unsafe { direct_slice(jvm, &heap) }.is_err());
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,43 @@
+//! Formatting Java references with `toString()`.
+
+use duchess::{java, prelude::*, Global, Jvm, Local};
+
+#[test]
+fn global_display_and_debug() -> duchess::GlobalResult<()> {
+    let list: Global<java::util::ArrayList<java::lang::String>> =
+        java::util::ArrayList::new().global().execute()?;
+    list.add("duchess").execute()?;
+    list.add("java").execute()?;
+
+    assert_eq!(list.display().to_string(), "[duchess, java]");
+    // `to_string()` is still Java's `toString()`
+    let text: String = list.to_string().assert_not_null().to_rust().execute()?;
+    assert_eq!(text, "[duchess, java]");
+    assert_eq!(
+        format!("{list:?}"),
+        r#"Global<java.util.ArrayList>("[duchess, java]")"#
+    );
+    Ok(())
+}
+
+#[test]
+fn global_display_on_detached_thread() -> duchess::GlobalResult<()> {
+    let string: Global<java::lang::String> =
+        "hello".to_java().assert_not_null().global().execute()?;
+    let text = std::thread::spawn(move || format!("{}", string.display()))
+        .join()
+        .unwrap();
+    assert_eq!(text, "hello");
+    Ok(())
+}
+
+#[test]
+fn local_display_and_debug() -> duchess::GlobalResult<()> {
+    Jvm::with(|jvm| {
+        let string: Local<java::lang::String> =
+            "rust".to_java().assert_not_null().execute_with(jvm)?;
+        assert_eq!(format!("<{}>", string.display()), "<rust>");
+        assert_eq!(format!("{string:?}"), r#"Local<java.lang.String>("rust")"#);
+        Ok(())
+    })
+}
@@ -0,0 +1,22 @@
+use duchess::{Error, Jvm};
+
+#[test]
+fn lists_the_jvm_in_use() {
+    Jvm::with(|_jvm| Ok(())).unwrap();
+
+    let jvms = Jvm::existing_jvms().unwrap();
+    assert_eq!(jvms.len(), 1);
+    assert!(jvms[0].is_selected());
+    assert!(!jvms[0].as_raw().is_null());
+
+    // Selecting the JVM in use again is fine
+    jvms[0].select().unwrap();
+}
+
+#[test]
+fn multiple_jvms_error_explains_how_to_select_one() {
+    let error: duchess::GlobalResult<()> = Err(Error::MultipleJvms(2));
+    let message = error.err().unwrap().to_string();
+    assert!(message.contains("found 2 JVMs"), "{message}");
+    assert!(message.contains("ExistingJvm::select"), "{message}");
+}
@@ -0,0 +1,152 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use duchess::{flow, java, prelude::*, Error, Global, Jvm};
+use futures_core::Stream;
+
+#[allow(dead_code)]
+mod jdk {
+    duchess::java_package! {
+        package java.util.concurrent;
+
+        public class java.util.concurrent.SubmissionPublisher<T> {
+            public java.util.concurrent.SubmissionPublisher();
+            public int submit(T);
+            public void close();
+        }
+    }
+}
+
+use jdk::java::util::concurrent::SubmissionPublisher;
+
+/// Collects the items of `stream`, parking the thread until each is ready.
+fn collect<S: Stream + Unpin>(mut stream: S) -> Vec<S::Item> {
+    struct Unpark(Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut items = vec![];
+    loop {
+        match Pin::new(&mut stream).poll_next(&mut cx) {
+            Poll::Ready(Some(item)) => items.push(item),
+            Poll::Ready(None) => return items,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+fn to_strings(items: Vec<Global<java::lang::String>>) -> Vec<String> {
+    Jvm::with(|jvm| {
+        items
+            .iter()
+            .map(|item| (&**item).to_rust().execute_with(jvm))
+            .collect()
+    })
+    .unwrap()
+}
+
+struct IterStream<I>(I);
+
+impl<I: Iterator + Unpin> Stream for IterStream<I> {
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+        Poll::Ready(self.0.next())
+    }
+}
+
+#[test]
+fn consume_java_publisher() {
+    let stream = Jvm::with(|jvm| {
+        let publisher: duchess::Local<SubmissionPublisher<java::lang::String>> =
+            SubmissionPublisher::new().execute_with(jvm)?;
+        let stream = flow::subscribe::<java::lang::String>(jvm, &*publisher)?;
+        // More items than are requested at once, so the stream has to ask for more
+        for i in 0..100 {
+            publisher.submit(&format!("item {i}")).execute_with(jvm)?;
+        }
+        publisher.close().execute_with(jvm)?;
+        Ok(stream)
+    })
+    .unwrap();
+
+    let items: Vec<_> = collect(stream).into_iter().map(Result::unwrap).collect();
+    let expected: Vec<String> = (0..100).map(|i| format!("item {i}")).collect();
+    assert_eq!(to_strings(items), expected);
+}
+
+#[test]
+fn rust_stream_round_trip() {
+    let strings: Vec<Global<java::lang::String>> = Jvm::with(|jvm| {
+        (0..50)
+            .map(|i| {
+                let s = format!("{i}")
+                    .to_java()
+                    .assert_not_null()
+                    .execute_with(jvm)?;
+                Ok(jvm.global(&*s))
+            })
+            .collect()
+    })
+    .unwrap();
+
+    let stream = Jvm::with(|jvm| {
+        let publisher = flow::publisher(jvm, IterStream(strings.into_iter()))?;
+        flow::subscribe::<java::lang::String>(jvm, &*publisher)
+    })
+    .unwrap();
+
+    let items: Vec<_> = collect(stream).into_iter().map(Result::unwrap).collect();
+    let expected: Vec<String> = (0..50).map(|i| format!("{i}")).collect();
+    assert_eq!(to_strings(items), expected);
+}
+
+#[test]
+fn second_subscriber_fails() {
+    let (first, second) = Jvm::with(|jvm| {
+        let publisher = flow::publisher(
+            jvm,
+            IterStream(std::iter::empty::<Global<java::lang::String>>()),
+        )?;
+        let first = flow::subscribe::<java::lang::String>(jvm, &*publisher)?;
+        let second = flow::subscribe::<java::lang::String>(jvm, &*publisher)?;
+        Ok((first, second))
+    })
+    .unwrap();
+
+    assert!(collect(first).is_empty());
+    let second = collect(second);
+    assert_eq!(second.len(), 1);
+    let Err(Error::Thrown(exception)) = &second[0] else {
+        panic!("expected the second subscriber to fail");
+    };
+    let message: String = Jvm::with(|jvm| {
+        exception
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)
+    })
+    .unwrap();
+    assert!(message.contains("IllegalStateException"), "{message}");
+}
+
+#[test]
+fn not_a_publisher() {
+    Jvm::with(|jvm| {
+        let object = java::lang::Object::new().execute_with(jvm)?;
+        let result = flow::subscribe::<java::lang::String>(jvm, &*object);
+        assert!(matches!(result, Err(Error::JvmInternal(_))));
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,48 @@
+//! Frozen Java objects, whose values are cached.
+
+use std::sync::Arc;
+
+use duchess::{java, prelude::*, Frozen};
+
+/// Java's `String.hashCode`.
+fn java_hash(s: &str) -> i32 {
+    s.encode_utf16().fold(0_i32, |hash, unit| {
+        hash.wrapping_mul(31).wrapping_add(i32::from(unit))
+    })
+}
+
+#[test]
+fn values_are_read_from_many_threads() -> duchess::GlobalResult<()> {
+    let string = "duchess".to_java().assert_not_null().global().execute()?;
+    let frozen: Arc<Frozen<java::lang::String, String>> =
+        Arc::new(Frozen::assume_immutable(string));
+
+    std::thread::scope(|s| {
+        for _ in 0..8 {
+            let frozen = Arc::clone(&frozen);
+            s.spawn(move || {
+                assert_eq!(frozen.value().unwrap(), "duchess");
+                assert_eq!(frozen.hash_code().unwrap(), java_hash("duchess"));
+                assert_eq!(frozen.string().unwrap(), "duchess");
+            });
+        }
+    });
+    assert_eq!(frozen.to_string(), "duchess");
+    Ok(())
+}
+
+#[test]
+fn values_are_computed_once() -> duchess::GlobalResult<()> {
+    let list = java::util::ArrayList::<java::lang::String>::new()
+        .global()
+        .execute()?;
+    list.add("a").execute()?;
+    let frozen: Frozen<_> = Frozen::assume_immutable(list);
+    assert_eq!(frozen.string()?, "[a]");
+
+    // Breaks the promise, to show that the string isn't computed again
+    frozen.add("b").execute()?;
+    assert_eq!(frozen.string()?, "[a]");
+    assert_eq!(frozen.size().execute()?, 2);
+    Ok(())
+}
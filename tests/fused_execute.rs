@@ -0,0 +1,128 @@
+//! Tests that `execute` fuses chained operations into one crossing, counted with the `count-crossings` feature.
+#![cfg(feature = "count-crossings")]
+
+use duchess::{crossings, java, prelude::*, Jvm};
+
+/// Converts `"chain"` to Java and calls `toString` on it `N` times, in one `execute`.
+fn to_string_chain<const N: usize>() -> crossings::Crossings {
+    macro_rules! chain {
+        ($op:expr;) => { $op };
+        ($op:expr; $_:tt $($rest:tt)*) => { chain!($op.to_string().assert_not_null(); $($rest)*) };
+    }
+    let op = "chain".to_java::<java::lang::String>().assert_not_null();
+    let (len, crossings) = crossings::count(|| match N {
+        4 => chain!(op; 1 2 3 4).length().execute(),
+        12 => chain!(op; 1 2 3 4 5 6 7 8 9 10 11 12).length().execute(),
+        _ => unreachable!(),
+    });
+    assert_eq!(len.unwrap(), 5);
+    crossings
+}
+
+#[test]
+fn chained_ops_share_one_frame() {
+    // The first run looks up the method IDs
+    to_string_chain::<4>();
+    to_string_chain::<12>();
+
+    let short = to_string_chain::<4>();
+    let long = to_string_chain::<12>();
+    assert_eq!(short.local_frames, 1);
+    assert_eq!(long.local_frames, 1);
+    // Each `toString` is a call and an `ExceptionCheck`, without a `DeleteLocalRef` for the string it returns: the
+    // intermediate strings are released along with the frame
+    assert_eq!(long.jni_calls - short.jni_calls, 2 * 8);
+}
+
+#[test]
+fn execute_attaches_once() {
+    Jvm::with(|_| Ok(())).unwrap();
+    std::thread::spawn(|| {
+        let (_, detached) = crossings::count(|| to_string_chain::<4>());
+        assert_eq!(detached.attaches, 1);
+
+        Jvm::attach_thread_permanently().unwrap();
+        let (_, attached) = crossings::count(|| to_string_chain::<4>());
+        assert_eq!(attached.attaches, 0);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn nested_execute_pushes_its_own_frame() {
+    let (len, crossings) = crossings::count(|| {
+        Jvm::with(|jvm| {
+            let s = "nested".to_java::<java::lang::String>().execute_with(jvm)?;
+            assert!(s.is_some());
+            Ok("inner"
+                .to_java::<java::lang::String>()
+                .assert_not_null()
+                .length()
+                .execute()
+                .unwrap())
+        })
+    });
+    assert_eq!(len.unwrap(), 5);
+    assert_eq!(crossings.local_frames, 1);
+}
+
+#[test]
+fn many_locals_in_one_execute() {
+    // Far more locals than the frame reserves room for, which are deleted in batches past its capacity
+    let strings: Vec<String> = (0..1_000).map(|i| i.to_string()).collect();
+    let round_trip: Vec<String> = strings
+        .to_java::<java::util::List<java::lang::String>>()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(round_trip, strings);
+}
+
+#[test]
+fn errors_escape_the_frame() {
+    let error = Vec::<String>::new()
+        .to_java::<java::util::List<java::lang::String>>()
+        .assert_not_null()
+        .iterator()
+        .assert_not_null()
+        .next()
+        .assert_not_null()
+        .length()
+        .execute()
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("NoSuchElementException"),
+        "{error}"
+    );
+}
+
+#[test]
+fn short_chains_skip_the_frame() {
+    let string = "short"
+        .to_java::<java::lang::String>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap();
+    let scalar = || crossings::count(|| string.code_point_at(0).execute());
+    let one_local =
+        || crossings::count(|| string.to_char_array().assert_not_null().length().execute());
+    // The first runs look up the method IDs
+    scalar();
+    one_local();
+
+    let (code_point, crossings) = scalar();
+    assert_eq!(code_point.unwrap(), 's' as i32);
+    assert_eq!(crossings.local_frames, 0);
+    // The call and its exception check, without pushing and popping a frame that would hold no locals
+    assert_eq!(crossings.jni_calls, 2);
+
+    let (len, crossings) = one_local();
+    assert_eq!(len.unwrap(), 5);
+    assert_eq!(crossings.local_frames, 0);
+    // `toCharArray` and its exception check, `GetArrayLength` and the `DeleteLocalRef` of the array: one call less
+    // than pushing and popping a frame to release it
+    assert_eq!(crossings.jni_calls, 4);
+}
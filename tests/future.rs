@@ -0,0 +1,154 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+    time::Duration,
+};
+
+use duchess::{future, java, prelude::*, Error, Global, Jvm};
+
+/// Polls `future` to completion, parking the thread until it is woken.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+fn java_string(s: &str) -> Global<java::lang::String> {
+    s.to_java::<java::lang::String>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap()
+}
+
+fn to_string(s: &Global<java::lang::String>) -> String {
+    (&**s).to_rust().execute().unwrap()
+}
+
+/// Pending until a thread that it spawns wakes it.
+struct WokenLater(bool);
+
+impl Future for WokenLater {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        let waker = cx.waker().clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            waker.wake();
+        });
+        Poll::Pending
+    }
+}
+
+#[test]
+fn await_java_future() {
+    let completable = java::util::concurrent::CompletableFuture::<java::lang::String>::new()
+        .global()
+        .execute()
+        .unwrap();
+    let future = Jvm::with(|jvm| future::when_complete(jvm, &completable)).unwrap();
+
+    let value = java_string("done");
+    let completer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        completable.complete(&value).execute().unwrap()
+    });
+
+    let result = block_on(future).unwrap().unwrap();
+    assert_eq!(to_string(&result), "done");
+    assert!(completer.join().unwrap());
+}
+
+#[test]
+fn await_failed_java_future() {
+    let completable = java::util::concurrent::CompletableFuture::<java::lang::String>::new()
+        .global()
+        .execute()
+        .unwrap();
+    let exception = java::lang::IllegalStateException::new("broken")
+        .global()
+        .execute()
+        .unwrap();
+    completable
+        .complete_exceptionally(&exception)
+        .execute()
+        .unwrap();
+
+    let future = Jvm::with(|jvm| future::when_complete(jvm, &completable)).unwrap();
+    let Err(error) = block_on(future) else {
+        panic!("expected the future to fail");
+    };
+    assert!(error.is_instance::<java::lang::IllegalStateException>());
+}
+
+#[test]
+fn java_joins_rust_future() {
+    let value = java_string("from rust");
+    let completable = Jvm::with(|jvm| {
+        let completable = future::completable(jvm, async move {
+            WokenLater(false).await;
+            Ok(Some(value))
+        })?;
+        Ok(jvm.global(&*completable))
+    })
+    .unwrap();
+
+    let result: String = completable
+        .join()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(result, "from rust");
+}
+
+#[test]
+fn rust_errors_fail_the_java_future() {
+    let completable = Jvm::with(|jvm| {
+        let completable = future::completable::<_, java::lang::String>(jvm, async {
+            Err(Error::JvmInternal("broken".into()))
+        })?;
+        Ok(jvm.global(&*completable))
+    })
+    .unwrap();
+
+    let Err(error) = completable.join().global().execute() else {
+        panic!("expected `join` to throw");
+    };
+    assert!(error.is_instance::<java::util::concurrent::CompletionException>());
+    assert!(completable.is_completed_exceptionally().execute().unwrap());
+}
+
+#[test]
+fn round_trip() {
+    let value = java_string("there and back");
+    let future = Jvm::with(|jvm| {
+        let completable = future::completable(jvm, async move { Ok(Some(value)) })?;
+        future::when_complete(jvm, &completable)
+    })
+    .unwrap();
+
+    let result = block_on(future).unwrap().unwrap();
+    assert_eq!(to_string(&result), "there and back");
+}
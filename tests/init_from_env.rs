@@ -0,0 +1,18 @@
+use duchess::{Error, Jvm};
+
+#[test]
+fn init_from_env_reuses_existing_jvm() {
+    Jvm::with(|jvm| {
+        // The env belongs to the JVM duchess is already using, so this is a no-op.
+        unsafe { Jvm::init_from_env(jvm.env().as_ptr()) }.unwrap();
+        Ok(())
+    })
+    .unwrap();
+
+    assert!(matches!(
+        unsafe { Jvm::init_from_raw(std::ptr::null_mut()) },
+        Err(Error::JvmInternal(_))
+    ));
+
+    Jvm::with(|_jvm| Ok(())).unwrap();
+}
@@ -0,0 +1,159 @@
+use std::{
+    io::{Cursor, Read, Write},
+    sync::{Arc, Mutex},
+};
+
+use duchess::{
+    io::{JavaInputStream, JavaOutputStream},
+    java,
+    prelude::*,
+    Jvm,
+};
+
+fn data(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn read_from_java_stream() {
+    let bytes = data(20_000);
+    let mut stream = Jvm::with(|jvm| {
+        let array = bytes
+            .to_java::<java::Array<i8>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let stream = java::io::ByteArrayInputStream::new(&array)
+            .global()
+            .execute_with(jvm)?;
+        JavaInputStream::with_capacity(jvm, stream.upcast(), 1000)
+    })
+    .unwrap();
+
+    let mut read = Vec::new();
+    stream.read_to_end(&mut read).unwrap();
+    assert_eq!(read, bytes);
+}
+
+#[test]
+fn write_to_java_stream() {
+    let bytes = data(20_000);
+    let (array_stream, mut stream) = Jvm::with(|jvm| {
+        let array_stream = java::io::ByteArrayOutputStream::new()
+            .global()
+            .execute_with(jvm)?;
+        let output = jvm.global(&*array_stream);
+        let stream = JavaOutputStream::new(jvm, output.upcast())?;
+        Ok((array_stream, stream))
+    })
+    .unwrap();
+
+    stream.write_all(&bytes).unwrap();
+    stream.flush().unwrap();
+
+    let written: Vec<u8> = array_stream
+        .to_byte_array()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn java_reads_rust_reader() {
+    let bytes = data(20_000);
+    let read: Vec<u8> = Jvm::with(|jvm| {
+        let stream = java::io::InputStream::from_read(jvm, Cursor::new(bytes.clone()))?;
+        stream
+            .read_all_bytes()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)
+    })
+    .unwrap();
+    assert_eq!(read, bytes);
+}
+
+#[derive(Clone, Default)]
+struct SharedVec(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedVec {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn java_writes_rust_writer() {
+    let bytes = data(20_000);
+    let written = SharedVec::default();
+    Jvm::with(|jvm| {
+        let array = bytes
+            .to_java::<java::Array<i8>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let input = java::io::ByteArrayInputStream::new(&array).execute_with(jvm)?;
+        let output = java::io::OutputStream::from_write(jvm, written.clone())?;
+        let copied = input.transfer_to(&output).execute_with(jvm)?;
+        assert_eq!(copied, bytes.len() as i64);
+        output.close().execute_with(jvm)?;
+
+        // Writing to a closed stream throws
+        let array = vec![1_u8]
+            .to_java::<java::Array<i8>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert!(output.write(&array, 0, 1).execute_with(jvm).is_err());
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(*written.0.lock().unwrap(), bytes);
+}
+
+struct Failing;
+
+impl Read for Failing {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "disk on fire",
+        ))
+    }
+}
+
+#[test]
+fn rust_errors_are_thrown_as_io_exceptions() {
+    let stream = Jvm::with(|jvm| {
+        let stream = java::io::InputStream::from_read(jvm, Failing)?;
+        Ok(jvm.global(&*stream))
+    })
+    .unwrap();
+
+    let result: duchess::GlobalResult<Vec<u8>> = stream
+        .read_all_bytes()
+        .assert_not_null()
+        .to_rust()
+        .execute();
+    let error = result.unwrap_err();
+    assert!(error.is_instance::<java::io::IOException>());
+    assert!(error.to_string().contains("disk on fire"), "{error}");
+}
+
+#[test]
+fn rust_reader_through_java_stream() {
+    let bytes = data(20_000);
+    let mut stream = Jvm::with(|jvm| {
+        let stream = java::io::InputStream::from_read(jvm, Cursor::new(bytes.clone()))?;
+        let stream = jvm.global(&*stream);
+        JavaInputStream::new(jvm, stream)
+    })
+    .unwrap();
+
+    let mut read = Vec::new();
+    stream.read_to_end(&mut read).unwrap();
+    assert_eq!(read, bytes);
+}
@@ -0,0 +1,50 @@
+use duchess::{java, prelude::*, Error, Jvm, Local};
+
+#[test]
+fn clone_cloneable() {
+    Jvm::with(|jvm| {
+        let list: Local<java::util::ArrayList<java::lang::String>> =
+            java::util::ArrayList::new().execute_with(jvm)?;
+        list.add("a").execute_with(jvm)?;
+
+        let copy = list.java_clone().execute_with(jvm)?;
+        copy.add("b").execute_with(jvm)?;
+        assert_eq!(list.size().execute_with(jvm)?, 1);
+        assert_eq!(copy.size().execute_with(jvm)?, 2);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn clone_not_cloneable() {
+    Jvm::with(|jvm| {
+        let object = java::lang::Object::new().execute_with(jvm)?;
+        let Err(Error::Thrown(exception)) = object.java_clone().execute_with(jvm) else {
+            panic!("expected `clone()` to throw");
+        };
+        let message: String = exception
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(message.contains("CloneNotSupportedException"), "{message}");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn clone_with_copy_constructor() {
+    Jvm::with(|jvm| {
+        // `String` isn't `Cloneable` (so `clone()` would throw), but has a `String(String)` constructor.
+        jvm.register_copy_constructor::<java::lang::String>()?;
+
+        let s: Local<java::lang::String> = "hello".to_java().assert_not_null().execute_with(jvm)?;
+        let copy = s.java_clone().execute_with(jvm)?;
+        let copy: String = (&*copy).to_rust().execute_with(jvm)?;
+        assert_eq!(copy, "hello");
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,40 @@
+//! Comparing Java objects with `equals`/`hashCode` and by identity.
+
+use std::collections::HashMap;
+
+use duchess::{java, prelude::*, Global, JavaEq, Jvm, Local};
+
+fn string(s: &str) -> duchess::GlobalResult<Global<java::lang::String>> {
+    s.to_java().assert_not_null().global().execute()
+}
+
+#[test]
+fn java_eq_as_map_key() -> duchess::GlobalResult<()> {
+    let mut counts = HashMap::new();
+    for word in ["rust", "java", "rust"] {
+        *counts.entry(JavaEq::new(string(word)?)).or_insert(0) += 1;
+    }
+    assert_eq!(counts.len(), 2);
+    assert_eq!(counts[&JavaEq::new(string("rust")?)], 2);
+    assert_eq!(counts[&JavaEq::new(string("java")?)], 1);
+    Ok(())
+}
+
+#[test]
+fn ptr_eq() -> duchess::GlobalResult<()> {
+    let a = string("duchess")?;
+    let b = string("duchess")?;
+    assert!(!Global::ptr_eq(&a, &*b));
+    assert!(JavaEq::new(a) == JavaEq::new(b));
+
+    Jvm::with(|jvm| {
+        let list = java::util::ArrayList::<java::lang::String>::new().execute_with(jvm)?;
+        let same: Global<java::util::ArrayList<java::lang::String>> = jvm.global(&*list);
+        let other: Local<java::util::ArrayList<java::lang::String>> =
+            java::util::ArrayList::new().execute_with(jvm)?;
+        assert!(Global::ptr_eq(&same, &*list));
+        assert!(Local::ptr_eq(&list, &*same));
+        assert!(!Local::ptr_eq(&list, &*other));
+        Ok(())
+    })
+}
@@ -0,0 +1,52 @@
+use duchess::{java, prelude::*, Global, JavaIterator, Jvm};
+
+fn strings(values: &[&str]) -> Global<java::util::ArrayList<java::lang::String>> {
+    Jvm::with(|jvm| {
+        let values: Vec<String> = values.iter().map(|s| s.to_string()).collect();
+        let list = values
+            .to_java::<java::util::ArrayList<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        Ok(jvm.global(&*list))
+    })
+    .unwrap()
+}
+
+fn to_string(s: &java::lang::String) -> String {
+    Jvm::with(|jvm| s.to_rust().execute_with(jvm)).unwrap()
+}
+
+#[test]
+fn for_loop_over_iterable() {
+    let list = strings(&["a", "b", "c"]);
+    let iter = Jvm::with(|jvm| JavaIterator::from_iterable(jvm, &list)).unwrap();
+
+    let mut seen = vec![];
+    for element in iter {
+        seen.push(to_string(&element.unwrap()));
+    }
+    assert_eq!(seen, ["a", "b", "c"]);
+}
+
+#[test]
+fn global_iterator_into_iter() {
+    let list = strings(&["x", "y"]);
+    let iter = Jvm::with(|jvm| {
+        let iter = list.iterator().assert_not_null().execute_with(jvm)?;
+        Ok(jvm.global(&*iter))
+    })
+    .unwrap();
+
+    let elements: Vec<String> = iter
+        .into_iter()
+        .map(|element| to_string(&element.unwrap()))
+        .collect();
+    assert_eq!(elements, ["x", "y"]);
+}
+
+#[test]
+fn empty() {
+    let list = strings(&[]);
+    let mut iter = Jvm::with(|jvm| JavaIterator::from_iterable(jvm, &list)).unwrap();
+    assert!(iter.next().is_none());
+}
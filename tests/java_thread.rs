@@ -0,0 +1,90 @@
+use duchess::{
+    current_java_thread, current_thread_is_interrupted, java, prelude::*, JavaThreadOptions, Jvm,
+};
+
+/// Launches the JVM on the test thread, so that the spawned threads aren't the main thread.
+fn launch() {
+    Jvm::with(|_| Ok(())).unwrap();
+}
+
+#[test]
+fn options_configure_the_java_thread() {
+    launch();
+    std::thread::spawn(|| {
+        let thread = JavaThreadOptions::new()
+            .name("rust-worker")
+            .priority(java::lang::Thread::get_max_priority().execute().unwrap())
+            .daemon(true)
+            .attach_permanently()
+            .unwrap();
+        assert!(thread.is_daemon().execute().unwrap());
+        assert_eq!(thread.get_priority().execute().unwrap(), 10);
+
+        let current = current_java_thread().unwrap();
+        let name: String = current
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        assert_eq!(name, "rust-worker");
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn attached_threads_can_be_renamed() {
+    launch();
+    std::thread::spawn(|| {
+        Jvm::attach_thread_permanently().unwrap();
+        let thread = JavaThreadOptions::new()
+            .name("renamed")
+            .daemon(true)
+            .attach_permanently()
+            .unwrap();
+        // Too late to become a daemon
+        assert!(!thread.is_daemon().execute().unwrap());
+        let name: String = thread
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        assert_eq!(name, "renamed");
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn out_of_range_priorities_are_rejected() {
+    launch();
+    std::thread::spawn(|| {
+        let Err(error) = JavaThreadOptions::new().priority(42).attach_permanently() else {
+            panic!("priority 42 was accepted");
+        };
+        assert!(
+            error.to_string().contains("IllegalArgumentException"),
+            "{error}"
+        );
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn interrupts_are_visible() {
+    launch();
+    std::thread::spawn(|| {
+        let thread = JavaThreadOptions::new().attach_permanently().unwrap();
+        assert!(!current_thread_is_interrupted().unwrap());
+        thread.interrupt().execute().unwrap();
+        assert!(current_thread_is_interrupted().unwrap());
+        // Checking doesn't clear the status, but `Thread.interrupted` does
+        assert!(java::lang::Thread::interrupted().execute().unwrap());
+        assert!(!current_thread_is_interrupted().unwrap());
+    })
+    .join()
+    .unwrap();
+}
@@ -0,0 +1,258 @@
+use std::time::{Duration, SystemTime};
+
+use duchess::{java, prelude::*, Error, Jvm};
+
+#[test]
+fn instant_keeps_nanos() {
+    Jvm::with(|jvm| {
+        let time = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        let instant = time
+            .to_java::<java::time::Instant>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(instant.get_epoch_second().execute_with(jvm)?, 1_700_000_000);
+        assert_eq!(instant.get_nano().execute_with(jvm)?, 123_456_789);
+
+        let back: SystemTime = (&*instant).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn instant_before_epoch() {
+    Jvm::with(|jvm| {
+        let time = SystemTime::UNIX_EPOCH - Duration::new(10, 1);
+        let instant = time
+            .to_java::<java::time::Instant>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(instant.get_epoch_second().execute_with(jvm)?, -11);
+        assert_eq!(instant.get_nano().execute_with(jvm)?, 999_999_999);
+
+        let back: SystemTime = (&*instant).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn instant_out_of_range() {
+    Jvm::with(|jvm| {
+        // Beyond `Instant.MAX`, which `SystemTime` can represent on Unix
+        let Some(time) = SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(1 << 60)) else {
+            return Ok(());
+        };
+        let result = time
+            .try_to_java::<java::time::Instant>()
+            .execute_with(jvm)?;
+        assert!(result.is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn duration_round_trip() {
+    Jvm::with(|jvm| {
+        let duration = Duration::new(90, 5);
+        let java_duration = duration
+            .to_java::<java::time::Duration>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(java_duration.to_nanos().execute_with(jvm)?, 90_000_000_005);
+
+        let back: Duration = (&*java_duration).to_rust().execute_with(jvm)?;
+        assert_eq!(back, duration);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn negative_duration_fails() {
+    Jvm::with(|jvm| {
+        let java_duration = java::time::Duration::of_millis(-1_500_i64)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let result: Result<Duration, _> = (&*java_duration).try_to_rust().execute_with(jvm)?;
+        assert!(result.is_err());
+
+        let error = (&*java_duration)
+            .to_rust::<Duration>()
+            .execute_with(jvm)
+            .unwrap_err();
+        assert!(matches!(error, Error::Conversion(_)));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn duration_out_of_range() {
+    Jvm::with(|jvm| {
+        let result = Duration::MAX
+            .try_to_java::<java::time::Duration>()
+            .execute_with(jvm)?;
+        assert!(result.is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn local_date_time_is_utc() {
+    Jvm::with(|jvm| {
+        // 2023-11-14T22:13:20.000000042Z
+        let time = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 42);
+        let local = time
+            .to_java::<java::time::LocalDateTime>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(local.get_year().execute_with(jvm)?, 2023);
+        assert_eq!(local.get_month_value().execute_with(jvm)?, 11);
+        assert_eq!(local.get_day_of_month().execute_with(jvm)?, 14);
+        assert_eq!(local.get_hour().execute_with(jvm)?, 22);
+        assert_eq!(local.get_minute().execute_with(jvm)?, 13);
+        assert_eq!(local.get_second().execute_with(jvm)?, 20);
+        assert_eq!(local.get_nano().execute_with(jvm)?, 42);
+
+        let back: SystemTime = (&*local).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+
+        let before_epoch = java::time::LocalDateTime::of(1969, 12, 31, 23, 59, 59, 500)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let back: SystemTime = (&*before_epoch).to_rust().execute_with(jvm)?;
+        assert_eq!(back, SystemTime::UNIX_EPOCH - Duration::new(0, 999_999_500));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn local_date_time_out_of_range() {
+    Jvm::with(|jvm| {
+        // Beyond `LocalDateTime.MAX`, which `SystemTime` can represent on Unix
+        let Some(time) = SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(1 << 60)) else {
+            return Ok(());
+        };
+        let result = time
+            .try_to_java::<java::time::LocalDateTime>()
+            .execute_with(jvm)?;
+        assert!(result.is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono_round_trips() {
+    use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+
+    Jvm::with(|jvm| {
+        let time = DateTime::from_timestamp(-1_700_000_000, 123_456_789).unwrap();
+        let instant = time
+            .to_java::<java::time::Instant>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(
+            instant.get_epoch_second().execute_with(jvm)?,
+            -1_700_000_000
+        );
+        let back: DateTime<Utc> = (&*instant).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+
+        let naive = time.naive_utc();
+        let local = naive
+            .to_java::<java::time::LocalDateTime>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(local.get_year().execute_with(jvm)?, 1916);
+        let back: NaiveDateTime = (&*local).to_rust().execute_with(jvm)?;
+        assert_eq!(back, naive);
+
+        for delta in [
+            TimeDelta::new(90, 5).unwrap(),
+            TimeDelta::milliseconds(-1_500),
+        ] {
+            let duration = delta
+                .to_java::<java::time::Duration>()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            assert_eq!(
+                duration.to_nanos().execute_with(jvm)?,
+                delta.num_nanoseconds().unwrap()
+            );
+            let back: TimeDelta = (&*duration).to_rust().execute_with(jvm)?;
+            assert_eq!(back, delta);
+        }
+
+        // Beyond the years that chrono supports
+        let result = java::time::Instant::get_max()
+            .assert_not_null()
+            .try_to_rust::<DateTime<Utc>>()
+            .execute_with(jvm)?;
+        assert!(result.is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn time_round_trips() {
+    use time::{OffsetDateTime, PrimitiveDateTime};
+
+    Jvm::with(|jvm| {
+        let time = OffsetDateTime::from_unix_timestamp_nanos(-1_700_000_000_123_456_789).unwrap();
+        let instant = time
+            .to_java::<java::time::Instant>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(
+            instant.get_epoch_second().execute_with(jvm)?,
+            -1_700_000_001
+        );
+        assert_eq!(instant.get_nano().execute_with(jvm)?, 876_543_211);
+        let back: OffsetDateTime = (&*instant).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+
+        let primitive = PrimitiveDateTime::new(time.date(), time.time());
+        let local = primitive
+            .to_java::<java::time::LocalDateTime>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(local.get_year().execute_with(jvm)?, 1916);
+        let back: PrimitiveDateTime = (&*local).to_rust().execute_with(jvm)?;
+        assert_eq!(back, primitive);
+
+        for duration in [
+            time::Duration::new(90, 5),
+            time::Duration::milliseconds(-1_500),
+        ] {
+            let java_duration = duration
+                .to_java::<java::time::Duration>()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            assert_eq!(
+                i128::from(java_duration.to_nanos().execute_with(jvm)?),
+                duration.whole_nanoseconds()
+            );
+            let back: time::Duration = (&*java_duration).to_rust().execute_with(jvm)?;
+            assert_eq!(back, duration);
+        }
+
+        // Beyond the years that the time crate supports
+        let result = java::time::Instant::get_max()
+            .assert_not_null()
+            .try_to_rust::<OffsetDateTime>()
+            .execute_with(jvm)?;
+        assert!(result.is_err());
+        Ok(())
+    })
+    .unwrap();
+}
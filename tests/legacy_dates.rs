@@ -0,0 +1,77 @@
+use std::time::{Duration, SystemTime};
+
+use duchess::{java, prelude::*, Jvm};
+
+#[test]
+fn date_round_trip() {
+    Jvm::with(|jvm| {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let date = time
+            .to_java::<java::util::Date>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(date.get_time().execute_with(jvm)?, 1_700_000_000_123);
+
+        let back: SystemTime = (&*date).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn date_before_epoch_truncates_to_the_past() {
+    Jvm::with(|jvm| {
+        let time = SystemTime::UNIX_EPOCH - Duration::from_micros(1500);
+        let date = time
+            .to_java::<java::util::Date>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(date.get_time().execute_with(jvm)?, -2);
+
+        let back: SystemTime = (&*date).to_rust().execute_with(jvm)?;
+        assert_eq!(back, SystemTime::UNIX_EPOCH - Duration::from_millis(2));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn calendar_round_trip() {
+    Jvm::with(|jvm| {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_millis(86_400_000);
+        let calendar = time
+            .to_java::<java::util::Calendar>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let date = calendar.get_time().assert_not_null().execute_with(jvm)?;
+        assert_eq!(date.get_time().execute_with(jvm)?, 86_400_000);
+
+        let back: SystemTime = (&*calendar).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn timestamp_keeps_nanos() {
+    Jvm::with(|jvm| {
+        let time = SystemTime::UNIX_EPOCH - Duration::new(10, 1);
+        let timestamp = time
+            .to_java::<java::sql::Timestamp>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(timestamp.get_nanos().execute_with(jvm)?, 999_999_999);
+
+        let back: SystemTime = (&*timestamp).to_rust().execute_with(jvm)?;
+        assert_eq!(back, time);
+
+        let timestamp = java::sql::Timestamp::new(1_000_i64).execute_with(jvm)?;
+        timestamp.set_nanos(42).execute_with(jvm)?;
+        let from_java: SystemTime = (&*timestamp).to_rust().execute_with(jvm)?;
+        assert_eq!(from_java, SystemTime::UNIX_EPOCH + Duration::new(1, 42));
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,53 @@
+use duchess::{java, prelude::*, Jvm, Local};
+
+#[test]
+fn vec_round_trip() {
+    Jvm::with(|jvm| {
+        let rust = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let list: Local<java::util::List<java::lang::String>> = rust
+            .to_java::<java::util::List<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(list.size().execute_with(jvm)?, 3);
+
+        let back: Vec<String> = (&*list).to_rust().execute_with(jvm)?;
+        assert_eq!(back, rust);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn slice_to_array_list() {
+    Jvm::with(|jvm| {
+        let rust: &[String] = &["x".to_string(), "y".to_string()];
+        let list = rust
+            .to_java::<java::util::ArrayList<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let back: Vec<String> = (&*list).to_rust().execute_with(jvm)?;
+        assert_eq!(back, rust);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn list_method_to_vec() {
+    Jvm::with(|jvm| {
+        let rust = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let list = rust
+            .to_java::<java::util::ArrayList<java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        // A method returning a `List<String>` converts directly into a `Vec<String>`
+        let tail: Vec<String> = list
+            .sub_list(1, 3)
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(tail, ["b", "c"]);
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,61 @@
+use duchess::{java, prelude::*, Error, Jvm, Local};
+
+#[test]
+fn frames_release_borrowed_locals() {
+    Jvm::with(|jvm| {
+        let mut total = 0;
+        for i in 0..10_000 {
+            // Borrowed refs normally live until the end of `Jvm::with`, but here they are
+            // released with each frame.
+            total += jvm.with_frame(4, |jvm| {
+                let s: &java::lang::String = i
+                    .to_string()
+                    .to_java()
+                    .assert_not_null()
+                    .execute_borrowed(jvm)?;
+                s.length().execute_with(jvm)
+            })?;
+        }
+        assert_eq!(total, 38_890);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn exceptions_escape_frames() {
+    Jvm::with(|jvm| {
+        let result = jvm.with_frame(4, |jvm| {
+            let list: Local<java::util::ArrayList<java::lang::String>> =
+                java::util::ArrayList::new().execute_with(jvm)?;
+            list.get(3).execute_with(jvm)?;
+            Ok(())
+        });
+        let Err(Error::Thrown(exception)) = result else {
+            panic!("expected an exception, got {result:?}");
+        };
+        let message: String = exception
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(message.contains("IndexOutOfBoundsException"), "{message}");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn ops_in_frames_keep_their_result() {
+    Jvm::with(|jvm| {
+        let s: Local<java::lang::String> = "hello"
+            .to_java()
+            .assert_not_null()
+            .in_frame()
+            .execute_with(jvm)?;
+        let len = s.length().in_frame().execute_with(jvm)?;
+        assert_eq!(len, 5);
+        Ok(())
+    })
+    .unwrap();
+}
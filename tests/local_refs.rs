@@ -0,0 +1,72 @@
+use duchess::{java, prelude::*, Jvm, Local};
+
+#[test]
+fn many_short_lived_locals() {
+    Jvm::with(|jvm| {
+        let keep: Local<java::lang::String> =
+            "keep".to_java().assert_not_null().execute_with(jvm)?;
+        for i in 0..10_000 {
+            let s: Local<java::lang::String> = i
+                .to_string()
+                .to_java()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            assert_eq!(s.length().execute_with(jvm)?, i.to_string().len() as i32);
+        }
+        // Locals created before the loop are unaffected by the deletes of the ones in it.
+        let keep: String = (&*keep).to_rust().execute_with(jvm)?;
+        assert_eq!(keep, "keep");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn borrowed_execution() {
+    Jvm::with(|jvm| {
+        let mut borrowed: Vec<&java::lang::String> = vec![];
+        for i in 0..100 {
+            borrowed.push(
+                i.to_string()
+                    .to_java()
+                    .assert_not_null()
+                    .execute_borrowed(jvm)?,
+            );
+        }
+        // Borrowed refs stay valid for the whole scope, even as other locals come and go.
+        for (i, s) in borrowed.into_iter().enumerate() {
+            let s: String = s.to_rust().execute_with(jvm)?;
+            assert_eq!(s, i.to_string());
+        }
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn borrowed_out_of_nested_scopes() {
+    Jvm::with(|jvm| {
+        // Borrowed with the outer `jvm`, in a nested `Jvm::with` and in a frame pushed in it
+        let (nested, in_frame) = Jvm::with(|inner| {
+            let nested = "nested".to_java().assert_not_null().execute_borrowed(jvm);
+            let in_frame = inner.with_frame(16, |_| {
+                Ok("in frame".to_java().assert_not_null().execute_borrowed(jvm))
+            })?;
+            Ok((nested, in_frame))
+        })
+        .unwrap();
+        let (nested, in_frame): (&java::lang::String, &java::lang::String) = (nested?, in_frame?);
+
+        // New locals would reuse the refs, had they been deleted when the nested scope ended
+        let others: Vec<Local<java::lang::String>> = (0..100)
+            .map(|i| i.to_string().to_java().assert_not_null().execute_with(jvm))
+            .collect::<Result<_, _>>()?;
+        let nested: String = nested.to_rust().execute_with(jvm)?;
+        assert_eq!(nested, "nested");
+        let in_frame: String = in_frame.to_rust().execute_with(jvm)?;
+        assert_eq!(in_frame, "in frame");
+        drop(others);
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,60 @@
+use std::collections::{BTreeMap, HashMap};
+
+use duchess::{java, prelude::*, Jvm, Local};
+
+#[test]
+fn hash_map_round_trip() {
+    Jvm::with(|jvm| {
+        let rust: HashMap<String, String> = [("a", "1"), ("b", "2")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let map: Local<java::util::Map<java::lang::String, java::lang::String>> = rust
+            .to_java::<java::util::Map<java::lang::String, java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(map.size().execute_with(jvm)?, 2);
+
+        let back: HashMap<String, String> = (&*map).to_rust().execute_with(jvm)?;
+        assert_eq!(back, rust);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn btree_map_round_trip() {
+    Jvm::with(|jvm| {
+        let rust: BTreeMap<String, String> = [("x", "10"), ("y", "20"), ("z", "30")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let map = rust
+            .to_java::<java::util::HashMap<java::lang::String, java::lang::String>>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        let back: BTreeMap<String, String> = (&*map).to_rust().execute_with(jvm)?;
+        assert_eq!(back, rust);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn null_values_are_rejected() {
+    Jvm::with(|jvm| {
+        let map: Local<java::util::HashMap<java::lang::String, java::lang::String>> =
+            java::util::HashMap::new().execute_with(jvm)?;
+        let key = "a".execute_with(jvm)?;
+        let key: &java::lang::Object = key.as_jref()?;
+        let _: Option<Local<java::lang::Object>> =
+            duchess::call!(jvm, map.put(key, None::<&java::lang::Object>))?;
+        assert_eq!(map.size().execute_with(jvm)?, 1);
+
+        let back: Result<HashMap<String, String>, _> = (&*map).to_rust().execute_with(jvm);
+        assert!(matches!(back, Err(duchess::Error::NullDeref)));
+        Ok(())
+    })
+    .unwrap();
+}
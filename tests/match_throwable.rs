@@ -0,0 +1,64 @@
+//! Dispatching caught exceptions on their class with `match_throwable!`.
+
+use duchess::{java, prelude::*, Error, Global, Jvm, Local};
+
+/// Describes the exception thrown by `Character.toString(code_point)`, if any.
+fn describe(code_point: i32) -> duchess::GlobalResult<&'static str> {
+    Jvm::with(|jvm| {
+        let exception = match java::lang::Character::to_string(code_point).execute_with(jvm) {
+            Ok(_) => return Ok("none"),
+            Err(Error::Thrown(exception)) => exception,
+            Err(e) => return Err(e),
+        };
+        duchess::match_throwable!(jvm, &exception, {
+            java::lang::IllegalStateException => |_| "illegal state",
+            java::lang::IllegalArgumentException => |_| "illegal argument",
+            java::lang::RuntimeException => |_| "runtime",
+            _ => |_| "other",
+        })
+    })
+}
+
+#[test]
+fn first_matching_arm_wins() -> duchess::GlobalResult<()> {
+    assert_eq!(describe(0x61)?, "none");
+    // Not a code point
+    assert_eq!(describe(-1)?, "illegal argument");
+    Ok(())
+}
+
+#[test]
+fn fallback_gets_the_exception() -> duchess::GlobalResult<()> {
+    let list = java::util::ArrayList::<java::lang::String>::new()
+        .global()
+        .execute()?;
+    let Err(Error::Thrown(exception)) = list.get(3).global().execute() else {
+        panic!("expected `get` to throw");
+    };
+    // `IndexOutOfBoundsException` isn't an `IllegalArgumentException`
+    let rethrown = Jvm::with(|jvm| {
+        duchess::match_throwable!(jvm, &exception, {
+            java::lang::IllegalArgumentException => |_| false,
+            _ => |e: &Global<java::lang::Throwable>| Global::ptr_eq(e, &*exception),
+        })
+    })?;
+    assert!(rethrown);
+    Ok(())
+}
+
+#[test]
+fn arms_get_the_downcast_exception() -> duchess::GlobalResult<()> {
+    let message: String = Jvm::with(|jvm| {
+        let exception = java::lang::IllegalStateException::new("broken")
+            .upcast::<java::lang::Throwable>()
+            .execute_with(jvm)?;
+        duchess::match_throwable!(jvm, &exception, {
+            java::lang::IllegalStateException => |e: Local<'_, java::lang::IllegalStateException>| {
+                e.get_message().assert_not_null().to_rust().execute_with(jvm)
+            },
+            _ => |_| panic!("expected an `IllegalStateException`"),
+        })?
+    })?;
+    assert_eq!(message, "broken");
+    Ok(())
+}
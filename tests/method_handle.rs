@@ -0,0 +1,166 @@
+use duchess::{
+    java,
+    method_handle::{CallPath, DynamicMethod},
+    prelude::*,
+    JavaObject, Jvm,
+};
+
+#[test]
+fn paths_agree_on_static_method_with_primitives() {
+    Jvm::with(|jvm| {
+        let class = java::lang::Integer::class(jvm)?;
+        let method = DynamicMethod::new(jvm, &class, "compare", "(II)I", true)?;
+        assert_eq!(method.arity(), 2);
+        assert_eq!(method.path(), CallPath::Jni);
+
+        let a = java::lang::Integer::value_of(3)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let b = java::lang::Integer::value_of(7)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        for path in [CallPath::Jni, CallPath::MethodHandle] {
+            let result = method
+                .invoke_with(jvm, path, None, &[Some(a.as_ref()), Some(b.as_ref())])?
+                .unwrap();
+            let result = result
+                .try_downcast::<java::lang::Integer>()
+                .execute_with(jvm)?
+                .unwrap();
+            assert_eq!(result.int_value().execute_with(jvm)?, -1, "{path:?}");
+        }
+
+        // Arguments of the wrong class are rejected rather than passed to JNI
+        let s = "3"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert!(method
+            .invoke_with(
+                jvm,
+                CallPath::Jni,
+                None,
+                &[Some(s.as_ref()), Some(b.as_ref())]
+            )
+            .is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn paths_agree_on_instance_method() {
+    Jvm::with(|jvm| {
+        let class = java::lang::String::class(jvm)?;
+        let method = DynamicMethod::new(
+            jvm,
+            &class,
+            "concat",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            false,
+        )?;
+
+        let hello = "hello, "
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let world = "world"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        for path in [CallPath::Jni, CallPath::MethodHandle] {
+            let result = method
+                .invoke_with(jvm, path, Some(hello.as_ref()), &[Some(world.as_ref())])?
+                .unwrap();
+            let result = result
+                .try_downcast::<java::lang::String>()
+                .execute_with(jvm)?
+                .unwrap();
+            let result: &java::lang::String = &result;
+            let result: String = result.to_rust().execute_with(jvm)?;
+            assert_eq!(result, "hello, world", "{path:?}");
+        }
+
+        // Instance methods need a receiver
+        assert!(method.invoke(jvm, None, &[Some(world.as_ref())]).is_err());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn calibrate_selects_a_path() {
+    Jvm::with(|jvm| {
+        let class = java::lang::Integer::class(jvm)?;
+        let method = DynamicMethod::new(jvm, &class, "sum", "(II)I", true)?;
+        let a = java::lang::Integer::value_of(1)
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let b = java::lang::Integer::value_of(2)
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        let (path, _, _) =
+            method.calibrate(jvm, None, &[Some(a.as_ref()), Some(b.as_ref())], 100)?;
+        assert_eq!(method.path(), path);
+
+        method.set_path(CallPath::MethodHandle);
+        assert_eq!(method.path(), CallPath::MethodHandle);
+        let result = method
+            .invoke(jvm, None, &[Some(a.as_ref()), Some(b.as_ref())])?
+            .unwrap();
+        let result = result
+            .try_downcast::<java::lang::Integer>()
+            .execute_with(jvm)?
+            .unwrap();
+        assert_eq!(result.int_value().execute_with(jvm)?, 3);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn many_arguments() {
+    Jvm::with(|jvm| {
+        let compiled = duchess::compile::compile(
+            jvm,
+            &[(
+                "gen.Many",
+                "package gen;
+                public class Many {
+                    public static long sum(int a, int b, int c, int d, int e, int f, int g, int h, int i, int j, int k) {
+                        return a + b + c + d + e + f + g + h + i + j + k;
+                    }
+                }",
+            )],
+            &[],
+        )?
+        .unwrap();
+        let classes = compiled.load(jvm)?;
+        let class = classes.class(jvm, "gen.Many")?;
+        // More arguments than are kept on the stack
+        let method = DynamicMethod::new(jvm, &class, "sum", "(IIIIIIIIIII)J", true)?;
+
+        let mut args = vec![];
+        for i in 1..=11 {
+            args.push(
+                java::lang::Integer::value_of(i)
+                    .assert_not_null()
+                    .execute_with(jvm)?,
+            );
+        }
+        let args: Vec<Option<&java::lang::Object>> = args.iter().map(|a| Some(a.as_ref())).collect();
+        for path in [CallPath::Jni, CallPath::MethodHandle] {
+            for _ in 0..3 {
+                let result = method.invoke_with(jvm, path, None, &args)?.unwrap();
+                let result = result
+                    .try_downcast::<java::lang::Long>()
+                    .execute_with(jvm)?
+                    .unwrap();
+                assert_eq!(result.long_value().execute_with(jvm)?, 66, "{path:?}");
+            }
+        }
+        Ok(())
+    })
+    .unwrap();
+}
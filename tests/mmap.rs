@@ -0,0 +1,95 @@
+use duchess::{
+    mmap::{MapMode, MapRegion, MappedFile},
+    prelude::*,
+    Jvm,
+};
+
+fn temp_file(name: &str, contents: &[u8]) -> String {
+    let path = std::env::temp_dir().join(format!("duchess-mmap-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn region_arithmetic() {
+    let region = MapRegion::new(100, 50);
+    assert_eq!(region.end(), 150);
+    assert!(region.contains(100) && region.contains(149) && !region.contains(150));
+    assert_eq!(
+        region.intersection(MapRegion::new(140, 100)),
+        Some(MapRegion::new(140, 10))
+    );
+    assert_eq!(region.intersection(MapRegion::new(150, 10)), None);
+    assert_eq!(region.align_to(64), MapRegion::new(64, 128));
+    assert_eq!(
+        region.chunks(20).collect::<Vec<_>>(),
+        [
+            MapRegion::new(100, 20),
+            MapRegion::new(120, 20),
+            MapRegion::new(140, 10)
+        ]
+    );
+}
+
+#[test]
+fn changes_are_shared() {
+    let path = temp_file("shared", b"hello, world");
+
+    let mapped = Jvm::with(|jvm| {
+        let mut mapped =
+            unsafe { MappedFile::map(jvm, &path, MapRegion::new(7, 5), MapMode::ReadWrite)? };
+        assert_eq!(mapped.as_slice(), b"world");
+
+        // Rust writes, Java reads
+        mapped.as_mut_slice()[0] = b'W';
+        let first = mapped.as_java().get(0).execute_with(jvm)?;
+        assert_eq!(first, b'W' as i8);
+
+        // Java writes, Rust reads
+        mapped.as_java().put(4, b'D' as i8).execute_with(jvm)?;
+        assert_eq!(mapped.as_slice(), b"WorlD");
+
+        mapped.force(jvm)?;
+        Ok(mapped)
+    })
+    .unwrap();
+    drop(mapped);
+
+    assert_eq!(std::fs::read(&path).unwrap(), b"hello, WorlD");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn private_mappings_are_not_written_back() {
+    let path = temp_file("private", b"abc");
+    Jvm::with(|jvm| {
+        let mut mapped =
+            unsafe { MappedFile::map(jvm, &path, MapRegion::new(0, 3), MapMode::Private)? };
+        mapped.as_mut_slice().copy_from_slice(b"xyz");
+        mapped.force(jvm)?;
+        assert_eq!(mapped.as_slice(), b"xyz");
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"abc");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn missing_file() {
+    let result = Jvm::with(|jvm| {
+        unsafe {
+            MappedFile::map(
+                jvm,
+                "/nonexistent/duchess-mmap",
+                MapRegion::new(0, 1),
+                MapMode::ReadOnly,
+            )?
+        };
+        Ok(())
+    });
+    assert_eq!(
+        result.unwrap_err().thrown_class_name().as_deref(),
+        Some("java.io.FileNotFoundException")
+    );
+}
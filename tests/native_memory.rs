@@ -0,0 +1,48 @@
+use duchess::{direct_buffer::DirectBuffer, java, memory, Jvm};
+
+const MIB: usize = 1024 * 1024;
+
+fn launch() {
+    Jvm::builder()
+        .max_direct_memory_size(16 * MIB as u64)
+        .report_native_memory()
+        .launch_or_use_existing()
+        .unwrap();
+}
+
+#[test]
+fn pinned_memory_is_counted() {
+    launch();
+    Jvm::with(|jvm| {
+        let buffer = DirectBuffer::new(jvm, vec![0; MIB])?;
+        let runnable = java::lang::Runnable::from_fn(jvm, || {})?;
+        let stats = memory::stats();
+        assert!(stats.direct_buffers >= 1);
+        assert!(stats.direct_buffer_bytes >= MIB);
+        assert!(stats.proxies >= 1);
+        assert!(stats.proxy_bytes > 0);
+        assert!(stats.total_bytes() > MIB);
+        drop((buffer, runnable));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn buffers_beyond_the_limit_fail() {
+    launch();
+    let error = Jvm::with(|jvm| DirectBuffer::new(jvm, vec![0; 32 * MIB]).map(drop)).unwrap_err();
+    assert!(
+        error.to_string().contains("OutOfMemoryError"),
+        "unexpected error: {error}"
+    );
+}
+
+#[test]
+fn unreachable_buffers_make_room_for_new_ones() {
+    launch();
+    // Four times the limit, which only fits if the JVM collects the buffers that are no longer used
+    for _ in 0..64 {
+        Jvm::with(|jvm| DirectBuffer::new(jvm, vec![0; MIB]).map(drop)).unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use duchess::{java, prelude::*, Jvm};
+
+fn current_thread_id() -> i64 {
+    java::lang::Thread::current_thread()
+        .assert_not_null()
+        .get_id()
+        .execute()
+        .unwrap()
+}
+
+#[test]
+fn nested_with_reuses_the_attachment() {
+    let outer: String = Jvm::with(|jvm| {
+        let hello = "hello"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let inner: String =
+            Jvm::with(|jvm| duchess::text::to_upper_case("world").execute_with(jvm)).unwrap();
+        // `execute` nests too, and locals of the outer scope stay usable in it
+        let hello: String = (&*hello).to_rust().execute().unwrap();
+        Ok(format!("{hello} {inner}"))
+    })
+    .unwrap();
+    assert_eq!(outer, "hello WORLD");
+}
+
+#[test]
+fn thread_is_detached_once_the_outermost_scope_ends() {
+    std::thread::spawn(|| {
+        let (outer, inner) = Jvm::with(|_| {
+            let outer = current_thread_id();
+            Jvm::with(|_| Ok(())).unwrap();
+            // Still attached as the same Java thread after the inner scope ended
+            Ok((outer, current_thread_id()))
+        })
+        .unwrap();
+        assert_eq!(outer, inner);
+        // Attaching again makes a new Java thread
+        assert_ne!(current_thread_id(), outer);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn attaching_permanently_inside_a_scope_outlives_it() {
+    std::thread::spawn(|| {
+        let outer = Jvm::with(|_| {
+            Jvm::attach_thread_permanently().unwrap();
+            Ok(current_thread_id())
+        })
+        .unwrap();
+        assert_eq!(current_thread_id(), outer);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+fn nested_with_in_java_callbacks() {
+    Jvm::with(|jvm| {
+        let count = Arc::new(AtomicUsize::new(0));
+        let runnable = java::lang::Runnable::from_fn(jvm, {
+            let count = count.clone();
+            move || {
+                let len = "four"
+                    .to_java::<java::lang::String>()
+                    .assert_not_null()
+                    .length()
+                    .execute()
+                    .unwrap();
+                count.fetch_add(len as usize, Ordering::SeqCst);
+            }
+        })?;
+        runnable.run().execute_with(jvm)?;
+        Jvm::with(|jvm| runnable.run().execute_with(jvm)).unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 8);
+        Ok(())
+    })
+    .unwrap();
+}
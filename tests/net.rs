@@ -0,0 +1,81 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use duchess::{java, prelude::*, Jvm};
+
+#[test]
+fn ip_addresses_round_trip() {
+    Jvm::with(|jvm| {
+        for ip in [
+            IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)),
+            IpAddr::V6(Ipv6Addr::LOCALHOST),
+        ] {
+            let address = ip
+                .to_java::<java::net::InetAddress>()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            let back: IpAddr = (&*address).to_rust().execute_with(jvm)?;
+            assert_eq!(back, ip);
+        }
+
+        let loopback = java::net::InetAddress::get_loopback_address()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let loopback: IpAddr = (&*loopback).to_rust().execute_with(jvm)?;
+        assert!(loopback.is_loopback());
+
+        let text: String = Ipv4Addr::new(10, 0, 0, 7)
+            .to_java::<java::net::InetAddress>()
+            .assert_not_null()
+            .get_host_address()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(text, "10.0.0.7");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn urls_round_trip() {
+    use duchess::ConversionError;
+    use url::Url;
+
+    Jvm::with(|jvm| {
+        let url = Url::parse("https://example.com/a%20b?q=1#top").unwrap();
+        let uri = url
+            .to_java::<java::net::URI>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let host: String = uri
+            .get_host()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(host, "example.com");
+        let back: Url = (&*uri).to_rust().execute_with(jvm)?;
+        assert_eq!(back, url);
+
+        let java_url = url
+            .to_java::<java::net::URL>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let back: Url = (&*java_url).to_rust().execute_with(jvm)?;
+        assert_eq!(back, url);
+
+        let relative = java::net::URI::create("a/b")
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let relative: Result<Url, _> = (&*relative).try_into_rust(jvm)?;
+        assert_eq!(
+            relative,
+            Err(ConversionError::Malformed {
+                value: "a/b".into(),
+                target: "url::Url"
+            })
+        );
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,90 @@
+use duchess::{java, prelude::*, Error, JavaObject, Jvm, Local};
+
+#[test]
+fn new_get_set() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<java::lang::String>> =
+            java::Array::new_with_len(3).execute_with(jvm)?;
+        assert_eq!(array.length().execute_with(jvm)?, 3);
+        assert!(array.get(0).execute_with(jvm)?.is_none());
+
+        array.set(1, "hello").execute_with(jvm)?;
+        let element: Option<String> = array.get(1).to_rust().execute_with(jvm)?;
+        assert_eq!(element.as_deref(), Some("hello"));
+
+        // Element ops chain into the element's methods
+        assert_eq!(array.get(1).length().execute_with(jvm)?, 5);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn from_elements_to_vec() {
+    Jvm::with(|jvm| {
+        let array = java::Array::<java::lang::String>::from_elements(jvm, ["a", "b", "c"])?;
+        let elements: Vec<String> = (&*array).to_rust().execute_with(jvm)?;
+        assert_eq!(elements, ["a", "b", "c"]);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn null_element_to_vec() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<java::lang::String>> =
+            java::Array::new_with_len(1).execute_with(jvm)?;
+        let result: duchess::Result<Vec<String>> = (&*array).to_rust().execute_with(jvm);
+        assert!(matches!(result, Err(Error::NullDeref)));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn index_out_of_bounds() {
+    Jvm::with(|jvm| {
+        let array: Local<java::Array<java::lang::String>> =
+            java::Array::new_with_len(1).execute_with(jvm)?;
+        let Err(Error::Thrown(exception)) = array.get(1).execute_with(jvm) else {
+            panic!("expected an out-of-bounds `get` to throw");
+        };
+        let message: String = exception
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(
+            message.contains("ArrayIndexOutOfBoundsException"),
+            "{message}"
+        );
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn array_classes() {
+    Jvm::with(|jvm| {
+        for _ in 0..2 {
+            let class = java::Array::<java::lang::String>::class(jvm)?;
+            let name: String = class
+                .get_name()
+                .assert_not_null()
+                .to_rust()
+                .execute_with(jvm)?;
+            assert_eq!(name, "[Ljava.lang.String;");
+        }
+
+        let class = java::Array::<java::Array<java::lang::String>>::class(jvm)?;
+        let name: String = class
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(name, "[[Ljava.lang.String;");
+        Ok(())
+    })
+    .unwrap();
+}
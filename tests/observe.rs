@@ -0,0 +1,48 @@
+//! Observing the operations run with `execute`.
+
+use std::sync::{Arc, Mutex};
+
+use duchess::{
+    java,
+    observe::{set_execution_observer, Execution},
+    prelude::*,
+};
+
+#[test]
+fn observer_sees_executed_ops() -> duchess::GlobalResult<()> {
+    let executions: Arc<Mutex<Vec<Execution>>> = Default::default();
+    let recorded = executions.clone();
+    let observer_thread = std::thread::current().id();
+    set_execution_observer(Some(move |execution: &Execution| {
+        if std::thread::current().id() == observer_thread {
+            // Not observed itself
+            "nested"
+                .to_java::<java::lang::String>()
+                .assert_not_null()
+                .length()
+                .execute()
+                .unwrap();
+            recorded.lock().unwrap().push(*execution);
+        }
+    }));
+
+    let op = "hello"
+        .to_java::<java::lang::String>()
+        .assert_not_null()
+        .length();
+    assert_eq!(op.execute()?, 5);
+    let failing = java::util::ArrayList::<java::lang::String>::new()
+        .get(0)
+        .global();
+    assert!(failing.execute().is_err());
+    set_execution_observer(None::<fn(&Execution)>);
+    op.execute()?;
+
+    let executions = executions.lock().unwrap();
+    assert_eq!(executions.len(), 2);
+    assert_eq!(executions[0].op, std::any::type_name_of_val(&op));
+    assert!(executions[0].succeeded);
+    assert_eq!(executions[1].op, std::any::type_name_of_val(&failing));
+    assert!(!executions[1].succeeded);
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+
+use duchess::{java, prelude::*, Jvm};
+
+#[test]
+fn path_to_file_and_back() {
+    Jvm::with(|jvm| {
+        let path = std::env::temp_dir().join("duchess").join("data.txt");
+        let file = path
+            .to_java::<java::io::File>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let name: String = file
+            .get_name()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert_eq!(name, "data.txt");
+        assert!(file.is_absolute().execute_with(jvm)?);
+
+        let back: PathBuf = (&*file).to_rust().execute_with(jvm)?;
+        assert_eq!(back, path);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn path_to_nio_path_and_back() {
+    Jvm::with(|jvm| {
+        let path = Path::new("some").join("relative").join("dir");
+        let nio_path = path
+            .to_java::<java::nio::file::Path>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert_eq!(nio_path.get_name_count().execute_with(jvm)?, 3);
+        assert!(!nio_path.is_absolute().execute_with(jvm)?);
+
+        let back: PathBuf = (&*nio_path).to_rust().execute_with(jvm)?;
+        assert_eq!(back, path);
+
+        let file = nio_path.to_file().assert_not_null().execute_with(jvm)?;
+        let from_file: PathBuf = (&*file).to_rust().execute_with(jvm)?;
+        assert_eq!(from_file, path);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn java_file_operations_see_rust_paths() {
+    Jvm::with(|jvm| {
+        let dir = std::env::temp_dir().join(format!("duchess-paths-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hello.txt"), "hello").unwrap();
+
+        let file = dir
+            .join("hello.txt")
+            .to_java::<java::io::File>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        assert!(file.is_file().execute_with(jvm)?);
+        assert_eq!(file.length().execute_with(jvm)?, 5);
+        assert!(file.delete().execute_with(jvm)?);
+        std::fs::remove_dir(&dir).unwrap();
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_paths_follow_the_jvm_encoding() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    Jvm::with(|jvm| {
+        let encoding = jvm.get_property("sun.jnu.encoding")?;
+        let path = Path::new(OsStr::from_bytes(b"/tmp/\xFF"));
+        let result = path.try_to_java::<java::io::File>().execute_with(jvm)?;
+        if encoding.as_deref() == Some("ISO-8859-1") {
+            // Every byte is a character
+            let file = result.unwrap().unwrap();
+            let back: PathBuf = (&*file).to_rust().execute_with(jvm)?;
+            assert_eq!(back, path);
+        } else {
+            // E.g. UTF-8, where the JVM can't name the file either
+            assert!(result.is_err(), "{encoding:?}");
+        }
+        Ok(())
+    })
+    .unwrap();
+}
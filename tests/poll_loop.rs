@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use duchess::{prelude::*, Error, Global, Jvm, PollLoop};
+
+duchess::java_package! {
+    package java.util.concurrent;
+
+    public class java.util.concurrent.LinkedBlockingQueue<E> {
+        public java.util.concurrent.LinkedBlockingQueue();
+        public boolean offer(E);
+        public E poll();
+    }
+}
+
+use java::util::concurrent::LinkedBlockingQueue;
+
+type Queue = LinkedBlockingQueue<duchess::java::lang::String>;
+
+fn new_queue() -> Global<Queue> {
+    Jvm::with(|jvm| {
+        let queue = LinkedBlockingQueue::new().execute_with(jvm)?;
+        Ok(jvm.global(&*queue))
+    })
+    .unwrap()
+}
+
+fn offer(queue: &Queue, value: &str) {
+    Jvm::with(|jvm| queue.offer(value).execute_with(jvm)).unwrap();
+}
+
+#[test]
+fn delivers_records_until_shutdown() {
+    let queue = new_queue();
+    let poll_loop = {
+        let queue = Jvm::with(|jvm| Ok(jvm.global(&*queue))).unwrap();
+        PollLoop::spawn(16, move |jvm| {
+            let record: Option<String> = queue.poll().to_rust().execute_with(jvm)?;
+            if record.is_none() {
+                // Stands in for a poll with a timeout
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Ok(record.into_iter().collect())
+        })
+        .unwrap()
+    };
+
+    for value in ["a", "b", "c"] {
+        offer(&queue, value);
+    }
+    let records: Vec<String> = (0..3)
+        .map(|_| {
+            poll_loop
+                .recv_timeout(Duration::from_secs(10))
+                .unwrap()
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(records, ["a", "b", "c"]);
+
+    poll_loop.shutdown().unwrap();
+}
+
+#[test]
+fn stops_after_exception() {
+    let poll_loop = PollLoop::spawn(16, move |jvm| {
+        // Throws `IndexOutOfBoundsException`
+        let list: duchess::Local<duchess::java::util::ArrayList<duchess::java::lang::String>> =
+            duchess::java::util::ArrayList::new().execute_with(jvm)?;
+        let record: String = list.get(0).assert_not_null().to_rust().execute_with(jvm)?;
+        Ok(vec![record])
+    })
+    .unwrap();
+
+    let Some(Err(Error::Thrown(_))) = poll_loop.recv() else {
+        panic!("expected the exception to be delivered");
+    };
+    assert!(poll_loop.recv().is_none());
+    poll_loop.shutdown().unwrap();
+}
+
+#[test]
+fn polls_on_a_daemon_thread() {
+    let poll_loop = PollLoop::spawn(1, move |jvm| {
+        let daemon = duchess::java::lang::Thread::current_thread()
+            .assert_not_null()
+            .is_daemon()
+            .execute_with(jvm)?;
+        std::thread::sleep(Duration::from_millis(10));
+        Ok(vec![daemon])
+    })
+    .unwrap();
+
+    assert!(poll_loop.recv().unwrap().unwrap());
+    poll_loop.shutdown().unwrap();
+}
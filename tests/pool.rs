@@ -0,0 +1,106 @@
+#![cfg(feature = "pool")]
+
+use std::{
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use duchess::{
+    java,
+    pool::{execute_on_pool, JvmPool},
+    prelude::*,
+    text, Error,
+};
+
+/// Polls `future` to completion, parking the thread until it is woken.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct Unpark(Thread);
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[test]
+fn ops_run_on_the_shared_pool() {
+    let upper = block_on(execute_on_pool(text::to_upper_case("pool"))).unwrap();
+    assert_eq!(upper, "POOL");
+
+    let string = block_on(execute_on_pool(
+        "global".to_java::<java::lang::String>().global(),
+    ))
+    .unwrap()
+    .unwrap();
+    // Global references are `Send`, so the result can be used on any thread
+    let back: String = std::thread::spawn(move || (&*string).to_rust().execute().unwrap())
+        .join()
+        .unwrap();
+    assert_eq!(back, "global");
+}
+
+#[test]
+fn ops_are_spread_over_the_pool() {
+    let pool = JvmPool::new(2).unwrap();
+    let words = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight",
+    ];
+    let futures: Vec<_> = words
+        .iter()
+        .map(|word| pool.execute_on_pool(text::to_upper_case(word)))
+        .collect();
+    for (word, future) in words.iter().zip(futures) {
+        assert_eq!(block_on(future).unwrap(), word.to_uppercase());
+    }
+}
+
+#[test]
+fn pool_threads_are_daemons() {
+    let pool = JvmPool::new(1).unwrap();
+    let op = java::lang::Thread::current_thread().assert_not_null();
+    assert!(block_on(pool.execute_on_pool(op.is_daemon())).unwrap());
+}
+
+#[test]
+fn exceptions_are_returned() {
+    let pool = JvmPool::new(1).unwrap();
+    let result = block_on(
+        pool.execute_on_pool(
+            java::util::ArrayList::<java::lang::String>::new()
+                .get(0)
+                .global(),
+        ),
+    );
+    let Err(Error::Thrown(exception)) = result else {
+        panic!("expected an IndexOutOfBoundsException");
+    };
+    let class: String = exception
+        .get_class()
+        .assert_not_null()
+        .get_name()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(class, "java.lang.IndexOutOfBoundsException");
+}
+
+#[test]
+fn dropping_the_pool_runs_pending_ops() {
+    let pool = JvmPool::new(1).unwrap();
+    let future = pool.execute_on_pool(text::to_lower_case("LATER"));
+    drop(pool);
+    assert_eq!(block_on(future).unwrap(), "later");
+}
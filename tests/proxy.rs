@@ -0,0 +1,65 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use duchess::{java, prelude::*, Jvm, Local};
+
+#[test]
+fn closures_as_functional_interfaces() {
+    Jvm::with(|jvm| {
+        let count = Arc::new(AtomicUsize::new(0));
+        let runnable = java::lang::Runnable::from_fn(jvm, {
+            let count = count.clone();
+            move || {
+                count.fetch_add(1, Ordering::SeqCst);
+            }
+        })?;
+        runnable.run().execute_with(jvm)?;
+        runnable.run().execute_with(jvm)?;
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        let by_length = java::util::Comparator::<java::lang::String>::from_fn(jvm, |jvm, a, b| {
+            Ok(a.length().execute_with(jvm)? - b.length().execute_with(jvm)?)
+        })?;
+        assert!(by_length.compare("ab", "abc").execute_with(jvm)? < 0);
+        assert!(by_length.compare("abc", "ab").execute_with(jvm)? > 0);
+
+        let len =
+            java::util::function::Function::<java::lang::String, java::lang::Integer>::from_fn(
+                jvm,
+                |jvm, s| match s {
+                    Some(s) => {
+                        let len = s.length().execute_with(jvm)?;
+                        java::lang::Integer::value_of(len).execute_with(jvm)
+                    }
+                    None => Ok(None),
+                },
+            )?;
+        let result: Option<Local<java::lang::Integer>> = len
+            .apply("hello")
+            .try_downcast::<java::lang::Integer>()
+            .execute_with(jvm)?
+            .ok();
+        assert_eq!(result.unwrap().int_value().execute_with(jvm)?, 5);
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn panics_become_runtime_exceptions() {
+    // Silence the expected panic, but keep the hook of the other tests
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = Jvm::with(|jvm| {
+        let runnable = java::lang::Runnable::from_fn(jvm, || panic!("boom"))?;
+        runnable.run().execute_with(jvm)
+    });
+    std::panic::set_hook(hook);
+    let err = result.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("java.lang.RuntimeException: Rust panic: boom"));
+}
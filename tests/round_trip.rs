@@ -0,0 +1,242 @@
+//! Randomized round trips of Rust values through Java, enabled by the `round-trip-tests` feature.
+//!
+//! The values are generated (and failing cases shrunk) by proptest, so `PROPTEST_CASES` sets the number of cases
+//! (default 256), and failing cases are saved to `proptest-regressions/` to be replayed first by later runs.
+#![cfg(feature = "round-trip-tests")]
+
+use std::collections::HashMap;
+
+use duchess::{java, prelude::*, Jvm};
+use proptest::{
+    collection::vec, prelude::*, sample::select, strategy::ValueTree, test_runner::TestRunner,
+};
+
+/// Mostly short lengths, sometimes long ones.
+fn len() -> impl Strategy<Value = usize> {
+    prop_oneof![
+        1 => Just(0),
+        7 => 0..16usize,
+        1 => 0..1024usize,
+        1 => 0..64 * 1024usize,
+    ]
+}
+
+/// Characters of every UTF-8 length, with an emphasis on those that Java encodes unusually: nul (which Modified
+/// UTF-8 encodes in two bytes) and characters outside the BMP (which take surrogate pairs).
+fn java_char() -> impl Strategy<Value = char> {
+    prop_oneof![
+        Just('\0'),
+        proptest::char::range('\u{1}', '\u{7f}'),
+        proptest::char::range('\u{80}', '\u{7ff}'),
+        proptest::char::range('\u{800}', '\u{d7ff}'),
+        proptest::char::range('\u{e000}', '\u{ffff}'),
+        proptest::char::range('\u{10000}', '\u{10ffff}'),
+    ]
+}
+
+fn string() -> impl Strategy<Value = String> {
+    len().prop_flat_map(|len| vec(java_char(), len).prop_map(String::from_iter))
+}
+
+/// A string for the elements of collections, which are kept short so that collections can be long.
+fn short_string() -> impl Strategy<Value = String> {
+    vec(java_char(), 0..32).prop_map(String::from_iter)
+}
+
+/// Collections of up to 256 elements.
+fn elements<S: Strategy + 'static>(element: S) -> impl Strategy<Value = Vec<S::Value>> {
+    let element = element.boxed();
+    len().prop_flat_map(move |len| vec(element.clone(), len.min(256)))
+}
+
+/// Picks boundary values half of the time.
+fn i32_value() -> impl Strategy<Value = i32> {
+    prop_oneof![
+        select(vec![
+            0,
+            1,
+            -1,
+            i32::MIN,
+            i32::MAX,
+            i32::MIN + 1,
+            i32::MAX - 1
+        ]),
+        any::<i32>(),
+    ]
+}
+
+fn i64_value() -> impl Strategy<Value = i64> {
+    prop_oneof![
+        select(vec![
+            0,
+            1,
+            -1,
+            i64::MIN,
+            i64::MAX,
+            i64::MIN + 1,
+            i64::MAX - 1
+        ]),
+        any::<i64>(),
+    ]
+}
+
+fn u8_value() -> impl Strategy<Value = u8> {
+    prop_oneof![select(vec![0, 1, 0x7f, 0x80, 0xff]), any::<u8>()]
+}
+
+/// Any bit pattern, including NaNs with payloads.
+fn f64_value() -> impl Strategy<Value = f64> {
+    prop_oneof![
+        select(vec![
+            0.0,
+            -0.0,
+            f64::MIN,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            f64::EPSILON,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ]),
+        any::<u64>().prop_map(f64::from_bits),
+    ]
+}
+
+macro_rules! round_trip {
+    ($values:expr, $java:ty) => {{
+        let back: Vec<_> = $values
+            .to_java::<java::Array<$java>>()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        back
+    }};
+}
+
+proptest! {
+    #[test]
+    fn strings(s in string()) {
+        let (utf16_len, back) = Jvm::with(|jvm| {
+            let java = s
+                .to_java::<java::lang::String>()
+                .assert_not_null()
+                .execute_with(jvm)?;
+            let utf16_len = java.length().execute_with(jvm)?;
+            let back: String = (&*java).to_rust().execute_with(jvm)?;
+            Ok((utf16_len, back))
+        })
+        .unwrap();
+        prop_assert_eq!(utf16_len as usize, s.encode_utf16().count());
+        prop_assert_eq!(back, s);
+    }
+
+    #[test]
+    fn i32_through_java_methods(n in i32_value()) {
+        let boxed = java::lang::Integer::value_of(n).assert_not_null();
+        prop_assert_eq!(boxed.int_value().execute().unwrap(), n);
+        let text: String = boxed
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        prop_assert_eq!(text, n.to_string());
+    }
+
+    #[test]
+    fn i64_through_java_methods(n in i64_value()) {
+        let boxed = java::lang::Long::value_of(n).assert_not_null();
+        prop_assert_eq!(boxed.long_value().execute().unwrap(), n);
+        let text: String = boxed
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        prop_assert_eq!(text, n.to_string());
+    }
+
+    #[test]
+    fn i32_arrays(v in elements(i32_value())) {
+        prop_assert_eq!(round_trip!(v, i32), v);
+    }
+
+    #[test]
+    fn i64_arrays(v in elements(i64_value())) {
+        prop_assert_eq!(round_trip!(v, i64), v);
+    }
+
+    #[test]
+    fn bool_arrays(v in elements(any::<bool>())) {
+        prop_assert_eq!(round_trip!(v, bool), v);
+    }
+
+    #[test]
+    fn char_arrays(v in elements(any::<u16>())) {
+        prop_assert_eq!(round_trip!(v, u16), v);
+    }
+
+    #[test]
+    fn f64_arrays(v in elements(f64_value())) {
+        let back: Vec<f64> = round_trip!(v, f64);
+        let bits = |v: &[f64]| v.iter().map(|f| f.to_bits()).collect::<Vec<_>>();
+        prop_assert_eq!(bits(&back), bits(&v));
+    }
+
+    #[test]
+    fn byte_arrays_keep_their_bits(v in elements(u8_value())) {
+        let array = v
+            .to_java::<java::Array<i8>>()
+            .assert_not_null()
+            .global()
+            .execute()
+            .unwrap();
+        let unsigned: Vec<u8> = (&*array).to_rust().execute().unwrap();
+        prop_assert_eq!(&unsigned, &v);
+        let signed: Vec<i8> = (&*array).to_rust().execute().unwrap();
+        prop_assert_eq!(signed, v.iter().map(|&b| b as i8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn string_lists(v in elements(short_string())) {
+        let back: Vec<String> = v
+            .to_java::<java::util::List<java::lang::String>>()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        prop_assert_eq!(back, v);
+    }
+
+    #[test]
+    fn string_maps(map in elements((short_string(), short_string())).prop_map(HashMap::from_iter)) {
+        let back: HashMap<String, String> = map
+            .to_java::<java::util::Map<java::lang::String, java::lang::String>>()
+            .assert_not_null()
+            .to_rust()
+            .execute()
+            .unwrap();
+        prop_assert_eq!(back, map);
+    }
+}
+
+/// A single string far longer than the generated ones, made by repeating a generated chunk (generating each of its
+/// characters would take minutes).
+#[test]
+fn huge_string() {
+    let mut runner = TestRunner::deterministic();
+    let chunk = vec(java_char(), 64 * 1024)
+        .prop_map(String::from_iter)
+        .new_tree(&mut runner)
+        .unwrap()
+        .current();
+    let s = chunk.repeat(64);
+    let java = s
+        .to_java::<java::lang::String>()
+        .assert_not_null()
+        .global()
+        .execute()
+        .unwrap();
+    let back: String = (&*java).to_rust().execute().unwrap();
+    assert!(back == s, "huge string changed in a round trip");
+}
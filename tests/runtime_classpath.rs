@@ -0,0 +1,67 @@
+//! Adding directories to the classes duchess loads once the JVM runs. The class loader is global, so everything is
+//! one test.
+
+use duchess::{compile::compile, java, prelude::*, Error, Jvm, Local};
+
+#[test]
+fn classes_load_from_added_directories() {
+    let dir = std::env::temp_dir().join(format!("duchess-classpath-{}", std::process::id()));
+    Jvm::with(|jvm| {
+        let compiled = compile(
+            jvm,
+            &[(
+                "plugin.Greeter",
+                "package plugin;
+                public class Greeter {
+                    public static String greet(String name) { return \"Hello, \" + name; }
+                }",
+            )],
+            &[],
+        )?
+        .unwrap();
+        std::fs::create_dir_all(dir.join("plugin")).unwrap();
+        for class in &compiled.classes {
+            let file = format!("{}.class", class.name.replace('.', "/"));
+            std::fs::write(dir.join(file), &class.bytes).unwrap();
+        }
+
+        let missing = duchess::plumbing::find_class(jvm, c"plugin/Greeter");
+        assert!(missing.is_err());
+
+        let not_found = jvm.add_classpath(dir.join("missing.jar"));
+        assert!(matches!(not_found, Err(Error::ClasspathNotFound(path)) if path == dir.join("missing.jar")));
+
+        jvm.add_classpath(&dir)?;
+        let greeter = duchess::plumbing::find_class(jvm, c"plugin/Greeter")?;
+        let name = "plugins"
+            .to_java::<java::lang::String>()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        let greeting: Option<Local<java::lang::String>> =
+            duchess::call!(jvm, static greeter.greet(&*name))?;
+        let greeting: String = (&*greeting.unwrap()).to_rust().execute_with(jvm)?;
+        assert_eq!(greeting, "Hello, plugins");
+
+        // JDK classes are still found, through the parent of the class loader
+        let list = java::util::ArrayList::<java::lang::String>::new().execute_with(jvm)?;
+        assert_eq!(list.size().execute_with(jvm)?, 0);
+
+        // Adding another directory reuses the class loader
+        let loader = greeter
+            .get_class_loader()
+            .assert_not_null()
+            .execute_with(jvm)?;
+        jvm.add_classpath(std::env::temp_dir())?;
+        let again = duchess::plumbing::find_class(jvm, c"plugin/Greeter")?;
+        let same_loader = again
+            .get_class_loader()
+            .assert_not_null()
+            .execute_with(jvm)?
+            .equals(&*loader)
+            .execute_with(jvm)?;
+        assert!(same_loader);
+        Ok(())
+    })
+    .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
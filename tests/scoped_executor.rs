@@ -0,0 +1,98 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use duchess::{java, prelude::*, scoped_executor};
+
+#[test]
+fn tasks_can_borrow_from_outside() {
+    let words = vec!["one".to_string(), "three".to_string()];
+    let lengths = scoped_executor(2, |executor| {
+        let tasks = words
+            .iter()
+            .map(|word| executor.submit(|| word.len()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(tasks
+            .into_iter()
+            .map(|task| task.join().unwrap())
+            .collect::<Vec<_>>())
+    })
+    .unwrap();
+    assert_eq!(lengths, [3, 5]);
+}
+
+#[test]
+fn scope_waits_for_all_tasks() {
+    let done = AtomicUsize::new(0);
+    scoped_executor(3, |executor| {
+        for _ in 0..10 {
+            executor.submit(|| {
+                std::thread::sleep(Duration::from_millis(10));
+                done.fetch_add(1, Ordering::SeqCst);
+            })?;
+        }
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(done.load(Ordering::SeqCst), 10);
+}
+
+#[test]
+fn tasks_run_on_java_threads() {
+    let name = scoped_executor(1, |executor| {
+        let task = executor.submit(|| {
+            java::lang::Thread::current_thread()
+                .assert_not_null()
+                .get_name()
+                .assert_not_null()
+                .to_rust()
+                .execute()
+        })?;
+        task.join().unwrap()
+    })
+    .unwrap();
+    assert!(name.starts_with("pool-"), "{name}");
+}
+
+#[test]
+fn joined_panics_are_returned() {
+    let panicked = scoped_executor(1, |executor| {
+        let task = executor.submit(|| panic!("boom"))?;
+        Ok(task.join().is_err())
+    })
+    .unwrap();
+    assert!(panicked);
+}
+
+#[test]
+#[should_panic(expected = "a task of a scoped executor panicked")]
+fn unjoined_panics_propagate() {
+    scoped_executor(1, |executor| {
+        drop(executor.submit(|| panic!("boom"))?);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn scope_errors_still_wait_for_tasks() {
+    let done = AtomicUsize::new(0);
+    let result = scoped_executor(1, |executor| {
+        executor.submit(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            done.fetch_add(1, Ordering::SeqCst);
+        })?;
+        Vec::<String>::new()
+            .to_java::<java::util::List<java::lang::String>>()
+            .assert_not_null()
+            .iterator()
+            .assert_not_null()
+            .next()
+            .assert_not_null()
+            .length()
+            .execute()
+    });
+    assert!(result.is_err());
+    assert_eq!(done.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,70 @@
+use duchess::{
+    script::{self, JShell, ScriptEngine},
+    Jvm,
+};
+
+#[test]
+fn jshell_keeps_state() {
+    let values = Jvm::with(|jvm| {
+        let shell = JShell::new(jvm)?;
+        let values = (
+            shell.eval(jvm, "int x = 40 + 2;")?,
+            shell.eval(jvm, "x * 2")?,
+            shell.eval(jvm, "\"a\" + x")?,
+            shell.eval(
+                jvm,
+                "System.getProperty(\"no.such.property\", \"fallback\");",
+            )?,
+        );
+        shell.close(jvm)?;
+        Ok(values)
+    })
+    .unwrap();
+    assert_eq!(
+        values,
+        (
+            Some("42".to_string()),
+            Some("84".to_string()),
+            Some("\"a42\"".to_string()),
+            Some("\"fallback\"".to_string()),
+        )
+    );
+}
+
+#[test]
+fn jshell_errors() {
+    let (thrown, rejected) = Jvm::with(|jvm| {
+        let shell = JShell::new(jvm)?;
+        let thrown = shell
+            .eval(jvm, "throw new IllegalStateException(\"boom\");")
+            .unwrap_err()
+            .into_global(jvm);
+        let rejected = shell.eval(jvm, "int y = ;").unwrap_err().into_global(jvm);
+        Ok((thrown, rejected))
+    })
+    .unwrap();
+
+    assert_eq!(
+        thrown.thrown_class_name().as_deref(),
+        Some("jdk.jshell.EvalException")
+    );
+    assert!(thrown.to_string().contains("boom"), "{thrown}");
+
+    assert!(rejected.is_instance::<script::javax::script::ScriptException>());
+    assert!(rejected.to_string().contains("int y = ;"), "{rejected}");
+}
+
+#[test]
+fn shared_shell() {
+    script::eval("String greeting = \"hello\";").unwrap();
+    assert_eq!(
+        script::eval("greeting.length()").unwrap().as_deref(),
+        Some("5")
+    );
+}
+
+#[test]
+fn missing_script_engine() {
+    let engine = Jvm::with(|jvm| Ok(ScriptEngine::by_name(jvm, "no-such-language")?.is_some()));
+    assert!(!engine.unwrap());
+}
@@ -0,0 +1,93 @@
+use duchess::{java, prelude::*, Error, Jvm, Local};
+
+#[test]
+fn round_trip() {
+    let bytes = Jvm::with(|jvm| {
+        let list: Local<java::util::ArrayList<java::lang::String>> =
+            java::util::ArrayList::new().execute_with(jvm)?;
+        list.add("a").execute_with(jvm)?;
+        list.add("b").execute_with(jvm)?;
+        list.serialize().execute_with(jvm)
+    })
+    .unwrap();
+
+    Jvm::with(|jvm| {
+        let filter = jvm.object_input_filter("java.util.ArrayList;java.lang.*;!*")?;
+        let list = jvm
+            .deserialize::<java::util::ArrayList<java::lang::String>>(&bytes, &filter)?
+            .expect("serialized a non-null list");
+        assert_eq!(list.size().execute_with(jvm)?, 2);
+        let first: String = list.get(0).assert_not_null().to_rust().execute_with(jvm)?;
+        assert_eq!(first, "a");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn not_serializable() {
+    Jvm::with(|jvm| {
+        let object = java::lang::Object::new().execute_with(jvm)?;
+        let Err(Error::Thrown(exception)) = object.serialize().execute_with(jvm) else {
+            panic!("expected `writeObject` to throw");
+        };
+        let message: String = exception
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(message.contains("NotSerializableException"), "{message}");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn deserialize_wrong_class() {
+    Jvm::with(|jvm| {
+        let bytes = "hello"
+            .to_java::<java::lang::String>()
+            .serialize()
+            .execute_with(jvm)?;
+        let filter = jvm.object_input_filter("java.lang.String;!*")?;
+        let result = jvm.deserialize::<java::util::ArrayList<java::lang::String>>(&bytes, &filter);
+        assert!(matches!(result, Err(Error::JvmInternal(_))));
+
+        let string = jvm
+            .deserialize::<java::lang::String>(&bytes, &filter)?
+            .unwrap();
+        let string: String = (&*string).to_rust().execute_with(jvm)?;
+        assert_eq!(string, "hello");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn deserialize_rejected_class() {
+    Jvm::with(|jvm| {
+        let list: Local<java::util::ArrayList<java::lang::String>> =
+            java::util::ArrayList::new().execute_with(jvm)?;
+        let bytes = list.serialize().execute_with(jvm)?;
+
+        let filter = jvm.object_input_filter("java.lang.*;!*")?;
+        let Err(Error::Thrown(exception)) =
+            jvm.deserialize::<java::util::ArrayList<java::lang::String>>(&bytes, &filter)
+        else {
+            panic!("expected the filter to reject `ArrayList`");
+        };
+        let message: String = exception
+            .to_string()
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        assert!(message.contains("InvalidClassException"), "{message}");
+
+        assert!(matches!(
+            jvm.object_input_filter(""),
+            Err(Error::JvmInternal(_))
+        ));
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,41 @@
+//! System properties and environment variables through `Jvm`.
+
+use duchess::Jvm;
+
+#[test]
+fn properties() {
+    Jvm::with(|jvm| {
+        let version = jvm.get_property("java.version")?;
+        assert!(version.is_some_and(|version| !version.is_empty()));
+
+        let key = "duchess.test.property";
+        assert_eq!(jvm.get_property(key)?, None);
+        assert_eq!(jvm.set_property(key, "one")?, None);
+        assert_eq!(jvm.set_property(key, "two")?.as_deref(), Some("one"));
+        assert_eq!(jvm.get_property(key)?.as_deref(), Some("two"));
+        assert_eq!(jvm.clear_property(key)?.as_deref(), Some("two"));
+        assert_eq!(jvm.get_property(key)?, None);
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn empty_keys_throw() {
+    let error = Jvm::with(|jvm| jvm.get_property("").map(drop)).unwrap_err();
+    assert!(
+        error.to_string().contains("IllegalArgumentException"),
+        "{error}"
+    );
+}
+
+#[test]
+fn environment_variables() {
+    Jvm::with(|jvm| {
+        let path = jvm.get_env_var("PATH")?;
+        assert_eq!(path, std::env::var("PATH").ok());
+        assert_eq!(jvm.get_env_var("DUCHESS_NO_SUCH_VARIABLE")?, None);
+        Ok(())
+    })
+    .unwrap();
+}
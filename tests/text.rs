@@ -0,0 +1,59 @@
+use duchess::{
+    prelude::*,
+    text::{self, Form},
+    Jvm,
+};
+
+#[test]
+fn case_conversion_ignores_the_default_locale() {
+    Jvm::with(|jvm| {
+        assert_eq!(text::to_upper_case("straße").execute_with(jvm)?, "STRASSE");
+        assert_eq!(
+            text::to_upper_case("istanbul").execute_with(jvm)?,
+            "ISTANBUL"
+        );
+        assert_eq!(text::to_lower_case("İ").execute_with(jvm)?, "i\u{307}");
+        assert_eq!(text::to_lower_case("ΣΑΣ").execute_with(jvm)?, "σας");
+        assert_eq!(text::to_lower_case("").execute_with(jvm)?, "");
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn normalization_forms() {
+    Jvm::with(|jvm| {
+        let decomposed = "e\u{301}";
+        assert_eq!(
+            text::normalize(decomposed, Form::Nfc).execute_with(jvm)?,
+            "\u{e9}"
+        );
+        assert_eq!(
+            text::normalize("\u{e9}", Form::Nfd).execute_with(jvm)?,
+            decomposed
+        );
+
+        let ligature = "\u{fb01}";
+        assert_eq!(
+            text::normalize(ligature, Form::Nfc).execute_with(jvm)?,
+            ligature
+        );
+        assert_eq!(
+            text::normalize(ligature, Form::Nfkc).execute_with(jvm)?,
+            "fi"
+        );
+        assert_eq!(
+            text::normalize("\u{1e9b}\u{323}", Form::Nfkd).execute_with(jvm)?,
+            "s\u{323}\u{307}"
+        );
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn ops_can_run_outside_with() {
+    let op = text::to_upper_case("duchess");
+    assert_eq!(op.execute().unwrap(), "DUCHESS");
+    assert_eq!(op.execute().unwrap(), "DUCHESS");
+}
@@ -0,0 +1,158 @@
+use duchess::{
+    java::{
+        self,
+        lang::{IllegalArgumentException, IllegalStateException, RuntimeException, Throwable},
+    },
+    prelude::*,
+    Error, GlobalResult, JavaStackTrace,
+};
+
+/// Fails with an `IndexOutOfBoundsException`.
+fn get_from_empty_list() -> GlobalResult<Option<String>> {
+    java::util::ArrayList::<java::lang::String>::new()
+        .get(0)
+        .to_rust()
+        .execute()
+}
+
+#[test]
+fn is_instance() {
+    let error = get_from_empty_list().unwrap_err();
+    assert!(error.is_instance::<Throwable>());
+    assert!(error.is_instance::<RuntimeException>());
+    assert!(!error.is_instance::<IllegalArgumentException>());
+    assert!(!Error::<duchess::Global<Throwable>>::NullDeref.is_instance::<Throwable>());
+}
+
+#[test]
+fn downcast() {
+    let error = get_from_empty_list().unwrap_err();
+    let Err(error) = error.downcast::<IllegalArgumentException>() else {
+        panic!("not an IllegalArgumentException");
+    };
+    let exception = error.downcast::<RuntimeException>().unwrap();
+    let message: String = exception
+        .get_message()
+        .assert_not_null()
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert!(message.contains('0'), "{message}");
+}
+
+#[test]
+fn thrown_class_name() {
+    let error = get_from_empty_list().unwrap_err();
+    assert_eq!(
+        error.thrown_class_name().as_deref(),
+        Some("java.lang.IndexOutOfBoundsException")
+    );
+    assert_eq!(
+        Error::<duchess::Global<Throwable>>::NullDeref.thrown_class_name(),
+        None
+    );
+}
+
+#[test]
+fn with_backtrace() {
+    let error = get_from_empty_list().unwrap_err().with_backtrace();
+    let trace = error.backtrace().expect("captured backtrace");
+    assert_eq!(trace.class_name, "java.lang.IndexOutOfBoundsException");
+    assert!(trace
+        .frames
+        .iter()
+        .any(|frame| frame.class_name == "java.util.ArrayList" && frame.method_name == "get"));
+
+    let display = error.to_string();
+    assert!(
+        display.starts_with("Java invocation threw: java.lang.IndexOutOfBoundsException: "),
+        "{display}"
+    );
+    assert!(
+        display.contains("\n\tat java.util.ArrayList.get("),
+        "{display}"
+    );
+
+    // Still usable as a thrown exception
+    assert!(error.is_instance::<RuntimeException>());
+}
+
+#[test]
+fn backtrace_includes_causes() {
+    let trace = duchess::Jvm::with(|jvm| {
+        let exception = IllegalStateException::new("outer").execute_with(jvm)?;
+        let cause = IllegalArgumentException::new("inner").execute_with(jvm)?;
+        exception.init_cause(&cause).execute_with(jvm)?;
+        JavaStackTrace::capture(jvm, &exception)
+    })
+    .unwrap();
+
+    assert_eq!(trace.class_name, "java.lang.IllegalStateException");
+    assert_eq!(trace.message.as_deref(), Some("outer"));
+    let cause = trace.cause.as_deref().unwrap();
+    assert_eq!(cause.class_name, "java.lang.IllegalArgumentException");
+    assert_eq!(cause.message.as_deref(), Some("inner"));
+    assert!(cause.cause.is_none());
+    assert!(trace
+        .to_string()
+        .ends_with("\nCaused by: java.lang.IllegalArgumentException: inner"));
+}
+
+#[test]
+fn source_reports_causes() {
+    let error = duchess::Jvm::with(|jvm| {
+        let root = IllegalArgumentException::new("root").execute_with(jvm)?;
+        let middle = IllegalStateException::new("middle").execute_with(jvm)?;
+        middle.init_cause(&root).execute_with(jvm)?;
+        let outer = IllegalStateException::new("outer").execute_with(jvm)?;
+        outer.init_cause(&middle).execute_with(jvm)?;
+        let error = Error::Thrown(outer.upcast()).with_backtrace(jvm);
+        Ok(error.into_global(jvm))
+    })
+    .unwrap();
+
+    let display = error.to_string();
+    assert!(
+        display.starts_with("Java invocation threw: java.lang.IllegalStateException: outer"),
+        "{display}"
+    );
+    assert!(!display.contains("Caused by"), "{display}");
+
+    let mut causes = vec![];
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        causes.push(cause.to_string().lines().next().unwrap().to_owned());
+        source = cause.source();
+    }
+    assert_eq!(
+        causes,
+        [
+            "java.lang.IllegalStateException: middle",
+            "java.lang.IllegalArgumentException: root"
+        ]
+    );
+
+    // Without captured details, there is nothing to report
+    let error = get_from_empty_list().unwrap_err();
+    assert!(std::error::Error::source(&error).is_none());
+}
+
+#[test]
+fn methods_after_assert_not_null() {
+    let length = java::lang::Integer::value_of(42)
+        .assert_not_null()
+        .to_string()
+        .assert_not_null()
+        .length()
+        .execute()
+        .unwrap();
+    assert_eq!(length, 2);
+
+    // The null check fails the whole chain
+    let length = java::util::HashMap::<java::lang::String, java::lang::String>::new()
+        .get("missing")
+        .assert_not_null()
+        .length()
+        .execute();
+    assert!(matches!(length, Err(Error::NullDeref)));
+}
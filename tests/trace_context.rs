@@ -0,0 +1,96 @@
+use duchess::{java, prelude::*, trace_context::TraceContext, Jvm};
+
+const TRACE_PARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+#[test]
+fn parse_and_format() {
+    let context: TraceContext = TRACE_PARENT.parse().unwrap();
+    assert_eq!(context.trace_id[0], 0x4b);
+    assert_eq!(context.trace_id[15], 0x36);
+    assert_eq!(
+        context.parent_id,
+        [0x00, 0xf0, 0x67, 0xaa, 0x0b, 0xa9, 0x02, 0xb7]
+    );
+    assert!(context.is_sampled());
+    assert_eq!(context.trace_parent(), TRACE_PARENT);
+}
+
+#[test]
+fn invalid_headers() {
+    for header in [
+        "",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+        "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+        "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+        "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+    ] {
+        assert!(header.parse::<TraceContext>().is_err(), "{header}");
+    }
+
+    // Future versions may add fields
+    assert!(
+        "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra"
+            .parse::<TraceContext>()
+            .is_ok()
+    );
+}
+
+#[test]
+fn inject_and_extract() {
+    let context = TraceContext::parse(TRACE_PARENT, Some("vendor=value")).unwrap();
+
+    let (headers, extracted) = Jvm::with(|jvm| {
+        let carrier = java::util::HashMap::<java::lang::String, java::lang::String>::new()
+            .execute_with(jvm)?;
+        context.inject(jvm, &carrier)?;
+
+        let header: String = carrier
+            .get("traceparent")
+            .assert_not_null()
+            .to_rust()
+            .execute_with(jvm)?;
+        let size = carrier.size().execute_with(jvm)?;
+        let extracted = TraceContext::extract(jvm, &carrier)?;
+        Ok(((header, size), extracted))
+    })
+    .unwrap();
+
+    assert_eq!(headers, (TRACE_PARENT.to_string(), 2));
+    assert_eq!(extracted, Some(context));
+}
+
+#[test]
+fn extract_missing_or_invalid() {
+    let extracted = Jvm::with(|jvm| {
+        let carrier = java::util::HashMap::<java::lang::String, java::lang::String>::new()
+            .execute_with(jvm)?;
+        let missing = TraceContext::extract(jvm, &carrier)?;
+        carrier.put("traceparent", "garbage").execute_with(jvm)?;
+        let invalid = TraceContext::extract(jvm, &carrier)?;
+        Ok((missing, invalid))
+    })
+    .unwrap();
+    assert_eq!(extracted, (None, None));
+}
+
+#[cfg(feature = "opentelemetry")]
+#[test]
+fn span_context_round_trip() {
+    use opentelemetry::trace::TraceContextExt;
+
+    let context = TraceContext::parse(TRACE_PARENT, Some("vendor=value")).unwrap();
+    let span_context = context.to_span_context();
+    assert!(span_context.is_remote());
+    assert_eq!(
+        TraceContext::from_span_context(&span_context),
+        Some(context.clone())
+    );
+
+    assert_eq!(TraceContext::current(), None);
+    let _guard = opentelemetry::Context::current()
+        .with_remote_span_context(span_context)
+        .attach();
+    assert_eq!(TraceContext::current(), Some(context));
+}
@@ -0,0 +1,70 @@
+use duchess::{
+    java::{
+        self,
+        lang::{IllegalArgumentException, RuntimeException},
+    },
+    prelude::*,
+    Error, Global, Jvm,
+};
+
+fn empty_list() -> Global<java::util::ArrayList<java::lang::String>> {
+    Jvm::with(|jvm| {
+        let list = java::util::ArrayList::<java::lang::String>::new().execute_with(jvm)?;
+        Ok(jvm.global(&*list))
+    })
+    .unwrap()
+}
+
+#[test]
+fn first_matching_arm_wins() {
+    let list = empty_list();
+    let value: Option<String> = list
+        .get(0)
+        .catching()
+        .catch::<IllegalArgumentException, _>(|jvm, _| {
+            "illegal argument".to_java().execute_with(jvm)
+        })
+        .catch::<RuntimeException, _>(|jvm, _| "runtime".to_java().execute_with(jvm))
+        .catch::<java::lang::Throwable, _>(|jvm, _| "throwable".to_java().execute_with(jvm))
+        .to_rust()
+        .execute()
+        .unwrap();
+    assert_eq!(value.as_deref(), Some("runtime"));
+}
+
+#[test]
+fn unmatched_exceptions_are_rethrown() {
+    let list = empty_list();
+    let result = list
+        .get(0)
+        .catching()
+        .catch::<IllegalArgumentException, _>(|_, _| Ok(None))
+        .to_rust::<Option<String>>()
+        .execute();
+    assert!(matches!(result, Err(Error::Thrown(_))));
+}
+
+#[test]
+fn finally_runs_on_success_and_exception() {
+    let list = empty_list();
+    let other = empty_list();
+
+    let size = list.size().finally(other.add("a")).execute().unwrap();
+    assert_eq!(size, 0);
+
+    let result = list
+        .get(0)
+        .finally(other.add("b"))
+        .to_rust::<Option<String>>()
+        .execute();
+    assert!(matches!(result, Err(Error::Thrown(_))));
+
+    assert_eq!(other.size().execute().unwrap(), 2);
+}
+
+#[test]
+fn finally_exception_replaces_result() {
+    let list = empty_list();
+    let result = list.size().finally(list.get(0)).execute();
+    assert!(matches!(result, Err(Error::Thrown(_))));
+}
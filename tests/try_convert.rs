@@ -0,0 +1,49 @@
+use duchess::{java, prelude::*, ConversionError, Jvm, Local};
+
+#[test]
+fn out_of_range_scalars() {
+    Jvm::with(|jvm| {
+        let s: Local<java::lang::String> = "a"
+            .repeat(300)
+            .to_java()
+            .assert_not_null()
+            .execute_with(jvm)?;
+
+        let len: Result<u16, _> = s.length().try_to_rust().execute_with(jvm)?;
+        assert_eq!(len, Ok(300));
+
+        let len: Result<u8, _> = s.length().try_to_rust().execute_with(jvm)?;
+        assert_eq!(
+            len,
+            Err(ConversionError::OutOfRange {
+                value: "300".into(),
+                target: "u8"
+            })
+        );
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn fallible_round_trip() {
+    Jvm::with(|jvm| {
+        let data = vec![1i32, 2, 3];
+        let array = data
+            .try_to_java::<java::Array<i32>>()
+            .execute_with(jvm)?
+            .unwrap()
+            .unwrap();
+        let back: Vec<i32> = (&*array).to_rust().execute_with(jvm)?;
+        assert_eq!(back, data);
+
+        let s = "hello"
+            .try_to_java::<java::lang::String>()
+            .execute_with(jvm)?
+            .unwrap();
+        let s: Result<Option<String>, _> = s.try_into_rust(jvm)?;
+        assert_eq!(s, Ok(Some("hello".to_string())));
+        Ok(())
+    })
+    .unwrap();
+}
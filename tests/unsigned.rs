@@ -0,0 +1,71 @@
+//! Rust unsigned integers passed to Java integer parameters.
+
+use std::num::Wrapping;
+
+use duchess::{java, prelude::*, ConversionError, Error};
+
+#[test]
+fn unsigned_integers_widen() -> duchess::GlobalResult<()> {
+    let long: i64 = java::lang::Long::value_of(u32::MAX)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(long, i64::from(u32::MAX));
+    let short: i16 = java::lang::Short::value_of(u8::MAX)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(short, 255);
+    Ok(())
+}
+
+#[test]
+fn unsigned_integers_are_checked() -> duchess::GlobalResult<()> {
+    let int: i32 = java::lang::Integer::value_of(7_u64)
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(int, 7);
+
+    let result = java::lang::Integer::value_of(u32::MAX).global().execute();
+    assert!(
+        matches!(
+            result,
+            Err(Error::Conversion(ConversionError::OutOfRange { .. }))
+        ),
+        "{result:?}"
+    );
+    let result = java::lang::Byte::value_of(200_u8).global().execute();
+    assert!(result.is_err());
+    let result = java::lang::Long::value_of(u64::MAX).global().execute();
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn wrapping_opts_into_wrapping_around() -> duchess::GlobalResult<()> {
+    let int: i32 = java::lang::Integer::value_of(Wrapping(u32::MAX))
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(int, -1);
+    let long: i64 = java::lang::Long::value_of(Wrapping(u64::MAX - 1))
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(long, -2);
+    Ok(())
+}
+
+#[test]
+fn usize_lengths_and_indices() -> duchess::GlobalResult<()> {
+    let len: usize = 3;
+    let array = java::Array::<java::lang::String>::new_with_len(len)
+        .global()
+        .execute()?;
+    assert_eq!(array.length().execute()?, 3);
+    let element = array.get(len - 1).global().execute()?;
+    assert!(element.is_none());
+    assert!(array.get(usize::MAX).global().execute().is_err());
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! `java.util.UUID` to and from `uuid::Uuid`.
+
+#![cfg(feature = "uuid")]
+
+use duchess::{java, prelude::*};
+use uuid::Uuid;
+
+#[test]
+fn uuids_round_trip() -> duchess::GlobalResult<()> {
+    let value = Uuid::parse_str("123e4567-e89b-42d3-a456-426614174000").unwrap();
+    let uuid = value
+        .to_java::<java::util::UUID>()
+        .assert_not_null()
+        .global()
+        .execute()?;
+    let string: String = uuid.to_string().assert_not_null().to_rust().execute()?;
+    assert_eq!(string, value.to_string());
+    assert_eq!(uuid.version().execute()?, 4);
+
+    let back: Uuid = (&*uuid).to_rust().execute()?;
+    assert_eq!(back, value);
+    Ok(())
+}
+
+#[test]
+fn high_bits_survive() -> duchess::GlobalResult<()> {
+    for value in [0, u128::MAX, 1 << 127, 1 << 63, u128::from(u64::MAX)] {
+        let value = Uuid::from_u128(value);
+        let back: Uuid = value
+            .to_java::<java::util::UUID>()
+            .assert_not_null()
+            .to_rust()
+            .execute()?;
+        assert_eq!(back, value);
+    }
+
+    let parsed: Uuid = java::util::UUID::from_string("ffffffff-ffff-ffff-0000-000000000001")
+        .assert_not_null()
+        .to_rust()
+        .execute()?;
+    assert_eq!(
+        parsed,
+        Uuid::from_u128(0xffffffff_ffff_ffff_0000_000000000001)
+    );
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use duchess::{java, prelude::*, Global, Jvm, Weak};
+
+#[test]
+fn upgrade_until_collected() {
+    let weak: Weak<java::lang::Object> = Jvm::with(|jvm| {
+        let object = java::lang::Object::new().execute_with(jvm)?;
+        let strong: Global<java::lang::Object> = jvm.global(&*object);
+        let weak = jvm.weak(&*object);
+        drop(object);
+
+        java::lang::System::gc().execute_with(jvm)?;
+        assert!(weak.upgrade(jvm).is_some(), "still strongly reachable");
+        drop(strong);
+        Ok(weak)
+    })
+    .unwrap();
+
+    // Weak refs can be moved across threads and upgraded in a different `Jvm::with` call. Each attempt gets its own
+    // call so that the local refs created by `upgrade` are released before the next GC.
+    std::thread::spawn(move || {
+        for _ in 0..100 {
+            let collected = Jvm::with(|jvm| {
+                java::lang::System::gc().execute_with(jvm)?;
+                Ok(weak.upgrade(jvm).is_none())
+            })
+            .unwrap();
+            if collected {
+                return;
+            }
+        }
+        panic!("object was never collected");
+    })
+    .join()
+    .unwrap();
+}